@@ -3,8 +3,9 @@
 use std::io::Write;
 use std::time::Instant;
 
+use clap::ValueEnum;
 use stellar_ast_lowering::LowerToHir;
-use stellar_database::State;
+use stellar_database::{Path, State, DUMMY_PACKAGE_ID};
 use stellar_diagnostics::DiagnosticsEmitter;
 use stellar_filesystem::file_utils::make_unique_file;
 use stellar_interner::{PathId, DUMMY_IDENTIFIER_ID};
@@ -12,14 +13,30 @@ use stellar_parser::read_and_parse_module;
 
 use crate::log::{log_error, log_info};
 
-pub fn command(filepath: &str) {
+/// A form, in which lowered HIR is emitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Emit {
+    /// Serialized HIR written into `hir.json`.
+    Json,
+
+    /// HIR rendered back into source code (with all constructs desugared)
+    /// printed to stdout.
+    Hir,
+}
+
+pub fn command(filepath: &str, emit: Emit) {
     let mut diagnostics_emitter = DiagnosticsEmitter::new();
     let mut state = State::new();
     let filepath = PathId::from(filepath);
 
     let mut now = Instant::now();
 
-    match read_and_parse_module(&mut state, DUMMY_IDENTIFIER_ID, filepath) {
+    match read_and_parse_module(
+        &mut state,
+        DUMMY_PACKAGE_ID,
+        Path::from(DUMMY_IDENTIFIER_ID),
+        filepath,
+    ) {
         Err(..) => {
             log_error(format!("cannot read the file {filepath}"));
         }
@@ -29,13 +46,18 @@ pub fn command(filepath: &str) {
             now = Instant::now();
 
             let hir = LowerToHir::run_all(&mut state, vec![ast]);
-            let hir = hir.first_key_value().unwrap().1;
+            let hir = hir.values().next().unwrap();
 
             log_info("Lowered", format!("in {}s", now.elapsed().as_secs_f64()));
 
             diagnostics_emitter.emit_global_diagnostics(state.diagnostics());
 
             if state.diagnostics().is_ok() {
+                if emit == Emit::Hir {
+                    print!("{}", stellar_hir::pretty::print(hir));
+                    return;
+                }
+
                 now = Instant::now();
 
                 let hir_string = serde_json::to_string(hir).unwrap();
@@ -64,7 +64,7 @@ use clap::{Parser, Subcommand};
 // mod collect_signatures;
 mod lex;
 mod log;
-// mod lower;
+mod lower;
 mod parse;
 mod parse_manifest;
 // mod resolve_imports;
@@ -109,10 +109,18 @@ enum Commands {
     Parse { filepath: String },
     #[cfg(feature = "debug")]
     #[command(about = "Debug mode: parse a given source file, lower its AST and serialize HIR")]
-    Hir { filepath: String },
+    Hir {
+        filepath: String,
+        #[arg(long, value_enum, default_value_t = lower::Emit::Json)]
+        emit: lower::Emit,
+    },
     #[cfg(feature = "debug")]
     #[command(about = "Debug mode: parse a given source file, lower its AST and serialize HIR")]
-    LowerAst { filepath: String },
+    LowerAst {
+        filepath: String,
+        #[arg(long, value_enum, default_value_t = lower::Emit::Json)]
+        emit: lower::Emit,
+    },
     #[cfg(feature = "debug")]
     #[command(about = "Debug mode: parses a given manifest file")]
     ParseManifest { filepath: String },
@@ -175,10 +183,10 @@ fn main() {
         Commands::Ast { filepath } | Commands::Parse { filepath } => {
             parse::command(&filepath);
         }
-        // #[cfg(feature = "debug")]
-        // Commands::Hir { filepath } | Commands::LowerAst { filepath } => {
-        //     lower::command(&filepath);
-        // }
+        #[cfg(feature = "debug")]
+        Commands::Hir { filepath, emit } | Commands::LowerAst { filepath, emit } => {
+            lower::command(&filepath, emit);
+        }
         #[cfg(feature = "debug")]
         Commands::ParseManifest { filepath } => {
             parse_manifest::command(&filepath);
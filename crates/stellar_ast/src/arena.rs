@@ -0,0 +1,1323 @@
+//! Arena-allocated AST of function bodies.
+//!
+//! Every [`Box`] and [`Vec`] of the [owned AST](crate) is a separate heap
+//! allocation, so large bodies are made of thousands of small allocations,
+//! that are slow to create and to free. Nodes of this module are instead
+//! allocated in a bump [`Arena`]: children are `&'a` references and lists are
+//! `&'a` slices, so a whole tree lives in a few large chunks of memory and is
+//! freed at once, when the arena is dropped.
+//!
+//! The module mirrors expressions, statements, patterns and types, that make
+//! up the overwhelming majority of nodes. Leaf data, e.g. [`IdentifierAST`],
+//! [`NodeId`] and operators, is shared with the owned AST. Nodes never own
+//! heap memory (string literals are allocated in the arena as well), so all
+//! of them are [`Copy`] and dropping the arena without running destructors
+//! doesn't leak:
+//!
+//! ```
+//! use stellar_ast::{arena::{self, Arena}, Expression, Literal, DUMMY_NODE_ID};
+//! use stellar_filesystem::location::DUMMY_LOCATION;
+//!
+//! let expression = Expression::Literal {
+//!     node_id: DUMMY_NODE_ID,
+//!     literal: Literal::String {
+//!         value: "hello".to_owned(),
+//!         location: DUMMY_LOCATION,
+//!     },
+//! };
+//!
+//! let arena = Arena::new();
+//! let allocated = arena.alloc_expression(&expression);
+//!
+//! assert!(matches!(
+//!     allocated,
+//!     arena::Expression::Literal {
+//!         literal: arena::Literal::String { value: "hello", .. },
+//!         ..
+//!     }
+//! ));
+//! ```
+//!
+//! This module is only available with the `arena` feature.
+
+use bumpalo::Bump;
+use stellar_filesystem::location::Location;
+
+use crate::{
+    BinaryOperator, BindingMode, ConstValue, ElementKind, IdentifierAST, LambdaCapture, NodeId,
+    PostfixOperator, PrefixOperator, RangeKind,
+};
+
+/// A bump allocator, that owns arena-allocated nodes.
+///
+/// See [module level docs](crate::arena) for more details.
+#[derive(Debug, Default)]
+pub struct Arena {
+    bump: Bump,
+}
+
+impl Arena {
+    /// Creates a new empty arena.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new empty arena, that can hold at least `capacity` bytes
+    /// before allocating more memory.
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            bump: Bump::with_capacity(capacity),
+        }
+    }
+
+    /// Returns how many bytes are allocated for the arena, including the
+    /// unused space of its chunks.
+    #[inline]
+    #[must_use]
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+
+    /// Allocates a copy of an expression in the arena.
+    #[inline]
+    pub fn alloc_expression<'a>(&'a self, expression: &crate::Expression) -> &'a Expression<'a> {
+        self.bump.alloc(self.expression(expression))
+    }
+
+    /// Allocates a copy of a statements block, e.g. a function body, in the
+    /// arena.
+    #[inline]
+    pub fn alloc_statements_block<'a>(
+        &'a self,
+        statements: &[crate::Statement],
+    ) -> &'a [Statement<'a>] {
+        self.statements_block(statements)
+    }
+
+    /// Allocates a copy of a statement in the arena.
+    #[inline]
+    pub fn alloc_statement<'a>(&'a self, statement: &crate::Statement) -> &'a Statement<'a> {
+        self.bump.alloc(self.statement(statement))
+    }
+
+    /// Allocates a copy of a pattern in the arena.
+    #[inline]
+    pub fn alloc_pattern<'a>(&'a self, pattern: &crate::Pattern) -> &'a Pattern<'a> {
+        self.bump.alloc(self.pattern(pattern))
+    }
+
+    /// Allocates a copy of a type in the arena.
+    #[inline]
+    pub fn alloc_type<'a>(&'a self, ty: &crate::Type) -> &'a Type<'a> {
+        self.bump.alloc(self.ty(ty))
+    }
+
+    fn slice<'a, T, U>(&'a self, items: &[T], f: impl FnMut(&T) -> U) -> &'a [U] {
+        self.bump.alloc_slice_fill_iter(items.iter().map(f))
+    }
+
+    fn boxed<'a, T, U>(&'a self, node: &T, f: impl FnOnce(&T) -> U) -> &'a U {
+        self.bump.alloc(f(node))
+    }
+
+    fn statements_block<'a>(&'a self, statements: &[crate::Statement]) -> &'a [Statement<'a>] {
+        self.slice(statements, |statement| self.statement(statement))
+    }
+
+    fn expression<'a>(&'a self, expression: &crate::Expression) -> Expression<'a> {
+        use crate::Expression as E;
+
+        let expression_ref = |inner: &crate::Expression| self.alloc_expression(inner);
+
+        match expression {
+            E::List {
+                node_id,
+                location,
+                elements,
+            } => Expression::List {
+                node_id: *node_id,
+                location: *location,
+                elements: self.slice(elements, |element| ListElement {
+                    kind: element.kind,
+                    value: self.expression(&element.value),
+                }),
+            },
+            E::ArrayRepeat {
+                node_id,
+                location,
+                value,
+                size,
+            } => Expression::ArrayRepeat {
+                node_id: *node_id,
+                location: *location,
+                value: expression_ref(value),
+                size: expression_ref(size),
+            },
+            E::Borrow {
+                node_id,
+                location,
+                is_mutable,
+                inner,
+            } => Expression::Borrow {
+                node_id: *node_id,
+                location: *location,
+                is_mutable: *is_mutable,
+                inner: expression_ref(inner),
+            },
+            E::Map {
+                node_id,
+                location,
+                entries,
+            } => Expression::Map {
+                node_id: *node_id,
+                location: *location,
+                entries: self.slice(entries, |entry| MapEntry {
+                    key: self.expression(&entry.key),
+                    value: self.expression(&entry.value),
+                }),
+            },
+            E::As {
+                node_id,
+                location,
+                left,
+                right,
+            } => Expression::As {
+                node_id: *node_id,
+                location: *location,
+                left: expression_ref(left),
+                right: self.ty(right),
+            },
+            E::Loop {
+                node_id,
+                location,
+                statements_block,
+            } => Expression::Loop {
+                node_id: *node_id,
+                location: *location,
+                statements_block: self.statements_block(statements_block),
+            },
+            E::Binary {
+                node_id,
+                location,
+                left,
+                operator,
+                right,
+            } => Expression::Binary {
+                node_id: *node_id,
+                location: *location,
+                left: expression_ref(left),
+                operator: *operator,
+                right: expression_ref(right),
+            },
+            E::Range {
+                node_id,
+                location,
+                start,
+                end,
+                kind,
+            } => Expression::Range {
+                node_id: *node_id,
+                location: *location,
+                start: start.as_deref().map(expression_ref),
+                end: end.as_deref().map(expression_ref),
+                kind: *kind,
+            },
+            E::StatementsBlock {
+                node_id,
+                location,
+                block,
+            } => Expression::StatementsBlock {
+                node_id: *node_id,
+                location: *location,
+                block: self.statements_block(block),
+            },
+            E::Await {
+                node_id,
+                location,
+                inner,
+            } => Expression::Await {
+                node_id: *node_id,
+                location: *location,
+                inner: expression_ref(inner),
+            },
+            E::Unsafe {
+                node_id,
+                location,
+                block,
+            } => Expression::Unsafe {
+                node_id: *node_id,
+                location: *location,
+                block: self.statements_block(block),
+            },
+            E::Try {
+                node_id,
+                location,
+                block,
+            } => Expression::Try {
+                node_id: *node_id,
+                location: *location,
+                block: self.statements_block(block),
+            },
+            E::Literal { node_id, literal } => Expression::Literal {
+                node_id: *node_id,
+                literal: self.literal(literal),
+            },
+            E::Identifier {
+                node_id,
+                identifier,
+            } => Expression::Identifier {
+                node_id: *node_id,
+                identifier: *identifier,
+            },
+            E::Underscore { node_id, location } => Expression::Underscore {
+                node_id: *node_id,
+                location: *location,
+            },
+            E::Parenthesized {
+                node_id,
+                location,
+                inner,
+            } => Expression::Parenthesized {
+                node_id: *node_id,
+                location: *location,
+                inner: expression_ref(inner),
+            },
+            E::If {
+                node_id,
+                location,
+                if_blocks,
+                r#else,
+            } => Expression::If {
+                node_id: *node_id,
+                location: *location,
+                if_blocks: self.slice(if_blocks, |(condition, block)| {
+                    (self.expression(condition), self.statements_block(block))
+                }),
+                r#else: r#else.as_deref().map(|block| self.statements_block(block)),
+            },
+            E::IfLet {
+                node_id,
+                location,
+                pattern,
+                expression,
+                block,
+                r#else,
+            } => Expression::IfLet {
+                node_id: *node_id,
+                location: *location,
+                pattern: self.pattern(pattern),
+                expression: expression_ref(expression),
+                block: self.statements_block(block),
+                r#else: r#else.as_deref().map(|block| self.statements_block(block)),
+            },
+            E::FieldAccess {
+                node_id,
+                location,
+                left,
+                right,
+            } => Expression::FieldAccess {
+                node_id: *node_id,
+                location: *location,
+                left: expression_ref(left),
+                right: *right,
+            },
+            E::TupleIndex {
+                node_id,
+                location,
+                left,
+                index,
+                index_location,
+            } => Expression::TupleIndex {
+                node_id: *node_id,
+                location: *location,
+                left: expression_ref(left),
+                index: *index,
+                index_location: *index_location,
+            },
+            E::Prefix {
+                node_id,
+                location,
+                inner,
+                operator,
+            } => Expression::Prefix {
+                node_id: *node_id,
+                location: *location,
+                inner: expression_ref(inner),
+                operator: *operator,
+            },
+            E::Postfix {
+                node_id,
+                location,
+                inner,
+                operator,
+            } => Expression::Postfix {
+                node_id: *node_id,
+                location: *location,
+                inner: expression_ref(inner),
+                operator: *operator,
+            },
+            E::While {
+                node_id,
+                location,
+                condition,
+                statements_block,
+            } => Expression::While {
+                node_id: *node_id,
+                location: *location,
+                condition: expression_ref(condition),
+                statements_block: self.statements_block(statements_block),
+            },
+            E::WhileLet {
+                node_id,
+                location,
+                pattern,
+                expression,
+                statements_block,
+            } => Expression::WhileLet {
+                node_id: *node_id,
+                location: *location,
+                pattern: self.pattern(pattern),
+                expression: expression_ref(expression),
+                statements_block: self.statements_block(statements_block),
+            },
+            E::For {
+                node_id,
+                location,
+                pattern,
+                iterable,
+                statements_block,
+            } => Expression::For {
+                node_id: *node_id,
+                location: *location,
+                pattern: self.pattern(pattern),
+                iterable: expression_ref(iterable),
+                statements_block: self.statements_block(statements_block),
+            },
+            E::Call {
+                node_id,
+                location,
+                callee,
+                arguments,
+            } => Expression::Call {
+                node_id: *node_id,
+                location: *location,
+                callee: expression_ref(callee),
+                arguments: self.slice(arguments, |argument| CallArgument {
+                    name: argument.name,
+                    kind: argument.kind,
+                    value: self.expression(&argument.value),
+                    is_shorthand: argument.is_shorthand,
+                }),
+            },
+            E::TypeArguments {
+                node_id,
+                location,
+                left,
+                arguments,
+            } => Expression::TypeArguments {
+                node_id: *node_id,
+                location: *location,
+                left: expression_ref(left),
+                arguments: self.slice(arguments, |argument| self.ty(argument)),
+            },
+            E::Tuple {
+                node_id,
+                location,
+                elements,
+            } => Expression::Tuple {
+                node_id: *node_id,
+                location: *location,
+                elements: self.slice(elements, |element| self.expression(element)),
+            },
+            E::Struct {
+                node_id,
+                location,
+                left,
+                fields,
+                rest,
+            } => Expression::Struct {
+                node_id: *node_id,
+                location: *location,
+                left: expression_ref(left),
+                fields: self.slice(fields, |field| StructFieldExpression {
+                    name: field.name,
+                    value: field.value.as_ref().map(|value| self.expression(value)),
+                }),
+                rest: rest.as_deref().map(expression_ref),
+            },
+            E::Match {
+                node_id,
+                location,
+                expression,
+                block,
+            } => Expression::Match {
+                node_id: *node_id,
+                location: *location,
+                expression: expression_ref(expression),
+                block: self.slice(block, |item| MatchExpressionItem {
+                    left: self.pattern(&item.left),
+                    right: self.expression(&item.right),
+                }),
+            },
+            E::Lambda {
+                node_id,
+                location,
+                captures,
+                parameters,
+                return_type,
+                value,
+            } => Expression::Lambda {
+                node_id: *node_id,
+                location: *location,
+                captures: self.bump.alloc_slice_copy(captures),
+                parameters: self.slice(parameters, |parameter| LambdaFunctionParameter {
+                    name: parameter.name,
+                    ty: parameter.ty.as_ref().map(|ty| self.ty(ty)),
+                }),
+                return_type: return_type.as_ref().map(|ty| self.ty(ty)),
+                value: expression_ref(value),
+            },
+        }
+    }
+
+    fn statement<'a>(&'a self, statement: &crate::Statement) -> Statement<'a> {
+        use crate::Statement as S;
+
+        match statement {
+            S::Defer { node_id, body } => Statement::Defer {
+                node_id: *node_id,
+                body: match body {
+                    crate::DeferBody::Call(call) => DeferBody::Call(self.expression(call)),
+                    crate::DeferBody::Block { location, block } => DeferBody::Block {
+                        location: *location,
+                        block: self.statements_block(block),
+                    },
+                },
+            },
+            S::Expression {
+                node_id,
+                expression,
+                has_semicolon,
+            } => Statement::Expression {
+                node_id: *node_id,
+                expression: self.expression(expression),
+                has_semicolon: *has_semicolon,
+            },
+            S::Break { node_id, location } => Statement::Break {
+                node_id: *node_id,
+                location: *location,
+            },
+            S::Continue { node_id, location } => Statement::Continue {
+                node_id: *node_id,
+                location: *location,
+            },
+            S::Return {
+                node_id,
+                expression,
+            } => Statement::Return {
+                node_id: *node_id,
+                expression: self.expression(expression),
+            },
+            S::Yield {
+                node_id,
+                location,
+                expression,
+            } => Statement::Yield {
+                node_id: *node_id,
+                location: *location,
+                expression: self.expression(expression),
+            },
+            S::Let {
+                node_id,
+                pattern,
+                value,
+                ty,
+            } => Statement::Let {
+                node_id: *node_id,
+                pattern: self.pattern(pattern),
+                value: self.expression(value),
+                ty: ty.as_ref().map(|ty| self.ty(ty)),
+            },
+        }
+    }
+
+    fn pattern<'a>(&'a self, pattern: &crate::Pattern) -> Pattern<'a> {
+        use crate::Pattern as P;
+
+        let patterns = |patterns: &[crate::Pattern]| self.slice(patterns, |p| self.pattern(p));
+
+        match pattern {
+            P::Literal(literal) => Pattern::Literal(self.literal(literal)),
+            P::NegativeNumericLiteral(literal) => {
+                Pattern::NegativeNumericLiteral(self.negative_numeric_literal(literal))
+            }
+            P::Identifier {
+                location,
+                binding_mode,
+                identifier,
+                pattern,
+            } => Pattern::Identifier {
+                location: *location,
+                binding_mode: *binding_mode,
+                identifier: *identifier,
+                pattern: pattern
+                    .as_deref()
+                    .map(|pattern| self.boxed(pattern, |p| self.pattern(p))),
+            },
+            P::Wildcard { location } => Pattern::Wildcard {
+                location: *location,
+            },
+            P::Struct {
+                location,
+                path,
+                fields,
+            } => Pattern::Struct {
+                location: *location,
+                path: self.path(path),
+                fields: self.slice(fields, |field| match field {
+                    crate::StructFieldPattern::NotRest {
+                        location,
+                        field_name,
+                        value_pattern,
+                    } => StructFieldPattern::NotRest {
+                        location: *location,
+                        field_name: *field_name,
+                        value_pattern: value_pattern.as_ref().map(|p| self.pattern(p)),
+                    },
+                    crate::StructFieldPattern::Rest { location } => StructFieldPattern::Rest {
+                        location: *location,
+                    },
+                }),
+            },
+            P::TupleLike {
+                location,
+                path,
+                inner_patterns,
+            } => Pattern::TupleLike {
+                location: *location,
+                path: self.path(path),
+                inner_patterns: patterns(inner_patterns),
+            },
+            P::Tuple { location, elements } => Pattern::Tuple {
+                location: *location,
+                elements: patterns(elements),
+            },
+            P::Path { path } => Pattern::Path {
+                path: self.path(path),
+            },
+            P::List {
+                location,
+                inner_patterns,
+            } => Pattern::List {
+                location: *location,
+                inner_patterns: patterns(inner_patterns),
+            },
+            P::Grouped { location, inner } => Pattern::Grouped {
+                location: *location,
+                inner: self.boxed(inner.as_ref(), |p| self.pattern(p)),
+            },
+            P::Or {
+                location,
+                alternatives,
+            } => Pattern::Or {
+                location: *location,
+                alternatives: patterns(alternatives),
+            },
+            P::Range {
+                location,
+                start,
+                end,
+                kind,
+            } => Pattern::Range {
+                location: *location,
+                start: self.boxed(start.as_ref(), |p| self.pattern(p)),
+                end: self.boxed(end.as_ref(), |p| self.pattern(p)),
+                kind: *kind,
+            },
+            P::Rest { location } => Pattern::Rest {
+                location: *location,
+            },
+        }
+    }
+
+    fn ty<'a>(&'a self, ty: &crate::Type) -> Type<'a> {
+        use crate::Type as T;
+
+        let types = |types: &[crate::Type]| self.slice(types, |ty| self.ty(ty));
+        let type_ref = |ty: &crate::Type| self.boxed(ty, |ty| self.ty(ty));
+
+        match ty {
+            T::Constructor(constructor) => Type::Constructor(self.type_constructor(constructor)),
+            T::Tuple {
+                location,
+                element_types,
+            } => Type::Tuple {
+                location: *location,
+                element_types: types(element_types),
+            },
+            T::Function {
+                location,
+                parameter_types,
+                return_type,
+            } => Type::Function {
+                location: *location,
+                parameter_types: types(parameter_types),
+                return_type: return_type.as_deref().map(type_ref),
+            },
+            T::Parenthesized { location, inner } => Type::Parenthesized {
+                location: *location,
+                inner: type_ref(inner),
+            },
+            T::Underscore { location } => Type::Underscore {
+                location: *location,
+            },
+            T::InterfaceObject { location, bounds } => Type::InterfaceObject {
+                location: *location,
+                bounds: self.slice(bounds, |bound| self.type_constructor(bound)),
+            },
+            T::Const { location, value } => Type::Const {
+                location: *location,
+                value: *value,
+            },
+            T::Array {
+                location,
+                element_type,
+                size,
+            } => Type::Array {
+                location: *location,
+                element_type: type_ref(element_type),
+                size: type_ref(size),
+            },
+            T::Reference {
+                location,
+                is_mutable,
+                inner,
+            } => Type::Reference {
+                location: *location,
+                is_mutable: *is_mutable,
+                inner: type_ref(inner),
+            },
+            T::Optional { location, inner } => Type::Optional {
+                location: *location,
+                inner: type_ref(inner),
+            },
+        }
+    }
+
+    fn type_constructor<'a>(&'a self, constructor: &crate::TypeConstructor) -> TypeConstructor<'a> {
+        TypeConstructor {
+            location: constructor.location,
+            path: self.path(&constructor.path),
+            arguments: self.slice(&constructor.arguments, |argument| self.ty(argument)),
+        }
+    }
+
+    fn path<'a>(&'a self, path: &crate::Path) -> Path<'a> {
+        Path {
+            location: path.location,
+            identifiers: self.bump.alloc_slice_copy(&path.identifiers),
+        }
+    }
+
+    fn literal<'a>(&'a self, literal: &crate::Literal) -> Literal<'a> {
+        match literal {
+            crate::Literal::Boolean { value, location } => Literal::Boolean {
+                value: *value,
+                location: *location,
+            },
+            crate::Literal::Character { value, location } => Literal::Character {
+                value: *value,
+                location: *location,
+            },
+            crate::Literal::String { value, location } => Literal::String {
+                value: self.bump.alloc_str(value),
+                location: *location,
+            },
+            crate::Literal::Integer { value, location } => Literal::Integer {
+                value: self.bump.alloc_slice_copy(value.limbs()),
+                location: *location,
+            },
+            crate::Literal::Float { value, location } => Literal::Float {
+                value: *value,
+                location: *location,
+            },
+        }
+    }
+
+    fn negative_numeric_literal<'a>(
+        &'a self,
+        literal: &crate::NegativeNumericLiteral,
+    ) -> NegativeNumericLiteral<'a> {
+        match literal {
+            crate::NegativeNumericLiteral::Float { location, value } => {
+                NegativeNumericLiteral::Float {
+                    location: *location,
+                    value: *value,
+                }
+            }
+            crate::NegativeNumericLiteral::Integer { location, value } => {
+                NegativeNumericLiteral::Integer {
+                    location: *location,
+                    value: self.bump.alloc_slice_copy(value.limbs()),
+                }
+            }
+        }
+    }
+}
+
+/// A literal, see [`crate::Literal`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Literal<'a> {
+    Boolean {
+        value: bool,
+        location: Location,
+    },
+    Character {
+        value: char,
+        location: Location,
+    },
+    String {
+        value: &'a str,
+        location: Location,
+    },
+    /// Little-endian 64-bit limbs of the value, see
+    /// [`IntegerValue::limbs`](crate::IntegerValue::limbs).
+    Integer {
+        value: &'a [u64],
+        location: Location,
+    },
+    Float {
+        value: f64,
+        location: Location,
+    },
+}
+
+/// A negative numeric literal, see [`crate::NegativeNumericLiteral`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NegativeNumericLiteral<'a> {
+    Float {
+        location: Location,
+        value: f64,
+    },
+    /// Little-endian 64-bit limbs of the absolute value, see
+    /// [`IntegerValue::limbs`](crate::IntegerValue::limbs).
+    Integer {
+        location: Location,
+        value: &'a [u64],
+    },
+}
+
+/// A path, see [`crate::Path`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Path<'a> {
+    pub location: Location,
+    pub identifiers: &'a [IdentifierAST],
+}
+
+/// A type constructor, see [`crate::TypeConstructor`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct TypeConstructor<'a> {
+    pub location: Location,
+    pub path: Path<'a>,
+    pub arguments: &'a [Type<'a>],
+}
+
+/// A type, see [`crate::Type`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Type<'a> {
+    Constructor(TypeConstructor<'a>),
+    Tuple {
+        location: Location,
+        element_types: &'a [Self],
+    },
+    Function {
+        location: Location,
+        parameter_types: &'a [Self],
+        return_type: Option<&'a Self>,
+    },
+    Parenthesized {
+        location: Location,
+        inner: &'a Self,
+    },
+    Underscore {
+        location: Location,
+    },
+    InterfaceObject {
+        location: Location,
+        bounds: &'a [TypeConstructor<'a>],
+    },
+    Const {
+        location: Location,
+        value: ConstValue,
+    },
+    Array {
+        location: Location,
+        element_type: &'a Self,
+        size: &'a Self,
+    },
+    Reference {
+        location: Location,
+        is_mutable: bool,
+        inner: &'a Self,
+    },
+    Optional {
+        location: Location,
+        inner: &'a Self,
+    },
+}
+
+/// A pattern, see [`crate::Pattern`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Pattern<'a> {
+    Literal(Literal<'a>),
+    NegativeNumericLiteral(NegativeNumericLiteral<'a>),
+    Identifier {
+        location: Location,
+        binding_mode: BindingMode,
+        identifier: IdentifierAST,
+        pattern: Option<&'a Self>,
+    },
+    Wildcard {
+        location: Location,
+    },
+    Struct {
+        location: Location,
+        path: Path<'a>,
+        fields: &'a [StructFieldPattern<'a>],
+    },
+    TupleLike {
+        location: Location,
+        path: Path<'a>,
+        inner_patterns: &'a [Self],
+    },
+    Tuple {
+        location: Location,
+        elements: &'a [Self],
+    },
+    Path {
+        path: Path<'a>,
+    },
+    List {
+        location: Location,
+        inner_patterns: &'a [Self],
+    },
+    Grouped {
+        location: Location,
+        inner: &'a Self,
+    },
+    Or {
+        location: Location,
+        alternatives: &'a [Self],
+    },
+    Range {
+        location: Location,
+        start: &'a Self,
+        end: &'a Self,
+        kind: RangeKind,
+    },
+    Rest {
+        location: Location,
+    },
+}
+
+/// A pattern used to match a struct field, see [`crate::StructFieldPattern`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StructFieldPattern<'a> {
+    NotRest {
+        location: Location,
+        field_name: IdentifierAST,
+        value_pattern: Option<Pattern<'a>>,
+    },
+    Rest {
+        location: Location,
+    },
+}
+
+/// An expression, see [`crate::Expression`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Expression<'a> {
+    List {
+        node_id: NodeId,
+        location: Location,
+        elements: &'a [ListElement<'a>],
+    },
+    ArrayRepeat {
+        node_id: NodeId,
+        location: Location,
+        value: &'a Self,
+        size: &'a Self,
+    },
+    Borrow {
+        node_id: NodeId,
+        location: Location,
+        is_mutable: bool,
+        inner: &'a Self,
+    },
+    Map {
+        node_id: NodeId,
+        location: Location,
+        entries: &'a [MapEntry<'a>],
+    },
+    As {
+        node_id: NodeId,
+        location: Location,
+        left: &'a Self,
+        right: Type<'a>,
+    },
+    Loop {
+        node_id: NodeId,
+        location: Location,
+        statements_block: &'a [Statement<'a>],
+    },
+    Binary {
+        node_id: NodeId,
+        location: Location,
+        left: &'a Self,
+        operator: BinaryOperator,
+        right: &'a Self,
+    },
+    Range {
+        node_id: NodeId,
+        location: Location,
+        start: Option<&'a Self>,
+        end: Option<&'a Self>,
+        kind: RangeKind,
+    },
+    StatementsBlock {
+        node_id: NodeId,
+        location: Location,
+        block: &'a [Statement<'a>],
+    },
+    Await {
+        node_id: NodeId,
+        location: Location,
+        inner: &'a Self,
+    },
+    Unsafe {
+        node_id: NodeId,
+        location: Location,
+        block: &'a [Statement<'a>],
+    },
+    Try {
+        node_id: NodeId,
+        location: Location,
+        block: &'a [Statement<'a>],
+    },
+    Literal {
+        node_id: NodeId,
+        literal: Literal<'a>,
+    },
+    Identifier {
+        node_id: NodeId,
+        identifier: IdentifierAST,
+    },
+    Underscore {
+        node_id: NodeId,
+        location: Location,
+    },
+    Parenthesized {
+        node_id: NodeId,
+        location: Location,
+        inner: &'a Self,
+    },
+    If {
+        node_id: NodeId,
+        location: Location,
+        if_blocks: &'a [(Self, &'a [Statement<'a>])],
+        r#else: Option<&'a [Statement<'a>]>,
+    },
+    IfLet {
+        node_id: NodeId,
+        location: Location,
+        pattern: Pattern<'a>,
+        expression: &'a Self,
+        block: &'a [Statement<'a>],
+        r#else: Option<&'a [Statement<'a>]>,
+    },
+    FieldAccess {
+        node_id: NodeId,
+        location: Location,
+        left: &'a Self,
+        right: IdentifierAST,
+    },
+    TupleIndex {
+        node_id: NodeId,
+        location: Location,
+        left: &'a Self,
+        index: usize,
+        index_location: Location,
+    },
+    Prefix {
+        node_id: NodeId,
+        location: Location,
+        inner: &'a Self,
+        operator: PrefixOperator,
+    },
+    Postfix {
+        node_id: NodeId,
+        location: Location,
+        inner: &'a Self,
+        operator: PostfixOperator,
+    },
+    While {
+        node_id: NodeId,
+        location: Location,
+        condition: &'a Self,
+        statements_block: &'a [Statement<'a>],
+    },
+    WhileLet {
+        node_id: NodeId,
+        location: Location,
+        pattern: Pattern<'a>,
+        expression: &'a Self,
+        statements_block: &'a [Statement<'a>],
+    },
+    For {
+        node_id: NodeId,
+        location: Location,
+        pattern: Pattern<'a>,
+        iterable: &'a Self,
+        statements_block: &'a [Statement<'a>],
+    },
+    Call {
+        node_id: NodeId,
+        location: Location,
+        callee: &'a Self,
+        arguments: &'a [CallArgument<'a>],
+    },
+    TypeArguments {
+        node_id: NodeId,
+        location: Location,
+        left: &'a Self,
+        arguments: &'a [Type<'a>],
+    },
+    Tuple {
+        node_id: NodeId,
+        location: Location,
+        elements: &'a [Self],
+    },
+    Struct {
+        node_id: NodeId,
+        location: Location,
+        left: &'a Self,
+        fields: &'a [StructFieldExpression<'a>],
+        rest: Option<&'a Self>,
+    },
+    Match {
+        node_id: NodeId,
+        location: Location,
+        expression: &'a Self,
+        block: &'a [MatchExpressionItem<'a>],
+    },
+    Lambda {
+        node_id: NodeId,
+        location: Location,
+        captures: &'a [LambdaCapture],
+        parameters: &'a [LambdaFunctionParameter<'a>],
+        return_type: Option<Type<'a>>,
+        value: &'a Self,
+    },
+}
+
+impl Expression<'_> {
+    /// Returns the location of the expression.
+    #[inline]
+    #[must_use]
+    pub const fn location(&self) -> Location {
+        match self {
+            Self::List { location, .. }
+            | Self::ArrayRepeat { location, .. }
+            | Self::Borrow { location, .. }
+            | Self::Map { location, .. }
+            | Self::As { location, .. }
+            | Self::Loop { location, .. }
+            | Self::Binary { location, .. }
+            | Self::Range { location, .. }
+            | Self::StatementsBlock { location, .. }
+            | Self::Await { location, .. }
+            | Self::Unsafe { location, .. }
+            | Self::Try { location, .. }
+            | Self::Literal {
+                literal:
+                    Literal::Boolean { location, .. }
+                    | Literal::Character { location, .. }
+                    | Literal::String { location, .. }
+                    | Literal::Integer { location, .. }
+                    | Literal::Float { location, .. },
+                ..
+            }
+            | Self::Identifier {
+                identifier: IdentifierAST { location, .. },
+                ..
+            }
+            | Self::Underscore { location, .. }
+            | Self::Parenthesized { location, .. }
+            | Self::If { location, .. }
+            | Self::IfLet { location, .. }
+            | Self::FieldAccess { location, .. }
+            | Self::TupleIndex { location, .. }
+            | Self::Prefix { location, .. }
+            | Self::Postfix { location, .. }
+            | Self::While { location, .. }
+            | Self::WhileLet { location, .. }
+            | Self::For { location, .. }
+            | Self::Call { location, .. }
+            | Self::TypeArguments { location, .. }
+            | Self::Tuple { location, .. }
+            | Self::Struct { location, .. }
+            | Self::Match { location, .. }
+            | Self::Lambda { location, .. } => *location,
+        }
+    }
+
+    /// Returns the node ID of the expression.
+    #[inline]
+    #[must_use]
+    pub const fn node_id(&self) -> NodeId {
+        match self {
+            Self::List { node_id, .. }
+            | Self::ArrayRepeat { node_id, .. }
+            | Self::Borrow { node_id, .. }
+            | Self::Map { node_id, .. }
+            | Self::As { node_id, .. }
+            | Self::Loop { node_id, .. }
+            | Self::Binary { node_id, .. }
+            | Self::Range { node_id, .. }
+            | Self::StatementsBlock { node_id, .. }
+            | Self::Await { node_id, .. }
+            | Self::Unsafe { node_id, .. }
+            | Self::Try { node_id, .. }
+            | Self::Literal { node_id, .. }
+            | Self::Identifier { node_id, .. }
+            | Self::Underscore { node_id, .. }
+            | Self::Parenthesized { node_id, .. }
+            | Self::If { node_id, .. }
+            | Self::IfLet { node_id, .. }
+            | Self::FieldAccess { node_id, .. }
+            | Self::TupleIndex { node_id, .. }
+            | Self::Prefix { node_id, .. }
+            | Self::Postfix { node_id, .. }
+            | Self::While { node_id, .. }
+            | Self::WhileLet { node_id, .. }
+            | Self::For { node_id, .. }
+            | Self::Call { node_id, .. }
+            | Self::TypeArguments { node_id, .. }
+            | Self::Tuple { node_id, .. }
+            | Self::Struct { node_id, .. }
+            | Self::Match { node_id, .. }
+            | Self::Lambda { node_id, .. } => *node_id,
+        }
+    }
+}
+
+/// An element of a list expression, see [`crate::ListElement`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ListElement<'a> {
+    pub kind: ElementKind,
+    pub value: Expression<'a>,
+}
+
+/// An entry of a map expression, see [`crate::MapEntry`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct MapEntry<'a> {
+    pub key: Expression<'a>,
+    pub value: Expression<'a>,
+}
+
+/// An argument in a call expression, see [`crate::CallArgument`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CallArgument<'a> {
+    pub name: Option<IdentifierAST>,
+    pub kind: ElementKind,
+    pub value: Expression<'a>,
+    pub is_shorthand: bool,
+}
+
+/// A field item in a struct expression, see [`crate::StructFieldExpression`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct StructFieldExpression<'a> {
+    pub name: IdentifierAST,
+    pub value: Option<Expression<'a>>,
+}
+
+/// A match expression item, see [`crate::MatchExpressionItem`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct MatchExpressionItem<'a> {
+    pub left: Pattern<'a>,
+    pub right: Expression<'a>,
+}
+
+/// A lambda function parameter, see [`crate::LambdaFunctionParameter`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LambdaFunctionParameter<'a> {
+    pub name: IdentifierAST,
+    pub ty: Option<Type<'a>>,
+}
+
+/// A body of a defer statement, see [`crate::DeferBody`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DeferBody<'a> {
+    Call(Expression<'a>),
+    Block {
+        location: Location,
+        block: &'a [Statement<'a>],
+    },
+}
+
+/// A statement, see [`crate::Statement`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Statement<'a> {
+    Defer {
+        node_id: NodeId,
+        body: DeferBody<'a>,
+    },
+    Expression {
+        node_id: NodeId,
+        expression: Expression<'a>,
+        has_semicolon: bool,
+    },
+    Break {
+        node_id: NodeId,
+        location: Location,
+    },
+    Continue {
+        node_id: NodeId,
+        location: Location,
+    },
+    Return {
+        node_id: NodeId,
+        expression: Expression<'a>,
+    },
+    Yield {
+        node_id: NodeId,
+        location: Location,
+        expression: Expression<'a>,
+    },
+    Let {
+        node_id: NodeId,
+        pattern: Pattern<'a>,
+        value: Expression<'a>,
+        ty: Option<Type<'a>>,
+    },
+}
+
+impl Statement<'_> {
+    /// Returns the node ID of the statement.
+    #[inline]
+    #[must_use]
+    pub const fn node_id(&self) -> NodeId {
+        match self {
+            Self::Defer { node_id, .. }
+            | Self::Expression { node_id, .. }
+            | Self::Break { node_id, .. }
+            | Self::Continue { node_id, .. }
+            | Self::Return { node_id, .. }
+            | Self::Yield { node_id, .. }
+            | Self::Let { node_id, .. } => *node_id,
+        }
+    }
+}
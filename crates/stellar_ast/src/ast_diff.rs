@@ -0,0 +1,337 @@
+//! Structural comparison of two versions of a module.
+//!
+//! [`diff_modules()`] matches items of the old and the new module by their
+//! kinds and paths, e.g. `Point.new` for a method `new` of a struct `Point`,
+//! and reports items, that were added, removed or changed, as well as
+//! functions, that were moved into another item or submodule without being
+//! changed:
+//!
+//! ```
+//! use stellar_ast::{
+//!     ast_diff::{diff_modules, ItemChange},
+//!     builder::{named_type, FunctionBuilder, StructBuilder},
+//!     Module, ModuleItem,
+//! };
+//! use stellar_interner::DUMMY_PATH_ID;
+//!
+//! let module = |items: Vec<ModuleItem>| Module {
+//!     filepath: DUMMY_PATH_ID,
+//!     items,
+//!     docstring: None,
+//! };
+//! let new = || FunctionBuilder::new("new").with_return_type(named_type("Point"));
+//!
+//! let old = module(vec![
+//!     StructBuilder::new("Point").into(),
+//!     new().into(),
+//! ]);
+//! let new = module(vec![StructBuilder::new("Point").with_method(new()).into()]);
+//!
+//! let diff = diff_modules(&old, &new);
+//!
+//! assert!(matches!(
+//!     diff.changes(),
+//!     [ItemChange::Moved { from, to, .. }] if from == "new" && to == "Point.new"
+//! ));
+//! ```
+//!
+//! Items are compared by their [unparsed](crate::unparse) source code, so
+//! locations, node IDs and formatting don't matter: an item, that was only
+//! shifted by an edit before it or reformatted, is not reported. Docstrings
+//! are not compared as well. Order of items is not significant.
+//!
+//! Methods are compared separately from the items, that contain them, so a
+//! changed method body is reported as a change of the method only.
+
+use std::{
+    collections::{hash_map::Entry as MapEntry, HashMap, VecDeque},
+    fmt::{self, Display},
+};
+
+use stellar_filesystem::location::Location;
+
+use crate::{
+    unparse::{unparse_item, unparse_type},
+    Module, ModuleItem, ModuleItemKind, Type,
+};
+
+/// A difference between two versions of an item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemChange {
+    /// The item exists only in the new module.
+    Added {
+        /// Path of the item, e.g. `Point.new`.
+        path: String,
+
+        /// Location of the item in the new module.
+        location: Location,
+    },
+
+    /// The item exists only in the old module.
+    Removed {
+        /// Path of the item, e.g. `Point.new`.
+        path: String,
+
+        /// Location of the item in the old module.
+        location: Location,
+    },
+
+    /// The item exists in both modules, but its contents are different.
+    Changed {
+        /// Path of the item, e.g. `Point.new`.
+        path: String,
+
+        /// Location of the item in the old module.
+        old_location: Location,
+
+        /// Location of the item in the new module.
+        new_location: Location,
+    },
+
+    /// The function was moved into another item or submodule without being
+    /// changed, e.g. from `new` to `Point.new`.
+    Moved {
+        /// Path of the function in the old module.
+        from: String,
+
+        /// Path of the function in the new module.
+        to: String,
+
+        /// Location of the function in the old module.
+        old_location: Location,
+
+        /// Location of the function in the new module.
+        new_location: Location,
+    },
+}
+
+impl ItemChange {
+    /// Returns the path of the item, for moved functions - the new one.
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Added { path, .. } | Self::Removed { path, .. } | Self::Changed { path, .. } => {
+                path
+            }
+            Self::Moved { to, .. } => to,
+        }
+    }
+}
+
+impl Display for ItemChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added { path, .. } => write!(f, "added `{path}`"),
+            Self::Removed { path, .. } => write!(f, "removed `{path}`"),
+            Self::Changed { path, .. } => write!(f, "changed `{path}`"),
+            Self::Moved { from, to, .. } => write!(f, "moved `{from}` to `{to}`"),
+        }
+    }
+}
+
+/// Changes between two versions of a module, see [`diff_modules()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AstDiff {
+    changes: Vec<ItemChange>,
+}
+
+impl AstDiff {
+    /// Returns the changes: added, changed and moved items in the order of
+    /// the new module, followed by removed items in the order of the old one.
+    #[inline]
+    #[must_use]
+    pub fn changes(&self) -> &[ItemChange] {
+        &self.changes
+    }
+
+    /// Returns `true` if the modules are structurally equal.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Display for AstDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, change) in self.changes.iter().enumerate() {
+            if idx != 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{change}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compares two versions of a module, see [module level docs](crate::ast_diff)
+/// for more details.
+#[must_use]
+pub fn diff_modules(old: &Module, new: &Module) -> AstDiff {
+    let old_items = flatten(&old.items);
+    let new_items = flatten(&new.items);
+
+    // items with the same kind and path are matched in order of appearance
+    let mut old_by_key = HashMap::<_, VecDeque<usize>>::new();
+    for (idx, item) in old_items.iter().enumerate() {
+        old_by_key
+            .entry((item.kind, item.path.as_str()))
+            .or_default()
+            .push_back(idx);
+    }
+
+    let mut old_matched = vec![false; old_items.len()];
+    let mut added = vec![];
+    let mut changes = vec![];
+
+    for (new_idx, item) in new_items.iter().enumerate() {
+        let old_idx = match old_by_key.entry((item.kind, item.path.as_str())) {
+            MapEntry::Occupied(mut entry) => entry.get_mut().pop_front(),
+            MapEntry::Vacant(..) => None,
+        };
+
+        let Some(old_idx) = old_idx else {
+            added.push((changes.len(), new_idx));
+            changes.push(ItemChange::Added {
+                path: item.path.clone(),
+                location: item.location,
+            });
+            continue;
+        };
+
+        old_matched[old_idx] = true;
+
+        let old_item = &old_items[old_idx];
+        if old_item.source != item.source {
+            changes.push(ItemChange::Changed {
+                path: item.path.clone(),
+                old_location: old_item.location,
+                new_location: item.location,
+            });
+        }
+    }
+
+    let mut removed = old_items
+        .iter()
+        .zip(old_matched)
+        .filter_map(|(item, matched)| (!matched).then_some(item))
+        .collect::<Vec<_>>();
+
+    // an added function, that is the same as a removed one, was moved
+    for (change_idx, new_idx) in added {
+        let item = &new_items[new_idx];
+
+        if item.kind != ModuleItemKind::Function {
+            continue;
+        }
+
+        let Some(removed_idx) = removed
+            .iter()
+            .position(|old_item| old_item.kind == item.kind && old_item.source == item.source)
+        else {
+            continue;
+        };
+        let old_item = removed.remove(removed_idx);
+
+        changes[change_idx] = ItemChange::Moved {
+            from: old_item.path.clone(),
+            to: item.path.clone(),
+            old_location: old_item.location,
+            new_location: item.location,
+        };
+    }
+
+    changes.extend(removed.into_iter().map(|item| ItemChange::Removed {
+        path: item.path.clone(),
+        location: item.location,
+    }));
+
+    AstDiff { changes }
+}
+
+/// An item or a method with its source code, that doesn't include methods
+/// and items of submodules.
+struct FlatItem {
+    kind: ModuleItemKind,
+    path: String,
+    location: Location,
+    source: String,
+}
+
+/// Splits items into containers without methods, methods and items of
+/// submodules.
+fn flatten(items: &[ModuleItem]) -> Vec<FlatItem> {
+    let mut flat_items = vec![];
+    flatten_into(items, "", &mut flat_items);
+    flat_items
+}
+
+fn flatten_into(items: &[ModuleItem], prefix: &str, flat_items: &mut Vec<FlatItem>) {
+    for item in items {
+        let mut container = item.clone();
+        let (methods, submodule_items) = match &mut container {
+            ModuleItem::Enum(enum_) => (std::mem::take(&mut enum_.methods), None),
+            ModuleItem::Impl(impl_) => (std::mem::take(&mut impl_.methods), None),
+            ModuleItem::Interface(interface) => (std::mem::take(&mut interface.methods), None),
+            ModuleItem::Struct(struct_) => (std::mem::take(&mut struct_.methods), None),
+            ModuleItem::TupleLikeStruct(struct_) => (std::mem::take(&mut struct_.methods), None),
+            ModuleItem::Module(submodule) => (vec![], submodule.items.as_mut().map(std::mem::take)),
+            _ => (vec![], None),
+        };
+
+        let source = unparse_item(&container);
+        let path = format!("{prefix}{}", item_name(item, &source));
+
+        flat_items.push(FlatItem {
+            kind: item.kind(),
+            path: path.clone(),
+            location: item.location(),
+            source,
+        });
+
+        for method in methods {
+            let method = ModuleItem::Function(method);
+
+            flat_items.push(FlatItem {
+                kind: ModuleItemKind::Function,
+                path: format!("{path}.{}", item_name(&method, "")),
+                location: method.location(),
+                source: unparse_item(&method),
+            });
+        }
+
+        if let Some(items) = submodule_items {
+            flatten_into(&items, &format!("{path}."), flat_items);
+        }
+    }
+}
+
+/// Returns the name of an item, for items without names - a part of their
+/// source code, e.g. `import std.io` or `impl ToString for Point`.
+fn item_name(item: &ModuleItem, source: &str) -> String {
+    if let Some(name) = item.name_identifier_id() {
+        return String::from(name);
+    }
+
+    match item {
+        ModuleItem::Import { .. } => source
+            .lines()
+            .last()
+            .unwrap_or_default()
+            .trim_end_matches(';')
+            .to_owned(),
+        ModuleItem::Impl(impl_) => match &impl_.interface {
+            Some(interface) => format!(
+                "impl {} for {}",
+                unparse_type(&Type::Constructor(interface.clone())),
+                unparse_type(&impl_.ty)
+            ),
+            None => format!("impl {}", unparse_type(&impl_.ty)),
+        },
+        ModuleItem::Extern(block) => format!("extern {:?}", block.abi),
+        _ => unreachable!("all other items have names"),
+    }
+}
@@ -0,0 +1,118 @@
+//! Compact binary encoding of modules, see [`Module::encode`].
+//!
+//! The encoding is meant for caching parse results on disk between compiler
+//! runs: it is several times smaller and faster to decode than JSON.
+//!
+//! ```
+//! use stellar_ast::Module;
+//! use stellar_interner::PathId;
+//!
+//! let module = Module {
+//!     filepath: PathId::from("main.sr"),
+//!     items: vec![],
+//!     docstring: None,
+//! };
+//!
+//! let bytes = module.encode();
+//!
+//! assert_eq!(Module::decode(&bytes).unwrap(), module);
+//! assert!(Module::decode(b"not a module").is_err());
+//! ```
+//!
+//! Modules are encoded as [`MessagePack`](https://msgpack.org) using their
+//! `serde` representation. Unlike `bincode`, that is used for [`PackageData`],
+//! or `postcard`, `MessagePack` is self-describing, which is required to decode
+//! internally tagged enums, e.g. [`Expression`].
+//!
+//! Encoded modules start with a header containing [`FORMAT_VERSION`], so
+//! caches written by another version of the compiler are rejected with
+//! [`DecodeError::UnsupportedVersion`] instead of being misread.
+//!
+//! [`PackageData`]: ../stellar_database/struct.PackageData.html
+//! [`Expression`]: crate::Expression
+
+use std::fmt::{self, Display};
+
+use crate::Module;
+
+/// Version of the encoding, which is bumped every time the serialized AST
+/// changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Bytes every encoded module starts with.
+const MAGIC: &[u8; 4] = b"SAST";
+
+/// Error returned when bytes can't be decoded into a module.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The bytes don't start with the header of an encoded module.
+    MissingHeader,
+
+    /// The module was encoded with another version of the encoding.
+    UnsupportedVersion(u32),
+
+    /// The bytes after the header are not a valid encoded module.
+    Malformed(rmp_serde::decode::Error),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingHeader => f.write_str("bytes are not an encoded module"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported module encoding version {version} (expected {FORMAT_VERSION})"
+            ),
+            Self::Malformed(error) => write!(f, "malformed encoded module: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Malformed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Module {
+    /// Encodes the module into bytes, see [module level docs](crate::binary)
+    /// for more details.
+    ///
+    /// # Panics
+    /// Panics if the module can't be serialized, which never happens for
+    /// modules produced by the parser.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(FORMAT_VERSION.to_le_bytes());
+
+        rmp_serde::encode::write_named(&mut bytes, self).unwrap();
+
+        bytes
+    }
+
+    /// Decodes a module encoded with [`Module::encode`].
+    ///
+    /// # Errors
+    /// Returns an error if the bytes are not an encoded module or were
+    /// encoded with another version of the encoding.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let Some(rest) = bytes.strip_prefix(MAGIC) else {
+            return Err(DecodeError::MissingHeader);
+        };
+
+        let Some((version, rest)) = rest.split_first_chunk::<4>() else {
+            return Err(DecodeError::MissingHeader);
+        };
+
+        let version = u32::from_le_bytes(*version);
+        if version != FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+
+        rmp_serde::from_slice(rest).map_err(DecodeError::Malformed)
+    }
+}
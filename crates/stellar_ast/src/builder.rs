@@ -0,0 +1,678 @@
+//! Builders, that construct AST nodes without spelling out every field.
+//!
+//! Code generators and tests often need trees, that don't come from any
+//! source file. Builders of this module fill locations with
+//! [`DUMMY_LOCATION`] and node IDs with [`DUMMY_NODE_ID`], so only the
+//! meaningful parts of a node have to be written:
+//!
+//! ```
+//! use stellar_ast::{
+//!     builder::{named_type, ExprBuilder, FunctionBuilder},
+//!     unparse, Module, RawBinaryOperator,
+//! };
+//! use stellar_interner::DUMMY_PATH_ID;
+//!
+//! let sum = ExprBuilder::identifier("a").binary(
+//!     RawBinaryOperator::Plus,
+//!     ExprBuilder::identifier("b"),
+//! );
+//! let function = FunctionBuilder::new("sum")
+//!     .with_parameter("a", named_type("int32"))
+//!     .with_parameter("b", named_type("int32"))
+//!     .with_return_type(named_type("int32"))
+//!     .with_tail(sum);
+//!
+//! let module = Module {
+//!     filepath: DUMMY_PATH_ID,
+//!     items: vec![function.into()],
+//!     docstring: None,
+//! };
+//!
+//! assert_eq!(
+//!     unparse(&module),
+//!     "fun sum(a: int32, b: int32): int32 {\n    a + b\n}\n"
+//! );
+//! ```
+
+use stellar_filesystem::location::DUMMY_LOCATION;
+use stellar_interner::IdentifierId;
+
+use crate::{
+    Attribute, BinaryOperator, BindingMode, CallArgument, ElementKind, Expression, Function,
+    FunctionParameter, FunctionSignature, GenericParameter, IdentifierAST, IntegerValue,
+    ListElement, Literal, ModuleItem, NotSelfFunctionParameter, Path, Pattern, PostfixOperator,
+    PrefixOperator, RawBinaryOperator, RawPostfixOperator, RawPrefixOperator,
+    SelfFunctionParameter, Statement, Struct, StructField, Type, TypeConstructor, Visibility,
+    DUMMY_NODE_ID,
+};
+
+/// Returns an identifier with a dummy location, e.g. `foo`.
+#[inline]
+#[must_use]
+pub fn identifier(name: &str) -> IdentifierAST {
+    IdentifierAST {
+        location: DUMMY_LOCATION,
+        id: IdentifierId::from(name),
+    }
+}
+
+/// Returns a path with a dummy location from its dotted form, e.g. `std.io`.
+#[must_use]
+pub fn path(path: &str) -> Path {
+    Path {
+        location: DUMMY_LOCATION,
+        identifiers: path.split('.').map(identifier).collect(),
+    }
+}
+
+/// Returns a type constructor without arguments, e.g. `int32`, `std.io.File`.
+#[inline]
+#[must_use]
+pub fn named_type(path: &str) -> Type {
+    generic_type(path, [])
+}
+
+/// Returns a type constructor with arguments, e.g. `List[int32]`.
+#[must_use]
+pub fn generic_type(path: &str, arguments: impl IntoIterator<Item = Type>) -> Type {
+    Type::Constructor(TypeConstructor {
+        location: DUMMY_LOCATION,
+        path: self::path(path),
+        arguments: arguments.into_iter().collect(),
+    })
+}
+
+/// Returns an identifier pattern, that binds a value to an immutable
+/// variable, e.g. `x`.
+#[inline]
+#[must_use]
+pub fn identifier_pattern(name: &str) -> Pattern {
+    Pattern::Identifier {
+        location: DUMMY_LOCATION,
+        binding_mode: BindingMode::Value,
+        identifier: identifier(name),
+        pattern: None,
+    }
+}
+
+/// Builds an expression from its leftmost part, e.g. `foo(a).bar` is built
+/// as `ExprBuilder::identifier("foo").call([a]).field("bar")`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ExprBuilder {
+    expression: Expression,
+}
+
+impl ExprBuilder {
+    /// Starts building from an existing expression.
+    #[inline]
+    #[must_use]
+    pub const fn new(expression: Expression) -> Self {
+        Self { expression }
+    }
+
+    /// Starts building from an identifier, e.g. `foo`.
+    #[inline]
+    #[must_use]
+    pub fn identifier(name: &str) -> Self {
+        Self::new(Expression::Identifier {
+            node_id: DUMMY_NODE_ID,
+            identifier: identifier(name),
+        })
+    }
+
+    /// Starts building from a literal.
+    #[inline]
+    #[must_use]
+    pub const fn literal(literal: Literal) -> Self {
+        Self::new(Expression::Literal {
+            node_id: DUMMY_NODE_ID,
+            literal,
+        })
+    }
+
+    /// Starts building from a boolean literal, e.g. `true`.
+    #[inline]
+    #[must_use]
+    pub const fn boolean(value: bool) -> Self {
+        Self::literal(Literal::Boolean {
+            value,
+            location: DUMMY_LOCATION,
+        })
+    }
+
+    /// Starts building from a character literal, e.g. `'a'`.
+    #[inline]
+    #[must_use]
+    pub const fn character(value: char) -> Self {
+        Self::literal(Literal::Character {
+            value,
+            location: DUMMY_LOCATION,
+        })
+    }
+
+    /// Starts building from an integer literal, e.g. `3`.
+    #[inline]
+    #[must_use]
+    pub const fn integer(value: u64) -> Self {
+        Self::literal(Literal::Integer {
+            value: IntegerValue::new(value),
+            location: DUMMY_LOCATION,
+        })
+    }
+
+    /// Starts building from a float literal, e.g. `3.14`.
+    #[inline]
+    #[must_use]
+    pub const fn float(value: f64) -> Self {
+        Self::literal(Literal::Float {
+            value,
+            location: DUMMY_LOCATION,
+        })
+    }
+
+    /// Starts building from a string literal, e.g. `"hello"`.
+    #[inline]
+    #[must_use]
+    pub fn string(value: impl Into<String>) -> Self {
+        Self::literal(Literal::String {
+            value: value.into(),
+            location: DUMMY_LOCATION,
+        })
+    }
+
+    /// Starts building from a list expression, e.g. `[1, 2]`.
+    #[must_use]
+    pub fn list<E: Into<Expression>>(elements: impl IntoIterator<Item = E>) -> Self {
+        Self::new(Expression::List {
+            node_id: DUMMY_NODE_ID,
+            location: DUMMY_LOCATION,
+            elements: elements
+                .into_iter()
+                .map(|element| ListElement {
+                    kind: ElementKind::Single,
+                    value: element.into(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Starts building from a tuple expression, e.g. `(1, "a")`.
+    #[must_use]
+    pub fn tuple<E: Into<Expression>>(elements: impl IntoIterator<Item = E>) -> Self {
+        Self::new(Expression::Tuple {
+            node_id: DUMMY_NODE_ID,
+            location: DUMMY_LOCATION,
+            elements: elements.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Accesses a field of the expression, e.g. `a.b`.
+    #[must_use]
+    pub fn field(self, name: &str) -> Self {
+        Self::new(Expression::FieldAccess {
+            node_id: DUMMY_NODE_ID,
+            location: DUMMY_LOCATION,
+            left: Box::new(self.expression),
+            right: identifier(name),
+        })
+    }
+
+    /// Calls the expression with positional arguments, e.g. `a(b, c)`.
+    #[must_use]
+    pub fn call<E: Into<Expression>>(self, arguments: impl IntoIterator<Item = E>) -> Self {
+        Self::new(Expression::Call {
+            node_id: DUMMY_NODE_ID,
+            location: DUMMY_LOCATION,
+            callee: Box::new(self.expression),
+            arguments: arguments
+                .into_iter()
+                .map(|argument| CallArgument {
+                    name: None,
+                    kind: ElementKind::Single,
+                    value: argument.into(),
+                    is_shorthand: false,
+                })
+                .collect(),
+        })
+    }
+
+    /// Calls a method of the expression, e.g. `a.b(c)`.
+    #[inline]
+    #[must_use]
+    pub fn method_call<E: Into<Expression>>(
+        self,
+        name: &str,
+        arguments: impl IntoIterator<Item = E>,
+    ) -> Self {
+        self.field(name).call(arguments)
+    }
+
+    /// Applies a binary operator to the expression and a given right operand,
+    /// e.g. `a + b`.
+    #[must_use]
+    pub fn binary(self, operator: RawBinaryOperator, right: impl Into<Expression>) -> Self {
+        Self::new(Expression::Binary {
+            node_id: DUMMY_NODE_ID,
+            location: DUMMY_LOCATION,
+            left: Box::new(self.expression),
+            operator: BinaryOperator {
+                raw: operator,
+                location: DUMMY_LOCATION,
+            },
+            right: Box::new(right.into()),
+        })
+    }
+
+    /// Applies a prefix operator to the expression, e.g. `!a`.
+    #[must_use]
+    pub fn prefix(self, operator: RawPrefixOperator) -> Self {
+        Self::new(Expression::Prefix {
+            node_id: DUMMY_NODE_ID,
+            location: DUMMY_LOCATION,
+            inner: Box::new(self.expression),
+            operator: PrefixOperator {
+                raw: operator,
+                location: DUMMY_LOCATION,
+            },
+        })
+    }
+
+    /// Applies a postfix operator to the expression, e.g. `a?`.
+    #[must_use]
+    pub fn postfix(self, operator: RawPostfixOperator) -> Self {
+        Self::new(Expression::Postfix {
+            node_id: DUMMY_NODE_ID,
+            location: DUMMY_LOCATION,
+            inner: Box::new(self.expression),
+            operator: PostfixOperator {
+                raw: operator,
+                location: DUMMY_LOCATION,
+            },
+        })
+    }
+
+    /// Casts the expression to a given type, e.g. `a as int32`.
+    #[must_use]
+    pub fn cast(self, ty: Type) -> Self {
+        Self::new(Expression::As {
+            node_id: DUMMY_NODE_ID,
+            location: DUMMY_LOCATION,
+            left: Box::new(self.expression),
+            right: ty,
+        })
+    }
+
+    /// Wraps the expression into parentheses, e.g. `(a)`.
+    #[must_use]
+    pub fn parenthesized(self) -> Self {
+        Self::new(Expression::Parenthesized {
+            node_id: DUMMY_NODE_ID,
+            location: DUMMY_LOCATION,
+            inner: Box::new(self.expression),
+        })
+    }
+
+    /// Returns the built expression.
+    #[inline]
+    #[must_use]
+    pub fn build(self) -> Expression {
+        self.expression
+    }
+
+    /// Returns an expression statement with a semicolon, e.g. `a();`.
+    #[inline]
+    #[must_use]
+    pub fn into_statement(self) -> Statement {
+        Statement::Expression {
+            node_id: DUMMY_NODE_ID,
+            expression: self.expression,
+            has_semicolon: true,
+        }
+    }
+}
+
+impl From<ExprBuilder> for Expression {
+    #[inline]
+    fn from(builder: ExprBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Builds a function, e.g. `fun foo(a: int32): int32 { a }`.
+///
+/// The function has an empty body unless [`FunctionBuilder::without_body`]
+/// is used.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FunctionBuilder {
+    signature: FunctionSignature,
+    body: Option<Vec<Statement>>,
+}
+
+impl FunctionBuilder {
+    /// Starts building a private function with a given name and no
+    /// parameters.
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            signature: FunctionSignature {
+                attributes: vec![],
+                visibility: Visibility::Private,
+                is_async: false,
+                is_unsafe: false,
+                is_generator: false,
+                name: identifier(name),
+                generic_parameters: vec![],
+                parameters: vec![],
+                return_type: None,
+                where_predicates: vec![],
+                docstring: None,
+            },
+            body: Some(vec![]),
+        }
+    }
+
+    /// Sets the visibility of the function.
+    #[inline]
+    #[must_use]
+    pub const fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.signature.visibility = visibility;
+        self
+    }
+
+    /// Makes the function public, e.g. `pub fun foo() {}`.
+    #[inline]
+    #[must_use]
+    pub const fn with_public_visibility(self) -> Self {
+        self.with_visibility(Visibility::Public(DUMMY_LOCATION))
+    }
+
+    /// Makes the function asynchronous, e.g. `async fun foo() {}`.
+    #[inline]
+    #[must_use]
+    pub const fn with_async(mut self) -> Self {
+        self.signature.is_async = true;
+        self
+    }
+
+    /// Makes the function unsafe, e.g. `unsafe fun foo() {}`.
+    #[inline]
+    #[must_use]
+    pub const fn with_unsafe(mut self) -> Self {
+        self.signature.is_unsafe = true;
+        self
+    }
+
+    /// Adds an attribute without arguments, e.g. `#[inline]`.
+    #[must_use]
+    pub fn with_attribute(mut self, path: &str) -> Self {
+        self.signature.attributes.push(Attribute {
+            location: DUMMY_LOCATION,
+            path: self::path(path),
+            arguments: None,
+        });
+        self
+    }
+
+    /// Adds a type parameter without bounds, e.g. `T` in `fun foo[T]() {}`.
+    #[must_use]
+    pub fn with_generic_parameter(mut self, name: &str) -> Self {
+        self.signature.generic_parameters.push(GenericParameter {
+            name: identifier(name),
+            bounds: None,
+            const_type: None,
+            default_value: None,
+        });
+        self
+    }
+
+    /// Adds a `self` parameter.
+    #[must_use]
+    pub fn with_self_parameter(mut self) -> Self {
+        self.signature
+            .parameters
+            .push(FunctionParameter::SelfParameter(SelfFunctionParameter {
+                self_location: DUMMY_LOCATION,
+                ty: None,
+            }));
+        self
+    }
+
+    /// Adds a parameter, e.g. `a: int32`.
+    #[must_use]
+    pub fn with_parameter(mut self, name: &str, ty: Type) -> Self {
+        self.signature
+            .parameters
+            .push(FunctionParameter::NotSelfParameter(
+                NotSelfFunctionParameter {
+                    pattern: identifier_pattern(name),
+                    ty,
+                },
+            ));
+        self
+    }
+
+    /// Sets the return type of the function.
+    #[must_use]
+    pub fn with_return_type(mut self, ty: Type) -> Self {
+        self.signature.return_type = Some(ty);
+        self
+    }
+
+    /// Appends a statement to the body of the function.
+    #[must_use]
+    pub fn with_statement(mut self, statement: Statement) -> Self {
+        self.body.get_or_insert_with(Vec::new).push(statement);
+        self
+    }
+
+    /// Appends a let statement, e.g. `let a = 1;`, to the body of the
+    /// function.
+    #[inline]
+    #[must_use]
+    pub fn with_let(self, name: &str, value: impl Into<Expression>) -> Self {
+        self.with_statement(Statement::Let {
+            node_id: DUMMY_NODE_ID,
+            pattern: identifier_pattern(name),
+            value: value.into(),
+            ty: None,
+        })
+    }
+
+    /// Appends a return statement, e.g. `return a;`, to the body of the
+    /// function.
+    #[inline]
+    #[must_use]
+    pub fn with_return(self, value: impl Into<Expression>) -> Self {
+        self.with_statement(Statement::Return {
+            node_id: DUMMY_NODE_ID,
+            expression: value.into(),
+        })
+    }
+
+    /// Appends an expression without a semicolon, that is the result of the
+    /// function, e.g. `a + b` in `fun sum(a: int32, b: int32): int32 { a + b }`.
+    #[inline]
+    #[must_use]
+    pub fn with_tail(self, value: impl Into<Expression>) -> Self {
+        self.with_statement(Statement::Expression {
+            node_id: DUMMY_NODE_ID,
+            expression: value.into(),
+            has_semicolon: false,
+        })
+    }
+
+    /// Removes the body of the function, e.g. for interface methods and
+    /// functions in extern blocks.
+    #[inline]
+    #[must_use]
+    pub fn without_body(mut self) -> Self {
+        self.body = None;
+        self
+    }
+
+    /// Returns the built function.
+    #[inline]
+    #[must_use]
+    pub fn build(self) -> Function {
+        Function {
+            node_id: DUMMY_NODE_ID,
+            signature: self.signature,
+            body: self.body,
+            body_location: None,
+        }
+    }
+}
+
+impl From<FunctionBuilder> for Function {
+    #[inline]
+    fn from(builder: FunctionBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<FunctionBuilder> for ModuleItem {
+    #[inline]
+    fn from(builder: FunctionBuilder) -> Self {
+        Self::Function(builder.build())
+    }
+}
+
+/// Builds a struct, e.g. `struct Point { x: int32, y: int32 }`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StructBuilder {
+    item: Struct,
+}
+
+impl StructBuilder {
+    /// Starts building a private struct with a given name and no fields.
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            item: Struct {
+                node_id: DUMMY_NODE_ID,
+                attributes: vec![],
+                visibility: Visibility::Private,
+                name: identifier(name),
+                generic_parameters: vec![],
+                where_predicates: vec![],
+                fields: vec![],
+                methods: vec![],
+                implements: None,
+                docstring: None,
+            },
+        }
+    }
+
+    /// Sets the visibility of the struct.
+    #[inline]
+    #[must_use]
+    pub const fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.item.visibility = visibility;
+        self
+    }
+
+    /// Makes the struct public, e.g. `pub struct A {}`.
+    #[inline]
+    #[must_use]
+    pub const fn with_public_visibility(self) -> Self {
+        self.with_visibility(Visibility::Public(DUMMY_LOCATION))
+    }
+
+    /// Adds an attribute without arguments, e.g. `#[derive]`.
+    #[must_use]
+    pub fn with_attribute(mut self, path: &str) -> Self {
+        self.item.attributes.push(Attribute {
+            location: DUMMY_LOCATION,
+            path: self::path(path),
+            arguments: None,
+        });
+        self
+    }
+
+    /// Adds a type parameter without bounds, e.g. `T` in `struct A[T] {}`.
+    #[must_use]
+    pub fn with_generic_parameter(mut self, name: &str) -> Self {
+        self.item.generic_parameters.push(GenericParameter {
+            name: identifier(name),
+            bounds: None,
+            const_type: None,
+            default_value: None,
+        });
+        self
+    }
+
+    /// Adds a private field, e.g. `x: int32`.
+    #[inline]
+    #[must_use]
+    pub fn with_field(self, name: &str, ty: Type) -> Self {
+        self.with_field_of_visibility(Visibility::Private, name, ty)
+    }
+
+    /// Adds a public field, e.g. `pub x: int32`.
+    #[inline]
+    #[must_use]
+    pub fn with_public_field(self, name: &str, ty: Type) -> Self {
+        self.with_field_of_visibility(Visibility::Public(DUMMY_LOCATION), name, ty)
+    }
+
+    /// Adds a field with a given visibility.
+    #[must_use]
+    pub fn with_field_of_visibility(
+        mut self,
+        visibility: Visibility,
+        name: &str,
+        ty: Type,
+    ) -> Self {
+        self.item.fields.push(StructField {
+            attributes: vec![],
+            visibility,
+            name: identifier(name),
+            ty,
+            docstring: None,
+        });
+        self
+    }
+
+    /// Adds an implemented interface, e.g. `ToString` in
+    /// `struct A implements ToString {}`.
+    #[must_use]
+    pub fn with_implements(mut self, interface: &str) -> Self {
+        self.item
+            .implements
+            .get_or_insert_with(Vec::new)
+            .push(TypeConstructor {
+                location: DUMMY_LOCATION,
+                path: path(interface),
+                arguments: vec![],
+            });
+        self
+    }
+
+    /// Adds a method, e.g. a function built with [`FunctionBuilder`].
+    #[must_use]
+    pub fn with_method(mut self, method: impl Into<Function>) -> Self {
+        self.item.methods.push(method.into());
+        self
+    }
+
+    /// Returns the built struct.
+    #[inline]
+    #[must_use]
+    pub fn build(self) -> Struct {
+        self.item
+    }
+}
+
+impl From<StructBuilder> for Struct {
+    #[inline]
+    fn from(builder: StructBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl From<StructBuilder> for ModuleItem {
+    #[inline]
+    fn from(builder: StructBuilder) -> Self {
+        Self::Struct(builder.build())
+    }
+}
@@ -0,0 +1,152 @@
+//! Usual comments (`//`) attached to statements and struct fields.
+//!
+//! Comments are not stored in the AST itself. Instead, if the parser is asked
+//! to collect them, it builds a side table, that maps nodes to the comments
+//! around them, so that formatters and doc tools can put them back:
+//!
+//! ```stellar
+//! fun main() {
+//!     // leading comment of `let`
+//!     let a = 1; // trailing comment of `let`
+//!
+//!     // dangling comment
+//! }
+//! ```
+//!
+//! A comment is:
+//!
+//! - *trailing*, if it starts on the line, where a statement or a field ends,
+//!   e.g. `let a = 1; // comment` or `a: int32, // comment`;
+//! - *leading*, if it is located before a statement or a field and after the
+//!   previous one (or the opening brace of the block);
+//! - *dangling*, if there are no statements or fields after it in the
+//!   enclosing block, e.g. comments in an empty function body.
+//!
+//! Only comments in statements blocks and between struct fields are collected,
+//! comments between items and inside of expressions are not. Like
+//! [docstrings](crate::Docstring), comments only store their location, so the
+//! text is resolved using the source with [`Comment::text`].
+
+use std::collections::HashMap;
+
+use stellar_filesystem::location::Location;
+
+use crate::NodeId;
+
+/// A usual comment, e.g. `// comment`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Comment {
+    /// Location of the comment including `//`.
+    pub location: Location,
+}
+
+impl Comment {
+    /// Returns the text of the comment in a given source of the file, that
+    /// contains it, without `//` and surrounding whitespace.
+    #[inline]
+    #[must_use]
+    pub fn text(self, source: &str) -> &str {
+        source[self.location]
+            .strip_prefix("//")
+            .unwrap_or_default()
+            .trim()
+    }
+}
+
+/// A node, that comments can be attached to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum CommentTarget {
+    /// A statement with a given node ID.
+    Statement(NodeId),
+
+    /// A field of a struct or of a struct-like enum variant, identified by
+    /// location of its name.
+    StructField(Location),
+}
+
+/// Comments of a module, see [module level docs](crate::comments) for more
+/// details.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Comments {
+    leading: HashMap<CommentTarget, Vec<Comment>>,
+    trailing: HashMap<CommentTarget, Vec<Comment>>,
+    dangling: Vec<Comment>,
+}
+
+impl Comments {
+    /// Creates an empty comment table.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns comments before a given node.
+    #[inline]
+    #[must_use]
+    pub fn leading(&self, target: CommentTarget) -> &[Comment] {
+        self.leading.get(&target).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns comments on the line, where a given node ends.
+    #[inline]
+    #[must_use]
+    pub fn trailing(&self, target: CommentTarget) -> &[Comment] {
+        self.trailing.get(&target).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns comments, that are not followed by a statement or a field in
+    /// their block.
+    #[inline]
+    #[must_use]
+    pub fn dangling(&self) -> &[Comment] {
+        &self.dangling
+    }
+
+    /// Returns the number of comments.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.leading.values().map(Vec::len).sum::<usize>()
+            + self.trailing.values().map(Vec::len).sum::<usize>()
+            + self.dangling.len()
+    }
+
+    /// Returns `true` if there are no comments.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a comment before a given node.
+    #[inline]
+    pub fn add_leading(&mut self, target: CommentTarget, comment: Comment) {
+        self.leading.entry(target).or_default().push(comment);
+    }
+
+    /// Adds a comment on the line, where a given node ends.
+    #[inline]
+    pub fn add_trailing(&mut self, target: CommentTarget, comment: Comment) {
+        self.trailing.entry(target).or_default().push(comment);
+    }
+
+    /// Adds a comment, that is not followed by a statement or a field.
+    #[inline]
+    pub fn add_dangling(&mut self, comment: Comment) {
+        self.dangling.push(comment);
+    }
+
+    /// Moves comments of another table into this one, e.g. when a deferred
+    /// function body is parsed.
+    pub fn extend(&mut self, other: Self) {
+        for (target, comments) in other.leading {
+            self.leading.entry(target).or_default().extend(comments);
+        }
+
+        for (target, comments) in other.trailing {
+            self.trailing.entry(target).or_default().extend(comments);
+        }
+
+        self.dangling.extend(other.dangling);
+    }
+}
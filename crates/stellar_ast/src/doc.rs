@@ -0,0 +1,302 @@
+//! Structured representation of doc comments.
+//!
+//! The text of a [`Docstring`] is split into a summary, free-form description,
+//! sections started with Markdown headings, fenced code blocks and tags, such
+//! as `@param` and `@returns`:
+//!
+//! ```
+//! use stellar_ast::doc::{DocBlock, DocComment, DocTag};
+//!
+//! let comment = DocComment::parse(concat!(
+//!     "Reads a file.\n",
+//!     "\n",
+//!     "@param path A path to the file.\n",
+//!     "@returns Contents of the file.\n",
+//!     "\n",
+//!     "# Example\n",
+//!     "```\n",
+//!     "read(\"a.txt\")\n",
+//!     "```",
+//! ));
+//!
+//! assert_eq!(comment.summary, "Reads a file.");
+//! assert_eq!(comment.sections[0].title, "Example");
+//! assert!(matches!(
+//!     &comment.sections[0].blocks[0],
+//!     DocBlock::Code { code, .. } if code == "read(\"a.txt\")"
+//! ));
+//! assert!(matches!(
+//!     &comment.tags[0],
+//!     DocTag::Param { name, description } if name == "path" && description == "A path to the file."
+//! ));
+//! ```
+//!
+//! [`Docstring`]: crate::Docstring
+
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A parsed doc comment.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct DocComment {
+    /// The first paragraph, empty if the comment doesn't start with one.
+    pub summary: String,
+
+    /// Blocks after the summary and before the first section.
+    pub description: Vec<DocBlock>,
+
+    /// Sections started with Markdown headings, e.g. `# Examples`.
+    pub sections: Vec<DocSection>,
+
+    /// Tags, e.g. `@param path A path to the file.`, in the source order.
+    pub tags: Vec<DocTag>,
+}
+
+/// A section of a doc comment, e.g. `# Examples`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct DocSection {
+    /// The heading without `#` markers.
+    pub title: String,
+
+    /// Blocks of the section.
+    pub blocks: Vec<DocBlock>,
+}
+
+/// A block of text in a doc comment.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "kind"))]
+pub enum DocBlock {
+    /// Consecutive non-empty lines.
+    #[cfg_attr(feature = "serde", serde(rename = "paragraph"))]
+    Paragraph { text: String },
+
+    /// A fenced code block, e.g. ```` ```stellar ... ``` ````.
+    #[cfg_attr(feature = "serde", serde(rename = "code"))]
+    Code {
+        /// The language after the opening fence, e.g. `stellar`.
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        language: Option<String>,
+        code: String,
+    },
+}
+
+/// A tag of a doc comment, e.g. `@returns Contents of the file.`.
+///
+/// A description of a tag continues on the following lines until an empty
+/// line, another tag or a heading.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "kind"))]
+pub enum DocTag {
+    /// `@param <name> <description>`.
+    #[cfg_attr(feature = "serde", serde(rename = "param"))]
+    Param { name: String, description: String },
+
+    /// `@returns <description>`.
+    #[cfg_attr(feature = "serde", serde(rename = "returns"))]
+    Returns { description: String },
+
+    /// Any other tag, e.g. `@since 0.2`.
+    #[cfg_attr(feature = "serde", serde(rename = "other"))]
+    Other { name: String, description: String },
+}
+
+impl DocTag {
+    const fn description_mut(&mut self) -> &mut String {
+        match self {
+            Self::Param { description, .. }
+            | Self::Returns { description }
+            | Self::Other { description, .. } => description,
+        }
+    }
+}
+
+impl DocComment {
+    /// Parses the text of a doc comment, see [`Docstring::text`].
+    ///
+    /// [`Docstring::text`]: crate::Docstring::text
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let mut parser = DocCommentParser::default();
+
+        for line in text.lines() {
+            parser.line(line);
+        }
+
+        parser.finish()
+    }
+
+    /// Returns the description of a parameter, if it is documented with `@param`.
+    #[must_use]
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find_map(|tag| match tag {
+            DocTag::Param {
+                name: param,
+                description,
+            } if param == name => Some(description.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Returns the description of the return value, if it is documented with
+    /// `@returns`.
+    #[must_use]
+    pub fn returns(&self) -> Option<&str> {
+        self.tags.iter().find_map(|tag| match tag {
+            DocTag::Returns { description } => Some(description.as_str()),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Default)]
+struct DocCommentParser {
+    comment: DocComment,
+
+    /// Lines of the current paragraph.
+    paragraph: Vec<String>,
+
+    /// The language and lines of the current code block, if inside of it.
+    code: Option<(Option<String>, Vec<String>)>,
+
+    /// Whether the next line continues the description of the last tag.
+    in_tag: bool,
+}
+
+impl DocCommentParser {
+    fn line(&mut self, line: &str) {
+        let trimmed = line.trim();
+
+        if let Some((_, code)) = &mut self.code {
+            if trimmed.starts_with("```") {
+                self.finish_code();
+            } else {
+                code.push(line.to_owned());
+            }
+
+            return;
+        }
+
+        if let Some(language) = trimmed.strip_prefix("```") {
+            self.finish_paragraph();
+            self.in_tag = false;
+
+            let language = language.trim();
+            self.code = Some(((!language.is_empty()).then(|| language.to_owned()), vec![]));
+        } else if trimmed.is_empty() {
+            self.finish_paragraph();
+            self.in_tag = false;
+        } else if let Some(tag) = trimmed.strip_prefix('@') {
+            self.finish_paragraph();
+            self.in_tag = true;
+
+            let (name, description) = split_word(tag);
+            self.comment.tags.push(match name {
+                "param" => {
+                    let (name, description) = split_word(description);
+
+                    DocTag::Param {
+                        name: name.to_owned(),
+                        description: description.to_owned(),
+                    }
+                }
+                "returns" => DocTag::Returns {
+                    description: description.to_owned(),
+                },
+                _ => DocTag::Other {
+                    name: name.to_owned(),
+                    description: description.to_owned(),
+                },
+            });
+        } else if let Some(title) = heading(trimmed) {
+            self.finish_paragraph();
+            self.in_tag = false;
+
+            self.comment.sections.push(DocSection {
+                title: title.to_owned(),
+                blocks: vec![],
+            });
+        } else if let Some(tag) = self.comment.tags.last_mut().filter(|_| self.in_tag) {
+            let description = tag.description_mut();
+
+            if !description.is_empty() {
+                description.push(' ');
+            }
+
+            description.push_str(trimmed);
+        } else {
+            self.paragraph.push(trimmed.to_owned());
+        }
+    }
+
+    /// Returns blocks of the last section or of the description.
+    fn blocks(&mut self) -> &mut Vec<DocBlock> {
+        self.comment
+            .sections
+            .last_mut()
+            .map_or(&mut self.comment.description, |section| &mut section.blocks)
+    }
+
+    fn finish_paragraph(&mut self) {
+        if self.paragraph.is_empty() {
+            return;
+        }
+
+        let text = self.paragraph.join("\n");
+        self.paragraph.clear();
+
+        // the first block of the comment is the summary
+        if self.comment.summary.is_empty()
+            && self.comment.description.is_empty()
+            && self.comment.sections.is_empty()
+        {
+            self.comment.summary = text;
+        } else {
+            self.blocks().push(DocBlock::Paragraph { text });
+        }
+    }
+
+    fn finish_code(&mut self) {
+        if let Some((language, code)) = self.code.take() {
+            self.blocks().push(DocBlock::Code {
+                language,
+                code: code.join("\n"),
+            });
+        }
+    }
+
+    fn finish(mut self) -> DocComment {
+        self.finish_paragraph();
+
+        // an unterminated code block lasts until the end of the comment
+        self.finish_code();
+
+        self.comment
+    }
+}
+
+/// `# Title` -> `Title`.
+fn heading(line: &str) -> Option<&str> {
+    let title = line.trim_start_matches('#');
+
+    if title.len() == line.len() || !title.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    Some(title.trim())
+}
+
+/// `a b c` -> (`a`, `b c`).
+fn split_word(text: &str) -> (&str, &str) {
+    text.split_once(char::is_whitespace)
+        .map_or((text, ""), |(word, rest)| (word, rest.trim_start()))
+}
@@ -0,0 +1,348 @@
+//! Arbitrary-precision values of integer literals, see [`IntegerValue`].
+
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Write},
+    str::FromStr,
+};
+
+#[cfg(feature = "schemars")]
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{Schema, SchemaObject, SubschemaValidation},
+    JsonSchema,
+};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A non-negative integer of arbitrary size, e.g. value of an integer literal.
+///
+/// The parser doesn't know types of literals, so it accepts integers of any
+/// size and reports overflows only when the type of the literal is known, see
+/// [`IntegerValue::fits_unsigned`] and [`IntegerValue::fits_signed`]:
+///
+/// ```
+/// use stellar_ast::IntegerValue;
+///
+/// let value: IntegerValue = "0xFFFF_FFFF_FFFF_FFFF_FF".parse().unwrap();
+///
+/// assert_eq!(value.to_string(), "4722366482869645213695");
+/// assert_eq!(value.to_u64(), None);
+/// assert!(value.fits_unsigned(72));
+/// assert!(!value.fits_signed(72, false));
+///
+/// // `-128` fits into `int8`, `128` doesn't.
+/// let value = IntegerValue::from(128);
+///
+/// assert!(value.fits_signed(8, true));
+/// assert!(!value.fits_signed(8, false));
+/// ```
+///
+/// Negative literals, e.g. `-3` in patterns, store their absolute value. When
+/// serialized, values are numbers if they fit into `u64` and decimal strings
+/// otherwise.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct IntegerValue(Repr);
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+enum Repr {
+    Small(u64),
+
+    /// Little-endian limbs, there are always at least 2 of them and the last
+    /// one is not zero.
+    Big(Box<[u64]>),
+}
+
+/// Error returned when a string is not a valid integer literal.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ParseIntegerValueError;
+
+impl Display for ParseIntegerValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid integer literal")
+    }
+}
+
+impl std::error::Error for ParseIntegerValueError {}
+
+impl IntegerValue {
+    /// Creates a value, that fits into `u64`.
+    #[inline]
+    #[must_use]
+    pub const fn new(value: u64) -> Self {
+        Self(Repr::Small(value))
+    }
+
+    /// Parses digits in a given radix, digits can be separated with `_`.
+    ///
+    /// # Errors
+    /// Returns an error if there are no digits or some of them are invalid.
+    ///
+    /// # Panics
+    /// Panics if the radix is not in the range `2..=36`.
+    #[allow(clippy::cast_possible_truncation)] // limbs are the low 64 bits
+    pub fn from_str_radix(digits: &str, radix: u32) -> Result<Self, ParseIntegerValueError> {
+        let mut limbs = vec![0];
+        let mut has_digits = false;
+
+        for c in digits.chars().filter(|&c| c != '_') {
+            let digit = c.to_digit(radix).ok_or(ParseIntegerValueError)?;
+            has_digits = true;
+
+            let mut carry = u128::from(digit);
+
+            for limb in &mut limbs {
+                let value = u128::from(*limb) * u128::from(radix) + carry;
+                *limb = value as u64;
+                carry = value >> 64;
+            }
+
+            if carry != 0 {
+                limbs.push(carry as u64);
+            }
+        }
+
+        if has_digits {
+            Ok(Self::from_limbs(&limbs))
+        } else {
+            Err(ParseIntegerValueError)
+        }
+    }
+
+    /// Creates a value from little-endian 64-bit limbs.
+    #[must_use]
+    pub fn from_limbs(limbs: &[u64]) -> Self {
+        let len = limbs
+            .iter()
+            .rposition(|&limb| limb != 0)
+            .map_or(0, |i| i + 1);
+
+        match &limbs[..len] {
+            [] => Self(Repr::Small(0)),
+            [value] => Self(Repr::Small(*value)),
+            limbs => Self(Repr::Big(limbs.into())),
+        }
+    }
+
+    /// Returns little-endian 64-bit limbs of the value, the last one is not
+    /// zero unless the value is zero.
+    #[inline]
+    #[must_use]
+    pub fn limbs(&self) -> &[u64] {
+        match &self.0 {
+            Repr::Small(value) => std::slice::from_ref(value),
+            Repr::Big(limbs) => limbs,
+        }
+    }
+
+    /// Returns the value if it fits into `u64`.
+    #[inline]
+    #[must_use]
+    pub const fn to_u64(&self) -> Option<u64> {
+        match self.0 {
+            Repr::Small(value) => Some(value),
+            Repr::Big(_) => None,
+        }
+    }
+
+    /// Returns the value if it fits into `u128`.
+    #[inline]
+    #[must_use]
+    pub fn to_u128(&self) -> Option<u128> {
+        match *self.limbs() {
+            [low] => Some(u128::from(low)),
+            [low, high] => Some(u128::from(high) << 64 | u128::from(low)),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of bits required to represent the value.
+    #[allow(clippy::cast_possible_truncation)]
+    #[must_use]
+    pub fn bits(&self) -> u32 {
+        let limbs = self.limbs();
+        let last = limbs[limbs.len() - 1];
+
+        (limbs.len() as u32 - 1) * 64 + (64 - last.leading_zeros())
+    }
+
+    /// Returns `true` if the value fits into an unsigned integer type with a
+    /// given number of bits, e.g. `fits_unsigned(8)` for `uint8`.
+    #[inline]
+    #[must_use]
+    pub fn fits_unsigned(&self, bits: u32) -> bool {
+        self.bits() <= bits
+    }
+
+    /// Returns `true` if the value, negated if `negative` is `true`, fits into
+    /// a signed integer type with a given number of bits, e.g.
+    /// `fits_signed(8, true)` for `-128` in `int8`.
+    #[must_use]
+    pub fn fits_signed(&self, bits: u32, negative: bool) -> bool {
+        let value_bits = self.bits();
+
+        value_bits < bits || (negative && value_bits == bits && self.is_power_of_two())
+    }
+
+    fn is_power_of_two(&self) -> bool {
+        let (last, rest) = self.limbs().split_last().unwrap();
+
+        last.is_power_of_two() && rest.iter().all(|&limb| limb == 0)
+    }
+}
+
+impl From<u64> for IntegerValue {
+    #[inline]
+    fn from(value: u64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl FromStr for IntegerValue {
+    type Err = ParseIntegerValueError;
+
+    /// Parses an integer literal, e.g. `1_000`, `0xFF`, `0o17` or `0b101`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (digits, radix) = match s.get(..2) {
+            Some("0x" | "0X") => (&s[2..], 16),
+            Some("0o" | "0O") => (&s[2..], 8),
+            Some("0b" | "0B") => (&s[2..], 2),
+            _ => (s, 10),
+        };
+
+        Self::from_str_radix(digits, radix)
+    }
+}
+
+impl PartialOrd for IntegerValue {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IntegerValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (self.limbs(), other.limbs());
+
+        a.len()
+            .cmp(&b.len())
+            .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+    }
+}
+
+impl Display for IntegerValue {
+    #[allow(clippy::cast_possible_truncation)] // chunks are less than `CHUNK`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// The largest power of 10, that fits into `u64`.
+        const CHUNK: u128 = 10_000_000_000_000_000_000;
+
+        let Repr::Big(limbs) = &self.0 else {
+            return self.limbs()[0].fmt(f);
+        };
+
+        // Chunks of 19 decimal digits, from the least significant one.
+        let mut chunks = vec![];
+        let mut limbs = limbs.to_vec();
+
+        while !limbs.is_empty() {
+            let mut remainder = 0;
+
+            for limb in limbs.iter_mut().rev() {
+                let value = remainder << 64 | u128::from(*limb);
+                *limb = (value / CHUNK) as u64;
+                remainder = value % CHUNK;
+            }
+
+            chunks.push(remainder as u64);
+
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+
+        let (first, rest) = chunks.split_last().unwrap();
+        let mut result = first.to_string();
+
+        for chunk in rest.iter().rev() {
+            write!(result, "{chunk:019}")?;
+        }
+
+        f.pad_integral(true, "", &result)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for IntegerValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0 {
+            Repr::Small(value) => serializer.serialize_u64(value),
+            Repr::Big(_) => serializer.collect_str(self),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for IntegerValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct IntegerValueVisitor;
+
+        impl de::Visitor<'_> for IntegerValueVisitor {
+            type Value = IntegerValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a non-negative integer or a string of decimal digits")
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(value.into())
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                u64::try_from(value)
+                    .map(Into::into)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                IntegerValue::from_str_radix(value, 10)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_any(IntegerValueVisitor)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl JsonSchema for IntegerValue {
+    fn schema_name() -> String {
+        "IntegerValue".to_owned()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    generator.subschema_for::<u64>(),
+                    generator.subschema_for::<String>(),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
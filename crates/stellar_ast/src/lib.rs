@@ -24,6 +24,7 @@
 //!     "items": [
 //!         {
 //!             "kind": "function_module_item",
+//!             "node_id": 4,
 //!             "signature": {
 //!                 "visibility": {
 //!                     "kind": "private"
@@ -43,8 +44,10 @@
 //!             "body": [
 //!                 {
 //!                     "kind": "expression_statement",
+//!                     "node_id": 3,
 //!                     "expression": {
 //!                         "kind": "call_expression",
+//!                         "node_id": 2,
 //!                         "location": {
 //!                             "filepath": "test.sr",
 //!                             "start": 15,
@@ -52,6 +55,7 @@
 //!                         },
 //!                         "callee": {
 //!                             "kind": "identifier_expression",
+//!                             "node_id": 0,
 //!                             "location": {
 //!                                 "filepath": "test.sr",
 //!                                 "start": 15,
@@ -62,6 +66,7 @@
 //!                         "arguments": [
 //!                             {
 //!                                 "kind": "literal_expression",
+//!                                 "node_id": 1,
 //!                                 "literal_kind": "string",
 //!                                 "value": "hello world",
 //!                                 "location": {
@@ -82,11 +87,36 @@
 //!
 //! For more details see the module items and start with [`Module`] node.
 //!
+//! # Building
+//!
+//! Trees, that don't come from source code, can be constructed with builders
+//! of the [`builder`] module.
+//!
+//! # Comments
+//!
+//! Usual comments are not a part of AST, but the parser can collect them into
+//! a side table, see the [`comments`] module for more details.
+//!
+//! # Unparsing
+//!
+//! AST can be rendered back into source code with [`unparse()`], see the
+//! [`unparse`](mod@unparse) module for more details. Two versions of a module
+//! can be compared structurally with [`ast_diff::diff_modules()`]. Single
+//! nodes can be compared and hashed ignoring their locations, see the
+//! [`location_insensitive`] module.
 //!
 //! # Serialization
 //!
 //! If the `serde` feature is enabled, the AST can be serialized using the `serde`
-//! crate.
+//! crate. The `schemars` feature additionally provides `json_schema()` of the
+//! serialized AST, a generated copy of which is stored in `ast.schema.json`.
+//! The `binary` feature adds a compact binary encoding of modules for on-disk
+//! caches, see `Module::encode()`.
+//!
+//! # Arena allocation
+//!
+//! If the `arena` feature is enabled, function bodies can be copied into a bump
+//! allocator, see the `arena` module for more details.
 //!
 //! [`Token`]: crate::token::Token
 //! [`stellar_parser`]: ../stellar_parser/index.html
@@ -148,11 +178,15 @@
     clippy::option_if_let_else,
     clippy::unnested_or_patterns
 )]
+// `JsonSchema` derive generates qualified paths for newtype variants.
+#![cfg_attr(feature = "schemars", allow(unused_qualifications))]
 
 use std::fmt::Display;
 use std::str::FromStr;
 
 use derive_more::Display;
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
 #[cfg(feature = "serde")]
 use serde::Deserializer;
 #[cfg(feature = "serde")]
@@ -164,13 +198,30 @@ use stellar_interner::IdentifierId;
 use stellar_interner::PathId;
 use token::{Punctuator, RawToken};
 
+#[cfg(feature = "arena")]
+pub mod arena;
+pub mod ast_diff;
+#[cfg(feature = "binary")]
+pub mod binary;
+pub mod builder;
+pub mod comments;
+pub mod doc;
+mod integer;
+pub mod location_insensitive;
+pub mod metrics;
 pub mod precedence;
 pub mod token;
+pub mod unparse;
 pub mod visit;
+pub mod visit_mut;
+
+pub use integer::{IntegerValue, ParseIntegerValueError};
+pub use unparse::unparse;
 
 /// A literal, e.g. `true`, `3`, `\"hello\"`.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(tag = "literal_kind"))]
 pub enum Literal {
     /// Boolean literal, e.g. `true` or `false`.
@@ -185,9 +236,12 @@ pub enum Literal {
     #[cfg_attr(feature = "serde", serde(rename = "string"))]
     String { value: String, location: Location },
 
-    /// Integer literal, e.g. `123`,
+    /// Integer literal, e.g. `123`, `0xFF`.
     #[cfg_attr(feature = "serde", serde(rename = "integer"))]
-    Integer { value: u64, location: Location },
+    Integer {
+        value: IntegerValue,
+        location: Location,
+    },
 
     /// Float literal, e.g. `3.14`.
     #[cfg_attr(feature = "serde", serde(rename = "float"))]
@@ -211,6 +265,7 @@ impl Literal {
 /// An identifier with a specified location, e.g. `foo`, `std`.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct IdentifierAST {
     pub location: Location,
     pub id: IdentifierId,
@@ -231,9 +286,93 @@ macro_rules! dummy_identifier {
     };
 }
 
+/// ID of an expression, a statement or a module item, e.g. `NodeId(3)`.
+///
+/// IDs are assigned by the parser, so that the same source is always parsed
+/// into the same IDs, and are unique within a parsed module (but not across
+/// modules). Unlike locations, IDs of items don't change, when other items of
+/// the module are reparsed, so they can be used as keys of side tables built
+/// by later stages.
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Display)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[display(fmt = "#{_0}")]
+pub struct NodeId(pub usize);
+
+/// ID of a node, that is not produced by the parser, e.g. a node built by a
+/// code transformation.
+pub const DUMMY_NODE_ID: NodeId = NodeId(usize::MAX);
+
+impl NodeId {
+    /// Returns the next node ID.
+    #[inline]
+    #[must_use]
+    pub const fn next(self) -> Self {
+        Self(self.0 + 1)
+    }
+}
+
+/// Consecutive doc comments describing an item (`///`) or a module (`//!`).
+///
+/// Only the location of the comments is stored: most compilations never read
+/// docstrings, so their text is materialized on demand with
+/// [`Docstring::text`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct Docstring {
+    /// Location from the start of the first comment to the end of the last one.
+    pub location: Location,
+}
+
+impl Docstring {
+    /// Returns the text of the docstring in a given source of the file, that
+    /// contains it.
+    ///
+    /// Comment markers are stripped and lines are dedented by their common
+    /// indentation, so `/// a\n///   b` becomes `a\n  b`. Usual comments
+    /// between doc comments are skipped.
+    #[must_use]
+    pub fn text(self, source: &str) -> String {
+        let lines = source[self.location]
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim_start();
+
+                line.strip_prefix("///")
+                    .or_else(|| line.strip_prefix("//!"))
+            })
+            .collect::<Vec<_>>();
+
+        let indentation = lines
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+
+        lines
+            .iter()
+            .map(|line| line.get(indentation..).unwrap_or_default().trim_end())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Parses the docstring into a summary, sections and tags, see
+    /// [`DocComment`] for more details.
+    ///
+    /// [`DocComment`]: crate::doc::DocComment
+    #[inline]
+    #[must_use]
+    pub fn parse(self, source: &str) -> doc::DocComment {
+        doc::DocComment::parse(&self.text(source))
+    }
+}
+
 /// A sequence of identifiers separated by `.`, e.g. `std.io`, `foo`.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct Path {
     pub location: Location,
     pub identifiers: Vec<IdentifierAST>,
@@ -258,38 +397,148 @@ macro_rules! dummy_path {
     };
 }
 
-/// An import path, e.g. `std.io`, `std.io as myio`.
+/// An import path, e.g. `std.io`, `std.io as myio`, `std.collections.*`,
+/// `std.{io, fs}`.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct ImportPath {
-    pub path: Path,
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "kind"))]
+pub enum ImportPath {
+    /// Imports a single name, e.g. `std.io`, `std.io as myio`.
+    #[cfg_attr(feature = "serde", serde(rename = "single_import"))]
+    Single {
+        path: Path,
 
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub as_: Option<IdentifierAST>,
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        as_: Option<IdentifierAST>,
+    },
+
+    /// Imports all items and submodules of a module, e.g. `std.collections.*`.
+    #[cfg_attr(feature = "serde", serde(rename = "glob_import"))]
+    Glob {
+        /// The path to the module, e.g. `std.collections` in `std.collections.*`.
+        path: Path,
+    },
+
+    /// Imports several paths with a common prefix, e.g. `std.{io, fs as filesystem}`.
+    #[cfg_attr(feature = "serde", serde(rename = "group_import"))]
+    Group {
+        /// The common prefix, e.g. `std` in `std.{io, fs}`.
+        path: Path,
+
+        /// Imports relative to the prefix, e.g. `io` and `fs` in `std.{io, fs}`.
+        imports: Vec<Self>,
+    },
+}
+
+impl ImportPath {
+    /// Returns the imported path, for glob imports - the path to the module,
+    /// for import groups - the common prefix.
+    #[inline]
+    #[must_use]
+    pub const fn path(&self) -> &Path {
+        match self {
+            Self::Single { path, .. } | Self::Glob { path } | Self::Group { path, .. } => path,
+        }
+    }
+
+    /// Returns `true` if the import is a glob import, e.g. `std.collections.*`.
+    #[inline]
+    #[must_use]
+    pub const fn is_glob(&self) -> bool {
+        matches!(self, Self::Glob { .. })
+    }
+
+    /// Returns `true` if the import is a glob import or an import group
+    /// containing one, e.g. `std.{io, collections.*}`.
+    #[must_use]
+    pub fn contains_glob(&self) -> bool {
+        match self {
+            Self::Single { .. } => false,
+            Self::Glob { .. } => true,
+            Self::Group { imports, .. } => imports.iter().any(Self::contains_glob),
+        }
+    }
+
+    /// Splits import groups into separate imports with full paths, e.g.
+    /// `std.{io, fs.*}` into `std.io` and `std.fs.*`. Other imports are
+    /// returned as is.
+    #[must_use]
+    pub fn flatten(self) -> Vec<Self> {
+        match self {
+            Self::Group { path, imports } => imports
+                .into_iter()
+                .flat_map(|import| import.with_prefix(&path).flatten())
+                .collect(),
+            _ => vec![self],
+        }
+    }
+
+    /// Prepends a prefix to the path of the import.
+    fn with_prefix(mut self, prefix: &Path) -> Self {
+        let (Self::Single { path, .. } | Self::Glob { path } | Self::Group { path, .. }) =
+            &mut self;
+
+        path.location.start = prefix.location.start;
+        path.identifiers
+            .splice(0..0, prefix.identifiers.iter().copied());
+
+        self
+    }
 }
 
 /// A type constructor, e.g. `Option[T]`.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct TypeConstructor {
     pub location: Location,
     pub path: Path,
     pub arguments: Vec<Type>,
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+/// A negative numeric literal, e.g. `-3`, `-2.0`.
+#[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "serde", serde(tag = "kind"))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "literal_kind"))]
 pub enum NegativeNumericLiteral {
     #[cfg_attr(feature = "serde", serde(rename = "float"))]
     Float { location: Location, value: f64 },
+    /// Stores the absolute value of the literal, e.g. `3` for `-3`.
     #[cfg_attr(feature = "serde", serde(rename = "integer"))]
-    Integer { location: Location, value: u64 },
+    Integer {
+        location: Location,
+        value: IntegerValue,
+    },
+}
+
+/// A way an identifier pattern binds a matched value.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum BindingMode {
+    /// The value is moved or copied into an immutable variable, e.g. `x`.
+    #[cfg_attr(feature = "serde", serde(rename = "value"))]
+    Value,
+
+    /// The value is moved or copied into a mutable variable, e.g. `mut x`.
+    #[cfg_attr(feature = "serde", serde(rename = "mutable_value"))]
+    MutableValue,
+
+    /// The variable borrows the value, e.g. `ref x`.
+    #[cfg_attr(feature = "serde", serde(rename = "reference"))]
+    Reference,
+
+    /// The variable mutably borrows the value, e.g. `ref mut x`.
+    #[cfg_attr(feature = "serde", serde(rename = "mutable_reference"))]
+    MutableReference,
 }
 
 /// A pattern, e.g. `Some(x)`, `None`, `a @ [3, ..]`, `[1, .., 3]`, `(1, \"hello\")`, `3.2`.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum Pattern {
     /// A literal pattern, e.g. `3.14`, `'a'`, `true`.
@@ -300,10 +549,11 @@ pub enum Pattern {
     #[cfg_attr(feature = "serde", serde(rename = "negative_numeric_literal"))]
     NegativeNumericLiteral(NegativeNumericLiteral),
 
-    /// An identifier pattern, e.g. `f`, `list @ [3, ..]`.
+    /// An identifier pattern, e.g. `f`, `mut x`, `list @ [3, ..]`.
     #[cfg_attr(feature = "serde", serde(rename = "identifier_pattern"))]
     Identifier {
         location: Location,
+        binding_mode: BindingMode,
         identifier: IdentifierAST,
 
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
@@ -356,12 +606,24 @@ pub enum Pattern {
         inner: Box<Self>,
     },
 
-    /// An or pattern, e.g. `Some(..) | None`.
+    /// An or pattern, e.g. `Some(..) | None`. Always has at least two
+    /// alternatives.
     #[cfg_attr(feature = "serde", serde(rename = "or_pattern"))]
     Or {
         location: Location,
-        left: Box<Self>,
-        right: Box<Self>,
+        alternatives: Vec<Self>,
+    },
+
+    /// A range pattern, e.g. `1..=9`, `'a'..'z'`. Bounds are literal or
+    /// negative numeric literal patterns.
+    #[cfg_attr(feature = "serde", serde(rename = "range_pattern"))]
+    Range {
+        location: Location,
+        start: Box<Self>,
+        end: Box<Self>,
+
+        #[cfg_attr(feature = "serde", serde(rename = "range_kind"))]
+        kind: RangeKind,
     },
 
     /// A rest pattern - `..`.
@@ -390,6 +652,7 @@ impl Pattern {
             | Self::Identifier { location, .. }
             | Self::List { location, .. }
             | Self::Or { location, .. }
+            | Self::Range { location, .. }
             | Self::Rest { location }
             | Self::Struct { location, .. }
             | Self::Tuple { location, .. }
@@ -418,6 +681,7 @@ impl Pattern {
 /// `Person { citizenship: "USA", name, .. }`
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(untagged))]
 pub enum StructFieldPattern {
     /// A pattern used to match a struct field, which is not rest pattern (`..`),
@@ -436,6 +700,7 @@ pub enum StructFieldPattern {
 /// A type, e.g. `int32`, `(char): bool`, `(char, char)`.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum Type {
     /// A type path, e.g. `char`, `Option[T]`.
@@ -477,6 +742,39 @@ pub enum Type {
         location: Location,
         bounds: Vec<TypeConstructor>,
     },
+
+    /// A const generic argument, e.g. `4` in `Array[uint8, 4]`.
+    #[cfg_attr(feature = "serde", serde(rename = "const_argument"))]
+    Const {
+        location: Location,
+        value: ConstValue,
+    },
+
+    /// A fixed-size array type, e.g. `[int32; 4]` and `[T; N]`.
+    #[cfg_attr(feature = "serde", serde(rename = "array_type"))]
+    Array {
+        location: Location,
+        element_type: Box<Self>,
+
+        /// The size of the array: a const generic argument, e.g. `4`, or a
+        /// const generic parameter, e.g. `N`.
+        size: Box<Self>,
+    },
+
+    /// A reference type, e.g. `&String` and `&mut List[T]`.
+    #[cfg_attr(feature = "serde", serde(rename = "reference_type"))]
+    Reference {
+        location: Location,
+        is_mutable: bool,
+        inner: Box<Self>,
+    },
+
+    /// An optional type, e.g. `int32?`, a shorthand for `Option[int32]`.
+    #[cfg_attr(feature = "serde", serde(rename = "optional_type"))]
+    Optional {
+        location: Location,
+        inner: Box<Self>,
+    },
 }
 
 impl Type {
@@ -490,40 +788,146 @@ impl Type {
             | Self::Constructor(TypeConstructor { location, .. })
             | Self::InterfaceObject { location, .. }
             | Self::Tuple { location, .. }
-            | Self::Underscore { location } => *location,
+            | Self::Underscore { location }
+            | Self::Const { location, .. }
+            | Self::Array { location, .. }
+            | Self::Reference { location, .. }
+            | Self::Optional { location, .. } => *location,
         }
     }
 }
 
-/// A type parameter, e.g. `T` in `fun into[T](a: T);`.
+/// A value of a const generic argument, e.g. `4`, `true` or `'a'`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "value_kind"))]
+pub enum ConstValue {
+    #[cfg_attr(feature = "serde", serde(rename = "boolean"))]
+    Boolean { value: bool },
+    #[cfg_attr(feature = "serde", serde(rename = "character"))]
+    Character { value: char },
+    #[cfg_attr(feature = "serde", serde(rename = "integer"))]
+    Integer { value: u64 },
+}
+
+/// A type parameter, e.g. `T` in `fun into[T](a: T);`, or a const generic
+/// parameter, e.g. `N` in `struct Array[T, const N: usize] {}`.
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct GenericParameter {
     pub name: IdentifierAST,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub bounds: Option<Vec<TypeConstructor>>,
 
+    /// Type of a const generic parameter, e.g. `usize` in `const N: usize`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub const_type: Option<Type>,
+
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub default_value: Option<Type>,
 }
 
+impl GenericParameter {
+    /// Returns `true` if the generic parameter is a const generic parameter.
+    #[inline]
+    #[must_use]
+    pub const fn is_const(&self) -> bool {
+        self.const_type.is_some()
+    }
+}
+
 /// A type alias, e.g. `type MyResult = Result[String, MyError];`.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct TypeAlias {
+    pub node_id: NodeId,
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
     pub value: Type,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
+}
+
+/// A constant, e.g. `const MAX_SIZE: uint32 = 1024;`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct Constant {
+    pub node_id: NodeId,
+    pub attributes: Vec<Attribute>,
+    pub visibility: Visibility,
+    pub name: IdentifierAST,
+
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub ty: Type,
+
+    /// Value of the constant, `None` for constants declared in interfaces
+    /// without a default value.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub value: Option<Expression>,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub docstring: Option<Docstring>,
+}
+
+/// A static item, e.g. `static mut COUNTER: uint32 = 0;`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct Static {
+    pub node_id: NodeId,
+    pub attributes: Vec<Attribute>,
+    pub visibility: Visibility,
+    pub is_mutable: bool,
+    pub name: IdentifierAST,
+
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub ty: Type,
+    pub value: Expression,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub docstring: Option<Docstring>,
+}
+
+/// A submodule declaration, e.g. `module utils;`, or an inline submodule, e.g.
+/// `module utils { fun foo() {} }`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct Submodule {
+    pub node_id: NodeId,
+    pub attributes: Vec<Attribute>,
+    pub visibility: Visibility,
+    pub name: IdentifierAST,
+
+    /// Items of the inline submodule, `None` for a declaration.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub items: Option<Vec<ModuleItem>>,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub docstring: Option<Docstring>,
+}
+
+impl Submodule {
+    /// Returns `true` if the submodule is defined inline.
+    #[inline]
+    #[must_use]
+    pub const fn is_inline(&self) -> bool {
+        self.items.is_some()
+    }
 }
 
 /// A where clause predicate, e.g. `T: ToString`.
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct WherePredicate {
     #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub ty: Type,
@@ -534,18 +938,47 @@ pub struct WherePredicate {
 /// An expression.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum Expression {
-    /// List expression, e.g. `[1, 2, 3]`.
+    /// List expression, e.g. `[1, 2, 3]` and `[1, ..rest]`.
     #[cfg_attr(feature = "serde", serde(rename = "list_expression"))]
     List {
+        node_id: NodeId,
         location: Location,
-        elements: Vec<Self>,
+        elements: Vec<ListElement>,
+    },
+
+    /// Array repeat expression, e.g. `[0; 4]`.
+    #[cfg_attr(feature = "serde", serde(rename = "array_repeat_expression"))]
+    ArrayRepeat {
+        node_id: NodeId,
+        location: Location,
+        value: Box<Self>,
+        size: Box<Self>,
+    },
+
+    /// Borrow expression, e.g. `&a` and `&mut a`.
+    #[cfg_attr(feature = "serde", serde(rename = "borrow_expression"))]
+    Borrow {
+        node_id: NodeId,
+        location: Location,
+        is_mutable: bool,
+        inner: Box<Self>,
+    },
+
+    /// Map expression, e.g. `{"a": 1, "b": 2}` and `{:}`.
+    #[cfg_attr(feature = "serde", serde(rename = "map_expression"))]
+    Map {
+        node_id: NodeId,
+        location: Location,
+        entries: Vec<MapEntry>,
     },
 
     /// As expression, e.g. `a as float32`.
     #[cfg_attr(feature = "serde", serde(rename = "as_expression"))]
     As {
+        node_id: NodeId,
         location: Location,
         left: Box<Self>,
         right: Type,
@@ -554,6 +987,7 @@ pub enum Expression {
     /// Loop expression, e.g. `loop { ... }`
     #[cfg_attr(feature = "serde", serde(rename = "loop_expression"))]
     Loop {
+        node_id: NodeId,
         location: Location,
         statements_block: Vec<Statement>,
     },
@@ -561,34 +995,88 @@ pub enum Expression {
     /// Binary expression, e.g. `1 + 2`.
     #[cfg_attr(feature = "serde", serde(rename = "binary_expression"))]
     Binary {
+        node_id: NodeId,
         location: Location,
         left: Box<Self>,
         operator: BinaryOperator,
         right: Box<Self>,
     },
 
+    /// Range expression, e.g. `a..b`, `a..=b`, `..b`, `a..`.
+    #[cfg_attr(feature = "serde", serde(rename = "range_expression"))]
+    Range {
+        node_id: NodeId,
+        location: Location,
+
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        start: Option<Box<Self>>,
+
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        end: Option<Box<Self>>,
+
+        #[cfg_attr(feature = "serde", serde(rename = "range_kind"))]
+        kind: RangeKind,
+    },
+
     /// Block expression, e.g. `{ let b = 1; b }`.
     #[cfg_attr(feature = "serde", serde(rename = "statements_block_expression"))]
     StatementsBlock {
+        node_id: NodeId,
+        location: Location,
+        block: Vec<Statement>,
+    },
+
+    /// Await expression, e.g. `fetch(url).await`.
+    #[cfg_attr(feature = "serde", serde(rename = "await_expression"))]
+    Await {
+        node_id: NodeId,
+        location: Location,
+        inner: Box<Self>,
+    },
+
+    /// Unsafe block expression, e.g. `unsafe { ptr.read() }`.
+    #[cfg_attr(feature = "serde", serde(rename = "unsafe_expression"))]
+    Unsafe {
+        node_id: NodeId,
+        location: Location,
+        block: Vec<Statement>,
+    },
+
+    /// Try block expression, e.g. `try { a?.b? }`. `?` operators inside the
+    /// block propagate errors to the block instead of the enclosing function.
+    #[cfg_attr(feature = "serde", serde(rename = "try_expression"))]
+    Try {
+        node_id: NodeId,
         location: Location,
         block: Vec<Statement>,
     },
 
     /// Literal expression, e.g. `true`, `\"hello\"`, `1.2`.
     #[cfg_attr(feature = "serde", serde(rename = "literal_expression"))]
-    Literal(Literal),
+    Literal {
+        node_id: NodeId,
+
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        literal: Literal,
+    },
 
     /// Identifier expression, e.g. `foo`.
     #[cfg_attr(feature = "serde", serde(rename = "identifier_expression"))]
-    Identifier(IdentifierAST),
+    Identifier {
+        node_id: NodeId,
+
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        identifier: IdentifierAST,
+    },
 
     /// Underscore expression, e.g. `_`.
     #[cfg_attr(feature = "serde", serde(rename = "underscore_expression"))]
-    Underscore { location: Location },
+    Underscore { node_id: NodeId, location: Location },
 
     /// Parenthesized expression, e.g. `(1 + 2)`.
     #[cfg_attr(feature = "serde", serde(rename = "parenthesized_expression"))]
     Parenthesized {
+        node_id: NodeId,
         location: Location,
         inner: Box<Self>,
     },
@@ -596,22 +1084,46 @@ pub enum Expression {
     /// If expression, e.g. `if x { ... } else { ... }`.
     #[cfg_attr(feature = "serde", serde(rename = "if_expression"))]
     If {
+        node_id: NodeId,
         location: Location,
         if_blocks: Vec<(Self, Vec<Statement>)>,
         r#else: Option<Vec<Statement>>,
     },
 
+    /// If let expression, e.g. `if let Some(x) = y { ... } else { ... }`.
+    #[cfg_attr(feature = "serde", serde(rename = "if_let_expression"))]
+    IfLet {
+        node_id: NodeId,
+        location: Location,
+        pattern: Pattern,
+        expression: Box<Self>,
+        block: Vec<Statement>,
+        r#else: Option<Vec<Statement>>,
+    },
+
     /// Field access expression, e.g. `x.y`.
     #[cfg_attr(feature = "serde", serde(rename = "field_access_expression"))]
     FieldAccess {
+        node_id: NodeId,
         location: Location,
         left: Box<Self>,
         right: IdentifierAST,
     },
 
+    /// Tuple index expression, e.g. `x.0`.
+    #[cfg_attr(feature = "serde", serde(rename = "tuple_index_expression"))]
+    TupleIndex {
+        node_id: NodeId,
+        location: Location,
+        left: Box<Self>,
+        index: usize,
+        index_location: Location,
+    },
+
     /// Prefix expression, e.g. `!false`, `++a`.
     #[cfg_attr(feature = "serde", serde(rename = "prefix_expression"))]
     Prefix {
+        node_id: NodeId,
         location: Location,
         inner: Box<Self>,
         operator: PrefixOperator,
@@ -620,6 +1132,7 @@ pub enum Expression {
     /// Postfix expression, e.g. `safe_div(1, 0)?`, `a++`.
     #[cfg_attr(feature = "serde", serde(rename = "postfix_expression"))]
     Postfix {
+        node_id: NodeId,
         location: Location,
         inner: Box<Self>,
         operator: PostfixOperator,
@@ -628,22 +1141,45 @@ pub enum Expression {
     /// While expression, e.g. `while x != 0 {}`.
     #[cfg_attr(feature = "serde", serde(rename = "while_expression"))]
     While {
+        node_id: NodeId,
         location: Location,
         condition: Box<Self>,
         statements_block: Vec<Statement>,
     },
 
+    /// While let expression, e.g. `while let Some(x) = iter.next() {}`.
+    #[cfg_attr(feature = "serde", serde(rename = "while_let_expression"))]
+    WhileLet {
+        node_id: NodeId,
+        location: Location,
+        pattern: Pattern,
+        expression: Box<Self>,
+        statements_block: Vec<Statement>,
+    },
+
+    /// For expression, e.g. `for x in [1, 2, 3] {}`.
+    #[cfg_attr(feature = "serde", serde(rename = "for_expression"))]
+    For {
+        node_id: NodeId,
+        location: Location,
+        pattern: Pattern,
+        iterable: Box<Self>,
+        statements_block: Vec<Statement>,
+    },
+
     /// Call expression, e.g. `s.to_string()`.
     #[cfg_attr(feature = "serde", serde(rename = "call_expression"))]
     Call {
+        node_id: NodeId,
         location: Location,
         callee: Box<Self>,
-        arguments: Vec<Self>,
+        arguments: Vec<CallArgument>,
     },
 
     /// Type arguments expression, e.g. `sizeof[uint32]`.
     #[cfg_attr(feature = "serde", serde(rename = "type_arguments_expression"))]
     TypeArguments {
+        node_id: NodeId,
         location: Location,
         left: Box<Self>,
         arguments: Vec<Type>,
@@ -652,21 +1188,29 @@ pub enum Expression {
     /// Tuple expression, e.g. `(a, 32, \"hello\")`.
     #[cfg_attr(feature = "serde", serde(rename = "tuple_expression"))]
     Tuple {
+        node_id: NodeId,
         location: Location,
         elements: Vec<Self>,
     },
 
-    /// Struct expression, e.g. `Person { name: \"John\", age: 25 }`.
+    /// Struct expression, e.g. `Person { name: \"John\", age: 25 }`,
+    /// `Person { name: \"Ann\", ..defaults }`.
     #[cfg_attr(feature = "serde", serde(rename = "struct_expression"))]
     Struct {
+        node_id: NodeId,
         location: Location,
         left: Box<Self>,
         fields: Vec<StructFieldExpression>,
+
+        /// The expression after `..`, that provides the remaining fields.
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        rest: Option<Box<Self>>,
     },
 
     /// Match expression (`match fs.read_file(...) { ... }`).
     #[cfg_attr(feature = "serde", serde(rename = "match_expression"))]
     Match {
+        node_id: NodeId,
         location: Location,
         expression: Box<Self>,
         block: Vec<MatchExpressionItem>,
@@ -675,7 +1219,9 @@ pub enum Expression {
     /// Lambda expression (`|x| { x + 1 }`).
     #[cfg_attr(feature = "serde", serde(rename = "lambda_expression"))]
     Lambda {
+        node_id: NodeId,
         location: Location,
+        captures: Vec<LambdaCapture>,
         parameters: Vec<LambdaFunctionParameter>,
 
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
@@ -688,6 +1234,7 @@ pub enum Expression {
 /// A lambda function parameter, e.g. `x` in `|x| { x + 1 }`.
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct LambdaFunctionParameter {
     pub name: IdentifierAST,
 
@@ -696,6 +1243,34 @@ pub struct LambdaFunctionParameter {
     pub ty: Option<Type>,
 }
 
+/// An explicit capture of a lambda, e.g. `move x` and `&y` in `|[move x, &y]| x + y`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct LambdaCapture {
+    pub location: Location,
+    pub kind: CaptureKind,
+    pub name: IdentifierAST,
+}
+
+/// A way a lambda captures a variable.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum CaptureKind {
+    /// The variable is moved into the lambda, e.g. `move x`.
+    #[cfg_attr(feature = "serde", serde(rename = "move"))]
+    Move,
+
+    /// The lambda borrows the variable, e.g. `&x`.
+    #[cfg_attr(feature = "serde", serde(rename = "reference"))]
+    Reference,
+
+    /// The lambda mutably borrows the variable, e.g. `&mut x`.
+    #[cfg_attr(feature = "serde", serde(rename = "mutable_reference"))]
+    MutableReference,
+}
+
 impl Expression {
     /// Returns the location of the expression.
     #[inline]
@@ -703,31 +1278,86 @@ impl Expression {
     pub const fn location(&self) -> Location {
         match self {
             Self::List { location, .. }
+            | Self::ArrayRepeat { location, .. }
+            | Self::Map { location, .. }
+            | Self::Borrow { location, .. }
             | Self::As { location, .. }
             | Self::Binary { location, .. }
             | Self::StatementsBlock { location, .. }
-            | Self::Literal(
-                Literal::Integer { location, .. }
-                | Literal::Float { location, .. }
-                | Literal::Character { location, .. }
-                | Literal::String { location, .. }
-                | Literal::Boolean { location, .. },
-            )
+            | Self::Unsafe { location, .. }
+            | Self::Try { location, .. }
+            | Self::Await { location, .. }
+            | Self::Literal {
+                literal:
+                    Literal::Integer { location, .. }
+                    | Literal::Float { location, .. }
+                    | Literal::Character { location, .. }
+                    | Literal::String { location, .. }
+                    | Literal::Boolean { location, .. },
+                ..
+            }
             | Self::Loop { location, .. }
-            | Self::Identifier(IdentifierAST { location, .. })
+            | Self::Identifier {
+                identifier: IdentifierAST { location, .. },
+                ..
+            }
             | Self::Parenthesized { location, .. }
+            | Self::Range { location, .. }
             | Self::If { location, .. }
+            | Self::IfLet { location, .. }
             | Self::FieldAccess { location, .. }
+            | Self::TupleIndex { location, .. }
             | Self::Prefix { location, .. }
             | Self::Postfix { location, .. }
             | Self::While { location, .. }
+            | Self::WhileLet { location, .. }
+            | Self::For { location, .. }
             | Self::Call { location, .. }
             | Self::TypeArguments { location, .. }
             | Self::Tuple { location, .. }
             | Self::Struct { location, .. }
             | Self::Match { location, .. }
             | Self::Lambda { location, .. }
-            | Self::Underscore { location } => *location,
+            | Self::Underscore { location, .. } => *location,
+        }
+    }
+
+    /// Returns the node ID of the expression.
+    #[inline]
+    #[must_use]
+    pub const fn node_id(&self) -> NodeId {
+        match self {
+            Self::List { node_id, .. }
+            | Self::ArrayRepeat { node_id, .. }
+            | Self::Map { node_id, .. }
+            | Self::Borrow { node_id, .. }
+            | Self::As { node_id, .. }
+            | Self::Binary { node_id, .. }
+            | Self::StatementsBlock { node_id, .. }
+            | Self::Unsafe { node_id, .. }
+            | Self::Try { node_id, .. }
+            | Self::Await { node_id, .. }
+            | Self::Literal { node_id, .. }
+            | Self::Loop { node_id, .. }
+            | Self::Identifier { node_id, .. }
+            | Self::Parenthesized { node_id, .. }
+            | Self::Range { node_id, .. }
+            | Self::If { node_id, .. }
+            | Self::IfLet { node_id, .. }
+            | Self::FieldAccess { node_id, .. }
+            | Self::TupleIndex { node_id, .. }
+            | Self::Prefix { node_id, .. }
+            | Self::Postfix { node_id, .. }
+            | Self::While { node_id, .. }
+            | Self::WhileLet { node_id, .. }
+            | Self::For { node_id, .. }
+            | Self::Call { node_id, .. }
+            | Self::TypeArguments { node_id, .. }
+            | Self::Tuple { node_id, .. }
+            | Self::Struct { node_id, .. }
+            | Self::Match { node_id, .. }
+            | Self::Lambda { node_id, .. }
+            | Self::Underscore { node_id, .. } => *node_id,
         }
     }
 }
@@ -751,9 +1381,11 @@ macro_rules! operator_type {
         $(#[$($operator_type_doc)*])*
         #[derive(Debug, PartialEq, Copy, Clone, Eq, Hash)]
         #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        #[cfg_attr(feature = "schemars", derive(JsonSchema))]
         pub struct $operator_type_name {
             #[cfg_attr(feature = "serde", serde(serialize_with = "use_display"))]
             #[cfg_attr(feature = "serde", serde(deserialize_with = "use_from_str"))]
+            #[cfg_attr(feature = "schemars", schemars(with = "String"))]
             pub raw: $raw_operator_type_name,
             pub location: Location,
         }
@@ -927,6 +1559,36 @@ operator_type! {
     "%=" => PercentEq
 }
 
+impl RawBinaryOperator {
+    /// Returns the operator applied by a compound assignment operator, e.g.
+    /// `+` for `+=`, or `None` if the operator is not a compound assignment:
+    ///
+    /// ```
+    /// use stellar_ast::RawBinaryOperator;
+    ///
+    /// assert_eq!(
+    ///     RawBinaryOperator::PlusEq.without_assignment(),
+    ///     Some(RawBinaryOperator::Plus)
+    /// );
+    /// assert_eq!(RawBinaryOperator::Eq.without_assignment(), None);
+    /// assert_eq!(RawBinaryOperator::Plus.without_assignment(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn without_assignment(self) -> Option<Self> {
+        match self {
+            Self::PlusEq => Some(Self::Plus),
+            Self::MinusEq => Some(Self::Minus),
+            Self::AsteriskEq => Some(Self::Asterisk),
+            Self::SlashEq => Some(Self::Slash),
+            Self::PercentEq => Some(Self::Percent),
+            Self::OrEq => Some(Self::Or),
+            Self::AmpersandEq => Some(Self::Ampersand),
+            _ => None,
+        }
+    }
+}
+
 operator_type! {
     /// A prefix operator with a particular location.
     ///
@@ -980,9 +1642,24 @@ operator_type! {
     "--" => DoubleMinus
 }
 
+/// A kind of a range expression.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum RangeKind {
+    /// Range that doesn't include its end, e.g. `a..b`.
+    #[cfg_attr(feature = "serde", serde(rename = "exclusive"))]
+    Exclusive,
+
+    /// Range that includes its end, e.g. `a..=b`.
+    #[cfg_attr(feature = "serde", serde(rename = "inclusive"))]
+    Inclusive,
+}
+
 /// A match expression item - `pattern` `=>` `expression`.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct MatchExpressionItem {
     pub left: Pattern,
     pub right: Expression,
@@ -992,6 +1669,7 @@ pub struct MatchExpressionItem {
 /// e.g. `name: "John"` and `age` in `Person { name: "John", age }`.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct StructFieldExpression {
     pub name: IdentifierAST,
 
@@ -999,6 +1677,69 @@ pub struct StructFieldExpression {
     pub value: Option<Expression>,
 }
 
+/// An argument in a call expression (optionally `identifier` `:` and `expression`),
+/// e.g. `10`, `y: 20` and `..rest` in `draw(10, y: 20)` and `draw(..rest)`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct CallArgument {
+    /// The label of a named argument, matched to a parameter name.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub name: Option<IdentifierAST>,
+    pub kind: ElementKind,
+
+    /// The value of the argument. In the shorthand form, e.g. `y:` in
+    /// `draw(x: 10, y:)`, it is the variable named as the label.
+    pub value: Expression,
+
+    /// Whether the argument is written in the shorthand form, e.g. `y:`
+    /// instead of `y: y`.
+    pub is_shorthand: bool,
+}
+
+/// An entry of a map expression (`expression` `:` `expression`), e.g. `"a": 1`
+/// in `{"a": 1}`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct MapEntry {
+    pub key: Expression,
+    pub value: Expression,
+}
+
+/// An element of a list expression, e.g. `1` and `..rest` in `[1, ..rest]`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct ListElement {
+    pub kind: ElementKind,
+    pub value: Expression,
+}
+
+/// A kind of an element in a list expression or of a call argument.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum ElementKind {
+    /// A single value, e.g. `a`.
+    #[cfg_attr(feature = "serde", serde(rename = "single"))]
+    Single,
+
+    /// All elements of a collection, e.g. `..a`.
+    #[cfg_attr(feature = "serde", serde(rename = "spread"))]
+    Spread,
+}
+
+impl StructFieldExpression {
+    /// Returns `true` if the field is written in the shorthand form, e.g. `age`
+    /// instead of `age: age`.
+    #[inline]
+    #[must_use]
+    pub const fn is_shorthand(&self) -> bool {
+        self.value.is_none()
+    }
+}
+
 impl Expression {
     /// Returns `true` if this expression has a block in it (except function expressions).
     /// Used to determine if this expression has to have semicolon at the end.
@@ -1009,44 +1750,82 @@ impl Expression {
         matches!(
             self,
             Self::If { .. }
+                | Self::IfLet { .. }
                 | Self::While { .. }
+                | Self::WhileLet { .. }
+                | Self::For { .. }
                 | Self::Match { .. }
                 | Self::StatementsBlock { .. }
+                | Self::Unsafe { .. }
+                | Self::Try { .. }
         )
     }
 }
 
+/// A body of a defer statement.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "defer_kind"))]
+pub enum DeferBody {
+    /// A single call, e.g. `file.close()` in `defer file.close();`.
+    #[cfg_attr(feature = "serde", serde(rename = "call"))]
+    Call(Expression),
+
+    /// A statements block, e.g. `{ close(f); flush(log); }`.
+    #[cfg_attr(feature = "serde", serde(rename = "block"))]
+    Block {
+        location: Location,
+        block: Vec<Statement>,
+    },
+}
+
 /// A statement, e.g. `defer file.close()`, `return Some("hello");`, `break;`.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum Statement {
-    /// Defer statement - `defer <expr>;`, e.g. `defer file.close()`.
+    /// Defer statement - `defer <expr>;` or `defer { ... }`, e.g.
+    /// `defer file.close()`, `defer { close(f); flush(log); }`.
     #[cfg_attr(feature = "serde", serde(rename = "defer_statement"))]
-    Defer { call: Expression },
+    Defer { node_id: NodeId, body: DeferBody },
 
     /// Expression statement, e.g. `call();`.
     #[cfg_attr(feature = "serde", serde(rename = "expression_statement"))]
     Expression {
+        node_id: NodeId,
         expression: Expression,
         has_semicolon: bool,
     },
 
     /// Break statement - `break;`.
     #[cfg_attr(feature = "serde", serde(rename = "break_statement"))]
-    Break { location: Location },
+    Break { node_id: NodeId, location: Location },
 
     /// Continue statement - `continue`;
     #[cfg_attr(feature = "serde", serde(rename = "continue_statement"))]
-    Continue { location: Location },
+    Continue { node_id: NodeId, location: Location },
 
     /// Return statement - `return <expr>;`, e.g. `return 42;`.
     #[cfg_attr(feature = "serde", serde(rename = "return_statement"))]
-    Return { expression: Expression },
+    Return {
+        node_id: NodeId,
+        expression: Expression,
+    },
+
+    /// Yield statement - `yield <expr>;`, e.g. `yield item;`.
+    #[cfg_attr(feature = "serde", serde(rename = "yield_statement"))]
+    Yield {
+        node_id: NodeId,
+        location: Location,
+        expression: Expression,
+    },
 
     /// Let statement - `let <pattern> = <expr>;`, e.g. `let x = 1`.
     #[cfg_attr(feature = "serde", serde(rename = "let_statement"))]
     Let {
+        node_id: NodeId,
         pattern: Pattern,
         value: Expression,
 
@@ -1056,27 +1835,51 @@ pub enum Statement {
     },
 }
 
+impl Statement {
+    /// Returns the node ID of the statement.
+    #[inline]
+    #[must_use]
+    pub const fn node_id(&self) -> NodeId {
+        match self {
+            Self::Defer { node_id, .. }
+            | Self::Expression { node_id, .. }
+            | Self::Break { node_id, .. }
+            | Self::Continue { node_id, .. }
+            | Self::Return { node_id, .. }
+            | Self::Yield { node_id, .. }
+            | Self::Let { node_id, .. } => *node_id,
+        }
+    }
+}
+
 /// An interface module item.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct Interface {
+    pub node_id: NodeId,
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
     pub where_predicates: Vec<WherePredicate>,
+    pub constants: Vec<Constant>,
     pub methods: Vec<Function>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub inherits: Option<Vec<TypeConstructor>>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
 }
 
 /// An enum module item.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct Enum {
+    pub node_id: NodeId,
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
@@ -1088,13 +1891,16 @@ pub struct Enum {
     pub implements: Option<Vec<TypeConstructor>>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
 }
 
 /// A struct module item.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct Struct {
+    pub node_id: NodeId,
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
@@ -1106,13 +1912,16 @@ pub struct Struct {
     pub implements: Option<Vec<TypeConstructor>>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
 }
 
 /// A tuple-like struct module item.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct TupleLikeStruct {
+    pub node_id: NodeId,
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
@@ -1124,27 +1933,92 @@ pub struct TupleLikeStruct {
     pub implements: Option<Vec<TypeConstructor>>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
+}
+
+/// An implementation block, e.g. `impl Point { ... }`, `impl[T] Iterator for Range[T] { ... }`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct Impl {
+    pub node_id: NodeId,
+
+    /// Location of the entire implementation block.
+    pub location: Location,
+    pub attributes: Vec<Attribute>,
+    pub generic_parameters: Vec<GenericParameter>,
+
+    /// Interface implemented in the block, `None` for inherent implementations.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub interface: Option<TypeConstructor>,
+
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub ty: Type,
+    pub where_predicates: Vec<WherePredicate>,
+    pub methods: Vec<Function>,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub docstring: Option<Docstring>,
+}
+
+/// An extern block declaring foreign functions, e.g.
+/// `extern "C" { fun puts(s: CStr): int32; }`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct ExternBlock {
+    pub node_id: NodeId,
+
+    /// Location of the entire extern block.
+    pub location: Location,
+    pub attributes: Vec<Attribute>,
+
+    /// ABI of the declared functions, e.g. `C` in `extern "C"`.
+    pub abi: String,
+    pub functions: Vec<FunctionSignature>,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub docstring: Option<Docstring>,
 }
 
 /// A module item.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum ModuleItem {
+    /// A constant module item.
+    #[cfg_attr(feature = "serde", serde(rename = "constant_module_item"))]
+    Constant(Constant),
+
     /// An enum module item.
     #[cfg_attr(feature = "serde", serde(rename = "enum_module_item"))]
     Enum(Enum),
 
+    /// An extern block module item.
+    #[cfg_attr(feature = "serde", serde(rename = "extern_module_item"))]
+    Extern(ExternBlock),
+
     /// A function module item.
     #[cfg_attr(feature = "serde", serde(rename = "function_module_item"))]
     Function(Function),
 
+    /// An implementation block module item.
+    #[cfg_attr(feature = "serde", serde(rename = "impl_module_item"))]
+    Impl(Impl),
+
     /// An import module item.
     #[cfg_attr(feature = "serde", serde(rename = "import_module_item"))]
     Import {
+        node_id: NodeId,
+
         /// Location of the entire import item.
         location: Location,
+        attributes: Vec<Attribute>,
+
+        /// Visibility of the import, non-private imports re-export the
+        /// imported names, e.g. `pub import self.utils.fs.File;`.
+        visibility: Visibility,
         path: ImportPath,
     },
 
@@ -1152,6 +2026,14 @@ pub enum ModuleItem {
     #[cfg_attr(feature = "serde", serde(rename = "interface_module_item"))]
     Interface(Interface),
 
+    /// A submodule module item.
+    #[cfg_attr(feature = "serde", serde(rename = "submodule_module_item"))]
+    Module(Submodule),
+
+    /// A static module item.
+    #[cfg_attr(feature = "serde", serde(rename = "static_module_item"))]
+    Static(Static),
+
     /// A struct module item.
     #[cfg_attr(feature = "serde", serde(rename = "struct_module_item"))]
     Struct(Struct),
@@ -1166,12 +2048,36 @@ pub enum ModuleItem {
 }
 
 impl ModuleItem {
+    /// Returns the node ID of the item.
+    #[inline]
+    #[must_use]
+    pub const fn node_id(&self) -> NodeId {
+        match self {
+            Self::Constant(Constant { node_id, .. })
+            | Self::Enum(Enum { node_id, .. })
+            | Self::Extern(ExternBlock { node_id, .. })
+            | Self::Function(Function { node_id, .. })
+            | Self::Impl(Impl { node_id, .. })
+            | Self::Import { node_id, .. }
+            | Self::Interface(Interface { node_id, .. })
+            | Self::Module(Submodule { node_id, .. })
+            | Self::Static(Static { node_id, .. })
+            | Self::Struct(Struct { node_id, .. })
+            | Self::TupleLikeStruct(TupleLikeStruct { node_id, .. })
+            | Self::TypeAlias(TypeAlias { node_id, .. }) => *node_id,
+        }
+    }
+
     /// Returns the location of the item.
     #[inline]
     #[must_use]
     pub const fn location(&self) -> Location {
         match self {
-            Self::Enum(Enum {
+            Self::Constant(Constant {
+                name: IdentifierAST { location, .. },
+                ..
+            })
+            | Self::Enum(Enum {
                 name: IdentifierAST { location, .. },
                 ..
             })
@@ -1183,7 +2089,13 @@ impl ModuleItem {
                     },
                 ..
             })
+            | Self::Impl(Impl { location, .. })
+            | Self::Extern(ExternBlock { location, .. })
             | Self::Import { location, .. }
+            | Self::Static(Static {
+                name: IdentifierAST { location, .. },
+                ..
+            })
             | Self::Struct(Struct {
                 name: IdentifierAST { location, .. },
                 ..
@@ -1192,6 +2104,10 @@ impl ModuleItem {
                 name: IdentifierAST { location, .. },
                 ..
             })
+            | Self::Module(Submodule {
+                name: IdentifierAST { location, .. },
+                ..
+            })
             | Self::TupleLikeStruct(TupleLikeStruct {
                 name: IdentifierAST { location, .. },
                 ..
@@ -1208,7 +2124,11 @@ impl ModuleItem {
     #[must_use]
     pub const fn name_identifier_id(&self) -> Option<IdentifierId> {
         match self {
-            Self::Enum(Enum {
+            Self::Constant(Constant {
+                name: IdentifierAST { id, .. },
+                ..
+            })
+            | Self::Enum(Enum {
                 name: IdentifierAST { id, .. },
                 ..
             })
@@ -1220,6 +2140,10 @@ impl ModuleItem {
                     },
                 ..
             })
+            | Self::Static(Static {
+                name: IdentifierAST { id, .. },
+                ..
+            })
             | Self::Struct(Struct {
                 name: IdentifierAST { id, .. },
                 ..
@@ -1232,11 +2156,15 @@ impl ModuleItem {
                 name: IdentifierAST { id, .. },
                 ..
             })
+            | Self::Module(Submodule {
+                name: IdentifierAST { id, .. },
+                ..
+            })
             | Self::TypeAlias(TypeAlias {
                 name: IdentifierAST { id, .. },
                 ..
             }) => Some(*id),
-            Self::Import { .. } => None,
+            Self::Impl(..) | Self::Extern(..) | Self::Import { .. } => None,
         }
     }
 
@@ -1256,10 +2184,15 @@ impl ModuleItem {
     #[must_use]
     pub const fn kind(&self) -> ModuleItemKind {
         match self {
+            Self::Constant(..) => ModuleItemKind::Constant,
             Self::Enum { .. } => ModuleItemKind::Enum,
             Self::Function(..) => ModuleItemKind::Function,
+            Self::Impl(..) => ModuleItemKind::Impl,
+            Self::Extern(..) => ModuleItemKind::Extern,
             Self::Import { .. } => ModuleItemKind::Import,
             Self::Interface { .. } => ModuleItemKind::Interface,
+            Self::Module(..) => ModuleItemKind::Module,
+            Self::Static(..) => ModuleItemKind::Static,
             Self::Struct { .. } => ModuleItemKind::Struct,
             Self::TupleLikeStruct { .. } => ModuleItemKind::TupleLikeStruct,
             Self::TypeAlias(..) => ModuleItemKind::TypeAlias,
@@ -1271,16 +2204,20 @@ impl ModuleItem {
     #[must_use]
     pub const fn visibility(&self) -> Option<Visibility> {
         match self {
-            Self::Enum(Enum { visibility, .. })
+            Self::Constant(Constant { visibility, .. })
+            | Self::Enum(Enum { visibility, .. })
+            | Self::Static(Static { visibility, .. })
             | Self::Struct(Struct { visibility, .. })
             | Self::TupleLikeStruct(TupleLikeStruct { visibility, .. })
             | Self::Interface(Interface { visibility, .. })
+            | Self::Module(Submodule { visibility, .. })
             | Self::TypeAlias(TypeAlias { visibility, .. })
             | Self::Function(Function {
                 signature: FunctionSignature { visibility, .. },
                 ..
-            }) => Some(*visibility),
-            Self::Import { .. } => None,
+            })
+            | Self::Import { visibility, .. } => Some(*visibility),
+            Self::Impl(..) | Self::Extern(..) => None,
         }
     }
 
@@ -1294,23 +2231,61 @@ impl ModuleItem {
     pub fn visibility_or_panic(&self) -> Visibility {
         self.visibility().unwrap()
     }
+
+    /// Returns the attributes of the item.
+    #[inline]
+    #[must_use]
+    pub fn attributes(&self) -> &[Attribute] {
+        match self {
+            Self::Constant(Constant { attributes, .. })
+            | Self::Enum(Enum { attributes, .. })
+            | Self::Static(Static { attributes, .. })
+            | Self::Struct(Struct { attributes, .. })
+            | Self::TupleLikeStruct(TupleLikeStruct { attributes, .. })
+            | Self::Interface(Interface { attributes, .. })
+            | Self::Module(Submodule { attributes, .. })
+            | Self::TypeAlias(TypeAlias { attributes, .. })
+            | Self::Function(Function {
+                signature: FunctionSignature { attributes, .. },
+                ..
+            })
+            | Self::Impl(Impl { attributes, .. })
+            | Self::Extern(ExternBlock { attributes, .. })
+            | Self::Import { attributes, .. } => attributes,
+        }
+    }
 }
 
 /// A kind of module item.
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Display)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Display)]
 pub enum ModuleItemKind {
+    #[display(fmt = "constant")]
+    Constant,
+
     #[display(fmt = "enum")]
     Enum,
 
+    #[display(fmt = "extern block")]
+    Extern,
+
     #[display(fmt = "function")]
     Function,
 
+    #[display(fmt = "implementation")]
+    Impl,
+
     #[display(fmt = "import")]
     Import,
 
     #[display(fmt = "interface")]
     Interface,
 
+    #[display(fmt = "module")]
+    Module,
+
+    #[display(fmt = "static")]
+    Static,
+
     #[display(fmt = "struct")]
     Struct,
 
@@ -1329,8 +2304,9 @@ impl From<ModuleItemKind> for String {
 }
 
 /// An enum item, e.g. `None`, `Ok(T)`, `A { b: T }`.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum EnumItem {
     /// Just an identifier, e.g. `None` in `enum Option[T] { Some(T), None }`.
@@ -1339,7 +2315,7 @@ pub enum EnumItem {
         name: IdentifierAST,
 
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-        docstring: Option<String>,
+        docstring: Option<Docstring>,
     },
     /// A tuple-like enum item, e.g. `None` in `enum Option<T> { Some(T), None }`.
     #[cfg_attr(feature = "serde", serde(rename = "tuple_like_item"))]
@@ -1348,7 +2324,7 @@ pub enum EnumItem {
         fields: Vec<TupleField>,
 
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-        docstring: Option<String>,
+        docstring: Option<Docstring>,
     },
     /// A struct item, e.g. `A { b: T }` in `enum B { A { b: T } }`.
     #[cfg_attr(feature = "serde", serde(rename = "struct_item"))]
@@ -1357,13 +2333,14 @@ pub enum EnumItem {
         fields: Vec<StructField>,
 
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-        docstring: Option<String>,
+        docstring: Option<Docstring>,
     },
 }
 
 /// A tuple field, e.g. `pub String` in `pub struct Wrapper(pub String);`.
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct TupleField {
     pub visibility: Visibility,
 
@@ -1372,9 +2349,11 @@ pub struct TupleField {
 }
 
 /// A struct field, e.g. `name: String`, `pub age: uint32`.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct StructField {
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
 
@@ -1382,24 +2361,47 @@ pub struct StructField {
     pub ty: Type,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
 }
 
 /// A function.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct Function {
+    pub node_id: NodeId,
     pub signature: FunctionSignature,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub body: Option<Vec<Statement>>,
+
+    /// Location of the function body including braces, `None` if the
+    /// function doesn't have a body.
+    ///
+    /// Present even if parsing of the body was deferred.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub body_location: Option<Location>,
+}
+
+impl Function {
+    /// Returns `true` if the function has a body, which wasn't parsed yet.
+    #[inline]
+    #[must_use]
+    pub const fn is_body_deferred(&self) -> bool {
+        self.body.is_none() && self.body_location.is_some()
+    }
 }
 
 /// A function signature - information about function except a block.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct FunctionSignature {
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
+    pub is_async: bool,
+    pub is_unsafe: bool,
+    pub is_generator: bool,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
     pub parameters: Vec<FunctionParameter>,
@@ -1410,12 +2412,13 @@ pub struct FunctionSignature {
     pub where_predicates: Vec<WherePredicate>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
 }
 
 /// A function parameter, e.g. `self`, `self: Self`, `a: uint32`.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum FunctionParameter {
     /// A function parameter that is not `self`.
@@ -1430,6 +2433,7 @@ pub enum FunctionParameter {
 /// A self parameter, e.g. `self`, `self: Self`.
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct SelfFunctionParameter {
     pub self_location: Location,
 
@@ -1441,6 +2445,7 @@ pub struct SelfFunctionParameter {
 /// A function parameter that is not `self`, e.g. `a: uint32`.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct NotSelfFunctionParameter {
     pub pattern: Pattern,
 
@@ -1451,26 +2456,102 @@ pub struct NotSelfFunctionParameter {
 /// A Stellar module.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct Module {
     pub filepath: PathId,
 
     pub items: Vec<ModuleItem>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
+}
+
+impl Module {
+    /// Returns node counts, nesting depth and complexity estimates of the
+    /// module and its functions, see [`ModuleMetrics`] for more details.
+    ///
+    /// [`ModuleMetrics`]: crate::metrics::ModuleMetrics
+    #[inline]
+    #[must_use]
+    pub fn metrics(&self) -> metrics::ModuleMetrics {
+        metrics::ModuleMetrics::new(self)
+    }
+}
+
+/// Returns a JSON Schema describing serialized [`Module`]s.
+///
+/// The schema follows the `serde` representation of the AST, so it can be
+/// used to validate and deserialize AST dumps in tools, that are not written
+/// in Rust.
+#[cfg(feature = "schemars")]
+#[inline]
+#[must_use]
+pub fn json_schema() -> schemars::schema::RootSchema {
+    schemars::schema_for!(Module)
+}
+
+/// An attribute, e.g. `#[inline]`, `#[deprecated("use `bar` instead")]`.
+// not `Eq`, because arguments may contain float literals, so nodes with
+// attributes (`TypeAlias`, `StructField`, `EnumItem`, ...) aren't `Eq` either
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct Attribute {
+    pub location: Location,
+    pub path: Path,
+
+    /// Arguments of the attribute, `None` if the attribute has no parentheses.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub arguments: Option<Vec<Expression>>,
 }
 
-/// A visibility qualifier - `pub` or nothing (private visibility).
+/// A visibility qualifier - `pub`, `pub(package)`, `pub(super)` or nothing
+/// (private visibility).
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum Visibility {
+    /// Visible in the module, where the item is defined, and its submodules.
     #[default]
     #[cfg_attr(feature = "serde", serde(rename = "private"))]
     Private,
 
+    /// Visible everywhere, including dependent packages.
     #[cfg_attr(feature = "serde", serde(rename = "public"))]
     Public(#[cfg_attr(feature = "serde", serde(rename = "location"))] Location),
+
+    /// `pub(package)` - visible in the package, where the item is defined, but
+    /// not in dependent packages.
+    #[cfg_attr(feature = "serde", serde(rename = "package"))]
+    Package(#[cfg_attr(feature = "serde", serde(rename = "location"))] Location),
+
+    /// `pub(super)` - visible in the parent module of the module, where the
+    /// item is defined, and its submodules.
+    #[cfg_attr(feature = "serde", serde(rename = "super"))]
+    Super(#[cfg_attr(feature = "serde", serde(rename = "location"))] Location),
+}
+
+impl Visibility {
+    /// Returns `true` if the visibility is [`Visibility::Public`].
+    #[inline]
+    #[must_use]
+    pub const fn is_public(self) -> bool {
+        matches!(self, Self::Public(_))
+    }
+
+    /// Returns the location of the visibility qualifier, or `None` if the
+    /// visibility is private.
+    #[inline]
+    #[must_use]
+    pub const fn location_or_none(self) -> Option<Location> {
+        match self {
+            Self::Private => None,
+            Self::Public(location) | Self::Package(location) | Self::Super(location) => {
+                Some(location)
+            }
+        }
+    }
 }
 
 #[cfg(feature = "serde")]
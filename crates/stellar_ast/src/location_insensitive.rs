@@ -0,0 +1,462 @@
+//! Equality and hashing of AST nodes, that ignore locations.
+//!
+//! Derived [`PartialEq`] compares locations of nodes, so the same code parsed
+//! from a reformatted file or shifted by an edit before it is not equal to the
+//! original one. [`LocationInsensitive`] compares and hashes only the structure
+//! of nodes, so incremental caches and deduplication can detect, that code is
+//! semantically unchanged:
+//!
+//! ```
+//! use stellar_ast::{
+//!     builder::ExprBuilder, location_insensitive::LocationInsensitive, Expression,
+//! };
+//! use stellar_filesystem::location::ByteOffset;
+//!
+//! let a: Expression = ExprBuilder::identifier("a").field("b").into();
+//! let mut b = a.clone();
+//! if let Expression::FieldAccess { location, .. } = &mut b {
+//!     location.start = ByteOffset(42);
+//! }
+//!
+//! assert_ne!(a, b);
+//! assert!(a.eq_ignoring_locations(&b));
+//! ```
+//!
+//! Besides locations, node IDs and file paths of modules are ignored as well,
+//! because they also depend on where the code is and not on what it is.
+//! Docstrings only store their locations, so only their presence is compared.
+//! Float literals are compared by their bits, so `NaN` is equal to itself.
+
+use std::hash::{Hash, Hasher};
+
+use stellar_filesystem::location::Location;
+use stellar_interner::IdentifierId;
+
+use crate::{
+    Attribute, BinaryOperator, BindingMode, CallArgument, CaptureKind, ConstValue, Constant,
+    DeferBody, Docstring, ElementKind, Enum, EnumItem, Expression, ExternBlock, Function,
+    FunctionParameter, FunctionSignature, GenericParameter, IdentifierAST, Impl, ImportPath,
+    IntegerValue, Interface, LambdaCapture, LambdaFunctionParameter, ListElement, Literal,
+    MapEntry, MatchExpressionItem, Module, ModuleItem, NegativeNumericLiteral, NodeId,
+    NotSelfFunctionParameter, Path, Pattern, PostfixOperator, PrefixOperator, RangeKind,
+    RawBinaryOperator, RawPostfixOperator, RawPrefixOperator, SelfFunctionParameter, Statement,
+    Static, Struct, StructField, StructFieldExpression, StructFieldPattern, Submodule, TupleField,
+    TupleLikeStruct, Type, TypeAlias, TypeConstructor, Visibility, WherePredicate,
+};
+
+/// Equality and hashing, that ignore locations, see
+/// [module level docs](crate::location_insensitive) for more details.
+pub trait LocationInsensitive {
+    /// Returns `true` if the nodes are equal, when their locations are not
+    /// taken into account.
+    fn eq_ignoring_locations(&self, other: &Self) -> bool;
+
+    /// Feeds the node into a given hasher without its locations, so that
+    /// nodes equal by [`LocationInsensitive::eq_ignoring_locations`] have
+    /// equal hashes.
+    fn hash_ignoring_locations<H: Hasher>(&self, state: &mut H);
+}
+
+/// A reference to a node, that implements [`PartialEq`], [`Eq`] and [`Hash`]
+/// ignoring locations, e.g. to use nodes as keys of a hash map.
+#[derive(Debug, Clone, Copy)]
+pub struct IgnoringLocations<'a, T: ?Sized>(pub &'a T);
+
+impl<T: LocationInsensitive + ?Sized> PartialEq for IgnoringLocations<'_, T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignoring_locations(other.0)
+    }
+}
+
+impl<T: LocationInsensitive + ?Sized> Eq for IgnoringLocations<'_, T> {}
+
+impl<T: LocationInsensitive + ?Sized> Hash for IgnoringLocations<'_, T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash_ignoring_locations(state);
+    }
+}
+
+macro_rules! compare_values {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl LocationInsensitive for $ty {
+                #[inline]
+                fn eq_ignoring_locations(&self, other: &Self) -> bool {
+                    self == other
+                }
+
+                #[inline]
+                fn hash_ignoring_locations<H: Hasher>(&self, state: &mut H) {
+                    self.hash(state);
+                }
+            }
+        )*
+    };
+}
+
+compare_values! {
+    bool, char, u64, usize, String, IdentifierId, BindingMode, CaptureKind, RangeKind,
+    ElementKind, ConstValue, IntegerValue, RawBinaryOperator, RawPrefixOperator, RawPostfixOperator,
+}
+
+macro_rules! ignore {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl LocationInsensitive for $ty {
+                #[inline]
+                fn eq_ignoring_locations(&self, _: &Self) -> bool {
+                    true
+                }
+
+                #[inline]
+                fn hash_ignoring_locations<H: Hasher>(&self, _: &mut H) {}
+            }
+        )*
+    };
+}
+
+ignore!(Location, NodeId, Docstring);
+
+impl LocationInsensitive for f64 {
+    #[inline]
+    fn eq_ignoring_locations(&self, other: &Self) -> bool {
+        self.to_bits() == other.to_bits()
+    }
+
+    #[inline]
+    fn hash_ignoring_locations<H: Hasher>(&self, state: &mut H) {
+        self.to_bits().hash(state);
+    }
+}
+
+impl<T: LocationInsensitive + ?Sized> LocationInsensitive for &T {
+    #[inline]
+    fn eq_ignoring_locations(&self, other: &Self) -> bool {
+        (**self).eq_ignoring_locations(*other)
+    }
+
+    #[inline]
+    fn hash_ignoring_locations<H: Hasher>(&self, state: &mut H) {
+        (**self).hash_ignoring_locations(state);
+    }
+}
+
+impl<T: LocationInsensitive> LocationInsensitive for [T] {
+    fn eq_ignoring_locations(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other)
+                .all(|(a, b)| a.eq_ignoring_locations(b))
+    }
+
+    fn hash_ignoring_locations<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+
+        for element in self {
+            element.hash_ignoring_locations(state);
+        }
+    }
+}
+
+impl<T: LocationInsensitive> LocationInsensitive for Vec<T> {
+    #[inline]
+    fn eq_ignoring_locations(&self, other: &Self) -> bool {
+        self.as_slice().eq_ignoring_locations(other.as_slice())
+    }
+
+    #[inline]
+    fn hash_ignoring_locations<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash_ignoring_locations(state);
+    }
+}
+
+impl<T: LocationInsensitive> LocationInsensitive for Option<T> {
+    fn eq_ignoring_locations(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.eq_ignoring_locations(b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    fn hash_ignoring_locations<H: Hasher>(&self, state: &mut H) {
+        self.is_some().hash(state);
+
+        if let Some(value) = self {
+            value.hash_ignoring_locations(state);
+        }
+    }
+}
+
+impl<T: LocationInsensitive + ?Sized> LocationInsensitive for Box<T> {
+    #[inline]
+    fn eq_ignoring_locations(&self, other: &Self) -> bool {
+        self.as_ref().eq_ignoring_locations(other.as_ref())
+    }
+
+    #[inline]
+    fn hash_ignoring_locations<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().hash_ignoring_locations(state);
+    }
+}
+
+macro_rules! compare_tuples {
+    ($(($($element:ident $idx:tt),*)),* $(,)?) => {
+        $(
+            impl<$($element: LocationInsensitive),*> LocationInsensitive for ($($element,)*) {
+                #[inline]
+                #[allow(unused_variables)]
+                fn eq_ignoring_locations(&self, other: &Self) -> bool {
+                    true $(&& self.$idx.eq_ignoring_locations(&other.$idx))*
+                }
+
+                #[inline]
+                #[allow(unused_variables)]
+                fn hash_ignoring_locations<H: Hasher>(&self, state: &mut H) {
+                    $(self.$idx.hash_ignoring_locations(state);)*
+                }
+            }
+        )*
+    };
+}
+
+compare_tuples! {
+    (),
+    (A 0),
+    (A 0, B 1),
+    (A 0, B 1, C 2),
+    (A 0, B 1, C 2, D 3),
+}
+
+macro_rules! compare_fields {
+    ($($node:ident { $($field:ident),* }),* $(,)?) => {
+        $(
+            impl LocationInsensitive for $node {
+                fn eq_ignoring_locations(&self, other: &Self) -> bool {
+                    true $(&& self.$field.eq_ignoring_locations(&other.$field))*
+                }
+
+                fn hash_ignoring_locations<H: Hasher>(&self, state: &mut H) {
+                    $(self.$field.hash_ignoring_locations(state);)*
+                }
+            }
+        )*
+    };
+}
+
+compare_fields! {
+    IdentifierAST { id },
+    Path { identifiers },
+    TypeConstructor { path, arguments },
+    GenericParameter { name, bounds, const_type, default_value },
+    WherePredicate { ty, bounds },
+    Attribute { path, arguments },
+    BinaryOperator { raw },
+    PrefixOperator { raw },
+    PostfixOperator { raw },
+    LambdaFunctionParameter { name, ty },
+    LambdaCapture { kind, name },
+    MatchExpressionItem { left, right },
+    StructFieldExpression { name, value },
+    CallArgument { name, kind, value, is_shorthand },
+    MapEntry { key, value },
+    ListElement { kind, value },
+    SelfFunctionParameter { ty },
+    NotSelfFunctionParameter { pattern, ty },
+    FunctionSignature {
+        attributes, visibility, is_async, is_unsafe, is_generator, name, generic_parameters,
+        parameters, return_type, where_predicates, docstring
+    },
+    Function { signature, body },
+    TypeAlias { attributes, visibility, name, generic_parameters, value, docstring },
+    Constant { attributes, visibility, name, ty, value, docstring },
+    Static { attributes, visibility, is_mutable, name, ty, value, docstring },
+    Submodule { attributes, visibility, name, items, docstring },
+    TupleField { visibility, ty },
+    StructField { attributes, visibility, name, ty, docstring },
+    Interface {
+        attributes, visibility, name, generic_parameters, where_predicates, constants, methods,
+        inherits, docstring
+    },
+    Enum {
+        attributes, visibility, name, generic_parameters, where_predicates, items, methods,
+        implements, docstring
+    },
+    Struct {
+        attributes, visibility, name, generic_parameters, where_predicates, fields, methods,
+        implements, docstring
+    },
+    TupleLikeStruct {
+        attributes, visibility, name, generic_parameters, where_predicates, fields, methods,
+        implements, docstring
+    },
+    Impl { attributes, generic_parameters, interface, ty, where_predicates, methods, docstring },
+    ExternBlock { attributes, abi, functions, docstring },
+    Module { items, docstring },
+}
+
+macro_rules! compare_variants {
+    ($(
+        $node:ident {
+            $($variant:ident $({ $($field:ident),* })? $(($inner:ident))?),* $(,)?
+        }
+    ),* $(,)?) => {
+        $(
+            impl LocationInsensitive for $node {
+                fn eq_ignoring_locations(&self, other: &Self) -> bool {
+                    match (self, other) {
+                        $(
+                            (Self::$variant { .. }, Self::$variant { .. }) => {
+                                let Self::$variant $({ $($field,)* .. })? $(($inner))? = other
+                                else {
+                                    unreachable!()
+                                };
+                                let other = ($($($field,)*)? $($inner,)?);
+
+                                let Self::$variant $({ $($field,)* .. })? $(($inner))? = self
+                                else {
+                                    unreachable!()
+                                };
+
+                                ($($($field,)*)? $($inner,)?).eq_ignoring_locations(&other)
+                            }
+                        )*
+                        _ => false,
+                    }
+                }
+
+                fn hash_ignoring_locations<H: Hasher>(&self, state: &mut H) {
+                    std::mem::discriminant(self).hash(state);
+
+                    match self {
+                        $(
+                            Self::$variant $({ $($field,)* .. })? $(($inner))? => {
+                                ($($($field,)*)? $($inner,)?).hash_ignoring_locations(state);
+                            }
+                        )*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+compare_variants! {
+    Visibility { Private {}, Public {}, Package {}, Super {} },
+    Literal {
+        Boolean { value },
+        Character { value },
+        String { value },
+        Integer { value },
+        Float { value },
+    },
+    NegativeNumericLiteral { Float { value }, Integer { value } },
+    ImportPath {
+        Single { path, as_ },
+        Glob { path },
+        Group { path, imports },
+    },
+    Pattern {
+        Literal(literal),
+        NegativeNumericLiteral(literal),
+        Identifier { binding_mode, identifier, pattern },
+        Wildcard {},
+        Struct { path, fields },
+        TupleLike { path, inner_patterns },
+        Tuple { elements },
+        Path { path },
+        List { inner_patterns },
+        Grouped { inner },
+        Or { alternatives },
+        Range { start, end, kind },
+        Rest {},
+    },
+    StructFieldPattern {
+        NotRest { field_name, value_pattern },
+        Rest {},
+    },
+    Type {
+        Constructor(constructor),
+        Tuple { element_types },
+        Function { parameter_types, return_type },
+        Parenthesized { inner },
+        Underscore {},
+        InterfaceObject { bounds },
+        Const { value },
+        Array { element_type, size },
+        Reference { is_mutable, inner },
+        Optional { inner },
+    },
+    Expression {
+        List { elements },
+        ArrayRepeat { value, size },
+        Borrow { is_mutable, inner },
+        Map { entries },
+        As { left, right },
+        Loop { statements_block },
+        Binary { left, operator, right },
+        Range { start, end, kind },
+        StatementsBlock { block },
+        Await { inner },
+        Unsafe { block },
+        Try { block },
+        Literal { literal },
+        Identifier { identifier },
+        Underscore {},
+        Parenthesized { inner },
+        If { if_blocks, r#else },
+        IfLet { pattern, expression, block, r#else },
+        FieldAccess { left, right },
+        TupleIndex { left, index },
+        Prefix { inner, operator },
+        Postfix { inner, operator },
+        While { condition, statements_block },
+        WhileLet { pattern, expression, statements_block },
+        For { pattern, iterable, statements_block },
+        Call { callee, arguments },
+        TypeArguments { left, arguments },
+        Tuple { elements },
+        Struct { left, fields, rest },
+        Match { expression, block },
+        Lambda { captures, parameters, return_type, value },
+    },
+    DeferBody {
+        Call(call),
+        Block { block },
+    },
+    Statement {
+        Defer { body },
+        Expression { expression, has_semicolon },
+        Break {},
+        Continue {},
+        Return { expression },
+        Yield { expression },
+        Let { pattern, value, ty },
+    },
+    EnumItem {
+        Just { name, docstring },
+        TupleLike { name, fields, docstring },
+        Struct { name, fields, docstring },
+    },
+    FunctionParameter {
+        NotSelfParameter(parameter),
+        SelfParameter(parameter),
+    },
+    ModuleItem {
+        Constant(item),
+        Enum(item),
+        Extern(item),
+        Function(item),
+        Impl(item),
+        Import { attributes, visibility, path },
+        Interface(item),
+        Module(item),
+        Static(item),
+        Struct(item),
+        TupleLikeStruct(item),
+        TypeAlias(item),
+    },
+}
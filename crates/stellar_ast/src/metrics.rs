@@ -0,0 +1,220 @@
+//! Size and complexity metrics of a module, see [`Module::metrics()`].
+//!
+//! Metrics are meant for lint frameworks and quality gates, that need to
+//! e.g. reject too long or too complex functions without implementing their
+//! own traversal of the AST:
+//!
+//! ```
+//! use stellar_ast::{
+//!     builder::{ExprBuilder, FunctionBuilder},
+//!     Module,
+//! };
+//! use stellar_interner::DUMMY_PATH_ID;
+//!
+//! let call = ExprBuilder::identifier("foo").call::<ExprBuilder>([]);
+//! let module = Module {
+//!     filepath: DUMMY_PATH_ID,
+//!     items: vec![FunctionBuilder::new("main")
+//!         .with_statement(call.into_statement())
+//!         .into()],
+//!     docstring: None,
+//! };
+//!
+//! let metrics = module.metrics();
+//!
+//! assert_eq!(metrics.items, 1);
+//! assert_eq!(metrics.expressions, 2);
+//! assert_eq!(metrics.functions[0].statements, 1);
+//! assert_eq!(metrics.functions[0].cyclomatic_complexity, 1);
+//! ```
+//!
+//! The AST doesn't store source code, so lengths of functions are measured in
+//! statements. Lines can be counted with [`FunctionMetrics::lines()`] if the
+//! source is available.
+
+use stellar_filesystem::location::Location;
+
+use crate::{
+    visit::{walk_expression, walk_module_item, walk_pattern, walk_statement, walk_type, Visitor},
+    Expression, Function, IdentifierAST, Module, ModuleItem, Pattern, RawBinaryOperator, Statement,
+    Type,
+};
+
+/// Metrics of a module, see [module level docs](crate::metrics) for more
+/// details.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleMetrics {
+    /// Number of module items, including items of inline submodules.
+    pub items: usize,
+
+    /// Number of statements.
+    pub statements: usize,
+
+    /// Number of expressions.
+    pub expressions: usize,
+
+    /// Number of patterns.
+    pub patterns: usize,
+
+    /// Number of types.
+    pub types: usize,
+
+    /// The maximum nesting depth of statements blocks, a function body has
+    /// the depth of 1.
+    pub max_nesting_depth: usize,
+
+    /// Metrics of functions and methods in the order of their appearance.
+    pub functions: Vec<FunctionMetrics>,
+}
+
+/// Metrics of a function or a method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionMetrics {
+    /// Name of the function.
+    pub name: IdentifierAST,
+
+    /// Location of the function body including braces, see
+    /// [`Function::body_location`].
+    pub body_location: Option<Location>,
+
+    /// Number of statements in the body, including nested blocks.
+    pub statements: usize,
+
+    /// The maximum nesting depth of statements blocks in the body, the body
+    /// itself has the depth of 1.
+    pub max_nesting_depth: usize,
+
+    /// An estimate of the number of independent paths through the function:
+    /// `1` plus the number of conditions of `if`, `if let`, `while`,
+    /// `while let`, `for`, `&&`, `||` and alternatives of `match` expressions except
+    /// the first one.
+    pub cyclomatic_complexity: usize,
+}
+
+impl FunctionMetrics {
+    /// Returns the number of lines of the function body in a given source of
+    /// the file, that contains it, or `0` if the function doesn't have a body.
+    #[inline]
+    #[must_use]
+    pub fn lines(&self, source: &str) -> usize {
+        self.body_location
+            .map_or(0, |location| source[location].lines().count())
+    }
+}
+
+impl ModuleMetrics {
+    pub(crate) fn new(module: &Module) -> Self {
+        let mut collector = MetricsCollector::default();
+        collector.visit_module(module);
+        collector.metrics
+    }
+}
+
+#[derive(Default)]
+struct MetricsCollector {
+    metrics: ModuleMetrics,
+
+    /// Metrics of the function, that is being visited.
+    function: Option<FunctionMetrics>,
+    depth: usize,
+}
+
+impl MetricsCollector {
+    const fn add_decision_points(&mut self, count: usize) {
+        if let Some(function) = &mut self.function {
+            function.cyclomatic_complexity += count;
+        }
+    }
+}
+
+impl Visitor for MetricsCollector {
+    fn visit_module_item(&mut self, module_item: &ModuleItem) {
+        self.metrics.items += 1;
+        walk_module_item(self, module_item);
+    }
+
+    fn visit_function(&mut self, function: &Function) {
+        let outer_function = self.function.replace(FunctionMetrics {
+            name: function.signature.name,
+            body_location: function.body_location,
+            statements: 0,
+            max_nesting_depth: 0,
+            cyclomatic_complexity: 1,
+        });
+        let outer_depth = std::mem::take(&mut self.depth);
+
+        self.visit_function_signature(&function.signature);
+
+        if let Some(body) = &function.body {
+            self.visit_statements_block(body);
+        }
+
+        self.depth = outer_depth;
+
+        let metrics = std::mem::replace(&mut self.function, outer_function).unwrap();
+        self.metrics.functions.push(metrics);
+    }
+
+    fn visit_statements_block(&mut self, statements: &[Statement]) {
+        self.depth += 1;
+        self.metrics.max_nesting_depth = self.metrics.max_nesting_depth.max(self.depth);
+
+        if let Some(function) = &mut self.function {
+            function.max_nesting_depth = function.max_nesting_depth.max(self.depth);
+        }
+
+        for statement in statements {
+            self.visit_statement(statement);
+        }
+
+        self.depth -= 1;
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        self.metrics.statements += 1;
+
+        if let Some(function) = &mut self.function {
+            function.statements += 1;
+        }
+
+        walk_statement(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        self.metrics.expressions += 1;
+
+        match expression {
+            Expression::If { if_blocks, .. } => self.add_decision_points(if_blocks.len()),
+            Expression::IfLet { .. }
+            | Expression::While { .. }
+            | Expression::WhileLet { .. }
+            | Expression::For { .. } => {
+                self.add_decision_points(1);
+            }
+            Expression::Match { block, .. } => {
+                self.add_decision_points(block.len().saturating_sub(1));
+            }
+            Expression::Binary { operator, .. }
+                if matches!(
+                    operator.raw,
+                    RawBinaryOperator::DoubleAmpersand | RawBinaryOperator::DoubleOr
+                ) =>
+            {
+                self.add_decision_points(1);
+            }
+            _ => {}
+        }
+
+        walk_expression(self, expression);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        self.metrics.patterns += 1;
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_type(&mut self, ty: &Type) {
+        self.metrics.types += 1;
+        walk_type(self, ty);
+    }
+}
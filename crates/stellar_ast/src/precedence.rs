@@ -7,6 +7,30 @@
 //! operators, ensuring that expressions are parsed correctly and unambiguously.
 //!
 //! See [`Precedence`] for more details.
+//!
+//! # Operator table
+//!
+//! Precedences and associativities of all operators are defined in one table,
+//! [`OPERATORS`], which both the parser and the unparser consult, so a new
+//! operator only needs a new entry there. The table can also be rendered into
+//! documentation with [`markdown_table()`]:
+//!
+//! ```
+//! use stellar_ast::{
+//!     precedence::{Fixity, Operator, Precedence},
+//!     token::{Punctuator, RawToken},
+//! };
+//!
+//! let plus = Operator::find(Fixity::Infix, Punctuator::Plus.into()).unwrap();
+//! assert_eq!(plus.precedence, Precedence::Sum);
+//!
+//! // `+` after an operand is an infix operator
+//! assert_eq!(Precedence::from(RawToken::from(Punctuator::Plus)), Precedence::Sum);
+//! ```
+
+use std::fmt::Write;
+
+use crate::token::{Keyword, Punctuator, RawToken};
 
 /// Defines an enum representing different operator precedences.
 ///
@@ -26,6 +50,9 @@ pub enum Precedence {
     /// `*=`, `/=`, `^=`, `|=`.
     Assign,
 
+    /// Precedence corresponding to range expressions: `a..b`, `a..=b`.
+    Range,
+
     /// Precedence corresponding to binary expressions with `||` operator.
     DoubleOr,
 
@@ -91,3 +118,238 @@ pub enum Precedence {
     /// ```
     GenericArgument,
 }
+
+impl Precedence {
+    /// All precedences from the lowest to the highest.
+    pub const ALL: [Self; 19] = [
+        Self::Lowest,
+        Self::Assign,
+        Self::Range,
+        Self::DoubleOr,
+        Self::DoubleAmpersand,
+        Self::Or,
+        Self::Xor,
+        Self::Comparison,
+        Self::Shift,
+        Self::Sum,
+        Self::Product,
+        Self::Power,
+        Self::Mod,
+        Self::As,
+        Self::Unastellar,
+        Self::Call,
+        Self::Struct,
+        Self::Field,
+        Self::GenericArgument,
+    ];
+
+    /// Returns the precedence right below the given one, or [`Precedence::Lowest`]
+    /// for the lowest one.
+    #[inline]
+    #[must_use]
+    pub const fn lower(self) -> Self {
+        match self {
+            Self::Lowest => Self::Lowest,
+            _ => Self::ALL[self as usize - 1],
+        }
+    }
+}
+
+impl From<RawToken> for Precedence {
+    /// Returns the precedence of a token following an operand, i.e. of an
+    /// infix or a postfix operator, or [`Precedence::Lowest`] if the token
+    /// is not one.
+    fn from(value: RawToken) -> Self {
+        Operator::find(Fixity::Infix, value)
+            .or_else(|| Operator::find(Fixity::Postfix, value))
+            .map_or(Self::Lowest, |operator| operator.precedence)
+    }
+}
+
+impl From<Punctuator> for Precedence {
+    #[inline]
+    fn from(value: Punctuator) -> Self {
+        RawToken::Punctuator(value).into()
+    }
+}
+
+/// Position of an operator relative to its operands.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Fixity {
+    /// The operator goes before its operand, e.g. `!a`.
+    Prefix,
+
+    /// The operator goes between its operands, e.g. `a + b`.
+    Infix,
+
+    /// The operator goes after its operand, e.g. `a?` or `a(b)`.
+    Postfix,
+}
+
+/// How operators of the same precedence are grouped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Associativity {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+
+    /// `a = b = c` would be `a = (b = c)`.
+    Right,
+}
+
+/// An entry of the operator table, see [`OPERATORS`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Operator {
+    /// The token of the operator. For calls, struct expressions and type
+    /// arguments it is the opening bracket.
+    pub token: RawToken,
+
+    /// Position of the operator relative to its operands.
+    pub fixity: Fixity,
+
+    /// How tightly the operator binds its operands.
+    pub precedence: Precedence,
+
+    /// How operators of the same precedence are grouped.
+    pub associativity: Associativity,
+
+    /// Short description of the operator for documentation, e.g. `addition`.
+    pub description: &'static str,
+}
+
+impl Operator {
+    /// Returns the precedence, with which the right operand of an infix
+    /// operator is parsed: operators of the same precedence can only appear
+    /// there, if the operator is right-associative.
+    #[inline]
+    #[must_use]
+    pub const fn right_operand_precedence(self) -> Precedence {
+        match self.associativity {
+            Associativity::Left => self.precedence,
+            Associativity::Right => self.precedence.lower(),
+        }
+    }
+}
+
+macro_rules! operators {
+    {
+        $(
+            $fixity:ident {
+                $(
+                    $kind:ident :: $token:ident =>
+                        $precedence:ident, $associativity:ident, $description:literal;
+                )*
+            }
+        )*
+    } => {
+        /// Table of all operators, see [module level docs](crate::precedence).
+        pub const OPERATORS: &[Operator] = &[
+            $($(
+                Operator {
+                    token: RawToken::$kind($kind::$token),
+                    fixity: Fixity::$fixity,
+                    precedence: Precedence::$precedence,
+                    associativity: Associativity::$associativity,
+                    description: $description,
+                },
+            )*)*
+        ];
+
+        impl Operator {
+            /// Returns the operator with a given token and fixity, if there is
+            /// one.
+            #[must_use]
+            pub const fn find(fixity: Fixity, token: RawToken) -> Option<Self> {
+                match (fixity, token) {
+                    $($(
+                        (Fixity::$fixity, RawToken::$kind($kind::$token)) => Some(Self {
+                            token,
+                            fixity,
+                            precedence: Precedence::$precedence,
+                            associativity: Associativity::$associativity,
+                            description: $description,
+                        }),
+                    )*)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+operators! {
+    Prefix {
+        Punctuator::Bang => Unastellar, Right, "logical negation";
+        Punctuator::Tilde => Unastellar, Right, "bitwise negation";
+        Punctuator::DoublePlus => Unastellar, Right, "prefix increment";
+        Punctuator::DoubleMinus => Unastellar, Right, "prefix decrement";
+        Punctuator::Plus => Unastellar, Right, "unary plus";
+        Punctuator::Minus => Unastellar, Right, "negation";
+        Punctuator::Ampersand => Unastellar, Right, "borrow";
+    }
+    Infix {
+        Punctuator::Eq => Assign, Left, "assignment";
+        Punctuator::PlusEq => Assign, Left, "addition assignment";
+        Punctuator::MinusEq => Assign, Left, "subtraction assignment";
+        Punctuator::AsteriskEq => Assign, Left, "multiplication assignment";
+        Punctuator::SlashEq => Assign, Left, "division assignment";
+        Punctuator::PercentEq => Assign, Left, "remainder assignment";
+        Punctuator::OrEq => Assign, Left, "bitwise or assignment";
+        Punctuator::AmpersandEq => Assign, Left, "bitwise and assignment";
+        Punctuator::DoubleDot => Range, Left, "exclusive range";
+        Punctuator::DoubleDotEq => Range, Left, "inclusive range";
+        Punctuator::DoubleOr => DoubleOr, Left, "logical or";
+        Punctuator::DoubleAmpersand => DoubleAmpersand, Left, "logical and";
+        Punctuator::Or => Or, Left, "bitwise or";
+        Punctuator::DoubleEq => Comparison, Left, "equality";
+        Punctuator::BangEq => Comparison, Left, "inequality";
+        Punctuator::Less => Comparison, Left, "less than";
+        Punctuator::LessEq => Comparison, Left, "less than or equal";
+        Punctuator::Greater => Comparison, Left, "greater than";
+        Punctuator::GreaterEq => Comparison, Left, "greater than or equal";
+        Punctuator::LeftShift => Shift, Left, "left shift";
+        Punctuator::RightShift => Shift, Left, "right shift";
+        Punctuator::Plus => Sum, Left, "addition";
+        Punctuator::Minus => Sum, Left, "subtraction";
+        Punctuator::Asterisk => Product, Left, "multiplication";
+        Punctuator::Slash => Product, Left, "division";
+        Punctuator::DoubleAsterisk => Power, Left, "exponentiation";
+        Punctuator::Percent => Mod, Left, "remainder";
+        Keyword::As => As, Left, "cast";
+    }
+    Postfix {
+        Punctuator::QuestionMark => Unastellar, Left, "error propagation";
+        Punctuator::DoublePlus => Unastellar, Left, "postfix increment";
+        Punctuator::DoubleMinus => Unastellar, Left, "postfix decrement";
+        Punctuator::OpenParent => Call, Left, "call";
+        Punctuator::OpenBrace => Struct, Left, "struct expression";
+        Punctuator::Dot => Field, Left, "field access";
+        Punctuator::OpenBracket => GenericArgument, Left, "type arguments";
+    }
+}
+
+/// Renders [`OPERATORS`] into a Markdown table, from the operators binding
+/// the most tightly to the ones binding the least tightly.
+#[must_use]
+pub fn markdown_table() -> String {
+    let mut operators = OPERATORS.to_vec();
+    operators.sort_by_key(|operator| std::cmp::Reverse(operator.precedence));
+
+    let mut table = "| Operator | Fixity | Precedence | Associativity | Description |\n\
+        | --- | --- | --- | --- | --- |\n"
+        .to_owned();
+
+    for operator in operators {
+        writeln!(
+            table,
+            "| {} | {:?} | {:?} | {:?} | {} |",
+            operator.token,
+            operator.fixity,
+            operator.precedence,
+            operator.associativity,
+            operator.description
+        )
+        .unwrap();
+    }
+
+    table
+}
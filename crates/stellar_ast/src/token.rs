@@ -4,8 +4,6 @@ use derive_more::Display;
 use paste::paste;
 use stellar_filesystem::location::Location;
 
-use crate::precedence::Precedence;
-
 /// Represents error that scanning process can fail with.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Display)]
 pub enum RawLexError {
@@ -170,9 +168,10 @@ macro_rules! define_punctuators {
 }
 
 define_keywords! {
-    as, defer, else, enum, for, fun, if, pub, return,
-    struct, type, let, where, while, match, import, break,
-    continue, dyn, loop, interface, implements
+    as, const, defer, else, enum, for, in, fun, if, impl, pub, return,
+    static, mut, struct, type, let, where, while, match, import,
+    break, continue, dyn, loop, interface, implements, module, extern,
+    unsafe, async, await, gen, yield, try, move, ref
 }
 
 define_punctuators! {
@@ -224,6 +223,9 @@ define_punctuators! {
     /// Dot Dot (`..`).
     DoubleDot => "..",
 
+    /// Dot Dot Equal (`..=`).
+    DoubleDotEq => "..=",
+
     /// Equal (`=`).
     Eq => "=",
 
@@ -428,47 +430,6 @@ pub struct Token {
     pub raw: RawToken,
 }
 
-macro_rules! map_precedences {
-    { $($($punctuator:ident),* => $precedence:ident,)* } => {
-        impl From<Punctuator> for Precedence {
-            fn from(value: Punctuator) -> Self {
-                match value {
-                    $($(| Punctuator::$punctuator)* => Precedence::$precedence,)*
-                    _ => Precedence::Lowest
-                }
-            }
-        }
-    };
-}
-
-map_precedences! {
-    DoubleOr => DoubleOr,
-    DoubleAmpersand => DoubleAmpersand,
-    Or => Or,
-    Caret => Xor,
-    Eq, PlusEq, MinusEq, AsteriskEq, SlashEq, OrEq, CaretEq, PercentEq => Assign,
-    DoubleEq, BangEq, Less, LessEq, Greater, GreaterEq => Comparison,
-    LeftShift, RightShift => Shift,
-    OpenBracket => GenericArgument,
-    Plus, Minus => Sum,
-    Asterisk, Slash => Product,
-    Percent => Mod,
-    OpenParent => Call,
-    Dot => Field,
-    Tilde, DoublePlus, DoubleMinus, Bang, QuestionMark => Unastellar,
-    OpenBrace => Struct,
-}
-
-impl From<RawToken> for Precedence {
-    fn from(value: RawToken) -> Self {
-        match value {
-            RawToken::Punctuator(punctuator) => punctuator.into(),
-            RawToken::Keyword(Keyword::As) => Self::As,
-            _ => Self::Lowest,
-        }
-    }
-}
-
 impl From<Punctuator> for String {
     #[inline]
     fn from(punctuator: Punctuator) -> Self {
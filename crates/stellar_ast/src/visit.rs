@@ -14,14 +14,33 @@
 //!     }
 //! }
 //! ```
+//!
+//! Overriding a method replaces the default traversal of the node's children.
+//! To keep it, call the corresponding `walk_` function, e.g. [`walk_expression()`]
+//! after handling the node:
+//!
+//! ```
+//! use stellar_ast::{Expression, visit::{walk_expression, Visitor}};
+//!
+//! pub struct AllExpressionsCounter(usize);
+//!
+//! impl Visitor for AllExpressionsCounter {
+//!     fn visit_expression(&mut self, expression: &Expression) {
+//!         self.0 += 1;
+//!         walk_expression(self, expression);
+//!     }
+//! }
+//! ```
 
 use stellar_filesystem::location::Location;
 
 use crate::{
-    BinaryOperator, Enum, Expression, Function, GenericParameter, IdentifierAST, ImportPath,
-    Interface, LambdaFunctionParameter, Literal, MatchExpressionItem, Module, ModuleItem,
-    NegativeNumericLiteral, Path, Pattern, PostfixOperator, PrefixOperator, Statement, Struct,
-    StructField, StructFieldExpression, StructFieldPattern, TupleField, TupleLikeStruct, Type,
+    BinaryOperator, BindingMode, CallArgument, ConstValue, Constant, DeferBody, Enum, Expression,
+    ExternBlock, Function, FunctionParameter, FunctionSignature, GenericParameter, IdentifierAST,
+    Impl, ImportPath, Interface, LambdaCapture, LambdaFunctionParameter, ListElement, Literal,
+    MapEntry, MatchExpressionItem, Module, ModuleItem, NegativeNumericLiteral, Path, Pattern,
+    PostfixOperator, PrefixOperator, RangeKind, Statement, Static, Struct, StructField,
+    StructFieldExpression, StructFieldPattern, Submodule, TupleField, TupleLikeStruct, Type,
     TypeAlias, TypeConstructor, WherePredicate,
 };
 
@@ -39,15 +58,7 @@ pub trait Visitor {
 
     /// Visits a module item.
     fn visit_module_item(&mut self, module_item: &ModuleItem) {
-        match module_item {
-            ModuleItem::Enum(enum_) => self.visit_enum(enum_),
-            ModuleItem::Interface(interface) => self.visit_interface(interface),
-            ModuleItem::Function(function) => self.visit_function(function),
-            ModuleItem::Import { location, path } => self.visit_import(*location, path),
-            ModuleItem::Struct(struct_) => self.visit_struct(struct_),
-            ModuleItem::TupleLikeStruct(tl_struct) => self.visit_tuple_like_struct(tl_struct),
-            ModuleItem::TypeAlias(alias) => self.visit_type_alias(alias),
-        }
+        walk_module_item(self, module_item);
     }
 
     /// Visits an import.
@@ -58,6 +69,28 @@ pub trait Visitor {
     /// Visits an import path.
     fn visit_import_path(&mut self, path: &ImportPath) {}
 
+    /// Visits a constant.
+    fn visit_constant(&mut self, constant: &Constant) {
+        self.visit_type(&constant.ty);
+
+        if let Some(value) = &constant.value {
+            self.visit_expression(value);
+        }
+    }
+
+    /// Visits constants.
+    fn visit_constants(&mut self, constants: &[Constant]) {
+        for constant in constants {
+            self.visit_constant(constant);
+        }
+    }
+
+    /// Visits a static module item.
+    fn visit_static(&mut self, static_: &Static) {
+        self.visit_type(&static_.ty);
+        self.visit_expression(&static_.value);
+    }
+
     /// Visits an enum module item.
     fn visit_enum(&mut self, enum_: &Enum) {
         self.visit_generic_parameters(&enum_.generic_parameters);
@@ -70,6 +103,7 @@ pub trait Visitor {
     fn visit_interface(&mut self, interface: &Interface) {
         self.visit_generic_parameters(&interface.generic_parameters);
         self.visit_where_predicates(&interface.where_predicates);
+        self.visit_constants(&interface.constants);
         self.visit_methods(&interface.methods);
         self.visit_inherits(interface.inherits.as_deref());
     }
@@ -83,6 +117,26 @@ pub trait Visitor {
         self.visit_implements(struct_.implements.as_deref());
     }
 
+    /// Visits an implementation block.
+    fn visit_impl(&mut self, impl_: &Impl) {
+        self.visit_generic_parameters(&impl_.generic_parameters);
+
+        if let Some(interface) = &impl_.interface {
+            self.visit_type_constructor(interface);
+        }
+
+        self.visit_type(&impl_.ty);
+        self.visit_where_predicates(&impl_.where_predicates);
+        self.visit_methods(&impl_.methods);
+    }
+
+    /// Visits an extern block.
+    fn visit_extern_block(&mut self, block: &ExternBlock) {
+        for function in &block.functions {
+            self.visit_function_signature(function);
+        }
+    }
+
     /// Visits a tuple-like struct module item.
     fn visit_tuple_like_struct(&mut self, tl_struct: &TupleLikeStruct) {
         self.visit_generic_parameters(&tl_struct.generic_parameters);
@@ -92,6 +146,13 @@ pub trait Visitor {
         self.visit_implements(tl_struct.implements.as_deref());
     }
 
+    /// Visits a submodule module item, including items of an inline submodule.
+    fn visit_submodule(&mut self, submodule: &Submodule) {
+        for item in submodule.items.iter().flatten() {
+            self.visit_module_item(item);
+        }
+    }
+
     /// Visits a type alias module item.
     fn visit_type_alias(&mut self, alias: &TypeAlias) {
         self.visit_generic_parameters(&alias.generic_parameters);
@@ -131,6 +192,10 @@ pub trait Visitor {
 
     /// Visits a generic parameter.
     fn visit_generic_parameter(&mut self, generic_parameter: &GenericParameter) {
+        if let Some(const_type) = &generic_parameter.const_type {
+            self.visit_type(const_type);
+        }
+
         if let Some(default_value) = &generic_parameter.default_value {
             self.visit_type(default_value);
         }
@@ -155,11 +220,38 @@ pub trait Visitor {
 
     /// Visits a function.
     fn visit_function(&mut self, function: &Function) {
+        self.visit_function_signature(&function.signature);
+
         if let Some(body) = &function.body {
             self.visit_statements_block(body);
         }
     }
 
+    /// Visits a function signature.
+    fn visit_function_signature(&mut self, signature: &FunctionSignature) {
+        self.visit_generic_parameters(&signature.generic_parameters);
+
+        for parameter in &signature.parameters {
+            match parameter {
+                FunctionParameter::NotSelfParameter(parameter) => {
+                    self.visit_pattern(&parameter.pattern);
+                    self.visit_type(&parameter.ty);
+                }
+                FunctionParameter::SelfParameter(parameter) => {
+                    if let Some(ty) = &parameter.ty {
+                        self.visit_type(ty);
+                    }
+                }
+            }
+        }
+
+        if let Some(return_type) = &signature.return_type {
+            self.visit_type(return_type);
+        }
+
+        self.visit_where_predicates(&signature.where_predicates);
+    }
+
     /// Visits a method.
     fn visit_method(&mut self, method: &Function) {
         self.visit_function(method);
@@ -199,19 +291,7 @@ pub trait Visitor {
 
     /// Visits a statement.
     fn visit_statement(&mut self, statement: &Statement) {
-        match statement {
-            Statement::Break { location } => self.visit_break_statement(*location),
-            Statement::Continue { location } => self.visit_continue_statement(*location),
-            Statement::Defer { call } => self.visit_defer_expression(call),
-            Statement::Expression {
-                expression,
-                has_semicolon,
-            } => self.visit_expression_statement(expression, *has_semicolon),
-            Statement::Let { pattern, value, ty } => {
-                self.visit_let_statement(pattern, value, ty.as_ref());
-            }
-            Statement::Return { expression } => self.visit_return_statement(expression),
-        }
+        walk_statement(self, statement);
     }
 
     /// Visits a break statement.
@@ -220,8 +300,13 @@ pub trait Visitor {
     /// Visits a continue statement.
     fn visit_continue_statement(&mut self, location: Location) {}
 
-    /// Visits a defer expression.
-    fn visit_defer_expression(&mut self, call: &Expression) {}
+    /// Visits a defer statement.
+    fn visit_defer_statement(&mut self, body: &DeferBody) {
+        match body {
+            DeferBody::Call(call) => self.visit_expression(call),
+            DeferBody::Block { block, .. } => self.visit_statements_block(block),
+        }
+    }
 
     /// Visits an expression statement.
     fn visit_expression_statement(&mut self, expression: &Expression, has_semicolon: bool) {
@@ -243,52 +328,14 @@ pub trait Visitor {
         self.visit_expression(expression);
     }
 
+    /// Visits a yield statement.
+    fn visit_yield_statement(&mut self, location: Location, expression: &Expression) {
+        self.visit_expression(expression);
+    }
+
     /// Visits a pattern.
     fn visit_pattern(&mut self, pattern: &Pattern) {
-        match pattern {
-            Pattern::Grouped { location, inner } => self.visit_grouped_pattern(*location, inner),
-            Pattern::Identifier {
-                location,
-                identifier,
-                pattern,
-            } => self.visit_identifier_pattern(*location, *identifier, pattern),
-            Pattern::List {
-                location,
-                inner_patterns,
-            } => {
-                self.visit_list_pattern(*location, inner_patterns);
-            }
-            Pattern::Literal(literal) => self.visit_literal_pattern(literal),
-            Pattern::NegativeNumericLiteral(minus_number_literal) => {
-                self.visit_minus_number_literal_pattern(minus_number_literal);
-            }
-
-            Pattern::Or {
-                location,
-                left,
-                right,
-            } => self.visit_or_pattern(left, right),
-            Pattern::Path { path } => self.visit_path_pattern(path),
-            Pattern::Rest { location } => self.visit_rest_pattern(*location),
-            Pattern::Struct {
-                location,
-                path,
-                fields,
-            } => {
-                self.visit_struct_pattern(*location, path, fields);
-            }
-            Pattern::Tuple { location, elements } => {
-                self.visit_tuple_pattern(*location, elements);
-            }
-            Pattern::TupleLike {
-                location,
-                path,
-                inner_patterns,
-            } => {
-                self.visit_tuple_like_pattern(*location, path, inner_patterns);
-            }
-            Pattern::Wildcard { location } => self.visit_wildcard_pattern(*location),
-        }
+        walk_pattern(self, pattern);
     }
 
     /// Visits a grouped pattern.
@@ -300,6 +347,7 @@ pub trait Visitor {
     fn visit_identifier_pattern(
         &mut self,
         location: Location,
+        binding_mode: BindingMode,
         identifier: IdentifierAST,
         pattern: &Option<Box<Pattern>>,
     ) {
@@ -326,14 +374,27 @@ pub trait Visitor {
     }
 
     /// Visits an or pattern.
-    fn visit_or_pattern(&mut self, left: &Pattern, right: &Pattern) {
-        self.visit_pattern(left);
-        self.visit_pattern(right);
+    fn visit_or_pattern(&mut self, location: Location, alternatives: &[Pattern]) {
+        for pattern in alternatives {
+            self.visit_pattern(pattern);
+        }
     }
 
     /// Visits a path pattern.
     fn visit_path_pattern(&mut self, path: &Path) {}
 
+    /// Visits a range pattern.
+    fn visit_range_pattern(
+        &mut self,
+        location: Location,
+        start: &Pattern,
+        end: &Pattern,
+        kind: RangeKind,
+    ) {
+        self.visit_pattern(start);
+        self.visit_pattern(end);
+    }
+
     /// Visits a rest pattern.
     fn visit_rest_pattern(&mut self, location: Location) {}
 
@@ -372,27 +433,7 @@ pub trait Visitor {
 
     /// Visits a type.
     fn visit_type(&mut self, ty: &Type) {
-        match ty {
-            Type::Constructor(constructor) => self.visit_type_constructor(constructor),
-            Type::Function {
-                location,
-                parameter_types,
-                return_type,
-            } => self.visit_function_type(*location, parameter_types, return_type.as_deref()),
-            Type::InterfaceObject { location, bounds } => {
-                self.visit_interface_object_type(*location, bounds);
-            }
-            Type::Parenthesized { location, inner } => {
-                self.visit_parenthesized_type(*location, inner);
-            }
-            Type::Tuple {
-                location,
-                element_types,
-            } => {
-                self.visit_tuple_type(*location, element_types);
-            }
-            Type::Underscore { location } => self.visit_underscore_type(*location),
-        }
+        walk_type(self, ty);
     }
 
     /// Visits arguments in a type constructor.
@@ -404,7 +445,7 @@ pub trait Visitor {
 
     /// Visits a type constructor.
     fn visit_type_constructor(&mut self, constructor: &TypeConstructor) {
-        self.visit_type_arguments(&constructor.arguments);
+        walk_type_constructor(self, constructor);
     }
 
     /// Visits a function type.
@@ -450,117 +491,28 @@ pub trait Visitor {
     /// Visit an underscore type.
     fn visit_underscore_type(&mut self, location: Location) {}
 
+    /// Visits a const generic argument.
+    fn visit_const_argument(&mut self, location: Location, value: ConstValue) {}
+
+    /// Visits a reference type.
+    fn visit_reference_type(&mut self, location: Location, is_mutable: bool, inner: &Type) {
+        self.visit_type(inner);
+    }
+
+    /// Visits an optional type.
+    fn visit_optional_type(&mut self, location: Location, inner: &Type) {
+        self.visit_type(inner);
+    }
+
+    /// Visits an array type.
+    fn visit_array_type(&mut self, location: Location, element_type: &Type, size: &Type) {
+        self.visit_type(element_type);
+        self.visit_type(size);
+    }
+
     /// Visits an expression.
     fn visit_expression(&mut self, expression: &Expression) {
-        match expression {
-            Expression::As {
-                location,
-                left,
-                right,
-            } => {
-                self.visit_as_expression(*location, left, right);
-            }
-            Expression::Binary {
-                location,
-                left,
-                operator,
-                right,
-            } => {
-                self.visit_binary_expression(*location, left, *operator, right);
-            }
-            Expression::Call {
-                location,
-                callee,
-                arguments,
-            } => {
-                self.visit_call_expression(*location, callee, arguments);
-            }
-            Expression::FieldAccess {
-                location,
-                left,
-                right,
-            } => {
-                self.visit_field_access_expression(*location, left, *right);
-            }
-            Expression::Identifier(identifier) => self.visit_identifier_expression(*identifier),
-            Expression::List { location, elements } => {
-                self.visit_list_expression(*location, elements);
-            }
-            Expression::Literal(literal) => self.visit_literal_expression(literal),
-            Expression::If {
-                location,
-                if_blocks,
-                r#else,
-            } => {
-                self.visit_if_expression(*location, if_blocks, r#else.as_deref());
-            }
-            Expression::Lambda {
-                location,
-                parameters,
-                return_type,
-                value,
-            } => {
-                self.visit_lambda_expression(*location, parameters, return_type.as_ref(), value);
-            }
-            Expression::Loop {
-                location,
-                statements_block,
-            } => {
-                self.visit_loop_expression(*location, statements_block);
-            }
-            Expression::Match {
-                location,
-                expression,
-                block,
-            } => {
-                self.visit_match_expression(*location, expression, block);
-            }
-            Expression::Parenthesized { location, inner } => {
-                self.visit_parenthesized_expression(*location, inner);
-            }
-            Expression::Postfix {
-                location,
-                inner,
-                operator,
-            } => {
-                self.visit_postfix_expression(*location, inner, *operator);
-            }
-            Expression::Prefix {
-                location,
-                inner,
-                operator,
-            } => {
-                self.visit_prefix_expression(*location, inner, *operator);
-            }
-            Expression::StatementsBlock { location, block } => {
-                self.visit_statements_block_expression(*location, block);
-            }
-            Expression::Struct {
-                location,
-                left,
-                fields,
-            } => {
-                self.visit_struct_expression(*location, left, fields);
-            }
-            Expression::Tuple { location, elements } => {
-                self.visit_tuple_expression(*location, elements);
-            }
-            Expression::While {
-                location,
-                condition,
-                statements_block,
-            } => {
-                self.visit_while_expression(*location, condition, statements_block);
-            }
-            Expression::TypeArguments {
-                location,
-                left,
-                arguments,
-            } => {
-                self.visit_type_arguments_expression(*location, left, arguments);
-            }
-            Expression::Underscore { location } => self.visit_underscore_expression(*location),
-        }
+        walk_expression(self, expression);
     }
 
     /// Visits an as expression.
@@ -586,15 +538,20 @@ pub trait Visitor {
         &mut self,
         location: Location,
         callee: &Expression,
-        arguments: &[Expression],
+        arguments: &[CallArgument],
     ) {
         self.visit_expression(callee);
 
         for argument in arguments {
-            self.visit_expression(argument);
+            self.visit_call_argument(argument);
         }
     }
 
+    /// Visits a call argument.
+    fn visit_call_argument(&mut self, argument: &CallArgument) {
+        self.visit_expression(&argument.value);
+    }
+
     /// Visits a field access expression.
     fn visit_field_access_expression(
         &mut self,
@@ -605,16 +562,66 @@ pub trait Visitor {
         self.visit_expression(left);
     }
 
+    /// Visits a tuple index expression.
+    fn visit_tuple_index_expression(
+        &mut self,
+        location: Location,
+        left: &Expression,
+        index: usize,
+        index_location: Location,
+    ) {
+        self.visit_expression(left);
+    }
+
     /// Visits an identifier expression.
     fn visit_identifier_expression(&mut self, identifier: IdentifierAST) {}
 
     /// Visits a list expression.
-    fn visit_list_expression(&mut self, location: Location, elements: &[Expression]) {
+    fn visit_list_expression(&mut self, location: Location, elements: &[ListElement]) {
         for element in elements {
-            self.visit_expression(element);
+            self.visit_list_element(element);
         }
     }
 
+    /// Visits an element of a list expression.
+    fn visit_list_element(&mut self, element: &ListElement) {
+        self.visit_expression(&element.value);
+    }
+
+    /// Visits a borrow expression.
+    fn visit_borrow_expression(
+        &mut self,
+        location: Location,
+        is_mutable: bool,
+        inner: &Expression,
+    ) {
+        self.visit_expression(inner);
+    }
+
+    /// Visits a map expression.
+    fn visit_map_expression(&mut self, location: Location, entries: &[MapEntry]) {
+        for entry in entries {
+            self.visit_map_entry(entry);
+        }
+    }
+
+    /// Visits an entry of a map expression.
+    fn visit_map_entry(&mut self, entry: &MapEntry) {
+        self.visit_expression(&entry.key);
+        self.visit_expression(&entry.value);
+    }
+
+    /// Visits an array repeat expression.
+    fn visit_array_repeat_expression(
+        &mut self,
+        location: Location,
+        value: &Expression,
+        size: &Expression,
+    ) {
+        self.visit_expression(value);
+        self.visit_expression(size);
+    }
+
     /// Visits a literal expression.
     fn visit_literal_expression(&mut self, literal: &Literal) {}
 
@@ -635,14 +642,37 @@ pub trait Visitor {
         }
     }
 
+    /// Visits an if let expression.
+    fn visit_if_let_expression(
+        &mut self,
+        location: Location,
+        pattern: &Pattern,
+        expression: &Expression,
+        block: &[Statement],
+        r#else: Option<&[Statement]>,
+    ) {
+        self.visit_pattern(pattern);
+        self.visit_expression(expression);
+        self.visit_statements_block(block);
+
+        if let Some(r#else) = r#else {
+            self.visit_statements_block(r#else);
+        }
+    }
+
     /// Visits a lambda expression.
     fn visit_lambda_expression(
         &mut self,
         location: Location,
+        captures: &[LambdaCapture],
         parameters: &[LambdaFunctionParameter],
         return_type: Option<&Type>,
         value: &Expression,
     ) {
+        for capture in captures {
+            self.visit_lambda_capture(capture);
+        }
+
         for parameter in parameters {
             self.visit_lambda_function_parameter(parameter);
         }
@@ -657,6 +687,9 @@ pub trait Visitor {
     /// Visits an underscore expression.
     fn visit_underscore_expression(&mut self, location: Location) {}
 
+    /// Visits an explicit capture of a lambda.
+    fn visit_lambda_capture(&mut self, capture: &LambdaCapture) {}
+
     /// Visits a lambda function parameter.
     fn visit_lambda_function_parameter(&mut self, parameter: &LambdaFunctionParameter) {
         if let Some(ty) = &parameter.ty {
@@ -714,20 +747,57 @@ pub trait Visitor {
         self.visit_expression(inner);
     }
 
+    /// Visits a range expression.
+    fn visit_range_expression(
+        &mut self,
+        location: Location,
+        start: Option<&Expression>,
+        end: Option<&Expression>,
+        kind: RangeKind,
+    ) {
+        if let Some(start) = start {
+            self.visit_expression(start);
+        }
+
+        if let Some(end) = end {
+            self.visit_expression(end);
+        }
+    }
+
     /// Visits a statements block expression.
     fn visit_statements_block_expression(&mut self, location: Location, block: &[Statement]) {
         self.visit_statements_block(block);
     }
 
+    /// Visits an await expression.
+    fn visit_await_expression(&mut self, location: Location, inner: &Expression) {
+        self.visit_expression(inner);
+    }
+
+    /// Visits an unsafe block expression.
+    fn visit_unsafe_expression(&mut self, location: Location, block: &[Statement]) {
+        self.visit_statements_block(block);
+    }
+
+    /// Visits a try block expression.
+    fn visit_try_expression(&mut self, location: Location, block: &[Statement]) {
+        self.visit_statements_block(block);
+    }
+
     /// Visits a struct expression.
     fn visit_struct_expression(
         &mut self,
         location: Location,
         left: &Expression,
         fields: &[StructFieldExpression],
+        rest: Option<&Expression>,
     ) {
         self.visit_expression(left);
         self.visit_struct_field_expressions(fields);
+
+        if let Some(rest) = rest {
+            self.visit_expression(rest);
+        }
     }
 
     /// Visits struct field expressions.
@@ -763,6 +833,32 @@ pub trait Visitor {
         self.visit_statements_block(statements_block);
     }
 
+    /// Visits a while let expression.
+    fn visit_while_let_expression(
+        &mut self,
+        location: Location,
+        pattern: &Pattern,
+        expression: &Expression,
+        statements_block: &[Statement],
+    ) {
+        self.visit_pattern(pattern);
+        self.visit_expression(expression);
+        self.visit_statements_block(statements_block);
+    }
+
+    /// Visits a for expression.
+    fn visit_for_expression(
+        &mut self,
+        location: Location,
+        pattern: &Pattern,
+        iterable: &Expression,
+        statements_block: &[Statement],
+    ) {
+        self.visit_pattern(pattern);
+        self.visit_expression(iterable);
+        self.visit_statements_block(statements_block);
+    }
+
     /// Visits type arguments expression.
     fn visit_type_arguments_expression(
         &mut self,
@@ -774,3 +870,366 @@ pub trait Visitor {
         self.visit_type_arguments(arguments);
     }
 }
+
+/// Walks a module item, visiting all of its children.
+pub fn walk_module_item<V: Visitor + ?Sized>(visitor: &mut V, module_item: &ModuleItem) {
+    match module_item {
+        ModuleItem::Constant(constant) => visitor.visit_constant(constant),
+        ModuleItem::Enum(enum_) => visitor.visit_enum(enum_),
+        ModuleItem::Interface(interface) => visitor.visit_interface(interface),
+        ModuleItem::Static(static_) => visitor.visit_static(static_),
+        ModuleItem::Function(function) => visitor.visit_function(function),
+        ModuleItem::Impl(impl_) => visitor.visit_impl(impl_),
+        ModuleItem::Extern(block) => visitor.visit_extern_block(block),
+        ModuleItem::Import { location, path, .. } => visitor.visit_import(*location, path),
+        ModuleItem::Module(submodule) => visitor.visit_submodule(submodule),
+        ModuleItem::Struct(struct_) => visitor.visit_struct(struct_),
+        ModuleItem::TupleLikeStruct(tl_struct) => visitor.visit_tuple_like_struct(tl_struct),
+        ModuleItem::TypeAlias(alias) => visitor.visit_type_alias(alias),
+    }
+}
+
+/// Walks a statement, visiting all of its children.
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match statement {
+        Statement::Break { location, .. } => visitor.visit_break_statement(*location),
+        Statement::Continue { location, .. } => visitor.visit_continue_statement(*location),
+        Statement::Defer { body, .. } => visitor.visit_defer_statement(body),
+        Statement::Expression {
+            expression,
+            has_semicolon,
+            ..
+        } => visitor.visit_expression_statement(expression, *has_semicolon),
+        Statement::Let {
+            pattern, value, ty, ..
+        } => {
+            visitor.visit_let_statement(pattern, value, ty.as_ref());
+        }
+        Statement::Return { expression, .. } => visitor.visit_return_statement(expression),
+        Statement::Yield {
+            location,
+            expression,
+            ..
+        } => visitor.visit_yield_statement(*location, expression),
+    }
+}
+
+/// Walks a pattern, visiting all of its children.
+pub fn walk_pattern<V: Visitor + ?Sized>(visitor: &mut V, pattern: &Pattern) {
+    match pattern {
+        Pattern::Grouped { location, inner } => visitor.visit_grouped_pattern(*location, inner),
+        Pattern::Identifier {
+            location,
+            binding_mode,
+            identifier,
+            pattern,
+        } => visitor.visit_identifier_pattern(*location, *binding_mode, *identifier, pattern),
+        Pattern::List {
+            location,
+            inner_patterns,
+        } => {
+            visitor.visit_list_pattern(*location, inner_patterns);
+        }
+        Pattern::Literal(literal) => visitor.visit_literal_pattern(literal),
+        Pattern::NegativeNumericLiteral(minus_number_literal) => {
+            visitor.visit_minus_number_literal_pattern(minus_number_literal);
+        }
+
+        Pattern::Or {
+            location,
+            alternatives,
+        } => visitor.visit_or_pattern(*location, alternatives),
+        Pattern::Path { path } => visitor.visit_path_pattern(path),
+        Pattern::Range {
+            location,
+            start,
+            end,
+            kind,
+        } => visitor.visit_range_pattern(*location, start, end, *kind),
+        Pattern::Rest { location } => visitor.visit_rest_pattern(*location),
+        Pattern::Struct {
+            location,
+            path,
+            fields,
+        } => {
+            visitor.visit_struct_pattern(*location, path, fields);
+        }
+        Pattern::Tuple { location, elements } => {
+            visitor.visit_tuple_pattern(*location, elements);
+        }
+        Pattern::TupleLike {
+            location,
+            path,
+            inner_patterns,
+        } => {
+            visitor.visit_tuple_like_pattern(*location, path, inner_patterns);
+        }
+        Pattern::Wildcard { location } => visitor.visit_wildcard_pattern(*location),
+    }
+}
+
+/// Walks a type, visiting all of its children.
+pub fn walk_type<V: Visitor + ?Sized>(visitor: &mut V, ty: &Type) {
+    match ty {
+        Type::Constructor(constructor) => visitor.visit_type_constructor(constructor),
+        Type::Function {
+            location,
+            parameter_types,
+            return_type,
+        } => visitor.visit_function_type(*location, parameter_types, return_type.as_deref()),
+        Type::InterfaceObject { location, bounds } => {
+            visitor.visit_interface_object_type(*location, bounds);
+        }
+        Type::Parenthesized { location, inner } => {
+            visitor.visit_parenthesized_type(*location, inner);
+        }
+        Type::Tuple {
+            location,
+            element_types,
+        } => {
+            visitor.visit_tuple_type(*location, element_types);
+        }
+        Type::Underscore { location } => visitor.visit_underscore_type(*location),
+        Type::Const { location, value } => visitor.visit_const_argument(*location, *value),
+        Type::Array {
+            location,
+            element_type,
+            size,
+        } => visitor.visit_array_type(*location, element_type, size),
+        Type::Reference {
+            location,
+            is_mutable,
+            inner,
+        } => visitor.visit_reference_type(*location, *is_mutable, inner),
+        Type::Optional { location, inner } => visitor.visit_optional_type(*location, inner),
+    }
+}
+
+/// Walks a type constructor, visiting all of its children.
+pub fn walk_type_constructor<V: Visitor + ?Sized>(visitor: &mut V, constructor: &TypeConstructor) {
+    visitor.visit_type_arguments(&constructor.arguments);
+}
+
+/// Walks an expression, visiting all of its children.
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::As {
+            location,
+            left,
+            right,
+            ..
+        } => {
+            visitor.visit_as_expression(*location, left, right);
+        }
+        Expression::Binary {
+            location,
+            left,
+            operator,
+            right,
+            ..
+        } => {
+            visitor.visit_binary_expression(*location, left, *operator, right);
+        }
+        Expression::Range {
+            location,
+            start,
+            end,
+            kind,
+            ..
+        } => {
+            visitor.visit_range_expression(*location, start.as_deref(), end.as_deref(), *kind);
+        }
+        Expression::Call {
+            location,
+            callee,
+            arguments,
+            ..
+        } => {
+            visitor.visit_call_expression(*location, callee, arguments);
+        }
+        Expression::FieldAccess {
+            location,
+            left,
+            right,
+            ..
+        } => {
+            visitor.visit_field_access_expression(*location, left, *right);
+        }
+        Expression::TupleIndex {
+            location,
+            left,
+            index,
+            index_location,
+            ..
+        } => visitor.visit_tuple_index_expression(*location, left, *index, *index_location),
+        Expression::Identifier { identifier, .. } => {
+            visitor.visit_identifier_expression(*identifier);
+        }
+        Expression::List {
+            location, elements, ..
+        } => {
+            visitor.visit_list_expression(*location, elements);
+        }
+        Expression::ArrayRepeat {
+            location,
+            value,
+            size,
+            ..
+        } => visitor.visit_array_repeat_expression(*location, value, size),
+        Expression::Map {
+            location, entries, ..
+        } => visitor.visit_map_expression(*location, entries),
+        Expression::Borrow {
+            location,
+            is_mutable,
+            inner,
+            ..
+        } => visitor.visit_borrow_expression(*location, *is_mutable, inner),
+        Expression::Literal { literal, .. } => visitor.visit_literal_expression(literal),
+        Expression::If {
+            location,
+            if_blocks,
+            r#else,
+            ..
+        } => {
+            visitor.visit_if_expression(*location, if_blocks, r#else.as_deref());
+        }
+        Expression::IfLet {
+            location,
+            pattern,
+            expression,
+            block,
+            r#else,
+            ..
+        } => {
+            visitor.visit_if_let_expression(
+                *location,
+                pattern,
+                expression,
+                block,
+                r#else.as_deref(),
+            );
+        }
+        Expression::Lambda {
+            location,
+            captures,
+            parameters,
+            return_type,
+            value,
+            ..
+        } => {
+            visitor.visit_lambda_expression(
+                *location,
+                captures,
+                parameters,
+                return_type.as_ref(),
+                value,
+            );
+        }
+        Expression::Loop {
+            location,
+            statements_block,
+            ..
+        } => {
+            visitor.visit_loop_expression(*location, statements_block);
+        }
+        Expression::Match {
+            location,
+            expression,
+            block,
+            ..
+        } => {
+            visitor.visit_match_expression(*location, expression, block);
+        }
+        Expression::Parenthesized {
+            location, inner, ..
+        } => {
+            visitor.visit_parenthesized_expression(*location, inner);
+        }
+        Expression::Postfix {
+            location,
+            inner,
+            operator,
+            ..
+        } => {
+            visitor.visit_postfix_expression(*location, inner, *operator);
+        }
+        Expression::Prefix {
+            location,
+            inner,
+            operator,
+            ..
+        } => {
+            visitor.visit_prefix_expression(*location, inner, *operator);
+        }
+        Expression::StatementsBlock {
+            location, block, ..
+        } => {
+            visitor.visit_statements_block_expression(*location, block);
+        }
+        Expression::Await {
+            location, inner, ..
+        } => {
+            visitor.visit_await_expression(*location, inner);
+        }
+        Expression::Unsafe {
+            location, block, ..
+        } => {
+            visitor.visit_unsafe_expression(*location, block);
+        }
+        Expression::Try {
+            location, block, ..
+        } => {
+            visitor.visit_try_expression(*location, block);
+        }
+        Expression::Struct {
+            location,
+            left,
+            fields,
+            rest,
+            ..
+        } => {
+            visitor.visit_struct_expression(*location, left, fields, rest.as_deref());
+        }
+        Expression::Tuple {
+            location, elements, ..
+        } => {
+            visitor.visit_tuple_expression(*location, elements);
+        }
+        Expression::While {
+            location,
+            condition,
+            statements_block,
+            ..
+        } => {
+            visitor.visit_while_expression(*location, condition, statements_block);
+        }
+        Expression::WhileLet {
+            location,
+            pattern,
+            expression,
+            statements_block,
+            ..
+        } => {
+            visitor.visit_while_let_expression(*location, pattern, expression, statements_block);
+        }
+        Expression::For {
+            location,
+            pattern,
+            iterable,
+            statements_block,
+            ..
+        } => {
+            visitor.visit_for_expression(*location, pattern, iterable, statements_block);
+        }
+        Expression::TypeArguments {
+            location,
+            left,
+            arguments,
+            ..
+        } => {
+            visitor.visit_type_arguments_expression(*location, left, arguments);
+        }
+        Expression::Underscore { location, .. } => visitor.visit_underscore_expression(*location),
+    }
+}
@@ -0,0 +1,646 @@
+//! Provides a [`VisitorMut`] trait to traverse and rewrite AST in place.
+//!
+//! [`VisitorMut`] mirrors [`Visitor`](crate::visit::Visitor), but every method
+//! receives a mutable reference to the node, so that it can be modified
+//! without reconstructing the rest of the tree:
+//!
+//! ```
+//! use stellar_ast::{Expression, visit_mut::{walk_expression_mut, VisitorMut}};
+//! use stellar_interner::IdentifierId;
+//!
+//! pub struct IdentifierRenamer {
+//!     from: IdentifierId,
+//!     to: IdentifierId,
+//! }
+//!
+//! impl VisitorMut for IdentifierRenamer {
+//!     fn visit_expression_mut(&mut self, expression: &mut Expression) {
+//!         if let Expression::Identifier { identifier, .. } = expression {
+//!             if identifier.id == self.from {
+//!                 identifier.id = self.to;
+//!             }
+//!         }
+//!
+//!         walk_expression_mut(self, expression);
+//!     }
+//! }
+//! ```
+//!
+//! A node can also be replaced by value. To take ownership of a child, swap
+//! it out with [`std::mem::replace`] first, e.g. to unwrap parenthesized
+//! expressions:
+//!
+//! ```
+//! use stellar_ast::{Expression, NodeId, DUMMY_NODE_ID, visit_mut::{walk_expression_mut, VisitorMut}};
+//! use stellar_filesystem::location::DUMMY_LOCATION;
+//!
+//! pub struct ParenthesesRemover;
+//!
+//! impl VisitorMut for ParenthesesRemover {
+//!     fn visit_expression_mut(&mut self, expression: &mut Expression) {
+//!         walk_expression_mut(self, expression);
+//!
+//!         if let Expression::Parenthesized { inner, .. } = expression {
+//!             let placeholder = Expression::Underscore {
+//!                 node_id: DUMMY_NODE_ID,
+//!                 location: DUMMY_LOCATION,
+//!             };
+//!             *expression = std::mem::replace(&mut **inner, placeholder);
+//!         }
+//!     }
+//! }
+//!
+//! let mut expression = Expression::Parenthesized {
+//!     node_id: NodeId(2),
+//!     location: DUMMY_LOCATION,
+//!     inner: Box::new(Expression::Parenthesized {
+//!         node_id: NodeId(1),
+//!         location: DUMMY_LOCATION,
+//!         inner: Box::new(Expression::Underscore {
+//!             node_id: NodeId(0),
+//!             location: DUMMY_LOCATION,
+//!         }),
+//!     }),
+//! };
+//!
+//! ParenthesesRemover.visit_expression_mut(&mut expression);
+//!
+//! assert_eq!(expression.node_id(), NodeId(0));
+//! ```
+//!
+//! As with [`Visitor`](crate::visit::Visitor), overriding a method replaces
+//! the default traversal of the node's children. To keep it, call the
+//! corresponding `walk_*_mut` function.
+
+use crate::{
+    Attribute, DeferBody, EnumItem, Expression, Function, FunctionParameter, FunctionSignature,
+    GenericParameter, Module, ModuleItem, Pattern, Statement, StructField, StructFieldPattern,
+    TupleField, Type, TypeConstructor, WherePredicate,
+};
+
+/// Allows to traverse and modify AST in place.
+///
+/// See [module level docs](crate::visit_mut) for more details.
+pub trait VisitorMut {
+    /// Visits a module.
+    fn visit_module_mut(&mut self, module: &mut Module) {
+        walk_module_mut(self, module);
+    }
+
+    /// Visits a module item.
+    fn visit_module_item_mut(&mut self, module_item: &mut ModuleItem) {
+        walk_module_item_mut(self, module_item);
+    }
+
+    /// Visits a function or a method.
+    fn visit_function_mut(&mut self, function: &mut Function) {
+        walk_function_mut(self, function);
+    }
+
+    /// Visits a function signature.
+    fn visit_function_signature_mut(&mut self, signature: &mut FunctionSignature) {
+        walk_function_signature_mut(self, signature);
+    }
+
+    /// Visits a block of statements.
+    fn visit_statements_block_mut(&mut self, statements: &mut Vec<Statement>) {
+        for statement in statements {
+            self.visit_statement_mut(statement);
+        }
+    }
+
+    /// Visits a statement.
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement);
+    }
+
+    /// Visits an expression.
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression);
+    }
+
+    /// Visits a pattern.
+    fn visit_pattern_mut(&mut self, pattern: &mut Pattern) {
+        walk_pattern_mut(self, pattern);
+    }
+
+    /// Visits a type.
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        walk_type_mut(self, ty);
+    }
+
+    /// Visits a type constructor.
+    fn visit_type_constructor_mut(&mut self, constructor: &mut TypeConstructor) {
+        walk_type_constructor_mut(self, constructor);
+    }
+}
+
+/// Walks a module, visiting all of its items.
+pub fn walk_module_mut<V: VisitorMut + ?Sized>(visitor: &mut V, module: &mut Module) {
+    for item in &mut module.items {
+        visitor.visit_module_item_mut(item);
+    }
+}
+
+/// Walks a module item, visiting all of its children.
+pub fn walk_module_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, module_item: &mut ModuleItem) {
+    match module_item {
+        ModuleItem::Constant(constant) => {
+            walk_attributes_mut(visitor, &mut constant.attributes);
+            visitor.visit_type_mut(&mut constant.ty);
+
+            if let Some(value) = &mut constant.value {
+                visitor.visit_expression_mut(value);
+            }
+        }
+        ModuleItem::Enum(enum_) => {
+            walk_attributes_mut(visitor, &mut enum_.attributes);
+            walk_generic_parameters_mut(visitor, &mut enum_.generic_parameters);
+            walk_where_predicates_mut(visitor, &mut enum_.where_predicates);
+
+            for item in &mut enum_.items {
+                match item {
+                    EnumItem::Just { .. } => {}
+                    EnumItem::TupleLike { fields, .. } => walk_tuple_fields_mut(visitor, fields),
+                    EnumItem::Struct { fields, .. } => walk_struct_fields_mut(visitor, fields),
+                }
+            }
+
+            walk_methods_mut(visitor, &mut enum_.methods);
+            walk_bounds_mut(visitor, enum_.implements.as_deref_mut());
+        }
+        ModuleItem::Extern(block) => {
+            walk_attributes_mut(visitor, &mut block.attributes);
+
+            for signature in &mut block.functions {
+                visitor.visit_function_signature_mut(signature);
+            }
+        }
+        ModuleItem::Function(function) => visitor.visit_function_mut(function),
+        ModuleItem::Impl(impl_) => {
+            walk_attributes_mut(visitor, &mut impl_.attributes);
+            walk_generic_parameters_mut(visitor, &mut impl_.generic_parameters);
+
+            if let Some(interface) = &mut impl_.interface {
+                visitor.visit_type_constructor_mut(interface);
+            }
+
+            visitor.visit_type_mut(&mut impl_.ty);
+            walk_where_predicates_mut(visitor, &mut impl_.where_predicates);
+            walk_methods_mut(visitor, &mut impl_.methods);
+        }
+        ModuleItem::Import { attributes, .. } => walk_attributes_mut(visitor, attributes),
+        ModuleItem::Interface(interface) => {
+            walk_attributes_mut(visitor, &mut interface.attributes);
+            walk_generic_parameters_mut(visitor, &mut interface.generic_parameters);
+            walk_where_predicates_mut(visitor, &mut interface.where_predicates);
+
+            for constant in &mut interface.constants {
+                walk_attributes_mut(visitor, &mut constant.attributes);
+                visitor.visit_type_mut(&mut constant.ty);
+
+                if let Some(value) = &mut constant.value {
+                    visitor.visit_expression_mut(value);
+                }
+            }
+
+            walk_methods_mut(visitor, &mut interface.methods);
+            walk_bounds_mut(visitor, interface.inherits.as_deref_mut());
+        }
+        ModuleItem::Module(submodule) => {
+            walk_attributes_mut(visitor, &mut submodule.attributes);
+
+            if let Some(items) = &mut submodule.items {
+                for item in items {
+                    visitor.visit_module_item_mut(item);
+                }
+            }
+        }
+        ModuleItem::Static(static_) => {
+            walk_attributes_mut(visitor, &mut static_.attributes);
+            visitor.visit_type_mut(&mut static_.ty);
+            visitor.visit_expression_mut(&mut static_.value);
+        }
+        ModuleItem::Struct(struct_) => {
+            walk_attributes_mut(visitor, &mut struct_.attributes);
+            walk_generic_parameters_mut(visitor, &mut struct_.generic_parameters);
+            walk_where_predicates_mut(visitor, &mut struct_.where_predicates);
+            walk_struct_fields_mut(visitor, &mut struct_.fields);
+            walk_methods_mut(visitor, &mut struct_.methods);
+            walk_bounds_mut(visitor, struct_.implements.as_deref_mut());
+        }
+        ModuleItem::TupleLikeStruct(struct_) => {
+            walk_attributes_mut(visitor, &mut struct_.attributes);
+            walk_generic_parameters_mut(visitor, &mut struct_.generic_parameters);
+            walk_where_predicates_mut(visitor, &mut struct_.where_predicates);
+            walk_tuple_fields_mut(visitor, &mut struct_.fields);
+            walk_methods_mut(visitor, &mut struct_.methods);
+            walk_bounds_mut(visitor, struct_.implements.as_deref_mut());
+        }
+        ModuleItem::TypeAlias(alias) => {
+            walk_attributes_mut(visitor, &mut alias.attributes);
+            walk_generic_parameters_mut(visitor, &mut alias.generic_parameters);
+            visitor.visit_type_mut(&mut alias.value);
+        }
+    }
+}
+
+/// Walks a function, visiting its signature and body.
+pub fn walk_function_mut<V: VisitorMut + ?Sized>(visitor: &mut V, function: &mut Function) {
+    visitor.visit_function_signature_mut(&mut function.signature);
+
+    if let Some(body) = &mut function.body {
+        visitor.visit_statements_block_mut(body);
+    }
+}
+
+/// Walks a function signature, visiting all of its children.
+pub fn walk_function_signature_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    signature: &mut FunctionSignature,
+) {
+    walk_attributes_mut(visitor, &mut signature.attributes);
+    walk_generic_parameters_mut(visitor, &mut signature.generic_parameters);
+
+    for parameter in &mut signature.parameters {
+        match parameter {
+            FunctionParameter::NotSelfParameter(parameter) => {
+                visitor.visit_pattern_mut(&mut parameter.pattern);
+                visitor.visit_type_mut(&mut parameter.ty);
+            }
+            FunctionParameter::SelfParameter(parameter) => {
+                if let Some(ty) = &mut parameter.ty {
+                    visitor.visit_type_mut(ty);
+                }
+            }
+        }
+    }
+
+    if let Some(return_type) = &mut signature.return_type {
+        visitor.visit_type_mut(return_type);
+    }
+
+    walk_where_predicates_mut(visitor, &mut signature.where_predicates);
+}
+
+/// Walks a statement, visiting all of its children.
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match statement {
+        Statement::Break { .. } | Statement::Continue { .. } => {}
+        Statement::Defer { body, .. } => match body {
+            DeferBody::Call(call) => visitor.visit_expression_mut(call),
+            DeferBody::Block { block, .. } => visitor.visit_statements_block_mut(block),
+        },
+        Statement::Expression { expression, .. }
+        | Statement::Return { expression, .. }
+        | Statement::Yield { expression, .. } => visitor.visit_expression_mut(expression),
+        Statement::Let {
+            pattern, value, ty, ..
+        } => {
+            visitor.visit_pattern_mut(pattern);
+            visitor.visit_expression_mut(value);
+
+            if let Some(ty) = ty {
+                visitor.visit_type_mut(ty);
+            }
+        }
+    }
+}
+
+/// Walks an expression, visiting all of its children.
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+    match expression {
+        Expression::Literal { .. }
+        | Expression::Identifier { .. }
+        | Expression::Underscore { .. } => {}
+        Expression::List { elements, .. } => {
+            for element in elements {
+                visitor.visit_expression_mut(&mut element.value);
+            }
+        }
+        Expression::ArrayRepeat { value, size, .. } => {
+            visitor.visit_expression_mut(value);
+            visitor.visit_expression_mut(size);
+        }
+        Expression::Borrow { inner, .. }
+        | Expression::Await { inner, .. }
+        | Expression::Parenthesized { inner, .. }
+        | Expression::Prefix { inner, .. }
+        | Expression::Postfix { inner, .. }
+        | Expression::FieldAccess { left: inner, .. }
+        | Expression::TupleIndex { left: inner, .. } => visitor.visit_expression_mut(inner),
+        Expression::Map { entries, .. } => {
+            for entry in entries {
+                visitor.visit_expression_mut(&mut entry.key);
+                visitor.visit_expression_mut(&mut entry.value);
+            }
+        }
+        Expression::As { left, right, .. } => {
+            visitor.visit_expression_mut(left);
+            visitor.visit_type_mut(right);
+        }
+        Expression::Loop {
+            statements_block: block,
+            ..
+        }
+        | Expression::StatementsBlock { block, .. }
+        | Expression::Unsafe { block, .. }
+        | Expression::Try { block, .. } => visitor.visit_statements_block_mut(block),
+        Expression::Binary { left, right, .. } => {
+            visitor.visit_expression_mut(left);
+            visitor.visit_expression_mut(right);
+        }
+        Expression::Range { start, end, .. } => {
+            if let Some(start) = start {
+                visitor.visit_expression_mut(start);
+            }
+
+            if let Some(end) = end {
+                visitor.visit_expression_mut(end);
+            }
+        }
+        Expression::If {
+            if_blocks, r#else, ..
+        } => {
+            for (condition, block) in if_blocks {
+                visitor.visit_expression_mut(condition);
+                visitor.visit_statements_block_mut(block);
+            }
+
+            if let Some(r#else) = r#else {
+                visitor.visit_statements_block_mut(r#else);
+            }
+        }
+        Expression::IfLet {
+            pattern,
+            expression,
+            block,
+            r#else,
+            ..
+        } => {
+            visitor.visit_pattern_mut(pattern);
+            visitor.visit_expression_mut(expression);
+            visitor.visit_statements_block_mut(block);
+
+            if let Some(r#else) = r#else {
+                visitor.visit_statements_block_mut(r#else);
+            }
+        }
+        Expression::While {
+            condition,
+            statements_block,
+            ..
+        } => {
+            visitor.visit_expression_mut(condition);
+            visitor.visit_statements_block_mut(statements_block);
+        }
+        Expression::WhileLet {
+            pattern,
+            expression,
+            statements_block,
+            ..
+        } => {
+            visitor.visit_pattern_mut(pattern);
+            visitor.visit_expression_mut(expression);
+            visitor.visit_statements_block_mut(statements_block);
+        }
+        Expression::For {
+            pattern,
+            iterable,
+            statements_block,
+            ..
+        } => {
+            visitor.visit_pattern_mut(pattern);
+            visitor.visit_expression_mut(iterable);
+            visitor.visit_statements_block_mut(statements_block);
+        }
+        Expression::Call {
+            callee, arguments, ..
+        } => {
+            visitor.visit_expression_mut(callee);
+
+            for argument in arguments {
+                visitor.visit_expression_mut(&mut argument.value);
+            }
+        }
+        Expression::TypeArguments {
+            left, arguments, ..
+        } => {
+            visitor.visit_expression_mut(left);
+
+            for argument in arguments {
+                visitor.visit_type_mut(argument);
+            }
+        }
+        Expression::Tuple { elements, .. } => {
+            for element in elements {
+                visitor.visit_expression_mut(element);
+            }
+        }
+        Expression::Struct {
+            left, fields, rest, ..
+        } => {
+            visitor.visit_expression_mut(left);
+
+            for field in fields {
+                if let Some(value) = &mut field.value {
+                    visitor.visit_expression_mut(value);
+                }
+            }
+
+            if let Some(rest) = rest {
+                visitor.visit_expression_mut(rest);
+            }
+        }
+        Expression::Match {
+            expression, block, ..
+        } => {
+            visitor.visit_expression_mut(expression);
+
+            for item in block {
+                visitor.visit_pattern_mut(&mut item.left);
+                visitor.visit_expression_mut(&mut item.right);
+            }
+        }
+        Expression::Lambda {
+            parameters,
+            return_type,
+            value,
+            ..
+        } => {
+            for parameter in parameters {
+                if let Some(ty) = &mut parameter.ty {
+                    visitor.visit_type_mut(ty);
+                }
+            }
+
+            if let Some(return_type) = return_type {
+                visitor.visit_type_mut(return_type);
+            }
+
+            visitor.visit_expression_mut(value);
+        }
+    }
+}
+
+/// Walks a pattern, visiting all of its children.
+pub fn walk_pattern_mut<V: VisitorMut + ?Sized>(visitor: &mut V, pattern: &mut Pattern) {
+    match pattern {
+        Pattern::Literal(..)
+        | Pattern::NegativeNumericLiteral(..)
+        | Pattern::Wildcard { .. }
+        | Pattern::Path { .. }
+        | Pattern::Rest { .. } => {}
+        Pattern::Identifier { pattern, .. } => {
+            if let Some(pattern) = pattern {
+                visitor.visit_pattern_mut(pattern);
+            }
+        }
+        Pattern::Struct { fields, .. } => {
+            for field in fields {
+                if let StructFieldPattern::NotRest {
+                    value_pattern: Some(value_pattern),
+                    ..
+                } = field
+                {
+                    visitor.visit_pattern_mut(value_pattern);
+                }
+            }
+        }
+        Pattern::TupleLike {
+            inner_patterns: patterns,
+            ..
+        }
+        | Pattern::Tuple {
+            elements: patterns, ..
+        }
+        | Pattern::List {
+            inner_patterns: patterns,
+            ..
+        }
+        | Pattern::Or {
+            alternatives: patterns,
+            ..
+        } => {
+            for pattern in patterns {
+                visitor.visit_pattern_mut(pattern);
+            }
+        }
+        Pattern::Grouped { inner, .. } => visitor.visit_pattern_mut(inner),
+        Pattern::Range { start, end, .. } => {
+            visitor.visit_pattern_mut(start);
+            visitor.visit_pattern_mut(end);
+        }
+    }
+}
+
+/// Walks a type, visiting all of its children.
+pub fn walk_type_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ty: &mut Type) {
+    match ty {
+        Type::Underscore { .. } | Type::Const { .. } => {}
+        Type::Constructor(constructor) => visitor.visit_type_constructor_mut(constructor),
+        Type::Tuple { element_types, .. } => {
+            for element_type in element_types {
+                visitor.visit_type_mut(element_type);
+            }
+        }
+        Type::Function {
+            parameter_types,
+            return_type,
+            ..
+        } => {
+            for parameter_type in parameter_types {
+                visitor.visit_type_mut(parameter_type);
+            }
+
+            if let Some(return_type) = return_type {
+                visitor.visit_type_mut(return_type);
+            }
+        }
+        Type::Parenthesized { inner, .. }
+        | Type::Reference { inner, .. }
+        | Type::Optional { inner, .. } => visitor.visit_type_mut(inner),
+        Type::InterfaceObject { bounds, .. } => walk_bounds_mut(visitor, Some(bounds)),
+        Type::Array {
+            element_type, size, ..
+        } => {
+            visitor.visit_type_mut(element_type);
+            visitor.visit_type_mut(size);
+        }
+    }
+}
+
+/// Walks a type constructor, visiting its type arguments.
+pub fn walk_type_constructor_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    constructor: &mut TypeConstructor,
+) {
+    for argument in &mut constructor.arguments {
+        visitor.visit_type_mut(argument);
+    }
+}
+
+fn walk_attributes_mut<V: VisitorMut + ?Sized>(visitor: &mut V, attributes: &mut [Attribute]) {
+    for attribute in attributes {
+        if let Some(arguments) = &mut attribute.arguments {
+            for argument in arguments {
+                visitor.visit_expression_mut(argument);
+            }
+        }
+    }
+}
+
+fn walk_generic_parameters_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    generic_parameters: &mut [GenericParameter],
+) {
+    for generic_parameter in generic_parameters {
+        walk_bounds_mut(visitor, generic_parameter.bounds.as_deref_mut());
+
+        if let Some(const_type) = &mut generic_parameter.const_type {
+            visitor.visit_type_mut(const_type);
+        }
+
+        if let Some(default_value) = &mut generic_parameter.default_value {
+            visitor.visit_type_mut(default_value);
+        }
+    }
+}
+
+fn walk_where_predicates_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    predicates: &mut [WherePredicate],
+) {
+    for predicate in predicates {
+        visitor.visit_type_mut(&mut predicate.ty);
+        walk_bounds_mut(visitor, Some(&mut predicate.bounds));
+    }
+}
+
+fn walk_bounds_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    bounds: Option<&mut [TypeConstructor]>,
+) {
+    for bound in bounds.into_iter().flatten() {
+        visitor.visit_type_constructor_mut(bound);
+    }
+}
+
+fn walk_methods_mut<V: VisitorMut + ?Sized>(visitor: &mut V, methods: &mut [Function]) {
+    for method in methods {
+        visitor.visit_function_mut(method);
+    }
+}
+
+fn walk_tuple_fields_mut<V: VisitorMut + ?Sized>(visitor: &mut V, fields: &mut [TupleField]) {
+    for field in fields {
+        visitor.visit_type_mut(&mut field.ty);
+    }
+}
+
+fn walk_struct_fields_mut<V: VisitorMut + ?Sized>(visitor: &mut V, fields: &mut [StructField]) {
+    for field in fields {
+        walk_attributes_mut(visitor, &mut field.attributes);
+        visitor.visit_type_mut(&mut field.ty);
+    }
+}
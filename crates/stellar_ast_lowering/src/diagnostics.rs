@@ -1,5 +1,6 @@
 use stellar_diagnostics::define_diagnostics;
 use stellar_filesystem::location::Location;
+use stellar_interner::IdentifierId;
 
 define_diagnostics! {
     diagnostic(warning) UnnecessaryGroupedPattern(
@@ -37,4 +38,167 @@ define_diagnostics! {
             primary { self.location.end_byte_location() => "help: remove these parentheses" }
         }
     }
+
+    diagnostic(warning) RedundantFieldInitializer(
+        self,
+        location: Location,
+        name: IdentifierId
+    ) {
+        code { "W005" }
+        message { "redundant field initializer" }
+        labels {
+            primary { self.location => format!("help: replace with `{}`", self.name) }
+        }
+        notes {
+            "note: a field initialized with a variable of the same name can be written once"
+        }
+    }
+
+    diagnostic(warning) RedundantArgumentValue(
+        self,
+        location: Location,
+        name: IdentifierId
+    ) {
+        code { "W008" }
+        message { "redundant argument value" }
+        labels {
+            primary { self.location => format!("help: replace with `{}:`", self.name) }
+        }
+        notes {
+            "note: an argument passed as a variable named as its label can be written without the value"
+        }
+    }
+
+    diagnostic(error) ShorthandOfNonLocalVariable(
+        self,
+        location: Location,
+        name: IdentifierId
+    ) {
+        code { "E034" }
+        message { format!("`{}` in the shorthand form is not a local variable", self.name) }
+        labels {
+            primary { self.location => "not a local variable" }
+        }
+        notes {
+            format!("help: write the value explicitly, e.g. `{}: {}`", self.name, self.name)
+        }
+    }
+
+    diagnostic(warning) UnreachableStatement(
+        self,
+        location: Location,
+        diverging_location: Location
+    ) {
+        code { "W006" }
+        message { "unreachable statement" }
+        labels {
+            primary { self.location => "unreachable statement" }
+            secondary { self.diverging_location => "any code following this statement is unreachable" }
+        }
+    }
+
+    diagnostic(error) YieldOutsideOfGenerator(
+        self,
+        location: Location
+    ) {
+        code { "E019" }
+        message { "`yield` outside of a generator function" }
+        labels {
+            primary { self.location => "not allowed here" }
+        }
+        notes {
+            "help: mark the function with `gen`, e.g. `gen fun items()`"
+        }
+    }
+
+    diagnostic(error) DuplicateLambdaCapture(
+        self,
+        name: IdentifierId,
+        first_capture_location: Location,
+        second_capture_location: Location
+    ) {
+        code { "E020" }
+        message { format!("`{}` is captured multiple times", self.name) }
+        labels {
+            primary { self.second_capture_location => "captured again here" }
+            secondary { self.first_capture_location => format!("first capture of `{}`", self.name) }
+        }
+    }
+
+    diagnostic(error) BreakOutsideOfLoop(
+        self,
+        location: Location
+    ) {
+        code { "E026" }
+        message { "`break` outside of a loop" }
+        labels {
+            primary { self.location => "cannot break out of here" }
+        }
+        notes {
+            "note: lambdas and deferred blocks can't break out of loops they are defined in"
+        }
+    }
+
+    diagnostic(error) ContinueOutsideOfLoop(
+        self,
+        location: Location
+    ) {
+        code { "E027" }
+        message { "`continue` outside of a loop" }
+        labels {
+            primary { self.location => "cannot continue here" }
+        }
+        notes {
+            "note: lambdas and deferred blocks can't continue loops they are defined in"
+        }
+    }
+
+    diagnostic(error) DeferOfNonCall(
+        self,
+        location: Location
+    ) {
+        code { "E028" }
+        message { "expected a call after `defer`" }
+        labels {
+            primary { self.location => "not a call" }
+        }
+        notes {
+            "help: wrap the expression into a block, e.g. `defer { a + b; }`"
+        }
+    }
+
+    diagnostic(error) MultipleRestPatterns(
+        self,
+        first_rest_location: Location,
+        second_rest_location: Location
+    ) {
+        code { "E029" }
+        message { "`..` can be used at most once in a list pattern" }
+        labels {
+            primary { self.second_rest_location => "used again here" }
+            secondary { self.first_rest_location => "first use of `..`" }
+        }
+    }
+
+    diagnostic(error) ConstantOverflow(
+        self,
+        location: Location
+    ) {
+        code { "E030" }
+        message { "constant expression overflows `int64`" }
+        labels {
+            primary { self.location => "overflows here" }
+        }
+    }
+
+    diagnostic(error) ConstantDivisionByZero(
+        self,
+        location: Location
+    ) {
+        code { "E031" }
+        message { "division by zero in a constant expression" }
+        labels {
+            primary { self.location => "divides by zero" }
+        }
+    }
 }
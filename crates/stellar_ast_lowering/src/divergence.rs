@@ -0,0 +1,112 @@
+//! Detection of statements, after which the rest of a statements block is
+//! unreachable, see [`diverges`].
+
+use stellar_ast::{
+    visit::Visitor, DeferBody, Expression, LambdaCapture, LambdaFunctionParameter, Pattern,
+    Statement, Type,
+};
+use stellar_filesystem::location::Location;
+
+/// Returns `true` if statements following a given one are never executed:
+///
+/// * `return`, `break` and `continue` statements.
+/// * `loop` expressions without `break` statements, that break out of them.
+pub(crate) fn diverges(statement: &Statement) -> bool {
+    match statement {
+        Statement::Break { .. } | Statement::Continue { .. } | Statement::Return { .. } => true,
+        Statement::Expression {
+            expression: Expression::Loop {
+                statements_block, ..
+            },
+            ..
+        } => {
+            let mut finder = BreakFinder { found: false };
+            finder.visit_statements_block(statements_block);
+
+            !finder.found
+        }
+        _ => false,
+    }
+}
+
+/// Returns the location of a statement.
+pub(crate) fn statement_location(statement: &Statement) -> Location {
+    match statement {
+        Statement::Defer {
+            body: DeferBody::Call(call),
+            ..
+        } => call.location(),
+        Statement::Defer {
+            body: DeferBody::Block { location, .. },
+            ..
+        }
+        | Statement::Break { location, .. }
+        | Statement::Continue { location, .. }
+        | Statement::Yield { location, .. } => *location,
+        Statement::Expression { expression, .. } | Statement::Return { expression, .. } => {
+            expression.location()
+        }
+        Statement::Let { pattern, value, .. } => Location {
+            end: value.location().end,
+            ..pattern.location()
+        },
+    }
+}
+
+/// Finds `break` statements, that break out of the loop, which body is
+/// visited.
+struct BreakFinder {
+    found: bool,
+}
+
+impl Visitor for BreakFinder {
+    fn visit_break_statement(&mut self, _location: Location) {
+        self.found = true;
+    }
+
+    // deferred blocks and lambdas can't break out of loops they are defined
+    // in, and `break` in nested loops breaks out of them
+
+    fn visit_defer_statement(&mut self, _body: &DeferBody) {}
+
+    fn visit_lambda_expression(
+        &mut self,
+        _location: Location,
+        _captures: &[LambdaCapture],
+        _parameters: &[LambdaFunctionParameter],
+        _return_type: Option<&Type>,
+        _value: &Expression,
+    ) {
+    }
+
+    fn visit_loop_expression(&mut self, _location: Location, _statements_block: &[Statement]) {}
+
+    fn visit_while_expression(
+        &mut self,
+        _location: Location,
+        condition: &Expression,
+        _statements_block: &[Statement],
+    ) {
+        self.visit_expression(condition);
+    }
+
+    fn visit_while_let_expression(
+        &mut self,
+        _location: Location,
+        _pattern: &Pattern,
+        expression: &Expression,
+        _statements_block: &[Statement],
+    ) {
+        self.visit_expression(expression);
+    }
+
+    fn visit_for_expression(
+        &mut self,
+        _location: Location,
+        _pattern: &Pattern,
+        iterable: &Expression,
+        _statements_block: &[Statement],
+    ) {
+        self.visit_expression(iterable);
+    }
+}
@@ -0,0 +1,422 @@
+//! Folds constant integer and boolean expressions, e.g. `1 + 2 * 3` into `7`
+//! and `true && !false` into `true`, so that later stages work with smaller
+//! HIR and constants can be evaluated in const contexts.
+//!
+//! Integers are folded as `int64` values. Operations, that overflow or divide
+//! by zero, are reported at the location of the expression and left as is.
+//! Float literals are never folded.
+//!
+//! The pass is disabled by default, see [`Config::fold_constants`].
+//!
+//! [`Config::fold_constants`]: crate::pipeline::Config::fold_constants
+
+use std::mem;
+
+use stellar_ast::{
+    BinaryOperator, IntegerValue, Literal, PrefixOperator, RawBinaryOperator, RawPrefixOperator,
+};
+use stellar_diagnostics::Diagnostics;
+use stellar_filesystem::location::Location;
+use stellar_hir::{
+    Constant, Enum, Expression, Function, HirId, Impl, Module, ModuleItem, SourceMap, Statement,
+    Struct, TupleLikeStruct, DUMMY_HIR_ID,
+};
+
+use crate::{
+    diagnostics::{ConstantDivisionByZero, ConstantOverflow},
+    pipeline::LoweringPass,
+};
+
+/// Folds constant expressions of a lowered module in place and reports
+/// overflows into `diagnostics`.
+///
+/// Folded expressions keep HIR IDs of the original ones, so their origins
+/// still point to the code, that user wrote.
+pub fn fold_constants(module: &mut Module, diagnostics: &mut Diagnostics) {
+    let mut folder = ConstantFolder {
+        diagnostics,
+        source_map: mem::take(&mut module.source_map),
+    };
+
+    for item in &mut module.items {
+        folder.fold_module_item(item);
+    }
+
+    module.source_map = folder.source_map;
+}
+
+/// Runs [`fold_constants`] in a [`LoweringPipeline`].
+///
+/// [`LoweringPipeline`]: crate::pipeline::LoweringPipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstantFoldingPass;
+
+impl LoweringPass for ConstantFoldingPass {
+    fn name(&self) -> &str {
+        "constant_folding"
+    }
+
+    fn run(&mut self, module: &mut Module, diagnostics: &mut Diagnostics) {
+        fold_constants(module, diagnostics);
+    }
+}
+
+/// A value of a constant expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Value {
+    Integer(i64),
+    Boolean(bool),
+}
+
+/// Why a constant expression can't be folded.
+enum FoldError {
+    /// The expression is not constant, e.g. `a + 1`, or the operator can't
+    /// be applied to the values, e.g. `1 && true`.
+    NotConstant,
+    Overflow,
+    DivisionByZero,
+}
+
+struct ConstantFolder<'d> {
+    diagnostics: &'d mut Diagnostics,
+
+    /// Source map of the module, new nodes are recorded into it.
+    source_map: SourceMap,
+}
+
+impl ConstantFolder<'_> {
+    fn fold_module_item(&mut self, item: &mut ModuleItem) {
+        match item {
+            ModuleItem::Constant(constant) => self.fold_constant(constant),
+            ModuleItem::Static(r#static) => self.fold_expression(&mut r#static.value),
+            ModuleItem::Function(function) => self.fold_function(function),
+            ModuleItem::Enum(Enum { methods, .. })
+            | ModuleItem::Struct(Struct { methods, .. })
+            | ModuleItem::TupleLikeStruct(TupleLikeStruct { methods, .. })
+            | ModuleItem::Impl(Impl { methods, .. }) => {
+                for method in methods {
+                    self.fold_function(method);
+                }
+            }
+            ModuleItem::Interface(interface) => {
+                for constant in &mut interface.constants {
+                    self.fold_constant(constant);
+                }
+
+                for method in &mut interface.methods {
+                    self.fold_function(method);
+                }
+            }
+            ModuleItem::Extern(_) | ModuleItem::Import { .. } | ModuleItem::TypeAlias(_) => {}
+        }
+    }
+
+    fn fold_constant(&mut self, constant: &mut Constant) {
+        if let Some(value) = &mut constant.value {
+            self.fold_expression(value);
+        }
+    }
+
+    fn fold_function(&mut self, function: &mut Function) {
+        if let Some(body) = &mut function.body {
+            self.fold_statements_block(body);
+        }
+    }
+
+    fn fold_statements_block(&mut self, block: &mut [Statement]) {
+        for statement in block {
+            match statement {
+                Statement::Defer { block, .. } => self.fold_statements_block(block),
+                Statement::Expression { expression, .. }
+                | Statement::Return { expression, .. }
+                | Statement::Yield { expression, .. }
+                | Statement::Let {
+                    value: expression, ..
+                } => self.fold_expression(expression),
+                Statement::Break { .. } | Statement::Continue { .. } => {}
+            }
+        }
+    }
+
+    fn fold_expression(&mut self, expression: &mut Expression) {
+        match expression {
+            Expression::List { elements, .. } => {
+                for element in elements {
+                    self.fold_expression(&mut element.value);
+                }
+            }
+            Expression::Map { entries, .. } => {
+                for entry in entries {
+                    self.fold_expression(&mut entry.key);
+                    self.fold_expression(&mut entry.value);
+                }
+            }
+            Expression::ArrayRepeat { value, size, .. } => {
+                self.fold_expression(value);
+                self.fold_expression(size);
+            }
+            Expression::Binary { left, right, .. } => {
+                self.fold_expression(left);
+                self.fold_expression(right);
+            }
+            Expression::Range { start, end, .. } => {
+                for bound in [start, end].into_iter().flatten() {
+                    self.fold_expression(bound);
+                }
+            }
+            Expression::StatementsBlock { block, .. }
+            | Expression::Unsafe { block, .. }
+            | Expression::Try { block, .. } => self.fold_statements_block(block),
+            Expression::If {
+                if_blocks, r#else, ..
+            } => {
+                for (condition, block) in if_blocks {
+                    self.fold_expression(condition);
+                    self.fold_statements_block(block);
+                }
+
+                if let Some(block) = r#else {
+                    self.fold_statements_block(block);
+                }
+            }
+            Expression::Borrow { inner, .. }
+            | Expression::Await { inner, .. }
+            | Expression::Prefix { inner, .. }
+            | Expression::Postfix { inner, .. }
+            | Expression::As { left: inner, .. }
+            | Expression::FieldAccess { left: inner, .. }
+            | Expression::TupleIndex { left: inner, .. }
+            | Expression::TypeArguments { left: inner, .. }
+            | Expression::Lambda { value: inner, .. } => self.fold_expression(inner),
+            Expression::While {
+                condition,
+                statements_block,
+                ..
+            } => {
+                self.fold_expression(condition);
+                self.fold_statements_block(statements_block);
+            }
+            Expression::Call {
+                callee, arguments, ..
+            } => {
+                self.fold_expression(callee);
+
+                for argument in arguments {
+                    self.fold_expression(&mut argument.value);
+                }
+            }
+            Expression::Tuple { elements, .. } => {
+                for element in elements {
+                    self.fold_expression(element);
+                }
+            }
+            Expression::Struct {
+                left, fields, rest, ..
+            } => {
+                self.fold_expression(left);
+
+                for field in fields {
+                    self.fold_expression(&mut field.value);
+                }
+
+                if let Some(rest) = rest {
+                    self.fold_expression(rest);
+                }
+            }
+            Expression::Match {
+                expression, block, ..
+            } => {
+                self.fold_expression(expression);
+
+                for item in block {
+                    self.fold_expression(&mut item.right);
+                }
+            }
+            Expression::Literal { .. }
+            | Expression::Identifier { .. }
+            | Expression::Underscore { .. } => {}
+        }
+
+        // already in the simplest form, e.g. `1` or `-1`
+        if value_of(expression).is_some() {
+            return;
+        }
+
+        match evaluate(expression) {
+            Ok(value) => *expression = self.constant(value, expression),
+            Err(FoldError::NotConstant) => {}
+            Err(FoldError::Overflow) => self
+                .diagnostics
+                .add_diagnostic(ConstantOverflow::new(expression.location())),
+            Err(FoldError::DivisionByZero) => self
+                .diagnostics
+                .add_diagnostic(ConstantDivisionByZero::new(expression.location())),
+        }
+    }
+
+    /// Builds a literal for the value of a folded expression. Negative
+    /// integers are built as `-` applied to a literal, like the parser does.
+    fn constant(&mut self, value: Value, folded: &Expression) -> Expression {
+        let (hir_id, location) = (folded.hir_id(), folded.location());
+
+        match value {
+            Value::Boolean(value) => Expression::Literal {
+                hir_id,
+                literal: Literal::Boolean { value, location },
+            },
+            Value::Integer(value) if value >= 0 => integer_literal(hir_id, value, location),
+            Value::Integer(value) => {
+                let literal_hir_id = self
+                    .source_map
+                    .origin(hir_id)
+                    .map_or(DUMMY_HIR_ID, |origin| self.source_map.insert(origin));
+
+                Expression::Prefix {
+                    hir_id,
+                    location,
+                    inner: Box::new(integer_literal(literal_hir_id, value, location)),
+                    operator: PrefixOperator {
+                        raw: RawPrefixOperator::Minus,
+                        location,
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn integer_literal(hir_id: HirId, value: i64, location: Location) -> Expression {
+    Expression::Literal {
+        hir_id,
+        literal: Literal::Integer {
+            value: IntegerValue::new(value.unsigned_abs()),
+            location,
+        },
+    }
+}
+
+/// Returns the value of a literal, e.g. `true`, `1` or `-1`.
+fn value_of(expression: &Expression) -> Option<Value> {
+    match expression {
+        Expression::Literal {
+            literal: Literal::Boolean { value, .. },
+            ..
+        } => Some(Value::Boolean(*value)),
+        Expression::Literal {
+            literal: Literal::Integer { value, .. },
+            ..
+        } => i64::try_from(value.to_u64()?).ok().map(Value::Integer),
+        Expression::Prefix {
+            inner,
+            operator:
+                PrefixOperator {
+                    raw: RawPrefixOperator::Minus,
+                    ..
+                },
+            ..
+        } => match &**inner {
+            Expression::Literal {
+                literal: Literal::Integer { value, .. },
+                ..
+            } => 0_i64
+                .checked_sub_unsigned(value.to_u64()?)
+                .map(Value::Integer),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Evaluates a binary or a prefix expression, which operands are already
+/// folded.
+fn evaluate(expression: &Expression) -> Result<Value, FoldError> {
+    match expression {
+        Expression::Binary {
+            left,
+            operator: BinaryOperator { raw, .. },
+            right,
+            ..
+        } => {
+            let (Some(left), Some(right)) = (value_of(left), value_of(right)) else {
+                return Err(FoldError::NotConstant);
+            };
+
+            match (left, right) {
+                (Value::Integer(left), Value::Integer(right)) => {
+                    evaluate_integer_binary(left, *raw, right)
+                }
+                (Value::Boolean(left), Value::Boolean(right)) => {
+                    evaluate_boolean_binary(left, *raw, right)
+                }
+                _ => Err(FoldError::NotConstant),
+            }
+        }
+        Expression::Prefix {
+            inner,
+            operator: PrefixOperator { raw, .. },
+            ..
+        } => match (raw, value_of(inner).ok_or(FoldError::NotConstant)?) {
+            (RawPrefixOperator::Bang, Value::Boolean(value)) => Ok(Value::Boolean(!value)),
+            (RawPrefixOperator::Tilde, Value::Integer(value)) => Ok(Value::Integer(!value)),
+            (RawPrefixOperator::Minus, Value::Integer(value)) => value
+                .checked_neg()
+                .map(Value::Integer)
+                .ok_or(FoldError::Overflow),
+            (RawPrefixOperator::Plus, Value::Integer(value)) => Ok(Value::Integer(value)),
+            _ => Err(FoldError::NotConstant),
+        },
+        _ => Err(FoldError::NotConstant),
+    }
+}
+
+fn evaluate_integer_binary(
+    left: i64,
+    operator: RawBinaryOperator,
+    right: i64,
+) -> Result<Value, FoldError> {
+    let shift = || u32::try_from(right).map_err(|_| FoldError::Overflow);
+
+    let result = match operator {
+        RawBinaryOperator::Plus => left.checked_add(right),
+        RawBinaryOperator::Minus => left.checked_sub(right),
+        RawBinaryOperator::Asterisk => left.checked_mul(right),
+        RawBinaryOperator::Slash | RawBinaryOperator::Percent if right == 0 => {
+            return Err(FoldError::DivisionByZero)
+        }
+        RawBinaryOperator::Slash => left.checked_div(right),
+        RawBinaryOperator::Percent => left.checked_rem(right),
+        // negative exponents are not folded, the result is not an integer
+        RawBinaryOperator::DoubleAsterisk => {
+            left.checked_pow(u32::try_from(right).map_err(|_| FoldError::NotConstant)?)
+        }
+        RawBinaryOperator::LeftShift => left.checked_shl(shift()?),
+        RawBinaryOperator::RightShift => left.checked_shr(shift()?),
+        RawBinaryOperator::Ampersand => Some(left & right),
+        RawBinaryOperator::Or => Some(left | right),
+        RawBinaryOperator::DoubleEq => return Ok(Value::Boolean(left == right)),
+        RawBinaryOperator::BangEq => return Ok(Value::Boolean(left != right)),
+        RawBinaryOperator::Less => return Ok(Value::Boolean(left < right)),
+        RawBinaryOperator::LessEq => return Ok(Value::Boolean(left <= right)),
+        RawBinaryOperator::Greater => return Ok(Value::Boolean(left > right)),
+        RawBinaryOperator::GreaterEq => return Ok(Value::Boolean(left >= right)),
+        _ => return Err(FoldError::NotConstant),
+    };
+
+    result.map(Value::Integer).ok_or(FoldError::Overflow)
+}
+
+fn evaluate_boolean_binary(
+    left: bool,
+    operator: RawBinaryOperator,
+    right: bool,
+) -> Result<Value, FoldError> {
+    match operator {
+        RawBinaryOperator::DoubleAmpersand | RawBinaryOperator::Ampersand => {
+            Ok(Value::Boolean(left && right))
+        }
+        RawBinaryOperator::DoubleOr | RawBinaryOperator::Or => Ok(Value::Boolean(left || right)),
+        RawBinaryOperator::DoubleEq => Ok(Value::Boolean(left == right)),
+        RawBinaryOperator::BangEq => Ok(Value::Boolean(left != right)),
+        _ => Err(FoldError::NotConstant),
+    }
+}
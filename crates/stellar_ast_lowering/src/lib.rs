@@ -7,7 +7,27 @@
 //! * removes parenthesized types.
 //! * removes grouped patterns.
 //! * converts `loop {}` into `while true {}`.
+//! * converts `if let` and `while let` expressions into `match` expressions.
+//! * converts `for` loops into `while true {}` loops calling `.iterator()` and
+//!   `.next()`.
+//! * converts compound assignments into usual ones, e.g. `a += b` into
+//!   `a = a + b`, unless the place has side effects, e.g. `f().a += b`.
+//! * converts `expr?` into `match` expressions returning early in functions
+//!   and lambdas, that return `Result` or `Option`.
 //! * converts `interface A[T]: B[T] + C` into `interface A[T] where Self: B[T] + C`.
+//! * records variables captured by lambdas, see
+//!   [`stellar_hir::CapturedVariable`].
+//! * desugars shorthand struct fields and call arguments, e.g. `Person { name }`
+//!   and `greet(name:)`, checking that they refer to local variables, and
+//!   warns about longer forms, that can be shortened, e.g. `name: name`.
+//! * warns about unreachable statements, e.g. ones following `return` or
+//!   `loop` without `break`.
+//!
+//! The lowered HIR is then checked by the [`validate`] pass, so that malformed
+//! code, e.g. `break` outside of a loop, doesn't reach later stages. Constant
+//! expressions, e.g. `1 + 2 * 3`, can then be folded by the optional [`fold`]
+//! pass. Passes are run by the [`pipeline::LoweringPipeline`], which allows
+//! to disable them and to register custom ones.
 //!
 //! See the [`stellar_hir`] crate for more details.
 #![doc(
@@ -15,23 +35,104 @@
     html_favicon_url = "https://raw.githubusercontent.com/quantumatic/stellar/main/additional/icon/stellar.png"
 )]
 
-#[cfg(feature = "debug")]
-use std::time::Instant;
+use std::mem;
 
-use diagnostics::{UnnecessaryGroupedPattern, UnnecessaryParenthesizedExpression};
-use stellar_ast::IdentifierAST;
-use stellar_database::{ModuleId, State};
+use diagnostics::{
+    DuplicateLambdaCapture, RedundantArgumentValue, RedundantFieldInitializer,
+    ShorthandOfNonLocalVariable, UnnecessaryGroupedPattern, UnnecessaryParenthesizedExpression,
+    UnreachableStatement, YieldOutsideOfGenerator,
+};
+use divergence::{diverges, statement_location};
+use pipeline::LoweringPipeline;
+use stellar_ast::{CaptureKind, IdentifierAST, NodeId};
+use stellar_database::{cancellation::CancellationToken, ModuleId, State};
+use stellar_diagnostics::Diagnostics;
 use stellar_filesystem::location::Location;
 use stellar_fx_hash::FxHashMap;
-use stellar_interner::builtin_identifiers::BIG_SELF;
+use stellar_hir::{CapturedVariable, HirId, Origin, SourceMap};
+use stellar_interner::{
+    builtin_identifiers::{
+        BIG_SELF, ERR, ITERATOR, NEXT, NONE, OK, OPTION, RESULT, SMALL_SELF, SOME,
+    },
+    IdentifierId,
+};
 use stellar_parser::ParseResult;
-#[cfg(feature = "debug")]
-use tracing::trace;
 
 mod diagnostics;
+mod divergence;
+pub mod fold;
+pub mod pipeline;
+pub mod validate;
 
 pub struct LowerToHir<'s> {
-    state: &'s mut State,
+    diagnostics: &'s mut Diagnostics,
+    cancellation_token: &'s CancellationToken,
+
+    /// Whether statements of a generator function are being lowered.
+    in_generator: bool,
+
+    /// How `?` propagates errors in the function or lambda, that is being
+    /// lowered, `None` if `?` is kept as is.
+    error_propagation: Option<ErrorPropagation>,
+
+    /// Origins of expressions and statements of the module, that is being
+    /// lowered.
+    source_map: SourceMap,
+
+    /// Variables of the function, that is being lowered, visible at the
+    /// current expression. Shadowing variables come last.
+    locals: Vec<IdentifierId>,
+
+    /// Lambdas, that are being lowered, innermost last.
+    lambdas: Vec<LambdaScope>,
+}
+
+/// A lambda, that is being lowered, see [`LowerToHir::use_variable`].
+struct LambdaScope {
+    /// The number of variables visible outside of the lambda.
+    outer_locals: usize,
+
+    /// The number of explicit captures at the start of `captured_variables`.
+    explicit_captures: usize,
+    captured_variables: Vec<CapturedVariable>,
+}
+
+/// Orders capture kinds by what a lambda can do with a captured variable.
+const fn capture_strength(kind: CaptureKind) -> u8 {
+    match kind {
+        CaptureKind::Reference => 0,
+        CaptureKind::MutableReference => 1,
+        CaptureKind::Move => 2,
+    }
+}
+
+/// A way `?` operators propagate errors, see
+/// [`LowerToHir::lower_question_mark`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorPropagation {
+    /// `Err(error)` is returned.
+    Result,
+
+    /// `None` is returned.
+    Option,
+}
+
+impl ErrorPropagation {
+    /// Returns the way `?` operators propagate errors in a function with a
+    /// given return type, `None` if it is neither `Result` nor `Option`.
+    fn of(return_type: Option<&stellar_ast::Type>) -> Option<Self> {
+        match return_type? {
+            stellar_ast::Type::Optional { .. } => Some(Self::Option),
+            stellar_ast::Type::Constructor(constructor) => {
+                match constructor.path.identifiers.last()?.id {
+                    RESULT => Some(Self::Result),
+                    OPTION => Some(Self::Option),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
 }
 
 /// A lowered module.
@@ -86,29 +187,42 @@ impl From<LoweredModule> for stellar_hir::Module {
 }
 
 impl<'s> LowerToHir<'s> {
+    /// Lowers modules with the default [`LoweringPipeline`].
     pub fn run_all(
         state: &'s mut State,
         modules: Vec<ParseResult>,
     ) -> FxHashMap<ModuleId, stellar_hir::Module> {
-        modules
-            .into_iter()
-            .map(|module| {
-                #[cfg(feature = "debug")]
-                let now = Instant::now();
-
-                let (module, ast) = (module.module(), module.into_ast());
-                let hir = LowerToHir { state }.run(ast);
-
-                #[cfg(feature = "debug")]
-                trace!(
-                    "lower_ast(module = '{}') <{} us>",
-                    module.filepath(state.db()),
-                    now.elapsed().as_micros()
-                );
+        LoweringPipeline::default().run_all(state, modules)
+    }
 
-                (module, hir)
-            })
-            .collect()
+    /// Lowers modules of a package in parallel with the default
+    /// [`LoweringPipeline`], see [`LoweringPipeline::lower_package`].
+    pub fn lower_package(
+        state: &'s mut State,
+        modules: Vec<ParseResult>,
+    ) -> Vec<(ModuleId, stellar_hir::Module)> {
+        LoweringPipeline::default().lower_package(state, modules)
+    }
+
+    /// Desugars a parsed module into HIR without running any passes.
+    pub(crate) fn desugar(
+        diagnostics: &mut Diagnostics,
+        cancellation_token: &CancellationToken,
+        module: ParseResult,
+    ) -> (ModuleId, stellar_hir::Module) {
+        let (module, ast) = (module.module(), module.into_ast());
+        let hir = LowerToHir {
+            diagnostics,
+            cancellation_token,
+            in_generator: false,
+            error_propagation: None,
+            source_map: SourceMap::new(),
+            locals: Vec::new(),
+            lambdas: Vec::new(),
+        }
+        .run(ast);
+
+        (module, hir)
     }
 
     fn run(&mut self, ast: stellar_ast::Module) -> stellar_hir::Module {
@@ -116,19 +230,178 @@ impl<'s> LowerToHir<'s> {
             filepath: ast.filepath,
             items: vec![],
             docstring: ast.docstring,
+            source_map: SourceMap::new(),
         };
 
         for item in ast.items {
-            lowered.items.push(self.lower_module_item(item));
+            if self.cancellation_token.is_cancelled() {
+                break;
+            }
+
+            if let stellar_ast::ModuleItem::Import {
+                location,
+                attributes,
+                visibility,
+                path,
+                ..
+            } = item
+            {
+                lowered
+                    .items
+                    .extend(Self::lower_import(location, attributes, visibility, path));
+            } else {
+                lowered.items.extend(self.lower_module_item(item));
+            }
         }
 
+        lowered.source_map = mem::take(&mut self.source_map);
         lowered
     }
 
+    /// Assigns an ID to a new HIR node lowered from a given AST node.
+    fn hir_id(&mut self, node_id: NodeId, location: Location) -> HirId {
+        self.source_map.insert(Origin { node_id, location })
+    }
+
+    /// Makes variables bound by a pattern visible, until the surrounding
+    /// scope is left, e.g. with [`Vec::truncate`] on `locals`.
+    fn bind_pattern(&mut self, pattern: &stellar_ast::Pattern) {
+        match pattern {
+            stellar_ast::Pattern::Identifier {
+                identifier,
+                pattern,
+                ..
+            } => {
+                self.locals.push(identifier.id);
+
+                if let Some(pattern) = pattern {
+                    self.bind_pattern(pattern);
+                }
+            }
+            stellar_ast::Pattern::Struct { fields, .. } => {
+                for field in fields {
+                    match field {
+                        stellar_ast::StructFieldPattern::NotRest {
+                            value_pattern: Some(pattern),
+                            ..
+                        } => self.bind_pattern(pattern),
+                        // `Person { name }` binds `name`
+                        stellar_ast::StructFieldPattern::NotRest { field_name, .. } => {
+                            self.locals.push(field_name.id);
+                        }
+                        stellar_ast::StructFieldPattern::Rest { .. } => {}
+                    }
+                }
+            }
+            stellar_ast::Pattern::TupleLike { inner_patterns, .. }
+            | stellar_ast::Pattern::List { inner_patterns, .. }
+            | stellar_ast::Pattern::Tuple {
+                elements: inner_patterns,
+                ..
+            }
+            | stellar_ast::Pattern::Or {
+                alternatives: inner_patterns,
+                ..
+            } => {
+                for pattern in inner_patterns {
+                    self.bind_pattern(pattern);
+                }
+            }
+            stellar_ast::Pattern::Grouped { inner, .. } => self.bind_pattern(inner),
+            stellar_ast::Pattern::Literal(_)
+            | stellar_ast::Pattern::NegativeNumericLiteral(_)
+            | stellar_ast::Pattern::Wildcard { .. }
+            | stellar_ast::Pattern::Path { .. }
+            | stellar_ast::Pattern::Range { .. }
+            | stellar_ast::Pattern::Rest { .. } => {}
+        }
+    }
+
+    /// Returns `true` if a variable with a given name is bound in the current
+    /// scope, e.g. by a parameter or a `let` statement.
+    fn is_local(&self, name: IdentifierId) -> bool {
+        self.locals.contains(&name)
+    }
+
+    /// Reports a shorthand struct field or call argument, e.g. `name` in
+    /// `Person { name }`, that doesn't refer to a local variable.
+    fn check_shorthand(&mut self, name: IdentifierAST) {
+        if !self.is_local(name.id) {
+            self.diagnostics
+                .add_diagnostic(ShorthandOfNonLocalVariable::new(name.location, name.id));
+        }
+    }
+
+    /// Records a use of a variable in lambdas, that capture it. Variables,
+    /// that are not local, e.g. functions and constants, are never captured.
+    fn use_variable(&mut self, name: IdentifierAST, kind: CaptureKind) {
+        let Some(local) = self.locals.iter().rposition(|local| *local == name.id) else {
+            return;
+        };
+
+        // the variable is captured by every lambda it is defined outside of
+        for lambda in self
+            .lambdas
+            .iter_mut()
+            .rev()
+            .take_while(|lambda| lambda.outer_locals > local)
+        {
+            match lambda
+                .captured_variables
+                .iter()
+                .position(|variable| variable.name.id == name.id)
+            {
+                // explicit captures are kept as written
+                Some(index) if index >= lambda.explicit_captures => {
+                    let variable = &mut lambda.captured_variables[index];
+
+                    if capture_strength(kind) > capture_strength(variable.kind) {
+                        variable.kind = kind;
+                    }
+                }
+                Some(_) => {}
+                None => lambda
+                    .captured_variables
+                    .push(CapturedVariable { name, kind }),
+            }
+        }
+    }
+
+    /// Lowers a place, that is assigned to or borrowed mutably, e.g. `a.b`
+    /// in `a.b = 1`.
+    fn lower_mutated_place(&mut self, ast: stellar_ast::Expression) -> stellar_hir::Expression {
+        let place = self.lower_expression(ast);
+
+        let mut root = &place;
+        loop {
+            match root {
+                stellar_hir::Expression::FieldAccess { left, .. }
+                | stellar_hir::Expression::TupleIndex { left, .. } => root = left,
+                stellar_hir::Expression::Identifier { identifier, .. } => {
+                    self.use_variable(*identifier, CaptureKind::MutableReference);
+                    break;
+                }
+                _ => break,
+            }
+        }
+
+        place
+    }
+
     /// Converts a given module item AST into HIR.
-    fn lower_module_item(&mut self, ast: stellar_ast::ModuleItem) -> stellar_hir::ModuleItem {
-        match ast {
+    fn lower_module_item(
+        &mut self,
+        ast: stellar_ast::ModuleItem,
+    ) -> Option<stellar_hir::ModuleItem> {
+        Some(match ast {
+            stellar_ast::ModuleItem::Constant(constant) => {
+                stellar_hir::ModuleItem::Constant(self.lower_constant(constant))
+            }
+            stellar_ast::ModuleItem::Static(static_) => {
+                stellar_hir::ModuleItem::Static(self.lower_static(static_))
+            }
             stellar_ast::ModuleItem::Enum(stellar_ast::Enum {
+                attributes,
                 visibility,
                 name,
                 generic_parameters,
@@ -137,7 +410,9 @@ impl<'s> LowerToHir<'s> {
                 methods,
                 implements,
                 docstring,
+                ..
             }) => stellar_hir::ModuleItem::Enum(stellar_hir::Enum {
+                attributes,
                 visibility,
                 name,
                 generic_parameters: self.lower_generic_parameters(generic_parameters),
@@ -159,6 +434,7 @@ impl<'s> LowerToHir<'s> {
                 docstring,
             }),
             stellar_ast::ModuleItem::Struct(stellar_ast::Struct {
+                attributes,
                 visibility,
                 name,
                 generic_parameters,
@@ -167,7 +443,9 @@ impl<'s> LowerToHir<'s> {
                 methods,
                 implements,
                 docstring,
+                ..
             }) => stellar_hir::ModuleItem::Struct(stellar_hir::Struct {
+                attributes,
                 visibility,
                 name,
                 generic_parameters: self.lower_generic_parameters(generic_parameters),
@@ -191,13 +469,51 @@ impl<'s> LowerToHir<'s> {
             stellar_ast::ModuleItem::Function(function) => {
                 stellar_hir::ModuleItem::Function(self.lower_function(function))
             }
-            stellar_ast::ModuleItem::Import { location, path } => {
-                stellar_hir::ModuleItem::Import { location, path }
-            }
+            stellar_ast::ModuleItem::Impl(stellar_ast::Impl {
+                location,
+                attributes,
+                generic_parameters,
+                interface,
+                ty,
+                where_predicates,
+                methods,
+                docstring,
+                ..
+            }) => stellar_hir::ModuleItem::Impl(stellar_hir::Impl {
+                location,
+                attributes,
+                generic_parameters: self.lower_generic_parameters(generic_parameters),
+                interface: interface.map(|interface| self.lower_type_constructor(interface)),
+                ty: self.lower_type(ty),
+                where_predicates: self.lower_where_predicates(where_predicates),
+                methods: methods
+                    .into_iter()
+                    .map(|method| self.lower_function(method))
+                    .collect(),
+                docstring,
+            }),
             stellar_ast::ModuleItem::TypeAlias(alias) => {
                 stellar_hir::ModuleItem::TypeAlias(self.lower_type_alias(alias))
             }
+            stellar_ast::ModuleItem::Extern(stellar_ast::ExternBlock {
+                location,
+                attributes,
+                abi,
+                functions,
+                docstring,
+                ..
+            }) => stellar_hir::ModuleItem::Extern(stellar_hir::ExternBlock {
+                location,
+                attributes,
+                abi,
+                functions: functions
+                    .into_iter()
+                    .map(|function| self.lower_function_signature(function))
+                    .collect(),
+                docstring,
+            }),
             stellar_ast::ModuleItem::TupleLikeStruct(stellar_ast::TupleLikeStruct {
+                attributes,
                 visibility,
                 name,
                 generic_parameters,
@@ -206,7 +522,9 @@ impl<'s> LowerToHir<'s> {
                 methods,
                 implements,
                 docstring,
+                ..
             }) => stellar_hir::ModuleItem::TupleLikeStruct(stellar_hir::TupleLikeStruct {
+                attributes,
                 visibility,
                 name,
                 generic_parameters: self.lower_generic_parameters(generic_parameters),
@@ -228,14 +546,18 @@ impl<'s> LowerToHir<'s> {
                 docstring,
             }),
             stellar_ast::ModuleItem::Interface(stellar_ast::Interface {
+                attributes,
                 visibility,
                 name,
                 generic_parameters,
                 where_predicates,
+                constants,
                 methods,
                 inherits,
                 docstring,
+                ..
             }) => stellar_hir::ModuleItem::Interface(stellar_hir::Interface {
+                attributes,
                 visibility,
                 name,
                 generic_parameters: self.lower_generic_parameters(generic_parameters),
@@ -267,20 +589,72 @@ impl<'s> LowerToHir<'s> {
 
                     where_predicates
                 },
+                constants: constants
+                    .into_iter()
+                    .map(|constant| self.lower_constant(constant))
+                    .collect(),
                 methods: methods
                     .into_iter()
                     .map(|method| self.lower_function(method))
                     .collect(),
                 docstring,
             }),
-        }
+            // inline submodules are lowered as separate modules, declarations
+            // are only used to link modules together
+            stellar_ast::ModuleItem::Module(_) => return None,
+            // import groups are lowered into several items by `lower_import`
+            stellar_ast::ModuleItem::Import { .. } => return None,
+        })
+    }
+
+    /// Converts an import into HIR. An import group becomes a separate import
+    /// for every path in it, e.g. `import std.{io, fs};` is lowered into
+    /// `import std.io;` and `import std.fs;`.
+    fn lower_import(
+        location: Location,
+        attributes: Vec<stellar_ast::Attribute>,
+        visibility: stellar_ast::Visibility,
+        path: stellar_ast::ImportPath,
+    ) -> impl Iterator<Item = stellar_hir::ModuleItem> {
+        path.flatten()
+            .into_iter()
+            .map(move |path| stellar_hir::ModuleItem::Import {
+                location,
+                attributes: attributes.clone(),
+                visibility,
+                path,
+            })
     }
 
     fn lower_function(&mut self, ast: stellar_ast::Function) -> stellar_hir::Function {
-        stellar_hir::Function {
+        let in_generator = mem::replace(&mut self.in_generator, ast.signature.is_generator);
+        let error_propagation = mem::replace(
+            &mut self.error_propagation,
+            ErrorPropagation::of(ast.signature.return_type.as_ref()),
+        );
+
+        let locals = self.locals.len();
+        for parameter in &ast.signature.parameters {
+            match parameter {
+                stellar_ast::FunctionParameter::NotSelfParameter(parameter) => {
+                    self.bind_pattern(&parameter.pattern);
+                }
+                stellar_ast::FunctionParameter::SelfParameter(_) => {
+                    self.locals.push(SMALL_SELF);
+                }
+            }
+        }
+
+        let function = stellar_hir::Function {
             signature: self.lower_function_signature(ast.signature),
             body: ast.body.map(|block| self.lower_statements_block(block)),
-        }
+        };
+
+        self.in_generator = in_generator;
+        self.error_propagation = error_propagation;
+        self.locals.truncate(locals);
+
+        function
     }
 
     fn lower_enum_item(&mut self, ast: stellar_ast::EnumItem) -> stellar_hir::EnumItem {
@@ -318,36 +692,109 @@ impl<'s> LowerToHir<'s> {
         &mut self,
         ast: Vec<stellar_ast::Statement>,
     ) -> Vec<stellar_hir::Statement> {
-        ast.into_iter()
+        // only the first unreachable statement is reported
+        if let Some(index) = ast.iter().position(diverges) {
+            if let Some(unreachable) = ast.get(index + 1) {
+                self.diagnostics.add_diagnostic(UnreachableStatement::new(
+                    statement_location(unreachable),
+                    statement_location(&ast[index]),
+                ));
+            }
+        }
+
+        let locals = self.locals.len();
+        let block = ast
+            .into_iter()
             .map(|statement| self.lower_statement(statement))
-            .collect()
+            .collect();
+        self.locals.truncate(locals);
+
+        block
     }
 
     fn lower_statement(&mut self, ast: stellar_ast::Statement) -> stellar_hir::Statement {
+        let node_id = ast.node_id();
+
         match ast {
-            stellar_ast::Statement::Break { location } => {
-                stellar_hir::Statement::Break { location }
-            }
-            stellar_ast::Statement::Continue { location } => {
-                stellar_hir::Statement::Continue { location }
-            }
-            stellar_ast::Statement::Defer { call } => {
-                let call = self.lower_expression(call);
+            stellar_ast::Statement::Break { location, .. } => stellar_hir::Statement::Break {
+                hir_id: self.hir_id(node_id, location),
+                location,
+            },
+            stellar_ast::Statement::Continue { location, .. } => stellar_hir::Statement::Continue {
+                hir_id: self.hir_id(node_id, location),
+                location,
+            },
+            stellar_ast::Statement::Defer { body, .. } => match body {
+                // `defer f();` is the same as `defer { f(); }`
+                stellar_ast::DeferBody::Call(call) => {
+                    let location = call.location();
 
-                stellar_hir::Statement::Defer { call }
-            }
-            stellar_ast::Statement::Return { expression } => stellar_hir::Statement::Return {
-                expression: self.lower_expression(expression),
+                    stellar_hir::Statement::Defer {
+                        hir_id: self.hir_id(node_id, location),
+                        location,
+                        block: vec![stellar_hir::Statement::Expression {
+                            hir_id: self.hir_id(node_id, location),
+                            expression: self.lower_expression(call),
+                            has_semicolon: true,
+                        }],
+                    }
+                }
+                stellar_ast::DeferBody::Block { location, block } => {
+                    stellar_hir::Statement::Defer {
+                        hir_id: self.hir_id(node_id, location),
+                        location,
+                        block: self.lower_statements_block(block),
+                    }
+                }
             },
-            stellar_ast::Statement::Let { pattern, value, ty } => stellar_hir::Statement::Let {
-                pattern: self.lower_pattern(pattern),
-                value: self.lower_expression(value),
-                ty: ty.map(|ty| self.lower_type(ty)),
+            stellar_ast::Statement::Return { expression, .. } => stellar_hir::Statement::Return {
+                hir_id: self.hir_id(node_id, expression.location()),
+                expression: self.lower_expression(expression),
             },
+            stellar_ast::Statement::Yield {
+                location,
+                expression,
+                ..
+            } => {
+                if !self.in_generator {
+                    self.diagnostics
+                        .add_diagnostic(YieldOutsideOfGenerator::new(location));
+                }
+
+                stellar_hir::Statement::Yield {
+                    hir_id: self.hir_id(node_id, location),
+                    location,
+                    expression: self.lower_expression(expression),
+                }
+            }
+            stellar_ast::Statement::Let {
+                pattern, value, ty, ..
+            } => {
+                let hir_id = self.hir_id(
+                    node_id,
+                    Location {
+                        end: value.location().end,
+                        ..pattern.location()
+                    },
+                );
+
+                // variables of the pattern are not visible in the value
+                let value = self.lower_expression(value);
+                self.bind_pattern(&pattern);
+
+                stellar_hir::Statement::Let {
+                    hir_id,
+                    pattern: self.lower_pattern(pattern),
+                    value,
+                    ty: ty.map(|ty| self.lower_type(ty)),
+                }
+            }
             stellar_ast::Statement::Expression {
                 expression,
                 has_semicolon,
+                ..
             } => stellar_hir::Statement::Expression {
+                hir_id: self.hir_id(node_id, expression.location()),
                 expression: self.lower_expression(expression),
                 has_semicolon,
             },
@@ -358,8 +805,7 @@ impl<'s> LowerToHir<'s> {
         match ast {
             stellar_ast::Pattern::Grouped { inner, .. } => {
                 if let stellar_ast::Pattern::Grouped { location, .. } = *inner {
-                    self.state
-                        .diagnostics_mut()
+                    self.diagnostics
                         .add_diagnostic(UnnecessaryGroupedPattern::new(location));
                 }
 
@@ -373,10 +819,12 @@ impl<'s> LowerToHir<'s> {
             }
             stellar_ast::Pattern::Identifier {
                 location,
+                binding_mode,
                 identifier,
                 pattern,
             } => stellar_hir::Pattern::Identifier {
                 location,
+                binding_mode,
                 identifier,
                 pattern: pattern.map(|pattern| Box::new(self.lower_pattern(*pattern))),
             },
@@ -393,14 +841,37 @@ impl<'s> LowerToHir<'s> {
             stellar_ast::Pattern::Literal(literal) => stellar_hir::Pattern::Literal(literal),
             stellar_ast::Pattern::Or {
                 location,
-                left,
-                right,
-            } => stellar_hir::Pattern::Or {
+                alternatives,
+            } => {
+                let mut lowered = Vec::with_capacity(alternatives.len());
+
+                // `(A | B) | C` is the same as `A | B | C`
+                for alternative in alternatives {
+                    match self.lower_pattern(alternative) {
+                        stellar_hir::Pattern::Or { alternatives, .. } => {
+                            lowered.extend(alternatives);
+                        }
+                        pattern => lowered.push(pattern),
+                    }
+                }
+
+                stellar_hir::Pattern::Or {
+                    location,
+                    alternatives: lowered,
+                }
+            }
+            stellar_ast::Pattern::Path { path } => stellar_hir::Pattern::Path { path },
+            stellar_ast::Pattern::Range {
                 location,
-                left: Box::new(self.lower_pattern(*left)),
-                right: Box::new(self.lower_pattern(*right)),
+                start,
+                end,
+                kind,
+            } => stellar_hir::Pattern::Range {
+                location,
+                start: Box::new(self.lower_pattern(*start)),
+                end: Box::new(self.lower_pattern(*end)),
+                kind,
             },
-            stellar_ast::Pattern::Path { path } => stellar_hir::Pattern::Path { path },
             stellar_ast::Pattern::Rest { location } => stellar_hir::Pattern::Rest { location },
             stellar_ast::Pattern::Struct {
                 location,
@@ -457,62 +928,130 @@ impl<'s> LowerToHir<'s> {
     }
 
     fn lower_expression(&mut self, ast: stellar_ast::Expression) -> stellar_hir::Expression {
+        let node_id = ast.node_id();
+
         match ast {
-            stellar_ast::Expression::Literal(literal) => stellar_hir::Expression::Literal(literal),
-            stellar_ast::Expression::Identifier(identifier) => {
-                stellar_hir::Expression::Identifier(identifier)
+            stellar_ast::Expression::Literal { literal, .. } => stellar_hir::Expression::Literal {
+                hir_id: self.hir_id(node_id, literal.location()),
+                literal,
+            },
+            stellar_ast::Expression::Identifier { identifier, .. } => {
+                self.use_variable(identifier, CaptureKind::Reference);
+
+                stellar_hir::Expression::Identifier {
+                    hir_id: self.hir_id(node_id, identifier.location),
+                    identifier,
+                }
             }
-            stellar_ast::Expression::Underscore { location } => {
-                stellar_hir::Expression::Underscore { location }
+            stellar_ast::Expression::Underscore { location, .. } => {
+                stellar_hir::Expression::Underscore {
+                    hir_id: self.hir_id(node_id, location),
+                    location,
+                }
             }
             stellar_ast::Expression::Loop {
                 location,
                 statements_block,
+                ..
             } => stellar_hir::Expression::While {
+                hir_id: self.hir_id(node_id, location),
                 location,
-                condition: Box::new(stellar_hir::Expression::Literal(
-                    stellar_ast::Literal::Boolean {
-                        value: true,
-                        location,
-                    },
-                )),
+                condition: Box::new(self.lower_loop_condition(node_id, location, "loop")),
                 statements_block: self.lower_statements_block(statements_block),
             },
-            stellar_ast::Expression::Tuple { location, elements } => {
-                stellar_hir::Expression::Tuple {
-                    location,
-                    elements: elements
-                        .into_iter()
-                        .map(|element| self.lower_expression(element))
-                        .collect(),
-                }
-            }
+            stellar_ast::Expression::Tuple {
+                location, elements, ..
+            } => stellar_hir::Expression::Tuple {
+                hir_id: self.hir_id(node_id, location),
+                location,
+                elements: elements
+                    .into_iter()
+                    .map(|element| self.lower_expression(element))
+                    .collect(),
+            },
             stellar_ast::Expression::Lambda {
                 location,
+                captures,
                 parameters,
                 return_type,
                 value,
-            } => stellar_hir::Expression::Lambda {
-                location,
-                parameters: parameters
+                ..
+            } => {
+                let lambda_error_propagation = ErrorPropagation::of(return_type.as_ref());
+
+                let hir_id = self.hir_id(node_id, location);
+                let captures = self.lower_lambda_captures(captures);
+                let parameters = parameters
                     .into_iter()
                     .map(|parameter| self.lower_lambda_function_parameter(parameter))
-                    .collect(),
-                return_type: return_type.map(|ty| self.lower_type(ty)),
-                value: Box::new(self.lower_expression(*value)),
-            },
+                    .collect::<Vec<_>>();
+                let return_type = return_type.map(|ty| self.lower_type(ty));
+
+                let mut captured_variables = Vec::<CapturedVariable>::new();
+
+                for capture in &captures {
+                    // explicit captures are uses of variables in enclosing lambdas
+                    self.use_variable(capture.name, capture.kind);
+
+                    // duplicates are reported in `lower_lambda_captures`
+                    if !captured_variables
+                        .iter()
+                        .any(|variable| variable.name.id == capture.name.id)
+                    {
+                        captured_variables.push(CapturedVariable {
+                            name: capture.name,
+                            kind: capture.kind,
+                        });
+                    }
+                }
+
+                let outer_locals = self.locals.len();
+                self.locals
+                    .extend(parameters.iter().map(|parameter| parameter.name.id));
+                self.lambdas.push(LambdaScope {
+                    outer_locals,
+                    explicit_captures: captured_variables.len(),
+                    captured_variables,
+                });
+
+                // lambdas can't be generators
+                let in_generator = mem::replace(&mut self.in_generator, false);
+                let error_propagation =
+                    mem::replace(&mut self.error_propagation, lambda_error_propagation);
+                let value = self.lower_expression(*value);
+                self.in_generator = in_generator;
+                self.error_propagation = error_propagation;
+
+                self.locals.truncate(outer_locals);
+                let captured_variables = self
+                    .lambdas
+                    .pop()
+                    .expect("lambda scope is pushed above")
+                    .captured_variables;
+
+                stellar_hir::Expression::Lambda {
+                    hir_id,
+                    location,
+                    captures,
+                    captured_variables,
+                    parameters,
+                    return_type,
+                    value: Box::new(value),
+                }
+            }
             stellar_ast::Expression::Match {
                 location,
                 expression,
                 block,
+                ..
             } => {
                 if let stellar_ast::Expression::Parenthesized { location, .. } = *expression {
-                    self.state
-                        .diagnostics_mut()
+                    self.diagnostics
                         .add_diagnostic(UnnecessaryParenthesizedExpression::new(location));
                 }
 
                 stellar_hir::Expression::Match {
+                    hir_id: self.hir_id(node_id, location),
                     location,
                     expression: Box::new(self.lower_expression(*expression)),
                     block: block
@@ -525,62 +1064,123 @@ impl<'s> LowerToHir<'s> {
                 location,
                 left,
                 fields,
+                rest,
+                ..
             } => stellar_hir::Expression::Struct {
+                hir_id: self.hir_id(node_id, location),
                 location,
                 left: Box::new(self.lower_expression(*left)),
                 fields: fields
                     .into_iter()
-                    .map(|field| self.lower_struct_field_expression(field))
+                    .map(|field| self.lower_struct_field_expression(node_id, field))
                     .collect(),
+                rest: rest.map(|rest| Box::new(self.lower_expression(*rest))),
             },
             stellar_ast::Expression::While {
                 location,
                 condition,
                 statements_block: body,
+                ..
             } => {
                 if let stellar_ast::Expression::Parenthesized { location, .. } = *condition {
-                    self.state
-                        .diagnostics_mut()
+                    self.diagnostics
                         .add_diagnostic(UnnecessaryParenthesizedExpression::new(location));
                 }
 
                 stellar_hir::Expression::While {
+                    hir_id: self.hir_id(node_id, location),
                     location,
                     condition: Box::new(self.lower_expression(*condition)),
                     statements_block: self.lower_statements_block(body),
                 }
             }
+            stellar_ast::Expression::WhileLet {
+                location,
+                pattern,
+                expression,
+                statements_block,
+                ..
+            } => self.lower_while_let_expression(
+                node_id,
+                location,
+                pattern,
+                *expression,
+                statements_block,
+            ),
+            stellar_ast::Expression::For {
+                location,
+                pattern,
+                iterable,
+                statements_block,
+                ..
+            } => self.lower_for_expression(node_id, location, pattern, *iterable, statements_block),
             stellar_ast::Expression::Prefix {
                 location,
                 inner,
                 operator,
+                ..
             } => stellar_hir::Expression::Prefix {
+                hir_id: self.hir_id(node_id, location),
                 location,
-                inner: Box::new(self.lower_expression(*inner)),
+                inner: Box::new(match operator.raw {
+                    stellar_ast::RawPrefixOperator::DoublePlus
+                    | stellar_ast::RawPrefixOperator::DoubleMinus => {
+                        self.lower_mutated_place(*inner)
+                    }
+                    _ => self.lower_expression(*inner),
+                }),
                 operator,
             },
             stellar_ast::Expression::Postfix {
                 location,
                 inner,
                 operator,
-            } => stellar_hir::Expression::Postfix {
-                location,
-                inner: Box::new(self.lower_expression(*inner)),
-                operator,
+                ..
+            } => match self.error_propagation {
+                Some(error_propagation)
+                    if operator.raw == stellar_ast::RawPostfixOperator::QuestionMark =>
+                {
+                    self.lower_question_mark(node_id, location, *inner, error_propagation)
+                }
+                _ => stellar_hir::Expression::Postfix {
+                    hir_id: self.hir_id(node_id, location),
+                    location,
+                    inner: Box::new(match operator.raw {
+                        stellar_ast::RawPostfixOperator::DoublePlus
+                        | stellar_ast::RawPostfixOperator::DoubleMinus => {
+                            self.lower_mutated_place(*inner)
+                        }
+                        stellar_ast::RawPostfixOperator::QuestionMark => {
+                            self.lower_expression(*inner)
+                        }
+                    }),
+                    operator,
+                },
             },
             stellar_ast::Expression::If {
                 location,
                 if_blocks,
                 r#else,
+                ..
             } => stellar_hir::Expression::If {
+                hir_id: self.hir_id(node_id, location),
                 location,
                 if_blocks: self.lower_if_blocks(if_blocks),
                 r#else: r#else.map(|else_block| self.lower_statements_block(else_block)),
             },
+            stellar_ast::Expression::IfLet {
+                location,
+                pattern,
+                expression,
+                block,
+                r#else,
+                ..
+            } => {
+                self.lower_if_let_expression(node_id, location, pattern, *expression, block, r#else)
+            }
             stellar_ast::Expression::Parenthesized { inner, .. } => {
                 if let stellar_ast::Expression::Parenthesized { location, .. } = *inner {
-                    self.state
-                        .diagnostics_mut()
+                    self.diagnostics
                         .add_diagnostic(UnnecessaryParenthesizedExpression::new(location));
                 }
 
@@ -591,91 +1191,769 @@ impl<'s> LowerToHir<'s> {
                 left,
                 right,
                 operator,
-            } => stellar_hir::Expression::Binary {
+                ..
+            } => match operator.raw.without_assignment() {
+                Some(raw) if Self::can_be_evaluated_twice(&left) => self.lower_compound_assignment(
+                    node_id,
+                    location,
+                    *left,
+                    stellar_ast::BinaryOperator { raw, ..operator },
+                    *right,
+                ),
+                compound => stellar_hir::Expression::Binary {
+                    hir_id: self.hir_id(node_id, location),
+                    location,
+                    left: Box::new(
+                        if compound.is_some() || operator.raw == stellar_ast::RawBinaryOperator::Eq
+                        {
+                            self.lower_mutated_place(*left)
+                        } else {
+                            self.lower_expression(*left)
+                        },
+                    ),
+                    operator,
+                    right: Box::new(self.lower_expression(*right)),
+                    is_compound_assignment: false,
+                },
+            },
+            stellar_ast::Expression::Range {
                 location,
-                left: Box::new(self.lower_expression(*left)),
-                right: Box::new(self.lower_expression(*right)),
-                operator,
+                start,
+                end,
+                kind,
+                ..
+            } => stellar_hir::Expression::Range {
+                hir_id: self.hir_id(node_id, location),
+                location,
+                start: start.map(|start| Box::new(self.lower_expression(*start))),
+                end: end.map(|end| Box::new(self.lower_expression(*end))),
+                kind,
             },
             stellar_ast::Expression::Call {
                 location,
                 callee,
                 arguments,
+                ..
             } => stellar_hir::Expression::Call {
+                hir_id: self.hir_id(node_id, location),
                 location,
                 callee: Box::new(self.lower_expression(*callee)),
                 arguments: arguments
                     .into_iter()
-                    .map(|argument| self.lower_expression(argument))
+                    .map(|argument| self.lower_call_argument(argument))
                     .collect(),
             },
             stellar_ast::Expression::As {
                 location,
                 left,
                 right,
+                ..
             } => stellar_hir::Expression::As {
+                hir_id: self.hir_id(node_id, location),
                 location,
                 left: Box::new(self.lower_expression(*left)),
                 right: self.lower_type(right),
             },
-            stellar_ast::Expression::List { location, elements } => stellar_hir::Expression::List {
+            stellar_ast::Expression::List {
+                location, elements, ..
+            } => stellar_hir::Expression::List {
+                hir_id: self.hir_id(node_id, location),
                 location,
                 elements: elements
                     .into_iter()
-                    .map(|element| self.lower_expression(element))
+                    .map(|element| self.lower_list_element(element))
                     .collect(),
             },
+            stellar_ast::Expression::Borrow {
+                location,
+                is_mutable,
+                inner,
+                ..
+            } => stellar_hir::Expression::Borrow {
+                hir_id: self.hir_id(node_id, location),
+                location,
+                is_mutable,
+                inner: Box::new(if is_mutable {
+                    self.lower_mutated_place(*inner)
+                } else {
+                    self.lower_expression(*inner)
+                }),
+            },
+            stellar_ast::Expression::Map {
+                location, entries, ..
+            } => stellar_hir::Expression::Map {
+                hir_id: self.hir_id(node_id, location),
+                location,
+                entries: entries
+                    .into_iter()
+                    .map(|entry| self.lower_map_entry(entry))
+                    .collect(),
+            },
+            stellar_ast::Expression::ArrayRepeat {
+                location,
+                value,
+                size,
+                ..
+            } => stellar_hir::Expression::ArrayRepeat {
+                hir_id: self.hir_id(node_id, location),
+                location,
+                value: Box::new(self.lower_expression(*value)),
+                size: Box::new(self.lower_expression(*size)),
+            },
             stellar_ast::Expression::FieldAccess {
                 location,
                 left,
                 right,
+                ..
             } => stellar_hir::Expression::FieldAccess {
+                hir_id: self.hir_id(node_id, location),
                 location,
                 left: Box::new(self.lower_expression(*left)),
                 right,
             },
+            stellar_ast::Expression::TupleIndex {
+                location,
+                left,
+                index,
+                index_location,
+                ..
+            } => stellar_hir::Expression::TupleIndex {
+                hir_id: self.hir_id(node_id, location),
+                location,
+                left: Box::new(self.lower_expression(*left)),
+                index,
+                index_location,
+            },
             stellar_ast::Expression::TypeArguments {
                 location,
                 left,
                 arguments,
+                ..
             } => stellar_hir::Expression::TypeArguments {
+                hir_id: self.hir_id(node_id, location),
                 location,
                 left: Box::new(self.lower_expression(*left)),
                 type_arguments: self.lower_type_arguments(arguments),
             },
-            stellar_ast::Expression::StatementsBlock { location, block } => {
-                stellar_hir::Expression::StatementsBlock {
+            stellar_ast::Expression::StatementsBlock {
+                location, block, ..
+            } => stellar_hir::Expression::StatementsBlock {
+                hir_id: self.hir_id(node_id, location),
+                location,
+                block: self.lower_statements_block(block),
+            },
+            stellar_ast::Expression::Await {
+                location, inner, ..
+            } => stellar_hir::Expression::Await {
+                hir_id: self.hir_id(node_id, location),
+                location,
+                inner: Box::new(self.lower_expression(*inner)),
+            },
+            stellar_ast::Expression::Unsafe {
+                location, block, ..
+            } => stellar_hir::Expression::Unsafe {
+                hir_id: self.hir_id(node_id, location),
+                location,
+                block: self.lower_statements_block(block),
+            },
+            stellar_ast::Expression::Try {
+                location, block, ..
+            } => {
+                let hir_id = self.hir_id(node_id, location);
+
+                // `?` operators propagate errors to the block
+                let error_propagation = self.error_propagation.take();
+                let block = self.lower_statements_block(block);
+                self.error_propagation = error_propagation;
+
+                stellar_hir::Expression::Try {
+                    hir_id,
                     location,
-                    block: self.lower_statements_block(block),
+                    block,
                 }
             }
         }
     }
 
+    /// Converts `if let <pattern> = <expression> { ... } else { ... }` into:
+    ///
+    /// ```txt
+    /// match <expression> {
+    ///     <pattern> => { ... },
+    ///     _ => { ... }
+    /// }
+    /// ```
+    fn lower_if_let_expression(
+        &mut self,
+        node_id: NodeId,
+        location: Location,
+        pattern: stellar_ast::Pattern,
+        expression: stellar_ast::Expression,
+        block: Vec<stellar_ast::Statement>,
+        r#else: Option<Vec<stellar_ast::Statement>>,
+    ) -> stellar_hir::Expression {
+        let locals = self.locals.len();
+        self.bind_pattern(&pattern);
+        let block = self.lower_statements_block(block);
+        self.locals.truncate(locals);
+
+        let r#else = r#else.map_or_else(Vec::new, |block| self.lower_statements_block(block));
+
+        self.lower_let_condition(node_id, location, pattern, expression, block, r#else)
+    }
+
+    /// Converts `while let <pattern> = <expression> { ... }` into:
+    ///
+    /// ```txt
+    /// while true {
+    ///     match <expression> {
+    ///         <pattern> => { ... },
+    ///         _ => { break; }
+    ///     }
+    /// }
+    /// ```
+    fn lower_while_let_expression(
+        &mut self,
+        node_id: NodeId,
+        location: Location,
+        pattern: stellar_ast::Pattern,
+        expression: stellar_ast::Expression,
+        statements_block: Vec<stellar_ast::Statement>,
+    ) -> stellar_hir::Expression {
+        let hir_id = self.hir_id(node_id, location);
+        let condition = self.lower_loop_condition(node_id, location, "while");
+
+        let locals = self.locals.len();
+        self.bind_pattern(&pattern);
+        let block = self.lower_statements_block(statements_block);
+        self.locals.truncate(locals);
+        let r#break = stellar_hir::Statement::Break {
+            hir_id: self.hir_id(node_id, location),
+            location,
+        };
+        let r#match =
+            self.lower_let_condition(node_id, location, pattern, expression, block, vec![r#break]);
+
+        stellar_hir::Expression::While {
+            hir_id,
+            location,
+            condition: Box::new(condition),
+            statements_block: vec![stellar_hir::Statement::Expression {
+                hir_id: self.hir_id(node_id, location),
+                expression: r#match,
+                has_semicolon: false,
+            }],
+        }
+    }
+
+    /// Converts `for <pattern> in <iterable> { ... }` into:
+    ///
+    /// ```txt
+    /// {
+    ///     let mut $iterator = <iterable>.iterator();
+    ///
+    ///     while true {
+    ///         match $iterator.next() {
+    ///             Some(<pattern>) => { ... },
+    ///             _ => { break; }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// `$iterator` can't be written in the source code, so it never shadows
+    /// variables of the loop body.
+    fn lower_for_expression(
+        &mut self,
+        node_id: NodeId,
+        location: Location,
+        pattern: stellar_ast::Pattern,
+        iterable: stellar_ast::Expression,
+        statements_block: Vec<stellar_ast::Statement>,
+    ) -> stellar_hir::Expression {
+        if let stellar_ast::Expression::Parenthesized { location, .. } = iterable {
+            self.diagnostics
+                .add_diagnostic(UnnecessaryParenthesizedExpression::new(location));
+        }
+
+        let iterator = IdentifierAST {
+            location,
+            id: IdentifierId::from("$iterator"),
+        };
+
+        let hir_id = self.hir_id(node_id, location);
+        let let_hir_id = self.hir_id(node_id, location);
+
+        let iterable_location = iterable.location();
+        let iterable = self.lower_expression(iterable);
+        let iterator_call = self.method_call(node_id, iterable_location, iterable, ITERATOR);
+
+        let locals = self.locals.len();
+        self.bind_pattern(&pattern);
+
+        let pattern_location = pattern.location();
+        let pattern = stellar_hir::Pattern::TupleLike {
+            location: pattern_location,
+            path: stellar_ast::Path {
+                location: pattern_location,
+                identifiers: vec![IdentifierAST {
+                    location: pattern_location,
+                    id: SOME,
+                }],
+            },
+            inner_patterns: vec![self.lower_pattern(pattern)],
+        };
+
+        let loop_hir_id = self.hir_id(node_id, location);
+        let condition = self.lower_loop_condition(node_id, location, "for");
+
+        let block = self.lower_statements_block(statements_block);
+        self.locals.truncate(locals);
+        let receiver = stellar_hir::Expression::Identifier {
+            hir_id: self.hir_id(node_id, location),
+            identifier: iterator,
+        };
+        let r#match = stellar_hir::Expression::Match {
+            hir_id: self.hir_id(node_id, location),
+            location,
+            expression: Box::new(self.method_call(node_id, location, receiver, NEXT)),
+            block: vec![
+                stellar_hir::MatchExpressionItem {
+                    left: pattern,
+                    right: stellar_hir::Expression::StatementsBlock {
+                        hir_id: self.hir_id(node_id, location),
+                        location,
+                        block,
+                    },
+                },
+                stellar_hir::MatchExpressionItem {
+                    left: stellar_hir::Pattern::Wildcard { location },
+                    right: stellar_hir::Expression::StatementsBlock {
+                        hir_id: self.hir_id(node_id, location),
+                        location,
+                        block: vec![stellar_hir::Statement::Break {
+                            hir_id: self.hir_id(node_id, location),
+                            location,
+                        }],
+                    },
+                },
+            ],
+        };
+
+        stellar_hir::Expression::StatementsBlock {
+            hir_id,
+            location,
+            block: vec![
+                stellar_hir::Statement::Let {
+                    hir_id: let_hir_id,
+                    pattern: stellar_hir::Pattern::Identifier {
+                        location,
+                        binding_mode: stellar_ast::BindingMode::MutableValue,
+                        identifier: iterator,
+                        pattern: None,
+                    },
+                    value: iterator_call,
+                    ty: None,
+                },
+                stellar_hir::Statement::Expression {
+                    hir_id: self.hir_id(node_id, location),
+                    expression: stellar_hir::Expression::While {
+                        hir_id: loop_hir_id,
+                        location,
+                        condition: Box::new(condition),
+                        statements_block: vec![stellar_hir::Statement::Expression {
+                            hir_id: self.hir_id(node_id, location),
+                            expression: r#match,
+                            has_semicolon: false,
+                        }],
+                    },
+                    has_semicolon: false,
+                },
+            ],
+        }
+    }
+
+    /// Builds `true` condition of a `while` loop lowered from a given loop,
+    /// e.g. `loop { ... }`. The condition originates from the loop's keyword.
+    fn lower_loop_condition(
+        &mut self,
+        node_id: NodeId,
+        location: Location,
+        keyword: &str,
+    ) -> stellar_hir::Expression {
+        let keyword_location = Location {
+            end: location.start + keyword.len(),
+            ..location
+        };
+
+        stellar_hir::Expression::Literal {
+            hir_id: self.hir_id(node_id, keyword_location),
+            literal: stellar_ast::Literal::Boolean {
+                value: true,
+                location,
+            },
+        }
+    }
+
+    /// Returns `true` if evaluating a place twice has no side effects, e.g.
+    /// for `a.b.0`, but not for `make().b`.
+    fn can_be_evaluated_twice(place: &stellar_ast::Expression) -> bool {
+        match place {
+            stellar_ast::Expression::Identifier { .. } => true,
+            stellar_ast::Expression::FieldAccess { left, .. }
+            | stellar_ast::Expression::TupleIndex { left, .. }
+            | stellar_ast::Expression::Parenthesized { inner: left, .. } => {
+                Self::can_be_evaluated_twice(left)
+            }
+            _ => false,
+        }
+    }
+
+    /// Converts `<left> <operator>= <right>` into:
+    ///
+    /// ```txt
+    /// <left> = <left> <operator> <right>
+    /// ```
+    ///
+    /// The inner binary expression is marked with `is_compound_assignment`, so
+    /// that the type checker can dispatch it to the compound assignment
+    /// operator overload.
+    ///
+    /// Only places, that [can be evaluated twice](Self::can_be_evaluated_twice),
+    /// are converted, others, e.g. `make().x += 1`, keep the compound operator.
+    fn lower_compound_assignment(
+        &mut self,
+        node_id: NodeId,
+        location: Location,
+        left: stellar_ast::Expression,
+        operator: stellar_ast::BinaryOperator,
+        right: stellar_ast::Expression,
+    ) -> stellar_hir::Expression {
+        let hir_id = self.hir_id(node_id, location);
+        let place = self.lower_mutated_place(left.clone());
+
+        // `<left>` is lowered twice to get distinct HIR IDs, so diagnostics
+        // are reported only for the first copy
+        let operation_hir_id = self.hir_id(node_id, location);
+        let diagnostics = self.diagnostics.diagnostics.len();
+        let left = self.lower_expression(left);
+        self.diagnostics.diagnostics.truncate(diagnostics);
+
+        stellar_hir::Expression::Binary {
+            hir_id,
+            location,
+            left: Box::new(place),
+            operator: stellar_ast::BinaryOperator {
+                raw: stellar_ast::RawBinaryOperator::Eq,
+                location: operator.location,
+            },
+            right: Box::new(stellar_hir::Expression::Binary {
+                hir_id: operation_hir_id,
+                location,
+                left: Box::new(left),
+                operator,
+                right: Box::new(self.lower_expression(right)),
+                is_compound_assignment: true,
+            }),
+            is_compound_assignment: false,
+        }
+    }
+
+    /// Converts `<expression>?` in a function returning `Result` into:
+    ///
+    /// ```txt
+    /// match <expression> {
+    ///     Ok($value) => $value,
+    ///     Err($error) => { return Err($error); }
+    /// }
+    /// ```
+    ///
+    /// and in a function returning `Option` into:
+    ///
+    /// ```txt
+    /// match <expression> {
+    ///     Some($value) => $value,
+    ///     None => { return None; }
+    /// }
+    /// ```
+    fn lower_question_mark(
+        &mut self,
+        node_id: NodeId,
+        location: Location,
+        expression: stellar_ast::Expression,
+        error_propagation: ErrorPropagation,
+    ) -> stellar_hir::Expression {
+        let identifier = |id| IdentifierAST { location, id };
+        let path = |id| stellar_ast::Path {
+            location,
+            identifiers: vec![identifier(id)],
+        };
+        let binding = |id| stellar_hir::Pattern::Identifier {
+            location,
+            binding_mode: stellar_ast::BindingMode::Value,
+            identifier: identifier(id),
+            pattern: None,
+        };
+
+        let hir_id = self.hir_id(node_id, location);
+        let expression = self.lower_expression(expression);
+
+        let value = IdentifierId::from("$value");
+        let (success, failure, residual) = match error_propagation {
+            ErrorPropagation::Result => {
+                let error = IdentifierId::from("$error");
+
+                (
+                    OK,
+                    stellar_hir::Pattern::TupleLike {
+                        location,
+                        path: path(ERR),
+                        inner_patterns: vec![binding(error)],
+                    },
+                    stellar_hir::Expression::Call {
+                        hir_id: self.hir_id(node_id, location),
+                        location,
+                        callee: Box::new(stellar_hir::Expression::Identifier {
+                            hir_id: self.hir_id(node_id, location),
+                            identifier: identifier(ERR),
+                        }),
+                        arguments: vec![stellar_hir::CallArgument {
+                            name: None,
+                            kind: stellar_ast::ElementKind::Single,
+                            value: stellar_hir::Expression::Identifier {
+                                hir_id: self.hir_id(node_id, location),
+                                identifier: identifier(error),
+                            },
+                            is_shorthand: false,
+                        }],
+                    },
+                )
+            }
+            ErrorPropagation::Option => (
+                SOME,
+                stellar_hir::Pattern::Path { path: path(NONE) },
+                stellar_hir::Expression::Identifier {
+                    hir_id: self.hir_id(node_id, location),
+                    identifier: identifier(NONE),
+                },
+            ),
+        };
+
+        stellar_hir::Expression::Match {
+            hir_id,
+            location,
+            expression: Box::new(expression),
+            block: vec![
+                stellar_hir::MatchExpressionItem {
+                    left: stellar_hir::Pattern::TupleLike {
+                        location,
+                        path: path(success),
+                        inner_patterns: vec![binding(value)],
+                    },
+                    right: stellar_hir::Expression::Identifier {
+                        hir_id: self.hir_id(node_id, location),
+                        identifier: identifier(value),
+                    },
+                },
+                stellar_hir::MatchExpressionItem {
+                    left: failure,
+                    right: stellar_hir::Expression::StatementsBlock {
+                        hir_id: self.hir_id(node_id, location),
+                        location,
+                        block: vec![stellar_hir::Statement::Return {
+                            hir_id: self.hir_id(node_id, location),
+                            expression: residual,
+                        }],
+                    },
+                },
+            ],
+        }
+    }
+
+    /// Builds a match expression with two arms: the one for a given pattern and
+    /// the wildcard one.
+    fn lower_let_condition(
+        &mut self,
+        node_id: NodeId,
+        location: Location,
+        pattern: stellar_ast::Pattern,
+        expression: stellar_ast::Expression,
+        block: Vec<stellar_hir::Statement>,
+        otherwise: Vec<stellar_hir::Statement>,
+    ) -> stellar_hir::Expression {
+        if let stellar_ast::Expression::Parenthesized { location, .. } = expression {
+            self.diagnostics
+                .add_diagnostic(UnnecessaryParenthesizedExpression::new(location));
+        }
+
+        stellar_hir::Expression::Match {
+            hir_id: self.hir_id(node_id, location),
+            location,
+            expression: Box::new(self.lower_expression(expression)),
+            block: vec![
+                stellar_hir::MatchExpressionItem {
+                    left: self.lower_pattern(pattern),
+                    right: stellar_hir::Expression::StatementsBlock {
+                        hir_id: self.hir_id(node_id, location),
+                        location,
+                        block,
+                    },
+                },
+                stellar_hir::MatchExpressionItem {
+                    left: stellar_hir::Pattern::Wildcard { location },
+                    right: stellar_hir::Expression::StatementsBlock {
+                        hir_id: self.hir_id(node_id, location),
+                        location,
+                        block: otherwise,
+                    },
+                },
+            ],
+        }
+    }
+
+    /// Builds a call of a method without arguments, e.g. `iter.next()`.
+    fn method_call(
+        &mut self,
+        node_id: NodeId,
+        location: Location,
+        receiver: stellar_hir::Expression,
+        method: IdentifierId,
+    ) -> stellar_hir::Expression {
+        stellar_hir::Expression::Call {
+            hir_id: self.hir_id(node_id, location),
+            location,
+            callee: Box::new(stellar_hir::Expression::FieldAccess {
+                hir_id: self.hir_id(node_id, location),
+                location,
+                left: Box::new(receiver),
+                right: IdentifierAST {
+                    location,
+                    id: method,
+                },
+            }),
+            arguments: vec![],
+        }
+    }
+
     fn lower_match_expression_item(
         &mut self,
         ast: stellar_ast::MatchExpressionItem,
     ) -> stellar_hir::MatchExpressionItem {
         if let stellar_ast::Expression::Parenthesized { location, .. } = ast.right {
-            self.state
-                .diagnostics_mut()
+            self.diagnostics
                 .add_diagnostic(UnnecessaryParenthesizedExpression::new(location));
         }
 
-        stellar_hir::MatchExpressionItem {
+        let locals = self.locals.len();
+        self.bind_pattern(&ast.left);
+
+        let item = stellar_hir::MatchExpressionItem {
             left: self.lower_pattern(ast.left),
             right: self.lower_expression(ast.right),
-        }
+        };
+
+        self.locals.truncate(locals);
+        item
     }
 
+    /// Lowers a field of a struct expression with a given node ID.
     fn lower_struct_field_expression(
         &mut self,
+        node_id: NodeId,
         ast: stellar_ast::StructFieldExpression,
     ) -> stellar_hir::StructExpressionItem {
+        let Some(value) = ast.value else {
+            self.check_shorthand(ast.name);
+            self.use_variable(ast.name, CaptureKind::Reference);
+
+            return stellar_hir::StructExpressionItem {
+                name: ast.name,
+                value: stellar_hir::Expression::Identifier {
+                    hir_id: self.hir_id(node_id, ast.name.location),
+                    identifier: ast.name,
+                },
+                is_shorthand: true,
+            };
+        };
+
+        if let stellar_ast::Expression::Identifier { identifier, .. } = &value {
+            if identifier.id == ast.name.id && self.is_local(identifier.id) {
+                self.diagnostics
+                    .add_diagnostic(RedundantFieldInitializer::new(
+                        Location {
+                            end: identifier.location.end,
+                            ..ast.name.location
+                        },
+                        ast.name.id,
+                    ));
+            }
+        }
+
         stellar_hir::StructExpressionItem {
             name: ast.name,
-            value: ast.value.map(|value| self.lower_expression(value)),
+            value: self.lower_expression(value),
+            is_shorthand: false,
+        }
+    }
+
+    fn lower_call_argument(&mut self, ast: stellar_ast::CallArgument) -> stellar_hir::CallArgument {
+        if let Some(name) = ast.name {
+            if ast.is_shorthand {
+                self.check_shorthand(name);
+            } else if let stellar_ast::Expression::Identifier { identifier, .. } = &ast.value {
+                if identifier.id == name.id && self.is_local(identifier.id) {
+                    self.diagnostics.add_diagnostic(RedundantArgumentValue::new(
+                        Location {
+                            end: identifier.location.end,
+                            ..name.location
+                        },
+                        name.id,
+                    ));
+                }
+            }
+        }
+
+        stellar_hir::CallArgument {
+            name: ast.name,
+            kind: ast.kind,
+            value: self.lower_expression(ast.value),
+            is_shorthand: ast.is_shorthand,
+        }
+    }
+
+    fn lower_map_entry(&mut self, ast: stellar_ast::MapEntry) -> stellar_hir::MapEntry {
+        stellar_hir::MapEntry {
+            key: self.lower_expression(ast.key),
+            value: self.lower_expression(ast.value),
+        }
+    }
+
+    fn lower_list_element(&mut self, ast: stellar_ast::ListElement) -> stellar_hir::ListElement {
+        stellar_hir::ListElement {
+            kind: ast.kind,
+            value: self.lower_expression(ast.value),
+        }
+    }
+
+    fn lower_lambda_captures(
+        &mut self,
+        captures: Vec<stellar_ast::LambdaCapture>,
+    ) -> Vec<stellar_hir::LambdaCapture> {
+        let mut captured = FxHashMap::default();
+
+        for capture in &captures {
+            if let Some(&first_capture_location) = captured.get(&capture.name.id) {
+                self.diagnostics.add_diagnostic(DuplicateLambdaCapture::new(
+                    capture.name.id,
+                    first_capture_location,
+                    capture.location,
+                ));
+            } else {
+                captured.insert(capture.name.id, capture.location);
+            }
         }
+
+        captures
     }
 
     fn lower_lambda_function_parameter(
@@ -703,8 +1981,7 @@ impl<'s> LowerToHir<'s> {
         if_block: (stellar_ast::Expression, Vec<stellar_ast::Statement>),
     ) -> (stellar_hir::Expression, Vec<stellar_hir::Statement>) {
         if let stellar_ast::Expression::Parenthesized { location, .. } = if_block.0 {
-            self.state
-                .diagnostics_mut()
+            self.diagnostics
                 .add_diagnostic(UnnecessaryParenthesizedExpression::new(location));
         }
 
@@ -725,7 +2002,11 @@ impl<'s> LowerToHir<'s> {
         ast: stellar_ast::FunctionSignature,
     ) -> stellar_hir::FunctionSignature {
         stellar_hir::FunctionSignature {
+            attributes: ast.attributes,
             visibility: ast.visibility,
+            is_async: ast.is_async,
+            is_unsafe: ast.is_unsafe,
+            is_generator: ast.is_generator,
             name: ast.name,
             generic_parameters: self.lower_generic_parameters(ast.generic_parameters),
             parameters: ast
@@ -779,6 +2060,7 @@ impl<'s> LowerToHir<'s> {
 
     fn lower_type_alias(&mut self, ast: stellar_ast::TypeAlias) -> stellar_hir::TypeAlias {
         stellar_hir::TypeAlias {
+            attributes: ast.attributes,
             visibility: ast.visibility,
             name: ast.name,
             generic_parameters: self.lower_generic_parameters(ast.generic_parameters),
@@ -787,8 +2069,32 @@ impl<'s> LowerToHir<'s> {
         }
     }
 
+    fn lower_constant(&mut self, ast: stellar_ast::Constant) -> stellar_hir::Constant {
+        stellar_hir::Constant {
+            attributes: ast.attributes,
+            visibility: ast.visibility,
+            name: ast.name,
+            ty: self.lower_type(ast.ty),
+            value: ast.value.map(|value| self.lower_expression(value)),
+            docstring: ast.docstring,
+        }
+    }
+
+    fn lower_static(&mut self, ast: stellar_ast::Static) -> stellar_hir::Static {
+        stellar_hir::Static {
+            attributes: ast.attributes,
+            visibility: ast.visibility,
+            is_mutable: ast.is_mutable,
+            name: ast.name,
+            ty: self.lower_type(ast.ty),
+            value: self.lower_expression(ast.value),
+            docstring: ast.docstring,
+        }
+    }
+
     fn lower_struct_field(&mut self, ast: stellar_ast::StructField) -> stellar_hir::StructField {
         stellar_hir::StructField {
+            attributes: ast.attributes,
             visibility: ast.visibility,
             name: ast.name,
             ty: self.lower_type(ast.ty),
@@ -824,6 +2130,7 @@ impl<'s> LowerToHir<'s> {
                     .map(|interface| self.lower_type_constructor(interface))
                     .collect()
             }),
+            const_type: ast.const_type.map(|ty| self.lower_type(ty)),
             default_value: ast.default_value.map(|ty| self.lower_type(ty)),
         }
     }
@@ -870,6 +2177,25 @@ impl<'s> LowerToHir<'s> {
         stellar_hir::Type::Underscore { location }
     }
 
+    /// `T?` -> `Option[T]`.
+    fn lower_optional_type(
+        &mut self,
+        location: Location,
+        inner: stellar_ast::Type,
+    ) -> stellar_hir::Type {
+        stellar_hir::Type::Constructor(stellar_hir::TypeConstructor {
+            location,
+            path: stellar_ast::Path {
+                location,
+                identifiers: vec![IdentifierAST {
+                    location,
+                    id: OPTION,
+                }],
+            },
+            arguments: vec![self.lower_type(inner)],
+        })
+    }
+
     fn lower_type(&mut self, ast: stellar_ast::Type) -> stellar_hir::Type {
         match ast {
             stellar_ast::Type::Function {
@@ -889,14 +2215,37 @@ impl<'s> LowerToHir<'s> {
             }
             stellar_ast::Type::Parenthesized { inner, .. } => {
                 if let stellar_ast::Type::Parenthesized { location, .. } = *inner {
-                    self.state
-                        .diagnostics_mut()
+                    self.diagnostics
                         .add_diagnostic(UnnecessaryParenthesizedExpression::new(location));
                 }
 
                 self.lower_type(*inner)
             }
             stellar_ast::Type::Underscore { location } => self.lower_underscore_type(location),
+            stellar_ast::Type::Const { location, value } => {
+                stellar_hir::Type::Const { location, value }
+            }
+            stellar_ast::Type::Reference {
+                location,
+                is_mutable,
+                inner,
+            } => stellar_hir::Type::Reference {
+                location,
+                is_mutable,
+                inner: Box::new(self.lower_type(*inner)),
+            },
+            stellar_ast::Type::Array {
+                location,
+                element_type,
+                size,
+            } => stellar_hir::Type::Array {
+                location,
+                element_type: Box::new(self.lower_type(*element_type)),
+                size: Box::new(self.lower_type(*size)),
+            },
+            stellar_ast::Type::Optional { location, inner } => {
+                self.lower_optional_type(location, *inner)
+            }
             stellar_ast::Type::InterfaceObject { location, bounds } => {
                 stellar_hir::Type::InterfaceObject {
                     location,
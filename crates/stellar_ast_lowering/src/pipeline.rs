@@ -0,0 +1,265 @@
+//! Lowering pipeline: desugaring of AST into HIR followed by passes over the
+//! lowered HIR.
+//!
+//! Built-in passes are enabled and disabled with [`Config`]:
+//!
+//! * [`ValidationPass`], enabled by default.
+//! * [`ConstantFoldingPass`], disabled by default.
+//!
+//! Disabling all of them gives "just desugared" HIR. Custom passes implement
+//! [`LoweringPass`] and are registered in the pipeline:
+//!
+//! ```
+//! use stellar_ast_lowering::pipeline::{Config, LoweringPass, LoweringPipeline};
+//! use stellar_database::{PackageData, Path, State};
+//! use stellar_diagnostics::Diagnostics;
+//! use stellar_interner::{IdentifierId, PathId};
+//! use stellar_parser::parse_module;
+//!
+//! /// Removes all items of a module.
+//! struct Strip;
+//!
+//! impl LoweringPass for Strip {
+//!     fn name(&self) -> &str {
+//!         "strip"
+//!     }
+//!
+//!     fn run(&mut self, module: &mut stellar_hir::Module, _diagnostics: &mut Diagnostics) {
+//!         module.items.clear();
+//!     }
+//! }
+//!
+//! let mut state = State::new();
+//! let name = IdentifierId::from("test");
+//! let filepath = PathId::from("test.sr");
+//! let package = PackageData::alloc_in_memory(state.db_mut(), name, filepath);
+//! let parse_result = parse_module(
+//!     &mut state,
+//!     package,
+//!     Path::new(vec![name]),
+//!     filepath,
+//!     "fun main() { break; }",
+//! );
+//! let module = parse_result.module();
+//!
+//! let mut pipeline = LoweringPipeline::new(Config::new().with_validation(false));
+//! pipeline.add_pass(Strip);
+//!
+//! let hir = pipeline.run_all(&mut state, vec![parse_result]);
+//!
+//! assert!(hir[&module].items.is_empty());
+//! // `break` outside of a loop is not reported without validation
+//! assert!(state.diagnostics().is_ok());
+//! ```
+
+use std::fmt::{self, Debug};
+#[cfg(feature = "debug")]
+use std::time::Instant;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use stellar_database::{ModuleId, State};
+use stellar_diagnostics::Diagnostics;
+use stellar_fx_hash::FxHashMap;
+use stellar_parser::ParseResult;
+#[cfg(feature = "debug")]
+use tracing::trace;
+
+use crate::LowerToHir;
+pub use crate::{fold::ConstantFoldingPass, validate::ValidationPass};
+
+/// A pass over lowered HIR, run by [`LoweringPipeline`] on every module after
+/// desugaring.
+pub trait LoweringPass {
+    /// Returns the name of the pass, e.g. `validation`.
+    fn name(&self) -> &str;
+
+    /// Runs the pass on a lowered module.
+    fn run(&mut self, module: &mut stellar_hir::Module, diagnostics: &mut Diagnostics);
+}
+
+/// Built-in passes of [`LoweringPipeline`], that are enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Whether [`ValidationPass`] is run, `true` by default.
+    pub validate: bool,
+
+    /// Whether [`ConstantFoldingPass`] is run, `false` by default.
+    pub fold_constants: bool,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            validate: true,
+            fold_constants: false,
+        }
+    }
+}
+
+impl Config {
+    /// Creates the default config.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether [`ValidationPass`] is run.
+    #[inline]
+    #[must_use]
+    pub const fn with_validation(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Sets whether [`ConstantFoldingPass`] is run.
+    #[inline]
+    #[must_use]
+    pub const fn with_constant_folding(mut self, fold_constants: bool) -> Self {
+        self.fold_constants = fold_constants;
+        self
+    }
+}
+
+/// Desugaring and passes, that lower parsed modules into HIR.
+pub struct LoweringPipeline {
+    passes: Vec<Box<dyn LoweringPass>>,
+}
+
+impl Debug for LoweringPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.pass_names()).finish()
+    }
+}
+
+impl Default for LoweringPipeline {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Config::default())
+    }
+}
+
+impl LoweringPipeline {
+    /// Creates a pipeline with built-in passes enabled in `config`.
+    #[must_use]
+    pub fn new(config: Config) -> Self {
+        let mut pipeline = Self { passes: Vec::new() };
+
+        if config.validate {
+            pipeline.add_pass(ValidationPass);
+        }
+
+        if config.fold_constants {
+            pipeline.add_pass(ConstantFoldingPass);
+        }
+
+        pipeline
+    }
+
+    /// Registers a pass, that is run after all registered ones.
+    #[inline]
+    pub fn add_pass(&mut self, pass: impl LoweringPass + 'static) {
+        self.passes.push(Box::new(pass));
+    }
+
+    /// Registers a pass, that is run at a given position, e.g. `0` for a
+    /// pass, that is run right after desugaring.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than the number of registered passes.
+    #[inline]
+    pub fn insert_pass(&mut self, index: usize, pass: impl LoweringPass + 'static) {
+        self.passes.insert(index, Box::new(pass));
+    }
+
+    /// Returns names of registered passes in the order they are run.
+    #[inline]
+    pub fn pass_names(&self) -> impl Iterator<Item = &str> {
+        self.passes.iter().map(|pass| pass.name())
+    }
+
+    /// Desugars modules into HIR and runs registered passes on every one of
+    /// them.
+    pub fn run_all(
+        &mut self,
+        state: &mut State,
+        modules: Vec<ParseResult>,
+    ) -> FxHashMap<ModuleId, stellar_hir::Module> {
+        let cancellation_token = state.cancellation_token().clone();
+
+        modules
+            .into_iter()
+            .map(|module| {
+                #[cfg(feature = "debug")]
+                let now = Instant::now();
+
+                let (module, mut hir) =
+                    LowerToHir::desugar(state.diagnostics_mut(), &cancellation_token, module);
+
+                for pass in &mut self.passes {
+                    pass.run(&mut hir, state.diagnostics_mut());
+                }
+
+                #[cfg(feature = "debug")]
+                trace!(
+                    "lower_ast(module = '{}') <{} us>",
+                    module.filepath(state.db()),
+                    now.elapsed().as_micros()
+                );
+
+                (module, hir)
+            })
+            .collect()
+    }
+
+    /// Lowers modules of a package like [`LoweringPipeline::run_all`], but
+    /// desugars them in parallel.
+    ///
+    /// Passes are run on the current thread, so they don't have to be thread
+    /// safe. Modules and their diagnostics are returned in the order of
+    /// `modules`, regardless of the order, in which threads finish.
+    pub fn lower_package(
+        &mut self,
+        state: &mut State,
+        modules: Vec<ParseResult>,
+    ) -> Vec<(ModuleId, stellar_hir::Module)> {
+        #[cfg(feature = "debug")]
+        let now = Instant::now();
+
+        let interners = state.interners().clone();
+        let cancellation_token = state.cancellation_token().clone();
+
+        let desugared: Vec<_> = modules
+            .into_par_iter()
+            .map(|module| {
+                // diagnostics are formatted with identifiers interned in the state
+                interners.run(|| {
+                    let mut diagnostics = Diagnostics::new();
+                    let (module, hir) =
+                        LowerToHir::desugar(&mut diagnostics, &cancellation_token, module);
+
+                    (module, hir, diagnostics)
+                })
+            })
+            .collect();
+
+        let lowered = desugared
+            .into_iter()
+            .map(|(module, mut hir, diagnostics)| {
+                state.diagnostics_mut().merge(diagnostics);
+
+                for pass in &mut self.passes {
+                    pass.run(&mut hir, state.diagnostics_mut());
+                }
+
+                (module, hir)
+            })
+            .collect();
+
+        #[cfg(feature = "debug")]
+        trace!("lower_package() <{} us>", now.elapsed().as_micros());
+
+        lowered
+    }
+}
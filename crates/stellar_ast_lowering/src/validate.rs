@@ -0,0 +1,136 @@
+//! Checks structural invariants of HIR, that the parser doesn't enforce:
+//!
+//! * `break` and `continue` statements appear only inside loops. Lambdas and
+//!   deferred blocks can't jump out of loops they are defined in.
+//! * The single expression form of `defer`, e.g. `defer file.close();`, is a
+//!   call.
+//! * Rest patterns appear at most once in a list pattern.
+//!
+//! The pass runs after lowering, so desugared loops, e.g. `for` loops, are
+//! checked like ordinary `while` loops.
+
+use stellar_diagnostics::Diagnostics;
+use stellar_filesystem::location::Location;
+use stellar_hir::{
+    visit::Visitor, Expression, LambdaCapture, LambdaFunctionParameter, Module, Pattern, Statement,
+    Type,
+};
+
+use crate::{
+    diagnostics::{
+        BreakOutsideOfLoop, ContinueOutsideOfLoop, DeferOfNonCall, MultipleRestPatterns,
+    },
+    pipeline::LoweringPass,
+};
+
+/// Checks structural invariants of a lowered module and reports violations
+/// into `diagnostics`.
+pub fn validate(module: &Module, diagnostics: &mut Diagnostics) {
+    HirValidator {
+        diagnostics,
+        loop_depth: 0,
+    }
+    .visit_module(module);
+}
+
+/// Runs [`validate`] in a [`LoweringPipeline`].
+///
+/// [`LoweringPipeline`]: crate::pipeline::LoweringPipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationPass;
+
+impl LoweringPass for ValidationPass {
+    fn name(&self) -> &str {
+        "validation"
+    }
+
+    fn run(&mut self, module: &mut Module, diagnostics: &mut Diagnostics) {
+        validate(module, diagnostics);
+    }
+}
+
+struct HirValidator<'d> {
+    diagnostics: &'d mut Diagnostics,
+
+    /// The number of loops around the statement, that is being checked.
+    loop_depth: usize,
+}
+
+impl HirValidator<'_> {
+    /// Checks a body, that can't break out of loops it is defined in.
+    fn outside_of_loops(&mut self, check: impl FnOnce(&mut Self)) {
+        let loop_depth = std::mem::take(&mut self.loop_depth);
+        check(self);
+        self.loop_depth = loop_depth;
+    }
+}
+
+impl Visitor for HirValidator<'_> {
+    fn visit_break_statement(&mut self, location: Location) {
+        if self.loop_depth == 0 {
+            self.diagnostics
+                .add_diagnostic(BreakOutsideOfLoop::new(location));
+        }
+    }
+
+    fn visit_continue_statement(&mut self, location: Location) {
+        if self.loop_depth == 0 {
+            self.diagnostics
+                .add_diagnostic(ContinueOutsideOfLoop::new(location));
+        }
+    }
+
+    fn visit_defer_statement(&mut self, location: Location, block: &[Statement]) {
+        // `defer f();` is lowered into a block with a single statement, that
+        // shares the location with the defer statement
+        if let [Statement::Expression { expression, .. }] = block {
+            if expression.location() == location && !matches!(expression, Expression::Call { .. }) {
+                self.diagnostics
+                    .add_diagnostic(DeferOfNonCall::new(location));
+            }
+        }
+
+        self.outside_of_loops(|this| this.visit_statements_block(block));
+    }
+
+    fn visit_list_pattern(&mut self, _location: Location, inner_patterns: &[Pattern]) {
+        let mut first_rest_location = None;
+
+        for pattern in inner_patterns {
+            if let Pattern::Rest { location } = pattern {
+                if let Some(first_rest_location) = first_rest_location {
+                    self.diagnostics
+                        .add_diagnostic(MultipleRestPatterns::new(first_rest_location, *location));
+                } else {
+                    first_rest_location = Some(*location);
+                }
+            }
+
+            self.visit_pattern(pattern);
+        }
+    }
+
+    fn visit_while_expression(
+        &mut self,
+        _location: Location,
+        condition: &Expression,
+        statements_block: &[Statement],
+    ) {
+        self.visit_expression(condition);
+
+        self.loop_depth += 1;
+        self.visit_statements_block(statements_block);
+        self.loop_depth -= 1;
+    }
+
+    fn visit_lambda_expression(
+        &mut self,
+        _location: Location,
+        _captures: &[LambdaCapture],
+        _parameters: &[LambdaFunctionParameter],
+        _return_type: Option<&Type>,
+        value: &Expression,
+    ) {
+        self.outside_of_loops(|this| this.visit_expression(value));
+    }
+}
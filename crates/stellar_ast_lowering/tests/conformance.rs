@@ -0,0 +1,1036 @@
+//! Conformance tests for AST lowering.
+//!
+//! Every AST expression, statement and module item variant has at least one
+//! source snippet, that is parsed, lowered and checked against the expected HIR
+//! variant. Variant lists are generated from exhaustive matches, so adding a new
+//! AST variant without updating this file is a compile error, and adding it
+//! without a snippet fails the corresponding test.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use stellar_ast::{Expression as AstExpression, ModuleItem as AstItem, Statement as AstStatement};
+use stellar_ast_lowering::LowerToHir;
+use stellar_database::{PackageData, Path, State};
+use stellar_hir::{
+    pretty::print_expression,
+    visit::{walk_expression, walk_statement, Visitor},
+    CaptureKind, Expression as HirExpression, HirId, ModuleItem as HirItem,
+    Statement as HirStatement,
+};
+use stellar_interner::{IdentifierId, PathId};
+use stellar_parser::parse_module;
+
+/// Generates a list of variant names and a function returning the name of a
+/// given variant. The match is exhaustive, so a missing variant is a compile error.
+macro_rules! variants {
+    ($ty:ident, $all:ident, $name_fn:ident { $($variant:ident),* $(,)? }) => {
+        const $all: &[&str] = &[$(stringify!($variant)),*];
+
+        const fn $name_fn(node: &$ty) -> &'static str {
+            match node {
+                $($ty::$variant { .. } => stringify!($variant),)*
+            }
+        }
+    };
+}
+
+variants! {
+    AstExpression, AST_EXPRESSIONS, ast_expression_name {
+        List, ArrayRepeat, Map, Borrow, As, Loop, Binary, Range, StatementsBlock, Unsafe,
+        Try, Await, Literal, Identifier, Underscore, Parenthesized, If, IfLet, FieldAccess, TupleIndex,
+        Prefix, Postfix,
+        While, WhileLet, For, Call, TypeArguments, Tuple, Struct, Match, Lambda,
+    }
+}
+
+variants! {
+    HirExpression, HIR_EXPRESSIONS, hir_expression_name {
+        List, ArrayRepeat, Map, Borrow, As, Binary, Range, StatementsBlock, Unsafe,
+        Try, Await, Literal, Identifier, Underscore, If, FieldAccess, TupleIndex, Prefix, Postfix,
+        While, Call,
+        TypeArguments, Tuple, Struct, Match, Lambda,
+    }
+}
+
+variants! {
+    AstStatement, AST_STATEMENTS, ast_statement_name {
+        Defer, Expression, Break, Continue, Return, Yield, Let,
+    }
+}
+
+variants! {
+    HirStatement, HIR_STATEMENTS, hir_statement_name {
+        Defer, Expression, Break, Continue, Return, Yield, Let,
+    }
+}
+
+variants! {
+    AstItem, AST_ITEMS, ast_item_name {
+        Constant, Enum, Extern, Function, Impl, Import, Interface, Module, Static,
+        Struct, TupleLikeStruct, TypeAlias,
+    }
+}
+
+variants! {
+    HirItem, HIR_ITEMS, hir_item_name {
+        Constant, Enum, Extern, Function, Impl, Import, Interface, Static, Struct,
+        TupleLikeStruct, TypeAlias,
+    }
+}
+
+/// Expected HIR variant of AST nodes, that are not lowered into HIR nodes.
+const NOT_LOWERED: &str = "<none>";
+
+/// Expression snippets and expected HIR variants.
+const EXPRESSIONS: &[(&str, &str)] = &[
+    ("[1, 2, 3]", "List"),
+    ("[0; 4]", "ArrayRepeat"),
+    ("{\"a\": 1, \"b\": 2}", "Map"),
+    ("&mut a", "Borrow"),
+    ("a as uint8", "As"),
+    ("loop {}", "While"),
+    ("1 + 2", "Binary"),
+    ("a..=b", "Range"),
+    ("{ a++; a }", "StatementsBlock"),
+    ("unsafe { a.read() }", "Unsafe"),
+    ("try { a?.b? }", "Try"),
+    ("fetch(url).await", "Await"),
+    ("\"hello\"", "Literal"),
+    ("foo", "Identifier"),
+    ("_", "Underscore"),
+    ("(a)", "Identifier"),
+    ("(1 + 2)", "Binary"),
+    ("if a { 1 } else { 2 }", "If"),
+    ("if let Some(x) = a { x } else { 0 }", "Match"),
+    ("a.b", "FieldAccess"),
+    ("a.0", "TupleIndex"),
+    ("!a", "Prefix"),
+    ("f()?", "Postfix"),
+    ("while a { }", "While"),
+    ("while let Some(x) = iter.next() { }", "While"),
+    ("for x in [1, 2, 3] { }", "StatementsBlock"),
+    ("f(1, 2)", "Call"),
+    ("f[int32]", "TypeArguments"),
+    ("(1, \"hello\", true)", "Tuple"),
+    ("Person { name, age: 3 }", "Struct"),
+    ("match a { true -> 1, _ -> 2 }", "Match"),
+    ("|a, b: int32| a + b", "Lambda"),
+];
+
+/// Statement snippets and expected HIR variants.
+const STATEMENTS: &[(&str, &str)] = &[
+    ("defer f();", "Defer"),
+    ("defer { f(); g(); }", "Defer"),
+    ("f();", "Expression"),
+    ("break;", "Break"),
+    ("continue;", "Continue"),
+    ("return 1;", "Return"),
+    ("yield 1;", "Yield"),
+    ("let a: int32 = 1;", "Let"),
+];
+
+/// Module item snippets and expected HIR variants.
+const ITEMS: &[(&str, &str)] = &[
+    ("const A: int32 = 1;", "Constant"),
+    ("enum A { B, C(int32), D { e: int32 } fun f() {} }", "Enum"),
+    ("extern \"C\" { fun f(a: int32): int32; }", "Extern"),
+    ("fun f[T](a: T): T where T: Clone { a }", "Function"),
+    ("impl[T] Iterator[T] for A[T] { fun next(self) {} }", "Impl"),
+    ("import std.io;", "Import"),
+    (
+        "interface A: B { const C: int32; fun f(self); }",
+        "Interface",
+    ),
+    ("module a { fun f() {} }", NOT_LOWERED),
+    ("module a;", NOT_LOWERED),
+    ("static mut A: int32 = 1;", "Static"),
+    ("struct A[T] implements B { c: T, fun f() {} }", "Struct"),
+    (
+        "struct A(pub int32, String) { fun f() {} }",
+        "TupleLikeStruct",
+    ),
+    ("type A[T] = List[T];", "TypeAlias"),
+];
+
+/// Parses and lowers a module, panicking with the source if any of the stages fails.
+fn lower(source: &str) -> (stellar_ast::Module, stellar_hir::Module) {
+    lower_in(&mut State::new(), source)
+}
+
+/// Same as [`lower`], but keeps diagnostics of the lowering in a given state.
+fn lower_in(state: &mut State, source: &str) -> (stellar_ast::Module, stellar_hir::Module) {
+    let name = IdentifierId::from("test");
+    let filepath = PathId::from("test.sr");
+    let package = PackageData::alloc_in_memory(state.db_mut(), name, filepath);
+
+    let parse_result = parse_module(state, package, Path::new(vec![name]), filepath, source);
+    let module = parse_result.module();
+    let ast = parse_result.ast().clone();
+
+    assert!(
+        state.diagnostics().is_ok(),
+        "failed to parse `{source}`: {:?}",
+        state.diagnostics()
+    );
+
+    let hir = panic::catch_unwind(AssertUnwindSafe(|| {
+        LowerToHir::run_all(state, vec![parse_result]).remove(&module)
+    }))
+    .unwrap_or_else(|_| panic!("lowering of `{source}` panicked"))
+    .unwrap();
+
+    (ast, hir)
+}
+
+/// Lowers a function with a single statement and returns the statement in AST and HIR.
+fn lower_statement(source: &str) -> (AstStatement, HirStatement) {
+    // `gen` allows `yield` statements
+    let (ast, hir) = lower(&format!("gen fun main() {{ {source} }}"));
+
+    let (AstItem::Function(ast), HirItem::Function(hir)) = (&ast.items[0], &hir.items[0]) else {
+        unreachable!()
+    };
+
+    (
+        ast.body.as_ref().unwrap()[0].clone(),
+        hir.body.as_ref().unwrap()[0].clone(),
+    )
+}
+
+/// Checks snippets against expected HIR variants and returns covered AST variants.
+fn check<A, H>(
+    cases: &[(&'static str, &'static str)],
+    lower: impl Fn(&str) -> (A, H),
+    ast_name: impl Fn(&A) -> &'static str,
+    hir_name: impl Fn(&H) -> &'static str,
+    hir_variants: &[&str],
+) -> Vec<&'static str> {
+    cases
+        .iter()
+        .map(|(source, expected)| {
+            assert!(
+                hir_variants.contains(expected),
+                "`{expected}` is not a HIR variant"
+            );
+
+            let (ast, hir) = lower(source);
+
+            assert_eq!(
+                hir_name(&hir),
+                *expected,
+                "`{source}` is lowered into an unexpected HIR node"
+            );
+
+            ast_name(&ast)
+        })
+        .collect()
+}
+
+/// Asserts that every AST variant is covered by at least one snippet.
+fn assert_covered(kind: &str, all: &[&str], covered: &[&str]) {
+    for variant in all {
+        assert!(
+            covered.contains(variant),
+            "no conformance snippet for the AST {kind} variant `{variant}`"
+        );
+    }
+}
+
+#[test]
+fn expressions() {
+    let covered = check(
+        EXPRESSIONS,
+        |source| {
+            let (AstStatement::Let { value: ast, .. }, HirStatement::Let { value: hir, .. }) =
+                lower_statement(&format!("let _ = {source};"))
+            else {
+                unreachable!()
+            };
+
+            (ast, hir)
+        },
+        ast_expression_name,
+        hir_expression_name,
+        HIR_EXPRESSIONS,
+    );
+
+    assert_covered("expression", AST_EXPRESSIONS, &covered);
+}
+
+#[test]
+fn statements() {
+    let covered = check(
+        STATEMENTS,
+        lower_statement,
+        ast_statement_name,
+        hir_statement_name,
+        HIR_STATEMENTS,
+    );
+
+    assert_covered("statement", AST_STATEMENTS, &covered);
+}
+
+#[test]
+fn items() {
+    let covered = check(
+        ITEMS,
+        |source| {
+            let (mut ast, mut hir) = lower(source);
+
+            (ast.items.remove(0), hir.items.pop())
+        },
+        ast_item_name,
+        |hir| hir.as_ref().map_or(NOT_LOWERED, hir_item_name),
+        &[HIR_ITEMS, &[NOT_LOWERED]].concat(),
+    );
+
+    assert_covered("module item", AST_ITEMS, &covered);
+}
+
+#[test]
+fn yield_outside_of_generator() {
+    let mut state = State::new();
+    lower_in(&mut state, "gen fun a() { yield 1; }");
+
+    assert!(state.diagnostics().is_ok());
+
+    // lambdas inside generators are not generators themselves
+    lower_in(&mut state, "gen fun a() { let f = || { yield 1; }; }");
+
+    assert_eq!(
+        state.diagnostics().diagnostics[0].code.as_deref(),
+        Some("E019")
+    );
+
+    let mut state = State::new();
+    lower_in(&mut state, "fun a() { yield 1; }");
+
+    assert_eq!(state.diagnostics().diagnostics.len(), 1);
+}
+
+#[test]
+fn struct_field_shorthand() {
+    let mut state = State::new();
+    let (_, hir) = lower_in(
+        &mut state,
+        "fun f(a: int32, b: int32, d: int32) { A { a, b: b, c: d }; }",
+    );
+
+    let HirItem::Function(function) = &hir.items[0] else {
+        unreachable!()
+    };
+    let Some(
+        [HirStatement::Expression {
+            expression: HirExpression::Struct { fields, .. },
+            ..
+        }],
+    ) = function.body.as_deref()
+    else {
+        panic!("expected struct expression");
+    };
+
+    assert_eq!(
+        fields
+            .iter()
+            .map(|field| field.is_shorthand)
+            .collect::<Vec<_>>(),
+        [true, false, false]
+    );
+    assert!(matches!(
+        fields[0].value,
+        HirExpression::Identifier { identifier, .. } if identifier == fields[0].name
+    ));
+
+    // only `b: b` can be shortened
+    assert_eq!(state.diagnostics().diagnostics.len(), 1);
+    assert_eq!(
+        state.diagnostics().diagnostics[0].code.as_deref(),
+        Some("W005")
+    );
+}
+
+#[test]
+fn shorthand_call_arguments() {
+    let mut state = State::new();
+    let (_, hir) = lower_in(
+        &mut state,
+        "fun f(x: int32, y: int32, z: int32) { draw(x:, y: y, z: x); }",
+    );
+
+    let HirItem::Function(function) = &hir.items[0] else {
+        unreachable!()
+    };
+    let Some(
+        [HirStatement::Expression {
+            expression: HirExpression::Call { arguments, .. },
+            ..
+        }],
+    ) = function.body.as_deref()
+    else {
+        panic!("expected call expression");
+    };
+
+    assert_eq!(
+        arguments
+            .iter()
+            .map(|argument| argument.is_shorthand)
+            .collect::<Vec<_>>(),
+        [true, false, false]
+    );
+    assert!(matches!(
+        arguments[0].value,
+        HirExpression::Identifier { identifier, .. } if Some(identifier) == arguments[0].name
+    ));
+
+    // only `y: y` can be shortened
+    assert_eq!(state.diagnostics().diagnostics.len(), 1);
+    assert_eq!(
+        state.diagnostics().diagnostics[0].code.as_deref(),
+        Some("W008")
+    );
+}
+
+#[test]
+fn shorthand_of_non_local_variable() {
+    let codes = |source| {
+        let mut state = State::new();
+        lower_in(&mut state, source);
+
+        state
+            .diagnostics()
+            .diagnostics
+            .iter()
+            .filter_map(|diagnostic| diagnostic.code.clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(codes("fun f() { A { a }; }"), ["E034"]);
+    assert_eq!(codes("fun f() { draw(x:); }"), ["E034"]);
+
+    // variables are only visible in their scope
+    assert_eq!(codes("fun f() { { let a = 1; } A { a }; }"), ["E034"]);
+    assert!(codes("fun f() { let a = 1; A { a }; draw(a:); }").is_empty());
+    assert!(codes("fun f() { |a| A { a }; }").is_empty());
+
+    // `a: a` can't be shortened, if `a` is not a local variable, e.g. a constant
+    assert!(codes("const a: int32 = 1; fun f() { A { a: a }; draw(a: a); }").is_empty());
+}
+
+#[test]
+fn optional_type_shorthand() {
+    let (_, hir) = lower("type A = List[int32?]??;");
+
+    let HirItem::TypeAlias(alias) = &hir.items[0] else {
+        unreachable!()
+    };
+
+    /// Returns the type, that `Option[T]` wraps, `None` if the type is not an option.
+    fn unwrap_option(ty: &stellar_hir::Type) -> Option<&stellar_hir::Type> {
+        match ty {
+            stellar_hir::Type::Constructor(constructor)
+                if constructor.path.identifiers.len() == 1
+                    && &*constructor.path.identifiers[0].id.as_str() == "Option" =>
+            {
+                constructor.arguments.first()
+            }
+            _ => None,
+        }
+    }
+
+    let list = unwrap_option(&alias.value)
+        .and_then(unwrap_option)
+        .expect("`T??` must be lowered to `Option[Option[T]]`");
+
+    let stellar_hir::Type::Constructor(list) = list else {
+        panic!("expected `List[int32?]`");
+    };
+
+    assert!(unwrap_option(&list.arguments[0]).is_some());
+}
+
+#[test]
+fn duplicate_lambda_capture() {
+    let mut state = State::new();
+    lower_in(&mut state, "fun f() { |[move a, &b]| a + b; }");
+
+    assert!(state.diagnostics().is_ok());
+
+    lower_in(&mut state, "fun f() { |[move a, &b, &mut a, &a]| a + b; }");
+
+    let codes = state
+        .diagnostics()
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.code.as_deref())
+        .collect::<Vec<_>>();
+
+    assert_eq!(codes, [Some("E020"), Some("E020")]);
+}
+
+#[test]
+fn grouped_or_patterns_are_flattened() {
+    let (_, hir) = lower("fun f() { let (A.B | A.C) | (A.D) = a; }");
+
+    let HirItem::Function(function) = &hir.items[0] else {
+        unreachable!()
+    };
+    let Some(
+        [HirStatement::Let {
+            pattern: stellar_hir::Pattern::Or { alternatives, .. },
+            ..
+        }],
+    ) = function.body.as_deref()
+    else {
+        panic!("expected or pattern");
+    };
+
+    assert_eq!(alternatives.len(), 3);
+    assert!(alternatives
+        .iter()
+        .all(|pattern| matches!(pattern, stellar_hir::Pattern::Path { .. })));
+}
+
+#[test]
+fn defer_call_is_lowered_into_block() {
+    let (_, hir) = lower("fun f() { defer close(); defer { flush(); close(); } }");
+
+    let HirItem::Function(function) = &hir.items[0] else {
+        unreachable!()
+    };
+
+    let lengths = function
+        .body
+        .iter()
+        .flatten()
+        .map(|statement| match statement {
+            HirStatement::Defer { block, .. } => {
+                assert!(block.iter().all(|statement| matches!(
+                    statement,
+                    HirStatement::Expression {
+                        has_semicolon: true,
+                        ..
+                    }
+                )));
+
+                block.len()
+            }
+            _ => panic!("expected defer statement"),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(lengths, [1, 2]);
+}
+
+#[test]
+fn for_loop_is_lowered_into_while_loop() {
+    let (_, hir) = lower("fun f() { for x in list { print(x); } }");
+
+    let HirItem::Function(function) = &hir.items[0] else {
+        unreachable!()
+    };
+    let Some(
+        [HirStatement::Expression {
+            expression: HirExpression::StatementsBlock { block, .. },
+            ..
+        }],
+    ) = function.body.as_deref()
+    else {
+        panic!("expected block");
+    };
+    let [HirStatement::Let {
+        pattern:
+            stellar_hir::Pattern::Identifier {
+                identifier: iterator,
+                ..
+            },
+        value: iterable,
+        ..
+    }, HirStatement::Expression {
+        expression: HirExpression::While {
+            statements_block, ..
+        },
+        ..
+    }] = block.as_slice()
+    else {
+        panic!("expected iterator variable and while loop");
+    };
+
+    /// Returns the receiver of a call of a given method without arguments.
+    fn method_call<'e>(expression: &'e HirExpression, name: &str) -> &'e HirExpression {
+        let HirExpression::Call {
+            callee, arguments, ..
+        } = expression
+        else {
+            panic!("expected method call");
+        };
+        let HirExpression::FieldAccess { left, right, .. } = callee.as_ref() else {
+            panic!("expected method call");
+        };
+
+        assert!(arguments.is_empty());
+        assert_eq!(&*right.id.as_str(), name);
+
+        left
+    }
+
+    let list = method_call(iterable, "iterator");
+    assert!(
+        matches!(list, HirExpression::Identifier { identifier: list, .. } if &*list.id.as_str() == "list")
+    );
+
+    let [HirStatement::Expression {
+        expression: HirExpression::Match {
+            expression, block, ..
+        },
+        ..
+    }] = statements_block.as_slice()
+    else {
+        panic!("expected match expression");
+    };
+
+    let receiver = method_call(expression, "next");
+    assert!(
+        matches!(receiver, HirExpression::Identifier { identifier: receiver, .. } if receiver == iterator)
+    );
+
+    assert!(matches!(
+        &block[0].left,
+        stellar_hir::Pattern::TupleLike { path, inner_patterns, .. }
+            if &*path.identifiers[0].id.as_str() == "Some" && inner_patterns.len() == 1
+    ));
+    assert!(matches!(
+        block[1].left,
+        stellar_hir::Pattern::Wildcard { .. }
+    ));
+}
+
+/// Returns the value of the first `let` statement in the first function.
+fn first_let_value(hir: &stellar_hir::Module) -> &HirExpression {
+    let HirItem::Function(function) = &hir.items[0] else {
+        unreachable!()
+    };
+    let Some([HirStatement::Let { value, .. }, ..]) = function.body.as_deref() else {
+        panic!("expected let statement");
+    };
+
+    value
+}
+
+/// Checks that `?` was lowered into a match expression with given constructors
+/// in patterns of its arms, and returns the residual returned early.
+fn question_mark_match<'a>(
+    expression: &'a HirExpression,
+    success: &str,
+    failure: &str,
+) -> &'a HirExpression {
+    let HirExpression::Match { block, .. } = expression else {
+        panic!("expected match expression, got {expression:?}");
+    };
+    let [success_arm, failure_arm] = block.as_slice() else {
+        panic!("expected two match arms");
+    };
+
+    assert!(matches!(
+        &success_arm.left,
+        stellar_hir::Pattern::TupleLike { path, inner_patterns, .. }
+            if &*path.identifiers[0].id.as_str() == success && inner_patterns.len() == 1
+    ));
+    assert!(matches!(
+        &failure_arm.left,
+        stellar_hir::Pattern::TupleLike { path, .. } | stellar_hir::Pattern::Path { path }
+            if &*path.identifiers[0].id.as_str() == failure
+    ));
+
+    let HirExpression::StatementsBlock { block, .. } = &failure_arm.right else {
+        panic!("expected block");
+    };
+    let [HirStatement::Return { expression, .. }] = block.as_slice() else {
+        panic!("expected return statement");
+    };
+
+    expression
+}
+
+#[test]
+fn question_mark_is_lowered_into_match_in_result_function() {
+    let (_, hir) = lower("fun f(): Result[int32, String] { let a = g()?; Ok(a) }");
+
+    let residual = question_mark_match(first_let_value(&hir), "Ok", "Err");
+
+    assert!(matches!(
+        residual,
+        HirExpression::Call { callee, arguments, .. }
+            if matches!(callee.as_ref(), HirExpression::Identifier { identifier: callee, .. } if &*callee.id.as_str() == "Err")
+                && arguments.len() == 1
+    ));
+}
+
+#[test]
+fn question_mark_is_lowered_into_match_in_option_function() {
+    for source in [
+        "fun f(): Option[int32] { let a = g()?; Some(a) }",
+        "fun f(): int32? { let a = g()?; Some(a) }",
+        "fun f() { let a = |x|: Option[int32] g()?; }",
+    ] {
+        let (_, hir) = lower(source);
+        let mut value = first_let_value(&hir);
+
+        if let HirExpression::Lambda { value: lambda, .. } = value {
+            value = lambda;
+        }
+
+        let residual = question_mark_match(value, "Some", "None");
+
+        assert!(
+            matches!(residual, HirExpression::Identifier { identifier: none, .. } if &*none.id.as_str() == "None"),
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn question_mark_is_kept_when_return_type_is_unknown() {
+    for source in [
+        "fun f(): int32 { let a = g()?; a }",
+        "fun f(): Result[int32, String] { let a = try { g()? }; Ok(0) }",
+        "fun f(): Result[int32, String] { let a = || g()?; Ok(0) }",
+    ] {
+        let (_, hir) = lower(source);
+        let mut value = first_let_value(&hir);
+
+        if let HirExpression::Lambda { value: lambda, .. } = value {
+            value = lambda;
+        }
+
+        if let HirExpression::Try { block, .. } = value {
+            let [HirStatement::Expression { expression, .. }] = block.as_slice() else {
+                panic!("expected expression statement");
+            };
+
+            value = expression;
+        }
+
+        assert!(
+            matches!(value, HirExpression::Postfix { .. }),
+            "{source}: {value:?}"
+        );
+    }
+}
+
+#[test]
+fn compound_assignment_is_lowered_into_assignment() {
+    let (_, hir) = lower_statement("a.b *= c + 1;");
+
+    let HirStatement::Expression {
+        expression:
+            HirExpression::Binary {
+                left: place,
+                operator,
+                right,
+                is_compound_assignment: false,
+                ..
+            },
+        ..
+    } = hir
+    else {
+        panic!("expected assignment");
+    };
+    assert_eq!(operator.raw, stellar_ast::RawBinaryOperator::Eq);
+
+    let HirExpression::Binary {
+        left,
+        operator,
+        right,
+        is_compound_assignment: true,
+        ..
+    } = *right
+    else {
+        panic!("expected compound assignment operation");
+    };
+    assert_eq!(operator.raw, stellar_ast::RawBinaryOperator::Asterisk);
+    assert_eq!(print_expression(&left), print_expression(&place));
+    assert_ne!(left.hir_id(), place.hir_id());
+    assert!(matches!(
+        *right,
+        HirExpression::Binary {
+            is_compound_assignment: false,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn compound_assignment_with_side_effects_is_kept() {
+    for source in ["next().b += 1;", "make(a).b.0 -= 1;", "(f()).x *= 2;"] {
+        let (_, hir) = lower_statement(source);
+
+        let HirStatement::Expression {
+            expression:
+                HirExpression::Binary {
+                    left,
+                    operator,
+                    right,
+                    is_compound_assignment: false,
+                    ..
+                },
+            ..
+        } = hir
+        else {
+            panic!("expected compound assignment: {source}");
+        };
+
+        // the call is evaluated only once
+        assert!(operator.raw.without_assignment().is_some(), "{source}");
+        assert_eq!(print_expression(&left).matches('(').count(), 1, "{source}");
+        assert!(matches!(*right, HirExpression::Literal { .. }), "{source}");
+    }
+}
+
+/// Lowers a module and returns codes of reported diagnostics.
+fn diagnostic_codes(source: &str) -> Vec<String> {
+    let mut state = State::new();
+    lower_in(&mut state, source);
+
+    state
+        .diagnostics()
+        .diagnostics
+        .iter()
+        .filter_map(|diagnostic| diagnostic.code.clone())
+        .collect()
+}
+
+#[test]
+fn break_and_continue_outside_of_loop() {
+    assert!(diagnostic_codes(
+        "fun f() { loop { break; }; while a { continue; }; for x in y { if x { break; } } }"
+    )
+    .is_empty());
+
+    assert_eq!(diagnostic_codes("fun f() { break; }"), ["E026"]);
+    assert_eq!(diagnostic_codes("fun f() { if a { continue; } }"), ["E027"]);
+
+    // lambdas and deferred blocks can't jump out of a loop
+    assert_eq!(
+        diagnostic_codes("fun f() { loop { let g = || { break; }; defer { continue; } } }"),
+        ["E026", "E027"]
+    );
+}
+
+#[test]
+fn defer_of_non_call() {
+    assert!(diagnostic_codes("fun f() { defer close(); defer { a + b; } }").is_empty());
+    assert_eq!(diagnostic_codes("fun f() { defer a + b; }"), ["E028"]);
+}
+
+#[test]
+fn multiple_rest_patterns() {
+    assert!(diagnostic_codes("fun f() { let [a, .., b] = c; let [[..], ..] = d; }").is_empty());
+    assert_eq!(
+        diagnostic_codes("fun f() { let [.., a, .., ..] = c; }"),
+        ["E029", "E029"]
+    );
+}
+
+#[test]
+fn unreachable_statements() {
+    assert!(diagnostic_codes(
+        "fun f() {
+            loop { if a { break; } };
+            loop { while b { break; }; for x in y { break; }; match c { _ -> { break; } } };
+            if d { return 1; };
+            2
+        }"
+    )
+    .is_empty());
+
+    // only the first unreachable statement of a block is reported
+    assert_eq!(
+        diagnostic_codes("fun f() { return 1; a(); b(); }"),
+        ["W006"]
+    );
+    assert_eq!(
+        diagnostic_codes("fun f() { while a { continue; b(); if c { break; d(); } } }"),
+        ["W006", "W006"]
+    );
+
+    // `break` in nested loops, lambdas and deferred blocks doesn't break out of `loop`
+    assert_eq!(
+        diagnostic_codes("fun f() { loop { while a { break; }; let g = || { break; }; }; b(); }"),
+        ["W006", "E026"]
+    );
+}
+
+#[test]
+fn unreachable_statement_location() {
+    let source = "fun f() { loop {}; let x = 1; }";
+
+    let mut state = State::new();
+    lower_in(&mut state, source);
+
+    let labels: Vec<_> = state.diagnostics().diagnostics[0]
+        .labels
+        .iter()
+        .map(|label| &source[label.location.start.0..label.location.end.0])
+        .collect();
+
+    assert_eq!(labels, ["x = 1", "loop"]);
+}
+
+/// Collects HIR IDs of all expressions and statements.
+#[derive(Default)]
+struct HirIdCollector(Vec<HirId>);
+
+impl Visitor for HirIdCollector {
+    fn visit_statement(&mut self, statement: &HirStatement) {
+        self.0.push(statement.hir_id());
+        walk_statement(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &HirExpression) {
+        self.0.push(expression.hir_id());
+        walk_expression(self, expression);
+    }
+}
+
+#[test]
+fn hir_ids_are_unique() {
+    let (_, hir) = lower(
+        "fun f(): Option[int32] {
+            for x in xs { a += x; }
+            while let Some(y) = g()? { defer close(); }
+            Some(S { a }.a)
+        }",
+    );
+
+    let mut collector = HirIdCollector::default();
+    collector.visit_module(&hir);
+
+    let mut ids = collector.0;
+    ids.sort_unstable();
+    ids.dedup();
+
+    assert_eq!(ids.len(), hir.source_map.len());
+    assert!(ids.iter().all(|id| hir.source_map.origin(*id).is_some()));
+}
+
+#[test]
+fn desugared_nodes_point_to_original_syntax() {
+    let source = "fun f() { loop { break; } }";
+    let (ast, hir) = lower(source);
+
+    let (AstItem::Function(ast), HirItem::Function(hir_function)) = (&ast.items[0], &hir.items[0])
+    else {
+        unreachable!()
+    };
+    let AstStatement::Expression {
+        expression: r#loop, ..
+    } = &ast.body.as_ref().unwrap()[0]
+    else {
+        panic!("expected loop");
+    };
+    let HirStatement::Expression {
+        expression: HirExpression::While { condition, .. },
+        ..
+    } = &hir_function.body.as_ref().unwrap()[0]
+    else {
+        panic!("expected while loop");
+    };
+
+    let origin = hir.source_map.origin(condition.hir_id()).unwrap();
+
+    assert_eq!(origin.node_id, r#loop.node_id());
+    assert_eq!(
+        &source[origin.location.start.0..origin.location.end.0],
+        "loop"
+    );
+}
+
+/// Collects variables captured by every lambda in the order of appearance,
+/// e.g. `["&a", "move b"]`.
+#[derive(Default)]
+struct CaptureCollector(Vec<Vec<String>>);
+
+impl Visitor for CaptureCollector {
+    fn visit_expression(&mut self, expression: &HirExpression) {
+        if let HirExpression::Lambda {
+            captured_variables, ..
+        } = expression
+        {
+            self.0.push(
+                captured_variables
+                    .iter()
+                    .map(|variable| {
+                        let kind = match variable.kind {
+                            CaptureKind::Move => "move ",
+                            CaptureKind::Reference => "&",
+                            CaptureKind::MutableReference => "&mut ",
+                        };
+
+                        format!("{kind}{}", variable.name.id)
+                    })
+                    .collect(),
+            );
+        }
+
+        walk_expression(self, expression);
+    }
+}
+
+fn lambda_captures(source: &str) -> Vec<Vec<String>> {
+    let (_, hir) = lower(source);
+
+    let mut collector = CaptureCollector::default();
+    collector.visit_module(&hir);
+    collector.0
+}
+
+#[test]
+fn lambdas_capture_local_variables() {
+    // functions, e.g. `g`, and parameters of lambdas are not captured
+    assert_eq!(
+        lambda_captures("fun f(a: int32, b: int32) { let c = 1; |x| g(a + x + c); |[move b]| b; }"),
+        [vec!["&a", "&c"], vec!["move b"]]
+    );
+    assert_eq!(
+        lambda_captures("struct S { fun f(self) { || self; } }"),
+        [vec!["&self"]]
+    );
+}
+
+#[test]
+fn lambdas_capture_mutated_variables_by_mutable_reference() {
+    assert_eq!(
+        lambda_captures(
+            "fun f() {
+                let mut a = 0; let mut b = (0,); let c = 0; let mut d = 0;
+                || { a += 1; b.0 = 2; c; d++; &mut e; };
+                |[&a]| { a = 1; };
+            }"
+        ),
+        [vec!["&mut a", "&mut b", "&c", "&mut d"], vec!["&a"]]
+    );
+}
+
+#[test]
+fn lambda_captures_respect_scopes() {
+    assert_eq!(
+        lambda_captures(
+            "fun f(a: int32, o: Option[int32]) {
+                || { let a = 1; a };
+                { let b = 1; };
+                || b;
+                if let Some(x) = o { || x; }
+                match o { Some(y) -> || y, _ -> || y }
+            }"
+        ),
+        [vec![], vec![], vec!["&x"], vec!["&y"], vec![]]
+    );
+}
+
+#[test]
+fn nested_lambdas_capture_variables_of_enclosing_lambdas() {
+    assert_eq!(
+        lambda_captures("fun f(a: int32) { |x| |[move x]| a + x; }"),
+        [vec!["&a"], vec!["move x", "&a"]]
+    );
+}
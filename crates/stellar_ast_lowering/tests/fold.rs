@@ -0,0 +1,171 @@
+use stellar_ast_lowering::{fold::fold_constants, LowerToHir};
+use stellar_database::{PackageData, Path, State};
+use stellar_hir::{pretty::print_expression, Expression, ModuleItem, Statement};
+use stellar_interner::{IdentifierId, PathId};
+use stellar_parser::parse_module;
+
+/// Lowers `const A: int64 = <source>;`, folds constants and returns the
+/// constant value with codes and locations of reported diagnostics.
+fn fold(source: &str) -> (Expression, Vec<(String, String)>) {
+    let mut state = State::new();
+    let name = IdentifierId::from("test");
+    let filepath = PathId::from("test.sr");
+    let package = PackageData::alloc_in_memory(state.db_mut(), name, filepath);
+
+    let module_source = format!("const A: int64 = {source};");
+    let parse_result = parse_module(
+        &mut state,
+        package,
+        Path::new(vec![name]),
+        filepath,
+        &module_source,
+    );
+    let module = parse_result.module();
+
+    assert!(
+        state.diagnostics().is_ok(),
+        "failed to parse `{source}`: {:?}",
+        state.diagnostics()
+    );
+
+    let mut hir = LowerToHir::run_all(&mut state, vec![parse_result])
+        .remove(&module)
+        .unwrap();
+    fold_constants(&mut hir, state.diagnostics_mut());
+
+    let diagnostics = state
+        .diagnostics()
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let location = diagnostic.labels[0].location;
+
+            (
+                diagnostic.code.clone().unwrap(),
+                module_source[location.start.0..location.end.0].to_owned(),
+            )
+        })
+        .collect();
+
+    let ModuleItem::Constant(constant) = hir.items.remove(0) else {
+        unreachable!()
+    };
+
+    (constant.value.unwrap(), diagnostics)
+}
+
+fn fold_to_string(source: &str) -> String {
+    let (value, diagnostics) = fold(source);
+    assert!(diagnostics.is_empty(), "`{source}`: {diagnostics:?}");
+
+    print_expression(&value)
+}
+
+#[test]
+fn integer_arithmetic() {
+    assert_eq!(fold_to_string("1 + 2 * 3"), "7");
+    assert_eq!(fold_to_string("(1 + 2) * 3"), "9");
+    assert_eq!(fold_to_string("2 ** 10 - 24"), "1000");
+    assert_eq!(fold_to_string("7 / 2 + 7 % 2"), "4");
+    assert_eq!(fold_to_string("1 << 4 | 1"), "17");
+    assert_eq!(fold_to_string("(12 >> 2) + ~0"), "2");
+    assert_eq!(fold_to_string("1 - 4"), "-3");
+    assert_eq!(fold_to_string("-(2 - 5)"), "3");
+}
+
+#[test]
+fn boolean_expressions() {
+    assert_eq!(fold_to_string("true && false"), "false");
+    assert_eq!(fold_to_string("!false || false"), "true");
+    assert_eq!(fold_to_string("1 + 1 == 2 && 3 > 4"), "false");
+    assert_eq!(fold_to_string("true != (1 <= 0)"), "true");
+}
+
+#[test]
+fn non_constant_expressions_are_kept() {
+    assert_eq!(fold_to_string("a + 1 * 2"), "a + 2");
+    assert_eq!(fold_to_string("1.5 * 2.0"), "1.5 * 2.0");
+    assert_eq!(fold_to_string("1 + true"), "1 + true");
+    assert_eq!(fold_to_string("2 ** -1"), "2 ** -1");
+    assert_eq!(fold_to_string("f(2 * 3, [1 + 1])"), "f(6, [2])");
+}
+
+#[test]
+fn negative_results_get_new_hir_ids() {
+    let (value, _) = fold("a + (2 - 5)");
+
+    let Expression::Binary { right, .. } = value else {
+        unreachable!()
+    };
+    let Expression::Prefix { hir_id, inner, .. } = *right else {
+        panic!("`2 - 5` is not folded into a negative literal")
+    };
+
+    assert_ne!(hir_id, inner.hir_id());
+}
+
+#[test]
+fn overflow() {
+    let (value, diagnostics) = fold("1 + 9223372036854775807 * 2");
+
+    assert_eq!(print_expression(&value), "1 + 9223372036854775807 * 2");
+    assert_eq!(
+        diagnostics,
+        [("E030".to_owned(), "9223372036854775807 * 2".to_owned())]
+    );
+
+    assert_eq!(fold("-9223372036854775808").1, []);
+    assert_eq!(fold("-(-9223372036854775807 - 1)").1.len(), 1);
+    assert_eq!(fold("1 << 64").1.len(), 1);
+}
+
+#[test]
+fn division_by_zero() {
+    let (_, diagnostics) = fold("(1 + 2) / (1 - 1)");
+
+    assert_eq!(
+        diagnostics,
+        [("E031".to_owned(), "(1 + 2) / (1 - 1)".to_owned())]
+    );
+    assert_eq!(fold("1 % 0").1[0].0, "E031");
+}
+
+#[test]
+fn function_bodies_are_folded() {
+    let mut state = State::new();
+    let name = IdentifierId::from("test");
+    let filepath = PathId::from("test.sr");
+    let package = PackageData::alloc_in_memory(state.db_mut(), name, filepath);
+
+    let parse_result = parse_module(
+        &mut state,
+        package,
+        Path::new(vec![name]),
+        filepath,
+        "impl A { fun f() { if 1 < 2 { return |x| x * (3 * 4); } } }",
+    );
+    let module = parse_result.module();
+
+    let mut hir = LowerToHir::run_all(&mut state, vec![parse_result])
+        .remove(&module)
+        .unwrap();
+    fold_constants(&mut hir, state.diagnostics_mut());
+
+    let ModuleItem::Impl(implementation) = &hir.items[0] else {
+        unreachable!()
+    };
+    let Some(
+        [Statement::Expression {
+            expression: expression @ Expression::If { .. },
+            ..
+        }],
+    ) = implementation.methods[0].body.as_deref()
+    else {
+        unreachable!()
+    };
+
+    assert_eq!(
+        print_expression(expression),
+        "if true {\n    return |x| x * 12;\n}"
+    );
+}
@@ -0,0 +1,172 @@
+use std::{cell::RefCell, rc::Rc};
+
+use stellar_ast_lowering::pipeline::{Config, LoweringPass, LoweringPipeline};
+use stellar_database::{PackageData, Path, State};
+use stellar_diagnostics::Diagnostics;
+use stellar_hir::{pretty::print, Module};
+use stellar_interner::{IdentifierId, Interners, PathId};
+use stellar_parser::{parse_module, ParseResult};
+
+/// Lowers a module with a given pipeline and returns the printed HIR with
+/// codes of reported diagnostics.
+fn lower(pipeline: &mut LoweringPipeline, source: &str) -> (String, Vec<String>) {
+    let mut state = State::new();
+    let name = IdentifierId::from("test");
+    let filepath = PathId::from("test.sr");
+    let package = PackageData::alloc_in_memory(state.db_mut(), name, filepath);
+
+    let parse_result = parse_module(&mut state, package, Path::new(vec![name]), filepath, source);
+    let module = parse_result.module();
+
+    let hir = pipeline
+        .run_all(&mut state, vec![parse_result])
+        .remove(&module)
+        .unwrap();
+
+    let codes = state
+        .diagnostics()
+        .diagnostics
+        .iter()
+        .filter_map(|diagnostic| diagnostic.code.clone())
+        .collect();
+
+    (print(&hir).trim().to_owned(), codes)
+}
+
+/// Records names of passes in the order they are run.
+struct Record {
+    name: &'static str,
+    log: Rc<RefCell<Vec<&'static str>>>,
+}
+
+impl LoweringPass for Record {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn run(&mut self, _module: &mut Module, _diagnostics: &mut Diagnostics) {
+        self.log.borrow_mut().push(self.name);
+    }
+}
+
+#[test]
+fn built_in_passes() {
+    assert_eq!(
+        LoweringPipeline::default().pass_names().collect::<Vec<_>>(),
+        ["validation"]
+    );
+    assert_eq!(
+        LoweringPipeline::new(Config::new().with_constant_folding(true))
+            .pass_names()
+            .collect::<Vec<_>>(),
+        ["validation", "constant_folding"]
+    );
+    assert_eq!(
+        LoweringPipeline::new(Config::new().with_validation(false))
+            .pass_names()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn just_desugaring() {
+    let mut pipeline = LoweringPipeline::new(Config::new().with_validation(false));
+
+    let (hir, codes) = lower(&mut pipeline, "fun f() { loop { break; }; 1 + 2; break; }");
+
+    assert_eq!(
+        hir,
+        "fun f() {\n    while true {\n        break;\n    };\n    1 + 2;\n    break;\n}"
+    );
+    assert!(codes.is_empty());
+}
+
+#[test]
+fn all_passes() {
+    let mut pipeline = LoweringPipeline::new(Config::new().with_constant_folding(true));
+
+    let (hir, codes) = lower(&mut pipeline, "fun f() { 1 + 2; break; }");
+
+    assert_eq!(hir, "fun f() {\n    3;\n    break;\n}");
+    assert_eq!(codes, ["E026"]);
+}
+
+#[test]
+fn custom_passes_are_run_in_order() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let record = |name| Record {
+        name,
+        log: Rc::clone(&log),
+    };
+
+    let mut pipeline = LoweringPipeline::default();
+    pipeline.add_pass(record("last"));
+    pipeline.insert_pass(0, record("first"));
+
+    assert_eq!(
+        pipeline.pass_names().collect::<Vec<_>>(),
+        ["first", "validation", "last"]
+    );
+
+    lower(&mut pipeline, "fun a() {}");
+    lower(&mut pipeline, "fun b() {}");
+
+    assert_eq!(*log.borrow(), ["first", "last", "first", "last"]);
+}
+
+#[test]
+fn lower_package_keeps_order_of_modules() {
+    // identifiers in diagnostics, reported on other threads, are resolved
+    // with interners of the state
+    Interners::new().run(|| {
+        let mut state = State::new();
+        let name = IdentifierId::from("test");
+        let package = PackageData::alloc_in_memory(state.db_mut(), name, PathId::from("test.sr"));
+
+        let parse_results: Vec<_> = (0..32)
+            .map(|i| {
+                parse_module(
+                    &mut state,
+                    package,
+                    Path::new(vec![name, IdentifierId::from(format!("m{i}"))]),
+                    PathId::from(format!("m{i}.sr")),
+                    &format!("fun f{i}() {{ |[&x{i}, &x{i}]| x{i}; break; }}"),
+                )
+            })
+            .collect();
+        let modules: Vec<_> = parse_results.iter().map(ParseResult::module).collect();
+
+        let lowered = LoweringPipeline::default().lower_package(&mut state, parse_results);
+
+        assert_eq!(
+            lowered
+                .iter()
+                .map(|(module, _)| *module)
+                .collect::<Vec<_>>(),
+            modules
+        );
+
+        let messages: Vec<_> = state
+            .diagnostics()
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                format!(
+                    "{} {}",
+                    diagnostic.labels[0].location.filepath, diagnostic.message
+                )
+            })
+            .collect();
+        let expected: Vec<_> = (0..32)
+            .flat_map(|i| {
+                [
+                    format!("m{i}.sr `x{i}` is captured multiple times"),
+                    format!("m{i}.sr `break` outside of a loop"),
+                ]
+            })
+            .collect();
+
+        assert_eq!(messages, expected);
+    });
+}
@@ -0,0 +1,345 @@
+//! Append-only storage for database entities, see [`Arena`].
+
+use std::{
+    fmt::{self, Debug},
+    ops::{Index, IndexMut},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, OnceLock,
+    },
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Capacity of the first bucket, every next bucket is twice as large.
+const FIRST_BUCKET_CAPACITY: usize = 8;
+
+/// Number of buckets, which is enough for `8 * (2^32 - 1)` values.
+const BUCKETS: usize = 32;
+
+/// A vector, values of which can be pushed through a shared reference.
+///
+/// Values are stored in buckets, that are never reallocated, so references to
+/// values stay valid while new values are pushed. This allows readers to look
+/// up entities in the database without any locking, while another thread
+/// allocates new ones:
+///
+/// * reading is lock-free: only an atomic length is loaded;
+/// * pushing is serialized by a mutex of the arena itself, so writers only
+///   contend when they allocate entities of the same kind in the same package;
+/// * mutating existing values requires a unique reference.
+pub(crate) struct Arena<T> {
+    buckets: [OnceLock<Box<[OnceLock<T>]>>; BUCKETS],
+
+    /// Number of values, that are initialized and visible to readers.
+    len: AtomicUsize,
+
+    /// Held while a value is pushed.
+    push_lock: Mutex<()>,
+}
+
+/// Returns the bucket and the position in the bucket of a value at a given
+/// index.
+#[inline]
+const fn location_of(idx: usize) -> (usize, usize) {
+    let shifted = idx + FIRST_BUCKET_CAPACITY;
+    let bucket = (usize::BITS - 1 - shifted.leading_zeros()) as usize
+        - FIRST_BUCKET_CAPACITY.trailing_zeros() as usize;
+
+    (bucket, shifted - (FIRST_BUCKET_CAPACITY << bucket))
+}
+
+impl<T> Arena<T> {
+    /// Creates a new empty arena.
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| OnceLock::new()),
+            len: AtomicUsize::new(0),
+            push_lock: Mutex::new(()),
+        }
+    }
+
+    /// Returns the number of values in the arena.
+    #[inline]
+    #[must_use]
+    pub(crate) fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    /// Returns a reference to a value at a given index, if it exists.
+    #[must_use]
+    pub(crate) fn get(&self, idx: usize) -> Option<&T> {
+        if idx >= self.len() {
+            return None;
+        }
+
+        let (bucket, position) = location_of(idx);
+        self.buckets[bucket].get()?[position].get()
+    }
+
+    /// Returns a mutable reference to a value at a given index, if it exists.
+    #[must_use]
+    pub(crate) fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        if idx >= *self.len.get_mut() {
+            return None;
+        }
+
+        let (bucket, position) = location_of(idx);
+        self.buckets[bucket].get_mut()?[position].get_mut()
+    }
+
+    /// Appends a value to the arena and returns its index.
+    ///
+    /// # Panics
+    /// Panics if the arena is full.
+    #[inline]
+    pub(crate) fn push(&self, value: T) -> usize {
+        self.push_with(|_| value)
+    }
+
+    /// Appends a value, created from its future index, to the arena and
+    /// returns the index.
+    ///
+    /// # Panics
+    /// Panics if the arena is full.
+    pub(crate) fn push_with(&self, value: impl FnOnce(usize) -> T) -> usize {
+        // a panicking writer can't leave the arena in an inconsistent state,
+        // so the lock is never considered poisoned
+        let _guard = self
+            .push_lock
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        let idx = self.len.load(Ordering::Relaxed);
+        let (bucket, position) = location_of(idx);
+
+        assert!(bucket < BUCKETS, "arena capacity overflow");
+
+        let slots = self.buckets[bucket].get_or_init(|| {
+            (0..FIRST_BUCKET_CAPACITY << bucket)
+                .map(|_| OnceLock::new())
+                .collect()
+        });
+
+        if slots[position].set(value(idx)).is_err() {
+            unreachable!("slot {idx} of an arena is initialized twice");
+        }
+
+        // publish the value to readers
+        self.len.store(idx + 1, Ordering::Release);
+
+        idx
+    }
+
+    /// Removes values starting from a given index.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        let old_len = *self.len.get_mut();
+
+        for idx in len..old_len {
+            let (bucket, position) = location_of(idx);
+
+            if let Some(slots) = self.buckets[bucket].get_mut() {
+                slots[position].take();
+            }
+        }
+
+        *self.len.get_mut() = len.min(old_len);
+    }
+
+    /// Returns an iterator over values of the arena.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len()).filter_map(|idx| self.get(idx))
+    }
+
+    /// Returns an iterator over mutable references to values of the arena.
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        let len = *self.len.get_mut();
+
+        self.buckets
+            .iter_mut()
+            .filter_map(OnceLock::get_mut)
+            .flat_map(|slots| slots.iter_mut())
+            .take(len)
+            .filter_map(OnceLock::get_mut)
+    }
+
+    /// Consumes the arena and returns its values.
+    #[cfg(test)]
+    #[must_use]
+    pub(crate) fn into_vec(self) -> Vec<T> {
+        let len = self.len.into_inner();
+
+        self.buckets
+            .into_iter()
+            .filter_map(OnceLock::into_inner)
+            .flat_map(|slots| slots.into_vec())
+            .take(len)
+            .filter_map(OnceLock::into_inner)
+            .collect()
+    }
+}
+
+impl<T> Default for Arena<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for Arena<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut arena = Self::new();
+        arena.extend(iter);
+        arena
+    }
+}
+
+impl<T> Extend<T> for Arena<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T> Index<usize> for Arena<T> {
+    type Output = T;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, idx: usize) -> &T {
+        let len = self.len();
+
+        self.get(idx).unwrap_or_else(|| {
+            panic!("index out of bounds: the len is {len} but the index is {idx}")
+        })
+    }
+}
+
+impl<T> IndexMut<usize> for Arena<T> {
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, idx: usize) -> &mut T {
+        let len = *self.len.get_mut();
+
+        self.get_mut(idx).unwrap_or_else(|| {
+            panic!("index out of bounds: the len is {len} but the index is {idx}")
+        })
+    }
+}
+
+impl<T: Debug> Debug for Arena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Arenas are serialized as sequences, just like vectors.
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for Arena<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Arena<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Self::from_iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Barrier, thread};
+
+    use super::{location_of, Arena};
+
+    #[test]
+    fn locations() {
+        assert_eq!(location_of(0), (0, 0));
+        assert_eq!(location_of(7), (0, 7));
+        assert_eq!(location_of(8), (1, 0));
+        assert_eq!(location_of(23), (1, 15));
+        assert_eq!(location_of(24), (2, 0));
+    }
+
+    #[test]
+    fn push_and_get() {
+        let mut arena = Arena::new();
+
+        for value in 0..100 {
+            assert_eq!(arena.push(value), value);
+        }
+
+        assert_eq!(arena.len(), 100);
+        assert_eq!(arena[42], 42);
+        assert_eq!(arena.get(100), None);
+
+        arena[42] = 0;
+        assert_eq!(arena.iter().sum::<usize>(), 4950 - 42);
+
+        for value in arena.iter_mut() {
+            *value += 1;
+        }
+
+        arena.truncate(10);
+        assert_eq!(arena.len(), 10);
+        assert_eq!(arena.get(10), None);
+        assert_eq!(arena.push(0), 10);
+        assert_eq!(arena.into_vec().len(), 11);
+    }
+
+    #[test]
+    fn references_stay_valid_while_pushing() {
+        let arena = Arena::new();
+        arena.push(String::from("first"));
+
+        let first = &arena[0];
+        for idx in 1..1000 {
+            arena.push(idx.to_string());
+        }
+
+        assert_eq!(first, "first");
+        assert_eq!(arena[999], "999");
+    }
+
+    #[test]
+    fn concurrent_readers_and_writers() {
+        const WRITERS: usize = 4;
+        const VALUES: usize = 1000;
+
+        let arena = Arena::new();
+        let barrier = Barrier::new(WRITERS + 1);
+
+        thread::scope(|scope| {
+            for _ in 0..WRITERS {
+                scope.spawn(|| {
+                    barrier.wait();
+
+                    for value in 0..VALUES {
+                        let idx = arena.push(value);
+                        assert_eq!(arena[idx], value);
+                    }
+                });
+            }
+
+            scope.spawn(|| {
+                barrier.wait();
+
+                // every value, that is visible, is fully initialized
+                while arena.len() < WRITERS * VALUES {
+                    assert!(arena.iter().all(|&value| value < VALUES));
+                }
+            });
+        });
+
+        assert_eq!(arena.len(), WRITERS * VALUES);
+        assert_eq!(
+            arena.iter().sum::<usize>(),
+            WRITERS * VALUES * (VALUES - 1) / 2
+        );
+    }
+}
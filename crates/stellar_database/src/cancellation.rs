@@ -0,0 +1,55 @@
+//! Cooperative cancellation of analysis.
+//!
+//! A language server starts analyzing a package after every edit, and a new
+//! edit makes the running analysis useless. Instead of waiting for it to
+//! finish, the server cancels its [`CancellationToken`]. The parser, the
+//! lowering and the analysis passes check the token of the [`State`] at item
+//! boundaries and stop, leaving partial results, which are marked as stale:
+//!
+//! ```
+//! use stellar_database::{cancellation::CancellationToken, State};
+//!
+//! let token = CancellationToken::new();
+//! let state = State::new().with_cancellation_token(token.clone());
+//!
+//! assert!(!state.is_cancelled());
+//!
+//! // e.g. from the main loop of the language server
+//! token.cancel();
+//!
+//! assert!(state.is_cancelled());
+//! ```
+//!
+//! [`State`]: crate::State
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A flag shared between the code running analysis and the code, that may
+/// want to stop it. Clones of the token refer to the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token, that is not cancelled.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation of all work using the token.
+    #[inline]
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if cancellation was requested.
+    #[inline]
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
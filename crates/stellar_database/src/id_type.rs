@@ -58,7 +58,18 @@ macro_rules! id_types {
 
                     #[allow(dead_code)]
                     #[doc = "Returns a mutable reference to [`" [<$what:camel Data>] "`] by its ID ([`" [<$what:camel Id>] "`])."]
+                    ///
+                    /// If there is a [`Snapshot`](crate::snapshot::Snapshot) of the database,
+                    /// the data is copied the first time it is changed, so it can be restored.
                     fn get_data_mut(self, db: &mut Database) -> &mut [<$what:camel Data>] {
+                        if db.should_record_change(stringify!($what), self.package(), self.idx()) {
+                            let original = self.get_data(db).clone();
+
+                            db.record_change(move |db| {
+                                db.package_mut(self.package()).[<$what _>][self.idx() - 1] = original;
+                            });
+                        }
+
                         &mut db.package_mut(self.package()).[<$what _>][self.idx() - 1]
                     }
 
@@ -83,13 +94,38 @@ macro_rules! id_types {
                     /// _This function is automatically generated using a macro!_
                     #[inline]
                     #[must_use]
-                    pub fn [<add_ $what>](&mut self, package: PackageId, data: [<$what:camel Data>]) -> [<$what:camel Id>] {
-                        self.package_mut(package).[<$what _>].push(data);
+                    pub fn [<add_ $what>](&self, package: PackageId, data: [<$what:camel Data>]) -> [<$what:camel Id>] {
+                        let idx = self.package(package).[<$what _>].push(data);
 
-                        [<$what:camel Id>](package, self.package(package).[<$what _>].len())
+                        [<$what:camel Id>](package, idx + 1)
                     }
                 }
             }
         )*
+
+        impl PackageData {
+            /// Returns numbers of entities of every kind in the package.
+            fn entity_counts(&self) -> FxHashMap<&'static str, usize> {
+                let mut counts = FxHashMap::default();
+
+                $(
+                    paste! {
+                        counts.insert(stringify!($what), self.[<$what _>].len());
+                    }
+                )*
+
+                counts
+            }
+
+            /// Removes entities allocated after numbers of entities were
+            /// [`counted`](PackageData::entity_counts).
+            fn truncate_entities(&mut self, counts: &FxHashMap<&'static str, usize>) {
+                $(
+                    paste! {
+                        self.[<$what _>].truncate(counts[stringify!($what)]);
+                    }
+                )*
+            }
+        }
     }
 }
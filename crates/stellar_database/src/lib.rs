@@ -3,27 +3,38 @@
     html_favicon_url = "https://raw.githubusercontent.com/quantumatic/stellar/main/additional/icon/stellar.png"
 )]
 
-use std::{iter, ops::Add};
+use std::{fmt::Display, iter, ops::Add};
 
 use filetime::FileTime;
 use paste::paste;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use stellar_ast::{IdentifierAST, ModuleItemKind, Visibility};
+use stellar_ast::{Attribute, IdentifierAST, ModuleItemKind, Visibility};
 use stellar_diagnostics::Diagnostics;
 use stellar_filesystem::location::{Location, DUMMY_LOCATION};
 use stellar_fx_hash::FxHashMap;
-use stellar_interner::{IdentifierId, PathId};
+use stellar_interner::{IdentifierId, Interners, PathId};
 
+mod arena;
+pub mod cancellation;
 #[macro_use]
 mod id_type;
+pub mod query;
+pub mod remap;
+mod search;
+pub mod snapshot;
 pub mod symbol;
 pub mod ty;
 
+use arena::Arena;
+use cancellation::CancellationToken;
+use query::Queries;
+use snapshot::Journal;
 pub use symbol::Symbol;
 use ty::{Type, TypeConstructor};
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Path {
     segments: Vec<IdentifierId>,
 }
@@ -48,6 +59,20 @@ impl Path {
     }
 }
 
+impl Display for Path {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.segments
+                .iter()
+                .map(|segment| segment.as_str())
+                .collect::<Vec<_>>()
+                .join(".")
+        )
+    }
+}
+
 impl From<IdentifierId> for Path {
     fn from(id: IdentifierId) -> Self {
         Self::new(vec![id])
@@ -92,7 +117,7 @@ impl Add<IdentifierAST> for Path {
 }
 
 /// A data that Stellar compiler has about an enum.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EnumData {
     pub signature: SignatureId,
@@ -104,7 +129,7 @@ impl EnumData {
     /// Creates a new enum data object in the database and returns its ID.
     #[inline]
     #[must_use]
-    pub fn alloc(db: &mut Database, signature: SignatureId) -> EnumId {
+    pub fn alloc(db: &Database, signature: SignatureId) -> EnumId {
         db.add_enum(signature.package(), Self::new(signature))
     }
 
@@ -153,10 +178,29 @@ impl EnumId {
     pub fn add_item(self, db: &mut Database, name: IdentifierId, item: EnumItemId) {
         self.get_data_mut(db).items.insert(name, item);
     }
+
+    /// Returns methods of the enum.
+    #[inline]
+    #[must_use]
+    pub fn methods(self, db: &Database) -> &FxHashMap<IdentifierId, FunctionId> {
+        &self.get_data(db).methods
+    }
+
+    /// Returns a method with a given name.
+    #[inline]
+    pub fn method(self, db: &Database, name: IdentifierId) -> Option<FunctionId> {
+        self.get_data(db).methods.get(&name).copied()
+    }
+
+    /// Adds a method to the enum.
+    #[inline]
+    pub fn add_method(self, db: &mut Database, name: IdentifierId, method: FunctionId) {
+        self.get_data_mut(db).methods.insert(name, method);
+    }
 }
 
 /// A data that Stellar compiler has about a struct.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct StructData {
     pub signature: SignatureId,
@@ -168,7 +212,7 @@ impl StructData {
     /// Creates a new struct data object in the database and returns its ID.
     #[inline]
     #[must_use]
-    pub fn alloc(db: &mut Database, signature: SignatureId) -> StructId {
+    pub fn alloc(db: &Database, signature: SignatureId) -> StructId {
         db.add_struct(signature.package(), Self::new(signature))
     }
 
@@ -198,21 +242,41 @@ impl StructId {
     pub fn fields(self, db: &Database) -> &FxHashMap<IdentifierId, FieldId> {
         &self.get_data(db).fields
     }
+
+    /// Returns methods of the struct.
+    #[inline]
+    #[must_use]
+    pub fn methods(self, db: &Database) -> &FxHashMap<IdentifierId, FunctionId> {
+        &self.get_data(db).methods
+    }
+
+    /// Returns a method with a given name.
+    #[inline]
+    pub fn method(self, db: &Database, name: IdentifierId) -> Option<FunctionId> {
+        self.get_data(db).methods.get(&name).copied()
+    }
+
+    /// Adds a method to the struct.
+    #[inline]
+    pub fn add_method(self, db: &mut Database, name: IdentifierId, method: FunctionId) {
+        self.get_data_mut(db).methods.insert(name, method);
+    }
 }
 
 /// A data that Stellar compiler has about a function.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TupleLikeStructData {
     pub signature: SignatureId,
     pub fields: Vec<(Visibility, Type)>,
+    pub methods: FxHashMap<IdentifierId, FunctionId>,
 }
 
 impl TupleLikeStructData {
     /// Creates a new tuple-like struct data object in the database and returns its ID.
     #[inline]
     #[must_use]
-    pub fn alloc(db: &mut Database, signature: SignatureId) -> TupleLikeStructId {
+    pub fn alloc(db: &Database, signature: SignatureId) -> TupleLikeStructId {
         db.add_tuple_like_struct(signature.package(), Self::new(signature))
     }
 
@@ -223,6 +287,7 @@ impl TupleLikeStructData {
         Self {
             signature,
             fields: Vec::new(),
+            methods: FxHashMap::default(),
         }
     }
 }
@@ -234,10 +299,29 @@ impl TupleLikeStructId {
     pub fn signature(self, db: &Database) -> SignatureId {
         self.get_data(db).signature
     }
+
+    /// Returns methods of the struct.
+    #[inline]
+    #[must_use]
+    pub fn methods(self, db: &Database) -> &FxHashMap<IdentifierId, FunctionId> {
+        &self.get_data(db).methods
+    }
+
+    /// Returns a method with a given name.
+    #[inline]
+    pub fn method(self, db: &Database, name: IdentifierId) -> Option<FunctionId> {
+        self.get_data(db).methods.get(&name).copied()
+    }
+
+    /// Adds a method to the struct.
+    #[inline]
+    pub fn add_method(self, db: &mut Database, name: IdentifierId, method: FunctionId) {
+        self.get_data_mut(db).methods.insert(name, method);
+    }
 }
 
 /// A data that Stellar compiler has about a field.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FieldData {
     pub visibility: Visibility,
@@ -250,7 +334,7 @@ impl FieldData {
     #[inline]
     #[must_use]
     pub fn alloc(
-        db: &mut Database,
+        db: &Database,
         package: PackageId,
         visibility: Visibility,
         name: IdentifierAST,
@@ -272,7 +356,7 @@ impl FieldData {
 }
 
 /// A data that Stellar compiler has about a predicate.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PredicateData {
     pub ty: Type,
@@ -284,7 +368,7 @@ impl PredicateData {
     #[inline]
     #[must_use]
     pub fn alloc(
-        db: &mut Database,
+        db: &Database,
         package: PackageId,
         ty: Type,
         bounds: Vec<TypeConstructor>,
@@ -337,7 +421,7 @@ impl GenericParameterScopeData {
     /// Creates a new generic parameter scope data object in the database and returns its ID.
     #[inline]
     #[must_use]
-    pub fn alloc(db: &mut Database, package: PackageId) -> GenericParameterScopeId {
+    pub fn alloc(db: &Database, package: PackageId) -> GenericParameterScopeId {
         db.add_generic_parameter_scope(package, Self::new(None))
     }
 
@@ -423,6 +507,14 @@ pub struct GenericParameterData {
     /// ```
     pub location: Location,
 
+    /// Type of the const generic parameter.
+    ///
+    /// ```txt
+    /// foo[const N: usize = 4]
+    ///              ^^^^^
+    /// ```
+    pub const_type: Option<Type>,
+
     /// Default value of the generic parameter.
     ///
     /// ```txt
@@ -437,27 +529,36 @@ impl GenericParameterData {
     #[inline]
     #[must_use]
     pub fn alloc(
-        db: &mut Database,
+        db: &Database,
         package: PackageId,
         location: Location,
+        const_type: Option<Type>,
         default_value: Option<Type>,
     ) -> GenericParameterId {
-        db.add_generic_parameter(package, Self::new(location, default_value))
+        db.add_generic_parameter(package, Self::new(location, const_type, default_value))
     }
 
     /// Creates a new generic parameter data object.
     #[inline]
     #[must_use]
-    pub fn new(location: Location, default_value: Option<Type>) -> Self {
+    pub fn new(location: Location, const_type: Option<Type>, default_value: Option<Type>) -> Self {
         Self {
             location,
+            const_type,
             default_value,
         }
     }
+
+    /// Returns `true` if the generic parameter is a const generic parameter.
+    #[inline]
+    #[must_use]
+    pub const fn is_const(&self) -> bool {
+        self.const_type.is_some()
+    }
 }
 
 /// A data that Stellar compiler has about an enum item.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EnumItemData {
     pub enum_: EnumId,
@@ -470,7 +571,7 @@ impl EnumItemData {
     #[inline]
     #[must_use]
     pub fn alloc(
-        db: &mut Database,
+        db: &Database,
         enum_: EnumId,
         name: IdentifierAST,
         module: ModuleId,
@@ -512,9 +613,10 @@ impl EnumItemId {
 }
 
 /// A data that Stellar compiler has about a particular type signature.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SignatureData {
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
     pub node_idx: usize,
@@ -530,7 +632,8 @@ impl SignatureData {
     #[inline]
     #[must_use]
     pub fn alloc(
-        db: &mut Database,
+        db: &Database,
+        attributes: Vec<Attribute>,
         visibility: Visibility,
         name: IdentifierAST,
         node_idx: usize,
@@ -540,7 +643,14 @@ impl SignatureData {
 
         db.add_signature(
             module.package(),
-            Self::new(visibility, name, node_idx, generic_parameter_scope, module),
+            Self::new(
+                attributes,
+                visibility,
+                name,
+                node_idx,
+                generic_parameter_scope,
+                module,
+            ),
         )
     }
 
@@ -548,6 +658,7 @@ impl SignatureData {
     #[inline]
     #[must_use]
     pub fn new(
+        attributes: Vec<Attribute>,
         visibility: Visibility,
         name: IdentifierAST,
         node_idx: usize,
@@ -555,6 +666,7 @@ impl SignatureData {
         module: ModuleId,
     ) -> Self {
         Self {
+            attributes,
             visibility,
             name,
             node_idx,
@@ -582,6 +694,13 @@ impl SignatureId {
         self.get_data(db).visibility
     }
 
+    /// Returns the attributes.
+    #[inline]
+    #[must_use]
+    pub fn attributes(self, db: &Database) -> &[Attribute] {
+        &self.get_data(db).attributes
+    }
+
     /// Returns the module.
     #[inline]
     #[must_use]
@@ -640,25 +759,46 @@ impl SignatureId {
 }
 
 /// A data that Stellar compiler has about a function.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FunctionData {
     pub signature: SignatureId,
+
+    /// ABI of a foreign function declared in an extern block, e.g. `C`.
+    /// `None` for functions defined in Stellar.
+    pub abi: Option<String>,
 }
 
 impl FunctionData {
     /// Creates a new function data object in the database and returns its ID.
     #[inline]
     #[must_use]
-    pub fn alloc(db: &mut Database, signature: SignatureId) -> FunctionId {
+    pub fn alloc(db: &Database, signature: SignatureId) -> FunctionId {
         db.add_function(signature.package(), Self::new(signature))
     }
 
+    /// Creates a new foreign function data object in the database and returns
+    /// its ID.
+    #[inline]
+    #[must_use]
+    pub fn alloc_foreign(db: &Database, signature: SignatureId, abi: String) -> FunctionId {
+        db.add_function(
+            signature.package(),
+            Self {
+                signature,
+                abi: Some(abi),
+            },
+        )
+    }
+
     /// Creates a new function data object.
     #[inline]
     #[must_use]
     pub fn new(signature: SignatureId) -> Self {
-        Self { signature }
+        Self {
+            signature,
+            abi: None,
+        }
     }
 }
 
@@ -669,13 +809,29 @@ impl FunctionId {
     pub fn signature(self, db: &Database) -> SignatureId {
         self.get_data(db).signature
     }
+
+    /// Returns the ABI of a foreign function, `None` if the function is
+    /// defined in Stellar.
+    #[inline]
+    #[must_use]
+    pub fn abi(self, db: &Database) -> Option<&str> {
+        self.get_data(db).abi.as_deref()
+    }
+
+    /// Returns `true` if the function is declared in an extern block.
+    #[inline]
+    #[must_use]
+    pub fn is_foreign(self, db: &Database) -> bool {
+        self.get_data(db).abi.is_some()
+    }
 }
 
 /// A data that Stellar compiler has about an interface.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct InterfaceData {
     pub signature: SignatureId,
+    pub constants: FxHashMap<IdentifierId, ConstantId>,
     pub methods: FxHashMap<IdentifierId, FunctionId>,
 }
 
@@ -683,7 +839,7 @@ impl InterfaceData {
     /// Creates a new interface data object in the database and returns its ID.
     #[inline]
     #[must_use]
-    pub fn alloc(db: &mut Database, signature: SignatureId) -> InterfaceId {
+    pub fn alloc(db: &Database, signature: SignatureId) -> InterfaceId {
         db.add_interface(signature.package(), Self::new(signature))
     }
 
@@ -693,6 +849,7 @@ impl InterfaceData {
     pub fn new(signature: SignatureId) -> Self {
         Self {
             signature,
+            constants: FxHashMap::default(),
             methods: FxHashMap::default(),
         }
     }
@@ -705,10 +862,48 @@ impl InterfaceId {
     pub fn signature(self, db: &Database) -> SignatureId {
         self.get_data(db).signature
     }
+
+    /// Returns associated constants of the interface.
+    #[inline]
+    #[must_use]
+    pub fn constants(self, db: &Database) -> &FxHashMap<IdentifierId, ConstantId> {
+        &self.get_data(db).constants
+    }
+
+    /// Returns an associated constant with a given name.
+    #[inline]
+    pub fn constant(self, db: &Database, name: IdentifierId) -> Option<ConstantId> {
+        self.get_data(db).constants.get(&name).copied()
+    }
+
+    /// Adds an associated constant to the interface.
+    #[inline]
+    pub fn add_constant(self, db: &mut Database, name: IdentifierId, constant: ConstantId) {
+        self.get_data_mut(db).constants.insert(name, constant);
+    }
+
+    /// Returns methods of the interface.
+    #[inline]
+    #[must_use]
+    pub fn methods(self, db: &Database) -> &FxHashMap<IdentifierId, FunctionId> {
+        &self.get_data(db).methods
+    }
+
+    /// Returns a method with a given name.
+    #[inline]
+    pub fn method(self, db: &Database, name: IdentifierId) -> Option<FunctionId> {
+        self.get_data(db).methods.get(&name).copied()
+    }
+
+    /// Adds a method to the interface.
+    #[inline]
+    pub fn add_method(self, db: &mut Database, name: IdentifierId, method: FunctionId) {
+        self.get_data_mut(db).methods.insert(name, method);
+    }
 }
 
 /// A data that Stellar compiler has about a module.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TypeAliasData {
     pub signature: SignatureId,
@@ -719,7 +914,7 @@ impl TypeAliasData {
     /// Creates a new type alias data object in the database and returns its ID.
     #[inline]
     #[must_use]
-    pub fn alloc(db: &mut Database, signature: SignatureId) -> TypeAliasId {
+    pub fn alloc(db: &Database, signature: SignatureId) -> TypeAliasId {
         db.add_type_alias(signature.package(), Self::new(signature))
     }
 
@@ -754,8 +949,223 @@ impl TypeAliasId {
     }
 }
 
+/// A data that Stellar compiler has about a constant.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConstantData {
+    pub signature: SignatureId,
+    pub has_value: bool,
+    pub ty: Type,
+}
+
+impl ConstantData {
+    /// Creates a new constant data object in the database and returns its ID.
+    #[inline]
+    #[must_use]
+    pub fn alloc(db: &Database, signature: SignatureId, has_value: bool) -> ConstantId {
+        db.add_constant(signature.package(), Self::new(signature, has_value))
+    }
+
+    /// Creates a new constant data object.
+    #[inline]
+    #[must_use]
+    pub fn new(signature: SignatureId, has_value: bool) -> Self {
+        Self {
+            signature,
+            has_value,
+            ty: Type::Unknown,
+        }
+    }
+}
+
+impl ConstantId {
+    /// Returns the signature of the constant.
+    #[inline]
+    #[must_use]
+    pub fn signature(self, db: &Database) -> SignatureId {
+        self.get_data(db).signature
+    }
+
+    /// Returns `true` if the constant has a value. Associated constants of
+    /// interfaces without a value must be defined by every implementation.
+    #[inline]
+    #[must_use]
+    pub fn has_value(self, db: &Database) -> bool {
+        self.get_data(db).has_value
+    }
+
+    /// Returns the type of the constant.
+    #[inline]
+    #[must_use]
+    pub fn ty(self, db: &Database) -> &Type {
+        &self.get_data(db).ty
+    }
+
+    /// Sets the type of the constant.
+    #[inline]
+    pub fn set_type(self, db: &mut Database, ty: Type) {
+        self.get_data_mut(db).ty = ty;
+    }
+}
+
+/// A data that Stellar compiler has about a static item.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StaticData {
+    pub signature: SignatureId,
+    pub is_mutable: bool,
+    pub ty: Type,
+}
+
+impl StaticData {
+    /// Creates a new static data object in the database and returns its ID.
+    #[inline]
+    #[must_use]
+    pub fn alloc(db: &Database, signature: SignatureId, is_mutable: bool) -> StaticId {
+        db.add_static(signature.package(), Self::new(signature, is_mutable))
+    }
+
+    /// Creates a new static data object.
+    #[inline]
+    #[must_use]
+    pub fn new(signature: SignatureId, is_mutable: bool) -> Self {
+        Self {
+            signature,
+            is_mutable,
+            ty: Type::Unknown,
+        }
+    }
+}
+
+impl StaticId {
+    /// Returns the signature of the static item.
+    #[inline]
+    #[must_use]
+    pub fn signature(self, db: &Database) -> SignatureId {
+        self.get_data(db).signature
+    }
+
+    /// Returns `true` if the static item is declared with `static mut`.
+    #[inline]
+    #[must_use]
+    pub fn is_mutable(self, db: &Database) -> bool {
+        self.get_data(db).is_mutable
+    }
+
+    /// Returns the type of the static item.
+    #[inline]
+    #[must_use]
+    pub fn ty(self, db: &Database) -> &Type {
+        &self.get_data(db).ty
+    }
+
+    /// Sets the type of the static item.
+    #[inline]
+    pub fn set_type(self, db: &mut Database, ty: Type) {
+        self.get_data_mut(db).ty = ty;
+    }
+}
+
+/// A data that Stellar compiler has about an implementation block.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ImplData {
+    pub module: ModuleId,
+    pub node_idx: usize,
+
+    /// The type, methods of which are defined in the block.
+    pub ty: Symbol,
+
+    /// The implemented interface, `None` for inherent implementations.
+    pub interface: Option<InterfaceId>,
+    pub methods: FxHashMap<IdentifierId, FunctionId>,
+}
+
+impl ImplData {
+    /// Creates a new implementation block data object in the database and returns its ID.
+    #[inline]
+    #[must_use]
+    pub fn alloc(
+        db: &Database,
+        module: ModuleId,
+        node_idx: usize,
+        ty: Symbol,
+        interface: Option<InterfaceId>,
+    ) -> ImplId {
+        db.add_impl(module.package(), Self::new(module, node_idx, ty, interface))
+    }
+
+    /// Creates a new implementation block data object.
+    #[inline]
+    #[must_use]
+    pub fn new(
+        module: ModuleId,
+        node_idx: usize,
+        ty: Symbol,
+        interface: Option<InterfaceId>,
+    ) -> Self {
+        Self {
+            module,
+            node_idx,
+            ty,
+            interface,
+            methods: FxHashMap::default(),
+        }
+    }
+}
+
+impl ImplId {
+    /// Returns the module, in which the implementation block is defined.
+    #[inline]
+    #[must_use]
+    pub fn module(self, db: &Database) -> ModuleId {
+        self.get_data(db).module
+    }
+
+    /// Returns the index of the implementation block in the items of its
+    /// module.
+    #[inline]
+    #[must_use]
+    pub fn node_idx(self, db: &Database) -> usize {
+        self.get_data(db).node_idx
+    }
+
+    /// Returns the type, methods of which are defined in the block.
+    #[inline]
+    #[must_use]
+    pub fn ty(self, db: &Database) -> Symbol {
+        self.get_data(db).ty
+    }
+
+    /// Returns the implemented interface, `None` for inherent implementations.
+    #[inline]
+    #[must_use]
+    pub fn interface(self, db: &Database) -> Option<InterfaceId> {
+        self.get_data(db).interface
+    }
+
+    /// Returns methods of the implementation block.
+    #[inline]
+    #[must_use]
+    pub fn methods(self, db: &Database) -> &FxHashMap<IdentifierId, FunctionId> {
+        &self.get_data(db).methods
+    }
+
+    /// Returns a method with a given name.
+    #[inline]
+    pub fn method(self, db: &Database, name: IdentifierId) -> Option<FunctionId> {
+        self.get_data(db).methods.get(&name).copied()
+    }
+
+    /// Adds a method to the implementation block.
+    #[inline]
+    pub fn add_method(self, db: &mut Database, name: IdentifierId, method: FunctionId) {
+        self.get_data_mut(db).methods.insert(name, method);
+    }
+}
+
 /// A data that Stellar compiler has about a module.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ModuleData {
     pub path: Path,
@@ -763,13 +1173,28 @@ pub struct ModuleData {
     pub module_item_symbols: FxHashMap<IdentifierId, Symbol>,
     pub submodules: FxHashMap<IdentifierId, ModuleId>,
     pub resolved_imports: FxHashMap<IdentifierId, Symbol>,
+    pub reexports: FxHashMap<IdentifierId, Reexport>,
+    pub impls: Vec<ImplId>,
+}
+
+/// A name re-exported by a module using a non-private import, e.g.
+/// `pub import self.utils.fs.File;`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Reexport {
+    /// The re-exported symbol.
+    pub symbol: Symbol,
+
+    /// Visibility of the import, which is used instead of the visibility of
+    /// the symbol, when the symbol is accessed through the module.
+    pub visibility: Visibility,
 }
 
 impl ModuleData {
     /// Creates a new module data object in the database and returns its ID.
     #[inline]
     #[must_use]
-    pub fn alloc(db: &mut Database, package: PackageId, path: Path, filepath: PathId) -> ModuleId {
+    pub fn alloc(db: &Database, package: PackageId, path: Path, filepath: PathId) -> ModuleId {
         db.add_module(package, Self::new(path, filepath))
     }
 
@@ -782,7 +1207,9 @@ impl ModuleData {
             filepath,
             submodules: FxHashMap::default(),
             resolved_imports: FxHashMap::default(),
+            reexports: FxHashMap::default(),
             module_item_symbols: FxHashMap::default(),
+            impls: Vec::new(),
         }
     }
 }
@@ -918,11 +1345,75 @@ impl ModuleId {
         &self.get_data(db).resolved_imports
     }
 
+    /// Returns the symbol imported into the module under a given name.
+    #[inline]
+    #[must_use]
+    pub fn resolved_import_or_none(self, db: &Database, name: IdentifierId) -> Option<Symbol> {
+        self.resolved_imports(db).get(&name).copied()
+    }
+
     /// Adds a resolved import to the module.
     #[inline]
     pub fn add_resolved_import(self, db: &mut Database, name: IdentifierId, symbol: Symbol) {
         self.get_data_mut(db).resolved_imports.insert(name, symbol);
     }
+
+    /// Returns names re-exported by the module.
+    #[inline]
+    #[must_use]
+    pub fn reexports(self, db: &Database) -> &FxHashMap<IdentifierId, Reexport> {
+        &self.get_data(db).reexports
+    }
+
+    /// Returns the name re-exported by the module under a given name.
+    #[inline]
+    #[must_use]
+    pub fn reexport_or_none(self, db: &Database, name: IdentifierId) -> Option<Reexport> {
+        self.reexports(db).get(&name).copied()
+    }
+
+    /// Adds a re-exported name to the module.
+    #[inline]
+    pub fn add_reexport(self, db: &mut Database, name: IdentifierId, reexport: Reexport) {
+        self.get_data_mut(db).reexports.insert(name, reexport);
+    }
+
+    /// Returns implementation blocks defined in the module.
+    #[inline]
+    #[must_use]
+    pub fn impls(self, db: &Database) -> &[ImplId] {
+        &self.get_data(db).impls
+    }
+
+    /// Adds an implementation block to the module.
+    #[inline]
+    pub fn add_impl(self, db: &mut Database, impl_: ImplId) {
+        self.get_data_mut(db).impls.push(impl_);
+    }
+
+    /// Returns IDs of all functions defined in the module, including methods.
+    ///
+    /// See [`Database::functions()`] for more details.
+    pub fn functions(self, db: &Database) -> impl Iterator<Item = FunctionId> + '_ {
+        db.functions(self.package())
+            .filter(move |function| function.signature(db).module(db) == self)
+    }
+
+    /// Returns IDs of all structs defined in the module.
+    ///
+    /// See [`Database::structs()`] for more details.
+    pub fn structs(self, db: &Database) -> impl Iterator<Item = StructId> + '_ {
+        db.structs(self.package())
+            .filter(move |struct_| struct_.signature(db).module(db) == self)
+    }
+
+    /// Returns IDs of signatures of all items defined in the module.
+    ///
+    /// See [`Database::signatures()`] for more details.
+    pub fn signatures(self, db: &Database) -> impl Iterator<Item = SignatureId> + '_ {
+        db.signatures(self.package())
+            .filter(move |signature| signature.module(db) == self)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -938,6 +1429,12 @@ impl PackageId {
         db.packages[self.0 - 1].name
     }
 
+    #[inline]
+    #[must_use]
+    pub fn path(self, db: &Database) -> PathId {
+        db.packages[self.0 - 1].path
+    }
+
     #[inline]
     #[must_use]
     pub fn parent(self, db: &Database) -> Option<PackageId> {
@@ -986,12 +1483,23 @@ impl PackageId {
     pub fn set_root_module(self, db: &mut Database, module: ModuleId) {
         db.packages[self.0 - 1].root_module = module;
     }
+
+    /// Adds a dependency to the package.
+    pub fn add_dependency(self, db: &mut Database, name: IdentifierId, dependency: PackageId) {
+        db.packages[self.0 - 1]
+            .dependencies
+            .insert(name, dependency);
+    }
 }
 
 /// The information Stellar compiler has about a particular package.
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PackageData {
+    /// The ID of the package in the database it was allocated in, used to
+    /// remap IDs when the package is moved to another database.
+    id: PackageId,
+
     #[allow(dead_code)]
     name: IdentifierId,
 
@@ -1027,22 +1535,50 @@ pub struct PackageData {
 
     /// The time of the last modification of the package folder.
     #[allow(dead_code)]
+    #[cfg_attr(feature = "serde", serde(with = "file_time"))]
     last_modification_time: Option<FileTime>,
 
     // Information about all package-related compiler entities.
-    module_: Vec<ModuleData>,
-    enum_: Vec<EnumData>,
-    enum_item_: Vec<EnumItemData>,
-    predicate_: Vec<PredicateData>,
-    struct_: Vec<StructData>,
-    tuple_like_struct_: Vec<TupleLikeStructData>,
-    field_: Vec<FieldData>,
-    function_: Vec<FunctionData>,
-    interface_: Vec<InterfaceData>,
-    type_alias_: Vec<TypeAliasData>,
-    generic_parameter_scope_: Vec<GenericParameterScopeData>,
-    generic_parameter_: Vec<GenericParameterData>,
-    signature_: Vec<SignatureData>,
+    module_: Arena<ModuleData>,
+    enum_: Arena<EnumData>,
+    enum_item_: Arena<EnumItemData>,
+    predicate_: Arena<PredicateData>,
+    struct_: Arena<StructData>,
+    tuple_like_struct_: Arena<TupleLikeStructData>,
+    field_: Arena<FieldData>,
+    function_: Arena<FunctionData>,
+    interface_: Arena<InterfaceData>,
+    type_alias_: Arena<TypeAliasData>,
+    constant_: Arena<ConstantData>,
+    static_: Arena<StaticData>,
+    impl_: Arena<ImplData>,
+    generic_parameter_scope_: Arena<GenericParameterScopeData>,
+    generic_parameter_: Arena<GenericParameterData>,
+    signature_: Arena<SignatureData>,
+}
+
+/// Serializes [`FileTime`] as seconds and nanoseconds since the Unix epoch,
+/// because `filetime` doesn't support `serde`.
+#[cfg(feature = "serde")]
+mod file_time {
+    use filetime::FileTime;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S>(time: &Option<FileTime>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        time.map(|time| (time.unix_seconds(), time.nanoseconds()))
+            .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<Option<FileTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<(i64, u32)>::deserialize(deserializer)?
+            .map(|(seconds, nanoseconds)| FileTime::from_unix_time(seconds, nanoseconds)))
+    }
 }
 
 /// Returns the last modification time of a folder with a given path.
@@ -1054,32 +1590,50 @@ fn last_modification_time_of(path: PathId) -> Option<FileTime> {
 }
 
 impl PackageData {
-    pub fn alloc(db: &mut Database, name: IdentifierId, path: PathId) -> PackageId {
+    pub fn alloc(db: &Database, name: IdentifierId, path: PathId) -> PackageId {
         let last_modification_time = last_modification_time_of(path);
 
-        db.packages.push(Self {
+        Self::alloc_with_modification_time(db, name, path, last_modification_time)
+    }
+
+    /// Allocates a package that doesn't exist on disk, without accessing the filesystem.
+    pub fn alloc_in_memory(db: &Database, name: IdentifierId, path: PathId) -> PackageId {
+        Self::alloc_with_modification_time(db, name, path, None)
+    }
+
+    fn alloc_with_modification_time(
+        db: &Database,
+        name: IdentifierId,
+        path: PathId,
+        last_modification_time: Option<FileTime>,
+    ) -> PackageId {
+        let idx = db.packages.push_with(|idx| Self {
+            id: PackageId(idx + 1),
             name,
             path,
             last_modification_time,
             root_module: DUMMY_MODULE_ID,
             parent: None,
             dependencies: FxHashMap::default(),
-            module_: Vec::new(),
-            enum_: Vec::new(),
-            enum_item_: Vec::new(),
-            predicate_: Vec::new(),
-            struct_: Vec::new(),
-            tuple_like_struct_: Vec::new(),
-            field_: Vec::new(),
-            function_: Vec::new(),
-            interface_: Vec::new(),
-            type_alias_: Vec::new(),
-            generic_parameter_scope_: Vec::new(),
-            generic_parameter_: Vec::new(),
-            signature_: Vec::new(),
+            module_: Arena::new(),
+            enum_: Arena::new(),
+            enum_item_: Arena::new(),
+            predicate_: Arena::new(),
+            struct_: Arena::new(),
+            tuple_like_struct_: Arena::new(),
+            field_: Arena::new(),
+            function_: Arena::new(),
+            interface_: Arena::new(),
+            type_alias_: Arena::new(),
+            constant_: Arena::new(),
+            static_: Arena::new(),
+            impl_: Arena::new(),
+            generic_parameter_scope_: Arena::new(),
+            generic_parameter_: Arena::new(),
+            signature_: Arena::new(),
         });
 
-        PackageId(db.packages.len())
+        PackageId(idx + 1)
     }
 
     #[inline]
@@ -1114,9 +1668,24 @@ impl From<Vec<u8>> for PackageData {
 }
 
 /// Storage for Stellar compiler entities.
+///
+/// Entities are allocated through a shared reference: entities of every kind
+/// in every package are stored in a separate append-only arena, so workers can
+/// look up entities (e.g. resolve signatures) on several threads, while
+/// another thread allocates new ones, without locking the whole database.
+/// Changing entities, that are already allocated, still requires a unique
+/// reference.
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Database {
-    packages: Vec<PackageData>,
+    packages: Arena<PackageData>,
+
+    /// Locations, where symbols are referenced, see [`Symbol::references()`].
+    references: FxHashMap<Symbol, Vec<Location>>,
+
+    /// Changes made since active snapshots were taken, see [`snapshot`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    journals: Vec<Journal>,
 }
 
 impl Database {
@@ -1154,17 +1723,136 @@ impl Database {
     pub fn package_mut_or_none(&mut self, id: PackageId) -> Option<&mut PackageData> {
         self.packages.get_mut(id.0 - 1)
     }
+
+    /// Records that a symbol is referenced at a given location.
+    #[inline]
+    pub fn add_reference(&mut self, symbol: Symbol, location: Location) {
+        self.references.entry(symbol).or_default().push(location);
+
+        self.record_change(move |db| {
+            let references = db.references.get_mut(&symbol).unwrap();
+            references.pop();
+
+            if references.is_empty() {
+                db.references.remove(&symbol);
+            }
+        });
+    }
+
+    /// Returns IDs of all functions of a package, including methods and
+    /// foreign functions, in the order they were allocated.
+    ///
+    /// The iterator doesn't borrow the database, so functions can be changed
+    /// while iterating. Functions allocated after the call are not included.
+    ///
+    /// ```
+    /// use stellar_ast::{dummy_identifier, Visibility};
+    /// use stellar_database::{Database, FunctionData, ModuleData, PackageData, Path, SignatureData};
+    /// use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+    ///
+    /// let db = Database::new();
+    /// let package = PackageData::alloc_in_memory(&db, IdentifierId::from("test"), DUMMY_PATH_ID);
+    /// let module = ModuleData::alloc(&db, package, Path::from(IdentifierId::from("test")), DUMMY_PATH_ID);
+    ///
+    /// let signature = SignatureData::alloc(
+    ///     &db,
+    ///     vec![],
+    ///     Visibility::Private,
+    ///     dummy_identifier!(IdentifierId::from("main")),
+    ///     0,
+    ///     module,
+    /// );
+    /// let main = FunctionData::alloc(&db, signature);
+    ///
+    /// assert_eq!(db.functions(package).collect::<Vec<_>>(), [main]);
+    /// assert_eq!(module.functions(&db).collect::<Vec<_>>(), [main]);
+    /// assert_eq!(db.structs(package).count(), 0);
+    /// assert_eq!(db.all_signatures().collect::<Vec<_>>(), [signature]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the package is not present in the database.
+    pub fn functions(&self, package: PackageId) -> impl Iterator<Item = FunctionId> {
+        let len = self.package(package).function_.len();
+
+        (1..=len).map(move |idx| FunctionId::new(package, idx))
+    }
+
+    /// Returns IDs of all structs of a package in the order they were
+    /// allocated, see [`Database::functions()`] for more details.
+    ///
+    /// # Panics
+    /// Panics if the package is not present in the database.
+    pub fn structs(&self, package: PackageId) -> impl Iterator<Item = StructId> {
+        let len = self.package(package).struct_.len();
+
+        (1..=len).map(move |idx| StructId::new(package, idx))
+    }
+
+    /// Returns IDs of signatures of all items of a package in the order they
+    /// were allocated, see [`Database::functions()`] for more details.
+    ///
+    /// # Panics
+    /// Panics if the package is not present in the database.
+    pub fn signatures(&self, package: PackageId) -> impl Iterator<Item = SignatureId> {
+        let len = self.package(package).signature_.len();
+
+        (1..=len).map(move |idx| SignatureId::new(package, idx))
+    }
+
+    /// Returns IDs of signatures of all items in all packages of the
+    /// database, see [`Database::functions()`] for more details.
+    pub fn all_signatures(&self) -> impl Iterator<Item = SignatureId> {
+        let packages = self
+            .packages
+            .iter()
+            .enumerate()
+            .map(|(idx, package)| (PackageId(idx + 1), package.signature_.len()))
+            .collect::<Vec<_>>();
+
+        packages
+            .into_iter()
+            .flat_map(|(package, len)| (1..=len).map(move |idx| SignatureId::new(package, idx)))
+    }
 }
 
 /// Contains database and diagnostics.
-#[derive(Default)]
+#[derive(Debug)]
 pub struct State {
     db: Database,
     diagnostics: Diagnostics,
     config: Config,
+
+    /// Checked at item boundaries to stop analysis early, see [`cancellation`].
+    cancellation_token: CancellationToken,
+
+    /// Interners used to create identifiers and paths stored in the database.
+    ///
+    /// Owned by the state to make sure that they live as long as the database.
+    interners: Interners,
+
+    /// Memoized results of analysis, see [`query`].
+    ///
+    /// `None` if results are not memoized, e.g. in one-shot runs, which would
+    /// only pay for copies of values, that are never reused.
+    queries: Option<Queries>,
+}
+
+impl Default for State {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            db: Database::default(),
+            diagnostics: Diagnostics::default(),
+            config: Config::default(),
+            cancellation_token: CancellationToken::default(),
+            interners: Interners::current(),
+            queries: None,
+        }
+    }
 }
 
-#[derive(Default)]
+#[derive(Default, Debug)]
 pub struct Config {}
 
 impl Config {
@@ -1176,7 +1864,8 @@ impl Config {
 }
 
 impl State {
-    /// Creates a new empty state.
+    /// Creates a new empty state, using interners current on this thread
+    /// (see [`Interners::current`]).
     #[inline]
     #[must_use]
     pub fn new() -> Self {
@@ -1191,6 +1880,57 @@ impl State {
         self
     }
 
+    /// Builds a new state with given interners.
+    ///
+    /// The interners must be entered (see [`Interners::enter`]) while working with the state.
+    #[inline]
+    #[must_use]
+    pub fn with_interners(mut self, interners: Interners) -> Self {
+        self.interners = interners;
+        self
+    }
+
+    /// Builds a new state, which analysis can be cancelled with a given token.
+    #[inline]
+    #[must_use]
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = token;
+        self
+    }
+
+    /// Builds a new state with given memoized queries, e.g. ones of a previous
+    /// analysis run, so that values, which inputs didn't change, are reused.
+    ///
+    /// Results of analysis are only memoized, if the state is built with
+    /// queries (they can be empty).
+    #[inline]
+    #[must_use]
+    pub fn with_queries(mut self, queries: Queries) -> Self {
+        self.queries = Some(queries);
+        self
+    }
+
+    /// Returns the token used to cancel analysis.
+    #[inline]
+    #[must_use]
+    pub const fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation_token
+    }
+
+    /// Returns `true` if analysis was cancelled, so its results are partial.
+    #[inline]
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation_token.is_cancelled()
+    }
+
+    /// Returns interners used by the state.
+    #[inline]
+    #[must_use]
+    pub const fn interners(&self) -> &Interners {
+        &self.interners
+    }
+
     /// Returns a reference to config.
     #[inline]
     #[must_use]
@@ -1219,6 +1959,25 @@ impl State {
         self.db
     }
 
+    /// Returns an immutable reference to memoized queries, or `None` if results
+    /// of analysis are not memoized, see [`State::with_queries`].
+    #[inline]
+    #[must_use]
+    pub const fn queries(&self) -> Option<&Queries> {
+        self.queries.as_ref()
+    }
+
+    /// Returns a mutable reference to memoized queries, which is needed to
+    /// set inputs and to compute values of queries, or `None` if results of
+    /// analysis are not memoized, see [`State::with_queries`].
+    ///
+    /// See [`query`] for more details.
+    #[inline]
+    #[must_use]
+    pub fn queries_mut(&mut self) -> Option<&mut Queries> {
+        self.queries.as_mut()
+    }
+
     /// Returns an immutable reference to diagnostics.
     #[inline]
     #[must_use]
@@ -1255,5 +2014,8 @@ id_types! {
     function,
     interface,
     type_alias,
+    constant,
+    static,
+    impl,
     module
 }
@@ -0,0 +1,459 @@
+//! Memoized queries with dependency tracking.
+//!
+//! Analysis results, that are expensive to compute, are expressed as
+//! [`Query`]s: pure functions of [`Input`]s and other queries. [`Queries`]
+//! memoizes their values and records, which inputs and queries every value
+//! was computed from. When an input changes, only values, that depend on it,
+//! are recomputed, and if a recomputed value is the same as before, values
+//! depending on it are reused as is.
+//!
+//! For example, editing a function body changes its source, but not its
+//! signature, so everything computed from the signature stays valid:
+//!
+//! ```
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! use stellar_database::query::{Input, Queries, Query};
+//!
+//! /// Source of a function with a given name.
+//! struct FunctionSource;
+//!
+//! impl Input for FunctionSource {
+//!     type Key = &'static str;
+//!     type Value = String;
+//! }
+//!
+//! /// Signature of a function: everything before its body.
+//! struct Signature;
+//!
+//! impl Query for Signature {
+//!     type Key = &'static str;
+//!     type Value = String;
+//!
+//!     fn execute(queries: &mut Queries, name: &&'static str) -> String {
+//!         let source = queries.input::<FunctionSource>(name);
+//!
+//!         source[..source.find('{').unwrap()].trim().to_owned()
+//!     }
+//! }
+//!
+//! static ARITY_EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+//!
+//! /// Number of parameters of a function.
+//! struct Arity;
+//!
+//! impl Query for Arity {
+//!     type Key = &'static str;
+//!     type Value = usize;
+//!
+//!     fn execute(queries: &mut Queries, name: &&'static str) -> usize {
+//!         ARITY_EXECUTIONS.fetch_add(1, Ordering::Relaxed);
+//!
+//!         let signature = queries.get::<Signature>(name);
+//!         let parameters = &signature[signature.find('(').unwrap() + 1..signature.find(')').unwrap()];
+//!
+//!         parameters.split(',').filter(|parameter| !parameter.trim().is_empty()).count()
+//!     }
+//! }
+//!
+//! let mut queries = Queries::new();
+//! queries.set::<FunctionSource>("f", "fun f(a: int32, b: int32) { a }".to_owned());
+//!
+//! assert_eq!(queries.get::<Arity>(&"f"), 2);
+//!
+//! // the signature is recomputed, but it didn't change, so the arity is reused
+//! queries.set::<FunctionSource>("f", "fun f(a: int32, b: int32) { b }".to_owned());
+//!
+//! assert_eq!(queries.get::<Arity>(&"f"), 2);
+//! assert_eq!(ARITY_EXECUTIONS.load(Ordering::Relaxed), 1);
+//!
+//! queries.set::<FunctionSource>("f", "fun f(a: int32) { a }".to_owned());
+//!
+//! assert_eq!(queries.get::<Arity>(&"f"), 1);
+//! assert_eq!(ARITY_EXECUTIONS.load(Ordering::Relaxed), 2);
+//! ```
+//!
+//! Values are validated lazily: setting an input only bumps the current
+//! [`Revision`], and a memoized value is checked against the revisions, in
+//! which its dependencies last changed, the next time it is requested.
+//!
+//! Every [`State`](crate::State) owns its queries, see
+//! [`State::queries_mut`](crate::State::queries_mut).
+
+use std::{
+    any::{type_name, Any, TypeId},
+    fmt::{self, Debug},
+    hash::Hash,
+    sync::Arc,
+};
+
+use stellar_fx_hash::{FxHashMap, FxHashSet};
+
+/// A point in time of [`Queries`], which is advanced every time an input
+/// changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Revision(pub u64);
+
+/// A value set from outside of the queries, e.g. contents of a source file.
+pub trait Input: 'static {
+    type Key: Clone + Eq + Hash + Send + Sync + 'static;
+    type Value: Clone + PartialEq + Send + Sync + 'static;
+}
+
+/// A value computed from inputs and other queries, which is memoized by
+/// [`Queries`].
+///
+/// [`Query::execute`] must be a pure function of inputs and queries it reads
+/// through `queries`: anything else it depends on is not tracked, so the
+/// memoized value is not recomputed, when it changes.
+pub trait Query: 'static {
+    type Key: Clone + Eq + Hash + Send + Sync + 'static;
+    type Value: Clone + PartialEq + Send + Sync + 'static;
+
+    /// Computes the value of the query for a given key.
+    fn execute(queries: &mut Queries, key: &Self::Key) -> Self::Value;
+}
+
+/// An input or a query, that a memoized value was computed from.
+#[derive(Clone)]
+struct Dependency {
+    key: Arc<dyn Any + Send + Sync>,
+
+    /// Returns the revision, in which the value of the dependency last
+    /// changed, recomputing it if needed.
+    changed_at: fn(&mut Queries, &dyn Any) -> Revision,
+}
+
+struct InputStorage<I: Input> {
+    /// Values together with revisions, in which they were set.
+    values: FxHashMap<I::Key, (I::Value, Revision)>,
+}
+
+struct Memo<V> {
+    value: V,
+
+    /// The last revision, in which the value was known to be up to date.
+    verified_at: Revision,
+
+    /// The revision, in which the value last changed.
+    changed_at: Revision,
+
+    dependencies: Arc<[Dependency]>,
+}
+
+struct QueryStorage<Q: Query> {
+    memos: FxHashMap<Q::Key, Memo<Q::Value>>,
+
+    /// Keys, which values are being computed, used to detect cycles.
+    in_progress: FxHashSet<Q::Key>,
+}
+
+/// Inputs and memoized values of queries. See [module level docs](self) for
+/// more details.
+#[derive(Default)]
+pub struct Queries {
+    revision: Revision,
+    storages: FxHashMap<TypeId, Box<dyn Any + Send + Sync>>,
+
+    /// Dependencies of queries being executed, the innermost one is the last.
+    active: Vec<Vec<Dependency>>,
+}
+
+impl Debug for Queries {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Queries")
+            .field("revision", &self.revision)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Queries {
+    /// Creates an empty storage of queries.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current revision.
+    #[inline]
+    #[must_use]
+    pub const fn revision(&self) -> Revision {
+        self.revision
+    }
+
+    /// Sets the value of an input. If the value differs from the previous
+    /// one, starts a new revision, so values depending on the input are
+    /// validated again, when they are requested.
+    pub fn set<I: Input>(&mut self, key: I::Key, value: I::Value) {
+        let revision = Revision(self.revision.0 + 1);
+        let storage = self.input_storage_mut::<I>();
+
+        if storage
+            .values
+            .get(&key)
+            .is_some_and(|(previous, _)| *previous == value)
+        {
+            return;
+        }
+
+        storage.values.insert(key, (value, revision));
+        self.revision = revision;
+    }
+
+    /// Returns the value of an input and records it as a dependency of the
+    /// query being executed.
+    ///
+    /// # Panics
+    /// Panics if the input is not set.
+    pub fn input<I: Input>(&mut self, key: &I::Key) -> I::Value {
+        let Some((value, _)) = self.input_storage_mut::<I>().values.get(key) else {
+            panic!("input `{}` is not set", type_name::<I>());
+        };
+        let value = value.clone();
+
+        self.record(Dependency {
+            key: Arc::new(key.clone()),
+            changed_at: Self::input_changed_at::<I>,
+        });
+
+        value
+    }
+
+    /// Returns the value of a query, computing it only if it is not memoized
+    /// or its dependencies changed, and records it as a dependency of the
+    /// query being executed.
+    ///
+    /// # Panics
+    /// Panics if computing the value requires the value itself.
+    pub fn get<Q: Query>(&mut self, key: &Q::Key) -> Q::Value {
+        let (value, _) = self.fetch::<Q>(key);
+
+        self.record(Dependency {
+            key: Arc::new(key.clone()),
+            changed_at: Self::query_changed_at::<Q>,
+        });
+
+        value
+    }
+
+    /// Returns an up to date value of a query with the revision, in which it
+    /// last changed.
+    fn fetch<Q: Query>(&mut self, key: &Q::Key) -> (Q::Value, Revision) {
+        let revision = self.revision;
+
+        if let Some(memo) = self.query_storage_mut::<Q>().memos.get(key) {
+            if memo.verified_at == revision {
+                return (memo.value.clone(), memo.changed_at);
+            }
+
+            let verified_at = memo.verified_at;
+            let dependencies = Arc::clone(&memo.dependencies);
+
+            if dependencies
+                .iter()
+                .all(|dependency| (dependency.changed_at)(self, &*dependency.key) <= verified_at)
+            {
+                let memo = self.query_storage_mut::<Q>().memos.get_mut(key).unwrap();
+                memo.verified_at = revision;
+
+                return (memo.value.clone(), memo.changed_at);
+            }
+        }
+
+        assert!(
+            self.query_storage_mut::<Q>()
+                .in_progress
+                .insert(key.clone()),
+            "cycle detected when computing `{}`",
+            type_name::<Q>()
+        );
+
+        self.active.push(Vec::new());
+        let value = Q::execute(self, key);
+        let dependencies = self.active.pop().unwrap();
+
+        let storage = self.query_storage_mut::<Q>();
+        storage.in_progress.remove(key);
+
+        // values depending on the query are reused, if the value is the same
+        let changed_at = match storage.memos.get(key) {
+            Some(memo) if memo.value == value => memo.changed_at,
+            _ => revision,
+        };
+
+        storage.memos.insert(
+            key.clone(),
+            Memo {
+                value: value.clone(),
+                verified_at: revision,
+                changed_at,
+                dependencies: dependencies.into(),
+            },
+        );
+
+        (value, changed_at)
+    }
+
+    fn record(&mut self, dependency: Dependency) {
+        if let Some(dependencies) = self.active.last_mut() {
+            dependencies.push(dependency);
+        }
+    }
+
+    fn input_changed_at<I: Input>(&mut self, key: &dyn Any) -> Revision {
+        let key = key.downcast_ref::<I::Key>().unwrap();
+
+        self.input_storage_mut::<I>().values[key].1
+    }
+
+    fn query_changed_at<Q: Query>(&mut self, key: &dyn Any) -> Revision {
+        let key = key.downcast_ref::<Q::Key>().unwrap();
+
+        self.fetch::<Q>(key).1
+    }
+
+    fn input_storage_mut<I: Input>(&mut self) -> &mut InputStorage<I> {
+        self.storages
+            .entry(TypeId::of::<I>())
+            .or_insert_with(|| {
+                Box::new(InputStorage::<I> {
+                    values: FxHashMap::default(),
+                })
+            })
+            .downcast_mut()
+            .unwrap()
+    }
+
+    fn query_storage_mut<Q: Query>(&mut self) -> &mut QueryStorage<Q> {
+        self.storages
+            .entry(TypeId::of::<Q>())
+            .or_insert_with(|| {
+                Box::new(QueryStorage::<Q> {
+                    memos: FxHashMap::default(),
+                    in_progress: FxHashSet::default(),
+                })
+            })
+            .downcast_mut()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::{Input, Queries, Query, Revision};
+
+    /// Source of a module with a given name: its functions, one per line, in
+    /// the form `name(calls, ...)`.
+    struct Source;
+
+    impl Input for Source {
+        type Key = &'static str;
+        type Value = String;
+    }
+
+    /// Names of functions defined in a module.
+    struct FunctionNames;
+
+    static FUNCTION_NAMES_EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+    impl Query for FunctionNames {
+        type Key = &'static str;
+        type Value = Vec<String>;
+
+        fn execute(queries: &mut Queries, module: &&'static str) -> Vec<String> {
+            FUNCTION_NAMES_EXECUTIONS.fetch_add(1, Ordering::Relaxed);
+
+            queries
+                .input::<Source>(module)
+                .lines()
+                .map(|line| line[..line.find('(').unwrap()].to_owned())
+                .collect()
+        }
+    }
+
+    /// Number of functions defined in all given modules.
+    struct FunctionCount;
+
+    static FUNCTION_COUNT_EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+    impl Query for FunctionCount {
+        type Key = Vec<&'static str>;
+        type Value = usize;
+
+        fn execute(queries: &mut Queries, modules: &Vec<&'static str>) -> usize {
+            FUNCTION_COUNT_EXECUTIONS.fetch_add(1, Ordering::Relaxed);
+
+            modules
+                .iter()
+                .map(|module| queries.get::<FunctionNames>(module).len())
+                .sum()
+        }
+    }
+
+    /// A query, that depends on itself.
+    struct Cycle;
+
+    impl Query for Cycle {
+        type Key = ();
+        type Value = ();
+
+        fn execute(queries: &mut Queries, (): &()) {
+            queries.get::<Self>(&());
+        }
+    }
+
+    // tests run in parallel, so only one of them executes queries, that
+    // count their executions
+
+    #[test]
+    fn values_are_memoized_and_recomputed_only_when_needed() {
+        let mut queries = Queries::new();
+        queries.set::<Source>("a", "f()\ng(f)".to_owned());
+        queries.set::<Source>("b", "h()".to_owned());
+
+        let modules = vec!["a", "b"];
+        assert_eq!(queries.get::<FunctionCount>(&modules), 3);
+        assert_eq!(queries.get::<FunctionCount>(&modules), 3);
+        assert_eq!(FUNCTION_NAMES_EXECUTIONS.load(Ordering::Relaxed), 2);
+        assert_eq!(FUNCTION_COUNT_EXECUTIONS.load(Ordering::Relaxed), 1);
+
+        // a function body changed, but names of functions are the same
+        queries.set::<Source>("a", "f()\ng()".to_owned());
+        assert_eq!(queries.get::<FunctionCount>(&modules), 3);
+        assert_eq!(FUNCTION_NAMES_EXECUTIONS.load(Ordering::Relaxed), 3);
+        assert_eq!(FUNCTION_COUNT_EXECUTIONS.load(Ordering::Relaxed), 1);
+
+        queries.set::<Source>("b", "h()\ni()".to_owned());
+        assert_eq!(queries.get::<FunctionCount>(&modules), 4);
+        assert_eq!(FUNCTION_NAMES_EXECUTIONS.load(Ordering::Relaxed), 4);
+        assert_eq!(FUNCTION_COUNT_EXECUTIONS.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn setting_the_same_value_keeps_the_revision() {
+        let mut queries = Queries::new();
+        assert_eq!(queries.revision(), Revision(0));
+
+        queries.set::<Source>("c", "f()".to_owned());
+        queries.set::<Source>("c", "f()".to_owned());
+        assert_eq!(queries.revision(), Revision(1));
+
+        queries.set::<Source>("c", "g()".to_owned());
+        assert_eq!(queries.revision(), Revision(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "is not set")]
+    fn missing_input() {
+        Queries::new().input::<Source>(&"missing");
+    }
+
+    #[test]
+    #[should_panic(expected = "cycle detected")]
+    fn cycle() {
+        Queries::new().get::<Cycle>(&());
+    }
+}
@@ -0,0 +1,365 @@
+//! Moves packages between databases.
+//!
+//! Every ID stored in the database contains the ID of the package it belongs
+//! to, so a package loaded from a cache, that was built in another workspace,
+//! can't be added to the database as is: its package ID may already be taken.
+//! [`Database::import_package`] assigns the package a fresh ID and replaces
+//! IDs of the package itself and of its dependencies in all of its data.
+//!
+//! Identifiers and paths don't need remapping: they are serialized as strings
+//! and interned again when the package is deserialized, so a package can be
+//! loaded with any `serde` format, that supports internally tagged enums.
+
+use std::fmt::{self, Display};
+
+use stellar_fx_hash::FxHashMap;
+use stellar_interner::IdentifierId;
+
+use crate::{
+    arena::Arena,
+    ty::{ArraySize, Type, TypeConstructor},
+    ConstantData, ConstantId, Database, EnumData, EnumId, EnumItemData, EnumItemId, FieldData,
+    FieldId, FunctionData, FunctionId, GenericParameterData, GenericParameterId,
+    GenericParameterScopeData, GenericParameterScopeId, ImplData, ImplId, InterfaceData,
+    InterfaceId, ModuleData, ModuleId, PackageData, PackageId, PredicateData, PredicateId,
+    Reexport, SignatureData, SignatureId, StaticData, StaticId, StructData, StructId, Symbol,
+    TupleLikeStructData, TupleLikeStructId, TypeAliasData, TypeAliasId, DUMMY_PACKAGE_ID,
+};
+
+/// Error returned when a package can't be imported into a database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportPackageError {
+    /// A dependency of the package is not present in the database.
+    MissingDependency(IdentifierId),
+
+    /// An ID of the package refers to a package, that is neither the package
+    /// itself nor one of its dependencies.
+    UnknownPackage(PackageId),
+}
+
+impl Display for ImportPackageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingDependency(name) => write!(f, "dependency `{name}` is not loaded"),
+            Self::UnknownPackage(package) => {
+                write!(f, "package data refers to an unknown package {}", package.0)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportPackageError {}
+
+impl Database {
+    /// Adds a package, that was allocated in another database (usually
+    /// deserialized from a cache), and returns its new ID.
+    ///
+    /// Dependencies of the package must already be present in the database,
+    /// `dependencies` maps their names to their IDs in this database. The
+    /// parent of the package is kept only if it is one of the dependencies.
+    ///
+    /// IDs are assigned deterministically: importing the same packages in the
+    /// same order always produces the same IDs.
+    ///
+    /// # Errors
+    /// Returns an error if a dependency of the package is not in
+    /// `dependencies` or if the package data refers to a package, that is not
+    /// its dependency.
+    pub fn import_package(
+        &mut self,
+        mut package: PackageData,
+        dependencies: &FxHashMap<IdentifierId, PackageId>,
+    ) -> Result<PackageId, ImportPackageError> {
+        let id = PackageId(self.packages.len() + 1);
+
+        let mut map = PackageIdMap {
+            ids: FxHashMap::default(),
+            unknown: None,
+        };
+        map.ids.insert(package.id, id);
+
+        for (name, dependency) in &package.dependencies {
+            map.ids.insert(
+                *dependency,
+                *dependencies
+                    .get(name)
+                    .ok_or(ImportPackageError::MissingDependency(*name))?,
+            );
+        }
+
+        package.parent = package
+            .parent
+            .and_then(|parent| map.ids.get(&parent).copied());
+        package.remap(&mut map);
+
+        if let Some(unknown) = map.unknown {
+            return Err(ImportPackageError::UnknownPackage(unknown));
+        }
+
+        self.packages.push(package);
+
+        Ok(id)
+    }
+}
+
+/// Maps package IDs of the source database to package IDs of the target one.
+struct PackageIdMap {
+    ids: FxHashMap<PackageId, PackageId>,
+
+    /// The first package ID, that is not in the map.
+    unknown: Option<PackageId>,
+}
+
+impl PackageIdMap {
+    fn remap(&mut self, package: &mut PackageId) {
+        // dummy IDs are the same in all databases
+        if *package == DUMMY_PACKAGE_ID {
+            return;
+        }
+
+        if let Some(new) = self.ids.get(package) {
+            *package = *new;
+        } else {
+            self.unknown.get_or_insert(*package);
+        }
+    }
+}
+
+/// Replaces package IDs in a value.
+trait Remap {
+    fn remap(&mut self, map: &mut PackageIdMap);
+}
+
+macro_rules! remap_ids {
+    ($($id:ident),*) => {
+        $(
+            impl Remap for $id {
+                #[inline]
+                fn remap(&mut self, map: &mut PackageIdMap) {
+                    map.remap(&mut self.0);
+                }
+            }
+        )*
+    };
+}
+
+remap_ids! {
+    EnumId, EnumItemId, StructId, TupleLikeStructId, FieldId, PredicateId,
+    GenericParameterScopeId, GenericParameterId, SignatureId, FunctionId, InterfaceId,
+    TypeAliasId, ConstantId, StaticId, ImplId, ModuleId
+}
+
+macro_rules! remap_fields {
+    ($($data:ident { $($field:ident),* }),*) => {
+        $(
+            impl Remap for $data {
+                fn remap(&mut self, map: &mut PackageIdMap) {
+                    $(self.$field.remap(map);)*
+                }
+            }
+        )*
+    };
+}
+
+remap_fields! {
+    ModuleData { module_item_symbols, submodules, resolved_imports, reexports, impls },
+    Reexport { symbol },
+    EnumData { signature, items, methods },
+    EnumItemData { enum_, module },
+    PredicateData { ty, bounds },
+    StructData { signature, fields, methods },
+    FieldData { ty },
+    FunctionData { signature },
+    InterfaceData { signature, constants, methods },
+    TypeAliasData { signature, ty },
+    ConstantData { signature, ty },
+    StaticData { signature, ty },
+    ImplData { module, ty, interface, methods },
+    GenericParameterScopeData { parent_scope, parameters },
+    GenericParameterData { const_type, default_value },
+    SignatureData { module, generic_parameter_scope, predicates, implements },
+    TypeConstructor { symbol, arguments },
+    PackageData {
+        id, root_module, dependencies, module_, enum_, enum_item_, predicate_, struct_,
+        tuple_like_struct_, field_, function_, interface_, type_alias_, constant_, static_,
+        impl_, generic_parameter_scope_, generic_parameter_, signature_
+    }
+}
+
+impl Remap for PackageId {
+    #[inline]
+    fn remap(&mut self, map: &mut PackageIdMap) {
+        map.remap(self);
+    }
+}
+
+impl Remap for TupleLikeStructData {
+    fn remap(&mut self, map: &mut PackageIdMap) {
+        self.signature.remap(map);
+        self.methods.remap(map);
+
+        for (_, ty) in &mut self.fields {
+            ty.remap(map);
+        }
+    }
+}
+
+impl Remap for Symbol {
+    fn remap(&mut self, map: &mut PackageIdMap) {
+        match self {
+            Self::Module(id) => id.remap(map),
+            Self::Enum(id) => id.remap(map),
+            Self::Struct(id) => id.remap(map),
+            Self::Function(id) => id.remap(map),
+            Self::Interface(id) => id.remap(map),
+            Self::TupleLikeStruct(id) => id.remap(map),
+            Self::TypeAlias(id) => id.remap(map),
+            Self::Constant(id) => id.remap(map),
+            Self::Static(id) => id.remap(map),
+            Self::EnumItem(id) => id.remap(map),
+            Self::BuiltinSymbol(_) => {}
+        }
+    }
+}
+
+impl Remap for Type {
+    fn remap(&mut self, map: &mut PackageIdMap) {
+        match self {
+            Self::Constructor(constructor) => constructor.remap(map),
+            Self::Tuple { element_types } => element_types.remap(map),
+            Self::Function {
+                parameter_types,
+                return_type,
+            } => {
+                parameter_types.remap(map);
+                return_type.remap(map);
+            }
+            Self::GenericParameter(id) => id.remap(map),
+            Self::InterfaceObject { bounds } => bounds.remap(map),
+            Self::Array { element_type, size } => {
+                element_type.remap(map);
+                size.remap(map);
+            }
+            Self::Unit | Self::Unknown | Self::Variable(_) => {}
+        }
+    }
+}
+
+impl Remap for ArraySize {
+    fn remap(&mut self, map: &mut PackageIdMap) {
+        match self {
+            Self::GenericParameter { id } => id.remap(map),
+            Self::Known { .. } => {}
+        }
+    }
+}
+
+impl<T: Remap> Remap for Vec<T> {
+    fn remap(&mut self, map: &mut PackageIdMap) {
+        for value in self {
+            value.remap(map);
+        }
+    }
+}
+
+impl<T: Remap> Remap for Arena<T> {
+    fn remap(&mut self, map: &mut PackageIdMap) {
+        for value in self.iter_mut() {
+            value.remap(map);
+        }
+    }
+}
+
+impl<T: Remap> Remap for Option<T> {
+    fn remap(&mut self, map: &mut PackageIdMap) {
+        if let Some(value) = self {
+            value.remap(map);
+        }
+    }
+}
+
+impl<T: Remap> Remap for Box<T> {
+    fn remap(&mut self, map: &mut PackageIdMap) {
+        self.as_mut().remap(map);
+    }
+}
+
+/// Only values are remapped, keys are always names.
+impl<T: Remap> Remap for FxHashMap<IdentifierId, T> {
+    fn remap(&mut self, map: &mut PackageIdMap) {
+        for value in self.values_mut() {
+            value.remap(map);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_fx_hash::FxHashMap;
+    use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+
+    use super::ImportPackageError;
+    use crate::{Database, ModuleData, PackageData, PackageId, Path, Symbol};
+
+    /// Allocates a package depending on `dep` in a database and takes its
+    /// data out of it.
+    fn package_data() -> PackageData {
+        let mut db = Database::new();
+        let dependency = PackageData::alloc_in_memory(&db, "dep".into(), DUMMY_PATH_ID);
+        let package = PackageData::alloc_in_memory(&db, "main".into(), DUMMY_PATH_ID);
+        package.add_dependency(&mut db, "dep".into(), dependency);
+
+        let dependency_module = ModuleData::alloc(
+            &db,
+            dependency,
+            Path::from(IdentifierId::from("dep")),
+            DUMMY_PATH_ID,
+        );
+        let module = ModuleData::alloc(
+            &db,
+            package,
+            Path::from(IdentifierId::from("main")),
+            DUMMY_PATH_ID,
+        );
+        package.set_root_module(&mut db, module);
+        module.add_resolved_import(&mut db, "dep".into(), Symbol::Module(dependency_module));
+
+        db.packages.into_vec().remove(1)
+    }
+
+    #[test]
+    fn import_package() {
+        let mut db = Database::new();
+        PackageData::alloc_in_memory(&db, "other".into(), DUMMY_PATH_ID);
+        let dependency = PackageData::alloc_in_memory(&db, "dep".into(), DUMMY_PATH_ID);
+        let dependency_module = ModuleData::alloc(
+            &db,
+            dependency,
+            Path::from(IdentifierId::from("dep")),
+            DUMMY_PATH_ID,
+        );
+
+        let mut dependencies = FxHashMap::default();
+        dependencies.insert(IdentifierId::from("dep"), dependency);
+
+        let package = db.import_package(package_data(), &dependencies).unwrap();
+        let module = package.root_module(&db);
+
+        assert_eq!(package, PackageId(3));
+        assert_eq!(module.package(), package);
+        assert_eq!(module.name(&db), IdentifierId::from("main"));
+        assert_eq!(
+            module.resolved_import_or_none(&db, "dep".into()),
+            Some(Symbol::Module(dependency_module))
+        );
+        assert_eq!(package.dependencies(&db), &dependencies);
+    }
+
+    #[test]
+    fn missing_dependency() {
+        assert_eq!(
+            Database::new().import_package(package_data(), &FxHashMap::default()),
+            Err(ImportPackageError::MissingDependency("dep".into()))
+        );
+    }
+}
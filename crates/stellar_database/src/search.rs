@@ -0,0 +1,272 @@
+//! Fuzzy search of symbols by their names, see [`Database::search_symbols`].
+
+use std::cmp::Reverse;
+
+use crate::{
+    ConstantId, Database, EnumId, EnumItemId, FunctionId, InterfaceId, ModuleId, PackageId,
+    StaticId, StructId, Symbol, TupleLikeStructId, TypeAliasId,
+};
+
+/// How well a name matches a query, better matches are smaller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Match {
+    /// The name is the query.
+    Exact,
+
+    /// The name starts with the query.
+    Prefix,
+
+    /// The name contains the query.
+    Substring,
+
+    /// Characters of the query appear in the name in the same order.
+    Fuzzy {
+        /// Number of characters of the query, that start words in the name,
+        /// e.g. `m` starts a word in `hmp` → `HashMap`, but not in
+        /// `hmp` → `Hashmap`. More is better.
+        word_starts: Reverse<usize>,
+
+        /// Number of characters of the name skipped between characters of
+        /// the query.
+        gaps: usize,
+    },
+}
+
+/// Returns how well a name matches a query, if it does, ignoring case.
+fn match_name(name: &str, query: &str) -> Option<Match> {
+    let lowercase_name = name.to_lowercase();
+    let lowercase_query = query.to_lowercase();
+
+    if lowercase_name == lowercase_query {
+        return Some(Match::Exact);
+    }
+
+    if lowercase_name.starts_with(&lowercase_query) {
+        return Some(Match::Prefix);
+    }
+
+    if lowercase_name.contains(&lowercase_query) {
+        return Some(Match::Substring);
+    }
+
+    let mut query_chars = lowercase_query.chars().peekable();
+    let mut previous = None;
+    let mut matched_word_starts = 0;
+    let mut gaps = 0;
+    let mut started = false;
+
+    for char in name.chars() {
+        if query_chars.peek().is_none() {
+            break;
+        }
+
+        // a character can be lowercased into several ones, e.g. `İ` into `i̇`
+        for (idx, lowercase) in char.to_lowercase().enumerate() {
+            let Some(&expected) = query_chars.peek() else {
+                break;
+            };
+
+            if lowercase == expected {
+                query_chars.next();
+                started = true;
+
+                if idx == 0 && is_word_start(previous, char) {
+                    matched_word_starts += 1;
+                }
+            } else if started {
+                gaps += 1;
+            }
+        }
+
+        previous = Some(char);
+    }
+
+    if query_chars.next().is_some() {
+        return None;
+    }
+
+    Some(Match::Fuzzy {
+        word_starts: Reverse(matched_word_starts),
+        gaps,
+    })
+}
+
+/// Returns `true` if a character starts a new word in an identifier, e.g.
+/// `M` in `HashMap` or `m` in `hash_map`.
+fn is_word_start(previous: Option<char>, char: char) -> bool {
+    match previous {
+        None => true,
+        Some(previous) => {
+            (previous == '_' && char != '_')
+                || (previous.is_lowercase() && char.is_uppercase())
+                || (!previous.is_numeric() && char.is_numeric())
+        }
+    }
+}
+
+impl Database {
+    /// Returns symbols of all packages, which names match a given query,
+    /// starting with the best matches.
+    ///
+    /// A name matches a query, if characters of the query appear in the name
+    /// in the same order, ignoring case, e.g. `hmap` matches `HashMap`. Exact
+    /// matches come first, followed by names starting with the query, names
+    /// containing it and then other matches, where matches with more query
+    /// characters at starts of words (`hmp` → `HashMap`) and with shorter
+    /// gaps are preferred. An empty query matches every symbol.
+    ///
+    /// Modules, module items, methods and enum items are searched.
+    #[must_use]
+    pub fn search_symbols(&self, query: &str) -> Vec<Symbol> {
+        let mut matches = self
+            .all_symbols()
+            .filter_map(|symbol| {
+                let name = symbol.name(self).id.as_str();
+                let rank = match_name(&name, query)?;
+
+                Some((rank, name.len(), symbol.path(self).to_string(), symbol))
+            })
+            .collect::<Vec<_>>();
+
+        // paths make the order deterministic for symbols with the same name
+        matches.sort_by(|(rank1, len1, path1, _), (rank2, len2, path2, _)| {
+            (rank1, len1, path1).cmp(&(rank2, len2, path2))
+        });
+
+        matches
+            .into_iter()
+            .map(|(_, _, _, symbol)| symbol)
+            .collect()
+    }
+
+    /// Returns all symbols defined in the database, that can be searched.
+    fn all_symbols(&self) -> impl Iterator<Item = Symbol> + '_ {
+        (1..=self.packages.len()).flat_map(move |package| {
+            let package = PackageId(package);
+            let data = self.package(package);
+
+            macro_rules! symbols {
+                ($($field:ident: $id:ident),*) => {
+                    std::iter::empty()
+                        $(
+                            .chain((1..=data.$field.len()).map(move |idx| {
+                                Symbol::from($id::new(package, idx))
+                            }))
+                        )*
+                };
+            }
+
+            symbols! {
+                module_: ModuleId,
+                enum_: EnumId,
+                enum_item_: EnumItemId,
+                struct_: StructId,
+                tuple_like_struct_: TupleLikeStructId,
+                function_: FunctionId,
+                interface_: InterfaceId,
+                type_alias_: TypeAliasId,
+                constant_: ConstantId,
+                static_: StaticId
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Reverse;
+
+    use stellar_ast::{dummy_identifier, Visibility};
+    use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+
+    use super::{match_name, Match};
+    use crate::{
+        Database, EnumData, EnumItemData, FunctionData, ModuleData, ModuleId, PackageData, Path,
+        SignatureData, SignatureId, StructData, Symbol,
+    };
+
+    fn module(db: &Database, name: &str) -> ModuleId {
+        let package = PackageData::alloc_in_memory(db, IdentifierId::from(name), DUMMY_PATH_ID);
+
+        ModuleData::alloc(
+            db,
+            package,
+            Path::from(IdentifierId::from(name)),
+            DUMMY_PATH_ID,
+        )
+    }
+
+    fn signature(db: &Database, module: ModuleId, name: &str) -> SignatureId {
+        SignatureData::alloc(
+            db,
+            vec![],
+            Visibility::Private,
+            dummy_identifier!(IdentifierId::from(name)),
+            0,
+            module,
+        )
+    }
+
+    fn names(db: &Database, symbols: Vec<Symbol>) -> Vec<String> {
+        symbols
+            .into_iter()
+            .map(|symbol| symbol.path(db).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn ranking() {
+        assert_eq!(match_name("HashMap", "hashmap"), Some(Match::Exact));
+        assert_eq!(match_name("HashMap", "hash"), Some(Match::Prefix));
+        assert_eq!(match_name("HashMap", "MAP"), Some(Match::Substring));
+        assert_eq!(match_name("HashMap", "xyz"), None);
+        assert_eq!(match_name("HashMap", "mh"), None);
+
+        assert!(match_name("HashMap", "hmp") < match_name("Hashmap", "hmp"));
+        assert!(match_name("hash_map", "hmp") < match_name("hashmap", "hmp"));
+        assert!(match_name("Heap", "hap") < match_name("HashMap", "hap"));
+
+        // `İ` is lowercased into two characters
+        assert_eq!(
+            match_name("İdBar", "ib"),
+            Some(Match::Fuzzy {
+                word_starts: Reverse(2),
+                gaps: 2
+            })
+        );
+        assert!(match_name("İr", "ir").is_some());
+    }
+
+    #[test]
+    fn search_across_packages() {
+        let db = Database::new();
+
+        let std = module(&db, "std");
+        let hash_map = StructData::alloc(&db, signature(&db, std, "HashMap"));
+        let option = EnumData::alloc(&db, signature(&db, std, "Option"));
+        let _some = EnumItemData::alloc(
+            &db,
+            option,
+            dummy_identifier!(IdentifierId::from("Some")),
+            std,
+        );
+
+        let main = module(&db, "main");
+        let _make_hash_map = FunctionData::alloc(&db, signature(&db, main, "make_hash_map"));
+        let _hmm = FunctionData::alloc(&db, signature(&db, main, "hmm"));
+
+        assert_eq!(
+            names(&db, db.search_symbols("hashmap")),
+            ["std.HashMap", "main.make_hash_map"]
+        );
+        assert_eq!(
+            names(&db, db.search_symbols("hm")),
+            ["main.hmm", "std.HashMap", "main.make_hash_map"]
+        );
+        assert_eq!(names(&db, db.search_symbols("some")), ["std.Option.Some"]);
+        assert_eq!(names(&db, db.search_symbols("MAIN")), ["main"]);
+        assert_eq!(db.search_symbols("xyz"), []);
+        assert_eq!(db.search_symbols("").len(), 7);
+        assert_eq!(db.search_symbols("HashMap")[0], Symbol::Struct(hash_map));
+    }
+}
@@ -0,0 +1,305 @@
+//! Speculative changes of the database, see [`Database::snapshot`].
+//!
+//! ```
+//! use stellar_database::{Database, ModuleData, PackageData, Path};
+//! use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+//!
+//! let mut db = Database::new();
+//! let package = PackageData::alloc_in_memory(&db, IdentifierId::from("test"), DUMMY_PATH_ID);
+//!
+//! let snapshot = db.snapshot();
+//!
+//! let path = Path::from(IdentifierId::from("test"));
+//! let module = ModuleData::alloc(&db, package, path, DUMMY_PATH_ID);
+//! package.set_root_module(&mut db, module);
+//!
+//! db.restore(snapshot);
+//!
+//! assert!(!module.is_valid(&db));
+//! assert_ne!(package.root_module(&db), module);
+//! ```
+//!
+//! Snapshots are generation-based for allocations and copy-on-write for
+//! changes: taking a snapshot only remembers numbers of entities in every
+//! package, and an entity is copied the first time it is changed after the
+//! snapshot was taken. Restoring a snapshot removes entities allocated after
+//! it and puts the copies back, so the cost of both operations depends only
+//! on the amount of changes made since the snapshot.
+
+use std::fmt::{self, Debug};
+
+use stellar_fx_hash::{FxHashMap, FxHashSet};
+use stellar_interner::IdentifierId;
+
+use crate::{Database, ModuleId, PackageId};
+
+/// A point, the database can be restored to, see [`Database::snapshot`].
+///
+/// Snapshots can be nested. Restoring or committing a snapshot also restores
+/// or commits snapshots taken after it.
+#[derive(Debug, PartialEq, Eq)]
+#[must_use = "a snapshot must be either restored or committed"]
+pub struct Snapshot {
+    /// Number of snapshots taken before this one, that are still active.
+    depth: usize,
+}
+
+/// Undoes a change of the database.
+type Undo = Box<dyn FnOnce(&mut Database) + Send + Sync>;
+
+/// State of a package at the moment a snapshot was taken.
+struct PackageState {
+    root_module: ModuleId,
+    dependencies: FxHashMap<IdentifierId, PackageId>,
+    entity_counts: FxHashMap<&'static str, usize>,
+}
+
+/// Changes made since a snapshot was taken.
+pub(crate) struct Journal {
+    packages: Vec<PackageState>,
+
+    /// Entities, that are already copied: kinds, packages and indices.
+    changed: FxHashSet<(&'static str, PackageId, usize)>,
+
+    /// Actions undoing the changes in the order, the changes were made.
+    undo: Vec<Undo>,
+}
+
+impl Debug for Journal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Journal")
+            .field("packages", &self.packages.len())
+            .field("changes", &self.undo.len())
+            .finish()
+    }
+}
+
+impl Database {
+    /// Takes a snapshot of the database, so that all the following changes
+    /// can be discarded with [`Database::restore`] or kept with
+    /// [`Database::commit`].
+    ///
+    /// See [module level docs](crate::snapshot) for more details.
+    pub fn snapshot(&mut self) -> Snapshot {
+        let packages = self
+            .packages
+            .iter()
+            .map(|package| PackageState {
+                root_module: package.root_module,
+                dependencies: package.dependencies.clone(),
+                entity_counts: package.entity_counts(),
+            })
+            .collect();
+
+        self.journals.push(Journal {
+            packages,
+            changed: FxHashSet::default(),
+            undo: Vec::new(),
+        });
+
+        Snapshot {
+            depth: self.journals.len() - 1,
+        }
+    }
+
+    /// Discards all changes made since a given snapshot was taken.
+    ///
+    /// # Panics
+    /// Panics if the snapshot is already restored or committed.
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        assert!(
+            snapshot.depth < self.journals.len(),
+            "snapshot is already restored or committed"
+        );
+
+        while self.journals.len() > snapshot.depth {
+            let journal = self.journals.pop().unwrap();
+
+            for undo in journal.undo.into_iter().rev() {
+                undo(self);
+            }
+
+            self.packages.truncate(journal.packages.len());
+
+            for (package, state) in self.packages.iter_mut().zip(journal.packages) {
+                package.root_module = state.root_module;
+                package.dependencies = state.dependencies;
+                package.truncate_entities(&state.entity_counts);
+            }
+        }
+    }
+
+    /// Keeps all changes made since a given snapshot was taken.
+    ///
+    /// The changes can still be discarded by restoring a snapshot taken
+    /// before the given one.
+    ///
+    /// # Panics
+    /// Panics if the snapshot is already restored or committed.
+    pub fn commit(&mut self, snapshot: Snapshot) {
+        assert!(
+            snapshot.depth < self.journals.len(),
+            "snapshot is already restored or committed"
+        );
+
+        let journals = self.journals.split_off(snapshot.depth);
+
+        if let Some(parent) = self.journals.last_mut() {
+            for journal in journals {
+                parent.changed.extend(journal.changed);
+                parent.undo.extend(journal.undo);
+            }
+        }
+    }
+
+    /// Returns `true` if there is a snapshot of the database and a given
+    /// entity must be copied before it is changed.
+    pub(crate) fn should_record_change(
+        &mut self,
+        kind: &'static str,
+        package: PackageId,
+        idx: usize,
+    ) -> bool {
+        let Some(journal) = self.journals.last_mut() else {
+            return false;
+        };
+
+        // entities allocated after the snapshot are simply removed
+        let existed = journal
+            .packages
+            .get(package.0 - 1)
+            .is_some_and(|state| idx <= state.entity_counts[kind]);
+
+        existed && journal.changed.insert((kind, package, idx))
+    }
+
+    /// Remembers how to undo a change, if there is a snapshot of the database.
+    pub(crate) fn record_change(
+        &mut self,
+        undo: impl FnOnce(&mut Database) + Send + Sync + 'static,
+    ) {
+        if let Some(journal) = self.journals.last_mut() {
+            journal.undo.push(Box::new(undo));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_ast::{dummy_identifier, Visibility};
+    use stellar_filesystem::location::DUMMY_LOCATION;
+    use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+
+    use crate::{
+        Database, FunctionData, ModuleData, ModuleId, PackageData, Path, SignatureData, StructData,
+        StructId, Symbol,
+    };
+
+    fn module(db: &Database) -> ModuleId {
+        let package = PackageData::alloc_in_memory(db, IdentifierId::from("test"), DUMMY_PATH_ID);
+
+        ModuleData::alloc(
+            db,
+            package,
+            Path::from(IdentifierId::from("test")),
+            DUMMY_PATH_ID,
+        )
+    }
+
+    fn struct_(db: &mut Database, module: ModuleId, name: &str) -> StructId {
+        let signature = SignatureData::alloc(
+            db,
+            vec![],
+            Visibility::Private,
+            dummy_identifier!(IdentifierId::from(name)),
+            0,
+            module,
+        );
+        let struct_ = StructData::alloc(db, signature);
+        module.add_module_item(db, IdentifierId::from(name), Symbol::Struct(struct_));
+
+        struct_
+    }
+
+    #[test]
+    fn restore() {
+        let mut db = Database::new();
+        let module = module(&db);
+        let point = struct_(&mut db, module, "Point");
+
+        let snapshot = db.snapshot();
+
+        let size = struct_(&mut db, module, "Size");
+        let method = FunctionData::alloc(&db, point.signature(&db));
+        point.add_method(&mut db, IdentifierId::from("new"), method);
+        db.add_reference(Symbol::Struct(point), DUMMY_LOCATION);
+
+        assert_eq!(module.module_item_symbols(&db).len(), 2);
+        assert!(point.method(&db, IdentifierId::from("new")).is_some());
+
+        db.restore(snapshot);
+
+        assert!(!size.is_valid(&db));
+        assert!(!method.is_valid(&db));
+        assert!(point.is_valid(&db));
+        assert_eq!(module.module_item_symbols(&db).len(), 1);
+        assert!(point.methods(&db).is_empty());
+        assert!(Symbol::Struct(point).references(&db).is_empty());
+
+        // IDs of removed entities are reused
+        assert_eq!(struct_(&mut db, module, "Size"), size);
+    }
+
+    #[test]
+    fn commit() {
+        let mut db = Database::new();
+        let module = module(&db);
+
+        let snapshot = db.snapshot();
+        let point = struct_(&mut db, module, "Point");
+        db.commit(snapshot);
+
+        assert!(point.is_valid(&db));
+        assert_eq!(module.module_item_symbols(&db).len(), 1);
+    }
+
+    #[test]
+    fn nested() {
+        let mut db = Database::new();
+        let module = module(&db);
+
+        let outer = db.snapshot();
+        let point = struct_(&mut db, module, "Point");
+
+        let inner = db.snapshot();
+        let size = struct_(&mut db, module, "Size");
+        db.restore(inner);
+
+        assert!(point.is_valid(&db));
+        assert!(!size.is_valid(&db));
+
+        let inner = db.snapshot();
+        let size = struct_(&mut db, module, "Size");
+        db.commit(inner);
+
+        assert!(size.is_valid(&db));
+
+        // committed changes are still restored with the outer snapshot
+        db.restore(outer);
+
+        assert!(!point.is_valid(&db));
+        assert!(module.module_item_symbols(&db).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "snapshot is already restored or committed")]
+    fn restore_twice() {
+        let mut db = Database::new();
+
+        let outer = db.snapshot();
+        let inner = db.snapshot();
+
+        db.restore(outer);
+        db.restore(inner);
+    }
+}
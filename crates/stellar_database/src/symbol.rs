@@ -123,6 +123,8 @@ symbols! {
     interface,
     tuple_like_struct,
     type_alias,
+    constant,
+    static,
     enum_item,
     builtin_symbol
 }
@@ -139,6 +141,8 @@ impl Symbol {
             Self::Interface(interface) => interface.signature(db),
             Self::TupleLikeStruct(struct_) => struct_.signature(db),
             Self::TypeAlias(alias) => alias.signature(db),
+            Self::Constant(constant) => constant.signature(db),
+            Self::Static(static_) => static_.signature(db),
             Self::EnumItem(_) | Self::Module(_) | Self::BuiltinSymbol(_) => unreachable!(),
         }
     }
@@ -152,6 +156,8 @@ impl Symbol {
             Self::Interface(interface) => interface.signature(db).module(db),
             Self::TupleLikeStruct(struct_) => struct_.signature(db).module(db),
             Self::TypeAlias(alias) => alias.signature(db).module(db),
+            Self::Constant(constant) => constant.signature(db).module(db),
+            Self::Static(static_) => static_.signature(db).module(db),
             Self::EnumItem(item) => item.module(db),
             Self::BuiltinSymbol(_) => DUMMY_MODULE_ID,
         }
@@ -172,6 +178,8 @@ impl Symbol {
             Self::Interface(interface) => interface.signature(db).name(db),
             Self::TupleLikeStruct(struct_) => struct_.signature(db).name(db),
             Self::TypeAlias(alias) => alias.signature(db).name(db),
+            Self::Constant(constant) => constant.signature(db).name(db),
+            Self::Static(static_) => static_.signature(db).name(db),
             Self::EnumItem(item) => item.name(db),
             Self::BuiltinSymbol(_) => todo!(),
         }
@@ -187,6 +195,8 @@ impl Symbol {
             Self::Interface(_) => Some(ModuleItemKind::Interface),
             Self::TupleLikeStruct(_) => Some(ModuleItemKind::TupleLikeStruct),
             Self::TypeAlias(_) => Some(ModuleItemKind::TypeAlias),
+            Self::Constant(_) => Some(ModuleItemKind::Constant),
+            Self::Static(_) => Some(ModuleItemKind::Static),
             Self::EnumItem(_) | Self::Module(_) | Self::BuiltinSymbol(_) => None,
         }
     }
@@ -197,6 +207,17 @@ impl Symbol {
         self.module_item_kind_or_none().unwrap()
     }
 
+    /// Returns locations, where the symbol is referenced in bodies and
+    /// signatures of items and in imports, in the order they were found.
+    ///
+    /// References are recorded by the type checker, so the list is empty
+    /// until names in the package are resolved.
+    #[inline]
+    #[must_use]
+    pub fn references(self, db: &Database) -> &[Location] {
+        db.references.get(&self).map_or(&[], Vec::as_slice)
+    }
+
     #[inline]
     #[must_use]
     pub fn path(self, db: &Database) -> Path {
@@ -209,6 +230,8 @@ impl Symbol {
             | Self::TupleLikeStruct(_)
             | Self::Function(_)
             | Self::TypeAlias(_)
+            | Self::Constant(_)
+            | Self::Static(_)
             | Self::Interface(_) => path + self.name(db).id,
             Self::EnumItem(item) => {
                 path + item.enum_(db).signature(db).name(db).id + item.name(db).id
@@ -72,6 +72,16 @@ pub enum Type {
         /// A list of interfaces, that will be used to construct a vtable.
         bounds: Vec<TypeConstructor>,
     },
+
+    /// A fixed-size array type: `[uint8; 4]`, `[T; N]`.
+    #[cfg_attr(feature = "serde", serde(rename = "array_type"))]
+    Array {
+        /// Type of array elements.
+        element_type: Box<Self>,
+
+        /// Number of array elements.
+        size: ArraySize,
+    },
 }
 
 impl Display for Type {
@@ -125,6 +135,10 @@ pub enum TypeKind {
     /// `bounds`.
     #[display(fmt = "interface object type")]
     InterfaceObject,
+
+    /// A fixed-size array type: `[uint8; 4]`, `[T; N]`.
+    #[display(fmt = "array type")]
+    Array,
 }
 
 impl Type {
@@ -141,12 +155,27 @@ impl Type {
             Self::Variable(..) => TypeKind::Variable,
             Self::GenericParameter(_) => TypeKind::GenericParameter,
             Self::InterfaceObject { .. } => TypeKind::InterfaceObject,
+            Self::Array { .. } => TypeKind::Array,
             Self::Unit => TypeKind::Unit,
             Self::Unknown => TypeKind::Unknown,
         }
     }
 }
 
+/// A size of a fixed-size array type.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind"))]
+pub enum ArraySize {
+    /// A size known at the definition, e.g. `4` in `[uint8; 4]`.
+    #[cfg_attr(feature = "serde", serde(rename = "known_size"))]
+    Known { value: u64 },
+
+    /// A const generic parameter, e.g. `N` in `[T; N]`.
+    #[cfg_attr(feature = "serde", serde(rename = "generic_size"))]
+    GenericParameter { id: GenericParameterId },
+}
+
 /// A type variable.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -35,7 +35,7 @@ pub enum Severity {
 
 /// A style of a diagnostic label.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd)]
-#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum LabelStyle {
     /// Labels that describe the primary cause of a diagnostic.
     Primary,
@@ -45,7 +45,7 @@ pub enum LabelStyle {
 
 /// A label describing an underlined region of code associated with a diagnostic.
 #[derive(Clone, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Label {
     /// The style of the label.
     pub style: LabelStyle,
@@ -100,7 +100,7 @@ impl Label {
 ///
 /// The position of a Diagnostic is considered to be the position of the [`Label`] that has the earliest starting position and has the highest style which appears in all the labels of the diagnostic.
 #[derive(Clone, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Diagnostic {
     /// The overall severity of the diagnostic
     pub severity: Severity,
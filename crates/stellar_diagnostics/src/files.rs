@@ -12,6 +12,8 @@ use stellar_filesystem::in_memory_file_storage::InMemoryFileStorage;
 use stellar_filesystem::location::{ByteOffset, Location};
 use stellar_interner::PathId;
 
+use crate::term::{ColumnEncoding, Config};
+
 /// An enum representing an error that happened while looking up a file or a piece of content in that file.
 #[derive(Debug)]
 pub enum Error {
@@ -137,20 +139,28 @@ pub(crate) trait DiagnosticsRenderHelper<'a> {
     /// # Note for trait implementors
     ///
     /// This is usually 1-indexed from the the start of the line.
-    /// A default implementation is provided, based on the [`column_index`]
-    /// function that is exported from the [`files`] module.
+    /// A default implementation is provided, based on the [`encoded_column_index`]
+    /// function that is exported from the [`files`] module. Columns are computed
+    /// in units specified by [`Config::column_encoding`].
     ///
     /// [`files`]: crate::files
-    /// [`column_index`]: crate::files::column_index
+    /// [`encoded_column_index`]: crate::files::encoded_column_index
     fn column_number(
         &'a self,
         filepath: PathId,
         line_index: usize,
         byte_offset: ByteOffset,
+        config: &Config,
     ) -> Result<usize, Error> {
         let source = self.source(filepath)?;
         let line_range = self.line_location(filepath, line_index)?;
-        let column_index = column_index(source, line_range, byte_offset);
+        let column_index = encoded_column_index(
+            source,
+            line_range,
+            byte_offset,
+            config.column_encoding,
+            config.tab_width,
+        );
 
         Ok(column_index + 1)
     }
@@ -161,12 +171,13 @@ pub(crate) trait DiagnosticsRenderHelper<'a> {
         &'a self,
         filepath: PathId,
         byte_offset: ByteOffset,
+        config: &Config,
     ) -> Result<ResolvedLocation, Error> {
         let line_index = self.line_index(filepath, byte_offset)?;
 
         Ok(ResolvedLocation {
             line_number: line_index + 1,
-            column_number: self.column_number(filepath, line_index, byte_offset)?,
+            column_number: self.column_number(filepath, line_index, byte_offset, config)?,
         })
     }
 
@@ -195,15 +206,59 @@ pub(crate) struct ResolvedLocation {
 /// character `+ 1` is returned.
 #[must_use]
 pub fn column_index(source: &str, location: Location, byte_offset: ByteOffset) -> usize {
+    encoded_column_index(source, location, byte_offset, ColumnEncoding::Chars, 0)
+}
+
+/// The column index at the given byte index in the source file, computed in
+/// units of a given [`ColumnEncoding`].
+///
+/// `tab_width` is only used for [`ColumnEncoding::Visual`].
+///
+/// If the byte index is smaller than the start of the line, then `0` is returned.
+/// If the byte index is past the end of the line, the column index after the last
+/// character is returned.
+#[must_use]
+pub fn encoded_column_index(
+    source: &str,
+    location: Location,
+    byte_offset: ByteOffset,
+    encoding: ColumnEncoding,
+    tab_width: usize,
+) -> usize {
     let end_index = std::cmp::min(
         byte_offset,
         std::cmp::min(location.end, source.len().into()),
     );
 
-    (location.start.0..end_index.0)
-        .filter(|byte_index| source.is_char_boundary(byte_index + 1))
-        .count()
+    let Some(line) = source.get(location.start.0..) else {
+        return 0;
+    };
+
+    line.char_indices()
+        .take_while(|(byte_index, _)| location.start.0 + byte_index < end_index.0)
+        .fold(0, |column, (_, ch)| {
+            column
+                + match encoding {
+                    ColumnEncoding::Chars => 1,
+                    ColumnEncoding::Utf16 => ch.len_utf16(),
+                    ColumnEncoding::Visual => visual_char_width(ch, column, tab_width),
+                }
+        })
+}
+
+/// The number of columns a given character occupies in a terminal, when it
+/// is displayed at a given visual column. Tabs are expanded to the next tab stop.
+#[must_use]
+pub fn visual_char_width(ch: char, column: usize, tab_width: usize) -> usize {
+    use unicode_width::UnicodeWidthChar;
+
+    match (ch, tab_width) {
+        ('\t', 0) => 0, // Guard divide-by-zero
+        ('\t', _) => tab_width - (column % tab_width),
+        (ch, _) => ch.width().unwrap_or(0),
+    }
 }
+
 impl From<LineTooLargeError> for Error {
     fn from(value: LineTooLargeError) -> Self {
         Self::LineTooLarge {
@@ -250,3 +305,43 @@ impl<'a> DiagnosticsRenderHelper<'a> for InMemoryFileStorage {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SOURCE: &str = "\tlet ф = \"日本😀\"; x";
+
+    fn column(encoding: ColumnEncoding, byte_offset: usize) -> usize {
+        encoded_column_index(
+            TEST_SOURCE,
+            Location {
+                filepath: PathId::from("test.sr"),
+                start: ByteOffset(0),
+                end: ByteOffset(TEST_SOURCE.len()),
+            },
+            ByteOffset(byte_offset),
+            encoding,
+            4,
+        )
+    }
+
+    #[test]
+    fn char_columns() {
+        assert_eq!(column(ColumnEncoding::Chars, TEST_SOURCE.len() - 1), 16);
+    }
+
+    #[test]
+    fn utf16_columns() {
+        // the emoji is encoded using a surrogate pair
+        assert_eq!(column(ColumnEncoding::Utf16, TEST_SOURCE.len() - 1), 17);
+        assert_eq!(column(ColumnEncoding::Utf16, "\tlet ".len()), 5);
+    }
+
+    #[test]
+    fn visual_columns() {
+        // the tab is expanded to 4 columns, CJK characters and the emoji are 2 columns wide
+        assert_eq!(column(ColumnEncoding::Visual, "\tlet ".len()), 8);
+        assert_eq!(column(ColumnEncoding::Visual, TEST_SOURCE.len() - 1), 22);
+    }
+}
@@ -138,6 +138,13 @@ impl Diagnostics {
         self.diagnostics.push(diagnostic);
     }
 
+    /// Moves diagnostics from `other` after the ones, that are already added.
+    #[inline]
+    pub fn merge(&mut self, other: Self) {
+        self.files_involved.extend(other.files_involved);
+        self.diagnostics.extend(other.diagnostics);
+    }
+
     /// Returns `true` if diagnostics are fatal.
     #[inline]
     #[must_use]
@@ -211,6 +218,18 @@ impl DiagnosticsEmitter {
         self
     }
 
+    /// Set the file storage used to render diagnostics.
+    ///
+    /// Files present in the storage are not read from disk, which allows
+    /// emitting diagnostics for sources that only exist in memory.
+    #[inline]
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)] // false-positive clippy lint
+    pub fn with_file_storage(mut self, file_storage: InMemoryFileStorage) -> Self {
+        self.file_storage = file_storage;
+        self
+    }
+
     /// Emit diagnostics associated with a particular file. If the file
     /// cannot be read, stops executing (no panic, diagnostic is just ignored).
     ///
@@ -243,7 +262,9 @@ impl DiagnosticsEmitter {
         files_involved: impl IntoIterator<Item = &'a PathId>,
     ) {
         for filepath in files_involved {
-            self.file_storage.read_and_add_file_or_panic(*filepath);
+            if self.file_storage.resolve_file(*filepath).is_none() {
+                self.file_storage.read_and_add_file_or_panic(*filepath);
+            }
         }
     }
 
@@ -15,6 +15,12 @@ pub struct Config {
     /// Defaults to: `4`.
     pub tab_width: usize,
 
+    /// Units in which column numbers of reported locations are computed.
+    /// Defaults to: [`ColumnEncoding::Chars`].
+    ///
+    /// [`ColumnEncoding::Chars`]: ColumnEncoding::Chars
+    pub column_encoding: ColumnEncoding,
+
     /// Styles to use when rendering the diagnostic.
     pub styles: Styles,
 
@@ -51,6 +57,7 @@ impl Default for Config {
         Self {
             display_style: DisplayStyle::Rich,
             tab_width: 4,
+            column_encoding: ColumnEncoding::Chars,
             styles: Styles::default(),
             chars: Chars::default(),
             start_context_lines: 3,
@@ -61,6 +68,23 @@ impl Default for Config {
     }
 }
 
+/// Units in which column numbers of reported locations are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColumnEncoding {
+    /// Number of Unicode scalar values from the start of the line.
+    #[default]
+    Chars,
+
+    /// Number of UTF-16 code units from the start of the line, which is what
+    /// language server clients expect.
+    Utf16,
+
+    /// Visual column, as displayed in terminals: tabs are expanded to the next
+    /// tab stop (see [`Config::tab_width`]) and wide characters occupy several
+    /// columns. Matches the alignment of carets in rendered snippets.
+    Visual,
+}
+
 /// The display style to use when rendering diagnostics.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DisplayStyle {
@@ -13,7 +13,7 @@ mod views;
 
 pub use termcolor;
 
-pub use self::config::{Chars, Config, DisplayStyle, Styles};
+pub use self::config::{Chars, ColumnEncoding, Config, DisplayStyle, Styles};
 
 /// A command line argument that configures the coloring of the output.
 ///
@@ -76,11 +76,9 @@ pub fn emit(
         DisplayStyle::Rich => {
             RichDiagnostic::new(diagnostic, config).render(in_memory_file_storage, &mut renderer)
         }
-        DisplayStyle::Medium => {
-            ShortDiagnostic::new(diagnostic, true).render(in_memory_file_storage, &mut renderer)
-        }
-        DisplayStyle::Short => {
-            ShortDiagnostic::new(diagnostic, false).render(in_memory_file_storage, &mut renderer)
-        }
+        DisplayStyle::Medium => ShortDiagnostic::new(diagnostic, config, true)
+            .render(in_memory_file_storage, &mut renderer),
+        DisplayStyle::Short => ShortDiagnostic::new(diagnostic, config, false)
+            .render(in_memory_file_storage, &mut renderer),
     }
 }
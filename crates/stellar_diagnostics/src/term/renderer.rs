@@ -5,7 +5,7 @@ use stellar_filesystem::location::ByteOffset;
 use termcolor::{ColorSpec, WriteColor};
 
 use crate::diagnostic::{LabelStyle, Severity};
-use crate::files::{Error, ResolvedLocation};
+use crate::files::{visual_char_width, Error, ResolvedLocation};
 use crate::term::{Chars, Config, Styles};
 
 /// The 'location focus' of a source code snippet.
@@ -668,19 +668,13 @@ impl<'w, 'c> Renderer<'w, 'c> {
         &self,
         char_indices: impl Iterator<Item = (usize, char)>,
     ) -> impl Iterator<Item = (Metrics, char)> {
-        use unicode_width::UnicodeWidthChar;
-
         let tab_width = self.config.tab_width;
         let mut unicode_column = 0;
 
         char_indices.map(move |(byte_index, ch)| {
             let metrics = Metrics {
                 byte_index,
-                unicode_width: match (ch, tab_width) {
-                    ('\t', 0) => 0, // Guard divide-by-zero
-                    ('\t', _) => tab_width - (unicode_column % tab_width),
-                    (ch, _) => ch.width().unwrap_or(0),
-                },
+                unicode_width: visual_char_width(ch, unicode_column, tab_width),
             };
             unicode_column += metrics.unicode_width;
 
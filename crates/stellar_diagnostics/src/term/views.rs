@@ -108,8 +108,11 @@ impl<'d, 'c> RichDiagnostic<'d, 'c> {
                 {
                     // this label has a higher style or has the same style but starts earlier
                     labeled_file.start = label.location.start;
-                    labeled_file.location = in_memory_file_storage
-                        .location(label.location.filepath, label.location.start)?;
+                    labeled_file.location = in_memory_file_storage.location(
+                        label.location.filepath,
+                        label.location.start,
+                        self.config,
+                    )?;
                     labeled_file.max_label_style = label.style;
                 }
                 labeled_file
@@ -118,11 +121,12 @@ impl<'d, 'c> RichDiagnostic<'d, 'c> {
                 labeled_files.push(LabeledFile {
                     filepath: label.location.filepath,
                     start: label.location.start,
-                    name: in_memory_file_storage
-                        .name(label.location.filepath)?
-                        .to_string(),
-                    location: in_memory_file_storage
-                        .location(label.location.filepath, label.location.start)?,
+                    name: in_memory_file_storage.name(label.location.filepath)?,
+                    location: in_memory_file_storage.location(
+                        label.location.filepath,
+                        label.location.start,
+                        self.config,
+                    )?,
                     num_multi_labels: 0,
                     lines: BTreeMap::new(),
                     max_label_style: label.style,
@@ -451,15 +455,21 @@ impl<'d, 'c> RichDiagnostic<'d, 'c> {
 }
 
 /// Output a short diagnostic, with a line number, severity, and message.
-pub(crate) struct ShortDiagnostic<'d> {
+pub(crate) struct ShortDiagnostic<'d, 'c> {
     diagnostic: &'d Diagnostic,
+    config: &'c Config,
     show_notes: bool,
 }
 
-impl<'d> ShortDiagnostic<'d> {
-    pub(crate) const fn new(diagnostic: &'d Diagnostic, show_notes: bool) -> ShortDiagnostic<'d> {
+impl<'d, 'c> ShortDiagnostic<'d, 'c> {
+    pub(crate) const fn new(
+        diagnostic: &'d Diagnostic,
+        config: &'c Config,
+        show_notes: bool,
+    ) -> ShortDiagnostic<'d, 'c> {
         ShortDiagnostic {
             diagnostic,
+            config,
             show_notes,
         }
     }
@@ -481,11 +491,12 @@ impl<'d> ShortDiagnostic<'d> {
 
             renderer.render_header(
                 Some(&Locus {
-                    name: in_memory_file_storage
-                        .name(label.location.filepath)?
-                        .to_string(),
-                    location: in_memory_file_storage
-                        .location(label.location.filepath, label.location.start)?,
+                    name: in_memory_file_storage.name(label.location.filepath)?,
+                    location: in_memory_file_storage.location(
+                        label.location.filepath,
+                        label.location.start,
+                        self.config,
+                    )?,
                 }),
                 self.diagnostic.severity,
                 self.diagnostic.code.as_deref(),
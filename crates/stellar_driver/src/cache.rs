@@ -0,0 +1,574 @@
+//! Package cache: lowered modules of source files stored on disk between
+//! builds.
+//!
+//! When a workspace has a [`PackageCache`], see [`Workspace::with_cache`],
+//! every parsed source file is stored in it together with the modules it
+//! defines (the module of the file and its inline submodules), encoded with
+//! [`stellar_hir::Module::encode`], and diagnostics reported while parsing
+//! and lowering them. On rebuilds files with unchanged contents are neither
+//! parsed nor lowered, their modules are decoded from the cache instead:
+//!
+//! ```no_run
+//! use stellar_driver::{cache::PackageCache, check, Workspace};
+//! use stellar_interner::IdentifierId;
+//!
+//! let workspace = Workspace::new("app", IdentifierId::from("app"))
+//!     .with_cache(PackageCache::new("app/target/cache"));
+//!
+//! // parses and lowers all files of the package and fills the cache
+//! check(&workspace)?;
+//! // parses and lowers only files, that changed since the previous build
+//! check(&workspace)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! Entries are keyed by file paths and are used only if the hash of the file
+//! contents matches the stored one. Everything, that depends on other files,
+//! e.g. checking that declared submodules exist, is redone on every build.
+//!
+//! Cached files are not passed to [`CompilerPlugin::after_parse`].
+//!
+//! # Snapshots
+//!
+//! The cache also stores a snapshot of the whole [`Database`] of the checked
+//! package (its modules and signatures together with loaded dependencies),
+//! lowered modules and diagnostics. If no source file of the package changed
+//! and headers of its dependencies are the same, the next build loads the
+//! snapshot and skips parsing, lowering and analysis entirely.
+//!
+//! A source file is unchanged, if its last modification time is the same as
+//! when the snapshot was stored or, if the file was only touched, the hash of
+//! its contents is the same. Adding or removing files invalidates the
+//! snapshot.
+//!
+//! Snapshots are neither stored nor loaded when plugins are registered, so
+//! that their hooks always run, and when only some files of the package are
+//! checked, see [`schedule`](crate::schedule).
+//!
+//! Like modules, the database is encoded as `MessagePack`: `bincode`, that is
+//! used for [`PackageData`], can't decode internally tagged enums, e.g.
+//! [`Type`].
+//!
+//! [`Workspace::with_cache`]: crate::Workspace::with_cache
+//! [`CompilerPlugin::after_parse`]: crate::plugin::CompilerPlugin::after_parse
+//! [`Database`]: stellar_database::Database
+//! [`PackageData`]: stellar_database::PackageData
+//! [`Type`]: stellar_database::ty::Type
+
+use std::{
+    fs, io,
+    path::{Path as FsPath, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use stellar_ast::{IdentifierAST, ModuleItem};
+use stellar_database::{ModuleData, ModuleId, PackageId, Path, State};
+use stellar_diagnostics::{diagnostic::Diagnostic, Diagnostics};
+use stellar_fx_hash::FxHashMap;
+use stellar_interner::{IdentifierId, PathId};
+use stellar_parser::{diagnostics::SubmoduleNotFound, ParseResult};
+#[cfg(feature = "debug")]
+use tracing::trace;
+
+use crate::remote::ContentHash;
+
+/// Version of the format of cache entries, which is bumped every time the
+/// format changes. Entries with other versions are ignored.
+const ENTRY_FORMAT_VERSION: u32 = 1;
+
+/// Bytes every cache entry starts with.
+const MAGIC: &[u8; 4] = b"SPKC";
+
+/// A cache storing lowered modules of source files in a directory. See
+/// [module level docs](self) for more details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageCache {
+    directory: PathBuf,
+}
+
+impl PackageCache {
+    /// Creates a cache in a given directory. The directory is created when the
+    /// first file is stored.
+    #[inline]
+    #[must_use]
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// Returns the directory of the cache.
+    #[inline]
+    #[must_use]
+    pub fn directory(&self) -> &FsPath {
+        &self.directory
+    }
+
+    /// Removes all entries of the cache.
+    ///
+    /// # Errors
+    /// Returns an error if the cache directory can't be removed.
+    pub fn clear(&self) -> Result<(), io::Error> {
+        match fs::remove_dir_all(&self.directory) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    fn entry_filename(filepath: &FsPath) -> String {
+        let key = ContentHash::of(filepath.as_os_str().as_encoded_bytes());
+
+        format!("{key}.hir")
+    }
+
+    /// Returns the entry of a file, if it is stored and the file didn't change
+    /// since then.
+    fn load(&self, filepath: &FsPath, source_hash: ContentHash) -> Option<Entry> {
+        let bytes = fs::read(self.directory.join(Self::entry_filename(filepath))).ok()?;
+
+        Entry::decode(&bytes).filter(|entry| entry.source_hash == source_hash)
+    }
+
+    fn store(&self, filepath: &FsPath, entry: &Entry) -> Result<(), io::Error> {
+        self.write(&Self::entry_filename(filepath), &entry.encode())
+    }
+
+    /// Writes a file with a given name into the cache directory.
+    pub(crate) fn write(&self, filename: &str, bytes: &[u8]) -> Result<(), io::Error> {
+        fs::create_dir_all(&self.directory)?;
+
+        // concurrent builds must never observe a partially written file
+        let path = self.directory.join(filename);
+        let temporary_path = path.with_extension(format!("{}.tmp", std::process::id()));
+
+        fs::write(&temporary_path, bytes)?;
+        fs::rename(temporary_path, path)
+    }
+}
+
+/// Modules defined in a source file and diagnostics reported while parsing
+/// and lowering it.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    source_hash: ContentHash,
+
+    /// The module of the file followed by its inline submodules, in the order
+    /// they are allocated in the database.
+    modules: Vec<CachedModule>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedModule {
+    path: Vec<IdentifierId>,
+
+    /// Submodules declared with `module name;`, which may be removed between
+    /// builds.
+    submodule_declarations: Vec<IdentifierAST>,
+
+    /// The lowered module, see [`stellar_hir::binary`].
+    #[serde(with = "serde_bytes")]
+    hir: Vec<u8>,
+}
+
+impl Entry {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(ENTRY_FORMAT_VERSION.to_le_bytes());
+
+        rmp_serde::encode::write_named(&mut bytes, self).unwrap();
+
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let rest = bytes.strip_prefix(MAGIC)?;
+        let (version, rest) = rest.split_first_chunk::<4>()?;
+
+        if u32::from_le_bytes(*version) != ENTRY_FORMAT_VERSION {
+            return None;
+        }
+
+        rmp_serde::from_slice(rest).ok()
+    }
+}
+
+/// A module decoded from the cache.
+struct RestoredModule {
+    module: ModuleId,
+    hir: stellar_hir::Module,
+    submodule_declarations: Vec<IdentifierAST>,
+}
+
+/// A file parsed during the build, which is stored in the cache once it is
+/// lowered.
+struct ParsedFile {
+    filepath: PathBuf,
+    source_hash: ContentHash,
+
+    /// Modules defined in the file with their submodule declarations.
+    modules: Vec<(ModuleId, Vec<IdentifierAST>)>,
+
+    /// Diagnostics reported while parsing the file.
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Use of a [`PackageCache`] during a single build.
+pub(crate) struct CacheSession {
+    cache: PackageCache,
+    restored: Vec<RestoredModule>,
+    parsed: Vec<ParsedFile>,
+}
+
+impl CacheSession {
+    pub(crate) const fn new(cache: PackageCache) -> Self {
+        Self {
+            cache,
+            restored: Vec::new(),
+            parsed: Vec::new(),
+        }
+    }
+
+    /// Allocates modules of a file, that didn't change since it was stored
+    /// in the cache, and reports its cached diagnostics.
+    ///
+    /// Returns the module of the file and its inline submodules, which are
+    /// already linked to their parents, or `None` if the file must be parsed.
+    pub(crate) fn restore(
+        &mut self,
+        state: &mut State,
+        package: PackageId,
+        path: &[IdentifierId],
+        filepath: &FsPath,
+        source: &str,
+    ) -> Option<(ModuleId, Vec<ModuleId>)> {
+        let entry = self
+            .cache
+            .load(filepath, ContentHash::of(source.as_bytes()))?;
+
+        // module paths change, when the package is renamed
+        if entry.modules.first()?.path != path {
+            return None;
+        }
+
+        let hirs = entry
+            .modules
+            .iter()
+            .map(|module| stellar_hir::Module::decode(&module.hir).ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        #[cfg(feature = "debug")]
+        trace!("restore_module(module = '{}')", filepath.display());
+
+        let filepath_id = PathId::from(filepath);
+        let mut allocated = FxHashMap::<Vec<IdentifierId>, ModuleId>::default();
+        let mut modules = Vec::with_capacity(hirs.len());
+
+        for (cached, hir) in entry.modules.into_iter().zip(hirs) {
+            let module = ModuleData::alloc(
+                state.db_mut(),
+                package,
+                Path::new(cached.path.clone()),
+                filepath_id,
+            );
+
+            if let Some(parent) = cached
+                .path
+                .split_last()
+                .and_then(|(_, parent_path)| allocated.get(parent_path))
+            {
+                parent.add_submodule(state.db_mut(), module);
+            }
+
+            allocated.insert(cached.path, module);
+            modules.push(module);
+            self.restored.push(RestoredModule {
+                module,
+                hir,
+                submodule_declarations: cached.submodule_declarations,
+            });
+        }
+
+        state.diagnostics_mut().merge(Diagnostics {
+            files_involved: entry
+                .diagnostics
+                .iter()
+                .flat_map(Diagnostic::files_involved)
+                .collect(),
+            diagnostics: entry.diagnostics,
+        });
+
+        let (module, inline_modules) = modules.split_first()?;
+
+        Some((*module, inline_modules.to_vec()))
+    }
+
+    /// Records a parsed file: parse results of its module and inline
+    /// submodules and diagnostics reported while parsing it.
+    pub(crate) fn record(
+        &mut self,
+        filepath: &FsPath,
+        source: &str,
+        parse_result: &ParseResult,
+        inline_modules: &[ParseResult],
+        diagnostics: Vec<Diagnostic>,
+    ) {
+        let mut modules = std::iter::once(parse_result)
+            .chain(inline_modules)
+            .map(|parse_result| {
+                let submodule_declarations = parse_result
+                    .ast()
+                    .items
+                    .iter()
+                    .filter_map(|item| match item {
+                        ModuleItem::Module(submodule) if !submodule.is_inline() => {
+                            Some(submodule.name)
+                        }
+                        _ => None,
+                    })
+                    .collect();
+
+                (parse_result.module(), submodule_declarations)
+            })
+            .collect::<Vec<_>>();
+        modules.sort_by_key(|(module, _)| module.idx());
+
+        self.parsed.push(ParsedFile {
+            filepath: filepath.to_path_buf(),
+            source_hash: ContentHash::of(source.as_bytes()),
+            modules,
+            diagnostics,
+        });
+    }
+
+    /// Reports submodules declared in restored modules, that don't exist,
+    /// like [`stellar_parser::check_submodule_declarations`] does for parsed
+    /// ones.
+    pub(crate) fn check_submodule_declarations(&self, state: &mut State) {
+        for restored in &self.restored {
+            for name in &restored.submodule_declarations {
+                if restored.module.submodule(state.db(), name.id).is_none() {
+                    state
+                        .diagnostics_mut()
+                        .add_diagnostic(SubmoduleNotFound::new(name.location, name.id));
+                }
+            }
+        }
+    }
+
+    /// Stores parsed files in the cache and adds restored modules to the
+    /// lowered ones.
+    ///
+    /// `lowering_diagnostics` are diagnostics reported while lowering parsed
+    /// files.
+    pub(crate) fn finish(
+        self,
+        state: &State,
+        modules: &mut FxHashMap<ModuleId, stellar_hir::Module>,
+        lowering_diagnostics: &[Diagnostic],
+    ) {
+        // results of a cancelled build are incomplete
+        if !state.is_cancelled() {
+            for file in self.parsed {
+                let filepath = PathId::from(&file.filepath);
+                let Some(cached_modules) = file
+                    .modules
+                    .into_iter()
+                    .map(|(module, submodule_declarations)| {
+                        Some(CachedModule {
+                            path: module.path(state.db()).segments().to_vec(),
+                            submodule_declarations,
+                            hir: modules.get(&module)?.encode(),
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    continue;
+                };
+
+                let entry = Entry {
+                    source_hash: file.source_hash,
+                    modules: cached_modules,
+                    diagnostics: file
+                        .diagnostics
+                        .into_iter()
+                        .chain(
+                            lowering_diagnostics
+                                .iter()
+                                .filter(|diagnostic| {
+                                    diagnostic
+                                        .labels
+                                        .first()
+                                        .is_some_and(|label| label.location.filepath == filepath)
+                                })
+                                .cloned(),
+                        )
+                        .collect(),
+                };
+
+                // the cache is an optimization, failing to fill it must not
+                // fail the build
+                #[allow(unused_variables)]
+                if let Err(error) = self.cache.store(&file.filepath, &entry) {
+                    #[cfg(feature = "debug")]
+                    trace!(
+                        "failed to cache module '{}': {error}",
+                        file.filepath.display()
+                    );
+                }
+            }
+        }
+
+        modules.extend(
+            self.restored
+                .into_iter()
+                .map(|restored| (restored.module, restored.hir)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, fs, rc::Rc};
+
+    use stellar_parser::ParseResult;
+
+    use crate::{
+        check_with_plugins,
+        plugin::{CompilerPlugin, PluginContext, Plugins, PLUGIN_API_VERSION},
+        test_utils::cached_workspace,
+        watch::DiagnosticsDelta,
+        CheckResult, Workspace,
+    };
+
+    /// Records file paths of parsed modules.
+    struct ParseRecorder {
+        parsed: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl CompilerPlugin for ParseRecorder {
+        fn name(&self) -> &'static str {
+            "parse_recorder"
+        }
+
+        fn api_version(&self) -> u32 {
+            PLUGIN_API_VERSION
+        }
+
+        fn after_parse(&mut self, _: &mut PluginContext<'_>, modules: &[ParseResult]) {
+            self.parsed
+                .borrow_mut()
+                .extend(modules.iter().map(|module| {
+                    let filepath = module.ast().filepath.to_string();
+
+                    filepath[filepath.rfind("src").unwrap()..].replace('\\', "/")
+                }));
+        }
+    }
+
+    /// Checks a package and returns the result with files, that were parsed.
+    fn check(workspace: &Workspace) -> (CheckResult, Vec<String>) {
+        let parsed = Rc::new(RefCell::new(Vec::new()));
+        let mut plugins = Plugins::new();
+        plugins
+            .register(ParseRecorder {
+                parsed: parsed.clone(),
+            })
+            .unwrap();
+
+        let result = check_with_plugins(workspace, &[], &mut plugins).unwrap();
+        let parsed = parsed.borrow().clone();
+
+        (result, parsed)
+    }
+
+    #[test]
+    fn unchanged_files_are_not_parsed_again() {
+        let workspace = cached_workspace(
+            "unchanged",
+            &[
+                ("package.sr", "module a;\nmodule b { fun f() { loop {} } }"),
+                ("a.sr", "fun g() { break; }\nfun h( {}"),
+            ],
+        );
+
+        let (first, parsed) = check(&workspace);
+        // `b` is parsed together with `package.sr`
+        assert_eq!(parsed, ["src/a.sr", "src/package.sr", "src/package.sr"]);
+        assert!(!first.diagnostics().diagnostics.is_empty());
+
+        let (second, parsed) = check(&workspace);
+        assert!(parsed.is_empty());
+
+        assert_eq!(second.modules(), first.modules());
+        assert!(DiagnosticsDelta::between(
+            &first.diagnostics().diagnostics,
+            &second.diagnostics().diagnostics
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn changed_files_are_parsed_again() {
+        let workspace = cached_workspace(
+            "changed",
+            &[("package.sr", "module a;"), ("a.sr", "pub fun f() {}")],
+        );
+
+        check(&workspace);
+
+        fs::write(
+            workspace.source_directory().join("a.sr"),
+            "pub fun f() { break; }",
+        )
+        .unwrap();
+
+        let (result, parsed) = check(&workspace);
+        assert_eq!(parsed, ["src/a.sr"]);
+        assert_eq!(result.diagnostics().diagnostics.len(), 1);
+
+        // the new version is cached
+        let (cached, parsed) = check(&workspace);
+        assert!(parsed.is_empty());
+        assert_eq!(cached.diagnostics().diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn submodules_of_cached_files_are_checked() {
+        let workspace = cached_workspace(
+            "submodules",
+            &[("package.sr", "module a;"), ("a.sr", "fun f() {}")],
+        );
+
+        assert!(check(&workspace).0.diagnostics().is_ok());
+
+        fs::remove_file(workspace.source_directory().join("a.sr")).unwrap();
+
+        let (result, parsed) = check(&workspace);
+        assert!(parsed.is_empty());
+        assert_eq!(
+            result.diagnostics().diagnostics[0].code.as_deref(),
+            Some("E014")
+        );
+    }
+
+    #[test]
+    fn invalid_entries_are_ignored() {
+        let workspace = cached_workspace("invalid", &[("package.sr", "fun f() {}")]);
+        let cache = workspace.cache().unwrap();
+
+        check(&workspace);
+
+        for entry in fs::read_dir(cache.directory()).unwrap() {
+            fs::write(entry.unwrap().path(), b"SPKC garbage").unwrap();
+        }
+
+        let (result, parsed) = check(&workspace);
+        assert_eq!(parsed, ["src/package.sr"]);
+        assert!(result.diagnostics().is_ok());
+
+        cache.clear().unwrap();
+        assert!(!cache.directory().exists());
+        assert_eq!(check(&workspace).1, ["src/package.sr"]);
+    }
+}
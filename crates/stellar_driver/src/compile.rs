@@ -0,0 +1,919 @@
+//! Hermetic compilation of a single module stored in memory.
+//!
+//! See [`compile_str()`] for more details.
+
+use stellar_ast_lowering::pipeline::{Config as LoweringConfig, LoweringPipeline};
+use stellar_database::{Database, ModuleId, PackageData, PackageId, Path, State, Symbol};
+use stellar_diagnostics::Diagnostics;
+use stellar_filesystem::{
+    in_memory_file::InMemoryFile, in_memory_file_storage::InMemoryFileStorage,
+};
+use stellar_fx_hash::FxHashMap;
+use stellar_interner::{IdentifierId, PathId};
+use stellar_parser::{check_submodule_declarations, extract_inline_modules, parse_module};
+use stellar_typechecker::{
+    check_integer_literals::CheckIntegerLiterals,
+    resolution::{
+        check_dead_items::CheckDeadItems, check_module_names::CheckModuleNames,
+        collect_definitions::CollectDefinitions, collect_references::CollectReferences,
+        resolve_impls::ResolveImpls, resolve_imports::ResolveImports,
+    },
+};
+
+use crate::plugin::Plugins;
+
+/// The last compilation stage run by [`compile_str()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum CompilationStage {
+    /// Only parse the source.
+    Parse,
+
+    /// Parse the source and lower AST into HIR.
+    Lower,
+
+    /// Parse, lower and analyze the module.
+    #[default]
+    Analyze,
+}
+
+/// Options for [`compile_str()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CompileOptions {
+    /// The last compilation stage to run.
+    stop_after: CompilationStage,
+
+    /// Passes run after lowering.
+    lowering: LoweringConfig,
+}
+
+impl CompileOptions {
+    /// Creates default compile options.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the last compilation stage to run.
+    #[inline]
+    #[must_use]
+    pub const fn stop_after(mut self, stage: CompilationStage) -> Self {
+        self.stop_after = stage;
+        self
+    }
+
+    /// Sets passes run after lowering, see
+    /// [`stellar_ast_lowering::pipeline`].
+    #[inline]
+    #[must_use]
+    pub const fn lowering(mut self, config: LoweringConfig) -> Self {
+        self.lowering = config;
+        self
+    }
+
+    /// Sets whether constant expressions, e.g. `1 + 2 * 3`, are folded after
+    /// lowering, see [`stellar_ast_lowering::fold`].
+    #[inline]
+    #[must_use]
+    pub const fn fold_constants(mut self, fold_constants: bool) -> Self {
+        self.lowering = self.lowering.with_constant_folding(fold_constants);
+        self
+    }
+}
+
+/// All artifacts produced by [`compile_str()`].
+#[derive(Debug)]
+pub struct CompileOutput {
+    state: State,
+    package: PackageId,
+    module: ModuleId,
+    ast: stellar_ast::Module,
+    hir: Option<stellar_hir::Module>,
+    files: InMemoryFileStorage,
+}
+
+impl CompileOutput {
+    /// Returns the state, containing the database and diagnostics.
+    #[inline]
+    #[must_use]
+    pub const fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Returns the ID of the compiled package.
+    #[inline]
+    #[must_use]
+    pub const fn package(&self) -> PackageId {
+        self.package
+    }
+
+    /// Returns the ID of the compiled module.
+    #[inline]
+    #[must_use]
+    pub const fn module(&self) -> ModuleId {
+        self.module
+    }
+
+    /// Returns the module AST.
+    #[inline]
+    #[must_use]
+    pub const fn ast(&self) -> &stellar_ast::Module {
+        &self.ast
+    }
+
+    /// Returns the module HIR, if the lowering stage was run.
+    #[inline]
+    #[must_use]
+    pub const fn hir(&self) -> Option<&stellar_hir::Module> {
+        self.hir.as_ref()
+    }
+
+    /// Returns the database containing symbol tables.
+    #[inline]
+    #[must_use]
+    pub const fn db(&self) -> &Database {
+        self.state.db()
+    }
+
+    /// Returns symbols defined in the module.
+    #[inline]
+    #[must_use]
+    pub fn symbols(&self) -> &FxHashMap<IdentifierId, Symbol> {
+        self.module.module_item_symbols(self.db())
+    }
+
+    /// Returns diagnostics emitted during compilation.
+    #[inline]
+    #[must_use]
+    pub const fn diagnostics(&self) -> &Diagnostics {
+        self.state.diagnostics()
+    }
+
+    /// Returns the storage containing the compiled source, which can be used to
+    /// render diagnostics without accessing the filesystem.
+    #[inline]
+    #[must_use]
+    pub const fn files(&self) -> &InMemoryFileStorage {
+        &self.files
+    }
+
+    /// Gives an ownership over the state.
+    #[inline]
+    #[must_use]
+    pub fn into_state(self) -> State {
+        self.state
+    }
+}
+
+/// Compiles a single module with a given name and source without touching the
+/// filesystem.
+///
+/// The module becomes the root module of a package with the same name, so items
+/// can be imported using both `self` and the package name.
+#[inline]
+#[must_use]
+pub fn compile_str(name: &str, source: &str, options: CompileOptions) -> CompileOutput {
+    compile_str_with_plugins(name, source, options, &mut Plugins::new())
+}
+
+/// Compiles a single module like [`compile_str()`], running plugin hooks after
+/// every stage that is run.
+///
+/// See [`crate::plugin`] for more details.
+#[must_use]
+pub fn compile_str_with_plugins(
+    name: &str,
+    source: &str,
+    options: CompileOptions,
+    plugins: &mut Plugins,
+) -> CompileOutput {
+    let mut state = State::new();
+
+    let name = IdentifierId::from(name);
+    let filepath = PathId::from(format!("{name}.sr"));
+
+    let package = PackageData::alloc_in_memory(state.db_mut(), name, filepath);
+    let mut parse_result =
+        parse_module(&mut state, package, Path::new(vec![name]), filepath, source);
+    let module = parse_result.module();
+    package.set_root_module(state.db_mut(), module);
+
+    let ast = parse_result.ast().clone();
+
+    let mut parse_results = extract_inline_modules(&mut state, &mut parse_result);
+    parse_results.insert(0, parse_result);
+    check_submodule_declarations(&mut state, &parse_results);
+
+    plugins.after_parse(&mut state, &parse_results);
+
+    let hir = if options.stop_after >= CompilationStage::Lower {
+        let mut modules =
+            LoweringPipeline::new(options.lowering).run_all(&mut state, parse_results);
+
+        plugins.after_lowering(&mut state, &modules);
+
+        if options.stop_after >= CompilationStage::Analyze {
+            CheckModuleNames::run_all(&mut state, &modules);
+            CollectDefinitions::run_all(&mut state, &modules);
+            ResolveImports::run_all(&mut state, &modules);
+            ResolveImpls::run_all(&mut state, &modules);
+            CollectReferences::run_all(&mut state, &modules);
+            CheckDeadItems::run_all(&mut state, &modules);
+            CheckIntegerLiterals::run_all(&mut state, &modules);
+
+            plugins.after_resolution(&mut state, &modules);
+        }
+
+        modules.remove(&module)
+    } else {
+        None
+    };
+
+    let mut files = InMemoryFileStorage::new();
+    files.add_file(
+        filepath,
+        InMemoryFile::new_from_source(filepath, source.to_owned()),
+    );
+
+    CompileOutput {
+        state,
+        package,
+        module,
+        ast,
+        hir,
+        files,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_interner::{IdentifierId, Interners, PathId};
+
+    use super::{compile_str, CompilationStage, CompileOptions};
+
+    #[test]
+    fn symbols() {
+        let output = compile_str("test", "fun main() {}", CompileOptions::new());
+
+        assert!(output.diagnostics().is_ok());
+        assert!(output.symbols().contains_key(&IdentifierId::from("main")));
+    }
+
+    #[test]
+    fn constants() {
+        let output = compile_str(
+            "test",
+            "const MAX_SIZE: uint32 = 1024;",
+            CompileOptions::new(),
+        );
+
+        assert!(output.diagnostics().is_ok());
+        assert!(output.symbols()[&IdentifierId::from("MAX_SIZE")].is_constant());
+    }
+
+    #[test]
+    fn interface_constants() {
+        let output = compile_str(
+            "test",
+            "interface Buffer {
+                const CAPACITY: uint32;
+                const ALIGNMENT: uint32 = 8;
+                fun len(self): uint32;
+            }",
+            CompileOptions::new(),
+        );
+
+        assert!(output.diagnostics().is_ok());
+
+        let buffer = output.symbols()[&IdentifierId::from("Buffer")]
+            .to_interface_or_none()
+            .unwrap();
+        let db = output.db();
+
+        assert!(!buffer
+            .constant(db, IdentifierId::from("CAPACITY"))
+            .unwrap()
+            .has_value(db));
+        assert!(buffer
+            .constant(db, IdentifierId::from("ALIGNMENT"))
+            .unwrap()
+            .has_value(db));
+        assert!(buffer.method(db, IdentifierId::from("len")).is_some());
+        assert!(!output
+            .symbols()
+            .contains_key(&IdentifierId::from("CAPACITY")));
+    }
+
+    #[test]
+    fn duplicate_interface_constants() {
+        let output = compile_str(
+            "test",
+            "interface A { const B: uint32; const B: uint32 = 1; }",
+            CompileOptions::new(),
+        );
+
+        assert!(!output.diagnostics().is_ok());
+    }
+
+    #[test]
+    fn statics() {
+        let output = compile_str(
+            "test",
+            "static mut COUNTER: uint32 = 0;",
+            CompileOptions::new(),
+        );
+
+        assert!(output.diagnostics().is_ok());
+
+        let counter = output.symbols()[&IdentifierId::from("COUNTER")]
+            .to_static_or_none()
+            .unwrap();
+
+        assert!(counter.is_mutable(output.db()));
+    }
+
+    #[test]
+    fn impls() {
+        let output = compile_str(
+            "test",
+            "interface Area { fun area(self): float64; }
+            struct Square { side: float64 }
+            impl Square { pub fun new(side: float64): Square { Square { side } } }
+            impl Area for Square { fun area(self): float64 { self.side * self.side } }",
+            CompileOptions::new(),
+        );
+
+        assert!(output.diagnostics().is_ok());
+
+        let square = output.symbols()[&IdentifierId::from("Square")]
+            .to_struct_or_none()
+            .unwrap();
+        let impls = output.module().impls(output.db());
+
+        assert!(square
+            .method(output.db(), IdentifierId::from("new"))
+            .is_some());
+        assert!(square
+            .method(output.db(), IdentifierId::from("area"))
+            .is_none());
+        assert_eq!(impls.len(), 2);
+        assert!(impls
+            .iter()
+            .any(|impl_| impl_.interface(output.db()).is_some()
+                && impl_
+                    .method(output.db(), IdentifierId::from("area"))
+                    .is_some()));
+    }
+
+    #[test]
+    fn duplicate_methods() {
+        let output = compile_str(
+            "test",
+            "struct A { fun foo() {} }
+            impl A { fun foo() {} }",
+            CompileOptions::new(),
+        );
+
+        assert!(!output.diagnostics().is_ok());
+    }
+
+    #[test]
+    fn inline_modules() {
+        let output = compile_str(
+            "test",
+            "module utils {
+                pub fun foo() {}
+
+                module fs { pub struct File {} }
+            }
+
+            import test.utils.foo;",
+            CompileOptions::new(),
+        );
+
+        assert!(output.diagnostics().is_ok());
+
+        let db = output.db();
+        let utils = output
+            .module()
+            .submodule(db, IdentifierId::from("utils"))
+            .unwrap();
+        let fs = utils.submodule(db, IdentifierId::from("fs")).unwrap();
+
+        assert_eq!(utils.path(db).to_string(), "test.utils");
+        assert!(utils
+            .module_item_symbols(db)
+            .contains_key(&IdentifierId::from("foo")));
+        assert!(fs
+            .module_item_symbols(db)
+            .contains_key(&IdentifierId::from("File")));
+        assert!(output
+            .module()
+            .resolved_imports(db)
+            .contains_key(&IdentifierId::from("foo")));
+    }
+
+    #[test]
+    fn restricted_visibility() {
+        let resolve = |import: &str| {
+            let output = compile_str(
+                "test",
+                &format!(
+                    "module a {{
+                        fun private() {{}}
+                        pub(super) fun parent() {{}}
+                        pub(package) fun package() {{}}
+
+                        module b {{
+                            pub(super) fun parent() {{}}
+                            import test.a.private;
+                        }}
+                    }}
+
+                    {import}"
+                ),
+                CompileOptions::new(),
+            );
+
+            output.diagnostics().is_ok()
+        };
+
+        assert!(resolve(""));
+        assert!(resolve("import test.a.parent;"));
+        assert!(resolve("import test.a.package;"));
+        assert!(!resolve("import test.a.private;"));
+        assert!(!resolve("import test.a.b.parent;"));
+    }
+
+    #[test]
+    fn glob_imports() {
+        let compile = |imports: &str| {
+            compile_str(
+                "test",
+                &format!(
+                    "module a {{
+                        pub fun foo() {{}}
+                        pub fun bar() {{ private() }}
+                        fun private() {{}}
+                    }}
+
+                    module b {{
+                        pub fun foo() {{}}
+                        pub enum Color {{ Red, Green }}
+                    }}
+
+                    {imports}"
+                ),
+                CompileOptions::new(),
+            )
+        };
+        let codes = |imports: &str| {
+            compile(imports)
+                .diagnostics()
+                .diagnostics
+                .iter()
+                .filter_map(|diagnostic| diagnostic.code.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let output = compile("import test.a.*; import test.b.Color.*;");
+        let root = output.module();
+
+        assert!(output.diagnostics().is_ok());
+        for name in ["foo", "bar", "Red", "Green"] {
+            assert!(root
+                .resolved_import_or_none(output.db(), IdentifierId::from(name))
+                .is_some());
+        }
+        assert!(root
+            .resolved_import_or_none(output.db(), IdentifierId::from("private"))
+            .is_none());
+
+        // `foo` is brought by both glob imports
+        assert_eq!(codes("import test.a.*; import test.b.*;"), ["E033"]);
+
+        // explicit imports and items of the module shadow glob imports
+        assert!(codes("import test.a.*; import test.b.*; import test.b.foo;").is_empty());
+        assert!(codes("import test.a.*; import test.b.*; pub fun foo() {}").is_empty());
+
+        assert_eq!(codes("import test.a.foo.*;"), ["E016"]);
+    }
+
+    #[test]
+    fn grouped_imports() {
+        let output = compile_str(
+            "test",
+            "module a {
+                pub fun foo() {}
+                pub module b { pub fun bar() {} }
+            }
+
+            import test.a.{foo as baz, b.{bar}};",
+            CompileOptions::new(),
+        );
+        let root = output.module();
+
+        assert!(output.diagnostics().is_ok());
+        assert_eq!(
+            output
+                .hir()
+                .unwrap()
+                .items
+                .iter()
+                .filter(|item| matches!(item, stellar_hir::ModuleItem::Import { .. }))
+                .count(),
+            2
+        );
+        for name in ["baz", "bar"] {
+            assert!(root
+                .resolved_import_or_none(output.db(), IdentifierId::from(name))
+                .is_some());
+        }
+    }
+
+    #[test]
+    fn reexports() {
+        let codes = |source: &str| {
+            compile_str("test", source, CompileOptions::new())
+                .diagnostics()
+                .diagnostics
+                .iter()
+                .filter_map(|diagnostic| diagnostic.code.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let output = compile_str(
+            "test",
+            "import test.a.{foo, Bar};
+            import test.c.*;
+
+            module a {
+                pub import test.a.b.foo;
+                pub import test.a.b.Baz as Bar;
+
+                module b {
+                    pub fun foo() {}
+                    pub struct Baz {}
+                }
+            }
+
+            module c {
+                pub import test.a.foo as qux;
+            }",
+            CompileOptions::new(),
+        );
+        let root = output.module();
+
+        assert!(output.diagnostics().is_ok());
+        for name in ["foo", "Bar", "qux"] {
+            assert!(root
+                .resolved_import_or_none(output.db(), IdentifierId::from(name))
+                .is_some());
+        }
+
+        // private imports are not re-exported
+        assert_eq!(
+            codes(
+                "import test.a.foo;
+                module a {
+                    import test.a.b.foo;
+                    pub module b { pub fun foo() {} }
+                }"
+            ),
+            ["E008"]
+        );
+
+        // the re-export is more visible than the item
+        assert_eq!(
+            codes(
+                "module a {
+                    pub import test.a.b.foo;
+                    pub module b { pub(package) fun foo() {} }
+                }"
+            ),
+            ["E017"]
+        );
+    }
+
+    #[test]
+    fn reexport_references() {
+        let output = compile_str(
+            "test",
+            "import test.a.foo;
+
+            module a {
+                pub import test.a.b.foo;
+                pub module b { pub fun foo() {} }
+            }",
+            CompileOptions::new(),
+        );
+        let db = output.db();
+        let b = output
+            .module()
+            .submodule(db, IdentifierId::from("a"))
+            .and_then(|a| a.submodule(db, IdentifierId::from("b")))
+            .unwrap();
+        let foo = b.symbol_or_none(db, IdentifierId::from("foo")).unwrap();
+
+        assert!(output.diagnostics().is_ok());
+
+        // one reference per import, although the re-export is resolved twice
+        assert_eq!(foo.references(db).len(), 2);
+    }
+
+    #[test]
+    fn unused_private_items() {
+        let unused = |source: &str| {
+            compile_str("test", source, CompileOptions::new())
+                .diagnostics()
+                .diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.code.as_deref() == Some("W007"))
+                .map(|diagnostic| diagnostic.message.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            unused(
+                "fun main() { used(); }
+                fun used(): Point { Point { x: 0 } }
+                fun unused() { helper(); }
+                fun helper() {}
+                struct Point { x: int32 }
+                struct Builder {}
+                impl Builder { pub fun new(): Builder { Builder {} } }
+                type Points = List[Point];
+                pub fun api(): Meters { Meters(1.0) }
+                struct Meters(pub float64);
+
+                module a {
+                    fun f() {}
+                    pub fun g() {}
+                }"
+            ),
+            [
+                "function `unused` is never used",
+                "function `helper` is never used",
+                "struct `Builder` is never used",
+                "type alias `Points` is never used",
+                "function `f` is never used",
+            ]
+        );
+
+        // items used in types, imports and through modules
+        assert!(unused(
+            "type Size = uint32;
+            fun main() { let size: Size = 0; a.g(); }
+            fun helper() {}
+
+            module a {
+                import test.helper;
+                pub fun g() { helper(); }
+            }"
+        )
+        .is_empty());
+
+        // `main` is only an entry point in the root module
+        assert_eq!(
+            unused("module a { fun main() {} }"),
+            ["function `main` is never used"]
+        );
+    }
+
+    #[test]
+    fn module_names_differing_only_in_case() {
+        let output = compile_str("test", "module Foo {} module foo {}", CompileOptions::new());
+        let diagnostics = &output.diagnostics().diagnostics;
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("E012"));
+        assert_eq!(
+            diagnostics[0].labels[0].location.filepath,
+            PathId::from("test.sr")
+        );
+    }
+
+    #[test]
+    fn integer_literal_overflows() {
+        let overflows = |source: &str| {
+            compile_str("test", source, CompileOptions::new())
+                .diagnostics()
+                .diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.code.as_deref() == Some("E002"))
+                .map(|diagnostic| diagnostic.message.clone())
+                .collect::<Vec<_>>()
+        };
+
+        // big literals are kept in the AST and checked against their types
+        assert_eq!(
+            overflows(
+                "fun main() {
+                    let a = 340282366920938463463374607431768211456;
+                    let b: uint8 = 256;
+                    let c: int8 = -129;
+                    let d: uint32 = -1;
+                    let e = match a { 18446744073709551616 -> 1, -9223372036854775809 -> 2, _ -> 3 };
+                }"
+            ),
+            [
+                "integer literal is out of range for `uint64`",
+                "integer literal is out of range for `uint8`",
+                "integer literal is out of range for `int8`",
+                "integer literal is out of range for `uint32`",
+                "integer literal is out of range for `uint64`",
+                "integer literal is out of range for `int64`",
+            ]
+        );
+
+        assert!(overflows(
+            "fun main() {
+                let a = 18446744073709551615;
+                let b = -9223372036854775808;
+                let c: uint8 = 255;
+                let d: int8 = -128;
+                let e: usize = 18_446_744_073_709_551_615;
+            }"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn foreign_functions() {
+        let output = compile_str(
+            "test",
+            "extern \"C\" {
+                pub fun puts(s: CStr): int32;
+                fun abort();
+            }
+
+            fun main() {}",
+            CompileOptions::new(),
+        );
+        let root = output.module();
+        let function = |name: &str| {
+            root.module_item_symbol_or_none(output.db(), IdentifierId::from(name))
+                .and_then(stellar_database::Symbol::to_function_or_none)
+                .unwrap()
+        };
+
+        assert!(output.diagnostics().is_ok());
+        assert_eq!(function("puts").abi(output.db()), Some("C"));
+        assert!(function("abort").is_foreign(output.db()));
+        assert!(!function("main").is_foreign(output.db()));
+    }
+
+    #[test]
+    fn references() {
+        let source = "fun main() { helper(); a.g(); }
+            fun helper() {}
+            fun other(point: Point) { helper(); }
+            struct Point {}
+
+            module a {
+                import test.helper;
+                pub fun g() { helper(); }
+            }";
+        let output = compile_str("test", source, CompileOptions::new());
+        let db = output.db();
+        let root = output.module();
+
+        let starts = |symbol: stellar_database::Symbol| {
+            let mut starts = symbol
+                .references(db)
+                .iter()
+                .map(|location| location.start.0)
+                .collect::<Vec<_>>();
+            starts.sort_unstable();
+            starts
+        };
+        let symbol = |name: &str| root.module_item_symbol(db, IdentifierId::from(name));
+
+        // every occurrence, except the definition itself
+        let helper = source
+            .match_indices("helper")
+            .map(|(start, _)| start)
+            .filter(|&start| !source[..start].ends_with("fun "))
+            .collect::<Vec<_>>();
+        assert_eq!(helper.len(), 4);
+        assert_eq!(starts(symbol("helper")), helper);
+
+        assert_eq!(starts(symbol("Point")), [source.find("Point)").unwrap()]);
+        assert_eq!(
+            starts(root.symbol(db, IdentifierId::from("a"))),
+            [source.find("a.g").unwrap()]
+        );
+        assert!(symbol("main").references(db).is_empty());
+    }
+
+    #[test]
+    fn references_respect_scopes() {
+        let source = "fun main() { helper(); let color = Color.Red; }
+            fun helper() {}
+            fun parameter(helper: int32): int32 { helper }
+            fun variable() { let helper = 1; helper; }
+            fun lambda() { let f = |helper| helper + 1; }
+            fun pattern(color: Color) { match color { Color.Green -> {}, helper -> { helper; } } }
+            fun generic[helper](value: helper) {}
+            enum Color { Red, Green }";
+        let output = compile_str("test", source, CompileOptions::new());
+        let db = output.db();
+        let root = output.module();
+
+        let starts = |symbol: stellar_database::Symbol| {
+            let mut starts = symbol
+                .references(db)
+                .iter()
+                .map(|location| location.start.0)
+                .collect::<Vec<_>>();
+            starts.sort_unstable();
+            starts
+        };
+        let symbol = |name: &str| root.module_item_symbol(db, IdentifierId::from(name));
+
+        // parameters, variables and generic parameters shadow the function
+        assert_eq!(starts(symbol("helper")), [source.find("helper()").unwrap()]);
+
+        let stellar_database::Symbol::Enum(color) = symbol("Color") else {
+            panic!("expected enum");
+        };
+        let item = |name: &str| {
+            stellar_database::Symbol::EnumItem(color.item(db, IdentifierId::from(name)).unwrap())
+        };
+
+        assert_eq!(
+            starts(symbol("Color")),
+            [
+                source.find("Color.Red").unwrap(),
+                source.find("Color)").unwrap(),
+                source.find("Color.Green").unwrap(),
+            ]
+        );
+        assert_eq!(starts(item("Red")), [source.find("Red;").unwrap()]);
+        assert_eq!(starts(item("Green")), [source.find("Green ->").unwrap()]);
+    }
+
+    #[test]
+    fn missing_submodule() {
+        let output = compile_str("test", "module utils;", CompileOptions::new());
+
+        assert!(!output.diagnostics().is_ok());
+    }
+
+    #[test]
+    fn parse_only() {
+        let output = compile_str(
+            "test",
+            "fun main() {}",
+            CompileOptions::new().stop_after(CompilationStage::Parse),
+        );
+
+        assert_eq!(output.ast().items.len(), 1);
+        assert!(output.hir().is_none());
+        assert!(output.symbols().is_empty());
+    }
+
+    #[test]
+    fn fold_constants() {
+        let source = "const SIZE: int64 = 4 * 1024;";
+
+        let folded = |fold_constants| {
+            let output = compile_str(
+                "test",
+                source,
+                CompileOptions::new().fold_constants(fold_constants),
+            );
+            assert!(output.diagnostics().is_ok());
+
+            stellar_hir::pretty::print(output.hir().unwrap())
+        };
+
+        assert_eq!(folded(true).trim(), "const SIZE: int64 = 4096;");
+        assert_eq!(folded(false).trim(), "const SIZE: int64 = 4 * 1024;");
+    }
+
+    #[test]
+    fn diagnostics() {
+        let output = compile_str("test", "fun main(", CompileOptions::new());
+
+        assert!(!output.diagnostics().is_ok());
+        assert!(output
+            .files()
+            .resolve_file(output.module().filepath(output.db()))
+            .is_some());
+    }
+
+    #[test]
+    fn isolated_interners() {
+        let interners = Interners::new();
+
+        interners.run(|| {
+            let output = compile_str("test", "fun isolated() {}", CompileOptions::new());
+
+            assert!(output.state().interners().ptr_eq(&interners));
+            assert!(output
+                .symbols()
+                .contains_key(&IdentifierId::from("isolated")));
+        });
+    }
+}
@@ -0,0 +1,398 @@
+//! Differential testing of the parser and the lowering against golden trees.
+//!
+//! A corpus is a directory of Stellar source files. Next to every source file
+//! `foo.sr` the corpus stores its serialized AST (`foo.ast.json`), HIR
+//! (`foo.hir.json`) and the HIR rendered back into source code
+//! (`foo.hir.txt`, see [`stellar_hir::pretty`]). [`verify_corpus()`] compiles
+//! every source file and compares the trees with the stored ones, so a grammar
+//! change shows exactly which constructs' trees changed:
+//!
+//! ```no_run
+//! use stellar_driver::corpus::verify_corpus;
+//!
+//! let report = verify_corpus("tests/corpus")?;
+//!
+//! assert!(report.is_ok(), "{report}");
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! When the change is intended, the golden files are updated (*blessed*) by
+//! running the same check with the [`BLESS_ENV_VARIABLE`] environment variable
+//! set, or by calling [`bless_corpus()`] directly:
+//!
+//! ```sh
+//! STELLAR_BLESS=1 cargo test --features serde
+//! ```
+//!
+//! Golden files are plain JSON and text, so the diff of a blessed change can be
+//! reviewed together with the change itself.
+
+use std::{
+    env,
+    fmt::{self, Display},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    compile::{compile_str, CompilationStage, CompileOptions},
+    package,
+};
+
+/// The environment variable, which makes [`verify_corpus()`] update golden
+/// files instead of comparing trees with them.
+pub const BLESS_ENV_VARIABLE: &str = "STELLAR_BLESS";
+
+/// A tree stored in a golden file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tree {
+    /// The AST produced by the parser.
+    Ast,
+
+    /// The HIR produced by the lowering.
+    Hir,
+
+    /// The HIR rendered back into source code, showing results of desugaring.
+    ExpandedHir,
+}
+
+impl Tree {
+    /// Returns the extension of golden files storing the tree.
+    #[inline]
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Ast => "ast.json",
+            Self::Hir => "hir.json",
+            Self::ExpandedHir => "hir.txt",
+        }
+    }
+}
+
+impl Display for Tree {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ast => "AST",
+            Self::Hir => "HIR",
+            Self::ExpandedHir => "expanded HIR",
+        })
+    }
+}
+
+/// A tree, that doesn't match its golden file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusMismatch {
+    source: PathBuf,
+    tree: Tree,
+
+    /// Contents of the golden file, `None` if it doesn't exist.
+    expected: Option<String>,
+    actual: String,
+}
+
+impl CorpusMismatch {
+    /// Returns the path of the source file.
+    #[inline]
+    #[must_use]
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// Returns the tree, that changed.
+    #[inline]
+    #[must_use]
+    pub const fn tree(&self) -> Tree {
+        self.tree
+    }
+
+    /// Returns the path of the golden file.
+    #[inline]
+    #[must_use]
+    pub fn golden(&self) -> PathBuf {
+        golden_path(&self.source, self.tree)
+    }
+
+    /// Returns the stored serialized tree, `None` if the golden file doesn't
+    /// exist.
+    #[inline]
+    #[must_use]
+    pub fn expected(&self) -> Option<&str> {
+        self.expected.as_deref()
+    }
+
+    /// Returns the serialized tree produced by the compiler.
+    #[inline]
+    #[must_use]
+    pub fn actual(&self) -> &str {
+        &self.actual
+    }
+}
+
+impl Display for CorpusMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(expected) = &self.expected else {
+            return write!(
+                f,
+                "{}: golden file `{}` is missing",
+                self.source.display(),
+                self.golden().display()
+            );
+        };
+
+        let mut expected_lines = expected.lines();
+        let mut actual_lines = self.actual.lines();
+        let mut line = 1;
+
+        // only the first difference is shown, the rest is usually a
+        // consequence of it
+        loop {
+            match (expected_lines.next(), actual_lines.next()) {
+                (Some(expected), Some(actual)) if expected == actual => line += 1,
+                (expected, actual) => {
+                    writeln!(
+                        f,
+                        "{}: {} changed at line {line} of `{}`",
+                        self.source.display(),
+                        self.tree,
+                        self.golden().display()
+                    )?;
+                    writeln!(f, "- {}", expected.unwrap_or("<end of file>"))?;
+                    return write!(f, "+ {}", actual.unwrap_or("<end of file>"));
+                }
+            }
+        }
+    }
+}
+
+/// The result of [`verify_corpus()`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CorpusReport {
+    checked: usize,
+    blessed: usize,
+    mismatches: Vec<CorpusMismatch>,
+}
+
+impl CorpusReport {
+    /// Returns `true` if all trees match their golden files.
+    #[inline]
+    #[must_use]
+    pub const fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Returns the number of checked source files.
+    #[inline]
+    #[must_use]
+    pub const fn checked(&self) -> usize {
+        self.checked
+    }
+
+    /// Returns the number of golden files, that were written, because they
+    /// changed or didn't exist.
+    #[inline]
+    #[must_use]
+    pub const fn blessed(&self) -> usize {
+        self.blessed
+    }
+
+    /// Returns trees, that don't match their golden files.
+    #[inline]
+    #[must_use]
+    pub fn mismatches(&self) -> &[CorpusMismatch] {
+        &self.mismatches
+    }
+}
+
+impl Display for CorpusReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checked {} files, {} trees changed",
+            self.checked,
+            self.mismatches.len()
+        )?;
+
+        for mismatch in &self.mismatches {
+            write!(f, "\n\n{mismatch}")?;
+        }
+
+        if !self.is_ok() {
+            write!(
+                f,
+                "\n\nrerun with `{BLESS_ENV_VARIABLE}=1` to update golden files"
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compiles every source file of a corpus directory (including
+/// subdirectories) and compares its AST and HIR with golden files.
+///
+/// If the [`BLESS_ENV_VARIABLE`] environment variable is set, golden files
+/// are updated instead, see [`bless_corpus()`].
+///
+/// # Errors
+/// Returns an error if the directory, a source file or a golden file cannot be
+/// read, or a golden file cannot be written.
+pub fn verify_corpus(directory: impl AsRef<Path>) -> Result<CorpusReport, io::Error> {
+    run(
+        directory.as_ref(),
+        env::var_os(BLESS_ENV_VARIABLE).is_some(),
+    )
+}
+
+/// Compiles every source file of a corpus directory (including
+/// subdirectories) and writes golden files, that changed or don't exist.
+///
+/// # Errors
+/// Returns an error if the directory or a source file cannot be read, or a
+/// golden file cannot be written.
+pub fn bless_corpus(directory: impl AsRef<Path>) -> Result<CorpusReport, io::Error> {
+    run(directory.as_ref(), true)
+}
+
+fn run(directory: &Path, bless: bool) -> Result<CorpusReport, io::Error> {
+    let mut report = CorpusReport::default();
+
+    for source_path in package::source_files(directory)? {
+        let source = fs::read_to_string(&source_path)?;
+
+        // the name is a part of locations, so it must not depend on where the
+        // corpus is
+        let name = source_path
+            .file_stem()
+            .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        let output = compile_str(
+            &name,
+            &source,
+            CompileOptions::new().stop_after(CompilationStage::Lower),
+        );
+
+        report.checked += 1;
+
+        for (tree, actual) in [
+            (Tree::Ast, serialize(output.ast())?),
+            (Tree::Hir, serialize(&output.hir())?),
+            (
+                Tree::ExpandedHir,
+                output
+                    .hir()
+                    .map_or_else(String::new, stellar_hir::pretty::print),
+            ),
+        ] {
+            let golden = golden_path(&source_path, tree);
+            let expected = match fs::read_to_string(&golden) {
+                Ok(expected) => Some(expected),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+                Err(error) => return Err(error),
+            };
+
+            if expected.as_deref() == Some(actual.as_str()) {
+                continue;
+            }
+
+            if bless {
+                fs::write(golden, actual)?;
+                report.blessed += 1;
+            } else {
+                report.mismatches.push(CorpusMismatch {
+                    source: source_path.clone(),
+                    tree,
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn serialize(tree: &impl Serialize) -> Result<String, io::Error> {
+    let mut json = serde_json::to_string_pretty(tree)?;
+    json.push('\n');
+
+    Ok(json)
+}
+
+/// `a/foo.sr` -> `a/foo.ast.json`.
+fn golden_path(source: &Path, tree: Tree) -> PathBuf {
+    source.with_extension(tree.extension())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{bless_corpus, run, CorpusMismatch, Tree};
+
+    /// Creates a corpus with given source files in a temporary directory.
+    fn corpus(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir()
+            .join("stellar_corpus_tests")
+            .join(format!("{}_{name}", std::process::id()));
+        // leftovers of a previous run
+        let _removed = fs::remove_dir_all(&root);
+
+        for (path, source) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+
+        root
+    }
+
+    #[test]
+    fn missing_golden_files() {
+        let root = corpus("missing", &[("a.sr", "fun main() {}")]);
+
+        let report = run(&root, false).unwrap();
+
+        assert_eq!(report.checked(), 1);
+        assert_eq!(report.mismatches().len(), 3);
+        assert_eq!(report.mismatches()[0].expected(), None);
+        assert!(!root.join("a.ast.json").exists());
+    }
+
+    #[test]
+    fn bless_and_verify() {
+        let root = corpus(
+            "bless",
+            &[("a.sr", "fun main() {}"), ("nested/b.sr", "struct A {}")],
+        );
+
+        let report = bless_corpus(&root).unwrap();
+
+        assert_eq!(report.blessed(), 6);
+        assert!(root.join("nested/b.hir.json").exists());
+        assert!(root.join("nested/b.hir.txt").exists());
+        assert!(run(&root, false).unwrap().is_ok());
+
+        // nothing changed, so nothing is written
+        assert_eq!(bless_corpus(&root).unwrap().blessed(), 0);
+    }
+
+    #[test]
+    fn changed_tree() {
+        let root = corpus("changed", &[("a.sr", "fun main() {}")]);
+        bless_corpus(&root).unwrap();
+
+        fs::write(root.join("a.sr"), "pub fun main() {}").unwrap();
+
+        let report = run(&root, false).unwrap();
+        let trees = report
+            .mismatches()
+            .iter()
+            .map(CorpusMismatch::tree)
+            .collect::<Vec<_>>();
+
+        assert_eq!(trees, [Tree::Ast, Tree::Hir, Tree::ExpandedHir]);
+        assert!(report.to_string().contains("AST changed at line"));
+    }
+}
@@ -0,0 +1,342 @@
+//! Package headers: public signatures of a package without function bodies.
+//!
+//! Downstream packages only need to know signatures of items exported by their
+//! dependencies, so a dependency can be checked once, turned into a
+//! [`PackageHeader`] and then loaded into the database of every package depending
+//! on it, without parsing and lowering its sources again:
+//!
+//! ```no_run
+//! use stellar_driver::{check, check_with_dependencies, Workspace};
+//! use stellar_interner::IdentifierId;
+//!
+//! let dependency = check(&Workspace::new("std", IdentifierId::from("std")))?;
+//! let header = dependency.header();
+//!
+//! let result = check_with_dependencies(
+//!     &Workspace::new("app", IdentifierId::from("app")),
+//!     &[header],
+//! )?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! With the `serde` feature enabled, headers can be serialized to store them
+//! between builds.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use stellar_database::{Database, ModuleData, ModuleId, PackageData, PackageId, Path, State};
+use stellar_fx_hash::FxHashMap;
+use stellar_hir::{Function, ModuleItem, SourceMap, Visibility};
+use stellar_interner::{IdentifierId, PathId};
+use stellar_typechecker::resolution::{
+    collect_definitions::CollectDefinitions, resolve_impls::ResolveImpls,
+    resolve_imports::ResolveImports,
+};
+
+/// Public signatures of all modules of a package.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PackageHeader {
+    name: IdentifierId,
+    path: PathId,
+    modules: Vec<ModuleHeader>,
+}
+
+/// Public signatures of a module.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ModuleHeader {
+    path: Vec<IdentifierId>,
+    module: stellar_hir::Module,
+}
+
+impl ModuleHeader {
+    /// Returns path segments of the module, starting with the package name.
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &[IdentifierId] {
+        &self.path
+    }
+
+    /// Returns public items of the module.
+    #[inline]
+    #[must_use]
+    pub fn items(&self) -> &[ModuleItem] {
+        &self.module.items
+    }
+}
+
+impl PackageHeader {
+    /// Extracts a header from the lowered modules of a package.
+    ///
+    /// Only public items are kept. Function and method bodies are removed, imports
+    /// are preserved, because public signatures may refer to imported items.
+    #[must_use]
+    pub fn extract(
+        db: &Database,
+        package: PackageId,
+        modules: &FxHashMap<ModuleId, stellar_hir::Module>,
+    ) -> Self {
+        let mut modules = modules
+            .iter()
+            .map(|(module, hir)| ModuleHeader {
+                path: module.path(db).segments().to_vec(),
+                module: stellar_hir::Module {
+                    filepath: hir.filepath,
+                    items: hir.items.iter().filter_map(item_header).collect(),
+                    docstring: hir.docstring,
+                    // diagnostics are reported only for the package's own code
+                    source_map: SourceMap::new(),
+                },
+            })
+            .collect::<Vec<_>>();
+
+        // make the header deterministic, so that it can be cached
+        modules.sort_by(|a, b| {
+            a.path
+                .iter()
+                .map(|segment| segment.as_str())
+                .cmp(b.path.iter().map(|segment| segment.as_str()))
+        });
+
+        Self {
+            name: package.name(db),
+            path: package.path(db),
+            modules,
+        }
+    }
+
+    /// Returns the name of the package.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> IdentifierId {
+        self.name
+    }
+
+    /// Returns headers of the package modules.
+    #[inline]
+    #[must_use]
+    pub fn modules(&self) -> &[ModuleHeader] {
+        &self.modules
+    }
+
+    /// Loads the header into the database as a new package and collects
+    /// definitions of its items.
+    ///
+    /// `dependencies` are packages that imports in the header can refer to.
+    pub fn load(
+        &self,
+        state: &mut State,
+        dependencies: impl IntoIterator<Item = PackageId>,
+    ) -> PackageId {
+        let package = PackageData::alloc_in_memory(state.db_mut(), self.name, self.path);
+
+        for dependency in dependencies {
+            let name = dependency.name(state.db());
+            package.add_dependency(state.db_mut(), name, dependency);
+        }
+
+        let mut module_ids = FxHashMap::default();
+        let mut modules = FxHashMap::default();
+
+        for header in &self.modules {
+            let module = ModuleData::alloc(
+                state.db_mut(),
+                package,
+                Path::new(header.path.clone()),
+                header.module.filepath,
+            );
+
+            module_ids.insert(header.path.as_slice(), module);
+            modules.insert(module, header.module.clone());
+        }
+
+        for header in &self.modules {
+            let module = module_ids[header.path.as_slice()];
+
+            if let Some((_, parent_path)) = header.path.split_last() {
+                if let Some(parent) = module_ids.get(parent_path) {
+                    parent.add_submodule(state.db_mut(), module);
+                } else if parent_path.is_empty() {
+                    package.set_root_module(state.db_mut(), module);
+                }
+            }
+        }
+
+        CollectDefinitions::run_all(state, &modules);
+        ResolveImports::run_all(state, &modules);
+        ResolveImpls::run_all(state, &modules);
+
+        package
+    }
+
+    /// Serializes the header.
+    ///
+    /// JSON is used, because HIR nodes are serialized as internally tagged enums,
+    /// which require a self-describing format.
+    ///
+    /// # Panics
+    /// Panics if the header cannot be serialized.
+    #[inline]
+    #[must_use]
+    #[cfg(feature = "serde")]
+    pub fn serialize(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap()
+    }
+
+    /// Deserializes the header.
+    ///
+    /// # Errors
+    /// Returns an error if bytes don't represent a valid header.
+    #[inline]
+    #[cfg(feature = "serde")]
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(bytes)
+    }
+}
+
+/// Returns the part of a module item visible to other packages.
+fn item_header(item: &ModuleItem) -> Option<ModuleItem> {
+    let is_public = |visibility: &Visibility| matches!(visibility, Visibility::Public(_));
+
+    match item {
+        ModuleItem::Import { .. } => Some(item.clone()),
+        ModuleItem::Constant(constant) if is_public(&constant.visibility) => Some(item.clone()),
+        ModuleItem::Static(static_) if is_public(&static_.visibility) => Some(item.clone()),
+        ModuleItem::TypeAlias(alias) if is_public(&alias.visibility) => Some(item.clone()),
+        ModuleItem::Function(function) if is_public(&function.signature.visibility) => {
+            Some(ModuleItem::Function(signature_only(function)))
+        }
+        ModuleItem::Interface(interface) if is_public(&interface.visibility) => {
+            let mut interface = interface.clone();
+            interface.methods = interface.methods.iter().map(signature_only).collect();
+
+            Some(ModuleItem::Interface(interface))
+        }
+        ModuleItem::Struct(struct_) if is_public(&struct_.visibility) => {
+            let mut struct_ = struct_.clone();
+            struct_.methods = public_methods(&struct_.methods);
+
+            Some(ModuleItem::Struct(struct_))
+        }
+        ModuleItem::TupleLikeStruct(struct_) if is_public(&struct_.visibility) => {
+            let mut struct_ = struct_.clone();
+            struct_.methods = public_methods(&struct_.methods);
+
+            Some(ModuleItem::TupleLikeStruct(struct_))
+        }
+        ModuleItem::Impl(impl_) => {
+            let mut impl_ = impl_.clone();
+
+            // all methods of interface implementations are accessible
+            // through the interface
+            impl_.methods = if impl_.interface.is_some() {
+                impl_.methods.iter().map(signature_only).collect()
+            } else {
+                public_methods(&impl_.methods)
+            };
+
+            Some(ModuleItem::Impl(impl_))
+        }
+        ModuleItem::Enum(enum_) if is_public(&enum_.visibility) => {
+            let mut enum_ = enum_.clone();
+            enum_.methods = public_methods(&enum_.methods);
+
+            Some(ModuleItem::Enum(enum_))
+        }
+        ModuleItem::Extern(block) => {
+            let mut block = block.clone();
+            block
+                .functions
+                .retain(|function| is_public(&function.visibility));
+
+            (!block.functions.is_empty()).then_some(ModuleItem::Extern(block))
+        }
+        _ => None,
+    }
+}
+
+fn public_methods(methods: &[Function]) -> Vec<Function> {
+    methods
+        .iter()
+        .filter(|method| matches!(method.signature.visibility, Visibility::Public(_)))
+        .map(signature_only)
+        .collect()
+}
+
+fn signature_only(function: &Function) -> Function {
+    Function {
+        signature: function.signature.clone(),
+        body: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_database::State;
+    use stellar_fx_hash::FxHashMap;
+    use stellar_hir::ModuleItem;
+    use stellar_interner::IdentifierId;
+
+    use super::PackageHeader;
+    use crate::compile::{compile_str, CompileOptions};
+
+    #[test]
+    fn extract_and_load() {
+        let output = compile_str(
+            "test",
+            "pub fun foo(): int32 { 1 }
+            fun bar() {}
+            pub(package) fun baz() {}
+            pub struct A {
+                pub a: int32,
+                fun private() {}
+                pub fun public() {}
+            }",
+            CompileOptions::new(),
+        );
+
+        let mut modules = FxHashMap::default();
+        modules.insert(output.module(), output.hir().unwrap().clone());
+
+        let header = PackageHeader::extract(output.db(), output.package(), &modules);
+        let items = header.modules()[0].items();
+
+        assert_eq!(items.len(), 2);
+        assert!(matches!(&items[0], ModuleItem::Function(function) if function.body.is_none()));
+        assert!(matches!(&items[1], ModuleItem::Struct(struct_) if struct_.methods.len() == 1));
+
+        let mut state = State::new();
+        let package = header.load(&mut state, []);
+        let symbols = package
+            .root_module(state.db())
+            .module_item_symbols(state.db());
+
+        assert!(state.diagnostics().is_ok());
+        assert!(symbols.contains_key(&IdentifierId::from("foo")));
+        assert!(symbols.contains_key(&IdentifierId::from("A")));
+        assert!(!symbols.contains_key(&IdentifierId::from("bar")));
+        assert!(!symbols.contains_key(&IdentifierId::from("baz")));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serialization_roundtrip() {
+        let output = compile_str(
+            "test",
+            "pub const X: int32 = 1 + 2;
+            pub fun foo(a: int32): int32 { a }",
+            CompileOptions::new(),
+        );
+
+        let mut modules = FxHashMap::default();
+        modules.insert(output.module(), output.hir().unwrap().clone());
+
+        let header = PackageHeader::extract(output.db(), output.package(), &modules);
+
+        assert_eq!(
+            PackageHeader::deserialize(&header.serialize()).unwrap(),
+            header
+        );
+    }
+}
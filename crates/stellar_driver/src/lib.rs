@@ -0,0 +1,466 @@
+//! # Driver
+//!
+//! The crate glues compilation stages of the Stellar programming language together:
+//! it discovers source files of a package, parses and lowers them and runs analysis
+//! passes on the result.
+//!
+//! - [`check`] analyzes a package once and returns [`CheckResult`].
+//! - [`compile::compile_str`] compiles a single module stored in memory, which is
+//!   useful for tests, playgrounds and checking documentation examples.
+//! - [`header::PackageHeader`] contains public signatures of an analyzed package,
+//!   which is enough to check packages depending on it, see
+//!   [`check_with_dependencies`].
+//! - [`watch::watch`] re-runs analysis every time source files change and reports
+//!   [`watch::DiagnosticsDelta`], which is what language servers and build tools
+//!   are interested in.
+//! - [`schedule::Scheduler`] analyzes files open in an editor before the rest of
+//!   the package, so that language servers can show their diagnostics early.
+//! - [`plugin::CompilerPlugin`] allows to run external analysis passes after
+//!   compilation stages, see [`check_with_plugins`].
+//! - [`reduce::reduce`] minimizes a source, that triggers a compiler bug, to a
+//!   small reproducer.
+//! - [`remote::ArtifactClient`] fetches checked dependencies from a remote cache
+//!   shared between machines (requires the `serde` feature).
+//! - [`cache::PackageCache`] stores lowered modules and the database of a package
+//!   on disk, so that unchanged files are neither parsed nor lowered on rebuilds
+//!   and unchanged packages aren't analyzed at all (requires the `binary`
+//!   feature).
+//! - [`corpus::verify_corpus`] compares trees of a corpus of source files with
+//!   stored golden trees (requires the `serde` feature).
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/quantumatic/stellar/main/additional/icon/stellar.png",
+    html_favicon_url = "https://raw.githubusercontent.com/quantumatic/stellar/main/additional/icon/stellar.png"
+)]
+#![warn(clippy::dbg_macro, missing_docs)]
+#![warn(
+    // rustc lint groups https://doc.rust-lang.org/rustc/lints/groups.html
+    future_incompatible,
+    let_underscore,
+    nonstandard_style,
+    rust_2018_compatibility,
+    rust_2018_idioms,
+    rust_2021_compatibility,
+    unused,
+    // rustc allowed-by-default lints https://doc.rust-lang.org/rustc/lints/listing/allowed-by-default.html
+    macro_use_extern_crate,
+    meta_variable_misuse,
+    missing_abi,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    non_ascii_idents,
+    noop_method_call,
+    single_use_lifetimes,
+    trivial_casts,
+    trivial_numeric_casts,
+    unreachable_pub,
+    unsafe_op_in_unsafe_fn,
+    unused_crate_dependencies,
+    unused_import_braces,
+    unused_lifetimes,
+    unused_qualifications,
+    unused_tuple_struct_fields,
+    variant_size_differences,
+    // rustdoc lints https://doc.rust-lang.org/rustdoc/lints.html
+    rustdoc::broken_intra_doc_links,
+    rustdoc::private_intra_doc_links,
+    rustdoc::missing_crate_level_docs,
+    rustdoc::private_doc_tests,
+    rustdoc::invalid_codeblock_attributes,
+    rustdoc::invalid_rust_codeblocks,
+    rustdoc::bare_urls,
+    // clippy categories https://doc.rust-lang.org/clippy/
+    clippy::all,
+    clippy::correctness,
+    clippy::suspicious,
+    clippy::style,
+    clippy::complexity,
+    clippy::perf,
+    clippy::pedantic,
+    clippy::nursery,
+)]
+#![allow(
+    clippy::module_name_repetitions,
+    clippy::too_many_lines,
+    clippy::option_if_let_else,
+    clippy::redundant_pub_crate,
+    clippy::unnested_or_patterns
+)]
+
+#[cfg(feature = "binary")]
+pub mod cache;
+pub mod compile;
+#[cfg(feature = "serde")]
+pub mod corpus;
+pub mod header;
+mod package;
+pub mod plugin;
+pub mod reduce;
+#[cfg(feature = "serde")]
+pub mod remote;
+pub mod schedule;
+#[cfg(feature = "binary")]
+mod snapshot;
+#[cfg(test)]
+mod test_utils;
+pub mod watch;
+
+#[cfg(feature = "debug")]
+use std::time::Instant;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use stellar_ast_lowering::LowerToHir;
+use stellar_database::{cancellation::CancellationToken, ModuleId, PackageId, State};
+use stellar_diagnostics::Diagnostics;
+use stellar_filesystem::path_resolver::PackagePathResolver;
+use stellar_fx_hash::FxHashMap;
+use stellar_interner::IdentifierId;
+use stellar_typechecker::{
+    check_integer_literals::CheckIntegerLiterals,
+    resolution::{
+        check_dead_items::CheckDeadItems, check_module_names::CheckModuleNames,
+        collect_definitions::CollectDefinitions, collect_references::CollectReferences,
+        resolve_impls::ResolveImpls, resolve_imports::ResolveImports,
+    },
+};
+#[cfg(feature = "debug")]
+use tracing::trace;
+
+#[cfg(feature = "binary")]
+use crate::{cache::CacheSession, snapshot::Fingerprint};
+use crate::{header::PackageHeader, package::PackageLoader, plugin::Plugins};
+
+/// The package being checked by the driver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    /// The path of the package root (the folder containing `package.toml`).
+    root: PathBuf,
+
+    /// The name of the package.
+    package_name: IdentifierId,
+
+    /// The cache of lowered modules, see [`Workspace::with_cache`].
+    #[cfg(feature = "binary")]
+    cache: Option<cache::PackageCache>,
+}
+
+impl Workspace {
+    /// Creates a new workspace for a package with a given root path and name.
+    #[inline]
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>, package_name: IdentifierId) -> Self {
+        Self {
+            root: root.into(),
+            package_name,
+            #[cfg(feature = "binary")]
+            cache: None,
+        }
+    }
+
+    /// Makes the driver store lowered modules and the database of the package
+    /// in a given cache and reuse them for files, that didn't change since the
+    /// previous build.
+    ///
+    /// See [`cache`] for more details.
+    #[cfg(feature = "binary")]
+    #[inline]
+    #[must_use]
+    pub fn with_cache(mut self, cache: cache::PackageCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Returns the cache of lowered modules, if the package uses one.
+    #[cfg(feature = "binary")]
+    #[inline]
+    #[must_use]
+    pub const fn cache(&self) -> Option<&cache::PackageCache> {
+        self.cache.as_ref()
+    }
+
+    /// Returns the path of the package root.
+    #[inline]
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the name of the package.
+    #[inline]
+    #[must_use]
+    pub const fn package_name(&self) -> IdentifierId {
+        self.package_name
+    }
+
+    /// Returns the path of the package source directory.
+    #[inline]
+    #[must_use]
+    pub fn source_directory(&self) -> PathBuf {
+        PackagePathResolver::new(&self.root).source_directory()
+    }
+
+    /// Returns paths of all Stellar source files in the package source directory,
+    /// sorted in lexicographical order.
+    ///
+    /// # Errors
+    /// Returns an error if the source directory or one of its subdirectories cannot be read.
+    pub fn source_files(&self) -> Result<Vec<PathBuf>, io::Error> {
+        package::source_files(&self.source_directory())
+    }
+}
+
+/// The result of analyzing a package with [`check`].
+#[derive(Debug)]
+pub struct CheckResult {
+    state: State,
+    package: PackageId,
+    modules: FxHashMap<ModuleId, stellar_hir::Module>,
+}
+
+impl CheckResult {
+    /// Returns the state, containing the database and diagnostics.
+    #[inline]
+    #[must_use]
+    pub const fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Returns the ID of the checked package.
+    #[inline]
+    #[must_use]
+    pub const fn package(&self) -> PackageId {
+        self.package
+    }
+
+    /// Returns lowered modules of the package.
+    #[inline]
+    #[must_use]
+    pub const fn modules(&self) -> &FxHashMap<ModuleId, stellar_hir::Module> {
+        &self.modules
+    }
+
+    /// Returns diagnostics emitted while checking the package.
+    #[inline]
+    #[must_use]
+    pub const fn diagnostics(&self) -> &Diagnostics {
+        self.state.diagnostics()
+    }
+
+    /// Returns `true` if checking was cancelled, so modules and diagnostics
+    /// are incomplete and must not be shown as the final result.
+    ///
+    /// See [`check_cancellable`] for more details.
+    #[inline]
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        self.state.is_cancelled()
+    }
+
+    /// Extracts public signatures of the package.
+    ///
+    /// See [`PackageHeader`] for more details.
+    #[inline]
+    #[must_use]
+    pub fn header(&self) -> PackageHeader {
+        PackageHeader::extract(self.state.db(), self.package, &self.modules)
+    }
+
+    /// Gives an ownership over the state.
+    #[inline]
+    #[must_use]
+    pub fn into_state(self) -> State {
+        self.state
+    }
+}
+
+/// Parses, lowers and analyzes all modules of a given package.
+///
+/// # Errors
+/// Returns an error if package source files cannot be read.
+#[inline]
+pub fn check(workspace: &Workspace) -> Result<CheckResult, io::Error> {
+    check_with_dependencies(workspace, &[])
+}
+
+/// Parses, lowers and analyzes all modules of a given package, which depends on
+/// packages with given headers.
+///
+/// Dependencies must be sorted topologically: imports of every header can only
+/// refer to packages that come before it.
+///
+/// # Errors
+/// Returns an error if package source files cannot be read.
+#[inline]
+pub fn check_with_dependencies(
+    workspace: &Workspace,
+    dependencies: &[PackageHeader],
+) -> Result<CheckResult, io::Error> {
+    check_with_plugins(workspace, dependencies, &mut Plugins::new())
+}
+
+/// Parses, lowers and analyzes all modules of a given package, which depends on
+/// packages with given headers, running plugin hooks after every stage.
+///
+/// See [`check_with_dependencies`] and [`plugin`] for more details.
+///
+/// # Errors
+/// Returns an error if package source files cannot be read.
+#[inline]
+pub fn check_with_plugins(
+    workspace: &Workspace,
+    dependencies: &[PackageHeader],
+    plugins: &mut Plugins,
+) -> Result<CheckResult, io::Error> {
+    check_using(State::new(), workspace, dependencies, plugins, None)
+}
+
+/// Parses, lowers and analyzes all modules of a given package, which depends on
+/// packages with given headers, until a given token is cancelled.
+///
+/// The token is checked at item boundaries, so a language server can abort
+/// in-flight analysis as soon as a new edit arrives. If the token is cancelled,
+/// the result contains everything analyzed so far and is marked as stale, see
+/// [`CheckResult::is_stale`]:
+///
+/// ```no_run
+/// use stellar_database::cancellation::CancellationToken;
+/// use stellar_driver::{check_cancellable, Workspace};
+/// use stellar_interner::IdentifierId;
+///
+/// let token = CancellationToken::new();
+/// let workspace = Workspace::new("app", IdentifierId::from("app"));
+///
+/// // usually done by the main loop of a language server, when a new edit
+/// // arrives, while analysis is running on another thread
+/// token.cancel();
+///
+/// let result = check_cancellable(&workspace, &[], token)?;
+/// assert!(result.is_stale());
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// # Errors
+/// Returns an error if package source files cannot be read.
+#[inline]
+pub fn check_cancellable(
+    workspace: &Workspace,
+    dependencies: &[PackageHeader],
+    token: CancellationToken,
+) -> Result<CheckResult, io::Error> {
+    check_using(
+        State::new().with_cancellation_token(token),
+        workspace,
+        dependencies,
+        &mut Plugins::new(),
+        None,
+    )
+}
+
+/// Checks a package, loading only given source files, or all of them if
+/// `files` is `None`.
+fn check_using(
+    mut state: State,
+    workspace: &Workspace,
+    dependencies: &[PackageHeader],
+    plugins: &mut Plugins,
+    files: Option<Vec<PathBuf>>,
+) -> Result<CheckResult, io::Error> {
+    // snapshots contain results of checking all files without plugins
+    #[cfg(feature = "binary")]
+    let snapshot_cache = workspace
+        .cache()
+        .filter(|_| files.is_none() && plugins.is_empty())
+        .and_then(|cache| Some((cache, Fingerprint::compute(workspace, dependencies)?)));
+
+    #[cfg(feature = "binary")]
+    if let Some((cache, fingerprint)) = &snapshot_cache {
+        if let Some((package, modules)) = snapshot::restore(cache, fingerprint, &mut state) {
+            return Ok(CheckResult {
+                state,
+                package,
+                modules,
+            });
+        }
+    }
+
+    let mut dependency_ids = Vec::with_capacity(dependencies.len());
+
+    for header in dependencies {
+        let dependency = header.load(&mut state, dependency_ids.iter().copied());
+        dependency_ids.push(dependency);
+    }
+
+    #[cfg(feature = "debug")]
+    let now = Instant::now();
+
+    #[cfg(feature = "binary")]
+    let mut cache = workspace.cache().cloned().map(CacheSession::new);
+
+    let mut loader = PackageLoader::new(&mut state, workspace);
+
+    if let Some(files) = files {
+        loader = loader.with_files(files);
+    }
+
+    #[cfg(feature = "binary")]
+    if let Some(cache) = &mut cache {
+        loader = loader.with_cache(cache);
+    }
+
+    let (package, parse_results) = loader.load()?;
+
+    for dependency in dependency_ids {
+        let name = dependency.name(state.db());
+        package.add_dependency(state.db_mut(), name, dependency);
+    }
+
+    #[cfg(feature = "debug")]
+    trace!("load_package() <{} us>", now.elapsed().as_micros());
+
+    plugins.after_parse(&mut state, &parse_results);
+
+    #[cfg(feature = "binary")]
+    let lowering_start = state.diagnostics().diagnostics.len();
+
+    #[allow(unused_mut)]
+    let mut modules = LowerToHir::lower_package(&mut state, parse_results)
+        .into_iter()
+        .collect();
+
+    #[cfg(feature = "binary")]
+    if let Some(cache) = cache {
+        cache.finish(
+            &state,
+            &mut modules,
+            &state.diagnostics().diagnostics[lowering_start..],
+        );
+    }
+
+    plugins.after_lowering(&mut state, &modules);
+
+    CheckModuleNames::run_all(&mut state, &modules);
+    CollectDefinitions::run_all(&mut state, &modules);
+    ResolveImports::run_all(&mut state, &modules);
+    ResolveImpls::run_all(&mut state, &modules);
+    CollectReferences::run_all(&mut state, &modules);
+    CheckDeadItems::run_all(&mut state, &modules);
+    CheckIntegerLiterals::run_all(&mut state, &modules);
+
+    plugins.after_resolution(&mut state, &modules);
+
+    let result = CheckResult {
+        state,
+        package,
+        modules,
+    };
+
+    #[cfg(feature = "binary")]
+    if let Some((cache, fingerprint)) = snapshot_cache {
+        snapshot::store(cache, fingerprint, &result);
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,331 @@
+//! Discovers and parses source files of a package.
+//!
+//! Module paths are derived from file paths relative to the package source directory:
+//!
+//! ```txt
+//! test
+//! |_ src
+//!    |_ package.sr    (module `test`)
+//!    |_ main.sr       (module `test.main`)
+//!    |_ utils
+//!       |_ package.sr (module `test.utils`)
+//!       |_ fs.sr      (module `test.utils.fs`)
+//! ```
+//!
+//! If a directory doesn't contain `package.sr`, an empty module is created for it.
+//!
+//! Inline submodules (`module name { ... }`) become submodules of the module
+//! they are defined in, without a file or directory of their own.
+
+#[cfg(feature = "debug")]
+use std::time::Instant;
+use std::{
+    fs, io,
+    path::{Path as FsPath, PathBuf},
+};
+
+use stellar_database::{ModuleId, PackageData, PackageId, Path, State};
+use stellar_fx_hash::FxHashMap;
+use stellar_interner::{IdentifierId, PathId};
+use stellar_parser::{
+    check_submodule_declarations, extract_inline_modules, parse_module, ParseResult,
+};
+#[cfg(feature = "debug")]
+use tracing::trace;
+
+#[cfg(feature = "binary")]
+use crate::cache::CacheSession;
+use crate::Workspace;
+
+/// The extension of Stellar source files.
+const SOURCE_FILE_EXTENSION: &str = "sr";
+
+/// The name of the file containing the root module of a directory.
+const DIRECTORY_MODULE_FILE_STEM: &str = "package";
+
+/// Returns paths of all Stellar source files in a given directory and its
+/// subdirectories, sorted in lexicographical order.
+pub(crate) fn source_files(directory: &FsPath) -> Result<Vec<PathBuf>, io::Error> {
+    fn collect(directory: &FsPath, files: &mut Vec<PathBuf>) -> Result<(), io::Error> {
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                collect(&path, files)?;
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == SOURCE_FILE_EXTENSION)
+            {
+                files.push(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    collect(directory, &mut files)?;
+    files.sort();
+
+    Ok(files)
+}
+
+/// Returns path segments of the module defined in a given file.
+pub(crate) fn module_path_segments(
+    source_directory: &FsPath,
+    package_name: IdentifierId,
+    filepath: &FsPath,
+) -> Vec<IdentifierId> {
+    let relative_path = filepath
+        .strip_prefix(source_directory)
+        .unwrap_or(filepath)
+        .with_extension("");
+
+    let mut segments = vec![package_name];
+    segments.extend(
+        relative_path
+            .iter()
+            .map(|component| IdentifierId::from(component.to_string_lossy().as_ref())),
+    );
+
+    if segments.len() > 1
+        && segments.last().map(|segment| segment.as_str()).as_deref()
+            == Some(DIRECTORY_MODULE_FILE_STEM)
+    {
+        segments.pop();
+    }
+
+    segments
+}
+
+/// Returns paths of the files, that can define a module with given path
+/// segments (excluding the package name): `a/b.sr` and `a/b/package.sr`.
+pub(crate) fn module_filepaths(
+    source_directory: &FsPath,
+    segments: &[IdentifierId],
+) -> [PathBuf; 2] {
+    let directory = segments
+        .iter()
+        .fold(source_directory.to_path_buf(), |directory, segment| {
+            directory.join(&*segment.as_str())
+        });
+
+    [
+        directory.with_extension(SOURCE_FILE_EXTENSION),
+        directory
+            .join(DIRECTORY_MODULE_FILE_STEM)
+            .with_extension(SOURCE_FILE_EXTENSION),
+    ]
+}
+
+/// Allocates a package in the database and parses all of its modules.
+pub(crate) struct PackageLoader<'s, 'w> {
+    state: &'s mut State,
+    workspace: &'w Workspace,
+    package: PackageId,
+    source_directory: PathBuf,
+
+    /// Files to load, all source files of the package if `None`.
+    files: Option<Vec<PathBuf>>,
+    modules: FxHashMap<Vec<IdentifierId>, ModuleId>,
+    parse_results: Vec<ParseResult>,
+
+    /// The cache, from which unchanged files are restored.
+    #[cfg(feature = "binary")]
+    cache: Option<&'w mut CacheSession>,
+}
+
+impl<'s, 'w> PackageLoader<'s, 'w> {
+    pub(crate) fn new(state: &'s mut State, workspace: &'w Workspace) -> Self {
+        let package = PackageData::alloc(
+            state.db_mut(),
+            workspace.package_name(),
+            PathId::from(workspace.root()),
+        );
+
+        Self {
+            state,
+            workspace,
+            package,
+            source_directory: workspace.source_directory(),
+            files: None,
+            modules: FxHashMap::default(),
+            parse_results: Vec::new(),
+            #[cfg(feature = "binary")]
+            cache: None,
+        }
+    }
+
+    /// Makes the loader parse only given source files, modules of other files
+    /// are left empty.
+    pub(crate) fn with_files(mut self, files: Vec<PathBuf>) -> Self {
+        self.files = Some(files);
+        self
+    }
+
+    /// Makes the loader restore modules of unchanged files from the cache
+    /// instead of parsing them and record parsed files.
+    #[cfg(feature = "binary")]
+    pub(crate) const fn with_cache(mut self, cache: &'w mut CacheSession) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub(crate) fn load(mut self) -> Result<(PackageId, Vec<ParseResult>), io::Error> {
+        let mut parsed_modules = Vec::new();
+
+        let files = match self.files.take() {
+            Some(files) => files,
+            None => source_files(&self.source_directory)?,
+        };
+
+        for filepath in files {
+            if self.state.is_cancelled() {
+                break;
+            }
+
+            #[cfg(feature = "debug")]
+            let now = Instant::now();
+
+            let segments = module_path_segments(
+                &self.source_directory,
+                self.workspace.package_name(),
+                &filepath,
+            );
+            let source = fs::read_to_string(&filepath)?;
+
+            #[cfg(feature = "binary")]
+            let restored = self.cache.as_mut().and_then(|cache| {
+                cache.restore(self.state, self.package, &segments, &filepath, &source)
+            });
+            #[cfg(not(feature = "binary"))]
+            let restored = None;
+
+            let (module, inline_modules) = match restored {
+                Some(modules) => modules,
+                None => self.parse_file(&segments, &filepath, &source),
+            };
+
+            #[cfg(feature = "debug")]
+            trace!(
+                "parse_module(module = '{}') <{} us>",
+                filepath.display(),
+                now.elapsed().as_micros()
+            );
+
+            // if several files map to the same module path, the first one wins here,
+            // the rest is reported in `CheckModuleNames`.
+            self.modules.entry(segments.clone()).or_insert(module);
+            parsed_modules.push((segments, module));
+
+            // inline modules are already linked to their parents
+            for module in inline_modules {
+                let segments = module.path(self.state.db()).segments().to_vec();
+
+                self.modules.entry(segments).or_insert(module);
+            }
+        }
+
+        // link modules only after all files are parsed, so that directories with
+        // `package.sr` don't get an empty module created for them.
+        for (segments, module) in parsed_modules {
+            self.link_to_parent(&segments, module);
+        }
+
+        let root_module = self.module_or_empty(&[self.workspace.package_name()]);
+        self.package
+            .set_root_module(self.state.db_mut(), root_module);
+
+        check_submodule_declarations(self.state, &self.parse_results);
+
+        #[cfg(feature = "binary")]
+        if let Some(cache) = &self.cache {
+            cache.check_submodule_declarations(self.state);
+        }
+
+        Ok((self.package, self.parse_results))
+    }
+
+    /// Parses a source file, returns its module and inline submodules.
+    fn parse_file(
+        &mut self,
+        segments: &[IdentifierId],
+        filepath: &FsPath,
+        source: &str,
+    ) -> (ModuleId, Vec<ModuleId>) {
+        #[cfg(feature = "binary")]
+        let diagnostics_start = self.state.diagnostics().diagnostics.len();
+
+        let mut parse_result = parse_module(
+            self.state,
+            self.package,
+            Path::new(segments.to_vec()),
+            PathId::from(filepath),
+            source,
+        );
+        let inline_modules = extract_inline_modules(self.state, &mut parse_result);
+
+        #[cfg(feature = "binary")]
+        if let Some(cache) = &mut self.cache {
+            cache.record(
+                filepath,
+                source,
+                &parse_result,
+                &inline_modules,
+                self.state.diagnostics().diagnostics[diagnostics_start..].to_vec(),
+            );
+        }
+
+        let module = parse_result.module();
+        let inline_module_ids = inline_modules.iter().map(ParseResult::module).collect();
+
+        self.parse_results.push(parse_result);
+        self.parse_results.extend(inline_modules);
+
+        (module, inline_module_ids)
+    }
+
+    /// Adds a module to submodules of its parent module.
+    fn link_to_parent(&mut self, segments: &[IdentifierId], module: ModuleId) {
+        let Some((_, parent_segments)) = segments.split_last() else {
+            return;
+        };
+
+        if parent_segments.is_empty() {
+            return;
+        }
+
+        let parent = self.module_or_empty(parent_segments);
+        parent.add_submodule(self.state.db_mut(), module);
+    }
+
+    /// Returns a module with given path segments, creating an empty one if it
+    /// doesn't exist yet.
+    fn module_or_empty(&mut self, segments: &[IdentifierId]) -> ModuleId {
+        if let Some(module) = self.modules.get(segments) {
+            return *module;
+        }
+
+        let directory = segments
+            .iter()
+            .skip(1)
+            .fold(self.source_directory.clone(), |directory, segment| {
+                directory.join(&*segment.as_str())
+            });
+
+        let parse_result = parse_module(
+            self.state,
+            self.package,
+            Path::new(segments.to_vec()),
+            PathId::from(directory),
+            "",
+        );
+        let module = parse_result.module();
+
+        self.modules.insert(segments.to_vec(), module);
+        self.parse_results.push(parse_result);
+        self.link_to_parent(segments, module);
+
+        module
+    }
+}
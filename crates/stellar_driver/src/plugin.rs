@@ -0,0 +1,355 @@
+//! Compiler plugins: external analysis passes run by the driver.
+//!
+//! A plugin implements [`CompilerPlugin`] and is registered in [`Plugins`],
+//! which are passed to [`check_with_plugins`] or
+//! [`compile_str_with_plugins`]. The driver calls plugin hooks after every
+//! compilation stage in the order the plugins were registered:
+//!
+//! ```
+//! use stellar_diagnostics::define_diagnostics;
+//! use stellar_driver::{
+//!     compile::{compile_str_with_plugins, CompileOptions},
+//!     plugin::{CompilerPlugin, PluginContext, Plugins},
+//! };
+//! use stellar_parser::ParseResult;
+//! use stellar_filesystem::location::Location;
+//!
+//! define_diagnostics! {
+//!     diagnostic(warning) TodoFunction(self, location: Location) {
+//!         code { "ACME001" }
+//!         message { "function is not implemented yet" }
+//!         labels {
+//!             primary { self.location }
+//!         }
+//!     }
+//! }
+//!
+//! struct TodoLint;
+//!
+//! impl CompilerPlugin for TodoLint {
+//!     fn name(&self) -> &str {
+//!         "todo_lint"
+//!     }
+//!
+//!     fn api_version(&self) -> u32 {
+//!         1
+//!     }
+//!
+//!     fn after_parse(&mut self, context: &mut PluginContext<'_>, modules: &[ParseResult]) {
+//!         for item in modules.iter().flat_map(|module| &module.ast().items) {
+//!             if item.name_identifier_id().is_some_and(|name| name.as_str().starts_with("todo_")) {
+//!                 context.add_diagnostic(TodoFunction::new(item.location()));
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let mut plugins = Plugins::new();
+//! plugins.register(TodoLint).unwrap();
+//!
+//! let output = compile_str_with_plugins(
+//!     "test",
+//!     "pub fun todo_main() {}",
+//!     CompileOptions::new(),
+//!     &mut plugins,
+//! );
+//!
+//! assert_eq!(output.diagnostics().diagnostics.len(), 1);
+//! ```
+//!
+//! Plugins are linked into the compiler statically, so the API is versioned
+//! with [`PLUGIN_API_VERSION`] instead of relying on an ABI: a plugin reports
+//! the version it was written for and is rejected at registration if the
+//! version is different.
+//!
+//! [`check_with_plugins`]: crate::check_with_plugins
+//! [`compile_str_with_plugins`]: crate::compile::compile_str_with_plugins
+
+use std::fmt::{self, Debug, Display};
+
+use stellar_database::{Database, ModuleId, State};
+use stellar_diagnostics::BuildDiagnostic;
+use stellar_fx_hash::FxHashMap;
+use stellar_parser::ParseResult;
+
+/// The version of the plugin API.
+///
+/// The version is bumped every time [`CompilerPlugin`] or [`PluginContext`]
+/// change in a way, that can break existing plugins.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// An analysis pass, that is run by the driver after compilation stages.
+///
+/// All hooks do nothing by default.
+pub trait CompilerPlugin {
+    /// Returns the name of the plugin, used in error messages.
+    fn name(&self) -> &str;
+
+    /// Returns the version of the plugin API the plugin is written for.
+    ///
+    /// **Note**: the version should be a literal, not [`PLUGIN_API_VERSION`],
+    /// otherwise a plugin written for an older API is never rejected.
+    fn api_version(&self) -> u32;
+
+    /// Called after all modules are parsed.
+    #[allow(unused_variables)]
+    fn after_parse(&mut self, context: &mut PluginContext<'_>, modules: &[ParseResult]) {}
+
+    /// Called after all modules are lowered into HIR.
+    #[allow(unused_variables)]
+    fn after_lowering(
+        &mut self,
+        context: &mut PluginContext<'_>,
+        modules: &FxHashMap<ModuleId, stellar_hir::Module>,
+    ) {
+    }
+
+    /// Called after name resolution: all definitions are collected and imports
+    /// and impls are resolved.
+    #[allow(unused_variables)]
+    fn after_resolution(
+        &mut self,
+        context: &mut PluginContext<'_>,
+        modules: &FxHashMap<ModuleId, stellar_hir::Module>,
+    ) {
+    }
+}
+
+/// Gives plugins read-only access to the database and allows them to emit
+/// diagnostics.
+#[derive(Debug)]
+pub struct PluginContext<'s> {
+    state: &'s mut State,
+}
+
+impl PluginContext<'_> {
+    /// Returns the database.
+    #[inline]
+    #[must_use]
+    pub const fn db(&self) -> &Database {
+        self.state.db()
+    }
+
+    /// Adds a diagnostic.
+    #[inline]
+    pub fn add_diagnostic(&mut self, diagnostic: impl BuildDiagnostic) {
+        self.state.diagnostics_mut().add_diagnostic(diagnostic);
+    }
+}
+
+/// Error returned when a plugin, written for another version of the plugin
+/// API, is registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncompatiblePluginError {
+    /// The name of the plugin.
+    pub name: String,
+
+    /// The version of the plugin API the plugin is written for.
+    pub api_version: u32,
+}
+
+impl Display for IncompatiblePluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "plugin `{}` is written for plugin API version {}, but the compiler supports version {}",
+            self.name, self.api_version, PLUGIN_API_VERSION
+        )
+    }
+}
+
+impl std::error::Error for IncompatiblePluginError {}
+
+/// Plugins registered on the driver.
+#[derive(Default)]
+pub struct Plugins {
+    plugins: Vec<Box<dyn CompilerPlugin>>,
+}
+
+impl Debug for Plugins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.plugins.iter().map(|plugin| plugin.name()))
+            .finish()
+    }
+}
+
+impl Plugins {
+    /// Creates an empty plugin list.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a plugin. Hooks of plugins are called in the order they were
+    /// registered.
+    ///
+    /// # Errors
+    /// Returns an error if the plugin is written for another version of the
+    /// plugin API.
+    pub fn register(
+        &mut self,
+        plugin: impl CompilerPlugin + 'static,
+    ) -> Result<(), IncompatiblePluginError> {
+        if plugin.api_version() != PLUGIN_API_VERSION {
+            return Err(IncompatiblePluginError {
+                name: plugin.name().to_owned(),
+                api_version: plugin.api_version(),
+            });
+        }
+
+        self.plugins.push(Box::new(plugin));
+        Ok(())
+    }
+
+    /// Returns the number of registered plugins.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    /// Returns `true` if no plugins are registered.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    pub(crate) fn after_parse(&mut self, state: &mut State, modules: &[ParseResult]) {
+        let mut context = PluginContext { state };
+
+        for plugin in &mut self.plugins {
+            plugin.after_parse(&mut context, modules);
+        }
+    }
+
+    pub(crate) fn after_lowering(
+        &mut self,
+        state: &mut State,
+        modules: &FxHashMap<ModuleId, stellar_hir::Module>,
+    ) {
+        let mut context = PluginContext { state };
+
+        for plugin in &mut self.plugins {
+            plugin.after_lowering(&mut context, modules);
+        }
+    }
+
+    pub(crate) fn after_resolution(
+        &mut self,
+        state: &mut State,
+        modules: &FxHashMap<ModuleId, stellar_hir::Module>,
+    ) {
+        let mut context = PluginContext { state };
+
+        for plugin in &mut self.plugins {
+            plugin.after_resolution(&mut context, modules);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use stellar_database::ModuleId;
+    use stellar_fx_hash::FxHashMap;
+    use stellar_interner::IdentifierId;
+    use stellar_parser::ParseResult;
+
+    use super::{CompilerPlugin, PluginContext, Plugins, PLUGIN_API_VERSION};
+    use crate::compile::{compile_str_with_plugins, CompilationStage, CompileOptions};
+
+    struct Recorder {
+        api_version: u32,
+        stages: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl CompilerPlugin for Recorder {
+        fn name(&self) -> &'static str {
+            "recorder"
+        }
+
+        fn api_version(&self) -> u32 {
+            self.api_version
+        }
+
+        fn after_parse(&mut self, _: &mut PluginContext<'_>, modules: &[ParseResult]) {
+            assert_eq!(modules.len(), 1);
+            self.stages.borrow_mut().push("parse");
+        }
+
+        fn after_lowering(
+            &mut self,
+            _: &mut PluginContext<'_>,
+            modules: &FxHashMap<ModuleId, stellar_hir::Module>,
+        ) {
+            assert_eq!(modules.len(), 1);
+            self.stages.borrow_mut().push("lowering");
+        }
+
+        fn after_resolution(
+            &mut self,
+            context: &mut PluginContext<'_>,
+            modules: &FxHashMap<ModuleId, stellar_hir::Module>,
+        ) {
+            let module = *modules.keys().next().unwrap();
+
+            assert!(module
+                .module_item_symbols(context.db())
+                .contains_key(&IdentifierId::from("main")));
+            self.stages.borrow_mut().push("resolution");
+        }
+    }
+
+    fn run(stop_after: CompilationStage) -> Vec<&'static str> {
+        let stages = Rc::new(RefCell::new(vec![]));
+        let mut plugins = Plugins::new();
+
+        plugins
+            .register(Recorder {
+                api_version: PLUGIN_API_VERSION,
+                stages: stages.clone(),
+            })
+            .unwrap();
+
+        let output = compile_str_with_plugins(
+            "test",
+            "fun main() {}",
+            CompileOptions::new().stop_after(stop_after),
+            &mut plugins,
+        );
+
+        assert!(output.diagnostics().is_ok());
+
+        let stages = stages.borrow().clone();
+        stages
+    }
+
+    #[test]
+    fn hooks() {
+        assert_eq!(
+            run(CompilationStage::Analyze),
+            ["parse", "lowering", "resolution"]
+        );
+        assert_eq!(run(CompilationStage::Lower), ["parse", "lowering"]);
+        assert_eq!(run(CompilationStage::Parse), ["parse"]);
+    }
+
+    #[test]
+    fn incompatible_api_version() {
+        let mut plugins = Plugins::new();
+
+        let error = plugins
+            .register(Recorder {
+                api_version: PLUGIN_API_VERSION + 1,
+                stages: Rc::default(),
+            })
+            .unwrap_err();
+
+        assert_eq!(error.name, "recorder");
+        assert!(plugins.is_empty());
+    }
+}
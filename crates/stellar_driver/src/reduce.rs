@@ -0,0 +1,395 @@
+//! Test case reduction: minimizes a source, that triggers a compiler bug.
+//!
+//! See [`reduce()`] for more details.
+
+use std::panic;
+
+use stellar_ast::{
+    token::{Keyword, Punctuator, RawToken},
+    visit::{walk_expression, Visitor},
+    Expression,
+};
+use stellar_filesystem::location::{ByteOffset, Location};
+use stellar_interner::DUMMY_PATH_ID;
+use stellar_lexer::Lexer;
+
+use crate::compile::{compile_str, CompilationStage, CompileOptions};
+
+/// Reduces a source, for which a given predicate holds (for example, the
+/// parser panics or a particular diagnostic is emitted), to a smaller source,
+/// for which the predicate still holds.
+///
+/// The reducer uses a simplified hierarchical delta debugging:
+///
+/// 1. The source is split into *forms* - items, statements, fields, match
+///    arms, call arguments, etc. A form ends with `;` or `,`, or with a block
+///    at the same nesting level, e.g. a function body.
+/// 2. Chunks of neighbouring forms are removed, starting with all forms of a
+///    block and halving the chunk size every time no chunk can be removed.
+/// 3. Expressions are replaced with their subexpressions, e.g. `a + foo(b)`
+///    with `a` or `foo(b)`. Expressions are found using the AST, so the step is
+///    skipped if the parser panics.
+///
+/// The steps are repeated until the source cannot be reduced anymore. If the
+/// predicate doesn't hold for the original source, it is returned unchanged.
+///
+/// ```
+/// use stellar_driver::{
+///     compile::{compile_str, CompileOptions},
+///     reduce::reduce,
+/// };
+///
+/// let source = "import std.io.println;
+///
+/// struct Point { x: int32, y: int32 }
+///
+/// pub(crate) fun distance(a: Point, b: Point): int32 {
+///     let dx = a.x - b.x;
+///     dx * dx
+/// }";
+///
+/// // find a minimal source, for which the parser reports an unknown visibility
+/// let reduced = reduce(source, |source| {
+///     compile_str("test", source, CompileOptions::new())
+///         .diagnostics()
+///         .diagnostics
+///         .iter()
+///         .any(|diagnostic| diagnostic.code.as_deref() == Some("E015"))
+/// });
+///
+/// assert_eq!(reduced, "pub(crate) fun distance(): int32 {}");
+/// ```
+///
+/// **Note**: a predicate checking that the compiler panics should catch the
+/// panic with [`std::panic::catch_unwind`].
+pub fn reduce(source: &str, mut predicate: impl FnMut(&str) -> bool) -> String {
+    let mut source = source.to_owned();
+
+    if !predicate(&source) {
+        return source;
+    }
+
+    loop {
+        let len = source.len();
+
+        source = remove_forms(source, &mut predicate);
+        source = hoist_subexpressions(source, &mut predicate);
+
+        if source.len() == len {
+            return source;
+        }
+    }
+}
+
+/// Removes chunks of forms in every block of the source.
+fn remove_forms(mut source: String, predicate: &mut impl FnMut(&str) -> bool) -> String {
+    let mut block_idx = 0;
+
+    while let Some(forms) = blocks(&source).get(block_idx) {
+        if let Some(reduced) = remove_chunk(&source, forms, predicate) {
+            // the block is split again, because locations of the forms changed
+            source = reduced;
+        } else {
+            block_idx += 1;
+        }
+    }
+
+    source
+}
+
+/// Removes the largest chunk of neighbouring forms, so that the predicate still
+/// holds.
+fn remove_chunk(
+    source: &str,
+    forms: &[Location],
+    predicate: &mut impl FnMut(&str) -> bool,
+) -> Option<String> {
+    let mut chunk_size = forms.len();
+
+    while chunk_size > 0 {
+        for first in (0..forms.len()).step_by(chunk_size) {
+            let last = (first + chunk_size).min(forms.len()) - 1;
+
+            let mut start = forms[first].start;
+            let mut end = forms[last].end;
+
+            if first == 0 {
+                // remove the whitespace after the chunk
+                end.0 += source[end.0..].len() - source[end.0..].trim_start().len();
+
+                if last == forms.len() - 1 {
+                    // the whole block is removed: `{ a; }` -> `{}`
+                    start.0 = source[..start.0].trim_end().len();
+                }
+            } else if last == forms.len() - 1 && source[..forms[first - 1].end.0].ends_with(',') {
+                // remove the trailing comma of the previous form, if the chunk
+                // ends the block: `foo(a, b)` -> `foo(a)`
+                start = forms[first - 1].end - 1;
+            } else {
+                // remove the whitespace before the chunk
+                start.0 = source[..start.0].trim_end().len();
+            }
+
+            let reduced = splice(source, start, end, "");
+
+            if predicate(&reduced) {
+                return Some(reduced);
+            }
+        }
+
+        chunk_size /= 2;
+    }
+
+    None
+}
+
+/// Replaces expressions with their subexpressions.
+fn hoist_subexpressions(mut source: String, predicate: &mut impl FnMut(&str) -> bool) -> String {
+    let mut candidate_idx = 0;
+
+    while let Some((location, replacement)) =
+        subexpressions(&source).and_then(|mut candidates| candidates.nth(candidate_idx))
+    {
+        let reduced = splice(&source, location.start, location.end, &replacement);
+
+        if reduced.len() < source.len() && predicate(&reduced) {
+            source = reduced;
+        } else {
+            candidate_idx += 1;
+        }
+    }
+
+    source
+}
+
+/// Returns locations of expressions together with the text of their
+/// subexpressions, or `None` if the parser panics.
+fn subexpressions(source: &str) -> Option<impl Iterator<Item = (Location, String)>> {
+    let output = panic::catch_unwind(|| {
+        compile_str(
+            "reduce",
+            source,
+            CompileOptions::new().stop_after(CompilationStage::Parse),
+        )
+    })
+    .ok()?;
+
+    let mut collector = SubexpressionsCollector {
+        source,
+        parents: vec![],
+        candidates: vec![],
+    };
+    collector.visit_module(output.ast());
+
+    Some(collector.candidates.into_iter())
+}
+
+struct SubexpressionsCollector<'s> {
+    source: &'s str,
+    parents: Vec<Location>,
+    candidates: Vec<(Location, String)>,
+}
+
+impl Visitor for SubexpressionsCollector<'_> {
+    fn visit_expression(&mut self, expression: &Expression) {
+        let location = expression.location();
+
+        if let Some(parent) = self.parents.last() {
+            self.candidates
+                .push((*parent, self.source[location].to_owned()));
+        }
+
+        self.parents.push(location);
+        walk_expression(self, expression);
+        self.parents.pop();
+    }
+}
+
+/// A block of forms being split.
+struct Block {
+    /// The punctuator closing the block, `None` for the top level.
+    close: Option<Punctuator>,
+
+    /// Locations of the forms, that are already split.
+    forms: Vec<Location>,
+
+    /// Location of the form being split.
+    current: Option<Location>,
+
+    /// Whether the form being split ended with a block, that can be continued,
+    /// for example, with `else`.
+    continuable: bool,
+}
+
+impl Block {
+    const fn new(close: Option<Punctuator>) -> Self {
+        Self {
+            close,
+            forms: vec![],
+            current: None,
+            continuable: false,
+        }
+    }
+
+    const fn extend_current(&mut self, location: Location) {
+        self.current = Some(match self.current {
+            Some(current) => Location {
+                end: location.end,
+                ..current
+            },
+            None => location,
+        });
+    }
+
+    fn finish_current(&mut self) {
+        self.forms.extend(self.current.take());
+        self.continuable = false;
+    }
+}
+
+/// Splits the source into forms. Returns forms of every block in the order the
+/// blocks start, so outer blocks come first.
+fn blocks(source: &str) -> Vec<Vec<Location>> {
+    let mut lexer = Lexer::new(DUMMY_PATH_ID, source);
+    let mut stack = vec![(ByteOffset(0), Block::new(None))];
+    let mut blocks = vec![];
+
+    loop {
+        let token = lexer.next_no_comments();
+        let block = &mut stack.last_mut().unwrap().1;
+
+        if block.continuable
+            && !matches!(
+                token.raw,
+                RawToken::Keyword(Keyword::Else)
+                    | RawToken::Punctuator(
+                        Punctuator::Semicolon | Punctuator::Comma | Punctuator::Dot
+                    )
+            )
+        {
+            block.finish_current();
+        }
+
+        match token.raw {
+            RawToken::EndOfFile => break,
+            RawToken::Punctuator(
+                open @ (Punctuator::OpenBrace | Punctuator::OpenBracket | Punctuator::OpenParent),
+            ) => {
+                block.extend_current(token.location);
+                stack.push((
+                    token.location.end,
+                    Block::new(Some(match open {
+                        Punctuator::OpenBrace => Punctuator::CloseBrace,
+                        Punctuator::OpenBracket => Punctuator::CloseBracket,
+                        _ => Punctuator::CloseParent,
+                    })),
+                ));
+            }
+            RawToken::Punctuator(close) if block.close == Some(close) => {
+                block.finish_current();
+
+                let (start, inner) = stack.pop().unwrap();
+                blocks.push((start, inner.forms));
+
+                let block = &mut stack.last_mut().unwrap().1;
+                block.extend_current(token.location);
+
+                // only blocks in braces contain items and statements, blocks
+                // in parentheses and brackets are split by commas
+                block.continuable = close == Punctuator::CloseBrace
+                    && matches!(block.close, None | Some(Punctuator::CloseBrace));
+            }
+            RawToken::Punctuator(Punctuator::Semicolon | Punctuator::Comma) => {
+                block.extend_current(token.location);
+                block.finish_current();
+            }
+            _ => block.extend_current(token.location),
+        }
+    }
+
+    // unclosed blocks
+    while let Some((start, mut block)) = stack.pop() {
+        block.finish_current();
+        blocks.push((start, block.forms));
+    }
+
+    blocks.sort_by_key(|(start, _)| *start);
+    blocks
+        .into_iter()
+        .map(|(_, forms)| forms)
+        .filter(|forms| !forms.is_empty())
+        .collect()
+}
+
+/// Replaces the text between two offsets.
+fn splice(source: &str, start: ByteOffset, end: ByteOffset, replacement: &str) -> String {
+    format!("{}{replacement}{}", &source[..start.0], &source[end.0..])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use super::reduce;
+    use crate::compile::{compile_str, CompilationStage, CompileOptions};
+
+    fn parses(source: &str) -> bool {
+        compile_str(
+            "test",
+            source,
+            CompileOptions::new().stop_after(CompilationStage::Parse),
+        )
+        .diagnostics()
+        .is_ok()
+    }
+
+    #[test]
+    fn items_and_statements() {
+        let source = "import std.io;
+
+struct A { a: int32, b: int32 }
+
+fun main() {
+    let a = 1;
+    if a > 0 { foo(); } else { bar(); }
+    baz();
+}
+
+fun unused() {}
+";
+
+        assert_eq!(
+            reduce(source, |source| parses(source) && source.contains("bar")),
+            "fun main() {\n    bar\n}\n"
+        );
+    }
+
+    #[test]
+    fn subexpressions() {
+        let source = "fun main() { let a = 1 + foo(bar, 2) * 3; }";
+
+        assert_eq!(
+            reduce(source, |source| parses(source)
+                && source.contains("foo(bar")),
+            "fun main() { let a = foo(bar); }"
+        );
+    }
+
+    #[test]
+    fn predicate_does_not_hold() {
+        assert_eq!(reduce("fun main() {}", |_| false), "fun main() {}");
+    }
+
+    #[test]
+    fn panicking_parser() {
+        // only forms are removed, because the AST is not available
+        let reduced = reduce("fun a() {} fun b() { c(); }", |source| {
+            panic::catch_unwind(|| {
+                assert!(!source.contains("c()"));
+            })
+            .is_err()
+        });
+
+        assert_eq!(reduced, "fun b() { c(); }");
+    }
+}
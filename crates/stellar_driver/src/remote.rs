@@ -0,0 +1,717 @@
+//! Remote build artifacts: sharing checked dependencies between machines.
+//!
+//! Checking a dependency produces an [`Artifact`] - its [`PackageHeader`]
+//! together with diagnostics emitted while checking it. Artifacts are stored
+//! in a [`RemoteCache`] under an [`ArtifactKey`], which is a hash of
+//! everything the result of checking a package depends on:
+//!
+//! - the compiler version and [`ARTIFACT_PROTOCOL_VERSION`],
+//! - the package name,
+//! - paths (relative to the source directory) and contents of its source files,
+//! - keys of its dependencies.
+//!
+//! So two machines checking the same sources always compute the same keys and
+//! one of them can reuse the work of another.
+//!
+//! An artifact is stored as a JSON envelope containing the protocol version,
+//! the key and a content hash of the payload (the header and diagnostics).
+//! [`ArtifactClient`] validates all of them before using a fetched artifact and
+//! falls back to checking the package locally if the artifact is missing,
+//! invalid or the cache is unavailable:
+//!
+//! ```no_run
+//! use stellar_driver::{
+//!     remote::{ArtifactClient, DirectoryCache},
+//!     Workspace,
+//! };
+//! use stellar_interner::IdentifierId;
+//!
+//! let mut client = ArtifactClient::new(DirectoryCache::new("/mnt/stellar-cache"));
+//!
+//! let result = client.check(
+//!     &Workspace::new("app", IdentifierId::from("app")),
+//!     &[Workspace::new("std", IdentifierId::from("std"))],
+//! )?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! The protocol is transport agnostic: implement [`RemoteCache`] to talk to an
+//! HTTP service, an object storage, etc.
+
+use std::{
+    fmt::{self, Display},
+    fs,
+    hash::Hasher,
+    io,
+    path::PathBuf,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use siphasher::sip128::{Hasher128, SipHasher13};
+use stellar_diagnostics::diagnostic::Diagnostic;
+#[cfg(feature = "debug")]
+use tracing::trace;
+
+use crate::{check_with_dependencies, header::PackageHeader, CheckResult, Workspace};
+
+/// The version of the artifact format. Artifacts with other versions are
+/// ignored.
+pub const ARTIFACT_PROTOCOL_VERSION: u32 = 1;
+
+/// A 128-bit hash of some content, displayed as 32 hexadecimal digits.
+///
+/// The hash is not cryptographic: it protects against corrupted and truncated
+/// artifacts, not against a malicious cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContentHash(u128);
+
+impl ContentHash {
+    /// Hashes given bytes.
+    #[inline]
+    #[must_use]
+    pub fn of(bytes: &[u8]) -> Self {
+        let mut hasher = ContentHasher::new();
+        hasher.write_bytes(bytes);
+        hasher.finish()
+    }
+}
+
+impl Display for ContentHash {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+/// Error returned when a string is not a valid [`ContentHash`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseContentHashError;
+
+impl Display for ParseContentHashError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("content hash must consist of 32 hexadecimal digits")
+    }
+}
+
+impl std::error::Error for ParseContentHashError {}
+
+impl FromStr for ContentHash {
+    type Err = ParseContentHashError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 32 || !s.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(ParseContentHashError);
+        }
+
+        u128::from_str_radix(s, 16)
+            .map(Self)
+            .map_err(|_| ParseContentHashError)
+    }
+}
+
+impl Serialize for ContentHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ContentHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Hashes a sequence of values. Every value is prefixed with its length, so
+/// that different sequences can't produce the same input for the hasher.
+pub(crate) struct ContentHasher(SipHasher13);
+
+impl ContentHasher {
+    pub(crate) fn new() -> Self {
+        Self(SipHasher13::new())
+    }
+
+    pub(crate) fn write_bytes(&mut self, bytes: &[u8]) {
+        self.0.write_u64(bytes.len() as u64);
+        self.0.write(bytes);
+    }
+
+    pub(crate) fn write_str(&mut self, s: &str) {
+        self.write_bytes(s.as_bytes());
+    }
+
+    pub(crate) fn finish(&self) -> ContentHash {
+        ContentHash(self.0.finish128().as_u128())
+    }
+}
+
+/// Identifies the result of checking a package. See [module level
+/// docs](self) for more details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ArtifactKey(ContentHash);
+
+impl ArtifactKey {
+    /// Computes the key of a package, which depends on packages with given
+    /// keys.
+    ///
+    /// # Errors
+    /// Returns an error if package source files cannot be read.
+    pub fn compute(workspace: &Workspace, dependencies: &[Self]) -> Result<Self, io::Error> {
+        let mut hasher = ContentHasher::new();
+
+        hasher.write_str(env!("CARGO_PKG_VERSION"));
+        hasher.write_str(&ARTIFACT_PROTOCOL_VERSION.to_string());
+        hasher.write_str(&workspace.package_name().as_str());
+
+        let source_directory = workspace.source_directory();
+        let files = workspace.source_files()?;
+
+        hasher.write_str(&files.len().to_string());
+
+        for file in files {
+            // keys must not depend on where the package is located
+            let relative = file
+                .strip_prefix(&source_directory)
+                .unwrap_or(&file)
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            hasher.write_str(&relative);
+            hasher.write_bytes(&fs::read(&file)?);
+        }
+
+        hasher.write_str(&dependencies.len().to_string());
+
+        for dependency in dependencies {
+            hasher.write_str(&dependency.to_string());
+        }
+
+        Ok(Self(hasher.finish()))
+    }
+
+    /// Returns the hash the key consists of.
+    #[inline]
+    #[must_use]
+    pub const fn hash(&self) -> ContentHash {
+        self.0
+    }
+}
+
+impl Display for ArtifactKey {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A checked package, that can be stored in a remote cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Artifact {
+    key: ArtifactKey,
+    payload: ArtifactPayload,
+}
+
+/// The part of an artifact covered by its content hash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ArtifactPayload {
+    header: PackageHeader,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// The serialized form of an artifact.
+#[derive(Serialize, Deserialize)]
+struct ArtifactEnvelope {
+    version: u32,
+    key: ArtifactKey,
+    hash: ContentHash,
+
+    /// Serialized [`ArtifactPayload`]. It is stored as a string, so that its
+    /// hash can be checked before it is deserialized.
+    payload: String,
+}
+
+/// Error returned when a fetched artifact can't be used.
+#[derive(Debug)]
+pub enum ArtifactError {
+    /// The artifact is not valid JSON or doesn't follow the protocol.
+    Malformed(serde_json::Error),
+
+    /// The artifact was produced with another version of the protocol.
+    UnsupportedVersion(u32),
+
+    /// The artifact is stored under a wrong key, which is the one the
+    /// artifact contains.
+    KeyMismatch(ArtifactKey),
+
+    /// The content hash of the artifact doesn't match its payload.
+    HashMismatch,
+}
+
+impl Display for ArtifactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(error) => write!(f, "malformed artifact: {error}"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported artifact protocol version {version} (expected {ARTIFACT_PROTOCOL_VERSION})"
+            ),
+            Self::KeyMismatch(key) => write!(f, "artifact is stored under a wrong key, expected {key}"),
+            Self::HashMismatch => f.write_str("artifact content hash mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for ArtifactError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Malformed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Artifact {
+    /// Creates an artifact from the result of checking a package with a given
+    /// key.
+    #[must_use]
+    pub fn new(key: ArtifactKey, result: &CheckResult) -> Self {
+        Self {
+            key,
+            payload: ArtifactPayload {
+                header: result.header(),
+                diagnostics: result.diagnostics().diagnostics.clone(),
+            },
+        }
+    }
+
+    /// Returns the key of the artifact.
+    #[inline]
+    #[must_use]
+    pub const fn key(&self) -> ArtifactKey {
+        self.key
+    }
+
+    /// Returns the header of the checked package.
+    #[inline]
+    #[must_use]
+    pub const fn header(&self) -> &PackageHeader {
+        &self.payload.header
+    }
+
+    /// Returns diagnostics emitted while checking the package.
+    #[inline]
+    #[must_use]
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.payload.diagnostics
+    }
+
+    /// Serializes the artifact.
+    ///
+    /// # Panics
+    /// Panics if the artifact cannot be serialized.
+    #[must_use]
+    pub fn serialize(&self) -> Vec<u8> {
+        let payload = serde_json::to_string(&self.payload).unwrap();
+
+        serde_json::to_vec(&ArtifactEnvelope {
+            version: ARTIFACT_PROTOCOL_VERSION,
+            key: self.key,
+            hash: ContentHash::of(payload.as_bytes()),
+            payload,
+        })
+        .unwrap()
+    }
+
+    /// Deserializes an artifact fetched with a given key, checking its
+    /// protocol version, key and content hash.
+    ///
+    /// # Errors
+    /// Returns an error if the artifact is malformed or fails one of the
+    /// checks.
+    pub fn deserialize(bytes: &[u8], key: ArtifactKey) -> Result<Self, ArtifactError> {
+        let envelope: ArtifactEnvelope =
+            serde_json::from_slice(bytes).map_err(ArtifactError::Malformed)?;
+
+        if envelope.version != ARTIFACT_PROTOCOL_VERSION {
+            return Err(ArtifactError::UnsupportedVersion(envelope.version));
+        }
+
+        if envelope.key != key {
+            return Err(ArtifactError::KeyMismatch(envelope.key));
+        }
+
+        if ContentHash::of(envelope.payload.as_bytes()) != envelope.hash {
+            return Err(ArtifactError::HashMismatch);
+        }
+
+        Ok(Self {
+            key,
+            payload: serde_json::from_str(&envelope.payload).map_err(ArtifactError::Malformed)?,
+        })
+    }
+}
+
+/// A service storing serialized artifacts by their keys.
+pub trait RemoteCache {
+    /// Returns the artifact stored under a given key, or `None` if there is no
+    /// such artifact.
+    ///
+    /// # Errors
+    /// Returns an error if the cache is unavailable.
+    fn fetch(&mut self, key: ArtifactKey) -> Result<Option<Vec<u8>>, io::Error>;
+
+    /// Stores an artifact under a given key.
+    ///
+    /// # Errors
+    /// Returns an error if the cache is unavailable.
+    fn store(&mut self, key: ArtifactKey, artifact: &[u8]) -> Result<(), io::Error>;
+}
+
+/// A cache storing artifacts as files in a directory, e.g. a network share
+/// mounted on every CI machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryCache {
+    directory: PathBuf,
+}
+
+impl DirectoryCache {
+    /// Creates a cache in a given directory. The directory is created when the
+    /// first artifact is stored.
+    #[inline]
+    #[must_use]
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    fn artifact_path(&self, key: ArtifactKey) -> PathBuf {
+        self.directory.join(format!("{key}.json"))
+    }
+}
+
+impl RemoteCache for DirectoryCache {
+    fn fetch(&mut self, key: ArtifactKey) -> Result<Option<Vec<u8>>, io::Error> {
+        match fs::read(self.artifact_path(key)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn store(&mut self, key: ArtifactKey, artifact: &[u8]) -> Result<(), io::Error> {
+        fs::create_dir_all(&self.directory)?;
+
+        // other machines must never observe a partially written artifact
+        let path = self.artifact_path(key);
+        let temporary_path = path.with_extension(format!("{}.tmp", std::process::id()));
+
+        fs::write(&temporary_path, artifact)?;
+        fs::rename(temporary_path, path)
+    }
+}
+
+/// Explains why an artifact was produced locally.
+#[derive(Debug)]
+pub enum FallbackReason {
+    /// The cache doesn't contain the artifact.
+    NotFound,
+
+    /// The cache couldn't be reached.
+    Unavailable(io::Error),
+
+    /// The fetched artifact can't be used.
+    Invalid(ArtifactError),
+}
+
+/// An artifact obtained by [`ArtifactClient`].
+#[derive(Debug)]
+pub struct FetchedArtifact {
+    /// The artifact.
+    pub artifact: Artifact,
+
+    /// `None` if the artifact was fetched from the cache, otherwise the reason
+    /// why the package was checked locally.
+    pub fallback_reason: Option<FallbackReason>,
+}
+
+/// Obtains artifacts of dependencies from a remote cache, checking them
+/// locally if needed. See [module level docs](self) for more details.
+#[derive(Debug)]
+pub struct ArtifactClient<C> {
+    cache: C,
+
+    /// Whether artifacts produced locally are stored in the cache.
+    upload: bool,
+}
+
+impl<C: RemoteCache> ArtifactClient<C> {
+    /// Creates a client using a given cache, which uploads artifacts produced
+    /// locally.
+    #[inline]
+    #[must_use]
+    pub const fn new(cache: C) -> Self {
+        Self {
+            cache,
+            upload: true,
+        }
+    }
+
+    /// Sets whether artifacts produced locally are stored in the cache. For
+    /// example, builds of untrusted pull requests should only read from it.
+    #[inline]
+    #[must_use]
+    pub const fn with_upload(mut self, upload: bool) -> Self {
+        self.upload = upload;
+        self
+    }
+
+    /// Returns the underlying cache.
+    #[inline]
+    #[must_use]
+    pub const fn cache(&self) -> &C {
+        &self.cache
+    }
+
+    /// Obtains the artifact of a package, which depends on packages with given
+    /// artifacts.
+    ///
+    /// Dependencies must be sorted topologically, see
+    /// [`check_with_dependencies`].
+    ///
+    /// # Errors
+    /// Returns an error if package source files cannot be read. Errors of the
+    /// cache are never returned, the package is checked locally instead.
+    pub fn fetch(
+        &mut self,
+        workspace: &Workspace,
+        dependencies: &[Artifact],
+    ) -> Result<FetchedArtifact, io::Error> {
+        let key = ArtifactKey::compute(
+            workspace,
+            &dependencies.iter().map(Artifact::key).collect::<Vec<_>>(),
+        )?;
+
+        let fallback_reason = match self.cache.fetch(key) {
+            Ok(Some(bytes)) => match Artifact::deserialize(&bytes, key) {
+                Ok(artifact) => {
+                    return Ok(FetchedArtifact {
+                        artifact,
+                        fallback_reason: None,
+                    });
+                }
+                Err(error) => FallbackReason::Invalid(error),
+            },
+            Ok(None) => FallbackReason::NotFound,
+            Err(error) => FallbackReason::Unavailable(error),
+        };
+
+        #[cfg(feature = "debug")]
+        trace!(
+            "checking `{}` locally: {fallback_reason:?}",
+            workspace.package_name()
+        );
+
+        let headers = dependencies
+            .iter()
+            .map(|dependency| dependency.header().clone())
+            .collect::<Vec<_>>();
+        let artifact = Artifact::new(key, &check_with_dependencies(workspace, &headers)?);
+
+        if self.upload {
+            // the cache is an optimization, failing to fill it must not fail
+            // the build
+            #[allow(unused_variables)]
+            if let Err(error) = self.cache.store(key, &artifact.serialize()) {
+                #[cfg(feature = "debug")]
+                trace!("failed to upload artifact {key}: {error}");
+            }
+        }
+
+        Ok(FetchedArtifact {
+            artifact,
+            fallback_reason: Some(fallback_reason),
+        })
+    }
+
+    /// Obtains artifacts of packages sorted topologically: every package can
+    /// only depend on packages that come before it.
+    ///
+    /// # Errors
+    /// Returns an error if source files of one of the packages cannot be read.
+    pub fn fetch_all(
+        &mut self,
+        workspaces: &[Workspace],
+    ) -> Result<Vec<FetchedArtifact>, io::Error> {
+        let mut fetched = Vec::with_capacity(workspaces.len());
+        let mut artifacts = Vec::with_capacity(workspaces.len());
+
+        for workspace in workspaces {
+            let artifact = self.fetch(workspace, &artifacts)?;
+
+            artifacts.push(artifact.artifact.clone());
+            fetched.push(artifact);
+        }
+
+        Ok(fetched)
+    }
+
+    /// Checks a package, obtaining artifacts of its dependencies (sorted
+    /// topologically) from the cache when possible.
+    ///
+    /// # Errors
+    /// Returns an error if source files of the package or of one of its
+    /// dependencies cannot be read.
+    pub fn check(
+        &mut self,
+        workspace: &Workspace,
+        dependencies: &[Workspace],
+    ) -> Result<CheckResult, io::Error> {
+        let headers = self
+            .fetch_all(dependencies)?
+            .into_iter()
+            .map(|fetched| fetched.artifact.payload.header)
+            .collect::<Vec<_>>();
+
+        check_with_dependencies(workspace, &headers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io, slice};
+
+    use stellar_fx_hash::FxHashMap;
+
+    use super::{
+        Artifact, ArtifactClient, ArtifactError, ArtifactKey, ContentHash, FallbackReason,
+        RemoteCache,
+    };
+    use crate::test_utils::workspace;
+
+    #[derive(Default)]
+    struct MemoryCache {
+        artifacts: FxHashMap<ArtifactKey, Vec<u8>>,
+        unavailable: bool,
+    }
+
+    impl RemoteCache for MemoryCache {
+        fn fetch(&mut self, key: ArtifactKey) -> Result<Option<Vec<u8>>, io::Error> {
+            if self.unavailable {
+                return Err(io::Error::other("unavailable"));
+            }
+
+            Ok(self.artifacts.get(&key).cloned())
+        }
+
+        fn store(&mut self, key: ArtifactKey, artifact: &[u8]) -> Result<(), io::Error> {
+            self.artifacts.insert(key, artifact.to_vec());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn content_hash_roundtrip() {
+        let hash = ContentHash::of(b"hello");
+
+        assert_eq!(hash.to_string().len(), 32);
+        assert_eq!(hash.to_string().parse(), Ok(hash));
+        assert!("xyz".parse::<ContentHash>().is_err());
+    }
+
+    #[test]
+    fn keys_depend_on_sources_only() {
+        let a = workspace("dep_a", &[("package.sr", "pub fun foo() {}")]);
+        let b = workspace("dep_b", &[("package.sr", "pub fun foo() {}")]);
+        let c = workspace("dep_c", &[("package.sr", "pub fun bar() {}")]);
+
+        let key = ArtifactKey::compute(&a, &[]).unwrap();
+
+        assert_eq!(ArtifactKey::compute(&b, &[]).unwrap(), key);
+        assert_ne!(ArtifactKey::compute(&c, &[]).unwrap(), key);
+        assert_ne!(ArtifactKey::compute(&a, &[key]).unwrap(), key);
+
+        for workspace in [a, b, c] {
+            fs::remove_dir_all(workspace.root()).unwrap();
+        }
+    }
+
+    #[test]
+    fn fetch_and_fallback() {
+        let dependency = workspace("dep_fetch", &[("package.sr", "pub fun foo() {}")]);
+        let app = workspace("app_fetch", &[("package.sr", "import dep.foo;")]);
+
+        let mut client = ArtifactClient::new(MemoryCache::default());
+
+        // the first build checks the dependency locally and uploads it
+        let fetched = client.fetch(&dependency, &[]).unwrap();
+        assert!(matches!(
+            fetched.fallback_reason,
+            Some(FallbackReason::NotFound)
+        ));
+
+        // the second one fetches it
+        let refetched = client.fetch(&dependency, &[]).unwrap();
+        assert!(refetched.fallback_reason.is_none());
+        assert_eq!(refetched.artifact, fetched.artifact);
+
+        let result = client.check(&app, slice::from_ref(&dependency)).unwrap();
+        assert!(result.diagnostics().is_ok());
+
+        // corrupted artifacts are ignored
+        let key = fetched.artifact.key();
+        let mut bytes = client.cache.artifacts[&key].clone();
+        let position = bytes.len() - 10;
+        bytes[position] ^= 1;
+        client.cache.artifacts.insert(key, bytes);
+
+        let fetched = client.fetch(&dependency, &[]).unwrap();
+        assert!(matches!(
+            fetched.fallback_reason,
+            Some(FallbackReason::Invalid(
+                ArtifactError::HashMismatch | ArtifactError::Malformed(_)
+            ))
+        ));
+
+        client.cache.unavailable = true;
+
+        let fetched = client.fetch(&dependency, &[]).unwrap();
+        assert!(matches!(
+            fetched.fallback_reason,
+            Some(FallbackReason::Unavailable(_))
+        ));
+
+        for workspace in [dependency, app] {
+            fs::remove_dir_all(workspace.root()).unwrap();
+        }
+    }
+
+    #[test]
+    fn key_mismatch() {
+        let dependency = workspace("dep_mismatch", &[("package.sr", "pub fun foo() {}")]);
+        let mut client = ArtifactClient::new(MemoryCache::default()).with_upload(false);
+
+        let artifact = client.fetch(&dependency, &[]).unwrap().artifact;
+        let other_key = ArtifactKey::compute(&dependency, &[artifact.key()]).unwrap();
+
+        assert!(client.cache().artifacts.is_empty());
+        assert!(matches!(
+            Artifact::deserialize(&artifact.serialize(), other_key),
+            Err(ArtifactError::KeyMismatch(_))
+        ));
+
+        fs::remove_dir_all(dependency.root()).unwrap();
+    }
+}
@@ -0,0 +1,506 @@
+//! Priority-based analysis scheduling for language servers.
+//!
+//! Analyzing a large package takes a while, but a user is usually interested
+//! only in diagnostics of the file they are editing. [`Scheduler`] keeps track
+//! of files open in the editor and analyzes them together with their direct
+//! dependencies first, before the whole package is analyzed:
+//!
+//! ```no_run
+//! use std::ops::ControlFlow;
+//!
+//! use stellar_database::cancellation::CancellationToken;
+//! use stellar_driver::{schedule::Scheduler, Workspace};
+//! use stellar_interner::IdentifierId;
+//!
+//! let workspace = Workspace::new("app", IdentifierId::from("app"));
+//!
+//! let mut scheduler = Scheduler::new();
+//! scheduler.open_file("app/src/utils.sr");
+//! scheduler.set_active_file("app/src/main.sr");
+//!
+//! scheduler.run(&workspace, &[], CancellationToken::new(), |pass| {
+//!     // the first pass contains diagnostics of `main.sr` and `utils.sr`,
+//!     // the second one diagnostics of the whole package
+//!     println!(
+//!         "{} diagnostics (complete: {})",
+//!         pass.result.diagnostics().diagnostics.len(),
+//!         pass.is_complete
+//!     );
+//!
+//!     ControlFlow::Continue(())
+//! })?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! Paths of files must be in the same form as the ones returned by
+//! [`Workspace::source_files`], i.e. start with the package root.
+
+use std::{
+    fs, io,
+    ops::ControlFlow,
+    path::{Path as FsPath, PathBuf},
+};
+
+use stellar_ast::{visit::Visitor, ImportPath, Submodule};
+use stellar_database::{cancellation::CancellationToken, PackageData, Path, State};
+use stellar_diagnostics::{diagnostic::Diagnostic, Diagnostics};
+use stellar_filesystem::location::Location;
+use stellar_fx_hash::{FxHashMap, FxHashSet};
+use stellar_interner::{builtin_identifiers::SMALL_SELF, IdentifierId, PathId};
+use stellar_parser::{parse_module_using, ParseState};
+
+use crate::{
+    check_using,
+    header::PackageHeader,
+    package::{module_filepaths, module_path_segments},
+    plugin::Plugins,
+    CheckResult, Workspace,
+};
+
+/// How soon a file is analyzed, files with higher priorities are analyzed
+/// first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    /// A file, that is analyzed only together with the whole package.
+    #[default]
+    Background,
+
+    /// A file, that is imported or declared by an open file.
+    Dependency,
+
+    /// A file open in the editor.
+    Open,
+
+    /// The file currently being edited.
+    Active,
+}
+
+/// A source file together with its priority, see [`Scheduler::plan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledFile {
+    /// Path of the file.
+    pub path: PathBuf,
+
+    /// Priority of the file.
+    pub priority: Priority,
+}
+
+/// Information passed to the [`Scheduler::run`] callback after every analysis
+/// pass.
+#[derive(Debug)]
+pub struct ScheduledPass {
+    /// The result of the analysis.
+    ///
+    /// For an incomplete pass, only diagnostics of open files are kept, because
+    /// diagnostics of their dependencies may refer to modules, that were not
+    /// analyzed.
+    pub result: CheckResult,
+
+    /// Whether the whole package was analyzed.
+    pub is_complete: bool,
+}
+
+/// Keeps track of priorities of source files and analyzes them in the order
+/// of their priorities. See [module level docs](self) for more details.
+#[derive(Debug, Clone, Default)]
+pub struct Scheduler {
+    /// Priorities set explicitly, files not present here have the
+    /// [`Priority::Background`] priority, unless they are dependencies of open
+    /// files.
+    priorities: FxHashMap<PathBuf, Priority>,
+}
+
+impl Scheduler {
+    /// Creates a scheduler without open files.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the priority of a file.
+    #[inline]
+    pub fn set_priority(&mut self, path: impl Into<PathBuf>, priority: Priority) {
+        let path = path.into();
+
+        if priority == Priority::Background {
+            self.priorities.remove(&path);
+        } else {
+            self.priorities.insert(path, priority);
+        }
+    }
+
+    /// Returns the priority of a file set explicitly, dependencies of open
+    /// files are only known after [`Scheduler::plan`].
+    #[inline]
+    #[must_use]
+    pub fn priority(&self, path: impl AsRef<FsPath>) -> Priority {
+        self.priorities
+            .get(path.as_ref())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Marks a file as open in the editor.
+    #[inline]
+    pub fn open_file(&mut self, path: impl Into<PathBuf>) {
+        self.set_priority(path, Priority::Open);
+    }
+
+    /// Marks a file as closed.
+    #[inline]
+    pub fn close_file(&mut self, path: impl AsRef<FsPath>) {
+        self.priorities.remove(path.as_ref());
+    }
+
+    /// Marks a file as the one currently being edited. The previously active
+    /// file stays open.
+    pub fn set_active_file(&mut self, path: impl Into<PathBuf>) {
+        for priority in self.priorities.values_mut() {
+            if *priority == Priority::Active {
+                *priority = Priority::Open;
+            }
+        }
+
+        self.set_priority(path, Priority::Active);
+    }
+
+    /// Returns all source files of the package sorted by their priorities,
+    /// files with the same priority are sorted in lexicographical order.
+    ///
+    /// Files, that are imported or declared as submodules by open files, get
+    /// the [`Priority::Dependency`] priority.
+    ///
+    /// # Errors
+    /// Returns an error if package source files cannot be read.
+    pub fn plan(&self, workspace: &Workspace) -> Result<Vec<ScheduledFile>, io::Error> {
+        let source_files = workspace.source_files()?;
+        let known_files = source_files.iter().collect::<FxHashSet<_>>();
+        let mut dependencies = FxHashSet::default();
+
+        for (path, priority) in &self.priorities {
+            if *priority >= Priority::Open && known_files.contains(path) {
+                dependencies.extend(direct_dependencies(workspace, path, &known_files)?);
+            }
+        }
+
+        let mut plan = source_files
+            .into_iter()
+            .map(|path| {
+                let mut priority = self.priority(&path);
+
+                if priority < Priority::Dependency && dependencies.contains(&path) {
+                    priority = Priority::Dependency;
+                }
+
+                ScheduledFile { path, priority }
+            })
+            .collect::<Vec<_>>();
+
+        // the sort is stable, so files stay sorted by their paths
+        plan.sort_by_key(|file| std::cmp::Reverse(file.priority));
+
+        Ok(plan)
+    }
+
+    /// Analyzes open files together with their dependencies, and then the
+    /// whole package, calling `callback` after every pass.
+    ///
+    /// The first pass is skipped if no files are open or if the open files
+    /// and their dependencies are the whole package. The second pass is
+    /// skipped if `callback` returns [`ControlFlow::Break`] or if the token is
+    /// cancelled, e.g. because priorities changed.
+    ///
+    /// # Errors
+    /// Returns an error if package source files cannot be read.
+    pub fn run<F>(
+        &self,
+        workspace: &Workspace,
+        dependencies: &[PackageHeader],
+        token: CancellationToken,
+        mut callback: F,
+    ) -> Result<(), io::Error>
+    where
+        F: FnMut(ScheduledPass) -> ControlFlow<()>,
+    {
+        let plan = self.plan(workspace)?;
+        let prioritized = plan
+            .iter()
+            .filter(|file| file.priority > Priority::Background)
+            .map(|file| file.path.clone())
+            .collect::<Vec<_>>();
+
+        if !prioritized.is_empty() && prioritized.len() < plan.len() {
+            let open_files = plan
+                .iter()
+                .filter(|file| file.priority >= Priority::Open)
+                .map(|file| PathId::from(&file.path))
+                .collect::<FxHashSet<_>>();
+
+            let mut result = check_using(
+                State::new().with_cancellation_token(token.clone()),
+                workspace,
+                dependencies,
+                &mut Plugins::new(),
+                Some(prioritized),
+            )?;
+            retain_diagnostics_of(result.state.diagnostics_mut(), &open_files);
+
+            if callback(ScheduledPass {
+                result,
+                is_complete: false,
+            })
+            .is_break()
+                || token.is_cancelled()
+            {
+                return Ok(());
+            }
+        }
+
+        let result = check_using(
+            State::new().with_cancellation_token(token),
+            workspace,
+            dependencies,
+            &mut Plugins::new(),
+            None,
+        )?;
+
+        let _ = callback(ScheduledPass {
+            result,
+            is_complete: true,
+        });
+
+        Ok(())
+    }
+}
+
+/// Removes diagnostics, that don't refer to any of given files.
+fn retain_diagnostics_of(diagnostics: &mut Diagnostics, files: &FxHashSet<PathId>) {
+    diagnostics.diagnostics.retain(|diagnostic| {
+        diagnostic
+            .files_involved()
+            .iter()
+            .any(|file| files.contains(file))
+    });
+    diagnostics.files_involved = diagnostics
+        .diagnostics
+        .iter()
+        .flat_map(Diagnostic::files_involved)
+        .collect();
+}
+
+/// Returns source files of modules, that are imported or declared as
+/// submodules in a given file.
+fn direct_dependencies(
+    workspace: &Workspace,
+    path: &FsPath,
+    known_files: &FxHashSet<&PathBuf>,
+) -> Result<Vec<PathBuf>, io::Error> {
+    let source = fs::read_to_string(path)?;
+    let source_directory = workspace.source_directory();
+    let segments = module_path_segments(&source_directory, workspace.package_name(), path);
+
+    // only imports and submodule declarations are needed, so the file is
+    // parsed in a separate state and function bodies are skipped
+    let mut state = State::new();
+    let package = PackageData::alloc_in_memory(
+        state.db_mut(),
+        workspace.package_name(),
+        PathId::from(workspace.root()),
+    );
+    let mut diagnostics = Diagnostics::new();
+    let parse_state = ParseState::new(PathId::from(path), &source, &mut diagnostics)
+        .with_deferred_function_bodies();
+    let ast = parse_module_using(
+        &mut state,
+        package,
+        Path::new(segments.clone()),
+        parse_state,
+    )
+    .into_ast();
+
+    let mut collector = DependenciesCollector {
+        package_name: workspace.package_name(),
+        module: segments,
+        modules: vec![],
+    };
+
+    for item in &ast.items {
+        collector.visit_module_item(item);
+    }
+
+    Ok(collector
+        .modules
+        .iter()
+        .filter_map(|segments| {
+            // the longest prefix, that is a module, e.g. `utils` for
+            // `self.utils.File`, or the root module
+            (0..=segments.len()).rev().find_map(|len| {
+                module_filepaths(&source_directory, &segments[..len])
+                    .into_iter()
+                    .find(|path| known_files.contains(path))
+            })
+        })
+        .collect())
+}
+
+/// Collects paths of modules, that are referred to by imports and submodule
+/// declarations of a module. Paths don't include the package name.
+struct DependenciesCollector {
+    package_name: IdentifierId,
+
+    /// Path of the module being visited, including the package name.
+    module: Vec<IdentifierId>,
+    modules: Vec<Vec<IdentifierId>>,
+}
+
+impl Visitor for DependenciesCollector {
+    fn visit_import(&mut self, _: Location, path: &ImportPath) {
+        for path in path.clone().flatten() {
+            let Some((first, rest)) = path.path().identifiers.split_first() else {
+                continue;
+            };
+
+            // imports from other packages are resolved using headers
+            if (first.id == SMALL_SELF || first.id == self.package_name) && !rest.is_empty() {
+                self.modules
+                    .push(rest.iter().map(|identifier| identifier.id).collect());
+            }
+        }
+    }
+
+    fn visit_submodule(&mut self, submodule: &Submodule) {
+        self.module.push(submodule.name.id);
+
+        if let Some(items) = &submodule.items {
+            for item in items {
+                self.visit_module_item(item);
+            }
+        } else {
+            self.modules.push(self.module[1..].to_vec());
+        }
+
+        self.module.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use stellar_database::cancellation::CancellationToken;
+
+    use super::{Priority, Scheduler};
+    use crate::test_utils::workspace;
+
+    const FILES: &[(&str, &str)] = &[
+        ("package.sr", "module main;"),
+        (
+            "main.sr",
+            "import self.utils.fs.File; import std.io; module cli;",
+        ),
+        ("main/cli.sr", "pub fun run() {}"),
+        ("utils/fs.sr", "pub struct File {}"),
+        ("unrelated.sr", "import self.main.unknown;"),
+    ];
+
+    #[test]
+    fn plan() {
+        let workspace = workspace("plan", FILES);
+        let source_directory = workspace.source_directory();
+
+        let mut scheduler = Scheduler::new();
+        scheduler.open_file(source_directory.join("package.sr"));
+        scheduler.set_active_file(source_directory.join("main.sr"));
+
+        let plan = scheduler
+            .plan(&workspace)
+            .unwrap()
+            .into_iter()
+            .map(|file| {
+                (
+                    file.path
+                        .strip_prefix(&source_directory)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/"),
+                    file.priority,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            plan,
+            [
+                ("main.sr".to_owned(), Priority::Active),
+                ("package.sr".to_owned(), Priority::Open),
+                ("main/cli.sr".to_owned(), Priority::Dependency),
+                ("utils/fs.sr".to_owned(), Priority::Dependency),
+                ("unrelated.sr".to_owned(), Priority::Background),
+            ]
+        );
+
+        // the previously active file stays open
+        scheduler.set_active_file(source_directory.join("package.sr"));
+        assert_eq!(
+            scheduler.priority(source_directory.join("main.sr")),
+            Priority::Open
+        );
+
+        scheduler.close_file(source_directory.join("main.sr"));
+        assert_eq!(
+            scheduler.priority(source_directory.join("main.sr")),
+            Priority::Background
+        );
+    }
+
+    #[test]
+    fn run() {
+        let workspace = workspace("run", FILES);
+
+        let mut scheduler = Scheduler::new();
+        scheduler.set_active_file(workspace.source_directory().join("main.sr"));
+
+        let mut passes = vec![];
+        scheduler
+            .run(&workspace, &[], CancellationToken::new(), |pass| {
+                passes.push((
+                    pass.is_complete,
+                    pass.result.modules().len(),
+                    pass.result.diagnostics().diagnostics.len(),
+                ));
+
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+
+        // `std` is not a dependency, the unresolved import in `unrelated.sr` is
+        // only reported in the complete pass
+        assert!(!passes[0].0);
+        assert_eq!(passes[0].2, 1);
+        assert!(passes[1].0);
+        assert!(passes[1].1 > passes[0].1);
+        assert_eq!(passes[1].2, 2);
+    }
+
+    #[test]
+    fn cancelled_run() {
+        let workspace = workspace("cancelled", FILES);
+        let token = CancellationToken::new();
+
+        let mut scheduler = Scheduler::new();
+        scheduler.open_file(workspace.source_directory().join("main.sr"));
+
+        let mut passes = 0;
+        scheduler
+            .run(&workspace, &[], token.clone(), |_| {
+                passes += 1;
+                token.cancel();
+
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+
+        assert_eq!(passes, 1);
+    }
+}
@@ -0,0 +1,354 @@
+//! Package snapshots: the whole database of a checked package stored in a
+//! [`PackageCache`] between builds, see [`cache`](crate::cache) for more
+//! details.
+
+use std::{
+    fs,
+    path::{Path as FsPath, PathBuf},
+};
+
+use filetime::FileTime;
+use serde::{Deserialize, Serialize};
+use stellar_database::{Database, ModuleId, PackageId, State};
+use stellar_diagnostics::{diagnostic::Diagnostic, Diagnostics};
+use stellar_fx_hash::FxHashMap;
+use stellar_interner::IdentifierId;
+#[cfg(feature = "debug")]
+use tracing::trace;
+
+use crate::{
+    cache::PackageCache,
+    header::PackageHeader,
+    remote::{ContentHash, ContentHasher},
+    CheckResult, Workspace,
+};
+
+/// Version of the format of snapshots, which is bumped every time the format
+/// changes. Snapshots with other versions are ignored.
+const SNAPSHOT_FORMAT_VERSION: u32 = 2;
+
+/// Bytes every snapshot starts with.
+const MAGIC: &[u8; 4] = b"SDBS";
+
+/// Name of the snapshot file in the cache directory.
+const SNAPSHOT_FILENAME: &str = "package.db";
+
+/// Last modification time of a file as seconds and nanoseconds since the Unix
+/// epoch.
+type ModificationTime = (i64, u32);
+
+fn modification_time_of(path: &FsPath) -> Option<ModificationTime> {
+    let time = FileTime::from_last_modification_time(&fs::metadata(path).ok()?);
+
+    Some((time.unix_seconds(), time.nanoseconds()))
+}
+
+/// Sources of a package and headers of its dependencies before a build.
+pub(crate) struct Fingerprint {
+    package_name: IdentifierId,
+    dependencies: ContentHash,
+    files: Vec<(PathBuf, ModificationTime)>,
+}
+
+impl Fingerprint {
+    /// Returns `None` if source files of the package can't be listed.
+    pub(crate) fn compute(workspace: &Workspace, dependencies: &[PackageHeader]) -> Option<Self> {
+        let files = workspace
+            .source_files()
+            .ok()?
+            .into_iter()
+            .map(|path| modification_time_of(&path).map(|modified| (path, modified)))
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut hasher = ContentHasher::new();
+        hasher.write_str(&dependencies.len().to_string());
+
+        for dependency in dependencies {
+            hasher.write_bytes(&dependency.serialize());
+        }
+
+        Some(Self {
+            package_name: workspace.package_name(),
+            dependencies: hasher.finish(),
+            files,
+        })
+    }
+}
+
+/// A source file of a package at the moment its snapshot was stored.
+#[derive(Serialize, Deserialize)]
+struct SourceFile {
+    path: PathBuf,
+    modified: ModificationTime,
+    hash: ContentHash,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotModule {
+    module: ModuleId,
+
+    /// The lowered module, see [`stellar_hir::binary`].
+    #[serde(with = "serde_bytes")]
+    hir: Vec<u8>,
+}
+
+/// The result of checking a package: the database (either borrowed from the
+/// result or owned after loading), lowered modules and diagnostics.
+#[derive(Serialize, Deserialize)]
+struct Snapshot<D> {
+    compiler_version: String,
+    package_name: IdentifierId,
+    dependencies: ContentHash,
+    files: Vec<SourceFile>,
+    package: PackageId,
+    database: D,
+    modules: Vec<SnapshotModule>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<D> Snapshot<D> {
+    /// Returns `true` if the snapshot was stored by this version of the
+    /// compiler for the same sources and dependencies.
+    fn is_fresh(&self, fingerprint: &Fingerprint) -> bool {
+        self.compiler_version == env!("CARGO_PKG_VERSION")
+            && self.package_name == fingerprint.package_name
+            && self.dependencies == fingerprint.dependencies
+            && self.files.len() == fingerprint.files.len()
+            && self
+                .files
+                .iter()
+                .zip(&fingerprint.files)
+                .all(|(stored, (path, modified))| {
+                    // files, that were only touched, are compared by contents
+                    stored.path == *path
+                        && (stored.modified == *modified
+                            || fs::read(path)
+                                .is_ok_and(|source| ContentHash::of(&source) == stored.hash))
+                })
+    }
+}
+
+impl Snapshot<&Database> {
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(SNAPSHOT_FORMAT_VERSION.to_le_bytes());
+
+        rmp_serde::encode::write_named(&mut bytes, self).unwrap();
+
+        bytes
+    }
+}
+
+impl Snapshot<Database> {
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let rest = bytes.strip_prefix(MAGIC)?;
+        let (version, rest) = rest.split_first_chunk::<4>()?;
+
+        if u32::from_le_bytes(*version) != SNAPSHOT_FORMAT_VERSION {
+            return None;
+        }
+
+        rmp_serde::from_slice(rest).ok()
+    }
+}
+
+/// Loads the snapshot of a package into the state, if sources of the package
+/// and headers of its dependencies didn't change since it was stored.
+///
+/// Returns the ID of the package and its lowered modules.
+pub(crate) fn restore(
+    cache: &PackageCache,
+    fingerprint: &Fingerprint,
+    state: &mut State,
+) -> Option<(PackageId, FxHashMap<ModuleId, stellar_hir::Module>)> {
+    let bytes = fs::read(cache.directory().join(SNAPSHOT_FILENAME)).ok()?;
+    let snapshot = Snapshot::decode(&bytes).filter(|snapshot| snapshot.is_fresh(fingerprint))?;
+
+    let modules = snapshot
+        .modules
+        .into_iter()
+        .map(|module| {
+            Some((
+                module.module,
+                stellar_hir::Module::decode(&module.hir).ok()?,
+            ))
+        })
+        .collect::<Option<FxHashMap<_, _>>>()?;
+
+    #[cfg(feature = "debug")]
+    trace!("restore_snapshot(package = '{}')", snapshot.package_name);
+
+    *state.db_mut() = snapshot.database;
+    state.diagnostics_mut().merge(Diagnostics {
+        files_involved: snapshot
+            .diagnostics
+            .iter()
+            .flat_map(Diagnostic::files_involved)
+            .collect(),
+        diagnostics: snapshot.diagnostics,
+    });
+
+    Some((snapshot.package, modules))
+}
+
+/// Stores the result of checking a package as its snapshot.
+pub(crate) fn store(cache: &PackageCache, fingerprint: Fingerprint, result: &CheckResult) {
+    // results of a cancelled build are incomplete
+    if result.is_stale() {
+        return;
+    }
+
+    // files changed during the build may not match the result
+    let Some(files) = fingerprint
+        .files
+        .into_iter()
+        .map(|(path, modified)| {
+            if modification_time_of(&path)? != modified {
+                return None;
+            }
+
+            Some(SourceFile {
+                hash: ContentHash::of(&fs::read(&path).ok()?),
+                path,
+                modified,
+            })
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return;
+    };
+
+    let snapshot = Snapshot {
+        compiler_version: env!("CARGO_PKG_VERSION").to_owned(),
+        package_name: fingerprint.package_name,
+        dependencies: fingerprint.dependencies,
+        files,
+        package: result.package(),
+        database: result.state().db(),
+        modules: result
+            .modules()
+            .iter()
+            .map(|(module, hir)| SnapshotModule {
+                module: *module,
+                hir: hir.encode(),
+            })
+            .collect(),
+        diagnostics: result.diagnostics().diagnostics.clone(),
+    };
+
+    // the cache is an optimization, failing to fill it must not fail the
+    // build
+    #[allow(unused_variables)]
+    if let Err(error) = cache.write(SNAPSHOT_FILENAME, &snapshot.encode()) {
+        #[cfg(feature = "debug")]
+        trace!("failed to store snapshot: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use filetime::FileTime;
+    use stellar_interner::IdentifierId;
+
+    use super::SNAPSHOT_FILENAME;
+    use crate::{check, check_with_dependencies, test_utils::cached_workspace, Workspace};
+
+    /// Rewrites a source file keeping its last modification time.
+    fn rewrite_unnoticed(workspace: &Workspace, path: &str, source: &str) {
+        let path = workspace.source_directory().join(path);
+        let modified = FileTime::from_last_modification_time(&fs::metadata(&path).unwrap());
+
+        fs::write(&path, source).unwrap();
+        filetime::set_file_mtime(&path, modified).unwrap();
+    }
+
+    /// Rewrites a source file, making sure its last modification time changes.
+    fn rewrite(workspace: &Workspace, path: &str, source: &str) {
+        let path = workspace.source_directory().join(path);
+        let modified = FileTime::from_last_modification_time(&fs::metadata(&path).unwrap());
+
+        fs::write(&path, source).unwrap();
+        filetime::set_file_mtime(
+            &path,
+            FileTime::from_unix_time(modified.unix_seconds() + 1, modified.nanoseconds()),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn unchanged_packages_are_not_checked_again() {
+        let workspace = cached_workspace(
+            "unchanged",
+            &[
+                ("package.sr", "module a;\npub fun f() {}"),
+                ("a.sr", "pub fun g() { break; }"),
+            ],
+        );
+
+        let first = check(&workspace).unwrap();
+        assert!(workspace
+            .cache()
+            .unwrap()
+            .directory()
+            .join(SNAPSHOT_FILENAME)
+            .exists());
+
+        // the snapshot is used, because the file looks unchanged
+        rewrite_unnoticed(&workspace, "a.sr", "pub fun g() {}");
+
+        let second = check(&workspace).unwrap();
+        let db = second.state().db();
+        let root = second.package().root_module(db);
+
+        assert_eq!(second.modules(), first.modules());
+        assert_eq!(
+            second.diagnostics().diagnostics,
+            first.diagnostics().diagnostics
+        );
+        assert!(root
+            .module_item_symbols(db)
+            .contains_key(&IdentifierId::from("f")));
+        assert!(root.submodule(db, IdentifierId::from("a")).is_some());
+    }
+
+    #[test]
+    fn changed_files_invalidate_snapshot() {
+        let workspace = cached_workspace("changed", &[("package.sr", "pub fun f() { break; }")]);
+
+        assert!(!check(&workspace).unwrap().diagnostics().is_ok());
+
+        // only touched
+        rewrite(&workspace, "package.sr", "pub fun f() { break; }");
+        assert!(!check(&workspace).unwrap().diagnostics().is_ok());
+
+        rewrite(&workspace, "package.sr", "pub fun f() {}");
+        assert!(check(&workspace).unwrap().diagnostics().is_ok());
+
+        fs::write(workspace.source_directory().join("b.sr"), "pub fun g() {}").unwrap();
+        assert_eq!(check(&workspace).unwrap().modules().len(), 2);
+    }
+
+    #[test]
+    fn changed_dependencies_invalidate_snapshot() {
+        let dependency = |source: &str| {
+            let dependency = cached_workspace("dependency", &[("package.sr", source)]);
+
+            check(&dependency).unwrap().header()
+        };
+        let workspace = cached_workspace("dependent", &[("package.sr", "import dependency.f;")]);
+
+        let header = dependency("pub fun f() {}");
+        assert!(check_with_dependencies(&workspace, &[header])
+            .unwrap()
+            .diagnostics()
+            .is_ok());
+
+        let header = dependency("pub fun g() {}");
+        assert!(!check_with_dependencies(&workspace, &[header])
+            .unwrap()
+            .diagnostics()
+            .is_ok());
+    }
+}
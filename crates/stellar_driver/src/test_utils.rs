@@ -0,0 +1,49 @@
+//! Fixtures shared by tests of the crate.
+
+use std::{
+    fs,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use stellar_interner::IdentifierId;
+
+#[cfg(feature = "binary")]
+use crate::cache::PackageCache;
+use crate::Workspace;
+
+/// Creates a package with given source files in a new temporary directory.
+///
+/// The package is named after `name` up to the first underscore, e.g. `dep`
+/// for `dep_a`, so that tests can create several packages with the same name.
+pub(crate) fn workspace(name: &str, files: &[(&str, &str)]) -> Workspace {
+    // tests run in parallel and may use the same names
+    static NEXT_DIRECTORY: AtomicUsize = AtomicUsize::new(0);
+
+    let root = std::env::temp_dir()
+        .join("stellar_driver_tests")
+        .join(format!(
+            "{}_{}_{name}",
+            std::process::id(),
+            NEXT_DIRECTORY.fetch_add(1, Ordering::Relaxed)
+        ));
+    // leftovers of a previous run
+    let _removed = fs::remove_dir_all(&root);
+
+    for (path, source) in files {
+        let path = root.join("src").join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+
+    Workspace::new(root, IdentifierId::from(name.split('_').next().unwrap()))
+}
+
+/// Creates a package like [`workspace`] does, which uses a cache in the same
+/// temporary directory.
+#[cfg(feature = "binary")]
+pub(crate) fn cached_workspace(name: &str, files: &[(&str, &str)]) -> Workspace {
+    let workspace = workspace(name, files);
+    let cache = PackageCache::new(workspace.root().join("target").join("cache"));
+
+    workspace.with_cache(cache)
+}
@@ -0,0 +1,236 @@
+//! Watch mode: re-analyzes a package every time its source files change.
+//!
+//! See [`watch()`] for more details.
+
+use std::{io, mem, ops::ControlFlow, path::PathBuf, thread, time::Duration};
+
+use filetime::FileTime;
+use stellar_database::{query::Queries, State};
+use stellar_diagnostics::diagnostic::Diagnostic;
+use stellar_fx_hash::FxHashMap;
+
+use crate::{check_using, plugin::Plugins, CheckResult, Workspace};
+
+/// Options of the watch loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchOptions {
+    /// How often source files are checked for changes.
+    poll_interval: Duration,
+}
+
+impl Default for WatchOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+impl WatchOptions {
+    /// Creates default watch options.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how often source files are checked for changes.
+    #[inline]
+    #[must_use]
+    pub const fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Returns how often source files are checked for changes.
+    #[inline]
+    #[must_use]
+    pub const fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
+
+/// Difference between diagnostics of two consecutive analysis runs.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DiagnosticsDelta {
+    /// Diagnostics that weren't present in the previous run.
+    pub added: Vec<Diagnostic>,
+
+    /// Diagnostics from the previous run that are no longer emitted.
+    pub removed: Vec<Diagnostic>,
+}
+
+impl DiagnosticsDelta {
+    /// Computes the difference between diagnostics of two analysis runs.
+    #[must_use]
+    pub fn between(previous: &[Diagnostic], current: &[Diagnostic]) -> Self {
+        let mut removed = previous.to_vec();
+        let mut added = Vec::new();
+
+        for diagnostic in current {
+            if let Some(idx) = removed.iter().position(|d| d == diagnostic) {
+                removed.remove(idx);
+            } else {
+                added.push(diagnostic.clone());
+            }
+        }
+
+        Self { added, removed }
+    }
+
+    /// Returns `true` if diagnostics didn't change.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Information passed to the [`watch()`] callback after every analysis run.
+#[derive(Debug)]
+pub struct WatchEvent<'r> {
+    /// Source files that were created, modified or removed since the previous run.
+    ///
+    /// For the first run, all source files of the package.
+    pub changed_files: Vec<PathBuf>,
+
+    /// The result of the analysis.
+    pub result: &'r CheckResult,
+
+    /// Diagnostics that changed since the previous run.
+    pub delta: DiagnosticsDelta,
+}
+
+/// Last modification times of package source files.
+type Snapshot = FxHashMap<PathBuf, FileTime>;
+
+fn snapshot(workspace: &Workspace) -> Result<Snapshot, io::Error> {
+    Ok(workspace
+        .source_files()?
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = path.metadata().ok()?;
+
+            Some((path, FileTime::from_last_modification_time(&metadata)))
+        })
+        .collect())
+}
+
+/// Returns files that were created, modified or removed between two snapshots.
+fn changed_files(previous: &Snapshot, current: &Snapshot) -> Vec<PathBuf> {
+    let mut changed_files = current
+        .iter()
+        .filter(|(path, time)| previous.get(*path) != Some(time))
+        .map(|(path, _)| path.clone())
+        .chain(
+            previous
+                .keys()
+                .filter(|path| !current.contains_key(*path))
+                .cloned(),
+        )
+        .collect::<Vec<_>>();
+
+    changed_files.sort();
+    changed_files
+}
+
+/// Analyzes the package and then re-analyzes it every time its source files
+/// change, calling `callback` after every run.
+///
+/// The loop runs until `callback` returns [`ControlFlow::Break`].
+///
+/// Every change causes the whole package to be re-analyzed, but memoized
+/// queries are kept between runs, so files, which source didn't change, are not
+/// parsed again. [`WatchEvent::changed_files`] tells which modules were
+/// actually affected. If the workspace has a cache (`Workspace::with_cache`
+/// with the `binary` feature), only changed files are parsed and lowered again.
+///
+/// # Errors
+/// Returns an error if package source files cannot be read.
+pub fn watch<F>(
+    workspace: &Workspace,
+    options: WatchOptions,
+    mut callback: F,
+) -> Result<(), io::Error>
+where
+    F: FnMut(WatchEvent<'_>) -> ControlFlow<()>,
+{
+    let mut previous_snapshot = snapshot(workspace)?;
+    let mut previous_diagnostics = Vec::new();
+    let mut changed_files = previous_snapshot.keys().cloned().collect::<Vec<_>>();
+    changed_files.sort();
+    let mut queries = Queries::new();
+
+    loop {
+        let mut result = check_using(
+            State::new().with_queries(mem::take(&mut queries)),
+            workspace,
+            &[],
+            &mut Plugins::new(),
+            None,
+        )?;
+        let diagnostics = &result.diagnostics().diagnostics;
+        let delta = DiagnosticsDelta::between(&previous_diagnostics, diagnostics);
+        previous_diagnostics.clone_from(diagnostics);
+
+        if callback(WatchEvent {
+            changed_files,
+            result: &result,
+            delta,
+        })
+        .is_break()
+        {
+            return Ok(());
+        }
+
+        queries = result
+            .state
+            .queries_mut()
+            .map(mem::take)
+            .unwrap_or_default();
+
+        changed_files = loop {
+            thread::sleep(options.poll_interval);
+
+            let snapshot = snapshot(workspace)?;
+            let changed_files = self::changed_files(&previous_snapshot, &snapshot);
+            previous_snapshot = snapshot;
+
+            if !changed_files.is_empty() {
+                break changed_files;
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use stellar_diagnostics::diagnostic::Diagnostic;
+
+    use super::DiagnosticsDelta;
+
+    #[test]
+    fn diagnostics_delta() {
+        let a = Diagnostic::error().with_message("a");
+        let b = Diagnostic::error().with_message("b");
+        let c = Diagnostic::warning().with_message("c");
+
+        let delta = DiagnosticsDelta::between(&[a.clone(), b.clone()], &[b, c.clone()]);
+
+        assert_eq!(delta.added, vec![c]);
+        assert_eq!(delta.removed, vec![a]);
+    }
+
+    #[test]
+    fn duplicate_diagnostics_delta() {
+        let a = Diagnostic::error().with_message("a");
+
+        let delta = DiagnosticsDelta::between(slice::from_ref(&a), &[a.clone(), a.clone()]);
+
+        assert_eq!(delta.added, vec![a]);
+        assert!(delta.removed.is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+//! Checks, that modules of the corpus survive the binary encoding, see
+//! [`stellar_ast::binary`] and [`stellar_hir::binary`].
+
+#![cfg(feature = "binary")]
+
+use std::{fs, path::PathBuf};
+
+use stellar_ast::Module;
+use stellar_driver::compile::{compile_str, CompilationStage, CompileOptions};
+
+const CORPUS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus");
+
+/// Returns paths and contents of source files of the corpus.
+fn corpus() -> Vec<(PathBuf, String)> {
+    let sources: Vec<_> = fs::read_dir(CORPUS_PATH)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "sr"))
+        .map(|path| {
+            let source = fs::read_to_string(&path).unwrap();
+
+            (path, source)
+        })
+        .collect();
+
+    assert!(!sources.is_empty());
+
+    sources
+}
+
+#[test]
+fn corpus_round_trip() {
+    for (path, source) in corpus() {
+        let output = compile_str(
+            "test",
+            &source,
+            CompileOptions::new().stop_after(CompilationStage::Parse),
+        );
+        let ast = output.ast();
+
+        let bytes = ast.encode();
+
+        assert_eq!(&Module::decode(&bytes).unwrap(), ast, "{}", path.display());
+        assert!(bytes.len() < serde_json::to_vec(ast).unwrap().len());
+    }
+}
+
+#[test]
+fn corpus_hir_round_trip() {
+    for (path, source) in corpus() {
+        let output = compile_str(
+            "test",
+            &source,
+            CompileOptions::new().stop_after(CompilationStage::Lower),
+        );
+        let hir = output.hir().unwrap();
+
+        let bytes = hir.encode();
+        let decoded = stellar_hir::Module::decode(&bytes).unwrap();
+
+        assert_eq!(&decoded, hir, "{}", path.display());
+        // the source map is not a part of the JSON representation
+        assert!(bytes.len() < serde_json::to_vec(&(hir, &hir.source_map)).unwrap().len());
+    }
+}
@@ -0,0 +1,13 @@
+//! Checks trees of the source files in the `corpus` directory against stored
+//! golden trees, see [`stellar_driver::corpus`].
+
+#![cfg(feature = "serde")]
+
+use stellar_driver::corpus::verify_corpus;
+
+#[test]
+fn corpus() {
+    let report = verify_corpus(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus")).unwrap();
+
+    assert!(report.is_ok(), "{report}");
+}
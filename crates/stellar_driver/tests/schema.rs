@@ -0,0 +1,66 @@
+//! Checks the stored JSON Schema of the serialized AST, see
+//! [`stellar_ast::json_schema()`].
+
+#![cfg(feature = "schemars")]
+
+use std::{env, fs};
+
+use jsonschema::JSONSchema;
+use serde_json::Value;
+use stellar_driver::corpus::BLESS_ENV_VARIABLE;
+
+const SCHEMA_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../stellar_ast/ast.schema.json"
+);
+const CORPUS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus");
+
+#[test]
+fn schema_is_up_to_date() {
+    let mut actual = serde_json::to_string_pretty(&stellar_ast::json_schema()).unwrap();
+    actual.push('\n');
+
+    if env::var_os(BLESS_ENV_VARIABLE).is_some() {
+        fs::write(SCHEMA_PATH, actual).unwrap();
+        return;
+    }
+
+    assert!(
+        fs::read_to_string(SCHEMA_PATH).ok().as_ref() == Some(&actual),
+        "`ast.schema.json` is outdated, rerun with `{BLESS_ENV_VARIABLE}=1` to update it"
+    );
+}
+
+#[test]
+fn corpus_matches_schema() {
+    let schema = serde_json::to_value(stellar_ast::json_schema()).unwrap();
+    let schema = JSONSchema::compile(&schema).unwrap();
+
+    let mut checked = 0;
+
+    for entry in fs::read_dir(CORPUS_PATH).unwrap() {
+        let path = entry.unwrap().path();
+
+        if !path.to_string_lossy().ends_with(".ast.json") {
+            continue;
+        }
+
+        let ast: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+        if let Err(errors) = schema.validate(&ast) {
+            let errors = errors
+                .map(|error| format!("{}: {error}", error.instance_path))
+                .collect::<Vec<_>>();
+
+            panic!(
+                "{} doesn't match the schema:\n{}",
+                path.display(),
+                errors.join("\n")
+            );
+        }
+
+        checked += 1;
+    }
+
+    assert!(checked > 0);
+}
@@ -65,3 +65,4 @@ pub mod in_memory_file;
 pub mod in_memory_file_storage;
 pub mod location;
 pub mod path_resolver;
+pub mod text_edit;
@@ -6,6 +6,8 @@ use std::{
 };
 
 use derive_more::Display;
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use stellar_interner::PathId;
@@ -13,6 +15,7 @@ use stellar_interner::PathId;
 /// Represents location in the source text.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct Location {
     /// Path of the source file.
     pub filepath: PathId,
@@ -35,6 +38,7 @@ impl Index<Location> for str {
 /// Offset of a byte in a source text.
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct ByteOffset(pub usize);
 
 impl From<usize> for ByteOffset {
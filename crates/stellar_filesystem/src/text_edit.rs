@@ -0,0 +1,572 @@
+//! Defines machine-applicable source code edits: [`TextEdit`], [`FileEdit`] and
+//! [`WorkspaceEdit`].
+//!
+//! Quick-fixes, renaming, the formatter and migration tools all describe their
+//! changes using these types, so that edits coming from different places can be
+//! merged together and applied in one go:
+//!
+//! ```
+//! use stellar_filesystem::{
+//!     location::{ByteOffset, Location},
+//!     text_edit::{FileEdit, TextEdit},
+//! };
+//! use stellar_interner::PathId;
+//!
+//! let filepath = PathId::from("main.sr");
+//! let source = "fun main() { foo(); }";
+//!
+//! let mut edit = FileEdit::new(filepath);
+//! edit.add(TextEdit::replace(
+//!     Location {
+//!         filepath,
+//!         start: ByteOffset(13),
+//!         end: ByteOffset(16),
+//!     },
+//!     "bar",
+//! ));
+//! edit.add(TextEdit::insert(filepath, ByteOffset(0), "pub "));
+//!
+//! assert_eq!(edit.apply(source).unwrap(), "pub fun main() { bar(); }");
+//! ```
+
+use std::{cmp::Ordering, fmt::Display};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use stellar_fx_hash::FxHashMap;
+use stellar_interner::PathId;
+
+use crate::{
+    in_memory_file::InMemoryFile,
+    in_memory_file_storage::InMemoryFileStorage,
+    location::{ByteOffset, Location},
+};
+
+/// Replacement of a piece of source text.
+///
+/// Insertions are represented as replacements of empty locations, deletions
+/// are represented as replacements with an empty string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TextEdit {
+    /// Location of the text to be replaced.
+    pub location: Location,
+
+    /// The text to replace the location with.
+    pub new_text: String,
+}
+
+impl TextEdit {
+    /// Creates an edit replacing text at the given location.
+    #[inline]
+    #[must_use]
+    pub fn replace(location: Location, new_text: impl Into<String>) -> Self {
+        Self {
+            location,
+            new_text: new_text.into(),
+        }
+    }
+
+    /// Creates an edit inserting text at the given offset.
+    #[inline]
+    #[must_use]
+    pub fn insert(filepath: PathId, offset: ByteOffset, new_text: impl Into<String>) -> Self {
+        Self::replace(
+            Location {
+                filepath,
+                start: offset,
+                end: offset,
+            },
+            new_text,
+        )
+    }
+
+    /// Creates an edit removing text at the given location.
+    #[inline]
+    #[must_use]
+    pub fn delete(location: Location) -> Self {
+        Self::replace(location, String::new())
+    }
+
+    /// Returns `true` if the edit only inserts text.
+    #[inline]
+    #[must_use]
+    pub fn is_insertion(&self) -> bool {
+        self.location.start == self.location.end
+    }
+
+    /// Returns `true` if the edit only removes text.
+    #[inline]
+    #[must_use]
+    pub const fn is_deletion(&self) -> bool {
+        self.new_text.is_empty()
+    }
+
+    /// Returns `true` if both edits modify the same piece of text, so that the
+    /// result of applying them depends on the order.
+    ///
+    /// Edits touching at their boundaries don't overlap. Several insertions at
+    /// the same offset don't overlap either, they are applied in order of
+    /// addition.
+    #[inline]
+    #[must_use]
+    #[allow(clippy::suspicious_operation_groupings)]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        let (a, b) = (self.location, other.location);
+
+        // an insertion strictly inside of a replaced text overlaps with it too
+        a.filepath == b.filepath && a.start < b.end && b.start < a.end
+    }
+
+    /// Compares the edits by their positions in the source text.
+    fn cmp_by_location(&self, other: &Self) -> Ordering {
+        self.location
+            .start
+            .cmp(&other.location.start)
+            .then(self.location.end.cmp(&other.location.end))
+    }
+}
+
+/// An error that happened when applying or combining edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEditError {
+    /// Two edits modify the same piece of text.
+    Overlapping {
+        /// Location of the first edit.
+        first: Location,
+
+        /// Location of the second edit.
+        second: Location,
+    },
+
+    /// The edit refers to text outside of the source.
+    OutOfBounds {
+        /// Location of the edit.
+        location: Location,
+
+        /// Length of the source (in bytes).
+        source_len: usize,
+    },
+
+    /// Start or end of the edit is not a boundary of a UTF-8 code point.
+    InvalidCharBoundary {
+        /// Location of the edit.
+        location: Location,
+    },
+
+    /// File edits belong to different files.
+    FilepathMismatch {
+        /// Path of the file being edited.
+        expected: PathId,
+
+        /// Path of the file the edits belong to.
+        found: PathId,
+    },
+
+    /// The edited file is not in the storage.
+    FileMissing {
+        /// Path of the file.
+        filepath: PathId,
+    },
+}
+
+impl Display for TextEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overlapping { first, second } => {
+                write!(f, "edits at {first} and {second} overlap")
+            }
+            Self::OutOfBounds {
+                location,
+                source_len,
+            } => write!(
+                f,
+                "edit at {location} is out of bounds, source length is {source_len}"
+            ),
+            Self::InvalidCharBoundary { location } => {
+                write!(f, "edit at {location} is not at a code point boundary")
+            }
+            Self::FilepathMismatch { expected, found } => {
+                write!(f, "edits of {found} cannot be applied to {expected}")
+            }
+            Self::FileMissing { filepath } => write!(f, "file {filepath} is missing"),
+        }
+    }
+}
+
+impl std::error::Error for TextEditError {}
+
+/// Edits of a single file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FileEdit {
+    filepath: PathId,
+    edits: Vec<TextEdit>,
+}
+
+impl FileEdit {
+    /// Creates an empty edit of the file with a given path.
+    #[inline]
+    #[must_use]
+    pub const fn new(filepath: PathId) -> Self {
+        Self {
+            filepath,
+            edits: Vec::new(),
+        }
+    }
+
+    /// Returns the path of the edited file.
+    #[inline]
+    #[must_use]
+    pub const fn filepath(&self) -> PathId {
+        self.filepath
+    }
+
+    /// Returns the text edits in order of addition, or in order of positions
+    /// if the edit was sorted.
+    #[inline]
+    #[must_use]
+    pub fn edits(&self) -> &[TextEdit] {
+        &self.edits
+    }
+
+    /// Returns `true` if the file edit doesn't change anything.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Adds a text edit.
+    ///
+    /// # Panics
+    /// If the text edit belongs to another file.
+    #[inline]
+    pub fn add(&mut self, edit: TextEdit) {
+        assert_eq!(
+            edit.location.filepath, self.filepath,
+            "text edit belongs to another file"
+        );
+
+        self.edits.push(edit);
+    }
+
+    /// Adds all text edits of another file edit, skipping the ones that are
+    /// already present. Edits produced by different tools often coincide, for
+    /// example when two quick-fixes add the same import.
+    ///
+    /// # Errors
+    /// If the file edits belong to different files.
+    pub fn merge(&mut self, other: Self) -> Result<(), TextEditError> {
+        if other.filepath != self.filepath {
+            return Err(TextEditError::FilepathMismatch {
+                expected: self.filepath,
+                found: other.filepath,
+            });
+        }
+
+        for edit in other.edits {
+            if !self.edits.contains(&edit) {
+                self.edits.push(edit);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sorts text edits by their positions. The sort is stable, so insertions
+    /// at the same offset keep order of addition.
+    #[inline]
+    pub fn sort(&mut self) {
+        self.edits.sort_by(TextEdit::cmp_by_location);
+    }
+
+    /// Checks that no two text edits overlap.
+    ///
+    /// # Errors
+    /// Returns the first pair of overlapping edits, in order of positions.
+    pub fn check_overlaps(&self) -> Result<(), TextEditError> {
+        let mut edits = self.edits.iter().collect::<Vec<_>>();
+        edits.sort_by(|a, b| a.cmp_by_location(b));
+
+        for (idx, edit) in edits.iter().enumerate() {
+            // edits are sorted, so only the following edits starting before
+            // the end of the current one can overlap with it
+            if let Some(next) = edits[idx + 1..]
+                .iter()
+                .take_while(|next| next.location.start < edit.location.end)
+                .find(|next| edit.overlaps(next))
+            {
+                return Err(TextEditError::Overlapping {
+                    first: edit.location,
+                    second: next.location,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the edits to the source of the file and returns the new source.
+    ///
+    /// # Errors
+    /// If some edits overlap, or don't fit into the source.
+    pub fn apply(&self, source: &str) -> Result<String, TextEditError> {
+        self.check_overlaps()?;
+
+        let mut edits = self.edits.iter().collect::<Vec<_>>();
+        edits.sort_by(|a, b| a.cmp_by_location(b));
+
+        for edit in &edits {
+            let Location { start, end, .. } = edit.location;
+
+            if start > end || end.0 > source.len() {
+                return Err(TextEditError::OutOfBounds {
+                    location: edit.location,
+                    source_len: source.len(),
+                });
+            }
+
+            if !source.is_char_boundary(start.0) || !source.is_char_boundary(end.0) {
+                return Err(TextEditError::InvalidCharBoundary {
+                    location: edit.location,
+                });
+            }
+        }
+
+        let mut result = String::with_capacity(source.len());
+        let mut offset = 0;
+
+        for edit in edits {
+            result.push_str(&source[offset..edit.location.start.0]);
+            result.push_str(&edit.new_text);
+
+            offset = edit.location.end.0;
+        }
+
+        result.push_str(&source[offset..]);
+
+        Ok(result)
+    }
+}
+
+/// Edits spanning several files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WorkspaceEdit {
+    files: FxHashMap<PathId, FileEdit>,
+}
+
+impl WorkspaceEdit {
+    /// Creates an empty workspace edit.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns edits of a file with a given path.
+    #[inline]
+    #[must_use]
+    pub fn file_edit(&self, filepath: PathId) -> Option<&FileEdit> {
+        self.files.get(&filepath)
+    }
+
+    /// Returns edits of all files.
+    #[inline]
+    pub fn file_edits(&self) -> impl Iterator<Item = &FileEdit> {
+        self.files.values()
+    }
+
+    /// Returns `true` if the workspace edit doesn't change anything.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.values().all(FileEdit::is_empty)
+    }
+
+    /// Adds a text edit to the edit of the corresponding file.
+    #[inline]
+    pub fn add(&mut self, edit: TextEdit) {
+        let filepath = edit.location.filepath;
+
+        self.files
+            .entry(filepath)
+            .or_insert_with(|| FileEdit::new(filepath))
+            .add(edit);
+    }
+
+    /// Adds all edits of a file, skipping the ones that are already present.
+    pub fn add_file_edit(&mut self, edit: FileEdit) {
+        let filepath = edit.filepath;
+
+        if let Some(existing) = self.files.get_mut(&filepath) {
+            // file paths are the same
+            let _ = existing.merge(edit);
+        } else {
+            self.files.insert(filepath, edit);
+        }
+    }
+
+    /// Adds all edits of another workspace edit, skipping the ones that are
+    /// already present.
+    #[inline]
+    pub fn merge(&mut self, other: Self) {
+        for edit in other.files.into_values() {
+            self.add_file_edit(edit);
+        }
+    }
+
+    /// Checks that no two text edits of the same file overlap.
+    ///
+    /// # Errors
+    /// Returns the first found pair of overlapping edits.
+    pub fn check_overlaps(&self) -> Result<(), TextEditError> {
+        self.files.values().try_for_each(FileEdit::check_overlaps)
+    }
+
+    /// Applies the edits to files in the storage.
+    ///
+    /// Either all files are modified, or none of them if any of the edits
+    /// cannot be applied.
+    ///
+    /// # Errors
+    /// If some file is missing in the storage, or some of its edits overlap or
+    /// don't fit into the source.
+    pub fn apply(&self, storage: &mut InMemoryFileStorage) -> Result<(), TextEditError> {
+        let sources = self
+            .files
+            .values()
+            .map(|edit| {
+                let file =
+                    storage
+                        .resolve_file(edit.filepath)
+                        .ok_or(TextEditError::FileMissing {
+                            filepath: edit.filepath,
+                        })?;
+
+                Ok((edit.filepath, edit.apply(&file.source)?))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (filepath, source) in sources {
+            storage.add_file(filepath, InMemoryFile::new_from_source(filepath, source));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_interner::PathId;
+
+    use super::{FileEdit, TextEdit, TextEditError, WorkspaceEdit};
+    use crate::{
+        in_memory_file::InMemoryFile,
+        in_memory_file_storage::InMemoryFileStorage,
+        location::{ByteOffset, Location},
+    };
+
+    fn location(filepath: PathId, start: usize, end: usize) -> Location {
+        Location {
+            filepath,
+            start: ByteOffset(start),
+            end: ByteOffset(end),
+        }
+    }
+
+    #[test]
+    fn apply_in_any_order() {
+        let filepath = PathId::from("test.sr");
+        let mut edit = FileEdit::new(filepath);
+
+        edit.add(TextEdit::delete(location(filepath, 10, 14)));
+        edit.add(TextEdit::replace(location(filepath, 4, 7), "b"));
+        edit.add(TextEdit::insert(filepath, ByteOffset(4), "x"));
+        edit.add(TextEdit::insert(filepath, ByteOffset(4), "y"));
+
+        assert_eq!(edit.apply("let aaa = 1234;").unwrap(), "let xyb = ;");
+    }
+
+    #[test]
+    fn overlapping() {
+        let filepath = PathId::from("test.sr");
+        let mut edit = FileEdit::new(filepath);
+
+        edit.add(TextEdit::replace(location(filepath, 0, 5), "a"));
+        edit.add(TextEdit::insert(filepath, ByteOffset(5), "b"));
+        assert!(edit.check_overlaps().is_ok());
+
+        edit.add(TextEdit::insert(filepath, ByteOffset(3), "c"));
+        assert_eq!(
+            edit.apply("0123456789"),
+            Err(TextEditError::Overlapping {
+                first: location(filepath, 0, 5),
+                second: location(filepath, 3, 3),
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_locations() {
+        let filepath = PathId::from("test.sr");
+
+        let mut edit = FileEdit::new(filepath);
+        edit.add(TextEdit::delete(location(filepath, 2, 10)));
+        assert!(matches!(
+            edit.apply("abc"),
+            Err(TextEditError::OutOfBounds { source_len: 3, .. })
+        ));
+
+        let mut edit = FileEdit::new(filepath);
+        edit.add(TextEdit::delete(location(filepath, 0, 1)));
+        assert!(matches!(
+            edit.apply("ё"),
+            Err(TextEditError::InvalidCharBoundary { .. })
+        ));
+    }
+
+    #[test]
+    fn merge_and_apply_workspace_edit() {
+        let a = PathId::from("a.sr");
+        let b = PathId::from("b.sr");
+
+        let mut storage = InMemoryFileStorage::new();
+        storage.add_file(a, InMemoryFile::new_from_source(a, "foo()".to_owned()));
+        storage.add_file(b, InMemoryFile::new_from_source(b, "foo".to_owned()));
+
+        let mut first = WorkspaceEdit::new();
+        first.add(TextEdit::replace(location(a, 0, 3), "bar"));
+
+        let mut second = WorkspaceEdit::new();
+        second.add(TextEdit::replace(location(a, 0, 3), "bar"));
+        second.add(TextEdit::replace(location(b, 0, 3), "bar"));
+
+        first.merge(second);
+        assert_eq!(first.file_edit(a).unwrap().edits().len(), 1);
+
+        first.apply(&mut storage).unwrap();
+        assert_eq!(storage.resolve_file(a).unwrap().source, "bar()");
+        assert_eq!(storage.resolve_file(b).unwrap().source, "bar");
+    }
+
+    #[test]
+    fn workspace_edit_is_atomic() {
+        let a = PathId::from("a.sr");
+        let b = PathId::from("b.sr");
+
+        let mut storage = InMemoryFileStorage::new();
+        storage.add_file(a, InMemoryFile::new_from_source(a, "foo".to_owned()));
+
+        let mut edit = WorkspaceEdit::new();
+        edit.add(TextEdit::replace(location(a, 0, 3), "bar"));
+        edit.add(TextEdit::replace(location(b, 0, 3), "bar"));
+
+        assert_eq!(
+            edit.apply(&mut storage),
+            Err(TextEditError::FileMissing { filepath: b })
+        );
+        assert_eq!(storage.resolve_file(a).unwrap().source, "foo");
+    }
+}
@@ -0,0 +1,161 @@
+//! Compact binary encoding of modules, see [`Module::encode`].
+//!
+//! The encoding is meant for caching lowered modules on disk between compiler
+//! runs, so that unchanged files are neither parsed nor lowered again:
+//!
+//! ```
+//! use stellar_ast::NodeId;
+//! use stellar_filesystem::location::{ByteOffset, Location};
+//! use stellar_hir::{Module, Origin, SourceMap};
+//! use stellar_interner::PathId;
+//!
+//! let filepath = PathId::from("main.sr");
+//!
+//! let mut source_map = SourceMap::new();
+//! source_map.insert(Origin {
+//!     node_id: NodeId(0),
+//!     location: Location {
+//!         filepath,
+//!         start: ByteOffset(0),
+//!         end: ByteOffset(4),
+//!     },
+//! });
+//!
+//! let module = Module {
+//!     filepath,
+//!     items: vec![],
+//!     docstring: None,
+//!     source_map,
+//! };
+//!
+//! let bytes = module.encode();
+//!
+//! assert_eq!(Module::decode(&bytes).unwrap(), module);
+//! assert!(Module::decode(b"not a module").is_err());
+//! ```
+//!
+//! Like AST modules (see `stellar_ast::binary`), HIR modules are encoded as
+//! [`MessagePack`](https://msgpack.org) using their `serde` representation.
+//! Unlike it, the encoding includes the [`SourceMap`] of the module, which is
+//! not a part of the JSON representation, but is needed to report diagnostics
+//! on the cached module.
+//!
+//! Encoded modules start with a header containing [`FORMAT_VERSION`], so
+//! caches written by another version of the compiler are rejected with
+//! [`DecodeError::UnsupportedVersion`] instead of being misread.
+
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Module, SourceMap};
+
+/// Version of the encoding, which is bumped every time the serialized HIR
+/// changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Bytes every encoded module starts with.
+const MAGIC: &[u8; 4] = b"SHIR";
+
+/// Error returned when bytes can't be decoded into a module.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The bytes don't start with the header of an encoded module.
+    MissingHeader,
+
+    /// The module was encoded with another version of the encoding.
+    UnsupportedVersion(u32),
+
+    /// The bytes after the header are not a valid encoded module.
+    Malformed(rmp_serde::decode::Error),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingHeader => f.write_str("bytes are not an encoded module"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported module encoding version {version} (expected {FORMAT_VERSION})"
+            ),
+            Self::Malformed(error) => write!(f, "malformed encoded module: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Malformed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A module together with its source map, which is skipped by the `serde`
+/// representation of [`Module`].
+#[derive(Serialize)]
+struct Encoded<'m> {
+    module: &'m Module,
+    source_map: &'m SourceMap,
+}
+
+/// Owned version of [`Encoded`].
+#[derive(Deserialize)]
+struct Decoded {
+    module: Module,
+    source_map: SourceMap,
+}
+
+impl Module {
+    /// Encodes the module into bytes, see [module level docs](crate::binary)
+    /// for more details.
+    ///
+    /// # Panics
+    /// Panics if the module can't be serialized, which never happens for
+    /// modules produced by the lowering.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(FORMAT_VERSION.to_le_bytes());
+
+        rmp_serde::encode::write_named(
+            &mut bytes,
+            &Encoded {
+                module: self,
+                source_map: &self.source_map,
+            },
+        )
+        .unwrap();
+
+        bytes
+    }
+
+    /// Decodes a module encoded with [`Module::encode`].
+    ///
+    /// # Errors
+    /// Returns an error if the bytes are not an encoded module or were
+    /// encoded with another version of the encoding.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let Some(rest) = bytes.strip_prefix(MAGIC) else {
+            return Err(DecodeError::MissingHeader);
+        };
+
+        let Some((version, rest)) = rest.split_first_chunk::<4>() else {
+            return Err(DecodeError::MissingHeader);
+        };
+
+        let version = u32::from_le_bytes(*version);
+        if version != FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+
+        let Decoded {
+            mut module,
+            source_map,
+        } = rmp_serde::from_slice(rest).map_err(DecodeError::Malformed)?;
+        module.source_map = source_map;
+
+        Ok(module)
+    }
+}
@@ -121,6 +121,12 @@
 //!                             }
 //!                         },
 //! ```
+//!
+//! # Serialization
+//!
+//! If the `serde` feature is enabled, HIR can be serialized using the `serde`
+//! crate. The `binary` feature adds a compact binary encoding of modules for
+//! on-disk caches, see `Module::encode()`.
 
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/quantumatic/stellar/main/additional/icon/stellar.png",
@@ -181,11 +187,111 @@
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-pub use stellar_ast::{IdentifierAST, ImportPath, Literal, Path, Visibility};
+pub use stellar_ast::{
+    Attribute, BindingMode, CaptureKind, ConstValue, Docstring, IdentifierAST, ImportPath,
+    LambdaCapture, Literal, NodeId, Path, Visibility,
+};
 use stellar_ast::{ModuleItemKind, NegativeNumericLiteral};
 use stellar_filesystem::location::Location;
 use stellar_interner::{IdentifierId, PathId};
 
+#[cfg(feature = "binary")]
+pub mod binary;
+pub mod pretty;
+pub mod visit;
+
+/// ID of an expression or a statement, e.g. `HirId(3)`.
+///
+/// IDs are assigned during lowering in the order nodes are produced, so that
+/// the same AST is always lowered into the same IDs, and are unique within a
+/// module (but not across modules). Unlike AST node IDs, every node produced
+/// by desugaring gets its own ID, see [`SourceMap`].
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HirId(pub usize);
+
+/// ID of a node, that is not produced by the lowering, e.g. a node built by
+/// hand.
+pub const DUMMY_HIR_ID: HirId = HirId(usize::MAX);
+
+/// Syntax, that a HIR node was lowered from.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Origin {
+    /// ID of the AST node.
+    pub node_id: NodeId,
+
+    /// Location of the syntax in the source code. For nodes produced by
+    /// desugaring it points to the sugar, e.g. to the `loop` keyword for
+    /// `true` in `while true` lowered from `loop { ... }`.
+    pub location: Location,
+}
+
+/// A map from HIR nodes of a module back to the syntax they were lowered from,
+/// so that diagnostics computed on desugared code can point to the code,
+/// that user wrote.
+///
+/// ```
+/// use stellar_ast::NodeId;
+/// use stellar_filesystem::location::DUMMY_LOCATION;
+/// use stellar_hir::{Origin, SourceMap};
+///
+/// let mut source_map = SourceMap::new();
+/// let origin = Origin {
+///     node_id: NodeId(2),
+///     location: DUMMY_LOCATION,
+/// };
+/// let hir_id = source_map.insert(origin);
+///
+/// assert_eq!(source_map.origin(hir_id), Some(origin));
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct SourceMap {
+    origins: Vec<Origin>,
+}
+
+impl SourceMap {
+    /// Creates an empty source map.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            origins: Vec::new(),
+        }
+    }
+
+    /// Records the origin of a new HIR node and returns the node's ID.
+    #[inline]
+    pub fn insert(&mut self, origin: Origin) -> HirId {
+        self.origins.push(origin);
+
+        HirId(self.origins.len() - 1)
+    }
+
+    /// Returns the syntax, that a HIR node was lowered from.
+    #[inline]
+    #[must_use]
+    pub fn origin(&self, hir_id: HirId) -> Option<Origin> {
+        self.origins.get(hir_id.0).copied()
+    }
+
+    /// Returns the number of recorded HIR nodes.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.origins.len()
+    }
+
+    /// Returns `true` if no HIR nodes are recorded.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.origins.is_empty()
+    }
+}
+
 /// A type constructor, e.g. `Option[T]`.
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -212,6 +318,7 @@ pub enum Pattern {
     #[cfg_attr(feature = "serde", serde(rename = "identifier_pattern"))]
     Identifier {
         location: Location,
+        binding_mode: BindingMode,
         identifier: IdentifierAST,
 
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
@@ -257,12 +364,24 @@ pub enum Pattern {
         inner_patterns: Vec<Self>,
     },
 
-    /// An or pattern, e.g. `Some(..) | None`.
+    /// An or pattern, e.g. `Some(..) | None`. Always has at least two
+    /// alternatives.
     #[cfg_attr(feature = "serde", serde(rename = "or_pattern"))]
     Or {
         location: Location,
-        left: Box<Self>,
-        right: Box<Self>,
+        alternatives: Vec<Self>,
+    },
+
+    /// A range pattern, e.g. `1..=9`, `'a'..'z'`. Bounds are literal or
+    /// negative numeric literal patterns.
+    #[cfg_attr(feature = "serde", serde(rename = "range_pattern"))]
+    Range {
+        location: Location,
+        start: Box<Self>,
+        end: Box<Self>,
+
+        #[cfg_attr(feature = "serde", serde(rename = "range_kind"))]
+        kind: stellar_ast::RangeKind,
     },
 
     /// A rest pattern - `..`.
@@ -283,6 +402,7 @@ impl Pattern {
             )
             | Self::List { location, .. }
             | Self::Or { location, .. }
+            | Self::Range { location, .. }
             | Self::Rest { location, .. }
             | Self::Struct { location, .. }
             | Self::Tuple { location, .. }
@@ -353,6 +473,32 @@ pub enum Type {
         location: Location,
         bounds: Vec<TypeConstructor>,
     },
+
+    /// A const generic argument, e.g. `4` in `Array[uint8, 4]`.
+    #[cfg_attr(feature = "serde", serde(rename = "const_argument"))]
+    Const {
+        location: Location,
+        value: ConstValue,
+    },
+
+    /// A fixed-size array type, e.g. `[int32; 4]` and `[T; N]`.
+    #[cfg_attr(feature = "serde", serde(rename = "array_type"))]
+    Array {
+        location: Location,
+        element_type: Box<Self>,
+
+        /// The size of the array: a const generic argument, e.g. `4`, or a
+        /// const generic parameter, e.g. `N`.
+        size: Box<Self>,
+    },
+
+    /// A reference type, e.g. `&String` and `&mut List[T]`.
+    #[cfg_attr(feature = "serde", serde(rename = "reference_type"))]
+    Reference {
+        location: Location,
+        is_mutable: bool,
+        inner: Box<Self>,
+    },
 }
 
 impl Type {
@@ -365,12 +511,16 @@ impl Type {
             | Self::Constructor(TypeConstructor { location, .. })
             | Self::InterfaceObject { location, .. }
             | Self::Tuple { location, .. }
-            | Self::Underscore { location } => *location,
+            | Self::Underscore { location }
+            | Self::Const { location, .. }
+            | Self::Array { location, .. }
+            | Self::Reference { location, .. } => *location,
         }
     }
 }
 
-/// A generic parameter, e.g. `T` in `fun into[T](a: T);`.
+/// A generic parameter, e.g. `T` in `fun into[T](a: T);`, or a const generic
+/// parameter, e.g. `N` in `struct Array[T, const N: usize] {}`.
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GenericParameter {
@@ -379,21 +529,61 @@ pub struct GenericParameter {
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub bounds: Option<Vec<TypeConstructor>>,
 
+    /// Type of a const generic parameter, e.g. `usize` in `const N: usize`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub const_type: Option<Type>,
+
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub default_value: Option<Type>,
 }
 
 /// A type alias, e.g. `type MyResult = Result[String, MyError]`.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TypeAlias {
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
     pub value: Type,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
+}
+
+/// A constant, e.g. `const MAX_SIZE: uint32 = 1024`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Constant {
+    pub attributes: Vec<Attribute>,
+    pub visibility: Visibility,
+    pub name: IdentifierAST,
+
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub ty: Type,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub value: Option<Expression>,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub docstring: Option<Docstring>,
+}
+
+/// A static item, e.g. `static mut COUNTER: uint32 = 0`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Static {
+    pub attributes: Vec<Attribute>,
+    pub visibility: Visibility,
+    pub is_mutable: bool,
+    pub name: IdentifierAST,
+
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub ty: Type,
+    pub value: Expression,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub docstring: Option<Docstring>,
 }
 
 /// A where clause item, e.g. `T: ToString`.
@@ -410,52 +600,142 @@ pub struct WherePredicate {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum Expression {
-    /// List expression, e.g. `[1, 2, 3]`.
+    /// List expression, e.g. `[1, 2, 3]` and `[1, ..rest]`.
     #[cfg_attr(feature = "serde", serde(rename = "list_expression"))]
     List {
+        hir_id: HirId,
         location: Location,
-        elements: Vec<Self>,
+        elements: Vec<ListElement>,
+    },
+
+    /// Array repeat expression, e.g. `[0; 4]`.
+    #[cfg_attr(feature = "serde", serde(rename = "array_repeat_expression"))]
+    ArrayRepeat {
+        hir_id: HirId,
+        location: Location,
+        value: Box<Self>,
+        size: Box<Self>,
+    },
+
+    /// Borrow expression, e.g. `&a` and `&mut a`.
+    #[cfg_attr(feature = "serde", serde(rename = "borrow_expression"))]
+    Borrow {
+        hir_id: HirId,
+        location: Location,
+        is_mutable: bool,
+        inner: Box<Self>,
+    },
+
+    /// Map expression, e.g. `{"a": 1, "b": 2}` and `{:}`.
+    #[cfg_attr(feature = "serde", serde(rename = "map_expression"))]
+    Map {
+        hir_id: HirId,
+        location: Location,
+        entries: Vec<MapEntry>,
     },
 
     /// As expression, e.g. `a as float32`.
     #[cfg_attr(feature = "serde", serde(rename = "as_expression"))]
     As {
+        hir_id: HirId,
         location: Location,
         left: Box<Self>,
         right: Type,
     },
 
     /// Binary expression, e.g. `1 + 2`.
+    ///
+    /// Compound assignments are lowered into usual ones, e.g. `a += b` into
+    /// `a = a + b`, so the operator is never `+=`, `-=`, etc.
     #[cfg_attr(feature = "serde", serde(rename = "binary_expression"))]
     Binary {
+        hir_id: HirId,
         location: Location,
         left: Box<Self>,
         operator: stellar_ast::BinaryOperator,
         right: Box<Self>,
+
+        /// Whether the expression was lowered from a compound assignment,
+        /// e.g. `a + b` in `a = a + b` lowered from `a += b`, so that it can be
+        /// dispatched to the corresponding operator overload.
+        is_compound_assignment: bool,
+    },
+
+    /// Range expression, e.g. `a..b`, `a..=b`, `..b`, `a..`.
+    #[cfg_attr(feature = "serde", serde(rename = "range_expression"))]
+    Range {
+        hir_id: HirId,
+        location: Location,
+
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        start: Option<Box<Self>>,
+
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        end: Option<Box<Self>>,
+
+        #[cfg_attr(feature = "serde", serde(rename = "range_kind"))]
+        kind: stellar_ast::RangeKind,
     },
 
     /// Block expression, e.g. `{ let b = 1; b }`.
     #[cfg_attr(feature = "serde", serde(rename = "block_expression"))]
     StatementsBlock {
+        hir_id: HirId,
+        location: Location,
+        block: Vec<Statement>,
+    },
+
+    /// Await expression, e.g. `fetch(url).await`.
+    #[cfg_attr(feature = "serde", serde(rename = "await_expression"))]
+    Await {
+        hir_id: HirId,
+        location: Location,
+        inner: Box<Self>,
+    },
+
+    /// Unsafe block expression, e.g. `unsafe { ptr.read() }`.
+    #[cfg_attr(feature = "serde", serde(rename = "unsafe_expression"))]
+    Unsafe {
+        hir_id: HirId,
+        location: Location,
+        block: Vec<Statement>,
+    },
+
+    /// Try block expression, e.g. `try { a?.b? }`. `?` operators inside the
+    /// block propagate errors to the block instead of the enclosing function.
+    #[cfg_attr(feature = "serde", serde(rename = "try_expression"))]
+    Try {
+        hir_id: HirId,
         location: Location,
         block: Vec<Statement>,
     },
 
     /// Literal expression, e.g. `true`, `\"hello\"`, `1.2`.
     #[cfg_attr(feature = "serde", serde(rename = "literal_expression"))]
-    Literal(Literal),
+    Literal {
+        hir_id: HirId,
+
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        literal: Literal,
+    },
 
     /// Identifier expression, e.g. `foo`.
     #[cfg_attr(feature = "serde", serde(rename = "identifier_expression"))]
-    Identifier(IdentifierAST),
+    Identifier {
+        hir_id: HirId,
+
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        identifier: IdentifierAST,
+    },
 
     /// Underscore expression, e.g. `_`.
     #[cfg_attr(feature = "serde", serde(rename = "underscore_expression"))]
-    Underscore { location: Location },
+    Underscore { hir_id: HirId, location: Location },
 
     /// If expression, e.g. `if x { ... } else { ... }`.
     #[cfg_attr(feature = "serde", serde(rename = "if_expression"))]
     If {
+        hir_id: HirId,
         location: Location,
         if_blocks: Vec<(Self, Vec<Statement>)>,
         r#else: Option<Vec<Statement>>,
@@ -464,14 +744,26 @@ pub enum Expression {
     /// Field access expression, e.g. `x.y`.
     #[cfg_attr(feature = "serde", serde(rename = "field_access_expression"))]
     FieldAccess {
+        hir_id: HirId,
         location: Location,
         left: Box<Self>,
         right: IdentifierAST,
     },
 
+    /// Tuple index expression, e.g. `x.0`.
+    #[cfg_attr(feature = "serde", serde(rename = "tuple_index_expression"))]
+    TupleIndex {
+        hir_id: HirId,
+        location: Location,
+        left: Box<Self>,
+        index: usize,
+        index_location: Location,
+    },
+
     /// Prefix expression, e.g. `!false`, `++a`.
     #[cfg_attr(feature = "serde", serde(rename = "prefix_expression"))]
     Prefix {
+        hir_id: HirId,
         location: Location,
         inner: Box<Self>,
         operator: stellar_ast::PrefixOperator,
@@ -480,6 +772,7 @@ pub enum Expression {
     /// Postfix expression, e.g. `safe_div(1, 0)?`, `a++`.
     #[cfg_attr(feature = "serde", serde(rename = "postfix_expression"))]
     Postfix {
+        hir_id: HirId,
         location: Location,
         inner: Box<Self>,
         operator: stellar_ast::PostfixOperator,
@@ -488,6 +781,7 @@ pub enum Expression {
     /// While expression, e.g. `while x != 0 {}`.
     #[cfg_attr(feature = "serde", serde(rename = "while_expression"))]
     While {
+        hir_id: HirId,
         location: Location,
         condition: Box<Self>,
         statements_block: Vec<Statement>,
@@ -496,13 +790,15 @@ pub enum Expression {
     /// Call expression, e.g. `s.to_string()`.
     #[cfg_attr(feature = "serde", serde(rename = "call_expression"))]
     Call {
+        hir_id: HirId,
         location: Location,
         callee: Box<Self>,
-        arguments: Vec<Self>,
+        arguments: Vec<CallArgument>,
     },
 
     /// Type expression, e.g. `A[int32]`.
     TypeArguments {
+        hir_id: HirId,
         location: Location,
         left: Box<Self>,
         type_arguments: Vec<Type>,
@@ -510,19 +806,26 @@ pub enum Expression {
 
     /// Tuple expression, e.g. `(a, 32, \"hello\")`.
     Tuple {
+        hir_id: HirId,
         location: Location,
         elements: Vec<Self>,
     },
 
     /// Struct expression, e.g. `Person { name: \"John\", age: 25 }`.
     Struct {
+        hir_id: HirId,
         location: Location,
         left: Box<Self>,
         fields: Vec<StructExpressionItem>,
+
+        /// The expression after `..`, that provides the remaining fields.
+        #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+        rest: Option<Box<Self>>,
     },
 
     /// Match expression (`match fs.read_file(...) { ... }`).
     Match {
+        hir_id: HirId,
         location: Location,
         expression: Box<Self>,
         block: Vec<MatchExpressionItem>,
@@ -530,13 +833,32 @@ pub enum Expression {
 
     /// Lambda expression (`|x| { x + 1 }`).
     Lambda {
+        hir_id: HirId,
         location: Location,
+        captures: Vec<LambdaCapture>,
+
+        /// Variables of enclosing functions and lambdas used in the lambda,
+        /// including explicitly captured ones.
+        captured_variables: Vec<CapturedVariable>,
         parameters: Vec<LambdaFunctionParameter>,
         return_type: Option<Type>,
         value: Box<Self>,
     },
 }
 
+/// A variable captured by a lambda, e.g. `x` in `|| x + 1`.
+///
+/// Unless the variable is captured explicitly, it is captured by mutable
+/// reference if the lambda assigns to it or borrows it mutably, and by
+/// reference otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CapturedVariable {
+    /// The variable with the location of its first use in the lambda.
+    pub name: IdentifierAST,
+    pub kind: CaptureKind,
+}
+
 /// A lambda function parameter, e.g. `x` in `|x| { x + 1 }`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -555,12 +877,23 @@ impl Expression {
     pub const fn location(&self) -> Location {
         match self {
             Self::List { location, .. }
+            | Self::ArrayRepeat { location, .. }
+            | Self::Map { location, .. }
+            | Self::Borrow { location, .. }
             | Self::As { location, .. }
             | Self::Binary { location, .. }
+            | Self::Range { location, .. }
             | Self::StatementsBlock { location, .. }
-            | Self::Identifier(IdentifierAST { location, .. })
+            | Self::Unsafe { location, .. }
+            | Self::Try { location, .. }
+            | Self::Await { location, .. }
+            | Self::Identifier {
+                identifier: IdentifierAST { location, .. },
+                ..
+            }
             | Self::If { location, .. }
             | Self::FieldAccess { location, .. }
+            | Self::TupleIndex { location, .. }
             | Self::Prefix { location, .. }
             | Self::Postfix { location, .. }
             | Self::While { location, .. }
@@ -570,8 +903,42 @@ impl Expression {
             | Self::Match { location, .. }
             | Self::Lambda { location, .. }
             | Self::TypeArguments { location, .. }
-            | Self::Underscore { location } => *location,
-            Self::Literal(literal) => literal.location(),
+            | Self::Underscore { location, .. } => *location,
+            Self::Literal { literal, .. } => literal.location(),
+        }
+    }
+
+    /// Returns the HIR ID of the expression.
+    #[inline]
+    #[must_use]
+    pub const fn hir_id(&self) -> HirId {
+        match self {
+            Self::List { hir_id, .. }
+            | Self::ArrayRepeat { hir_id, .. }
+            | Self::Map { hir_id, .. }
+            | Self::Borrow { hir_id, .. }
+            | Self::As { hir_id, .. }
+            | Self::Binary { hir_id, .. }
+            | Self::Range { hir_id, .. }
+            | Self::StatementsBlock { hir_id, .. }
+            | Self::Unsafe { hir_id, .. }
+            | Self::Try { hir_id, .. }
+            | Self::Await { hir_id, .. }
+            | Self::Literal { hir_id, .. }
+            | Self::Identifier { hir_id, .. }
+            | Self::If { hir_id, .. }
+            | Self::FieldAccess { hir_id, .. }
+            | Self::TupleIndex { hir_id, .. }
+            | Self::Prefix { hir_id, .. }
+            | Self::Postfix { hir_id, .. }
+            | Self::While { hir_id, .. }
+            | Self::Call { hir_id, .. }
+            | Self::Tuple { hir_id, .. }
+            | Self::Struct { hir_id, .. }
+            | Self::Match { hir_id, .. }
+            | Self::Lambda { hir_id, .. }
+            | Self::TypeArguments { hir_id, .. }
+            | Self::Underscore { hir_id, .. } => *hir_id,
         }
     }
 }
@@ -586,13 +953,54 @@ pub struct MatchExpressionItem {
 
 /// A field item in a struct expression (`identifier` and optionally `:` `expression`),
 /// e.g. `name: "John"` and `age` in `Person { name: "John", age }`.
+///
+/// The shorthand form `age` is desugared into `age: age`, so the value is
+/// always present.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct StructExpressionItem {
     pub name: IdentifierAST,
+    pub value: Expression,
 
+    /// Whether the field was written in the shorthand form, e.g. `age`
+    /// instead of `age: age`.
+    pub is_shorthand: bool,
+}
+
+/// An argument in a call expression (optionally `identifier` `:` and `expression`),
+/// e.g. `10`, `y: 20` and `..rest` in `draw(10, y: 20)` and `draw(..rest)`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CallArgument {
+    /// The label of a named argument, matched to a parameter name.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub value: Option<Expression>,
+    pub name: Option<IdentifierAST>,
+    pub kind: stellar_ast::ElementKind,
+
+    /// The value of the argument, the shorthand form `y:` is desugared into
+    /// `y: y`.
+    pub value: Expression,
+
+    /// Whether the argument was written in the shorthand form, e.g. `y:`
+    /// instead of `y: y`.
+    pub is_shorthand: bool,
+}
+
+/// An entry of a map expression (`expression` `:` `expression`), e.g. `"a": 1`
+/// in `{"a": 1}`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MapEntry {
+    pub key: Expression,
+    pub value: Expression,
+}
+
+/// An element of a list expression, e.g. `1` and `..rest` in `[1, ..rest]`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ListElement {
+    pub kind: stellar_ast::ElementKind,
+    pub value: Expression,
 }
 
 impl Expression {
@@ -614,32 +1022,50 @@ impl Expression {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum Statement {
-    /// Defer statement - `defer <expr>;`, e.g. `defer file.close()`.
+    /// Defer statement, e.g. `defer { close(f); flush(log); }`. The single
+    /// call form, e.g. `defer file.close();`, is lowered into a block.
     #[cfg_attr(feature = "serde", serde(rename = "defer_statement"))]
-    Defer { call: Expression },
+    Defer {
+        hir_id: HirId,
+        location: Location,
+        block: Vec<Self>,
+    },
 
     /// Expression statement, e.g. `call();`.
     #[cfg_attr(feature = "serde", serde(rename = "expression_statement"))]
     Expression {
+        hir_id: HirId,
         expression: Expression,
         has_semicolon: bool,
     },
 
     /// Break statement - `break;`.
     #[cfg_attr(feature = "serde", serde(rename = "break_statement"))]
-    Break { location: Location },
+    Break { hir_id: HirId, location: Location },
 
     /// Continue statement - `continue`;
     #[cfg_attr(feature = "serde", serde(rename = "continue_statement"))]
-    Continue { location: Location },
+    Continue { hir_id: HirId, location: Location },
 
     /// Return statement - `return <expr>;`, e.g. `return 42;`.
     #[cfg_attr(feature = "serde", serde(rename = "return_statement"))]
-    Return { expression: Expression },
+    Return {
+        hir_id: HirId,
+        expression: Expression,
+    },
+
+    /// Yield statement - `yield <expr>;`, e.g. `yield item;`.
+    #[cfg_attr(feature = "serde", serde(rename = "yield_statement"))]
+    Yield {
+        hir_id: HirId,
+        location: Location,
+        expression: Expression,
+    },
 
     /// Let statement - `let <pattern> = <expr>;`, e.g. `let x = 1`.
     #[cfg_attr(feature = "serde", serde(rename = "let_statement"))]
     Let {
+        hir_id: HirId,
         pattern: Pattern,
         value: Expression,
 
@@ -649,6 +1075,23 @@ pub enum Statement {
     },
 }
 
+impl Statement {
+    /// Returns the HIR ID of the statement.
+    #[inline]
+    #[must_use]
+    pub const fn hir_id(&self) -> HirId {
+        match self {
+            Self::Defer { hir_id, .. }
+            | Self::Expression { hir_id, .. }
+            | Self::Break { hir_id, .. }
+            | Self::Continue { hir_id, .. }
+            | Self::Return { hir_id, .. }
+            | Self::Yield { hir_id, .. }
+            | Self::Let { hir_id, .. } => *hir_id,
+        }
+    }
+}
+
 /// A function.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -663,7 +1106,11 @@ pub struct Function {
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FunctionSignature {
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
+    pub is_async: bool,
+    pub is_unsafe: bool,
+    pub is_generator: bool,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
     pub parameters: Vec<FunctionParameter>,
@@ -674,13 +1121,14 @@ pub struct FunctionSignature {
     pub where_predicates: Vec<WherePredicate>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
 }
 
 /// An enum module item.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Enum {
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
@@ -692,27 +1140,30 @@ pub struct Enum {
     pub implements: Option<Vec<TypeConstructor>>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
 }
 
 /// An interface module item.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Interface {
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
     pub where_predicates: Vec<WherePredicate>,
+    pub constants: Vec<Constant>,
     pub methods: Vec<Function>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
 }
 
 /// A struct module item.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Struct {
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
@@ -724,13 +1175,14 @@ pub struct Struct {
     pub implements: Option<Vec<TypeConstructor>>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
 }
 
 /// A tuple-like struct module item.
 #[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TupleLikeStruct {
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
     pub generic_parameters: Vec<GenericParameter>,
@@ -742,7 +1194,46 @@ pub struct TupleLikeStruct {
     pub implements: Option<Vec<TypeConstructor>>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
+}
+
+/// An implementation block, e.g. `impl Point { ... }`, `impl[T] Iterator for Range[T] { ... }`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Impl {
+    /// Location of the entire implementation block.
+    pub location: Location,
+    pub attributes: Vec<Attribute>,
+    pub generic_parameters: Vec<GenericParameter>,
+
+    /// Interface implemented in the block, `None` for inherent implementations.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub interface: Option<TypeConstructor>,
+
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub ty: Type,
+    pub where_predicates: Vec<WherePredicate>,
+    pub methods: Vec<Function>,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub docstring: Option<Docstring>,
+}
+
+/// An extern block declaring foreign functions, e.g.
+/// `extern "C" { fun puts(s: CStr): int32; }`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExternBlock {
+    /// Location of the entire extern block.
+    pub location: Location,
+    pub attributes: Vec<Attribute>,
+
+    /// ABI of the declared functions, e.g. `C` in `extern "C"`.
+    pub abi: String,
+    pub functions: Vec<FunctionSignature>,
+
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub docstring: Option<Docstring>,
 }
 
 /// A module item.
@@ -750,19 +1241,36 @@ pub struct TupleLikeStruct {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum ModuleItem {
+    /// Constant item.
+    #[cfg_attr(feature = "serde", serde(rename = "constant_module_item"))]
+    Constant(Constant),
+
     /// Enum item.
     #[cfg_attr(feature = "serde", serde(rename = "enum_module_item"))]
     Enum(Enum),
 
+    /// Extern block item.
+    #[cfg_attr(feature = "serde", serde(rename = "extern_module_item"))]
+    Extern(ExternBlock),
+
     /// Function item.
     #[cfg_attr(feature = "serde", serde(rename = "function_module_item"))]
     Function(Function),
 
+    /// Implementation block item.
+    #[cfg_attr(feature = "serde", serde(rename = "impl_module_item"))]
+    Impl(Impl),
+
     /// Import item.
     #[cfg_attr(feature = "serde", serde(rename = "import_module_item"))]
     Import {
         /// Location of the entire import item.
         location: Location,
+        attributes: Vec<Attribute>,
+
+        /// Visibility of the import, non-private imports re-export the
+        /// imported names.
+        visibility: Visibility,
         path: ImportPath,
     },
 
@@ -770,6 +1278,10 @@ pub enum ModuleItem {
     #[cfg_attr(feature = "serde", serde(rename = "interface_module_item"))]
     Interface(Interface),
 
+    /// Static item.
+    #[cfg_attr(feature = "serde", serde(rename = "static_module_item"))]
+    Static(Static),
+
     /// Struct item.
     #[cfg_attr(feature = "serde", serde(rename = "struct_module_item"))]
     Struct(Struct),
@@ -789,7 +1301,11 @@ impl ModuleItem {
     #[must_use]
     pub const fn location(&self) -> Location {
         match self {
-            Self::Enum(Enum {
+            Self::Constant(Constant {
+                name: IdentifierAST { location, .. },
+                ..
+            })
+            | Self::Enum(Enum {
                 name: IdentifierAST { location, .. },
                 ..
             })
@@ -801,7 +1317,13 @@ impl ModuleItem {
                     },
                 ..
             })
+            | Self::Impl(Impl { location, .. })
+            | Self::Extern(ExternBlock { location, .. })
             | Self::Import { location, .. }
+            | Self::Static(Static {
+                name: IdentifierAST { location, .. },
+                ..
+            })
             | Self::Struct(Struct {
                 name: IdentifierAST { location, .. },
                 ..
@@ -826,7 +1348,11 @@ impl ModuleItem {
     #[must_use]
     pub const fn name(&self) -> Option<IdentifierId> {
         match self {
-            Self::Enum(Enum {
+            Self::Constant(Constant {
+                name: IdentifierAST { id, .. },
+                ..
+            })
+            | Self::Enum(Enum {
                 name: IdentifierAST { id, .. },
                 ..
             })
@@ -838,6 +1364,10 @@ impl ModuleItem {
                     },
                 ..
             })
+            | Self::Static(Static {
+                name: IdentifierAST { id, .. },
+                ..
+            })
             | Self::Struct(Struct {
                 name: IdentifierAST { id, .. },
                 ..
@@ -854,7 +1384,7 @@ impl ModuleItem {
                 name: IdentifierAST { id, .. },
                 ..
             }) => Some(*id),
-            Self::Import { .. } => None,
+            Self::Impl(..) | Self::Extern(..) | Self::Import { .. } => None,
         }
     }
 
@@ -874,10 +1404,14 @@ impl ModuleItem {
     #[must_use]
     pub const fn kind(&self) -> ModuleItemKind {
         match self {
+            Self::Constant(..) => ModuleItemKind::Constant,
             Self::Enum { .. } => ModuleItemKind::Enum,
             Self::Function(..) => ModuleItemKind::Function,
+            Self::Impl(..) => ModuleItemKind::Impl,
+            Self::Extern(..) => ModuleItemKind::Extern,
             Self::Import { .. } => ModuleItemKind::Import,
             Self::Interface { .. } => ModuleItemKind::Interface,
+            Self::Static(..) => ModuleItemKind::Static,
             Self::Struct { .. } => ModuleItemKind::Struct,
             Self::TupleLikeStruct { .. } => ModuleItemKind::TupleLikeStruct,
             Self::TypeAlias(..) => ModuleItemKind::TypeAlias,
@@ -889,7 +1423,9 @@ impl ModuleItem {
     #[must_use]
     pub const fn visibility(&self) -> Visibility {
         match self {
-            Self::Enum(Enum { visibility, .. })
+            Self::Constant(Constant { visibility, .. })
+            | Self::Enum(Enum { visibility, .. })
+            | Self::Static(Static { visibility, .. })
             | Self::Struct(Struct { visibility, .. })
             | Self::TupleLikeStruct(TupleLikeStruct { visibility, .. })
             | Self::Interface(Interface { visibility, .. })
@@ -897,8 +1433,31 @@ impl ModuleItem {
             | Self::Function(Function {
                 signature: FunctionSignature { visibility, .. },
                 ..
-            }) => *visibility,
-            Self::Import { .. } => Visibility::Private,
+            })
+            | Self::Import { visibility, .. } => *visibility,
+            Self::Impl(..) | Self::Extern(..) => Visibility::Private,
+        }
+    }
+
+    /// Returns the attributes of the item.
+    #[inline]
+    #[must_use]
+    pub fn attributes(&self) -> &[Attribute] {
+        match self {
+            Self::Constant(Constant { attributes, .. })
+            | Self::Enum(Enum { attributes, .. })
+            | Self::Static(Static { attributes, .. })
+            | Self::Struct(Struct { attributes, .. })
+            | Self::TupleLikeStruct(TupleLikeStruct { attributes, .. })
+            | Self::Interface(Interface { attributes, .. })
+            | Self::TypeAlias(TypeAlias { attributes, .. })
+            | Self::Function(Function {
+                signature: FunctionSignature { attributes, .. },
+                ..
+            })
+            | Self::Impl(Impl { attributes, .. })
+            | Self::Extern(ExternBlock { attributes, .. })
+            | Self::Import { attributes, .. } => attributes,
         }
     }
 
@@ -925,7 +1484,7 @@ impl ModuleItem {
 }
 
 /// An enum item, e.g. `None`, `Ok(T)`, `A { b: T }`.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum EnumItem {
@@ -935,7 +1494,7 @@ pub enum EnumItem {
         name: IdentifierAST,
 
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-        docstring: Option<String>,
+        docstring: Option<Docstring>,
     },
     /// A tuple-like enum item, e.g. `None` in `enum Option<T> { Some(T), None }`.
     #[cfg_attr(feature = "serde", serde(rename = "tuple_like"))]
@@ -944,7 +1503,7 @@ pub enum EnumItem {
         fields: Vec<TupleField>,
 
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-        docstring: Option<String>,
+        docstring: Option<Docstring>,
     },
     /// A struct item, e.g. `A { b: T }` in `enum B { A { b: T } }`.
     #[cfg_attr(feature = "serde", serde(rename = "struct"))]
@@ -953,7 +1512,7 @@ pub enum EnumItem {
         fields: Vec<StructField>,
 
         #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-        docstring: Option<String>,
+        docstring: Option<Docstring>,
     },
 }
 
@@ -986,9 +1545,10 @@ pub struct TupleField {
 }
 
 /// A struct field, e.g. `name: String`, `pub age: uint32`.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct StructField {
+    pub attributes: Vec<Attribute>,
     pub visibility: Visibility,
     pub name: IdentifierAST,
 
@@ -996,7 +1556,7 @@ pub struct StructField {
     pub ty: Type,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
 }
 
 /// A function parameter, e.g. `self`, `self: Self`, `a: uint32`.
@@ -1042,5 +1602,9 @@ pub struct Module {
     pub items: Vec<ModuleItem>,
 
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-    pub docstring: Option<String>,
+    pub docstring: Option<Docstring>,
+
+    /// Origins of expressions and statements of the module.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub source_map: SourceMap,
 }
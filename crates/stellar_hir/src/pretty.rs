@@ -0,0 +1,1252 @@
+//! Renders HIR back into Stellar-like source code.
+//!
+//! Unlike [`stellar_ast::unparse`], the output is not meant to be parsed
+//! again: it shows the results of desugaring, that is done during lowering,
+//! e.g. `loop` expressions become `while true` loops and `for` loops become
+//! loops over iterators with synthetic variables, like `$iterator`, that users
+//! can't write:
+//!
+//! ```
+//! use stellar_filesystem::location::DUMMY_LOCATION;
+//! use stellar_hir::{pretty::print_expression, Expression, Literal, Statement, DUMMY_HIR_ID};
+//!
+//! let expression = Expression::While {
+//!     hir_id: DUMMY_HIR_ID,
+//!     location: DUMMY_LOCATION,
+//!     condition: Box::new(Expression::Literal {
+//!         hir_id: DUMMY_HIR_ID,
+//!         literal: Literal::Boolean {
+//!             location: DUMMY_LOCATION,
+//!             value: true,
+//!         },
+//!     }),
+//!     statements_block: vec![Statement::Break {
+//!         hir_id: DUMMY_HIR_ID,
+//!         location: DUMMY_LOCATION,
+//!     }],
+//! };
+//!
+//! assert_eq!(print_expression(&expression), "while true {\n    break;\n}");
+//! ```
+//!
+//! The layout follows [`stellar_ast::unparse`]: one item, statement or match
+//! arm per line, with nested blocks indented according to [`UnparseOptions`].
+//! Docstrings are not rendered.
+
+use stellar_ast::{
+    precedence::{Associativity, Fixity, Operator, Precedence},
+    token::RawToken,
+    unparse::{unparse_expression, unparse_literal, UnparseOptions},
+    ElementKind, NegativeNumericLiteral, RangeKind, RawPrefixOperator,
+};
+use stellar_filesystem::location::DUMMY_LOCATION;
+use stellar_interner::IdentifierId;
+
+use crate::{
+    Attribute, BindingMode, CaptureKind, ConstValue, Constant, EnumItem, Expression, Function,
+    FunctionParameter, FunctionSignature, GenericParameter, ImportPath, Literal, Module,
+    ModuleItem, Path, Pattern, Statement, StructField, StructFieldPattern, Type, TypeConstructor,
+    Visibility, WherePredicate,
+};
+
+/// Renders a module into source code using default options.
+#[must_use]
+pub fn print(module: &Module) -> String {
+    print_with_options(module, &UnparseOptions::default())
+}
+
+/// Renders a module into source code.
+#[must_use]
+pub fn print_with_options(module: &Module, options: &UnparseOptions) -> String {
+    let mut printer = Printer::new(options);
+    printer.module_items(&module.items);
+    printer.output
+}
+
+/// Renders a module item into source code using default options.
+#[must_use]
+pub fn print_item(item: &ModuleItem) -> String {
+    let options = UnparseOptions::default();
+    let mut printer = Printer::new(&options);
+    printer.module_item(item);
+    printer.output
+}
+
+/// Renders an expression into source code using default options.
+#[must_use]
+pub fn print_expression(expression: &Expression) -> String {
+    let options = UnparseOptions::default();
+    let mut printer = Printer::new(&options);
+    printer.expression(expression);
+    printer.output
+}
+
+/// Renders a pattern into source code.
+#[must_use]
+pub fn print_pattern(pattern: &Pattern) -> String {
+    let options = UnparseOptions::default();
+    let mut printer = Printer::new(&options);
+    printer.pattern(pattern);
+    printer.output
+}
+
+/// Renders a type into source code.
+#[must_use]
+pub fn print_type(ty: &Type) -> String {
+    let options = UnparseOptions::default();
+    let mut printer = Printer::new(&options);
+    printer.ty(ty);
+    printer.output
+}
+
+struct Printer<'o> {
+    options: &'o UnparseOptions,
+    output: String,
+    depth: usize,
+}
+
+impl<'o> Printer<'o> {
+    const fn new(options: &'o UnparseOptions) -> Self {
+        Self {
+            options,
+            output: String::new(),
+            depth: 0,
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        self.output.push_str(text);
+    }
+
+    fn newline(&mut self) {
+        self.output.push('\n');
+
+        for _ in 0..self.depth {
+            self.output.push_str(&self.options.indentation);
+        }
+    }
+
+    fn identifier(&mut self, id: IdentifierId) {
+        self.write(&id.as_str());
+    }
+
+    fn path(&mut self, path: &Path) {
+        for (idx, identifier) in path.identifiers.iter().enumerate() {
+            if idx != 0 {
+                self.write(".");
+            }
+
+            self.identifier(identifier.id);
+        }
+    }
+
+    /// Writes elements separated by `, `.
+    fn comma_separated<T>(&mut self, elements: &[T], mut write: impl FnMut(&mut Self, &T)) {
+        for (idx, element) in elements.iter().enumerate() {
+            if idx != 0 {
+                self.write(", ");
+            }
+
+            write(self, element);
+        }
+    }
+
+    /// Writes `{`, lines produced by `write` indented by one level, and `}`.
+    fn indented_block(&mut self, is_empty: bool, write: impl FnOnce(&mut Self)) {
+        if is_empty {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.depth += 1;
+        write(self);
+        self.depth -= 1;
+        self.newline();
+        self.write("}");
+    }
+
+    fn module_items(&mut self, items: &[ModuleItem]) {
+        for (idx, item) in items.iter().enumerate() {
+            if idx != 0 {
+                self.newline();
+
+                // imports are grouped together
+                if !matches!(
+                    (&items[idx - 1], item),
+                    (ModuleItem::Import { .. }, ModuleItem::Import { .. })
+                ) {
+                    self.newline();
+                }
+            }
+
+            self.module_item(item);
+        }
+
+        if !items.is_empty() {
+            self.write("\n");
+        }
+    }
+
+    /// Writes attributes, e.g. `#[inline, deprecated("use `bar`")]`.
+    fn attribute_list(&mut self, attributes: &[Attribute]) {
+        self.write("#[");
+        self.comma_separated(attributes, |this, attribute| {
+            this.path(&attribute.path);
+
+            if let Some(arguments) = &attribute.arguments {
+                this.write("(");
+                this.comma_separated(arguments, |this, argument| {
+                    this.write(&unparse_expression(argument));
+                });
+                this.write(")");
+            }
+        });
+        self.write("]");
+    }
+
+    fn attributes(&mut self, attributes: &[Attribute]) {
+        if attributes.is_empty() {
+            return;
+        }
+
+        self.attribute_list(attributes);
+        self.newline();
+    }
+
+    fn visibility(&mut self, visibility: Visibility) {
+        self.write(match visibility {
+            Visibility::Private => "",
+            Visibility::Public(..) => "pub ",
+            Visibility::Package(..) => "pub(package) ",
+            Visibility::Super(..) => "pub(super) ",
+        });
+    }
+
+    fn module_item(&mut self, item: &ModuleItem) {
+        match item {
+            ModuleItem::Constant(constant) => self.constant(constant),
+            ModuleItem::Enum(enum_) => {
+                self.attributes(&enum_.attributes);
+                self.visibility(enum_.visibility);
+                self.write("enum ");
+                self.identifier(enum_.name.id);
+                self.generic_parameters(&enum_.generic_parameters);
+                self.implements(enum_.implements.as_deref());
+                self.where_predicates(&enum_.where_predicates);
+                self.write(" ");
+
+                let is_empty = enum_.items.is_empty() && enum_.methods.is_empty();
+
+                self.indented_block(is_empty, |this| {
+                    for (idx, item) in enum_.items.iter().enumerate() {
+                        if idx != 0 {
+                            this.write(",");
+                        }
+
+                        this.newline();
+                        this.enum_item(item);
+                    }
+
+                    this.methods(&enum_.methods, !enum_.items.is_empty());
+                });
+            }
+            ModuleItem::Extern(block) => {
+                self.attributes(&block.attributes);
+                self.write("extern ");
+                self.literal(&Literal::String {
+                    value: block.abi.clone(),
+                    location: DUMMY_LOCATION,
+                });
+                self.write(" ");
+                self.indented_block(block.functions.is_empty(), |this| {
+                    for signature in &block.functions {
+                        this.newline();
+                        this.function_signature(signature);
+                        this.write(";");
+                    }
+                });
+            }
+            ModuleItem::Function(function) => self.function(function),
+            ModuleItem::Impl(impl_) => {
+                self.attributes(&impl_.attributes);
+                self.write("impl");
+                self.generic_parameters(&impl_.generic_parameters);
+                self.write(" ");
+
+                if let Some(interface) = &impl_.interface {
+                    self.type_constructor(interface);
+                    self.write(" for ");
+                }
+
+                self.ty(&impl_.ty);
+                self.where_predicates(&impl_.where_predicates);
+                self.write(" ");
+                self.indented_block(impl_.methods.is_empty(), |this| {
+                    this.methods(&impl_.methods, false);
+                });
+            }
+            ModuleItem::Import {
+                attributes,
+                visibility,
+                path,
+                ..
+            } => {
+                self.attributes(attributes);
+                self.visibility(*visibility);
+                self.write("import ");
+                self.import_path(path);
+                self.write(";");
+            }
+            ModuleItem::Interface(interface) => {
+                self.attributes(&interface.attributes);
+                self.visibility(interface.visibility);
+                self.write("interface ");
+                self.identifier(interface.name.id);
+                self.generic_parameters(&interface.generic_parameters);
+                self.where_predicates(&interface.where_predicates);
+                self.write(" ");
+
+                let is_empty = interface.constants.is_empty() && interface.methods.is_empty();
+
+                self.indented_block(is_empty, |this| {
+                    for constant in &interface.constants {
+                        this.newline();
+                        this.constant(constant);
+                    }
+
+                    this.methods(&interface.methods, !interface.constants.is_empty());
+                });
+            }
+            ModuleItem::Static(static_) => {
+                self.attributes(&static_.attributes);
+                self.visibility(static_.visibility);
+                self.write(if static_.is_mutable {
+                    "static mut "
+                } else {
+                    "static "
+                });
+                self.identifier(static_.name.id);
+                self.write(": ");
+                self.ty(&static_.ty);
+                self.write(" = ");
+                self.expression(&static_.value);
+                self.write(";");
+            }
+            ModuleItem::Struct(struct_) => {
+                self.attributes(&struct_.attributes);
+                self.visibility(struct_.visibility);
+                self.write("struct ");
+                self.identifier(struct_.name.id);
+                self.generic_parameters(&struct_.generic_parameters);
+                self.implements(struct_.implements.as_deref());
+                self.where_predicates(&struct_.where_predicates);
+                self.write(" ");
+
+                let is_empty = struct_.fields.is_empty() && struct_.methods.is_empty();
+
+                self.indented_block(is_empty, |this| {
+                    for field in &struct_.fields {
+                        this.newline();
+                        this.struct_field(field);
+                        this.write(",");
+                    }
+
+                    this.methods(&struct_.methods, !struct_.fields.is_empty());
+                });
+            }
+            ModuleItem::TupleLikeStruct(struct_) => {
+                self.attributes(&struct_.attributes);
+                self.visibility(struct_.visibility);
+                self.write("struct ");
+                self.identifier(struct_.name.id);
+                self.generic_parameters(&struct_.generic_parameters);
+                self.write("(");
+                self.comma_separated(&struct_.fields, |this, field| {
+                    this.visibility(field.visibility);
+                    this.ty(&field.ty);
+                });
+                self.write(")");
+                self.implements(struct_.implements.as_deref());
+                self.where_predicates(&struct_.where_predicates);
+
+                if struct_.methods.is_empty() {
+                    self.write(";");
+                } else {
+                    self.write(" ");
+                    self.indented_block(false, |this| this.methods(&struct_.methods, false));
+                }
+            }
+            ModuleItem::TypeAlias(alias) => {
+                self.attributes(&alias.attributes);
+                self.visibility(alias.visibility);
+                self.write("type ");
+                self.identifier(alias.name.id);
+                self.generic_parameters(&alias.generic_parameters);
+                self.write(" = ");
+                self.ty(&alias.value);
+                self.write(";");
+            }
+        }
+    }
+
+    fn import_path(&mut self, path: &ImportPath) {
+        match path {
+            ImportPath::Single { path, as_ } => {
+                self.path(path);
+
+                if let Some(as_) = as_ {
+                    self.write(" as ");
+                    self.identifier(as_.id);
+                }
+            }
+            ImportPath::Glob { path } => {
+                self.path(path);
+                self.write(".*");
+            }
+            ImportPath::Group { path, imports } => {
+                if !path.identifiers.is_empty() {
+                    self.path(path);
+                    self.write(".");
+                }
+
+                self.write("{");
+                self.comma_separated(imports, Self::import_path);
+                self.write("}");
+            }
+        }
+    }
+
+    fn constant(&mut self, constant: &Constant) {
+        self.attributes(&constant.attributes);
+        self.visibility(constant.visibility);
+        self.write("const ");
+        self.identifier(constant.name.id);
+        self.write(": ");
+        self.ty(&constant.ty);
+
+        if let Some(value) = &constant.value {
+            self.write(" = ");
+            self.expression(value);
+        }
+
+        self.write(";");
+    }
+
+    fn enum_item(&mut self, item: &EnumItem) {
+        match item {
+            EnumItem::Just { name, .. } => self.identifier(name.id),
+            EnumItem::TupleLike { name, fields, .. } => {
+                self.identifier(name.id);
+                self.write("(");
+                self.comma_separated(fields, |this, field| {
+                    this.visibility(field.visibility);
+                    this.ty(&field.ty);
+                });
+                self.write(")");
+            }
+            EnumItem::Struct { name, fields, .. } => {
+                self.identifier(name.id);
+
+                if fields.is_empty() {
+                    self.write(" {}");
+                } else {
+                    self.write(" { ");
+                    self.comma_separated(fields, Self::struct_field);
+                    self.write(" }");
+                }
+            }
+        }
+    }
+
+    fn struct_field(&mut self, field: &StructField) {
+        if !field.attributes.is_empty() {
+            self.attribute_list(&field.attributes);
+            self.write(" ");
+        }
+
+        self.visibility(field.visibility);
+        self.identifier(field.name.id);
+        self.write(": ");
+        self.ty(&field.ty);
+    }
+
+    /// Writes methods of a type, separated by blank lines. If `after_fields`
+    /// is `true`, the first method is separated from the preceding fields too.
+    fn methods(&mut self, methods: &[Function], after_fields: bool) {
+        for (idx, method) in methods.iter().enumerate() {
+            if idx != 0 || after_fields {
+                self.write("\n");
+            }
+
+            self.newline();
+            self.function(method);
+        }
+    }
+
+    fn function(&mut self, function: &Function) {
+        self.function_signature(&function.signature);
+
+        if let Some(body) = &function.body {
+            self.write(" ");
+            self.statements_block(body);
+        } else {
+            self.write(";");
+        }
+    }
+
+    fn function_signature(&mut self, signature: &FunctionSignature) {
+        self.attributes(&signature.attributes);
+        self.visibility(signature.visibility);
+
+        if signature.is_async {
+            self.write("async ");
+        }
+
+        if signature.is_unsafe {
+            self.write("unsafe ");
+        }
+
+        if signature.is_generator {
+            self.write("gen ");
+        }
+
+        self.write("fun ");
+        self.identifier(signature.name.id);
+        self.generic_parameters(&signature.generic_parameters);
+        self.write("(");
+        self.comma_separated(&signature.parameters, |this, parameter| match parameter {
+            FunctionParameter::NotSelfParameter(parameter) => {
+                this.pattern(&parameter.pattern);
+                this.write(": ");
+                this.ty(&parameter.ty);
+            }
+            FunctionParameter::SelfParameter(parameter) => {
+                this.write("self");
+
+                if let Some(ty) = &parameter.ty {
+                    this.write(": ");
+                    this.ty(ty);
+                }
+            }
+        });
+        self.write(")");
+
+        if let Some(return_type) = &signature.return_type {
+            self.write(": ");
+            self.ty(return_type);
+        }
+
+        self.where_predicates(&signature.where_predicates);
+    }
+
+    fn generic_parameters(&mut self, generic_parameters: &[GenericParameter]) {
+        if generic_parameters.is_empty() {
+            return;
+        }
+
+        self.write("[");
+        self.comma_separated(generic_parameters, |this, parameter| {
+            if let Some(const_type) = &parameter.const_type {
+                this.write("const ");
+                this.identifier(parameter.name.id);
+                this.write(": ");
+                this.ty(const_type);
+            } else {
+                this.identifier(parameter.name.id);
+
+                if let Some(bounds) = &parameter.bounds {
+                    this.write(": ");
+                    this.bounds(bounds);
+                }
+            }
+
+            if let Some(default_value) = &parameter.default_value {
+                this.write(" = ");
+                this.ty(default_value);
+            }
+        });
+        self.write("]");
+    }
+
+    fn where_predicates(&mut self, predicates: &[WherePredicate]) {
+        if predicates.is_empty() {
+            return;
+        }
+
+        self.write(" where ");
+        self.comma_separated(predicates, |this, predicate| {
+            this.ty(&predicate.ty);
+            this.write(": ");
+            this.bounds(&predicate.bounds);
+        });
+    }
+
+    fn implements(&mut self, implements: Option<&[TypeConstructor]>) {
+        if let Some(implements) = implements {
+            self.write(" implements ");
+            self.comma_separated(implements, Self::type_constructor);
+        }
+    }
+
+    fn bounds(&mut self, bounds: &[TypeConstructor]) {
+        for (idx, bound) in bounds.iter().enumerate() {
+            if idx != 0 {
+                self.write(" + ");
+            }
+
+            self.type_constructor(bound);
+        }
+    }
+
+    fn statements_block(&mut self, statements: &[Statement]) {
+        self.indented_block(statements.is_empty(), |this| {
+            for statement in statements {
+                this.newline();
+                this.statement(statement);
+            }
+        });
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Break { .. } => self.write("break;"),
+            Statement::Continue { .. } => self.write("continue;"),
+            Statement::Defer { block, .. } => {
+                self.write("defer ");
+                self.statements_block(block);
+            }
+            Statement::Expression {
+                expression,
+                has_semicolon,
+                ..
+            } => {
+                self.expression(expression);
+
+                if *has_semicolon {
+                    self.write(";");
+                }
+            }
+            Statement::Let {
+                pattern, value, ty, ..
+            } => {
+                self.write("let ");
+                self.pattern(pattern);
+
+                if let Some(ty) = ty {
+                    self.write(": ");
+                    self.ty(ty);
+                }
+
+                self.write(" = ");
+                self.expression(value);
+                self.write(";");
+            }
+            Statement::Return { expression, .. } => {
+                self.write("return ");
+                self.expression(expression);
+                self.write(";");
+            }
+            Statement::Yield { expression, .. } => {
+                self.write("yield ");
+                self.expression(expression);
+                self.write(";");
+            }
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Literal { literal, .. } => self.literal(literal),
+            Expression::Identifier { identifier, .. } => self.identifier(identifier.id),
+            Expression::Underscore { .. } => self.write("_"),
+            Expression::List { elements, .. } => {
+                self.write("[");
+                self.comma_separated(elements, |this, element| {
+                    if element.kind == ElementKind::Spread {
+                        this.write("..");
+                    }
+
+                    this.expression(&element.value);
+                });
+                self.write("]");
+            }
+            Expression::ArrayRepeat { value, size, .. } => {
+                self.write("[");
+                self.expression(value);
+                self.write("; ");
+                self.expression(size);
+                self.write("]");
+            }
+            Expression::Borrow {
+                is_mutable, inner, ..
+            } => {
+                self.write(if *is_mutable { "&mut " } else { "&" });
+                self.prefix_operand(inner);
+            }
+            Expression::Map { entries, .. } => {
+                if entries.is_empty() {
+                    // an empty map, not a format string
+                    #[allow(clippy::literal_string_with_formatting_args)]
+                    self.write("{:}");
+                    return;
+                }
+
+                self.write("{");
+                self.comma_separated(entries, |this, entry| {
+                    this.expression(&entry.key);
+                    this.write(": ");
+                    this.expression(&entry.value);
+                });
+                self.write("}");
+            }
+            Expression::As { left, right, .. } => {
+                self.left_operand(left, Precedence::As);
+                self.write(" as ");
+                self.ty(right);
+            }
+            Expression::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } => {
+                let (precedence, associativity) =
+                    Operator::find(Fixity::Infix, operator.raw.into())
+                        .map_or((Precedence::Lowest, Associativity::Left), |operator| {
+                            (operator.precedence, operator.associativity)
+                        });
+
+                // operands of the same precedence are parenthesized on the
+                // side, which the operator doesn't group them to
+                if associativity == Associativity::Left {
+                    self.left_operand(left, precedence);
+                } else {
+                    self.right_operand(left, precedence);
+                }
+
+                self.write(" ");
+                self.write(&operator.raw.to_string());
+                self.write(" ");
+
+                if associativity == Associativity::Left {
+                    self.right_operand(right, precedence);
+                } else {
+                    self.left_operand(right, precedence);
+                }
+            }
+            Expression::Range {
+                start, end, kind, ..
+            } => {
+                if let Some(start) = start {
+                    self.left_operand(start, Precedence::Range);
+                }
+
+                self.write(match kind {
+                    RangeKind::Exclusive => "..",
+                    RangeKind::Inclusive => "..=",
+                });
+
+                if let Some(end) = end {
+                    self.right_operand(end, Precedence::Range);
+                }
+            }
+            Expression::StatementsBlock { block, .. } => self.statements_block(block),
+            Expression::Await { inner, .. } => {
+                self.postfix_operand(inner);
+                self.write(".await");
+            }
+            Expression::Unsafe { block, .. } => {
+                self.write("unsafe ");
+                self.statements_block(block);
+            }
+            Expression::Try { block, .. } => {
+                self.write("try ");
+                self.statements_block(block);
+            }
+            Expression::If {
+                if_blocks, r#else, ..
+            } => {
+                for (idx, (condition, block)) in if_blocks.iter().enumerate() {
+                    if idx != 0 {
+                        self.write(" else ");
+                    }
+
+                    self.write("if ");
+                    self.condition(condition);
+                    self.write(" ");
+                    self.statements_block(block);
+                }
+
+                if let Some(r#else) = r#else {
+                    self.write(" else ");
+                    self.statements_block(r#else);
+                }
+            }
+            Expression::FieldAccess { left, right, .. } => {
+                self.postfix_operand(left);
+                self.write(".");
+                self.identifier(right.id);
+            }
+            Expression::TupleIndex { left, index, .. } => {
+                self.postfix_operand(left);
+                self.write(".");
+                self.write(&index.to_string());
+            }
+            Expression::Prefix {
+                inner, operator, ..
+            } => {
+                self.write(&operator.raw.to_string());
+
+                // `- -a` must not become `--a`
+                if let Expression::Prefix {
+                    operator: inner_operator,
+                    ..
+                } = inner.as_ref()
+                {
+                    if is_sign(operator.raw) && is_sign(inner_operator.raw) {
+                        self.write(" ");
+                    }
+                }
+
+                self.prefix_operand(inner);
+            }
+            Expression::Postfix {
+                inner, operator, ..
+            } => {
+                self.left_operand(inner, Precedence::Unastellar);
+                self.write(&operator.raw.to_string());
+            }
+            Expression::While {
+                condition,
+                statements_block,
+                ..
+            } => {
+                self.write("while ");
+                self.condition(condition);
+                self.write(" ");
+                self.statements_block(statements_block);
+            }
+            Expression::Call {
+                callee, arguments, ..
+            } => {
+                self.postfix_operand(callee);
+                self.write("(");
+                self.comma_separated(arguments, |this, argument| {
+                    if let Some(name) = argument.name {
+                        this.identifier(name.id);
+                        this.write(": ");
+                    }
+
+                    if argument.kind == ElementKind::Spread {
+                        this.write("..");
+                    }
+
+                    this.expression(&argument.value);
+                });
+                self.write(")");
+            }
+            Expression::TypeArguments {
+                left,
+                type_arguments,
+                ..
+            } => {
+                self.postfix_operand(left);
+                self.write("[");
+                self.comma_separated(type_arguments, Self::ty);
+                self.write("]");
+            }
+            Expression::Tuple { elements, .. } => {
+                self.write("(");
+                self.comma_separated(elements, Self::expression);
+
+                if elements.len() == 1 {
+                    self.write(",");
+                }
+
+                self.write(")");
+            }
+            Expression::Struct {
+                left, fields, rest, ..
+            } => {
+                self.postfix_operand(left);
+
+                if fields.is_empty() && rest.is_none() {
+                    self.write(" {}");
+                    return;
+                }
+
+                // shorthand fields are written in the desugared form
+                self.write(" { ");
+                self.comma_separated(fields, |this, field| {
+                    this.identifier(field.name.id);
+                    this.write(": ");
+                    this.expression(&field.value);
+                });
+
+                if let Some(rest) = rest {
+                    if !fields.is_empty() {
+                        self.write(", ");
+                    }
+
+                    self.write("..");
+                    self.expression(rest);
+                }
+
+                self.write(" }");
+            }
+            Expression::Match {
+                expression, block, ..
+            } => {
+                self.write("match ");
+                self.condition(expression);
+                self.write(" ");
+                self.indented_block(block.is_empty(), |this| {
+                    for item in block {
+                        this.newline();
+                        this.pattern(&item.left);
+                        this.write(" -> ");
+                        this.expression(&item.right);
+                        this.write(",");
+                    }
+                });
+            }
+            Expression::Lambda {
+                captures,
+                parameters,
+                return_type,
+                value,
+                ..
+            } => {
+                if captures.is_empty() && parameters.is_empty() {
+                    self.write("||");
+                } else {
+                    self.write("|");
+
+                    if !captures.is_empty() {
+                        self.write("[");
+                        self.comma_separated(captures, |this, capture| {
+                            this.write(match capture.kind {
+                                CaptureKind::Move => "move ",
+                                CaptureKind::Reference => "&",
+                                CaptureKind::MutableReference => "&mut ",
+                            });
+                            this.identifier(capture.name.id);
+                        });
+                        self.write("] ");
+                    }
+
+                    self.comma_separated(parameters, |this, parameter| {
+                        this.identifier(parameter.name.id);
+
+                        if let Some(ty) = &parameter.ty {
+                            this.write(": ");
+                            this.ty(ty);
+                        }
+                    });
+                    self.write("|");
+                }
+
+                if let Some(return_type) = return_type {
+                    self.write(": ");
+                    self.ty(return_type);
+                }
+
+                self.write(" ");
+                self.expression(value);
+            }
+        }
+    }
+
+    /// Writes a condition of `if`, `while` or `match`, where struct
+    /// expressions must be parenthesized.
+    fn condition(&mut self, expression: &Expression) {
+        if matches!(expression, Expression::Struct { .. }) {
+            self.parenthesized(expression);
+        } else {
+            self.expression(expression);
+        }
+    }
+
+    fn parenthesized(&mut self, expression: &Expression) {
+        self.write("(");
+        self.expression(expression);
+        self.write(")");
+    }
+
+    /// Writes the left operand of a binary, cast, range or postfix
+    /// expression, that binds at least as tightly as `precedence`.
+    fn left_operand(&mut self, expression: &Expression, precedence: Precedence) {
+        if expression_precedence(expression) < precedence || expression.with_block() {
+            self.parenthesized(expression);
+        } else {
+            self.expression(expression);
+        }
+    }
+
+    /// Writes the right operand of a binary or range expression, that binds
+    /// tighter than `precedence`, since the operators are left-associative.
+    fn right_operand(&mut self, expression: &Expression, precedence: Precedence) {
+        if expression_precedence(expression) <= precedence {
+            self.parenthesized(expression);
+        } else {
+            self.expression(expression);
+        }
+    }
+
+    /// Writes an operand of a prefix operator or a borrow.
+    fn prefix_operand(&mut self, expression: &Expression) {
+        if expression_precedence(expression) > Precedence::Unastellar
+            || matches!(
+                expression,
+                Expression::Prefix { .. } | Expression::Borrow { .. }
+            )
+        {
+            self.expression(expression);
+        } else {
+            self.parenthesized(expression);
+        }
+    }
+
+    /// Writes the left side of a call, a field access, a tuple index, type
+    /// arguments or a struct expression.
+    fn postfix_operand(&mut self, expression: &Expression) {
+        // `1.foo` is lexed as a float literal followed by an identifier
+        if matches!(
+            expression,
+            Expression::Literal {
+                literal: Literal::Integer { .. } | Literal::Float { .. },
+                ..
+            }
+        ) {
+            self.parenthesized(expression);
+        } else {
+            self.left_operand(expression, Precedence::GenericArgument);
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal) {
+        self.write(&unparse_literal(literal));
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Literal(literal) => self.literal(literal),
+            Pattern::NegativeNumericLiteral(literal) => {
+                self.write("-");
+
+                match literal {
+                    NegativeNumericLiteral::Integer { value, .. } => {
+                        self.write(&value.to_string());
+                    }
+                    NegativeNumericLiteral::Float { value, location } => {
+                        self.literal(&Literal::Float {
+                            value: *value,
+                            location: *location,
+                        });
+                    }
+                }
+            }
+            Pattern::Identifier {
+                binding_mode,
+                identifier,
+                pattern,
+                ..
+            } => {
+                self.write(match binding_mode {
+                    BindingMode::Value => "",
+                    BindingMode::MutableValue => "mut ",
+                    BindingMode::Reference => "ref ",
+                    BindingMode::MutableReference => "ref mut ",
+                });
+                self.identifier(identifier.id);
+
+                if let Some(pattern) = pattern {
+                    self.write(" @ ");
+                    self.pattern(pattern);
+                }
+            }
+            Pattern::Wildcard { .. } => self.write("_"),
+            Pattern::Struct { path, fields, .. } => {
+                self.path(path);
+
+                if fields.is_empty() {
+                    self.write(" {}");
+                    return;
+                }
+
+                self.write(" { ");
+                self.comma_separated(fields, |this, field| match field {
+                    StructFieldPattern::NotRest {
+                        field_name,
+                        value_pattern,
+                        ..
+                    } => {
+                        this.identifier(field_name.id);
+
+                        if let Some(value_pattern) = value_pattern {
+                            this.write(": ");
+                            this.pattern(value_pattern);
+                        }
+                    }
+                    StructFieldPattern::Rest { .. } => this.write(".."),
+                });
+                self.write(" }");
+            }
+            Pattern::TupleLike {
+                path,
+                inner_patterns,
+                ..
+            } => {
+                self.path(path);
+                self.write("(");
+                self.comma_separated(inner_patterns, Self::pattern);
+                self.write(")");
+            }
+            Pattern::Tuple { elements, .. } => {
+                self.write("(");
+                self.comma_separated(elements, Self::pattern);
+
+                if elements.len() == 1 {
+                    self.write(",");
+                }
+
+                self.write(")");
+            }
+            Pattern::Path { path } => self.path(path),
+            Pattern::List { inner_patterns, .. } => {
+                self.write("[");
+                self.comma_separated(inner_patterns, Self::pattern);
+                self.write("]");
+            }
+            Pattern::Or { alternatives, .. } => {
+                for (idx, alternative) in alternatives.iter().enumerate() {
+                    if idx != 0 {
+                        self.write(" | ");
+                    }
+
+                    // `a @ B | C` means `a @ (B | C)`
+                    if matches!(
+                        alternative,
+                        Pattern::Identifier {
+                            pattern: Some(..),
+                            ..
+                        }
+                    ) {
+                        self.write("(");
+                        self.pattern(alternative);
+                        self.write(")");
+                    } else {
+                        self.pattern(alternative);
+                    }
+                }
+            }
+            Pattern::Range {
+                start, end, kind, ..
+            } => {
+                self.pattern(start);
+                self.write(match kind {
+                    RangeKind::Exclusive => "..",
+                    RangeKind::Inclusive => "..=",
+                });
+                self.pattern(end);
+            }
+            Pattern::Rest { .. } => self.write(".."),
+        }
+    }
+
+    fn ty(&mut self, ty: &Type) {
+        match ty {
+            Type::Constructor(constructor) => self.type_constructor(constructor),
+            Type::Tuple { element_types, .. } => {
+                self.write("(");
+                self.comma_separated(element_types, Self::ty);
+
+                if element_types.len() == 1 {
+                    self.write(",");
+                }
+
+                self.write(")");
+            }
+            Type::Function {
+                parameter_types,
+                return_type,
+                ..
+            } => {
+                self.write("fun(");
+                self.comma_separated(parameter_types, Self::ty);
+                self.write(")");
+
+                if let Some(return_type) = return_type {
+                    self.write(": ");
+                    self.ty(return_type);
+                }
+            }
+            Type::Underscore { .. } => self.write("_"),
+            Type::InterfaceObject { bounds, .. } => {
+                self.write("dyn ");
+                self.bounds(bounds);
+            }
+            Type::Const { location, value } => match value {
+                ConstValue::Boolean { value } => self.write(if *value { "true" } else { "false" }),
+                ConstValue::Character { value } => self.literal(&Literal::Character {
+                    value: *value,
+                    location: *location,
+                }),
+                ConstValue::Integer { value } => self.write(&value.to_string()),
+            },
+            Type::Array {
+                element_type, size, ..
+            } => {
+                self.write("[");
+                self.ty(element_type);
+                self.write("; ");
+                self.ty(size);
+                self.write("]");
+            }
+            Type::Reference {
+                is_mutable, inner, ..
+            } => {
+                self.write(if *is_mutable { "&mut " } else { "&" });
+                self.ty(inner);
+            }
+        }
+    }
+
+    fn type_constructor(&mut self, constructor: &TypeConstructor) {
+        self.path(&constructor.path);
+
+        if !constructor.arguments.is_empty() {
+            self.write("[");
+            self.comma_separated(&constructor.arguments, Self::ty);
+            self.write("]");
+        }
+    }
+}
+
+/// Returns how tightly an expression binds to its operands. Primary
+/// expressions, calls and field accesses bind the most tightly.
+fn expression_precedence(expression: &Expression) -> Precedence {
+    match expression {
+        Expression::Binary { operator, .. } => RawToken::from(operator.raw).into(),
+        Expression::As { .. } => Precedence::As,
+        Expression::Range { .. } => Precedence::Range,
+        Expression::Prefix { .. } | Expression::Postfix { .. } | Expression::Borrow { .. } => {
+            Precedence::Unastellar
+        }
+        Expression::Lambda { .. } => Precedence::Lowest,
+        _ => Precedence::GenericArgument,
+    }
+}
+
+/// Returns `true` for prefix operators, that can't be directly followed by
+/// another one without a space.
+const fn is_sign(operator: RawPrefixOperator) -> bool {
+    matches!(
+        operator,
+        RawPrefixOperator::Plus
+            | RawPrefixOperator::Minus
+            | RawPrefixOperator::DoublePlus
+            | RawPrefixOperator::DoubleMinus
+    )
+}
@@ -0,0 +1,1149 @@
+//! Provides a [`Visitor`] trait to traverse HIR.
+//!
+//! In the [`Visitor`] trait, every method starts with the `visit_` and then
+//! the HIR node name. The trait mirrors [`stellar_ast::visit::Visitor`], except
+//! that nodes, which are desugared during lowering, e.g. `for` loops and
+//! parenthesized expressions, don't have methods.
+//!
+//! ```
+//! use stellar_hir::{Expression, visit::Visitor};
+//!
+//! pub struct AllExpressionsPrinter;
+//!
+//! impl Visitor for AllExpressionsPrinter {
+//!     fn visit_expression(&mut self, expression: &Expression) {
+//!         println!("expression found: {:?}", expression);
+//!     }
+//! }
+//! ```
+//!
+//! Overriding a method replaces the default traversal of the node's children.
+//! To keep it, call the corresponding `walk_` function, e.g. [`walk_expression()`]
+//! after handling the node:
+//!
+//! ```
+//! use stellar_filesystem::location::DUMMY_LOCATION;
+//! use stellar_hir::{
+//!     visit::{walk_expression, Visitor},
+//!     Expression, Literal, DUMMY_HIR_ID,
+//! };
+//!
+//! pub struct AllExpressionsCounter(usize);
+//!
+//! impl Visitor for AllExpressionsCounter {
+//!     fn visit_expression(&mut self, expression: &Expression) {
+//!         self.0 += 1;
+//!         walk_expression(self, expression);
+//!     }
+//! }
+//!
+//! let literal = || Expression::Literal {
+//!     hir_id: DUMMY_HIR_ID,
+//!     literal: Literal::Boolean {
+//!         location: DUMMY_LOCATION,
+//!         value: true,
+//!     },
+//! };
+//! let tuple = Expression::Tuple {
+//!     hir_id: DUMMY_HIR_ID,
+//!     location: DUMMY_LOCATION,
+//!     elements: vec![literal(), literal()],
+//! };
+//!
+//! let mut counter = AllExpressionsCounter(0);
+//! counter.visit_expression(&tuple);
+//!
+//! assert_eq!(counter.0, 3);
+//! ```
+
+use stellar_ast::{
+    BinaryOperator, NegativeNumericLiteral, PostfixOperator, PrefixOperator, RangeKind,
+};
+use stellar_filesystem::location::Location;
+
+use crate::{
+    BindingMode, CallArgument, ConstValue, Constant, Enum, EnumItem, Expression, ExternBlock,
+    Function, FunctionParameter, FunctionSignature, GenericParameter, IdentifierAST, Impl,
+    ImportPath, Interface, LambdaCapture, LambdaFunctionParameter, ListElement, Literal, MapEntry,
+    MatchExpressionItem, Module, ModuleItem, Path, Pattern, Statement, Static, Struct,
+    StructExpressionItem, StructField, StructFieldPattern, TupleField, TupleLikeStruct, Type,
+    TypeAlias, TypeConstructor, WherePredicate,
+};
+
+/// Allows to traverse HIR.
+///
+/// See [module level docs](crate::visit) for more details.
+#[allow(unused_variables)]
+pub trait Visitor {
+    /// Visits a module.
+    fn visit_module(&mut self, module: &Module) {
+        for item in &module.items {
+            self.visit_module_item(item);
+        }
+    }
+
+    /// Visits a module item.
+    fn visit_module_item(&mut self, module_item: &ModuleItem) {
+        walk_module_item(self, module_item);
+    }
+
+    /// Visits an import.
+    fn visit_import(&mut self, location: Location, path: &ImportPath) {
+        self.visit_import_path(path);
+    }
+
+    /// Visits an import path.
+    fn visit_import_path(&mut self, path: &ImportPath) {}
+
+    /// Visits a constant.
+    fn visit_constant(&mut self, constant: &Constant) {
+        self.visit_type(&constant.ty);
+
+        if let Some(value) = &constant.value {
+            self.visit_expression(value);
+        }
+    }
+
+    /// Visits constants.
+    fn visit_constants(&mut self, constants: &[Constant]) {
+        for constant in constants {
+            self.visit_constant(constant);
+        }
+    }
+
+    /// Visits a static module item.
+    fn visit_static(&mut self, static_: &Static) {
+        self.visit_type(&static_.ty);
+        self.visit_expression(&static_.value);
+    }
+
+    /// Visits an enum module item.
+    fn visit_enum(&mut self, enum_: &Enum) {
+        self.visit_generic_parameters(&enum_.generic_parameters);
+        self.visit_where_predicates(&enum_.where_predicates);
+
+        for item in &enum_.items {
+            self.visit_enum_item(item);
+        }
+
+        self.visit_methods(&enum_.methods);
+        self.visit_implements(enum_.implements.as_deref());
+    }
+
+    /// Visits an enum item.
+    fn visit_enum_item(&mut self, item: &EnumItem) {
+        match item {
+            EnumItem::Just { .. } => {}
+            EnumItem::TupleLike { fields, .. } => self.visit_tuple_fields(fields),
+            EnumItem::Struct { fields, .. } => self.visit_struct_fields(fields),
+        }
+    }
+
+    /// Visits an interface module item.
+    fn visit_interface(&mut self, interface: &Interface) {
+        self.visit_generic_parameters(&interface.generic_parameters);
+        self.visit_where_predicates(&interface.where_predicates);
+        self.visit_constants(&interface.constants);
+        self.visit_methods(&interface.methods);
+    }
+
+    /// Visits a struct module item.
+    fn visit_struct(&mut self, struct_: &Struct) {
+        self.visit_generic_parameters(&struct_.generic_parameters);
+        self.visit_where_predicates(&struct_.where_predicates);
+        self.visit_struct_fields(&struct_.fields);
+        self.visit_methods(&struct_.methods);
+        self.visit_implements(struct_.implements.as_deref());
+    }
+
+    /// Visits an implementation block.
+    fn visit_impl(&mut self, impl_: &Impl) {
+        self.visit_generic_parameters(&impl_.generic_parameters);
+
+        if let Some(interface) = &impl_.interface {
+            self.visit_type_constructor(interface);
+        }
+
+        self.visit_type(&impl_.ty);
+        self.visit_where_predicates(&impl_.where_predicates);
+        self.visit_methods(&impl_.methods);
+    }
+
+    /// Visits an extern block.
+    fn visit_extern_block(&mut self, block: &ExternBlock) {
+        for function in &block.functions {
+            self.visit_function_signature(function);
+        }
+    }
+
+    /// Visits a tuple-like struct module item.
+    fn visit_tuple_like_struct(&mut self, tl_struct: &TupleLikeStruct) {
+        self.visit_generic_parameters(&tl_struct.generic_parameters);
+        self.visit_where_predicates(&tl_struct.where_predicates);
+        self.visit_tuple_fields(&tl_struct.fields);
+        self.visit_methods(&tl_struct.methods);
+        self.visit_implements(tl_struct.implements.as_deref());
+    }
+
+    /// Visits a type alias module item.
+    fn visit_type_alias(&mut self, alias: &TypeAlias) {
+        self.visit_generic_parameters(&alias.generic_parameters);
+        self.visit_type(&alias.value);
+    }
+
+    /// Visits tuple fields.
+    fn visit_tuple_fields(&mut self, fields: &[TupleField]) {
+        for field in fields {
+            self.visit_tuple_field(field);
+        }
+    }
+
+    /// Visits a tuple field.
+    fn visit_tuple_field(&mut self, field: &TupleField) {
+        self.visit_type(&field.ty);
+    }
+
+    /// Visits struct fields.
+    fn visit_struct_fields(&mut self, fields: &[StructField]) {
+        for field in fields {
+            self.visit_struct_field(field);
+        }
+    }
+
+    /// Visits a struct field.
+    fn visit_struct_field(&mut self, field: &StructField) {
+        self.visit_type(&field.ty);
+    }
+
+    /// Visits generic parameters.
+    fn visit_generic_parameters(&mut self, generic_parameters: &[GenericParameter]) {
+        for generic_parameter in generic_parameters {
+            self.visit_generic_parameter(generic_parameter);
+        }
+    }
+
+    /// Visits a generic parameter.
+    fn visit_generic_parameter(&mut self, generic_parameter: &GenericParameter) {
+        if let Some(const_type) = &generic_parameter.const_type {
+            self.visit_type(const_type);
+        }
+
+        if let Some(default_value) = &generic_parameter.default_value {
+            self.visit_type(default_value);
+        }
+
+        if let Some(bounds) = &generic_parameter.bounds {
+            self.visit_bounds(bounds);
+        }
+    }
+
+    /// Visits where predicates.
+    fn visit_where_predicates(&mut self, predicates: &[WherePredicate]) {
+        for predicate in predicates {
+            self.visit_where_predicate(predicate);
+        }
+    }
+
+    /// Visits a where predicate.
+    fn visit_where_predicate(&mut self, predicate: &WherePredicate) {
+        self.visit_type(&predicate.ty);
+        self.visit_bounds(&predicate.bounds);
+    }
+
+    /// Visits a function.
+    fn visit_function(&mut self, function: &Function) {
+        self.visit_function_signature(&function.signature);
+
+        if let Some(body) = &function.body {
+            self.visit_statements_block(body);
+        }
+    }
+
+    /// Visits a function signature.
+    fn visit_function_signature(&mut self, signature: &FunctionSignature) {
+        self.visit_generic_parameters(&signature.generic_parameters);
+
+        for parameter in &signature.parameters {
+            match parameter {
+                FunctionParameter::NotSelfParameter(parameter) => {
+                    self.visit_pattern(&parameter.pattern);
+                    self.visit_type(&parameter.ty);
+                }
+                FunctionParameter::SelfParameter(parameter) => {
+                    if let Some(ty) = &parameter.ty {
+                        self.visit_type(ty);
+                    }
+                }
+            }
+        }
+
+        if let Some(return_type) = &signature.return_type {
+            self.visit_type(return_type);
+        }
+
+        self.visit_where_predicates(&signature.where_predicates);
+    }
+
+    /// Visits a method.
+    fn visit_method(&mut self, method: &Function) {
+        self.visit_function(method);
+    }
+
+    /// Visits methods.
+    fn visit_methods(&mut self, methods: &[Function]) {
+        for method in methods {
+            self.visit_method(method);
+        }
+    }
+
+    /// Visits interfaces, that a particular type implements.
+    fn visit_implements(&mut self, implements: Option<&[TypeConstructor]>) {
+        if let Some(implements) = implements {
+            for interface in implements {
+                self.visit_type_constructor(interface);
+            }
+        }
+    }
+
+    /// Visits a statements block.
+    fn visit_statements_block(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            self.visit_statement(statement);
+        }
+    }
+
+    /// Visits a statement.
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement);
+    }
+
+    /// Visits a break statement.
+    fn visit_break_statement(&mut self, location: Location) {}
+
+    /// Visits a continue statement.
+    fn visit_continue_statement(&mut self, location: Location) {}
+
+    /// Visits a defer statement.
+    fn visit_defer_statement(&mut self, location: Location, block: &[Statement]) {
+        self.visit_statements_block(block);
+    }
+
+    /// Visits an expression statement.
+    fn visit_expression_statement(&mut self, expression: &Expression, has_semicolon: bool) {
+        self.visit_expression(expression);
+    }
+
+    /// Visits a let statement.
+    fn visit_let_statement(&mut self, pattern: &Pattern, value: &Expression, ty: Option<&Type>) {
+        self.visit_pattern(pattern);
+        self.visit_expression(value);
+
+        if let Some(ty) = ty {
+            self.visit_type(ty);
+        }
+    }
+
+    /// Visits a return statement.
+    fn visit_return_statement(&mut self, expression: &Expression) {
+        self.visit_expression(expression);
+    }
+
+    /// Visits a yield statement.
+    fn visit_yield_statement(&mut self, location: Location, expression: &Expression) {
+        self.visit_expression(expression);
+    }
+
+    /// Visits a pattern.
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        walk_pattern(self, pattern);
+    }
+
+    /// Visits an identifier pattern.
+    fn visit_identifier_pattern(
+        &mut self,
+        location: Location,
+        binding_mode: BindingMode,
+        identifier: IdentifierAST,
+        pattern: Option<&Pattern>,
+    ) {
+        if let Some(pattern) = pattern {
+            self.visit_pattern(pattern);
+        }
+    }
+
+    /// Visits a list pattern.
+    fn visit_list_pattern(&mut self, location: Location, inner_patterns: &[Pattern]) {
+        for pattern in inner_patterns {
+            self.visit_pattern(pattern);
+        }
+    }
+
+    /// Visits a literal pattern.
+    fn visit_literal_pattern(&mut self, literal: &Literal) {}
+
+    /// Visits a negative numeric literal pattern.
+    fn visit_negative_numeric_literal_pattern(&mut self, literal: &NegativeNumericLiteral) {}
+
+    /// Visits an or pattern.
+    fn visit_or_pattern(&mut self, location: Location, alternatives: &[Pattern]) {
+        for pattern in alternatives {
+            self.visit_pattern(pattern);
+        }
+    }
+
+    /// Visits a path pattern.
+    fn visit_path_pattern(&mut self, path: &Path) {}
+
+    /// Visits a range pattern.
+    fn visit_range_pattern(
+        &mut self,
+        location: Location,
+        start: &Pattern,
+        end: &Pattern,
+        kind: RangeKind,
+    ) {
+        self.visit_pattern(start);
+        self.visit_pattern(end);
+    }
+
+    /// Visits a rest pattern.
+    fn visit_rest_pattern(&mut self, location: Location) {}
+
+    /// Visits a struct pattern.
+    fn visit_struct_pattern(
+        &mut self,
+        location: Location,
+        path: &Path,
+        field_patterns: &[StructFieldPattern],
+    ) {
+        for field_pattern in field_patterns {
+            self.visit_struct_field_pattern(field_pattern);
+        }
+    }
+
+    /// Visits a struct field pattern.
+    fn visit_struct_field_pattern(&mut self, field: &StructFieldPattern) {
+        if let StructFieldPattern::NotRest {
+            value_pattern: Some(value_pattern),
+            ..
+        } = field
+        {
+            self.visit_pattern(value_pattern);
+        }
+    }
+
+    /// Visits a tuple pattern.
+    fn visit_tuple_pattern(&mut self, location: Location, elements: &[Pattern]) {
+        for element in elements {
+            self.visit_pattern(element);
+        }
+    }
+
+    /// Visits a tuple-like pattern.
+    fn visit_tuple_like_pattern(
+        &mut self,
+        location: Location,
+        path: &Path,
+        inner_patterns: &[Pattern],
+    ) {
+        for pattern in inner_patterns {
+            self.visit_pattern(pattern);
+        }
+    }
+
+    /// Visits a wildcard pattern.
+    fn visit_wildcard_pattern(&mut self, location: Location) {}
+
+    /// Visits a type.
+    fn visit_type(&mut self, ty: &Type) {
+        walk_type(self, ty);
+    }
+
+    /// Visits arguments in a type constructor.
+    fn visit_type_arguments(&mut self, arguments: &[Type]) {
+        for argument in arguments {
+            self.visit_type(argument);
+        }
+    }
+
+    /// Visits a type constructor.
+    fn visit_type_constructor(&mut self, constructor: &TypeConstructor) {
+        walk_type_constructor(self, constructor);
+    }
+
+    /// Visits a function type.
+    fn visit_function_type(
+        &mut self,
+        location: Location,
+        parameter_types: &[Type],
+        return_type: Option<&Type>,
+    ) {
+        for parameter_type in parameter_types {
+            self.visit_type(parameter_type);
+        }
+
+        if let Some(return_type) = return_type {
+            self.visit_type(return_type);
+        }
+    }
+
+    /// Visits an interface object type.
+    fn visit_interface_object_type(&mut self, location: Location, bounds: &[TypeConstructor]) {
+        self.visit_bounds(bounds);
+    }
+
+    /// Visits type bounds.
+    fn visit_bounds(&mut self, bounds: &[TypeConstructor]) {
+        for bound in bounds {
+            self.visit_type_constructor(bound);
+        }
+    }
+
+    /// Visits a tuple type.
+    fn visit_tuple_type(&mut self, location: Location, element_types: &[Type]) {
+        for element_type in element_types {
+            self.visit_type(element_type);
+        }
+    }
+
+    /// Visit an underscore type.
+    fn visit_underscore_type(&mut self, location: Location) {}
+
+    /// Visits a const generic argument.
+    fn visit_const_argument(&mut self, location: Location, value: ConstValue) {}
+
+    /// Visits a reference type.
+    fn visit_reference_type(&mut self, location: Location, is_mutable: bool, inner: &Type) {
+        self.visit_type(inner);
+    }
+
+    /// Visits an array type.
+    fn visit_array_type(&mut self, location: Location, element_type: &Type, size: &Type) {
+        self.visit_type(element_type);
+        self.visit_type(size);
+    }
+
+    /// Visits an expression.
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression);
+    }
+
+    /// Visits an as expression.
+    fn visit_as_expression(&mut self, location: Location, left: &Expression, right: &Type) {
+        self.visit_expression(left);
+        self.visit_type(right);
+    }
+
+    /// Visits a binary expression.
+    fn visit_binary_expression(
+        &mut self,
+        location: Location,
+        left: &Expression,
+        operator: BinaryOperator,
+        right: &Expression,
+        is_compound_assignment: bool,
+    ) {
+        self.visit_expression(left);
+        self.visit_expression(right);
+    }
+
+    /// Visits a call expression.
+    fn visit_call_expression(
+        &mut self,
+        location: Location,
+        callee: &Expression,
+        arguments: &[CallArgument],
+    ) {
+        self.visit_expression(callee);
+
+        for argument in arguments {
+            self.visit_call_argument(argument);
+        }
+    }
+
+    /// Visits a call argument.
+    fn visit_call_argument(&mut self, argument: &CallArgument) {
+        self.visit_expression(&argument.value);
+    }
+
+    /// Visits a field access expression.
+    fn visit_field_access_expression(
+        &mut self,
+        location: Location,
+        left: &Expression,
+        right: IdentifierAST,
+    ) {
+        self.visit_expression(left);
+    }
+
+    /// Visits a tuple index expression.
+    fn visit_tuple_index_expression(
+        &mut self,
+        location: Location,
+        left: &Expression,
+        index: usize,
+        index_location: Location,
+    ) {
+        self.visit_expression(left);
+    }
+
+    /// Visits an identifier expression.
+    fn visit_identifier_expression(&mut self, identifier: IdentifierAST) {}
+
+    /// Visits a list expression.
+    fn visit_list_expression(&mut self, location: Location, elements: &[ListElement]) {
+        for element in elements {
+            self.visit_list_element(element);
+        }
+    }
+
+    /// Visits an element of a list expression.
+    fn visit_list_element(&mut self, element: &ListElement) {
+        self.visit_expression(&element.value);
+    }
+
+    /// Visits a borrow expression.
+    fn visit_borrow_expression(
+        &mut self,
+        location: Location,
+        is_mutable: bool,
+        inner: &Expression,
+    ) {
+        self.visit_expression(inner);
+    }
+
+    /// Visits a map expression.
+    fn visit_map_expression(&mut self, location: Location, entries: &[MapEntry]) {
+        for entry in entries {
+            self.visit_map_entry(entry);
+        }
+    }
+
+    /// Visits an entry of a map expression.
+    fn visit_map_entry(&mut self, entry: &MapEntry) {
+        self.visit_expression(&entry.key);
+        self.visit_expression(&entry.value);
+    }
+
+    /// Visits an array repeat expression.
+    fn visit_array_repeat_expression(
+        &mut self,
+        location: Location,
+        value: &Expression,
+        size: &Expression,
+    ) {
+        self.visit_expression(value);
+        self.visit_expression(size);
+    }
+
+    /// Visits a literal expression.
+    fn visit_literal_expression(&mut self, literal: &Literal) {}
+
+    /// Visits an if expression.
+    fn visit_if_expression(
+        &mut self,
+        location: Location,
+        if_blocks: &[(Expression, Vec<Statement>)],
+        r#else: Option<&[Statement]>,
+    ) {
+        for (condition, block) in if_blocks {
+            self.visit_expression(condition);
+            self.visit_statements_block(block);
+        }
+
+        if let Some(r#else) = r#else {
+            self.visit_statements_block(r#else);
+        }
+    }
+
+    /// Visits a lambda expression.
+    fn visit_lambda_expression(
+        &mut self,
+        location: Location,
+        captures: &[LambdaCapture],
+        parameters: &[LambdaFunctionParameter],
+        return_type: Option<&Type>,
+        value: &Expression,
+    ) {
+        for capture in captures {
+            self.visit_lambda_capture(capture);
+        }
+
+        for parameter in parameters {
+            self.visit_lambda_function_parameter(parameter);
+        }
+
+        if let Some(return_type) = return_type {
+            self.visit_type(return_type);
+        }
+
+        self.visit_expression(value);
+    }
+
+    /// Visits an underscore expression.
+    fn visit_underscore_expression(&mut self, location: Location) {}
+
+    /// Visits an explicit capture of a lambda.
+    fn visit_lambda_capture(&mut self, capture: &LambdaCapture) {}
+
+    /// Visits a lambda function parameter.
+    fn visit_lambda_function_parameter(&mut self, parameter: &LambdaFunctionParameter) {
+        if let Some(ty) = &parameter.ty {
+            self.visit_type(ty);
+        }
+    }
+
+    /// Visits a match expression.
+    fn visit_match_expression(
+        &mut self,
+        location: Location,
+        expression: &Expression,
+        block: &[MatchExpressionItem],
+    ) {
+        self.visit_expression(expression);
+
+        for item in block {
+            self.visit_match_expression_item(item);
+        }
+    }
+
+    /// Visits a match expression item.
+    fn visit_match_expression_item(&mut self, item: &MatchExpressionItem) {
+        self.visit_pattern(&item.left);
+        self.visit_expression(&item.right);
+    }
+
+    /// Visits a postfix expression.
+    fn visit_postfix_expression(
+        &mut self,
+        location: Location,
+        inner: &Expression,
+        operator: PostfixOperator,
+    ) {
+        self.visit_expression(inner);
+    }
+
+    /// Visits a prefix expression.
+    fn visit_prefix_expression(
+        &mut self,
+        location: Location,
+        inner: &Expression,
+        operator: PrefixOperator,
+    ) {
+        self.visit_expression(inner);
+    }
+
+    /// Visits a range expression.
+    fn visit_range_expression(
+        &mut self,
+        location: Location,
+        start: Option<&Expression>,
+        end: Option<&Expression>,
+        kind: RangeKind,
+    ) {
+        if let Some(start) = start {
+            self.visit_expression(start);
+        }
+
+        if let Some(end) = end {
+            self.visit_expression(end);
+        }
+    }
+
+    /// Visits a statements block expression.
+    fn visit_statements_block_expression(&mut self, location: Location, block: &[Statement]) {
+        self.visit_statements_block(block);
+    }
+
+    /// Visits an await expression.
+    fn visit_await_expression(&mut self, location: Location, inner: &Expression) {
+        self.visit_expression(inner);
+    }
+
+    /// Visits an unsafe block expression.
+    fn visit_unsafe_expression(&mut self, location: Location, block: &[Statement]) {
+        self.visit_statements_block(block);
+    }
+
+    /// Visits a try block expression.
+    fn visit_try_expression(&mut self, location: Location, block: &[Statement]) {
+        self.visit_statements_block(block);
+    }
+
+    /// Visits a struct expression.
+    fn visit_struct_expression(
+        &mut self,
+        location: Location,
+        left: &Expression,
+        fields: &[StructExpressionItem],
+        rest: Option<&Expression>,
+    ) {
+        self.visit_expression(left);
+
+        for field in fields {
+            self.visit_struct_expression_item(field);
+        }
+
+        if let Some(rest) = rest {
+            self.visit_expression(rest);
+        }
+    }
+
+    /// Visits a field item in a struct expression.
+    fn visit_struct_expression_item(&mut self, field: &StructExpressionItem) {
+        self.visit_expression(&field.value);
+    }
+
+    /// Visits a tuple expression.
+    fn visit_tuple_expression(&mut self, location: Location, elements: &[Expression]) {
+        for element in elements {
+            self.visit_expression(element);
+        }
+    }
+
+    /// Visits a while expression.
+    fn visit_while_expression(
+        &mut self,
+        location: Location,
+        condition: &Expression,
+        statements_block: &[Statement],
+    ) {
+        self.visit_expression(condition);
+        self.visit_statements_block(statements_block);
+    }
+
+    /// Visits type arguments expression.
+    fn visit_type_arguments_expression(
+        &mut self,
+        location: Location,
+        left: &Expression,
+        arguments: &[Type],
+    ) {
+        self.visit_expression(left);
+        self.visit_type_arguments(arguments);
+    }
+}
+
+/// Walks a module item, visiting all of its children.
+pub fn walk_module_item<V: Visitor + ?Sized>(visitor: &mut V, module_item: &ModuleItem) {
+    match module_item {
+        ModuleItem::Constant(constant) => visitor.visit_constant(constant),
+        ModuleItem::Enum(enum_) => visitor.visit_enum(enum_),
+        ModuleItem::Interface(interface) => visitor.visit_interface(interface),
+        ModuleItem::Static(static_) => visitor.visit_static(static_),
+        ModuleItem::Function(function) => visitor.visit_function(function),
+        ModuleItem::Impl(impl_) => visitor.visit_impl(impl_),
+        ModuleItem::Extern(block) => visitor.visit_extern_block(block),
+        ModuleItem::Import { location, path, .. } => visitor.visit_import(*location, path),
+        ModuleItem::Struct(struct_) => visitor.visit_struct(struct_),
+        ModuleItem::TupleLikeStruct(tl_struct) => visitor.visit_tuple_like_struct(tl_struct),
+        ModuleItem::TypeAlias(alias) => visitor.visit_type_alias(alias),
+    }
+}
+
+/// Walks a statement, visiting all of its children.
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match statement {
+        Statement::Break { location, .. } => visitor.visit_break_statement(*location),
+        Statement::Continue { location, .. } => visitor.visit_continue_statement(*location),
+        Statement::Defer {
+            location, block, ..
+        } => visitor.visit_defer_statement(*location, block),
+        Statement::Expression {
+            expression,
+            has_semicolon,
+            ..
+        } => visitor.visit_expression_statement(expression, *has_semicolon),
+        Statement::Let {
+            pattern, value, ty, ..
+        } => {
+            visitor.visit_let_statement(pattern, value, ty.as_ref());
+        }
+        Statement::Return { expression, .. } => visitor.visit_return_statement(expression),
+        Statement::Yield {
+            location,
+            expression,
+            ..
+        } => visitor.visit_yield_statement(*location, expression),
+    }
+}
+
+/// Walks a pattern, visiting all of its children.
+pub fn walk_pattern<V: Visitor + ?Sized>(visitor: &mut V, pattern: &Pattern) {
+    match pattern {
+        Pattern::Identifier {
+            location,
+            binding_mode,
+            identifier,
+            pattern,
+        } => visitor.visit_identifier_pattern(
+            *location,
+            *binding_mode,
+            *identifier,
+            pattern.as_deref(),
+        ),
+        Pattern::List {
+            location,
+            inner_patterns,
+        } => {
+            visitor.visit_list_pattern(*location, inner_patterns);
+        }
+        Pattern::Literal(literal) => visitor.visit_literal_pattern(literal),
+        Pattern::NegativeNumericLiteral(literal) => {
+            visitor.visit_negative_numeric_literal_pattern(literal);
+        }
+        Pattern::Or {
+            location,
+            alternatives,
+        } => visitor.visit_or_pattern(*location, alternatives),
+        Pattern::Path { path } => visitor.visit_path_pattern(path),
+        Pattern::Range {
+            location,
+            start,
+            end,
+            kind,
+        } => visitor.visit_range_pattern(*location, start, end, *kind),
+        Pattern::Rest { location } => visitor.visit_rest_pattern(*location),
+        Pattern::Struct {
+            location,
+            path,
+            fields,
+        } => {
+            visitor.visit_struct_pattern(*location, path, fields);
+        }
+        Pattern::Tuple { location, elements } => {
+            visitor.visit_tuple_pattern(*location, elements);
+        }
+        Pattern::TupleLike {
+            location,
+            path,
+            inner_patterns,
+        } => {
+            visitor.visit_tuple_like_pattern(*location, path, inner_patterns);
+        }
+        Pattern::Wildcard { location } => visitor.visit_wildcard_pattern(*location),
+    }
+}
+
+/// Walks a type, visiting all of its children.
+pub fn walk_type<V: Visitor + ?Sized>(visitor: &mut V, ty: &Type) {
+    match ty {
+        Type::Constructor(constructor) => visitor.visit_type_constructor(constructor),
+        Type::Function {
+            location,
+            parameter_types,
+            return_type,
+        } => visitor.visit_function_type(*location, parameter_types, return_type.as_deref()),
+        Type::InterfaceObject { location, bounds } => {
+            visitor.visit_interface_object_type(*location, bounds);
+        }
+        Type::Tuple {
+            location,
+            element_types,
+        } => {
+            visitor.visit_tuple_type(*location, element_types);
+        }
+        Type::Underscore { location } => visitor.visit_underscore_type(*location),
+        Type::Const { location, value } => visitor.visit_const_argument(*location, *value),
+        Type::Array {
+            location,
+            element_type,
+            size,
+        } => visitor.visit_array_type(*location, element_type, size),
+        Type::Reference {
+            location,
+            is_mutable,
+            inner,
+        } => visitor.visit_reference_type(*location, *is_mutable, inner),
+    }
+}
+
+/// Walks a type constructor, visiting all of its children.
+pub fn walk_type_constructor<V: Visitor + ?Sized>(visitor: &mut V, constructor: &TypeConstructor) {
+    visitor.visit_type_arguments(&constructor.arguments);
+}
+
+/// Walks an expression, visiting all of its children.
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::As {
+            location,
+            left,
+            right,
+            ..
+        } => {
+            visitor.visit_as_expression(*location, left, right);
+        }
+        Expression::Binary {
+            location,
+            left,
+            operator,
+            right,
+            is_compound_assignment,
+            ..
+        } => {
+            visitor.visit_binary_expression(
+                *location,
+                left,
+                *operator,
+                right,
+                *is_compound_assignment,
+            );
+        }
+        Expression::Range {
+            location,
+            start,
+            end,
+            kind,
+            ..
+        } => {
+            visitor.visit_range_expression(*location, start.as_deref(), end.as_deref(), *kind);
+        }
+        Expression::Call {
+            location,
+            callee,
+            arguments,
+            ..
+        } => {
+            visitor.visit_call_expression(*location, callee, arguments);
+        }
+        Expression::FieldAccess {
+            location,
+            left,
+            right,
+            ..
+        } => {
+            visitor.visit_field_access_expression(*location, left, *right);
+        }
+        Expression::TupleIndex {
+            location,
+            left,
+            index,
+            index_location,
+            ..
+        } => visitor.visit_tuple_index_expression(*location, left, *index, *index_location),
+        Expression::Identifier { identifier, .. } => {
+            visitor.visit_identifier_expression(*identifier);
+        }
+        Expression::List {
+            location, elements, ..
+        } => {
+            visitor.visit_list_expression(*location, elements);
+        }
+        Expression::ArrayRepeat {
+            location,
+            value,
+            size,
+            ..
+        } => visitor.visit_array_repeat_expression(*location, value, size),
+        Expression::Map {
+            location, entries, ..
+        } => visitor.visit_map_expression(*location, entries),
+        Expression::Borrow {
+            location,
+            is_mutable,
+            inner,
+            ..
+        } => visitor.visit_borrow_expression(*location, *is_mutable, inner),
+        Expression::Literal { literal, .. } => visitor.visit_literal_expression(literal),
+        Expression::If {
+            location,
+            if_blocks,
+            r#else,
+            ..
+        } => {
+            visitor.visit_if_expression(*location, if_blocks, r#else.as_deref());
+        }
+        Expression::Lambda {
+            location,
+            captures,
+            parameters,
+            return_type,
+            value,
+            ..
+        } => {
+            visitor.visit_lambda_expression(
+                *location,
+                captures,
+                parameters,
+                return_type.as_ref(),
+                value,
+            );
+        }
+        Expression::Match {
+            location,
+            expression,
+            block,
+            ..
+        } => {
+            visitor.visit_match_expression(*location, expression, block);
+        }
+        Expression::Postfix {
+            location,
+            inner,
+            operator,
+            ..
+        } => {
+            visitor.visit_postfix_expression(*location, inner, *operator);
+        }
+        Expression::Prefix {
+            location,
+            inner,
+            operator,
+            ..
+        } => {
+            visitor.visit_prefix_expression(*location, inner, *operator);
+        }
+        Expression::StatementsBlock {
+            location, block, ..
+        } => {
+            visitor.visit_statements_block_expression(*location, block);
+        }
+        Expression::Await {
+            location, inner, ..
+        } => {
+            visitor.visit_await_expression(*location, inner);
+        }
+        Expression::Unsafe {
+            location, block, ..
+        } => {
+            visitor.visit_unsafe_expression(*location, block);
+        }
+        Expression::Try {
+            location, block, ..
+        } => {
+            visitor.visit_try_expression(*location, block);
+        }
+        Expression::Struct {
+            location,
+            left,
+            fields,
+            rest,
+            ..
+        } => {
+            visitor.visit_struct_expression(*location, left, fields, rest.as_deref());
+        }
+        Expression::Tuple {
+            location, elements, ..
+        } => {
+            visitor.visit_tuple_expression(*location, elements);
+        }
+        Expression::While {
+            location,
+            condition,
+            statements_block,
+            ..
+        } => {
+            visitor.visit_while_expression(*location, condition, statements_block);
+        }
+        Expression::TypeArguments {
+            location,
+            left,
+            type_arguments,
+            ..
+        } => {
+            visitor.visit_type_arguments_expression(*location, left, type_arguments);
+        }
+        Expression::Underscore { location, .. } => visitor.visit_underscore_expression(*location),
+    }
+}
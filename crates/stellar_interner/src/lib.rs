@@ -19,6 +19,12 @@
 //! ```
 //!
 //! See the [`Interner`] for more information.
+//!
+//! # Sessions
+//!
+//! Identifiers and paths are interned into global interners, unless other
+//! [`Interners`] are entered on the current thread. This allows independent
+//! compilations to not share (and not pollute) interned data.
 
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/quantumatic/stellar/main/additional/icon/stellar.png",
@@ -77,19 +83,24 @@
     clippy::option_if_let_else
 )]
 
+mod session;
+
 use std::{
     fmt::Display,
     hash::BuildHasherDefault,
     hash::{BuildHasher, Hash, Hasher},
     marker::PhantomData,
-    path::Path,
-    str::{from_utf8_unchecked, FromStr},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
 };
 
 #[cfg(feature = "tuples")]
 use itertools::traits::HomogeneousTuple;
 #[cfg(feature = "tuples")]
 use itertools::Itertools;
+#[cfg(feature = "schemars")]
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -98,8 +109,8 @@ extern crate alloc;
 use alloc::{string::String, vec::Vec};
 
 use hashbrown::{hash_map::RawEntryMut, HashMap};
-use lazy_static::lazy_static;
-use parking_lot::RwLock;
+use session::with_current_interners;
+pub use session::{Interners, InternersGuard};
 use stellar_fx_hash::FxHasher;
 
 /// Represents unique symbol corresponding to some interned identifier.
@@ -113,10 +124,10 @@ impl<S> From<S> for IdentifierId
 where
     S: AsRef<str>,
 {
-    /// Interns a string.
+    /// Interns a string using the current [`Interners`].
     #[inline]
     fn from(s: S) -> Self {
-        IDENTIFIER_INTERNER.write().get_or_intern(s)
+        with_current_interners(|interners| interners.identifiers().write().get_or_intern(s))
     }
 }
 
@@ -125,18 +136,20 @@ impl IdentifierId {
     ///
     /// The operation is slowish because it requires locking the
     /// identifier interner.
+    ///
+    /// The identifier is resolved using the current [`Interners`]. The
+    /// returned string is shared with them and stays valid after they are
+    /// dropped.
     #[inline]
     #[must_use]
-    pub fn as_str(self) -> &'static str {
-        let interner_rlock = IDENTIFIER_INTERNER.read();
-
-        unsafe { std::mem::transmute(interner_rlock.resolve(self)) }
+    pub fn as_str(self) -> Arc<str> {
+        with_current_interners(|interners| interners.identifiers().read().resolve(self).clone())
     }
 }
 
 impl From<IdentifierId> for String {
     fn from(value: IdentifierId) -> Self {
-        value.as_str().to_owned()
+        value.as_str().as_ref().to_owned()
     }
 }
 
@@ -176,6 +189,18 @@ impl<'de> Deserialize<'de> for IdentifierId {
     }
 }
 
+/// Identifiers are serialized as strings.
+#[cfg(feature = "schemars")]
+impl JsonSchema for IdentifierId {
+    fn schema_name() -> String {
+        "IdentifierId".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
+}
+
 impl SymbolId for IdentifierId {
     #[inline]
     fn to_storage_index(self) -> usize {
@@ -253,15 +278,19 @@ where
 }
 
 /// Storage for interned strings.
+///
+/// Interned strings are owned by the storage and freed together with the
+/// [`Interners`] they belong to. [`IdentifierId::as_str`] hands out shared
+/// references to them, so resolved strings stay valid as long as they are
+/// used.
 #[derive(Debug, Clone)]
 struct InternerStorage<S>
 where
     S: SymbolId,
 {
     marker: PhantomData<fn() -> S>,
-    ends: Vec<usize>,
     /// All interned strings live here.
-    storage: String,
+    strings: Vec<Arc<str>>,
 }
 
 impl<S> Default for InternerStorage<S>
@@ -270,8 +299,7 @@ where
 {
     fn default() -> Self {
         Self {
-            ends: Vec::new(),
-            storage: String::new(),
+            strings: Vec::new(),
             marker: PhantomData,
         }
     }
@@ -305,8 +333,7 @@ where
     #[must_use]
     fn with_capacity(capacity: usize) -> Self {
         Self {
-            ends: Vec::with_capacity(capacity),
-            storage: String::default(),
+            strings: Vec::with_capacity(capacity),
             marker: PhantomData,
         }
     }
@@ -317,66 +344,31 @@ where
     }
 
     /// Resolves the given symbol to its original string.
-    fn resolve(&self, symbol: S) -> Option<&str> {
-        self.span_of(symbol).map(|span| self.str_at(span))
+    fn resolve(&self, symbol: S) -> Option<&Arc<str>> {
+        self.strings.get(symbol.to_storage_index())
     }
 
     /// Resolves the given symbol to its original string, but without additional checks.
     unsafe fn unchecked_resolve(&self, symbol_id: S) -> &str {
-        unsafe { self.str_at(self.unchecked_span_of(symbol_id)) }
+        unsafe { self.strings.get_unchecked(symbol_id.to_storage_index()) }
     }
 
     /// Shrink capacity to fit interned symbols exactly.
     #[allow(dead_code)]
     fn shrink_to_fit(&mut self) {
-        self.ends.shrink_to_fit();
-        self.storage.shrink_to_fit();
+        self.strings.shrink_to_fit();
     }
 
     /// Returns the index of the next symbol.
     fn next_symbol(&self) -> S {
-        S::from_storage_index(self.ends.len())
-    }
-
-    /// Returns the span for the given symbol if any.
-    fn span_of(&self, symbol_id: S) -> Option<Span> {
-        self.ends
-            .get(symbol_id.to_storage_index())
-            .copied()
-            .map(|end| Span {
-                start: self
-                    .ends
-                    .get(symbol_id.to_storage_index().wrapping_sub(1))
-                    .copied()
-                    .unwrap_or(0),
-                end,
-            })
-    }
-
-    /// Returns the span for the given symbol if any, but without additional checks.
-    unsafe fn unchecked_span_of(&self, symbol_id: S) -> Span {
-        let end = unsafe { *self.ends.get_unchecked(symbol_id.to_storage_index()) };
-        let start = self
-            .ends
-            .get(symbol_id.to_storage_index().wrapping_sub(1))
-            .copied()
-            .unwrap_or(0);
-
-        Span { start, end }
+        S::from_storage_index(self.strings.len())
     }
 
-    fn str_at(&self, span: Span) -> &str {
-        unsafe { from_utf8_unchecked(&self.storage.as_bytes()[span.start..span.end]) }
-    }
-
-    /// Pushes the string into the buffer and returns corresponding symbol.
+    /// Stores a copy of the string and returns corresponding symbol.
     fn push(&mut self, string: &str) -> S {
-        self.storage.push_str(string);
-
-        let end = self.storage.as_bytes().len();
         let symbol = self.next_symbol();
 
-        self.ends.push(end);
+        self.strings.push(Arc::from(string));
 
         symbol
     }
@@ -460,19 +452,11 @@ where
 
     /// Returns the string for the given symbol if any.
     #[must_use]
-    fn resolve(&self, symbol: S) -> Option<&str> {
+    fn resolve(&self, symbol: S) -> Option<&Arc<str>> {
         self.backend.resolve(symbol)
     }
 }
 
-/// Represents a location of an interned string inside the [`Backend`]'s internal
-/// string buffer.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-struct Span {
-    start: usize,
-    end: usize,
-}
-
 /// # Identifier Interner
 ///
 /// Data structure that allows to resolve/intern identifiers. The only
@@ -486,11 +470,6 @@ struct Span {
 #[derive(Debug, Clone, Default)]
 pub struct IdentifierInterner(Interner<IdentifierId>);
 
-lazy_static! {
-    static ref IDENTIFIER_INTERNER: RwLock<IdentifierInterner> =
-        RwLock::new(IdentifierInterner::new());
-}
-
 macro_rules! define_builtin_identifiers {
     ($($id_name:ident = $value:literal => $id:literal),+) => {
         /// Defines all builtin identifiers (that are automatically interned by
@@ -527,7 +506,9 @@ define_builtin_identifiers! {
     ISIZE = 11 => "isize", USIZE = 12 => "usize",
     BOOL = 13 => "bool", STRING = 14 => "String", LIST = 15 => "List",
     CHAR = 16 => "char", SMALL_SELF = 17 => "self", BIG_SELF = 18 => "Self",
-    SIZE_OF = 19 => "sizeof", STD = 20 => "std"
+    SIZE_OF = 19 => "sizeof", STD = 20 => "std", TRAIT = 21 => "trait",
+    OPTION = 22 => "Option", SOME = 23 => "Some", ITERATOR = 24 => "iterator", NEXT = 25 => "next",
+    RESULT = 26 => "Result", OK = 27 => "Ok", ERR = 28 => "Err", NONE = 29 => "None"
 }
 
 impl IdentifierInterner {
@@ -561,12 +542,12 @@ impl IdentifierInterner {
     ///
     /// let hello_id = identifier_interner.get_or_intern("hello");
     ///
-    /// assert_eq!(identifier_interner.resolve_or_none(hello_id), Some("hello"));
-    /// assert_eq!(identifier_interner.resolve_or_none(UINT8), Some("uint8")); // interned by default
+    /// assert_eq!(identifier_interner.resolve_or_none(hello_id).as_deref(), Some("hello"));
+    /// assert_eq!(identifier_interner.resolve_or_none(UINT8).as_deref(), Some("uint8")); // interned by default
     /// assert_eq!(identifier_interner.resolve_or_none(IdentifierId(3123123123)), None);
     /// ```
     #[must_use]
-    fn resolve_or_none(&self, id: IdentifierId) -> Option<&str> {
+    fn resolve_or_none(&self, id: IdentifierId) -> Option<&Arc<str>> {
         if id == DUMMY_IDENTIFIER_ID {
             None
         } else {
@@ -579,7 +560,7 @@ impl IdentifierInterner {
     /// # Panics
     /// If the identifier is not yet interned.
     #[must_use]
-    fn resolve(&self, id: IdentifierId) -> &str {
+    fn resolve(&self, id: IdentifierId) -> &Arc<str> {
         self.resolve_or_none(id)
             .unwrap_or_else(|| panic!("Failed to resolve identifier with Id: {id:?}"))
     }
@@ -592,10 +573,6 @@ impl IdentifierInterner {
 #[derive(Debug, Clone)]
 struct PathInterner(Interner<PathId>);
 
-lazy_static! {
-    static ref PATH_INTERNER: RwLock<PathInterner> = RwLock::new(PathInterner::new());
-}
-
 /// ID of a path in the [`PathInterner`].
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct PathId(pub usize);
@@ -604,21 +581,19 @@ impl<P> From<P> for PathId
 where
     P: AsRef<Path>,
 {
-    /// Interns the given path and returns its ID.
+    /// Interns the given path using the current [`Interners`] and returns its ID.
     #[inline]
     fn from(path: P) -> Self {
-        PATH_INTERNER.write().get_or_intern(path)
+        with_current_interners(|interners| interners.paths().write().get_or_intern(path))
     }
 }
 
 impl PathId {
-    /// Resolves the given path by ID.
+    /// Resolves the given path by ID using the current [`Interners`].
     #[inline]
     #[must_use]
-    pub fn as_path(self) -> &'static Path {
-        let interner_rlock = PATH_INTERNER.read();
-
-        unsafe { std::mem::transmute(interner_rlock.resolve(self)) }
+    pub fn as_path(self) -> PathBuf {
+        with_current_interners(|interners| interners.paths().read().resolve(self).to_owned())
     }
 }
 
@@ -665,6 +640,18 @@ impl<'de> Deserialize<'de> for PathId {
     }
 }
 
+/// Paths are serialized as strings.
+#[cfg(feature = "schemars")]
+impl JsonSchema for PathId {
+    fn schema_name() -> String {
+        "PathId".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
+}
+
 impl SymbolId for PathId {
     fn to_storage_index(self) -> usize {
         self.0 - 1
@@ -701,7 +688,7 @@ impl PathInterner {
         if id == DUMMY_PATH_ID {
             None
         } else {
-            self.0.resolve(id).map(Path::new)
+            self.0.resolve(id).map(|path| Path::new(path.as_ref()))
         }
     }
 
@@ -0,0 +1,243 @@
+//! Per-session interners.
+//!
+//! By default all identifiers and paths are interned into the process-wide
+//! interners. Independent compilations (concurrent builds, tests, language server
+//! workspaces) can use their own isolated [`Interners`] instead:
+//!
+//! ```
+//! use stellar_interner::{IdentifierId, Interners};
+//!
+//! let interners = Interners::new();
+//!
+//! let id = interners.run(|| IdentifierId::from("foo"));
+//! assert_eq!(&*interners.run(|| id.as_str()), "foo");
+//! ```
+//!
+//! [`IdentifierId::from`], [`IdentifierId::as_str`], [`PathId::from`] and
+//! [`PathId::as_path`] use the interners entered on the current thread, falling
+//! back to the global ones.
+
+use std::{cell::RefCell, marker::PhantomData, path::Path, sync::Arc};
+
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+
+use crate::{IdentifierId, IdentifierInterner, PathId, PathInterner};
+
+lazy_static! {
+    static ref GLOBAL_INTERNERS: Interners = Interners::new();
+}
+
+thread_local! {
+    static CURRENT_INTERNERS: RefCell<Option<Interners>> = const { RefCell::new(None) };
+}
+
+/// Identifier and path interners of a compilation session.
+///
+/// Cloning the value is cheap and gives a handle to the same interners.
+///
+/// Interned strings are freed once the last handle is dropped. Identifiers
+/// resolved with [`IdentifierId::as_str`] share the storage of the interners
+/// and stay valid after that.
+#[derive(Debug, Clone)]
+pub struct Interners {
+    identifiers: Arc<RwLock<IdentifierInterner>>,
+    paths: Arc<RwLock<PathInterner>>,
+}
+
+impl Default for Interners {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interners {
+    /// Creates new isolated interners, containing only builtin identifiers.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            identifiers: Arc::new(RwLock::new(IdentifierInterner::new())),
+            paths: Arc::new(RwLock::new(PathInterner::new())),
+        }
+    }
+
+    /// Returns the process-wide interners.
+    #[inline]
+    #[must_use]
+    pub fn global() -> Self {
+        GLOBAL_INTERNERS.clone()
+    }
+
+    /// Returns the interners entered on the current thread, or the global ones.
+    #[inline]
+    #[must_use]
+    pub fn current() -> Self {
+        with_current_interners(Self::clone)
+    }
+
+    /// Returns `true` if both handles point to the same interners.
+    #[inline]
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.identifiers, &other.identifiers)
+    }
+
+    /// Makes the interners current on this thread until the returned guard is dropped.
+    #[inline]
+    #[must_use = "the interners are only entered while the guard is alive"]
+    pub fn enter(&self) -> InternersGuard {
+        let previous = CURRENT_INTERNERS.with(|current| current.replace(Some(self.clone())));
+
+        InternersGuard {
+            previous,
+            marker: PhantomData,
+        }
+    }
+
+    /// Runs a closure with the interners being current on this thread.
+    #[inline]
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = self.enter();
+
+        f()
+    }
+
+    /// Interns an identifier.
+    #[inline]
+    #[must_use]
+    pub fn intern_identifier(&self, identifier: impl AsRef<str>) -> IdentifierId {
+        self.identifiers.write().get_or_intern(identifier)
+    }
+
+    /// Resolves an identifier by its ID.
+    #[inline]
+    #[must_use]
+    pub fn resolve_identifier(&self, id: IdentifierId) -> Option<String> {
+        self.identifiers
+            .read()
+            .resolve_or_none(id)
+            .map(ToString::to_string)
+    }
+
+    /// Interns a path.
+    #[inline]
+    #[must_use]
+    pub fn intern_path(&self, path: impl AsRef<Path>) -> PathId {
+        self.paths.write().get_or_intern(path)
+    }
+
+    pub(crate) fn identifiers(&self) -> &RwLock<IdentifierInterner> {
+        &self.identifiers
+    }
+
+    pub(crate) fn paths(&self) -> &RwLock<PathInterner> {
+        &self.paths
+    }
+}
+
+/// Restores previously entered interners when dropped.
+///
+/// See [`Interners::enter`].
+#[derive(Debug)]
+pub struct InternersGuard {
+    previous: Option<Interners>,
+
+    // the guard must be dropped on the thread it was created on
+    marker: PhantomData<*const ()>,
+}
+
+impl Drop for InternersGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+
+        CURRENT_INTERNERS.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Calls a closure with the interners entered on the current thread, or the global ones.
+#[allow(clippy::redundant_pub_crate)] // `pub` triggers `unreachable_pub`
+pub(crate) fn with_current_interners<R>(f: impl FnOnce(&Interners) -> R) -> R {
+    CURRENT_INTERNERS.with(|current| match &*current.borrow() {
+        Some(interners) => f(interners),
+        None => f(&GLOBAL_INTERNERS),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Arc, thread};
+
+    use super::Interners;
+    use crate::{builtin_identifiers, IdentifierId};
+
+    #[test]
+    fn isolation() {
+        let first = Interners::new();
+        let second = Interners::new();
+
+        let foo = first.run(|| IdentifierId::from("foo"));
+        let bar = second.run(|| IdentifierId::from("bar"));
+
+        assert_eq!(foo, bar);
+        assert_eq!(&*first.run(|| foo.as_str()), "foo");
+        assert_eq!(&*second.run(|| bar.as_str()), "bar");
+        assert_eq!(first.resolve_identifier(IdentifierId(foo.0 + 1)), None);
+    }
+
+    #[test]
+    fn builtin_identifiers() {
+        let interners = Interners::new();
+
+        assert_eq!(
+            interners.intern_identifier("self"),
+            builtin_identifiers::SMALL_SELF
+        );
+    }
+
+    #[test]
+    fn nested_sessions() {
+        let outer = Interners::new();
+        let inner = Interners::new();
+
+        outer.run(|| {
+            assert!(Interners::current().ptr_eq(&outer));
+            inner.run(|| assert!(Interners::current().ptr_eq(&inner)));
+            assert!(Interners::current().ptr_eq(&outer));
+        });
+
+        assert!(Interners::current().ptr_eq(&Interners::global()));
+    }
+
+    #[test]
+    fn resolved_strings_outlive_interners() {
+        let interners = Interners::new();
+        let id = interners.intern_identifier("foo");
+        let path = interners.intern_path("foo.sr");
+
+        let (name, filepath) = interners.run(|| (id.as_str(), path.as_path()));
+
+        // intern enough strings to reallocate the storage
+        for idx in 0..1000 {
+            let _ = interners.intern_identifier(idx.to_string());
+        }
+
+        drop(interners);
+
+        // the interned string is freed once the resolved one is dropped
+        assert_eq!(Arc::strong_count(&name), 1);
+        assert_eq!(&*name, "foo");
+        assert_eq!(filepath, Path::new("foo.sr"));
+    }
+
+    #[test]
+    fn threads() {
+        let interners = Interners::new();
+        let id = interners.intern_identifier("foo");
+
+        let handle = thread::spawn(move || interners.run(|| id.as_str().to_string()));
+
+        assert_eq!(handle.join().unwrap(), "foo");
+    }
+}
@@ -190,6 +190,32 @@ impl<'s> Lexer<'s> {
         }
     }
 
+    /// Creates a [`Lexer`] state instantiated at a given byte offset of the source.
+    ///
+    /// Locations of the scanned tokens are still relative to the beginning of the
+    /// source, so the lexer can resume scanning a part of the file previously
+    /// skipped by the parser.
+    ///
+    /// # Panics
+    /// Panics if the offset is out of bounds or is not on a character boundary.
+    #[inline]
+    #[must_use]
+    pub fn new_at(filepath: PathId, source: &'s str, offset: ByteOffset) -> Self {
+        let mut chars = source[offset.0..].chars();
+
+        Self {
+            filepath,
+            source,
+            current: chars.next(),
+            next: chars.next(),
+            chars,
+            offset,
+            scanned_identifier: IdentifierId(0),
+            scanned_char: '\0',
+            scanned_string: String::new(),
+        }
+    }
+
     /// Returns a string being scanned early on (after processing escape sequences) and
     /// cleans internal lexer string buffer. So it must be used only once!
     #[inline]
@@ -775,6 +801,7 @@ impl<'s> Lexer<'s> {
             (Some('|'), _) => self.advance_with(Punctuator::Or),
             (Some('?'), _) => self.advance_with(Punctuator::QuestionMark),
             (Some('&'), Some('&')) => self.advance_twice_with(Punctuator::DoubleAmpersand),
+            (Some('&'), Some('=')) => self.advance_twice_with(Punctuator::AmpersandEq),
             (Some('&'), _) => self.advance_with(Punctuator::Ampersand),
             (Some('^'), Some('=')) => self.advance_twice_with(Punctuator::CaretEq),
             (Some('^'), _) => self.advance_with(Punctuator::Caret),
@@ -787,9 +814,26 @@ impl<'s> Lexer<'s> {
             (Some('}'), _) => self.advance_with(Punctuator::CloseBrace),
             (Some(','), _) => self.advance_with(Punctuator::Comma),
             (Some(';'), _) => self.advance_with(Punctuator::Semicolon),
-            (Some('%'), Some('=')) => self.advance_with(Punctuator::PercentEq),
+            (Some('%'), Some('=')) => self.advance_twice_with(Punctuator::PercentEq),
             (Some('%'), _) => self.advance_with(Punctuator::Percent),
-            (Some('.'), Some('.')) => self.advance_twice_with(Punctuator::DoubleDot),
+            (Some('.'), Some('.')) => {
+                let start_offset = self.offset;
+                self.advance_twice();
+
+                if self.current == Some('=') {
+                    self.advance();
+
+                    Token {
+                        raw: Punctuator::DoubleDotEq.into(),
+                        location: self.location_from(start_offset),
+                    }
+                } else {
+                    Token {
+                        raw: Punctuator::DoubleDot.into(),
+                        location: self.location_from(start_offset),
+                    }
+                }
+            }
             _ => {
                 if self.current.is_ascii_digit()
                     || (self.current == Some('.') && self.next.is_ascii_digit())
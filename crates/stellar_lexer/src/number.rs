@@ -75,6 +75,12 @@ impl Lexer<'_> {
                     break 'processing_float;
                 }
 
+                // 1..2 is parsed as:
+                // Int(1) Punct(DoubleDot) Int(2)
+                if self.next == Some('.') {
+                    break 'processing_float;
+                }
+
                 number_kind = NumberKind::Float;
 
                 self.advance();
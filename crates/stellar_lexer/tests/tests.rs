@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
-    use stellar_ast::token::{RawLexError, RawToken::*};
+    use stellar_ast::token::{Punctuator, RawLexError, RawToken::*};
+    use stellar_filesystem::location::ByteOffset;
     use stellar_interner::DUMMY_PATH_ID;
     use stellar_lexer::Lexer;
 
@@ -49,6 +50,24 @@ mod tests {
         "`test\n",
         Error(RawLexError::UnterminatedWrappedIdentifier)
     );
+    lexer_test!(double_dot, "..", Punctuator(Punctuator::DoubleDot));
+    lexer_test!(double_dot_eq, "..=", Punctuator(Punctuator::DoubleDotEq));
+    lexer_test!(percent_eq, "%=", Punctuator(Punctuator::PercentEq));
+    lexer_test!(ampersand_eq, "&=", Punctuator(Punctuator::AmpersandEq));
+    lexer_test!(integer_before_range, "1..2", IntegerLiteral);
     lexer_test!(small_u, "'\\u{1E41}'", CharLiteral);
     lexer_test!(big_u, "\"\\U{0010FFFF}\"", StringLiteral);
+
+    #[test]
+    fn resume_at_offset() {
+        let mut lexer = Lexer::new_at(DUMMY_PATH_ID, "foo { bar }", ByteOffset(4));
+
+        let token = lexer.next_token();
+        assert_eq!(token.raw, Punctuator(Punctuator::OpenBrace));
+        assert_eq!(token.location.start, ByteOffset(4));
+
+        let token = lexer.next_token();
+        assert_eq!(token.raw, Identifier);
+        assert_eq!(token.location.start, ByteOffset(6));
+    }
 }
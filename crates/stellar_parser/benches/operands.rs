@@ -0,0 +1,52 @@
+//! Compares parsing of common operands (literals, identifiers, calls and field
+//! accesses) directly and through the precedence loop, see
+//! [`ParseState::without_fast_operands`].
+
+use std::fmt::Write;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use stellar_diagnostics::Diagnostics;
+use stellar_interner::DUMMY_PATH_ID;
+use stellar_parser::{parse_item_using, ParseState};
+
+/// Returns a function, which body consists of typical expressions.
+fn source() -> String {
+    let mut source = String::from("fun main() {\n");
+
+    for idx in 0..1000 {
+        writeln!(
+            source,
+            "    let a{idx} = self.items.get({idx}).unwrap().name.len() + count;
+    log.info(\"item\", a{idx}, b.c(d, 1.5).e, f(g.0, true));
+    a{idx} = -x.y * (z.w(1) - 2) as int32;"
+        )
+        .unwrap();
+    }
+
+    source.push('}');
+    source
+}
+
+fn parse(source: &str, fast: bool) {
+    let mut diagnostics = Diagnostics::new();
+    let mut state = ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics);
+
+    if !fast {
+        state = state.without_fast_operands();
+    }
+
+    black_box(parse_item_using(&mut state));
+    assert!(diagnostics.is_ok());
+}
+
+fn operands(c: &mut Criterion) {
+    let source = source();
+
+    let mut group = c.benchmark_group("operands");
+    group.bench_function("direct", |b| b.iter(|| parse(&source, true)));
+    group.bench_function("precedence loop", |b| b.iter(|| parse(&source, false)));
+    group.finish();
+}
+
+criterion_group!(benches, operands);
+criterion_main!(benches);
@@ -0,0 +1,68 @@
+use stellar_ast::{
+    token::{Punctuator, RawToken},
+    Attribute,
+};
+
+use crate::{expression::ExpressionParser, list::ListParser, path::PathParser, Parse, ParseState};
+
+/// Parses a single attribute, e.g. `inline`, `deprecated("use `bar` instead")`.
+struct AttributeParser;
+
+impl Parse for AttributeParser {
+    type Output = Option<Attribute>;
+
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        let start = state.next_token.location.start;
+
+        let path = PathParser.parse(state)?;
+
+        let arguments = if state.next_token.raw == Punctuator::OpenParent {
+            state.advance();
+
+            let arguments = ListParser::new(&[RawToken::from(Punctuator::CloseParent)], |state| {
+                ExpressionParser::default().parse(state)
+            })
+            .parse(state)?;
+
+            state.advance(); // `)`
+
+            Some(arguments)
+        } else {
+            None
+        };
+
+        Some(Attribute {
+            location: state.location_from(start),
+            path,
+            arguments,
+        })
+    }
+}
+
+/// Parses attribute lists, e.g. `#[inline] #[deprecated("..."), test]`.
+pub(crate) struct AttributesParser;
+
+impl Parse for AttributesParser {
+    type Output = Option<Vec<Attribute>>;
+
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        let mut attributes = vec![];
+
+        while state.next_token.raw == Punctuator::HashTag {
+            state.advance();
+
+            state.consume(Punctuator::OpenBracket)?;
+
+            attributes.extend(
+                ListParser::new(&[RawToken::from(Punctuator::CloseBracket)], |state| {
+                    AttributeParser.parse(state)
+                })
+                .parse(state)?,
+            );
+
+            state.advance(); // `]`
+        }
+
+        Some(attributes)
+    }
+}
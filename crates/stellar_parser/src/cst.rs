@@ -0,0 +1,330 @@
+//! Lossless concrete syntax tree (CST).
+//!
+//! Unlike the AST, the CST keeps every byte of the source: tokens, whitespace,
+//! comments and tokens the parser failed to make sense of. It is built from
+//! [parse events](crate::event), so that tools working with the source text,
+//! such as formatters and refactorings, can see the exact structure the parser
+//! recognized:
+//!
+//! ```
+//! use stellar_diagnostics::Diagnostics;
+//! use stellar_interner::DUMMY_PATH_ID;
+//! use stellar_parser::parse_cst;
+//!
+//! let source = "fun main() { // comment\n    println(1 + 2);\n}";
+//! let tree = parse_cst(DUMMY_PATH_ID, source, &mut Diagnostics::new());
+//!
+//! assert_eq!(tree.root().text(source), source);
+//! assert!(tree.errors().is_empty());
+//! ```
+
+use stellar_ast::token::RawToken;
+use stellar_filesystem::location::{ByteOffset, Location};
+use stellar_interner::PathId;
+
+use crate::event::{Event, NodeKind};
+
+/// A kind of a syntax token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntaxTokenKind {
+    /// A token consumed by the parser.
+    Token(RawToken),
+
+    /// Whitespace and comments between tokens.
+    Trivia,
+
+    /// Source text, that the parser didn't consume.
+    Skipped,
+}
+
+/// A leaf of a syntax tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntaxToken {
+    /// The kind of the token.
+    pub kind: SyntaxTokenKind,
+
+    /// The location of the token.
+    pub location: Location,
+}
+
+/// A child of a syntax node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    /// A nested node.
+    Node(SyntaxNode),
+
+    /// A token.
+    Token(SyntaxToken),
+}
+
+impl SyntaxElement {
+    /// Returns the location of the element.
+    #[inline]
+    #[must_use]
+    pub const fn location(&self) -> Location {
+        match self {
+            Self::Node(node) => node.location,
+            Self::Token(token) => token.location,
+        }
+    }
+}
+
+/// An inner node of a syntax tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxNode {
+    kind: NodeKind,
+    location: Location,
+    children: Vec<SyntaxElement>,
+}
+
+impl SyntaxNode {
+    /// Returns the kind of the node.
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> NodeKind {
+        self.kind
+    }
+
+    /// Returns the location of the node, including trivia inside of it.
+    #[inline]
+    #[must_use]
+    pub const fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Returns children of the node in the source order.
+    #[inline]
+    #[must_use]
+    pub fn children(&self) -> &[SyntaxElement] {
+        &self.children
+    }
+
+    /// Returns nested nodes of the node in the source order.
+    pub fn child_nodes(&self) -> impl Iterator<Item = &Self> {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// Returns the token, that contains a given offset, `None` if the offset
+    /// is outside of the node.
+    #[must_use]
+    pub fn token_at_offset(&self, offset: ByteOffset) -> Option<&SyntaxToken> {
+        let child = self.children.iter().find(|child| {
+            let location = child.location();
+
+            location.start <= offset && offset < location.end
+        })?;
+
+        match child {
+            SyntaxElement::Node(node) => node.token_at_offset(offset),
+            SyntaxElement::Token(token) => Some(token),
+        }
+    }
+
+    /// Returns the innermost node, that covers a given location, e.g. to find
+    /// the expression a selection belongs to. Returns the node itself, if none
+    /// of its nested nodes covers the location.
+    #[must_use]
+    pub fn covering_node(&self, location: Location) -> &Self {
+        self.child_nodes()
+            .find(|node| node.location.start <= location.start && location.end <= node.location.end)
+            .map_or(self, |node| node.covering_node(location))
+    }
+
+    /// Returns the source text of the node.
+    ///
+    /// # Panics
+    /// Panics if the source is not the one the tree was built from.
+    #[inline]
+    #[must_use]
+    pub fn text<'s>(&self, source: &'s str) -> &'s str {
+        &source[self.location]
+    }
+}
+
+/// An error recorded by the parser while building a syntax tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    /// The location of the unexpected token.
+    pub location: Location,
+
+    /// Description of what was expected, e.g. `identifier`.
+    pub expected: String,
+}
+
+/// A lossless syntax tree of a module together with parse errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxTree {
+    root: SyntaxNode,
+    errors: Vec<SyntaxError>,
+}
+
+impl SyntaxTree {
+    /// Builds a syntax tree of a module from parse events.
+    ///
+    /// The root is a [`NodeKind::Module`] node covering the whole source.
+    /// Trivia before the first token of a node is attached to the parent of
+    /// the node.
+    #[must_use]
+    pub fn build(filepath: PathId, source: &str, events: &[Event]) -> Self {
+        let mut builder = TreeBuilder {
+            filepath,
+            source,
+            offset: ByteOffset(0),
+            stack: vec![],
+            root: vec![],
+            errors: vec![],
+        };
+
+        for (idx, event) in events.iter().enumerate() {
+            match event {
+                Event::Start { kind } => {
+                    // leading trivia belongs to the parent
+                    if let Some(start) = events[idx..].iter().find_map(|event| match event {
+                        Event::Token(token) if !token.raw.eof() => Some(token.location.start),
+                        _ => None,
+                    }) {
+                        builder.gap(start);
+                    }
+
+                    builder.stack.push((*kind, builder.offset, vec![]));
+                }
+                Event::Token(token) => {
+                    if token.raw.eof() {
+                        continue;
+                    }
+
+                    builder.gap(token.location.start);
+                    builder.push_token(SyntaxTokenKind::Token(token.raw), token.location.end);
+                }
+                Event::Finish => builder.finish(),
+                Event::Error { location, expected } => builder.errors.push(SyntaxError {
+                    location: *location,
+                    expected: expected.clone(),
+                }),
+            }
+        }
+
+        // unfinished nodes, if events are not balanced
+        while !builder.stack.is_empty() {
+            builder.finish();
+        }
+
+        let end = ByteOffset(source.len());
+        builder.gap(end);
+
+        Self {
+            root: SyntaxNode {
+                kind: NodeKind::Module,
+                location: builder.location(ByteOffset(0), end),
+                children: builder.root,
+            },
+            errors: builder.errors,
+        }
+    }
+
+    /// Returns the root node.
+    #[inline]
+    #[must_use]
+    pub const fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    /// Returns errors recorded by the parser.
+    #[inline]
+    #[must_use]
+    pub fn errors(&self) -> &[SyntaxError] {
+        &self.errors
+    }
+}
+
+struct TreeBuilder<'s> {
+    filepath: PathId,
+    source: &'s str,
+
+    /// The end of the last token.
+    offset: ByteOffset,
+
+    /// Unfinished nodes: kinds, start offsets and children.
+    stack: Vec<(NodeKind, ByteOffset, Vec<SyntaxElement>)>,
+
+    /// Children of the root node.
+    root: Vec<SyntaxElement>,
+    errors: Vec<SyntaxError>,
+}
+
+impl TreeBuilder<'_> {
+    const fn location(&self, start: ByteOffset, end: ByteOffset) -> Location {
+        Location {
+            filepath: self.filepath,
+            start,
+            end,
+        }
+    }
+
+    /// Returns children of the innermost unfinished node.
+    fn children(&mut self) -> &mut Vec<SyntaxElement> {
+        self.stack
+            .last_mut()
+            .map_or(&mut self.root, |(_, _, children)| children)
+    }
+
+    fn push_token(&mut self, kind: SyntaxTokenKind, end: ByteOffset) {
+        let location = self.location(self.offset, end);
+        self.offset = end;
+
+        self.children()
+            .push(SyntaxElement::Token(SyntaxToken { kind, location }));
+    }
+
+    /// Adds the text between the last token and a given offset, which is
+    /// either trivia or text skipped by the lexer.
+    fn gap(&mut self, end: ByteOffset) {
+        if end <= self.offset {
+            return;
+        }
+
+        let text = &self.source[self.offset.0..end.0];
+        let kind = if text.trim_start().is_empty() || is_comment(text.trim_start()) {
+            SyntaxTokenKind::Trivia
+        } else {
+            SyntaxTokenKind::Skipped
+        };
+
+        self.push_token(kind, end);
+    }
+
+    fn finish(&mut self) {
+        let Some((kind, start, children)) = self.stack.pop() else {
+            return;
+        };
+
+        let node = SyntaxNode {
+            kind,
+            location: self.location(start, self.offset),
+            children,
+        };
+
+        self.children().push(SyntaxElement::Node(node));
+    }
+}
+
+/// Returns `true` if the text consists of comments and whitespace.
+fn is_comment(text: &str) -> bool {
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let Some(comment) = rest.strip_prefix("//") else {
+            return false;
+        };
+
+        rest = comment
+            .split_once('\n')
+            .map_or("", |(_, rest)| rest)
+            .trim_start();
+    }
+
+    true
+}
@@ -1,6 +1,9 @@
 //! Defines diagnostics for parser.
 
-use stellar_ast::token::{LexError, Token};
+use stellar_ast::{
+    token::{LexError, Token},
+    IdentifierAST,
+};
 use stellar_diagnostics::diagnostic::Label;
 use stellar_diagnostics::BuildDiagnostic;
 use stellar_diagnostics::{define_diagnostics, diagnostic::Diagnostic};
@@ -21,10 +24,33 @@ pub enum UnnecessaryVisibilityQualifierContext {
     },
 
     /// ```stellar
-    /// pub import ...;
+    /// pub interface F {
+    ///     pub const T: uint32;
+    ///     ^^^
+    /// }
+    /// ```
+    InterfaceConstant {
+        /// Location of a constant name.
+        name_location: Location,
+    },
+
+    /// ```stellar
+    /// pub import std.collections.*;
+    /// ^^^
+    /// ```
+    GlobImport,
+
+    /// ```stellar
+    /// pub impl Point { ... }
     /// ^^^
     /// ```
-    Import,
+    Impl,
+
+    /// ```stellar
+    /// pub extern "C" { ... }
+    /// ^^^
+    /// ```
+    Extern,
 }
 
 define_diagnostics! {
@@ -80,12 +106,160 @@ define_diagnostics! {
             secondary { self.got.location => "unexpected token" }
         }
     }
+
+    /// Diagnostic related to the `trait` keyword, that was renamed to `interface`.
+    diagnostic(warning) DeprecatedTraitKeyword(self, location: Location) {
+        code { "W003" }
+        message { "`trait` is deprecated, use `interface` instead" }
+        labels {
+            primary { self.location => "help: replace with `interface`" }
+        }
+        notes {
+            "note: the code can be migrated automatically with `stellar_rewriter::migrations`"
+        }
+    }
+
+    /// Diagnostic, that occurs in the newline termination mode, when a statement
+    /// ends at a line break, but the next line starts with a token, that could
+    /// also continue it, e.g. `(` in `let a = b` followed by `(c, d).print()`.
+    diagnostic(warning) AmbiguousLineBreak(self, token: Token) {
+        code { "W004" }
+        message { format!("ambiguous line break before {}", self.token.raw) }
+        labels {
+            primary { self.token.location => "this starts a new statement" }
+        }
+        notes {
+            "help: end the previous statement with `;` to make it explicit".to_owned()
+            format!("help: to continue the expression instead, move {} to the end of the previous line", self.token.raw)
+        }
+    }
+
+    /// Diagnostic, that occurs when a type before `for` in an implementation block
+    /// is not an interface, e.g. `impl (int32, int32) for Point {}`.
+    diagnostic(error) ExpectedInterfaceInImpl(self, location: Location) {
+        code { "E001" }
+        message { "expected an interface" }
+        labels {
+            primary { self.location => "this is not an interface" }
+        }
+    }
+
+    /// Diagnostic, that occurs when a declared submodule, e.g. `module utils;`,
+    /// is neither defined in a file nor inline.
+    diagnostic(error) SubmoduleNotFound(self, location: Location, name: String) {
+        code { "E014" }
+        message { format!("submodule `{}` is not found", self.name) }
+        labels {
+            primary { self.location }
+        }
+        notes {
+            format!("help: create `{}.sr` in the module directory or define the submodule inline: `module {} {{ ... }}`", self.name, self.name)
+        }
+    }
+
+    /// Diagnostic, that occurs when a function declared in an extern block has a
+    /// body, e.g. `extern "C" { fun puts(s: CStr): int32 {} }`.
+    diagnostic(error) ExternFunctionWithBody(
+        self,
+        name: IdentifierAST,
+        body_location: Location
+    ) {
+        code { "E018" }
+        message { format!("foreign function `{}` cannot have a body", self.name.id) }
+        labels {
+            primary { self.body_location => "help: replace the body with `;`" }
+            secondary { self.name.location => "declared in an extern block" }
+        }
+        notes {
+            "note: foreign functions are defined outside of the package and linked with it"
+        }
+    }
+
+    /// Diagnostic, that occurs when a visibility qualifier is restricted to
+    /// something other than `package` or `super`, e.g. `pub(crate)`.
+    diagnostic(error) UnknownVisibilityRestriction(self, location: Location, name: String) {
+        code { "E015" }
+        message { format!("unknown visibility restriction `{}`", self.name) }
+        labels {
+            primary { self.location }
+        }
+        notes {
+            "note: visibility can only be restricted to `pub(package)` or `pub(super)`"
+        }
+    }
+
+    /// Diagnostic, that occurs when an enabled feature doesn't exist.
+    diagnostic(error) UnknownFeature(self, name: IdentifierAST, available_features: String) {
+        code { "E021" }
+        message { format!("unknown feature `{}`", self.name.id) }
+        labels {
+            primary { self.name.location }
+        }
+        notes {
+            format!("note: available features are {}", self.available_features)
+        }
+    }
+
+    /// Diagnostic, that occurs when experimental syntax is used without
+    /// enabling its feature, e.g. a generator function without `generators`.
+    diagnostic(error) FeatureNotEnabled(self, location: Location, feature: String) {
+        code { "E022" }
+        message { format!("use of the experimental `{}` feature", self.feature) }
+        labels {
+            primary { self.location => "the feature is not enabled for this package" }
+        }
+        notes {
+            format!("help: enable the `{}` feature of the package", self.feature)
+        }
+    }
+
+    /// Diagnostic, that occurs when syntax nodes are nested deeper than the
+    /// parser allows, e.g. in `((((...))))` with thousands of parentheses.
+    diagnostic(error) NestingLimitReached(self, location: Location, limit: usize) {
+        code { "E023" }
+        message { "syntax is nested too deeply" }
+        labels {
+            primary { self.location => "the parser gave up here" }
+        }
+        notes {
+            format!("note: the nesting limit is {}", self.limit)
+            "help: split the code into smaller functions or variables".to_owned()
+        }
+    }
+
+    /// Diagnostic, that occurs when the parser consumed as many tokens as it
+    /// was allowed to, see [`ParseState::with_fuel`].
+    ///
+    /// [`ParseState::with_fuel`]: crate::ParseState::with_fuel
+    diagnostic(error) FuelExhausted(self, location: Location, fuel: usize) {
+        code { "E024" }
+        message { "the parser ran out of fuel" }
+        labels {
+            primary { self.location => "parsing stopped after this token" }
+        }
+        notes {
+            format!("note: the parser can consume at most {} tokens", self.fuel)
+        }
+    }
+
+    /// Diagnostic, that occurs when a tuple index is not a plain decimal
+    /// integer, e.g. `x.01` or `x.1e3`.
+    diagnostic(error) InvalidTupleIndex(self, location: Location, index: String) {
+        code { "E025" }
+        message { format!("invalid tuple index `{}`", self.index) }
+        labels {
+            primary { self.location }
+        }
+        notes {
+            "note: tuple indices are decimal integers without leading zeros, e.g. `x.0`"
+        }
+    }
 }
 
 /// Diagnostic related to an unnecessary visibility qualifier error.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct UnnecessaryVisibilityQualifierDiagnostic {
-    /// Location of `pub`.
+    /// Location of the visibility qualifier.
     pub location: Location,
 
     /// Context in which the error is found.
@@ -98,13 +272,22 @@ impl BuildDiagnostic for UnnecessaryVisibilityQualifierDiagnostic {
         let mut labels =
             vec![Label::primary(self.location).with_message("help: remove this `pub`")];
 
-        if let UnnecessaryVisibilityQualifierContext::InterfaceMethod { name_location } =
-            self.context
-        {
-            labels.push(
-                Label::secondary(name_location)
-                    .with_message("happened when processing the interface method"),
-            );
+        match self.context {
+            UnnecessaryVisibilityQualifierContext::InterfaceMethod { name_location } => {
+                labels.push(
+                    Label::secondary(name_location)
+                        .with_message("happened when processing the interface method"),
+                );
+            }
+            UnnecessaryVisibilityQualifierContext::InterfaceConstant { name_location } => {
+                labels.push(
+                    Label::secondary(name_location)
+                        .with_message("happened when processing the interface constant"),
+                );
+            }
+            UnnecessaryVisibilityQualifierContext::GlobImport
+            | UnnecessaryVisibilityQualifierContext::Impl
+            | UnnecessaryVisibilityQualifierContext::Extern => {}
         }
 
         Diagnostic::error()
@@ -119,8 +302,30 @@ impl BuildDiagnostic for UnnecessaryVisibilityQualifierDiagnostic {
                         "note: all interface methods are public by default".to_owned(),
                     ]
                 }
-                UnnecessaryVisibilityQualifierContext::Import => {
-                    vec!["note: using `pub` will not make the import public.".to_owned()]
+                UnnecessaryVisibilityQualifierContext::InterfaceConstant { .. } => {
+                    vec![
+                        "note: using `pub` for interface constant will not make the constant public"
+                            .to_owned(),
+                        "note: all interface constants are public by default".to_owned(),
+                    ]
+                }
+                UnnecessaryVisibilityQualifierContext::GlobImport => {
+                    vec![
+                        "note: glob imports cannot re-export names".to_owned(),
+                        "help: import the names, that should be re-exported, explicitly".to_owned(),
+                    ]
+                }
+                UnnecessaryVisibilityQualifierContext::Impl => {
+                    vec![
+                        "note: visibility of methods is specified separately for each method"
+                            .to_owned(),
+                    ]
+                }
+                UnnecessaryVisibilityQualifierContext::Extern => {
+                    vec![
+                        "note: visibility of foreign functions is specified separately for each function"
+                            .to_owned(),
+                    ]
                 }
             })
     }
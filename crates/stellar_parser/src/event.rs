@@ -0,0 +1,87 @@
+//! Parse events: a flat stream describing the structure of the parsed source.
+//!
+//! While parsing, the parser can record every token it consumes and every
+//! syntax node it starts and finishes. Nodes are started and finished using
+//! [`Marker`]s, so the stream is always balanced, even if a node failed to
+//! parse. A lossless concrete syntax tree is built from the stream by
+//! [`SyntaxTree::build`].
+//!
+//! Recording is disabled by default, see [`parse_events()`].
+//!
+//! [`SyntaxTree::build`]: crate::cst::SyntaxTree::build
+//! [`parse_events()`]: crate::parse_events
+
+use stellar_ast::token::Token;
+use stellar_filesystem::location::Location;
+
+use crate::ParseState;
+
+/// A kind of a syntax node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// The whole module, the root of a syntax tree.
+    Module,
+
+    /// A module item, e.g. `fun main() {}`.
+    Item,
+
+    /// A statement, e.g. `let a = 1;`.
+    Statement,
+
+    /// A block of statements in braces, e.g. `{ a(); b }`.
+    StatementsBlock,
+
+    /// An expression, e.g. `a + 1`.
+    Expression,
+
+    /// A pattern, e.g. `Some(x)`.
+    Pattern,
+
+    /// A type, e.g. `List[int32]`.
+    Type,
+}
+
+/// A parse event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A node of a given kind starts.
+    Start {
+        /// The kind of the node.
+        kind: NodeKind,
+    },
+
+    /// A token is consumed by the parser.
+    Token(Token),
+
+    /// The node, that was started last and is not finished yet, ends.
+    Finish,
+
+    /// The parser expected something else at a given location.
+    Error {
+        /// The location of the unexpected token.
+        location: Location,
+
+        /// Description of what was expected, e.g. `identifier`.
+        expected: String,
+    },
+}
+
+/// A started node, which must be completed with [`Marker::complete`].
+#[derive(Debug)]
+#[must_use = "markers must be completed"]
+pub(crate) struct Marker(());
+
+impl Marker {
+    /// Starts a node of a given kind.
+    pub(crate) fn start(state: &mut ParseState<'_, '_>, kind: NodeKind) -> Self {
+        state.push_event(Event::Start { kind });
+
+        Self(())
+    }
+
+    /// Finishes the node started by the marker.
+    #[allow(clippy::unused_self)] // consumed, so that the node is finished once
+    pub(crate) fn complete(self, state: &mut ParseState<'_, '_>) {
+        state.push_event(Event::Finish);
+    }
+}
@@ -1,21 +1,51 @@
 use stellar_ast::{
-    precedence::Precedence,
+    precedence::{Fixity, Operator, Precedence},
     token::{Keyword, Punctuator, RawToken},
-    BinaryOperator, Expression, IdentifierAST, LambdaFunctionParameter, MatchExpressionItem,
-    PostfixOperator, PrefixOperator, RawBinaryOperator, RawPostfixOperator, RawPrefixOperator,
-    StructFieldExpression,
+    BinaryOperator, CallArgument, CaptureKind, ElementKind, Expression, IdentifierAST,
+    LambdaCapture, LambdaFunctionParameter, ListElement, MapEntry, MatchExpressionItem, Pattern,
+    PostfixOperator, PrefixOperator, RangeKind, RawBinaryOperator, RawPostfixOperator,
+    RawPrefixOperator, Statement, StructFieldExpression,
 };
 use stellar_english_commons::enumeration::one_of;
+use stellar_filesystem::location::{ByteOffset, Location};
 
 use crate::{
+    diagnostics::InvalidTupleIndex,
+    event::NodeKind,
     list::ListParser,
     literal::LiteralParser,
+    options::Feature,
     pattern::PatternParser,
     r#type::{TypeArgumentsParser, TypeParser},
     statement::StatementsBlockParser,
     Parse, ParseState,
 };
 
+/// Returns `true` if a token can both start an expression and continue one,
+/// e.g. `(` in `f` followed by `(a)` on the next line.
+///
+/// See [`ParseState::with_newline_termination`] for more details.
+pub(crate) const fn can_start_and_continue_expression(token: RawToken) -> bool {
+    matches!(
+        token,
+        RawToken::Punctuator(
+            Punctuator::OpenParent
+                | Punctuator::OpenBracket
+                | Punctuator::OpenBrace
+                | Punctuator::Plus
+                | Punctuator::Minus
+                | Punctuator::DoublePlus
+                | Punctuator::DoubleMinus
+                | Punctuator::DoubleDot
+                | Punctuator::DoubleDotEq
+                | Punctuator::Or
+                | Punctuator::DoubleOr
+                | Punctuator::Ampersand
+                | Punctuator::DoubleAmpersand
+        )
+    )
+}
+
 /// Parser for Stellar expressions.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct ExpressionParser {
@@ -73,20 +103,77 @@ impl ExpressionParser {
     ) -> Option<Expression> {
         state.advance(); // `(`
 
-        let arguments = ListParser::new(&[RawToken::from(Punctuator::CloseParent)], |state| {
-            ExpressionParser::default().parse(state)
-        })
+        let arguments = ListParser::new(
+            &[RawToken::from(Punctuator::CloseParent)],
+            Self::parse_call_argument,
+        )
         .parse(state)?;
 
         state.advance();
 
         Some(Expression::Call {
+            node_id: state.new_node_id(),
             location: state.location_from(left.location().start),
             callee: Box::new(left),
             arguments,
         })
     }
 
+    fn parse_call_argument(state: &mut ParseState<'_, '_>) -> Option<CallArgument> {
+        let kind = Self::parse_element_kind(state);
+        let value = Self::default().parse(state)?;
+
+        // `x: 10` - the label is parsed as an identifier expression first
+        match value {
+            Expression::Identifier {
+                identifier: name, ..
+            } if kind == ElementKind::Single && state.next_token.raw == Punctuator::Colon => {
+                state.advance();
+
+                // `x:` - the shorthand form of `x: x`, the label is the value
+                if state.next_token.raw == Punctuator::Comma
+                    || state.next_token.raw == Punctuator::CloseParent
+                {
+                    return Some(CallArgument {
+                        name: Some(name),
+                        kind,
+                        value,
+                        is_shorthand: true,
+                    });
+                }
+
+                Some(CallArgument {
+                    name: Some(name),
+                    kind,
+                    value: Self::default().parse(state)?,
+                    is_shorthand: false,
+                })
+            }
+            _ => Some(CallArgument {
+                name: None,
+                kind,
+                value,
+                is_shorthand: false,
+            }),
+        }
+    }
+
+    /// Consumes `..` before a spread element of a list or a call, e.g. in
+    /// `[1, ..rest]`. Note that `..=` still starts a range expression, as
+    /// well as `..` followed by a token, that cannot start an expression,
+    /// e.g. in `f(..)` or `[1, ..]`.
+    fn parse_element_kind(state: &mut ParseState<'_, '_>) -> ElementKind {
+        if state.next_token.raw == Punctuator::DoubleDot
+            && !Self::default().range_end_is_omitted(state.lookahead().next_no_comments().raw)
+        {
+            state.advance();
+
+            ElementKind::Spread
+        } else {
+            ElementKind::Single
+        }
+    }
+
     fn parse_field_access_expression(
         self,
         state: &mut ParseState<'_, '_>,
@@ -94,15 +181,93 @@ impl ExpressionParser {
     ) -> Option<Expression> {
         state.advance(); // `.`
 
+        if state.next_token.raw == Keyword::Await {
+            state.advance();
+
+            return Some(Expression::Await {
+                node_id: state.new_node_id(),
+                location: state.location_from(left.location().start),
+                inner: Box::new(left),
+            });
+        }
+
+        // `x. 0`, `.0` without a space is lexed as a float literal
+        if state.next_token.raw == RawToken::IntegerLiteral {
+            state.advance();
+
+            return Self::finish_tuple_index_expression(state, left, state.current_token.location);
+        }
+
         let right = state.consume_identifier()?;
 
         Some(Expression::FieldAccess {
+            node_id: state.new_node_id(),
             location: state.location_from(left.location().start),
             left: Box::new(left),
             right,
         })
     }
 
+    /// Returns `true` if the next token is a float literal starting with `.`,
+    /// e.g. `.0` in `x.0`, which is a tuple index rather than a number.
+    fn at_tuple_index(state: &ParseState<'_, '_>) -> bool {
+        state.next_token.raw == RawToken::FloatLiteral
+            && state
+                .resolve_location(state.next_token.location)
+                .starts_with('.')
+    }
+
+    /// Returns the precedence of the next token, treating a tuple index like
+    /// a field access.
+    fn next_precedence(state: &ParseState<'_, '_>) -> Precedence {
+        if Self::at_tuple_index(state) {
+            Precedence::Field
+        } else {
+            state.next_token.raw.into()
+        }
+    }
+
+    /// Parses `.0` in `x.0`, see [`ExpressionParser::at_tuple_index`].
+    fn parse_tuple_index_expression(
+        state: &mut ParseState<'_, '_>,
+        left: Expression,
+    ) -> Option<Expression> {
+        state.advance();
+
+        let location = state.current_token.location;
+        let index_location = state.make_location(ByteOffset(location.start.0 + 1), location.end);
+
+        Self::finish_tuple_index_expression(state, left, index_location)
+    }
+
+    fn finish_tuple_index_expression(
+        state: &mut ParseState<'_, '_>,
+        left: Expression,
+        index_location: Location,
+    ) -> Option<Expression> {
+        let text = state.resolve_location(index_location);
+
+        // only plain decimal integers, e.g. not `01`, `1_0` or `1e3`
+        let is_decimal = text.bytes().all(|byte| byte.is_ascii_digit())
+            && (text == "0" || !text.starts_with('0'));
+
+        let Some(index) = text.parse().ok().filter(|_| is_decimal) else {
+            state
+                .diagnostics
+                .add_diagnostic(InvalidTupleIndex::new(index_location, text.to_owned()));
+
+            return None;
+        };
+
+        Some(Expression::TupleIndex {
+            node_id: state.new_node_id(),
+            location: state.location_from(left.location().start),
+            left: Box::new(left),
+            index,
+            index_location,
+        })
+    }
+
     fn parse_type_arguments_expression(
         self,
         state: &mut ParseState<'_, '_>,
@@ -111,6 +276,7 @@ impl ExpressionParser {
         let arguments = TypeArgumentsParser.parse(state)?;
 
         Some(Expression::TypeArguments {
+            node_id: state.new_node_id(),
             location: state.location_from(left.location().start),
             left: Box::new(left),
             arguments,
@@ -127,6 +293,7 @@ impl ExpressionParser {
         let right = TypeParser.parse(state)?;
 
         Some(Expression::As {
+            node_id: state.new_node_id(),
             location: state.location_from(left.location().start),
             left: Box::new(left),
             right,
@@ -156,17 +323,43 @@ impl ExpressionParser {
     ) -> Option<Expression> {
         state.advance(); // `{`
 
-        let fields = ListParser::new(&[RawToken::from(Punctuator::CloseBrace)], |state| {
-            self.parse_struct_field_expression(state)
-        })
+        let fields = ListParser::new(
+            &[
+                RawToken::from(Punctuator::CloseBrace),
+                RawToken::from(Punctuator::DoubleDot),
+            ],
+            |state| self.parse_struct_field_expression(state),
+        )
         .parse(state)?;
 
+        // `Person { name: "Ann", ..defaults }`
+        let rest = if state.next_token.raw == Punctuator::DoubleDot {
+            if !fields.is_empty() && state.current_token.raw != Punctuator::Comma {
+                state.add_unexpected_token_diagnostic("`,`");
+
+                return None;
+            }
+
+            state.advance();
+
+            let rest =
+                state.with_significant_newlines(false, |state| Self::default().parse(state))?;
+
+            state.expect(RawToken::from(Punctuator::CloseBrace))?;
+
+            Some(Box::new(rest))
+        } else {
+            None
+        };
+
         state.advance(); // `}`
 
         Some(Expression::Struct {
+            node_id: state.new_node_id(),
             location: state.location_from(left.location().start),
             left: Box::new(left),
             fields,
+            rest,
         })
     }
 
@@ -180,7 +373,8 @@ impl ExpressionParser {
             location: operator_token.location,
             raw: RawBinaryOperator::from(operator_token.raw),
         };
-        let precedence = state.next_token.raw.into();
+        let precedence = Operator::find(Fixity::Infix, operator_token.raw)
+            .map_or(Precedence::Lowest, Operator::right_operand_precedence);
 
         state.advance();
 
@@ -190,6 +384,7 @@ impl ExpressionParser {
             .parse(state)?;
 
         Some(Expression::Binary {
+            node_id: state.new_node_id(),
             location: state.location_from(left.location().start),
             left: Box::new(left),
             right: Box::new(right),
@@ -197,6 +392,196 @@ impl ExpressionParser {
         })
     }
 
+    /// Parses a range expression, starting from the range operator.
+    fn parse_range_expression(
+        self,
+        state: &mut ParseState<'_, '_>,
+        start: Option<Expression>,
+    ) -> Option<Expression> {
+        let start_offset = start
+            .as_ref()
+            .map_or(state.next_token.location.start, |start| {
+                start.location().start
+            });
+
+        let kind = if state.next_token.raw == Punctuator::DoubleDotEq {
+            RangeKind::Inclusive
+        } else {
+            RangeKind::Exclusive
+        };
+
+        state.advance(); // `..` or `..=`
+
+        // inclusive ranges must always have an end: `a..=`
+        let end = if kind == RangeKind::Exclusive && self.range_end_is_omitted(state.next_token.raw)
+        {
+            None
+        } else {
+            Some(Box::new(
+                ExpressionParser::new()
+                    .with_precedence(Precedence::Range)
+                    .prohibit_struct_expressions_if(self.prohibit_struct_expressions)
+                    .parse(state)?,
+            ))
+        };
+
+        Some(Expression::Range {
+            node_id: state.new_node_id(),
+            location: state.location_from(start_offset),
+            start: start.map(Box::new),
+            end,
+            kind,
+        })
+    }
+
+    /// Returns `true` if a given token after `..` cannot start the end of a
+    /// range expression, e.g. in `a..` or `list[1..]`.
+    const fn range_end_is_omitted(self, token: RawToken) -> bool {
+        match token {
+            RawToken::Punctuator(
+                Punctuator::CloseParent
+                | Punctuator::CloseBracket
+                | Punctuator::CloseBrace
+                | Punctuator::Comma
+                | Punctuator::Semicolon
+                | Punctuator::Arrow,
+            )
+            | RawToken::EndOfFile => true,
+            RawToken::Punctuator(Punctuator::OpenBrace) => self.prohibit_struct_expressions,
+            _ => false,
+        }
+    }
+
+    /// Parses an operand: a primary expression together with calls and field
+    /// accesses following it.
+    ///
+    /// Literals, identifiers, calls and field accesses make up the majority of
+    /// expressions in real code, so they are parsed directly here, without
+    /// going through the precedence loop of [`ExpressionParser::parse`],
+    /// unless it is disabled with [`ParseState::without_fast_operands`].
+    /// Other primary expressions are parsed by [`PrimaryExpressionParser`].
+    fn parse_operand(self, state: &mut ParseState<'_, '_>) -> Option<Expression> {
+        let mut operand = match state.next_token.raw {
+            RawToken::Identifier if state.fast_operands => {
+                let symbol = state.lexer.scanned_identifier;
+                state.advance();
+
+                Expression::Identifier {
+                    node_id: state.new_node_id(),
+                    identifier: IdentifierAST {
+                        location: state.current_token.location,
+                        id: symbol,
+                    },
+                }
+            }
+            RawToken::IntegerLiteral
+            | RawToken::FloatLiteral
+            | RawToken::StringLiteral
+            | RawToken::CharLiteral
+            | RawToken::TrueBoolLiteral
+            | RawToken::FalseBoolLiteral
+                if state.fast_operands =>
+            {
+                Expression::Literal {
+                    node_id: state.new_node_id(),
+                    literal: LiteralParser.parse(state)?,
+                }
+            }
+            _ => {
+                return PrimaryExpressionParser {
+                    in_statements_block: self.in_statements_block,
+                    prohibit_struct_expressions: self.prohibit_struct_expressions,
+                }
+                .parse(state)
+            }
+        };
+
+        // calls and field accesses bind tighter than any operator, that can
+        // precede the operand
+        if self.precedence >= Precedence::Call {
+            return Some(operand);
+        }
+
+        loop {
+            // `operand` becomes a child of the next node, see
+            // [`ExpressionParser::parse_expression`]
+            operand = match state.next_token.raw {
+                _ if Self::ends_at_line_break(state) => return Some(operand),
+                RawToken::Punctuator(Punctuator::OpenParent) => {
+                    state.enter_chain()?;
+                    self.parse_call_expression(state, operand)
+                }
+                RawToken::Punctuator(Punctuator::Dot) => {
+                    state.enter_chain()?;
+                    self.parse_field_access_expression(state, operand)
+                }
+                RawToken::FloatLiteral if Self::at_tuple_index(state) => {
+                    state.enter_chain()?;
+                    Self::parse_tuple_index_expression(state, operand)
+                }
+                _ => return Some(operand),
+            }?;
+        }
+    }
+
+    /// Returns `true` if the next `{` starts a map expression rather than a
+    /// statements block, e.g. `{"a": 1}` or `{:}`.
+    ///
+    /// The key of the first map entry is followed by `:`, which can't appear
+    /// outside of brackets in the first statement of a block, except for
+    /// statements starting with a keyword, lambdas and casts, that are
+    /// therefore never treated as maps.
+    fn starts_map_expression(state: &ParseState<'_, '_>) -> bool {
+        let mut lookahead = state.lookahead();
+        let mut depth = 0_usize;
+        let mut token = lookahead.next_no_comments();
+
+        // `{:}`
+        if token.raw == Punctuator::Colon {
+            return true;
+        }
+
+        loop {
+            match token.raw {
+                RawToken::Punctuator(
+                    Punctuator::OpenParent | Punctuator::OpenBracket | Punctuator::OpenBrace,
+                ) => depth += 1,
+                RawToken::Punctuator(
+                    Punctuator::CloseParent | Punctuator::CloseBracket | Punctuator::CloseBrace,
+                ) => {
+                    if depth == 0 {
+                        return false;
+                    }
+
+                    depth -= 1;
+                }
+                RawToken::Punctuator(Punctuator::Colon) if depth == 0 => return true,
+                RawToken::Punctuator(
+                    Punctuator::Semicolon | Punctuator::Or | Punctuator::DoubleOr,
+                )
+                | RawToken::Keyword(
+                    Keyword::Let
+                    | Keyword::Return
+                    | Keyword::Yield
+                    | Keyword::Defer
+                    | Keyword::Break
+                    | Keyword::Continue
+                    | Keyword::As,
+                ) if depth == 0 => return false,
+                RawToken::EndOfFile => return false,
+                _ => {}
+            }
+
+            token = lookahead.next_no_comments();
+        }
+    }
+
+    /// Returns `true` if the expression ends at a line break before the next
+    /// token, see [`ParseState::with_newline_termination`].
+    fn ends_at_line_break(state: &ParseState<'_, '_>) -> bool {
+        state.is_at_line_break() && can_start_and_continue_expression(state.next_token.raw)
+    }
+
     fn parse_postfix_expression(
         self,
         state: &mut ParseState<'_, '_>,
@@ -210,24 +595,27 @@ impl ExpressionParser {
         };
 
         Some(Expression::Postfix {
+            node_id: state.new_node_id(),
             location: state.location_from(left.location().start),
             inner: Box::new(left),
             operator,
         })
     }
-}
 
-impl Parse for ExpressionParser {
-    type Output = Option<Expression>;
+    fn parse_expression(self, state: &mut ParseState<'_, '_>) -> Option<Expression> {
+        let mut left = self.parse_operand(state)?;
 
-    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
-        let mut left = PrimaryExpressionParser {
-            in_statements_block: self.in_statements_block,
-            prohibit_struct_expressions: self.prohibit_struct_expressions,
-        }
-        .parse(state)?;
+        while self.precedence < Self::next_precedence(state)
+            && !left.with_block()
+            && !Self::ends_at_line_break(state)
+        {
+            if self.prohibit_struct_expressions && state.next_token.raw == Punctuator::OpenBrace {
+                break;
+            }
+
+            // `left` becomes a child of the next node
+            state.enter_chain()?;
 
-        while self.precedence < state.next_token.raw.into() && !left.with_block() {
             left = match state.next_token.raw {
                 RawToken::Punctuator(Punctuator::OpenParent) => {
                     self.parse_call_expression(state, left)
@@ -235,15 +623,17 @@ impl Parse for ExpressionParser {
                 RawToken::Punctuator(Punctuator::Dot) => {
                     self.parse_field_access_expression(state, left)
                 }
+                RawToken::FloatLiteral if Self::at_tuple_index(state) => {
+                    Self::parse_tuple_index_expression(state, left)
+                }
                 RawToken::Punctuator(Punctuator::OpenBracket) => {
                     self.parse_type_arguments_expression(state, left)
                 }
                 RawToken::Keyword(Keyword::As) => self.parse_cast_expression(state, left),
+                RawToken::Punctuator(Punctuator::DoubleDot | Punctuator::DoubleDotEq) => {
+                    self.parse_range_expression(state, Some(left))
+                }
                 RawToken::Punctuator(Punctuator::OpenBrace) => {
-                    if self.prohibit_struct_expressions {
-                        return Some(left);
-                    }
-
                     self.parse_struct_expression(state, left)
                 }
                 _ => {
@@ -262,6 +652,14 @@ impl Parse for ExpressionParser {
     }
 }
 
+impl Parse for ExpressionParser {
+    type Output = Option<Expression>;
+
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        state.node(NodeKind::Expression, |state| self.parse_expression(state))
+    }
+}
+
 struct PrimaryExpressionParser {
     in_statements_block: bool,
     prohibit_struct_expressions: bool,
@@ -293,17 +691,20 @@ impl PrimaryExpressionParser {
                     .contains(',')
                 {
                     Some(Expression::Tuple {
+                        node_id: state.new_node_id(),
                         location,
                         elements: vec![element],
                     })
                 } else {
                     Some(Expression::Parenthesized {
+                        node_id: state.new_node_id(),
                         location,
                         inner: Box::from(element),
                     })
                 }
             }
             (None, None) => Some(Expression::Tuple {
+                node_id: state.new_node_id(),
                 location,
                 elements: vec![],
             }),
@@ -315,6 +716,7 @@ impl PrimaryExpressionParser {
                 new_elements.append(&mut elements.collect::<Vec<_>>());
 
                 Some(Expression::Tuple {
+                    node_id: state.new_node_id(),
                     location,
                     elements: new_elements,
                 })
@@ -328,24 +730,138 @@ impl PrimaryExpressionParser {
 
         state.advance();
 
-        let elements = ListParser::new(&[RawToken::from(Punctuator::CloseBracket)], |state| {
-            ExpressionParser::default().parse(state)
-        })
-        .parse(state)?;
+        let closing_tokens = [RawToken::from(Punctuator::CloseBracket)];
+        let list_parser = ListParser::new(&closing_tokens, |state| {
+            Some(ListElement {
+                kind: ExpressionParser::parse_element_kind(state),
+                value: ExpressionParser::default().parse(state)?,
+            })
+        });
+
+        let elements = if matches!(
+            state.next_token.raw,
+            RawToken::Punctuator(Punctuator::CloseBracket | Punctuator::DoubleDot)
+        ) {
+            list_parser.parse(state)?
+        } else {
+            // the first element decides, whether it is `[value; size]` or a list
+            let value = state.with_significant_newlines(false, |state| {
+                ExpressionParser::default().parse(state)
+            })?;
+
+            if state.next_token.raw == Punctuator::Semicolon {
+                return Self::parse_array_repeat_expression(state, start, value);
+            }
+
+            list_parser.parse_after(
+                state,
+                ListElement {
+                    kind: ElementKind::Single,
+                    value,
+                },
+            )?
+        };
 
         state.advance();
 
         Some(Expression::List {
+            node_id: state.new_node_id(),
             location: state.location_from(start),
             elements,
         })
     }
 
+    fn parse_array_repeat_expression(
+        state: &mut ParseState<'_, '_>,
+        start: ByteOffset,
+        value: Expression,
+    ) -> Option<Expression> {
+        state.advance(); // `;`
+
+        let size = state
+            .with_significant_newlines(false, |state| ExpressionParser::default().parse(state))?;
+
+        state.consume(Punctuator::CloseBracket)?;
+
+        Some(Expression::ArrayRepeat {
+            node_id: state.new_node_id(),
+            location: state.location_from(start),
+            value: Box::new(value),
+            size: Box::new(size),
+        })
+    }
+
+    fn parse_map_expression(&self, state: &mut ParseState<'_, '_>) -> Option<Expression> {
+        let start = state.next_token.location.start;
+
+        state.advance(); // `{`
+
+        // `{:}` - empty map
+        if state.next_token.raw == Punctuator::Colon {
+            state.advance();
+            state.consume(Punctuator::CloseBrace)?;
+
+            return Some(Expression::Map {
+                node_id: state.new_node_id(),
+                location: state.location_from(start),
+                entries: vec![],
+            });
+        }
+
+        let entries = ListParser::new(&[RawToken::from(Punctuator::CloseBrace)], |state| {
+            let key = ExpressionParser::default().parse(state)?;
+
+            state.consume(Punctuator::Colon)?;
+
+            Some(MapEntry {
+                key,
+                value: ExpressionParser::default().parse(state)?,
+            })
+        })
+        .parse(state)?;
+
+        state.advance(); // `}`
+
+        Some(Expression::Map {
+            node_id: state.new_node_id(),
+            location: state.location_from(start),
+            entries,
+        })
+    }
+
     fn parse_block_expression(&self, state: &mut ParseState<'_, '_>) -> Option<Expression> {
         let start = state.next_token.location.start;
         let block = StatementsBlockParser.parse(state)?;
 
         Some(Expression::StatementsBlock {
+            node_id: state.new_node_id(),
+            location: state.location_from(start),
+            block,
+        })
+    }
+
+    fn parse_unsafe_expression(&self, state: &mut ParseState<'_, '_>) -> Option<Expression> {
+        let start = state.next_token.location.start;
+        state.advance(); // `unsafe`
+
+        let block = StatementsBlockParser.parse(state)?;
+
+        Some(Expression::Unsafe {
+            node_id: state.new_node_id(),
+            location: state.location_from(start),
+            block,
+        })
+    }
+
+    fn parse_try_expression(&self, state: &mut ParseState<'_, '_>) -> Option<Expression> {
+        let start = state.next_token.location.start;
+        state.advance(); // `try`
+        state.check_feature(Feature::TryBlocks, state.current_token.location);
+
+        let block = StatementsBlockParser.parse(state)?;
+
+        Some(Expression::Try {
+            node_id: state.new_node_id(),
             location: state.location_from(start),
             block,
         })
@@ -356,7 +872,25 @@ impl PrimaryExpressionParser {
 
         state.advance(); // `|` or `||`
 
-        let parameters = if state.current_token.raw == Punctuator::Or {
+        // `||` starts a lambda without parameters
+        let has_parameters = state.current_token.raw == Punctuator::Or;
+
+        let captures = if has_parameters && state.next_token.raw == Punctuator::OpenBracket {
+            state.advance(); // `[`
+
+            let captures = ListParser::new(&[RawToken::from(Punctuator::CloseBracket)], |state| {
+                Self::parse_lambda_capture(state)
+            })
+            .parse(state)?;
+
+            state.advance(); // `]`
+
+            captures
+        } else {
+            vec![]
+        };
+
+        let parameters = if has_parameters {
             let parameters = ListParser::new(&[RawToken::from(Punctuator::Or)], |state| {
                 let name = state.consume_identifier()?;
 
@@ -390,17 +924,59 @@ impl PrimaryExpressionParser {
         let value = ExpressionParser::default().parse(state)?;
 
         Some(Expression::Lambda {
+            node_id: state.new_node_id(),
             location: state.location_from(start),
+            captures,
             parameters,
             return_type,
             value: Box::new(value),
         })
     }
 
+    fn parse_lambda_capture(state: &mut ParseState<'_, '_>) -> Option<LambdaCapture> {
+        let start = state.next_token.location.start;
+
+        let kind = match state.next_token.raw {
+            RawToken::Keyword(Keyword::Move) => {
+                state.advance();
+
+                CaptureKind::Move
+            }
+            RawToken::Punctuator(Punctuator::Ampersand) => {
+                state.advance();
+
+                if state.next_token.raw == Keyword::Mut {
+                    state.advance();
+
+                    CaptureKind::MutableReference
+                } else {
+                    CaptureKind::Reference
+                }
+            }
+            _ => {
+                state.add_unexpected_token_diagnostic("lambda capture");
+
+                return None;
+            }
+        };
+
+        let name = state.consume_identifier()?;
+
+        Some(LambdaCapture {
+            location: state.location_from(start),
+            kind,
+            name,
+        })
+    }
+
     fn parse_if_expression(&self, state: &mut ParseState<'_, '_>) -> Option<Expression> {
         let start = state.next_token.location.start;
         state.advance(); // `if`
 
+        if state.next_token.raw == Keyword::Let {
+            return self.parse_if_let_expression(state, start);
+        }
+
         let condition = ExpressionParser::new()
             .prohibit_struct_expressions()
             .parse(state)?;
@@ -419,8 +995,18 @@ impl PrimaryExpressionParser {
                 break;
             }
 
+            let start = state.next_token.location.start;
             state.advance();
 
+            if state.next_token.raw == Keyword::Let {
+                r#else = Some(vec![Statement::Expression {
+                    node_id: state.new_node_id(),
+                    expression: self.parse_if_let_expression(state, start)?,
+                    has_semicolon: false,
+                }]);
+                break;
+            }
+
             let condition = ExpressionParser::new()
                 .prohibit_struct_expressions()
                 .parse(state)?;
@@ -430,12 +1016,66 @@ impl PrimaryExpressionParser {
         }
 
         Some(Expression::If {
+            node_id: state.new_node_id(),
             location: state.location_from(start),
             if_blocks,
             r#else,
         })
     }
 
+    /// Parses an if let expression after the `if` keyword, e.g. `let Some(x) = y { ... }`.
+    ///
+    /// `else if` after an if let expression is stored as a nested if expression
+    /// in the `else` block.
+    fn parse_if_let_expression(
+        &self,
+        state: &mut ParseState<'_, '_>,
+        start: ByteOffset,
+    ) -> Option<Expression> {
+        state.advance(); // `let`
+
+        let (pattern, expression) = self.parse_let_condition(state)?;
+        let block = StatementsBlockParser.parse(state)?;
+
+        let r#else = if state.next_token.raw == Keyword::Else {
+            state.advance();
+
+            if state.next_token.raw == Keyword::If {
+                Some(vec![Statement::Expression {
+                    node_id: state.new_node_id(),
+                    expression: self.parse_if_expression(state)?,
+                    has_semicolon: false,
+                }])
+            } else {
+                Some(StatementsBlockParser.parse(state)?)
+            }
+        } else {
+            None
+        };
+
+        Some(Expression::IfLet {
+            node_id: state.new_node_id(),
+            location: state.location_from(start),
+            pattern,
+            expression: Box::new(expression),
+            block,
+            r#else,
+        })
+    }
+
+    /// Parses `<pattern> = <expression>` in `if let` and `while let`.
+    fn parse_let_condition(&self, state: &mut ParseState<'_, '_>) -> Option<(Pattern, Expression)> {
+        let pattern = PatternParser.parse(state)?;
+
+        state.consume(Punctuator::Eq)?;
+
+        let expression = ExpressionParser::new()
+            .prohibit_struct_expressions()
+            .parse(state)?;
+
+        Some((pattern, expression))
+    }
+
     fn parse_match_expression_item(
         &self,
         state: &mut ParseState<'_, '_>,
@@ -476,6 +1116,7 @@ impl PrimaryExpressionParser {
         let block = self.parse_match_expression_block(state)?;
 
         Some(Expression::Match {
+            node_id: state.new_node_id(),
             location: state.location_from(start),
             expression: Box::new(expression),
             block,
@@ -486,6 +1127,21 @@ impl PrimaryExpressionParser {
         let start = state.next_token.location.start;
         state.advance(); // `while`
 
+        if state.next_token.raw == Keyword::Let {
+            state.advance(); // `let`
+
+            let (pattern, expression) = self.parse_let_condition(state)?;
+            let body = StatementsBlockParser.parse(state)?;
+
+            return Some(Expression::WhileLet {
+                node_id: state.new_node_id(),
+                location: state.location_from(start),
+                pattern,
+                expression: Box::new(expression),
+                statements_block: body,
+            });
+        }
+
         let condition = ExpressionParser::new()
             .prohibit_struct_expressions()
             .parse(state)?;
@@ -493,12 +1149,36 @@ impl PrimaryExpressionParser {
         let body = StatementsBlockParser.parse(state)?;
 
         Some(Expression::While {
+            node_id: state.new_node_id(),
             location: state.location_from(start),
             condition: Box::new(condition),
             statements_block: body,
         })
     }
 
+    fn parse_for_expression(state: &mut ParseState<'_, '_>) -> Option<Expression> {
+        let start = state.next_token.location.start;
+        state.advance(); // `for`
+
+        let pattern = PatternParser.parse(state)?;
+
+        state.consume(Keyword::In)?;
+
+        let iterable = ExpressionParser::new()
+            .prohibit_struct_expressions()
+            .parse(state)?;
+
+        let body = StatementsBlockParser.parse(state)?;
+
+        Some(Expression::For {
+            node_id: state.new_node_id(),
+            location: state.location_from(start),
+            pattern,
+            iterable: Box::new(iterable),
+            statements_block: body,
+        })
+    }
+
     fn parse_loop_expression(&self, state: &mut ParseState<'_, '_>) -> Option<Expression> {
         state.advance(); // `loop`
 
@@ -506,11 +1186,49 @@ impl PrimaryExpressionParser {
         let statements_block = StatementsBlockParser.parse(state)?;
 
         Some(Expression::Loop {
+            node_id: state.new_node_id(),
             location,
             statements_block,
         })
     }
 
+    fn parse_borrow_expression(&self, state: &mut ParseState<'_, '_>) -> Option<Expression> {
+        let start = state.next_token.location.start;
+        let is_double = state.next_token.raw == Punctuator::DoubleAmpersand;
+
+        state.advance(); // `&` or `&&`
+
+        let is_mutable = state.next_token.raw == Keyword::Mut;
+
+        if is_mutable {
+            state.advance();
+        }
+
+        let inner = ExpressionParser::new()
+            .with_precedence(prefix_operand_precedence(Punctuator::Ampersand.into()))
+            .prohibit_struct_expressions_if(self.prohibit_struct_expressions)
+            .parse(state)?;
+
+        // `&&a` is lexed as a single token, but means `&(&a)`
+        let borrow = Expression::Borrow {
+            node_id: state.new_node_id(),
+            location: state.location_from(if is_double { start + 1 } else { start }),
+            is_mutable,
+            inner: Box::new(inner),
+        };
+
+        Some(if is_double {
+            Expression::Borrow {
+                node_id: state.new_node_id(),
+                location: state.location_from(start),
+                is_mutable: false,
+                inner: Box::new(borrow),
+            }
+        } else {
+            borrow
+        })
+    }
+
     fn parse_prefix_expression(&self, state: &mut ParseState<'_, '_>) -> Option<Expression> {
         let operator_token = state.next_token;
         let operator: PrefixOperator = PrefixOperator {
@@ -520,11 +1238,12 @@ impl PrimaryExpressionParser {
         state.advance();
 
         let inner = ExpressionParser::new()
-            .with_precedence(Precedence::Unastellar)
+            .with_precedence(prefix_operand_precedence(operator_token.raw))
             .prohibit_struct_expressions_if(self.prohibit_struct_expressions)
             .parse(state)?;
 
         Some(Expression::Prefix {
+            node_id: state.new_node_id(),
             location: state.make_location(operator_token.location.start, inner.location().end),
             inner: Box::new(inner),
             operator,
@@ -542,32 +1261,56 @@ impl Parse for PrimaryExpressionParser {
             | RawToken::StringLiteral
             | RawToken::CharLiteral
             | RawToken::TrueBoolLiteral
-            | RawToken::FalseBoolLiteral => Some(Expression::Literal(LiteralParser.parse(state)?)),
+            | RawToken::FalseBoolLiteral => Some(Expression::Literal {
+                node_id: state.new_node_id(),
+                literal: LiteralParser.parse(state)?,
+            }),
             RawToken::Identifier => {
                 let symbol = state.lexer.scanned_identifier;
                 state.advance();
 
-                Some(Expression::Identifier(IdentifierAST {
-                    location: state.current_token.location,
-                    id: symbol,
-                }))
+                Some(Expression::Identifier {
+                    node_id: state.new_node_id(),
+                    identifier: IdentifierAST {
+                        location: state.current_token.location,
+                        id: symbol,
+                    },
+                })
             }
             RawToken::Punctuator(Punctuator::OpenParent) => {
                 self.parse_parenthesized_or_tuple_expression(state)
             }
             RawToken::Punctuator(Punctuator::OpenBracket) => self.parse_list_expression(state),
-            RawToken::Punctuator(Punctuator::OpenBrace) => self.parse_block_expression(state),
+            RawToken::Punctuator(Punctuator::OpenBrace) => {
+                if ExpressionParser::starts_map_expression(state) {
+                    self.parse_map_expression(state)
+                } else {
+                    self.parse_block_expression(state)
+                }
+            }
             RawToken::Punctuator(Punctuator::Or) | RawToken::Punctuator(Punctuator::DoubleOr) => {
                 self.parse_lambda_expression(state)
             }
+            RawToken::Punctuator(Punctuator::Ampersand | Punctuator::DoubleAmpersand) => {
+                self.parse_borrow_expression(state)
+            }
+            RawToken::Punctuator(Punctuator::DoubleDot | Punctuator::DoubleDotEq) => {
+                ExpressionParser::new()
+                    .prohibit_struct_expressions_if(self.prohibit_struct_expressions)
+                    .parse_range_expression(state, None)
+            }
             RawToken::Keyword(Keyword::If) => self.parse_if_expression(state),
             RawToken::Keyword(Keyword::Match) => self.parse_match_expression(state),
             RawToken::Keyword(Keyword::While) => self.parse_while_expression(state),
+            RawToken::Keyword(Keyword::For) => Self::parse_for_expression(state),
             RawToken::Keyword(Keyword::Loop) => self.parse_loop_expression(state),
+            RawToken::Keyword(Keyword::Unsafe) => self.parse_unsafe_expression(state),
+            RawToken::Keyword(Keyword::Try) => self.parse_try_expression(state),
             RawToken::Punctuator(Punctuator::Underscore) => {
                 state.advance();
 
                 Some(Expression::Underscore {
+                    node_id: state.new_node_id(),
                     location: state.current_token.location,
                 })
             }
@@ -591,3 +1334,10 @@ impl Parse for PrimaryExpressionParser {
         }
     }
 }
+
+/// Returns the precedence, with which the operand of a prefix operator is
+/// parsed, see [`stellar_ast::precedence::OPERATORS`].
+fn prefix_operand_precedence(token: RawToken) -> Precedence {
+    Operator::find(Fixity::Prefix, token)
+        .map_or(Precedence::Unastellar, |operator| operator.precedence)
+}
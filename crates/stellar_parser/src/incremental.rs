@@ -0,0 +1,544 @@
+//! Incremental reparsing of edited source files.
+//!
+//! Parsing a whole module again after every keystroke is wasteful: most edits
+//! only touch a single item. [`ParseResult::reparse`] finds items, that
+//! enclose an edit, parses only their new text and splices the new items into
+//! the module AST. Locations of the following items are moved by the length
+//! difference of the edit, so that they point into the new source:
+//!
+//! ```
+//! use stellar_database::{PackageData, Path, State};
+//! use stellar_diagnostics::Diagnostics;
+//! use stellar_filesystem::location::ByteOffset;
+//! use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+//! use stellar_parser::{incremental::TextEdit, parse_module};
+//!
+//! let source = "fun foo() { a(); }\nfun bar() {}";
+//!
+//! let mut state = State::new();
+//! let package =
+//!     PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("test"), DUMMY_PATH_ID);
+//! let mut result = parse_module(
+//!     &mut state,
+//!     package,
+//!     Path::from(IdentifierId::from("test")),
+//!     DUMMY_PATH_ID,
+//!     source,
+//! );
+//!
+//! // a(); -> a(); b();
+//! let edit = TextEdit::new(ByteOffset(16), ByteOffset(16), " b();");
+//! let source = edit.apply(source);
+//!
+//! assert!(result.reparse(&source, &edit, &mut Diagnostics::new()));
+//! let location = result.ast().items[1].location();
+//! assert_eq!(&source.as_str()[location], "bar");
+//! ```
+//!
+//! If the edit can't be handled locally, e.g. because it breaks an item or
+//! makes it consume the following ones, the whole module is parsed again.
+//!
+//! Items, that are not reparsed, keep their node IDs, while new nodes get IDs,
+//! that were never used in the module before, so side tables keyed by
+//! [`NodeId`] stay valid for the untouched items.
+//!
+//! [`NodeId`]: stellar_ast::NodeId
+
+use stellar_ast::{
+    token::RawToken, Attribute, BinaryOperator, CallArgument, Constant, DeferBody, Docstring, Enum,
+    EnumItem, Expression, ExternBlock, Function, FunctionParameter, FunctionSignature,
+    GenericParameter, IdentifierAST, Impl, ImportPath, Interface, LambdaCapture,
+    LambdaFunctionParameter, ListElement, Literal, MapEntry, MatchExpressionItem, Module,
+    ModuleItem, NegativeNumericLiteral, NotSelfFunctionParameter, Path, Pattern, PostfixOperator,
+    PrefixOperator, SelfFunctionParameter, Statement, Static, Struct, StructField,
+    StructFieldExpression, StructFieldPattern, Submodule, TupleField, TupleLikeStruct, Type,
+    TypeAlias, TypeConstructor, Visibility, WherePredicate,
+};
+use stellar_diagnostics::Diagnostics;
+use stellar_filesystem::location::{ByteOffset, Location};
+
+use crate::{items::ItemParser, Parse, ParseResult, ParseState};
+
+/// A replacement of a range of the source text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextEdit {
+    /// The start of the replaced range in the old source.
+    pub start: ByteOffset,
+
+    /// The end of the replaced range in the old source.
+    pub end: ByteOffset,
+
+    /// The text, that replaces the range.
+    pub text: String,
+}
+
+impl TextEdit {
+    /// Creates a new edit replacing a given range of the old source.
+    #[inline]
+    #[must_use]
+    pub fn new(start: ByteOffset, end: ByteOffset, text: impl Into<String>) -> Self {
+        Self {
+            start,
+            end,
+            text: text.into(),
+        }
+    }
+
+    /// Returns the end of the inserted text in the new source.
+    #[inline]
+    #[must_use]
+    pub const fn new_end(&self) -> ByteOffset {
+        ByteOffset(self.start.0 + self.text.len())
+    }
+
+    /// Applies the edit to the old source and returns the new one.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds or is not on character boundaries.
+    #[must_use]
+    pub fn apply(&self, source: &str) -> String {
+        let mut new_source = source.to_owned();
+        new_source.replace_range(self.start.0..self.end.0, &self.text);
+
+        new_source
+    }
+}
+
+/// Ends of items of a parsed module, which are used to find items enclosing
+/// an edit.
+///
+/// An item spans from the end of the previous one (or from the end of the
+/// module docstring) to its last token, so that comments and docstrings
+/// before an item belong to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ItemSpans {
+    /// The end of the module docstring, where the first item starts.
+    start: ByteOffset,
+
+    /// Ends of items in the source order.
+    ends: Vec<ByteOffset>,
+}
+
+/// Parses the module docstring and items, recording ends of items for
+/// [`ParseResult::reparse`].
+pub(crate) fn parse_module_ast(state: &mut ParseState<'_, '_>) -> (Module, ItemSpans) {
+    let docstring = state.consume_module_docstring();
+    let (items, ends) = parse_items(state, None);
+
+    (
+        Module {
+            filepath: state.lexer.filepath,
+            items,
+            docstring,
+        },
+        ItemSpans {
+            start: docstring.map_or(ByteOffset(0), |docstring| docstring.location.end),
+            ends,
+        },
+    )
+}
+
+/// Parses items until the end of file, or until an item, that starts at or
+/// after a given offset.
+fn parse_items(
+    state: &mut ParseState<'_, '_>,
+    end: Option<ByteOffset>,
+) -> (Vec<ModuleItem>, Vec<ByteOffset>) {
+    let mut items = vec![];
+    let mut ends = vec![];
+
+    while state.next_token.raw != RawToken::EndOfFile
+        && !state.cancellation_token.is_cancelled()
+        && end.is_none_or(|end| state.next_token.location.start < end)
+    {
+        if let Some(item) = ItemParser.parse(state) {
+            items.push(item);
+            ends.push(state.current_token.location.end);
+        }
+    }
+
+    (items, ends)
+}
+
+impl ParseResult {
+    /// Updates the module after an edit of its source.
+    ///
+    /// Only items enclosing the edit are parsed again, if possible. Otherwise,
+    /// the whole module is parsed again. `source` is the new source of the
+    /// module, see [`TextEdit::apply`]. Diagnostics are reported only for the
+    /// reparsed part of the module.
+    ///
+    /// Returns `true` if only items enclosing the edit were reparsed.
+    ///
+    /// Parse results of inline submodules, see [`extract_inline_modules()`],
+    /// don't know where their items are and are always parsed from scratch,
+    /// as if `source` contained only the submodule.
+    ///
+    /// If comments were collected, see [`ParseState::with_comments`], the
+    /// whole module is parsed again to keep them up to date.
+    ///
+    /// [`extract_inline_modules()`]: crate::extract_inline_modules
+    pub fn reparse(
+        &mut self,
+        source: &str,
+        edit: &TextEdit,
+        diagnostics: &mut Diagnostics,
+    ) -> bool {
+        if self.comments.is_none() && self.reparse_items(source, edit, diagnostics) {
+            return true;
+        }
+
+        let mut state = ParseState::new(self.ast.filepath, source, diagnostics)
+            .with_first_node_id(self.next_node_id);
+        if self.comments.is_some() {
+            state = state.with_comments();
+        }
+
+        let (ast, spans) = parse_module_ast(&mut state);
+
+        self.ast = ast;
+        self.item_spans = Some(spans);
+        self.next_node_id = state.next_node_id();
+        self.comments = state.comments;
+
+        false
+    }
+
+    /// Reparses items enclosing an edit. Returns `false` and leaves the module
+    /// as is, if the edit can't be handled locally.
+    fn reparse_items(
+        &mut self,
+        source: &str,
+        edit: &TextEdit,
+        diagnostics: &mut Diagnostics,
+    ) -> bool {
+        let Some(spans) = &mut self.item_spans else {
+            return false;
+        };
+
+        let new_end = edit.new_end();
+        let Some(old_len) = (source.len() + edit.end.0).checked_sub(new_end.0) else {
+            return false;
+        };
+
+        if edit.start < spans.start || edit.end < edit.start || edit.end.0 > old_len {
+            return false;
+        }
+
+        // items from the one containing the start of the edit to the one
+        // containing its end, the trailing comments are the last "item"
+        let first = spans.ends.partition_point(|end| *end <= edit.start);
+        let last = spans.ends.partition_point(|end| *end < edit.end).max(first);
+
+        let start = first
+            .checked_sub(1)
+            .map_or(spans.start, |idx| spans.ends[idx]);
+        let mut end = spans.ends.get(last).copied().unwrap_or(ByteOffset(old_len));
+        end.shift(edit);
+
+        let mut item_diagnostics = Diagnostics::new();
+        let mut state = ParseState::new_at(self.ast.filepath, source, start, &mut item_diagnostics)
+            .with_first_node_id(self.next_node_id);
+        let (items, ends) = parse_items(&mut state, Some(end));
+        let next_node_id = state.next_node_id();
+
+        let parsed_end = ends.last().copied().unwrap_or(start);
+
+        if !item_diagnostics.is_ok()
+            || parsed_end > end
+            || comment_continues(&source[start.0..end.0], parsed_end.0 - start.0)
+        {
+            return false;
+        }
+
+        let replaced = first..(last + 1).min(spans.ends.len());
+        let following = first + items.len();
+
+        self.ast.items.splice(replaced.clone(), items);
+        spans.ends.splice(replaced, ends);
+        self.next_node_id = next_node_id;
+
+        self.ast.items[following..].shift(edit);
+        spans.ends[following..].shift(edit);
+
+        diagnostics
+            .files_involved
+            .extend(item_diagnostics.files_involved);
+        diagnostics.diagnostics.extend(item_diagnostics.diagnostics);
+
+        true
+    }
+}
+
+/// Returns `true` if a comment after the last reparsed item doesn't end in the
+/// reparsed text, so it may hide tokens of the following items.
+fn comment_continues(text: &str, parsed_end: usize) -> bool {
+    text[parsed_end..]
+        .rsplit_once("//")
+        .is_some_and(|(_, comment)| !comment.contains('\n'))
+}
+
+/// Moves locations, that follow an edit, to their positions in the new source.
+trait Shift {
+    fn shift(&mut self, edit: &TextEdit);
+}
+
+impl Shift for ByteOffset {
+    #[inline]
+    fn shift(&mut self, edit: &TextEdit) {
+        self.0 = self.0 - edit.end.0 + edit.new_end().0;
+    }
+}
+
+impl Shift for Location {
+    #[inline]
+    fn shift(&mut self, edit: &TextEdit) {
+        self.start.shift(edit);
+        self.end.shift(edit);
+    }
+}
+
+impl<T: Shift> Shift for [T] {
+    fn shift(&mut self, edit: &TextEdit) {
+        for element in self {
+            element.shift(edit);
+        }
+    }
+}
+
+impl<T: Shift> Shift for Vec<T> {
+    #[inline]
+    fn shift(&mut self, edit: &TextEdit) {
+        self.as_mut_slice().shift(edit);
+    }
+}
+
+impl<T: Shift> Shift for Option<T> {
+    #[inline]
+    fn shift(&mut self, edit: &TextEdit) {
+        if let Some(value) = self {
+            value.shift(edit);
+        }
+    }
+}
+
+impl<T: Shift> Shift for Box<T> {
+    #[inline]
+    fn shift(&mut self, edit: &TextEdit) {
+        self.as_mut().shift(edit);
+    }
+}
+
+impl<A: Shift, B: Shift> Shift for (A, B) {
+    #[inline]
+    fn shift(&mut self, edit: &TextEdit) {
+        self.0.shift(edit);
+        self.1.shift(edit);
+    }
+}
+
+macro_rules! shift_fields {
+    ($($node:ident { $($field:ident),* }),* $(,)?) => {
+        $(
+            impl Shift for $node {
+                fn shift(&mut self, edit: &TextEdit) {
+                    $(self.$field.shift(edit);)*
+                }
+            }
+        )*
+    };
+}
+
+shift_fields! {
+    IdentifierAST { location },
+    Docstring { location },
+    Path { location, identifiers },
+    TypeConstructor { location, path, arguments },
+    GenericParameter { name, bounds, const_type, default_value },
+    WherePredicate { ty, bounds },
+    Attribute { location, path, arguments },
+    BinaryOperator { location },
+    PrefixOperator { location },
+    PostfixOperator { location },
+    LambdaFunctionParameter { name, ty },
+    LambdaCapture { location, name },
+    MatchExpressionItem { left, right },
+    StructFieldExpression { name, value },
+    CallArgument { name, value },
+    MapEntry { key, value },
+    ListElement { value },
+    SelfFunctionParameter { self_location, ty },
+    NotSelfFunctionParameter { pattern, ty },
+    FunctionSignature {
+        attributes, visibility, name, generic_parameters, parameters, return_type,
+        where_predicates, docstring
+    },
+    Function { signature, body, body_location },
+    TypeAlias { attributes, visibility, name, generic_parameters, value, docstring },
+    Constant { attributes, visibility, name, ty, value, docstring },
+    Static { attributes, visibility, name, ty, value, docstring },
+    Submodule { attributes, visibility, name, items, docstring },
+    TupleField { visibility, ty },
+    StructField { attributes, visibility, name, ty, docstring },
+    Interface {
+        attributes, visibility, name, generic_parameters, where_predicates, constants, methods,
+        inherits, docstring
+    },
+    Enum {
+        attributes, visibility, name, generic_parameters, where_predicates, items, methods,
+        implements, docstring
+    },
+    Struct {
+        attributes, visibility, name, generic_parameters, where_predicates, fields, methods,
+        implements, docstring
+    },
+    TupleLikeStruct {
+        attributes, visibility, name, generic_parameters, where_predicates, fields, methods,
+        implements, docstring
+    },
+    Impl {
+        location, attributes, generic_parameters, interface, ty, where_predicates, methods,
+        docstring
+    },
+    ExternBlock { location, attributes, functions, docstring },
+}
+
+macro_rules! shift_variants {
+    ($(
+        $node:ident {
+            $($variant:ident $({ $($field:ident),* })? $(($inner:ident))?),* $(,)?
+        }
+    ),* $(,)?) => {
+        $(
+            impl Shift for $node {
+                fn shift(&mut self, edit: &TextEdit) {
+                    match self {
+                        $(
+                            Self::$variant $({ $($field,)* .. })? $(($inner))? => {
+                                $($($field.shift(edit);)*)?
+                                $($inner.shift(edit);)?
+                            }
+                        )*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+shift_variants! {
+    Visibility { Private, Public(location), Package(location), Super(location) },
+    Literal {
+        Boolean { location },
+        Character { location },
+        String { location },
+        Integer { location },
+        Float { location },
+    },
+    NegativeNumericLiteral { Float { location }, Integer { location } },
+    ImportPath {
+        Single { path, as_ },
+        Glob { path },
+        Group { path, imports },
+    },
+    Pattern {
+        Literal(literal),
+        NegativeNumericLiteral(literal),
+        Identifier { location, identifier, pattern },
+        Wildcard { location },
+        Struct { location, path, fields },
+        TupleLike { location, path, inner_patterns },
+        Tuple { location, elements },
+        Path { path },
+        List { location, inner_patterns },
+        Grouped { location, inner },
+        Or { location, alternatives },
+        Range { location, start, end },
+        Rest { location },
+    },
+    StructFieldPattern {
+        NotRest { location, field_name, value_pattern },
+        Rest { location },
+    },
+    Type {
+        Constructor(constructor),
+        Tuple { location, element_types },
+        Function { location, parameter_types, return_type },
+        Parenthesized { location, inner },
+        Underscore { location },
+        InterfaceObject { location, bounds },
+        Const { location },
+        Array { location, element_type, size },
+        Reference { location, inner },
+        Optional { location, inner },
+    },
+    Expression {
+        List { location, elements },
+        ArrayRepeat { location, value, size },
+        Borrow { location, inner },
+        Map { location, entries },
+        As { location, left, right },
+        Loop { location, statements_block },
+        Binary { location, left, operator, right },
+        Range { location, start, end },
+        StatementsBlock { location, block },
+        Await { location, inner },
+        Unsafe { location, block },
+        Try { location, block },
+        Literal { literal },
+        Identifier { identifier },
+        Underscore { location },
+        Parenthesized { location, inner },
+        If { location, if_blocks, r#else },
+        IfLet { location, pattern, expression, block, r#else },
+        FieldAccess { location, left, right },
+        TupleIndex { location, left, index_location },
+        Prefix { location, inner, operator },
+        Postfix { location, inner, operator },
+        While { location, condition, statements_block },
+        WhileLet { location, pattern, expression, statements_block },
+        For { location, pattern, iterable, statements_block },
+        Call { location, callee, arguments },
+        TypeArguments { location, left, arguments },
+        Tuple { location, elements },
+        Struct { location, left, fields, rest },
+        Match { location, expression, block },
+        Lambda { location, captures, parameters, return_type, value },
+    },
+    DeferBody {
+        Call(call),
+        Block { location, block },
+    },
+    Statement {
+        Defer { body },
+        Expression { expression },
+        Break { location },
+        Continue { location },
+        Return { expression },
+        Yield { location, expression },
+        Let { pattern, value, ty },
+    },
+    EnumItem {
+        Just { name, docstring },
+        TupleLike { name, fields, docstring },
+        Struct { name, fields, docstring },
+    },
+    FunctionParameter {
+        NotSelfParameter(parameter),
+        SelfParameter(parameter),
+    },
+    ModuleItem {
+        Constant(item),
+        Enum(item),
+        Extern(item),
+        Function(item),
+        Impl(item),
+        Import { location, attributes, visibility, path },
+        Interface(item),
+        Module(item),
+        Static(item),
+        Struct(item),
+        TupleLikeStruct(item),
+        TypeAlias(item),
+    },
+}
@@ -1,17 +1,24 @@
 use stellar_ast::{
+    comments::CommentTarget,
     token::{Keyword, Punctuator, RawToken},
-    Enum, EnumItem, Function, FunctionParameter, FunctionSignature, IdentifierAST, Interface,
-    ModuleItem, NotSelfFunctionParameter, SelfFunctionParameter, Struct, StructField, TupleField,
-    TupleLikeStruct, TypeAlias, Visibility,
+    Attribute, Constant, Docstring, Enum, EnumItem, ExternBlock, Function, FunctionParameter,
+    FunctionSignature, IdentifierAST, Impl, Interface, ModuleItem, NotSelfFunctionParameter,
+    SelfFunctionParameter, Static, Struct, StructField, Submodule, TupleField, TupleLikeStruct,
+    Type, TypeAlias, Visibility,
 };
 use stellar_english_commons::enumeration::one_of;
 use stellar_interner::builtin_identifiers;
 
 use crate::{
+    attribute::AttributesParser,
     diagnostics::{
+        DeprecatedTraitKeyword, ExpectedInterfaceInImpl, ExternFunctionWithBody,
         UnnecessaryVisibilityQualifierContext, UnnecessaryVisibilityQualifierDiagnostic,
     },
+    event::NodeKind,
+    expression::ExpressionParser,
     list::ListParser,
+    options::Feature,
     path::ImportPathParser,
     pattern::PatternParser,
     r#type::{
@@ -23,6 +30,7 @@ use crate::{
 };
 
 struct ImportParser {
+    attributes: Vec<Attribute>,
     visibility: Visibility,
 }
 
@@ -32,31 +40,38 @@ impl Parse for ImportParser {
     fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
         let start = state.next_token.location.start;
 
-        if let Visibility::Public(location) = self.visibility {
-            state
-                .diagnostics
-                .add_diagnostic(UnnecessaryVisibilityQualifierDiagnostic {
-                    location,
-                    context: UnnecessaryVisibilityQualifierContext::Import,
-                });
-        }
-
         state.advance();
 
         let path = ImportPathParser.parse(state)?;
 
+        // only explicitly imported names can be re-exported
+        if let Some(location) = self.visibility.location_or_none() {
+            if path.contains_glob() {
+                state
+                    .diagnostics
+                    .add_diagnostic(UnnecessaryVisibilityQualifierDiagnostic {
+                        location,
+                        context: UnnecessaryVisibilityQualifierContext::GlobImport,
+                    });
+            }
+        }
+
         state.consume(Punctuator::Semicolon)?;
 
         Some(ModuleItem::Import {
+            node_id: state.new_node_id(),
             path,
+            attributes: self.attributes,
+            visibility: self.visibility,
             location: state.location_from(start),
         })
     }
 }
 
 struct StructFieldParser {
+    pub(crate) attributes: Vec<Attribute>,
     pub(crate) visibility: Visibility,
-    pub(crate) docstring: Option<String>,
+    pub(crate) docstring: Option<Docstring>,
 }
 
 impl Parse for StructFieldParser {
@@ -70,6 +85,7 @@ impl Parse for StructFieldParser {
         let ty = TypeParser.parse(state)?;
 
         Some(StructField {
+            attributes: self.attributes,
             visibility: self.visibility,
             name,
             ty,
@@ -85,16 +101,24 @@ impl Parse for StructFieldsParser {
 
     fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
         state.consume(Punctuator::OpenBrace)?;
+        state.start_comments_after_current_token();
 
         let fields = ListParser::new(&[RawToken::from(Punctuator::CloseBrace)], |state| {
-            StructFieldParser {
+            let start = state.next_token.location.start;
+            let field = StructFieldParser {
                 docstring: state.consume_local_docstring(),
+                attributes: AttributesParser.parse(state)?,
                 visibility: VisibilityParser.parse(state),
             }
-            .parse(state)
+            .parse(state)?;
+
+            state.attach_comments(CommentTarget::StructField(field.name.location), start);
+
+            Some(field)
         })
         .parse(state)?;
 
+        state.attach_dangling_comments();
         state.advance(); // `}`
 
         Some(fields)
@@ -102,8 +126,9 @@ impl Parse for StructFieldsParser {
 }
 
 struct StructParser {
+    pub(crate) attributes: Vec<Attribute>,
     pub(crate) visibility: Visibility,
-    pub(crate) docstring: Option<String>,
+    pub(crate) docstring: Option<Docstring>,
 }
 
 impl Parse for StructParser {
@@ -172,10 +197,15 @@ impl Parse for StructParser {
                         break;
                     }
 
+                    let docstring = state.consume_local_docstring();
+                    let attributes = AttributesParser.parse(state)?;
+                    let visibility = VisibilityParser.parse(state);
+
                     methods.push(
                         FunctionParser {
-                            visibility: VisibilityParser.parse(state),
-                            docstring: state.consume_local_docstring(),
+                            attributes,
+                            visibility,
+                            docstring,
                         }
                         .parse(state)?,
                     );
@@ -187,6 +217,8 @@ impl Parse for StructParser {
             state.advance();
 
             Some(ModuleItem::TupleLikeStruct(TupleLikeStruct {
+                node_id: state.new_node_id(),
+                attributes: self.attributes,
                 visibility: self.visibility,
                 name,
                 generic_parameters,
@@ -198,50 +230,80 @@ impl Parse for StructParser {
             }))
         } else if state.next_token.raw == Punctuator::OpenBrace {
             state.advance();
+            state.start_comments_after_current_token();
 
-            let fields = ListParser::new(
-                &[
-                    RawToken::from(Punctuator::CloseBrace),
-                    RawToken::from(Keyword::Fun),
-                    RawToken::from(Keyword::Pub),
-                ],
-                |state| {
-                    let docstring = state.consume_local_docstring();
-                    let visibility = VisibilityParser.parse(state);
-
-                    StructFieldParser {
-                        visibility,
-                        docstring,
-                    }
-                    .parse(state)
-                },
-            )
-            .parse(state)?;
-
+            let mut fields = vec![];
             let mut methods = vec![];
 
-            if state.next_token.raw == Keyword::Fun || state.next_token.raw == Keyword::Pub {
-                loop {
-                    if state.next_token.raw == Punctuator::CloseBrace {
-                        break;
-                    }
+            loop {
+                if state.next_token.raw == Punctuator::CloseBrace {
+                    state.attach_dangling_comments();
 
-                    let docstring = state.consume_local_docstring();
-                    let visibility = VisibilityParser.parse(state);
+                    break;
+                }
+
+                let start = state.next_token.location.start;
+                let docstring = state.consume_local_docstring();
+                let attributes = AttributesParser.parse(state)?;
+                let visibility = VisibilityParser.parse(state);
 
+                // fields must go before methods
+                if matches!(
+                    state.next_token.raw,
+                    RawToken::Keyword(
+                        Keyword::Fun | Keyword::Async | Keyword::Unsafe | Keyword::Gen
+                    )
+                ) || !methods.is_empty()
+                {
                     methods.push(
                         FunctionParser {
+                            attributes,
                             visibility,
                             docstring,
                         }
                         .parse(state)?,
                     );
+
+                    continue;
+                }
+
+                let field = StructFieldParser {
+                    attributes,
+                    visibility,
+                    docstring,
+                }
+                .parse(state)?;
+
+                state.attach_comments(CommentTarget::StructField(field.name.location), start);
+                fields.push(field);
+
+                match state.next_token.raw {
+                    RawToken::Punctuator(Punctuator::Comma) => state.advance(),
+                    RawToken::Punctuator(Punctuator::CloseBrace | Punctuator::HashTag)
+                    | RawToken::Keyword(
+                        Keyword::Fun
+                        | Keyword::Async
+                        | Keyword::Unsafe
+                        | Keyword::Gen
+                        | Keyword::Pub,
+                    )
+                    | RawToken::LocalDocComment => {}
+                    _ => {
+                        state.add_unexpected_token_diagnostic(one_of([
+                            Punctuator::Comma,
+                            Punctuator::CloseBrace,
+                        ]));
+
+                        return None;
+                    }
                 }
             }
 
             state.advance();
 
             Some(ModuleItem::Struct(Struct {
+                node_id: state.new_node_id(),
+                attributes: self.attributes,
                 visibility: self.visibility,
                 name,
                 generic_parameters,
@@ -280,14 +342,40 @@ impl Parse for NotSelfFunctionParameterParser {
 }
 
 struct FunctionParser {
+    attributes: Vec<Attribute>,
     visibility: Visibility,
-    docstring: Option<String>,
+    docstring: Option<Docstring>,
 }
 
 impl Parse for FunctionParser {
     type Output = Option<Function>;
 
     fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        let is_async = if state.next_token.raw == Keyword::Async {
+            state.advance();
+
+            true
+        } else {
+            false
+        };
+
+        let is_unsafe = if state.next_token.raw == Keyword::Unsafe {
+            state.advance();
+
+            true
+        } else {
+            false
+        };
+
+        let is_generator = if state.next_token.raw == Keyword::Gen {
+            state.advance();
+            state.check_feature(Feature::Generators, state.current_token.location);
+
+            true
+        } else {
+            false
+        };
+
         state.consume(Keyword::Fun)?;
 
         let name = state.consume_identifier()?;
@@ -330,9 +418,43 @@ impl Parse for FunctionParser {
 
         let where_predicates = WherePredicatesParser.optionally_parse(state)?;
 
+        let (body, body_location) = match state.next_token.raw {
+            RawToken::Punctuator(Punctuator::Semicolon) => {
+                state.advance();
+
+                (None, None)
+            }
+            RawToken::Punctuator(Punctuator::OpenBrace) => {
+                let start = state.next_token.location.start;
+
+                let body = if state.defer_function_bodies {
+                    state.skip_block()?;
+
+                    None
+                } else {
+                    Some(StatementsBlockParser.parse(state)?)
+                };
+
+                (body, Some(state.location_from(start)))
+            }
+            _ => {
+                state.add_unexpected_token_diagnostic(one_of([
+                    Punctuator::Semicolon,
+                    Punctuator::OpenBrace,
+                ]));
+
+                return None;
+            }
+        };
+
         Some(Function {
+            node_id: state.new_node_id(),
             signature: FunctionSignature {
+                attributes: self.attributes,
                 visibility: self.visibility,
+                is_async,
+                is_unsafe,
+                is_generator,
                 name,
                 generic_parameters,
                 parameters,
@@ -340,31 +462,149 @@ impl Parse for FunctionParser {
                 where_predicates,
                 docstring: self.docstring,
             },
-            body: match state.next_token.raw {
-                RawToken::Punctuator(Punctuator::Semicolon) => {
-                    state.advance();
+            body,
+            body_location,
+        })
+    }
+}
 
-                    None
-                }
-                RawToken::Punctuator(Punctuator::OpenBrace) => {
-                    Some(StatementsBlockParser.parse(state)?)
-                }
-                _ => {
-                    state.add_unexpected_token_diagnostic(one_of([
-                        Punctuator::Semicolon,
-                        Punctuator::OpenBrace,
-                    ]));
+struct ConstantParser {
+    attributes: Vec<Attribute>,
+    visibility: Visibility,
+    docstring: Option<Docstring>,
+}
+
+impl Parse for ConstantParser {
+    type Output = Option<Constant>;
 
-                    return None;
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        state.consume(Keyword::Const)?;
+
+        let name = state.consume_identifier()?;
+
+        state.consume(Punctuator::Colon)?;
+
+        let ty = TypeParser.parse(state)?;
+
+        let value = if state.next_token.raw == Punctuator::Eq {
+            state.advance();
+
+            Some(ExpressionParser::default().parse(state)?)
+        } else {
+            None
+        };
+
+        state.consume(Punctuator::Semicolon)?;
+
+        Some(Constant {
+            node_id: state.new_node_id(),
+            attributes: self.attributes,
+            visibility: self.visibility,
+            name,
+            ty,
+            value,
+            docstring: self.docstring,
+        })
+    }
+}
+
+struct SubmoduleParser {
+    attributes: Vec<Attribute>,
+    visibility: Visibility,
+    docstring: Option<Docstring>,
+}
+
+impl Parse for SubmoduleParser {
+    type Output = Option<Submodule>;
+
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        state.consume(Keyword::Module)?;
+
+        let name = state.consume_identifier()?;
+
+        let items = if state.next_token.raw == Punctuator::OpenBrace {
+            state.advance();
+
+            let mut items = vec![];
+
+            while !matches!(
+                state.next_token.raw,
+                RawToken::Punctuator(Punctuator::CloseBrace) | RawToken::EndOfFile
+            ) {
+                if let Some(item) = ItemParser.parse(state) {
+                    items.push(item);
                 }
-            },
+            }
+
+            state.consume(Punctuator::CloseBrace)?;
+
+            Some(items)
+        } else {
+            state.consume(Punctuator::Semicolon)?;
+
+            None
+        };
+
+        Some(Submodule {
+            node_id: state.new_node_id(),
+            attributes: self.attributes,
+            visibility: self.visibility,
+            name,
+            items,
+            docstring: self.docstring,
+        })
+    }
+}
+
+struct StaticParser {
+    attributes: Vec<Attribute>,
+    visibility: Visibility,
+    docstring: Option<Docstring>,
+}
+
+impl Parse for StaticParser {
+    type Output = Option<Static>;
+
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        state.consume(Keyword::Static)?;
+
+        let is_mutable = if state.next_token.raw == Keyword::Mut {
+            state.advance();
+
+            true
+        } else {
+            false
+        };
+
+        let name = state.consume_identifier()?;
+
+        state.consume(Punctuator::Colon)?;
+
+        let ty = TypeParser.parse(state)?;
+
+        state.consume(Punctuator::Eq)?;
+
+        let value = ExpressionParser::default().parse(state)?;
+
+        state.consume(Punctuator::Semicolon)?;
+
+        Some(Static {
+            node_id: state.new_node_id(),
+            attributes: self.attributes,
+            visibility: self.visibility,
+            is_mutable,
+            name,
+            ty,
+            value,
+            docstring: self.docstring,
         })
     }
 }
 
 struct TypeAliasParser {
+    attributes: Vec<Attribute>,
     visibility: Visibility,
-    docstring: Option<String>,
+    docstring: Option<Docstring>,
 }
 
 impl Parse for TypeAliasParser {
@@ -383,6 +623,8 @@ impl Parse for TypeAliasParser {
         state.consume(Punctuator::Semicolon)?;
 
         Some(ModuleItem::TypeAlias(TypeAlias {
+            node_id: state.new_node_id(),
+            attributes: self.attributes,
             visibility: self.visibility,
             name,
             generic_parameters,
@@ -393,8 +635,9 @@ impl Parse for TypeAliasParser {
 }
 
 struct InterfaceParser {
+    attributes: Vec<Attribute>,
     visibility: Visibility,
-    docstring: Option<String>,
+    docstring: Option<Docstring>,
 }
 
 impl Parse for InterfaceParser {
@@ -419,6 +662,7 @@ impl Parse for InterfaceParser {
 
         state.consume(Punctuator::OpenBrace)?;
 
+        let mut constants = vec![];
         let mut methods = vec![];
 
         loop {
@@ -426,13 +670,42 @@ impl Parse for InterfaceParser {
                 break;
             }
 
+            let docstring = state.consume_local_docstring();
+            let attributes = AttributesParser.parse(state)?;
+            let visibility = VisibilityParser.parse(state);
+
+            if state.next_token.raw == Keyword::Const {
+                let constant = ConstantParser {
+                    attributes,
+                    visibility,
+                    docstring,
+                }
+                .parse(state)?;
+
+                if let Some(location) = constant.visibility.location_or_none() {
+                    state
+                        .diagnostics
+                        .add_diagnostic(UnnecessaryVisibilityQualifierDiagnostic {
+                            location,
+                            context: UnnecessaryVisibilityQualifierContext::InterfaceConstant {
+                                name_location: constant.name.location,
+                            },
+                        });
+                }
+
+                constants.push(constant);
+
+                continue;
+            }
+
             let method = FunctionParser {
-                docstring: state.consume_local_docstring(),
-                visibility: VisibilityParser.parse(state),
+                attributes,
+                visibility,
+                docstring,
             }
             .parse(state)?;
 
-            if let Visibility::Public(location) = method.signature.visibility {
+            if let Some(location) = method.signature.visibility.location_or_none() {
                 state
                     .diagnostics
                     .add_diagnostic(UnnecessaryVisibilityQualifierDiagnostic {
@@ -449,10 +722,13 @@ impl Parse for InterfaceParser {
         state.advance();
 
         Some(ModuleItem::Interface(Interface {
+            node_id: state.new_node_id(),
+            attributes: self.attributes,
             visibility: self.visibility,
             name,
             generic_parameters,
             where_predicates,
+            constants,
             methods,
             inherits,
             docstring: self.docstring,
@@ -460,9 +736,156 @@ impl Parse for InterfaceParser {
     }
 }
 
+struct ImplParser {
+    attributes: Vec<Attribute>,
+    visibility: Visibility,
+    docstring: Option<Docstring>,
+}
+
+impl Parse for ImplParser {
+    type Output = Option<Impl>;
+
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        let start = state.next_token.location.start;
+
+        if let Some(location) = self.visibility.location_or_none() {
+            state
+                .diagnostics
+                .add_diagnostic(UnnecessaryVisibilityQualifierDiagnostic {
+                    location,
+                    context: UnnecessaryVisibilityQualifierContext::Impl,
+                });
+        }
+
+        state.advance();
+
+        let generic_parameters = GenericParametersParser.optionally_parse(state)?;
+
+        let ty = TypeParser.parse(state)?;
+
+        let (interface, ty) = if state.next_token.raw == Keyword::For {
+            state.advance();
+
+            let Type::Constructor(interface) = ty else {
+                state
+                    .diagnostics
+                    .add_diagnostic(ExpectedInterfaceInImpl::new(ty.location()));
+
+                return None;
+            };
+
+            (Some(interface), TypeParser.parse(state)?)
+        } else {
+            (None, ty)
+        };
+
+        let where_predicates = WherePredicatesParser.optionally_parse(state)?;
+
+        state.consume(Punctuator::OpenBrace)?;
+
+        let mut methods = vec![];
+
+        while state.next_token.raw != Punctuator::CloseBrace {
+            methods.push(
+                FunctionParser {
+                    docstring: state.consume_local_docstring(),
+                    attributes: AttributesParser.parse(state)?,
+                    visibility: VisibilityParser.parse(state),
+                }
+                .parse(state)?,
+            );
+        }
+
+        state.advance();
+
+        Some(Impl {
+            node_id: state.new_node_id(),
+            location: state.location_from(start),
+            attributes: self.attributes,
+            generic_parameters,
+            interface,
+            ty,
+            where_predicates,
+            methods,
+            docstring: self.docstring,
+        })
+    }
+}
+
+struct ExternBlockParser {
+    attributes: Vec<Attribute>,
+    visibility: Visibility,
+    docstring: Option<Docstring>,
+}
+
+impl Parse for ExternBlockParser {
+    type Output = Option<ExternBlock>;
+
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        let start = state.next_token.location.start;
+
+        if let Some(location) = self.visibility.location_or_none() {
+            state
+                .diagnostics
+                .add_diagnostic(UnnecessaryVisibilityQualifierDiagnostic {
+                    location,
+                    context: UnnecessaryVisibilityQualifierContext::Extern,
+                });
+        }
+
+        state.advance();
+
+        if state.next_token.raw != RawToken::StringLiteral {
+            state.add_unexpected_token_diagnostic("ABI string");
+
+            return None;
+        }
+
+        let abi = state.lexer.scanned_string();
+        state.advance();
+
+        state.consume(Punctuator::OpenBrace)?;
+
+        let mut functions = vec![];
+
+        while state.next_token.raw != Punctuator::CloseBrace {
+            let function = FunctionParser {
+                docstring: state.consume_local_docstring(),
+                attributes: AttributesParser.parse(state)?,
+                visibility: VisibilityParser.parse(state),
+            }
+            .parse(state)?;
+
+            // foreign functions are defined outside of the package
+            if let Some(body_location) = function.body_location {
+                state
+                    .diagnostics
+                    .add_diagnostic(ExternFunctionWithBody::new(
+                        function.signature.name,
+                        body_location,
+                    ));
+            }
+
+            functions.push(function.signature);
+        }
+
+        state.advance();
+
+        Some(ExternBlock {
+            node_id: state.new_node_id(),
+            location: state.location_from(start),
+            attributes: self.attributes,
+            abi,
+            functions,
+            docstring: self.docstring,
+        })
+    }
+}
+
 struct EnumParser {
+    attributes: Vec<Attribute>,
     visibility: Visibility,
-    docstring: Option<String>,
+    docstring: Option<Docstring>,
 }
 
 macro_rules! possibly_recover {
@@ -470,19 +893,8 @@ macro_rules! possibly_recover {
         if let Some(item) = $item {
             item
         } else {
-            loop {
-                match $state.next_token.raw {
-                    RawToken::Keyword(
-                        Keyword::Enum
-                        | Keyword::Import
-                        | Keyword::Struct
-                        | Keyword::Type
-                        | Keyword::Interface,
-                    )
-                    | RawToken::EndOfFile => break,
-                    _ => $state.advance(),
-                }
-            }
+            ItemParser::goto_next_valid_item($state);
+
             return None;
         }
     };
@@ -522,7 +934,11 @@ impl Parse for EnumParser {
         let items = ListParser::new(
             &[
                 RawToken::from(Punctuator::CloseBrace),
+                RawToken::from(Punctuator::HashTag),
                 RawToken::from(Keyword::Fun),
+                RawToken::from(Keyword::Async),
+                RawToken::from(Keyword::Unsafe),
+                RawToken::from(Keyword::Gen),
                 RawToken::from(Keyword::Pub),
             ],
             |state| EnumItemParser.parse(state),
@@ -537,11 +953,13 @@ impl Parse for EnumParser {
             }
 
             let docstring = state.consume_local_docstring();
+            let attributes = AttributesParser.parse(state)?;
             let visibility = VisibilityParser.parse(state);
 
             methods.push(possibly_recover!(
                 state,
                 FunctionParser {
+                    attributes,
                     visibility,
                     docstring,
                 }
@@ -552,6 +970,8 @@ impl Parse for EnumParser {
         state.advance(); // `}`
 
         Some(ModuleItem::Enum(Enum {
+            node_id: state.new_node_id(),
+            attributes: self.attributes,
             visibility: self.visibility,
             name,
             generic_parameters,
@@ -590,7 +1010,7 @@ impl Parse for EnumItemParser {
 
 struct EnumItemStructParser {
     name: IdentifierAST,
-    docstring: Option<String>,
+    docstring: Option<Docstring>,
 }
 
 impl Parse for EnumItemStructParser {
@@ -637,7 +1057,9 @@ impl Parse for ItemsParser {
     fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
         let mut items = vec![];
 
-        while state.next_token.raw != RawToken::EndOfFile {
+        while state.next_token.raw != RawToken::EndOfFile
+            && !state.cancellation_token.is_cancelled()
+        {
             if let Some(item) = ItemParser.parse(state) {
                 items.push(item);
             }
@@ -648,50 +1070,130 @@ impl Parse for ItemsParser {
 }
 
 impl ItemParser {
+    /// Skips tokens until the start of the next item, so that an error in one
+    /// item doesn't hide diagnostics in the following ones.
     fn goto_next_valid_item(state: &mut ParseState<'_, '_>) {
         loop {
             match state.next_token.raw {
                 RawToken::Keyword(
-                    Keyword::Enum
+                    Keyword::Const
+                    | Keyword::Enum
+                    | Keyword::Extern
+                    | Keyword::Fun
+                    | Keyword::Pub
+                    | Keyword::Impl
                     | Keyword::Import
+                    | Keyword::Static
                     | Keyword::Struct
                     | Keyword::Type
-                    | Keyword::Interface,
+                    | Keyword::Interface
+                    | Keyword::Module,
                 )
+                | RawToken::Punctuator(Punctuator::HashTag)
                 | RawToken::EndOfFile => break,
                 _ => state.advance(),
             }
         }
     }
-}
-
-pub(crate) struct ItemParser;
 
-impl Parse for ItemParser {
-    type Output = Option<ModuleItem>;
-
-    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+    fn parse_item(state: &mut ParseState<'_, '_>) -> Option<ModuleItem> {
         let docstring = state.consume_local_docstring();
+        let Some(attributes) = AttributesParser.parse(state) else {
+            Self::goto_next_valid_item(state);
+
+            return None;
+        };
         let visibility = VisibilityParser.parse(state);
 
         Some(match state.next_token.raw {
+            RawToken::Keyword(Keyword::Const) => ModuleItem::Constant(possibly_recover!(
+                state,
+                ConstantParser {
+                    attributes,
+                    visibility,
+                    docstring
+                }
+                .parse(state)
+            )),
             RawToken::Keyword(Keyword::Enum) => {
                 possibly_recover!(
                     state,
                     EnumParser {
+                        attributes,
                         visibility,
                         docstring
                     }
                     .parse(state)
                 )
             }
+            RawToken::Keyword(Keyword::Impl) => ModuleItem::Impl(possibly_recover!(
+                state,
+                ImplParser {
+                    attributes,
+                    visibility,
+                    docstring
+                }
+                .parse(state)
+            )),
+            RawToken::Keyword(Keyword::Extern) => ModuleItem::Extern(possibly_recover!(
+                state,
+                ExternBlockParser {
+                    attributes,
+                    visibility,
+                    docstring
+                }
+                .parse(state)
+            )),
             RawToken::Keyword(Keyword::Import) => {
-                possibly_recover!(state, ImportParser { visibility }.parse(state))
+                possibly_recover!(
+                    state,
+                    ImportParser {
+                        attributes,
+                        visibility
+                    }
+                    .parse(state)
+                )
             }
+            RawToken::Keyword(Keyword::Module) => ModuleItem::Module(possibly_recover!(
+                state,
+                SubmoduleParser {
+                    attributes,
+                    visibility,
+                    docstring
+                }
+                .parse(state)
+            )),
+            RawToken::Keyword(Keyword::Static) => ModuleItem::Static(possibly_recover!(
+                state,
+                StaticParser {
+                    attributes,
+                    visibility,
+                    docstring
+                }
+                .parse(state)
+            )),
             RawToken::Keyword(Keyword::Struct) => {
                 possibly_recover!(
                     state,
                     StructParser {
+                        attributes,
+                        visibility,
+                        docstring
+                    }
+                    .parse(state)
+                )
+            }
+            RawToken::Identifier
+                if state.lexer.scanned_identifier == builtin_identifiers::TRAIT =>
+            {
+                state
+                    .diagnostics
+                    .add_diagnostic(DeprecatedTraitKeyword::new(state.next_token.location));
+
+                possibly_recover!(
+                    state,
+                    InterfaceParser {
+                        attributes,
                         visibility,
                         docstring
                     }
@@ -702,23 +1204,28 @@ impl Parse for ItemParser {
                 possibly_recover!(
                     state,
                     InterfaceParser {
+                        attributes,
                         visibility,
                         docstring
                     }
                     .parse(state)
                 )
             }
-            RawToken::Keyword(Keyword::Fun) => ModuleItem::Function(possibly_recover!(
-                state,
-                FunctionParser {
-                    visibility,
-                    docstring
-                }
-                .parse(state)
-            )),
+            RawToken::Keyword(Keyword::Fun | Keyword::Async | Keyword::Unsafe | Keyword::Gen) => {
+                ModuleItem::Function(possibly_recover!(
+                    state,
+                    FunctionParser {
+                        attributes,
+                        visibility,
+                        docstring
+                    }
+                    .parse(state)
+                ))
+            }
             RawToken::Keyword(Keyword::Type) => possibly_recover!(
                 state,
                 TypeAliasParser {
+                    attributes,
                     visibility,
                     docstring
                 }
@@ -734,3 +1241,13 @@ impl Parse for ItemParser {
         })
     }
 }
+
+pub(crate) struct ItemParser;
+
+impl Parse for ItemParser {
+    type Output = Option<ModuleItem>;
+
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        state.node(NodeKind::Item, Self::parse_item)
+    }
+}
@@ -3,6 +3,26 @@
 //! It uses the lexer from the [`stellar_lexer`] crate to tokenize the input source
 //! code and produces an Abstract Syntax Tree (AST) that represents the parsed code.
 //!
+//! Besides whole modules, single items, statements, expressions, types and
+//! patterns can be parsed on their own, which is useful for tests and tools
+//! working with code fragments:
+//!
+//! ```
+//! use stellar_diagnostics::Diagnostics;
+//! use stellar_interner::DUMMY_PATH_ID;
+//! use stellar_parser::{parse_item, parse_statement, parse_type};
+//!
+//! let mut diagnostics = Diagnostics::new();
+//!
+//! assert!(parse_item(DUMMY_PATH_ID, "fun main() {}", &mut diagnostics).is_some());
+//! assert!(parse_statement(DUMMY_PATH_ID, "let a = 1;", &mut diagnostics).is_some());
+//! assert!(parse_type(DUMMY_PATH_ID, "List[int32]", &mut diagnostics).is_some());
+//! assert!(diagnostics.is_ok());
+//!
+//! assert!(parse_type(DUMMY_PATH_ID, "List[", &mut diagnostics).is_none());
+//! assert!(!diagnostics.is_ok());
+//! ```
+//!
 //! [`stellar_lexer`]: ../stellar_lexer/index.html
 
 #![doc(
@@ -64,32 +84,55 @@
     clippy::unnested_or_patterns
 )]
 
+mod attribute;
+pub mod cst;
 pub mod diagnostics;
+pub mod event;
 mod expression;
+pub mod incremental;
 mod items;
 mod list;
 mod literal;
+pub mod options;
 mod path;
 mod pattern;
+pub mod query;
 mod statement;
 mod r#type;
 
 #[cfg(feature = "debug")]
 use std::time::Instant;
-use std::{fs, io};
-
-use diagnostics::LexErrorDiagnostic;
+use std::{fs, io, mem, sync::Arc};
+
+// only used by benchmarks
+#[cfg(test)]
+use criterion as _;
+use cst::SyntaxTree;
+use diagnostics::{
+    AmbiguousLineBreak, FeatureNotEnabled, FuelExhausted, LexErrorDiagnostic, NestingLimitReached,
+    SubmoduleNotFound, UnknownFeature, UnknownVisibilityRestriction,
+};
+use event::{Event, Marker, NodeKind};
+use expression::can_start_and_continue_expression;
 pub use expression::ExpressionParser;
+use incremental::{parse_module_ast, ItemSpans};
 use items::{ItemParser, ItemsParser};
+use options::{Feature, ParseOptions};
 use pattern::PatternParser;
+use query::{ParseFile, SourceText};
 use r#type::TypeParser;
-use statement::StatementParser;
+use statement::{StatementParser, StatementsBlockParser};
 use stellar_ast::{
-    token::{Keyword, LexError, RawToken, Token},
-    Expression, IdentifierAST, Module, ModuleItem, Pattern, Statement, Type, Visibility,
+    comments::{Comment, CommentTarget, Comments},
+    token::{Keyword, LexError, Punctuator, RawToken, Token},
+    Docstring, Expression, IdentifierAST, IntegerValue, Module, ModuleItem, NodeId, Pattern,
+    Statement, Type, Visibility,
+};
+use stellar_database::{
+    cancellation::CancellationToken, ModuleData, ModuleId, PackageId, Path, State,
 };
-use stellar_database::{ModuleData, ModuleId, PackageId, Path, State};
 use stellar_diagnostics::Diagnostics;
+use stellar_english_commons::enumeration::all_of;
 use stellar_filesystem::location::{ByteOffset, Location};
 use stellar_interner::PathId;
 use stellar_lexer::Lexer;
@@ -112,6 +155,59 @@ pub struct ParseState<'s, 'd> {
 
     /// Diagnostics that is emitted during parsing.
     diagnostics: &'d mut Diagnostics,
+
+    /// Whether to skip function bodies, only recording their locations.
+    defer_function_bodies: bool,
+
+    /// Whether common operands are parsed without the precedence loop, see
+    /// [`ParseState::without_fast_operands`].
+    fast_operands: bool,
+
+    /// Checked before every item to stop parsing early.
+    cancellation_token: CancellationToken,
+
+    /// Whether statements can end at line breaks, see
+    /// [`ParseState::with_newline_termination`].
+    newline_termination: bool,
+
+    /// Whether line breaks are significant at the current position: they are
+    /// in statements blocks, but not inside of brackets.
+    significant_newlines: bool,
+
+    /// Recorded parse events, `None` if recording is disabled, see
+    /// [`crate::event`].
+    events: Option<Vec<Event>>,
+
+    /// Features, that can be used, `None` if experimental syntax is not
+    /// gated, see [`ParseState::with_options`].
+    enabled_features: Option<Vec<Feature>>,
+
+    /// How many syntax nodes enclose the current position.
+    depth: usize,
+
+    /// See [`ParseState::with_max_depth`].
+    max_depth: usize,
+
+    /// How many nodes are folded in chains around the current position, see
+    /// [`ParseState::enter_chain`].
+    chain_length: usize,
+
+    /// See [`ParseState::with_max_chain_length`].
+    max_chain_length: usize,
+
+    /// How many tokens can be consumed together with the initial amount,
+    /// `None` if unlimited, see [`ParseState::with_fuel`].
+    fuel: Option<(usize, usize)>,
+
+    /// ID of the next parsed node, see [`ParseState::with_first_node_id`].
+    next_node_id: NodeId,
+
+    /// Collected comments, `None` if collection is disabled, see
+    /// [`ParseState::with_comments`].
+    comments: Option<Comments>,
+
+    /// Comments before this offset are already collected.
+    comments_cursor: ByteOffset,
 }
 
 /// Represents AST node that can be parsed.
@@ -148,6 +244,17 @@ pub trait OptionallyParse: Sized {
 pub struct ParseResult {
     module: ModuleId,
     ast: Module,
+
+    /// Where items of the module are, `None` if the module is not parsed from
+    /// a whole file, see [`ParseResult::reparse`].
+    item_spans: Option<ItemSpans>,
+
+    /// ID of the next node, that is parsed into the module, see
+    /// [`ParseResult::reparse`].
+    next_node_id: NodeId,
+
+    /// Collected comments, see [`ParseState::with_comments`].
+    comments: Option<Comments>,
 }
 
 impl ParseResult {
@@ -155,7 +262,13 @@ impl ParseResult {
     #[inline]
     #[must_use]
     pub const fn new(module: ModuleId, ast: Module) -> Self {
-        Self { module, ast }
+        Self {
+            module,
+            ast,
+            item_spans: None,
+            next_node_id: NodeId(0),
+            comments: None,
+        }
     }
 
     /// Returns the module AST.
@@ -165,6 +278,22 @@ impl ParseResult {
         &self.ast
     }
 
+    /// Returns the ID, that will be assigned to the next node parsed into the
+    /// module, see [`ParseState::with_first_node_id`].
+    #[inline]
+    #[must_use]
+    pub const fn next_node_id(&self) -> NodeId {
+        self.next_node_id
+    }
+
+    /// Returns comments of the module, `None` if they were not collected, see
+    /// [`ParseState::with_comments`].
+    #[inline]
+    #[must_use]
+    pub const fn comments(&self) -> Option<&Comments> {
+        self.comments.as_ref()
+    }
+
     /// Returns the ID of the module in database.
     #[inline]
     #[must_use]
@@ -204,20 +333,26 @@ pub fn read_and_parse_module(
     let module = ModuleData::alloc(state.db_mut(), package, path, filepath);
     let source = fs::read_to_string(filepath.as_path())?;
 
-    let mut parse_state = ParseState::new(filepath, &source, state.diagnostics_mut());
+    let token = state.cancellation_token().clone();
+    let mut parse_state =
+        ParseState::new(filepath, &source, state.diagnostics_mut()).with_cancellation_token(token);
+    let (ast, item_spans) = parse_module_ast(&mut parse_state);
 
-    Ok(ParseResult::new(
+    Ok(ParseResult {
         module,
-        Module {
-            filepath: parse_state.lexer.filepath,
-            docstring: parse_state.consume_module_docstring(),
-            items: ItemsParser.parse(&mut parse_state),
-        },
-    ))
+        ast,
+        item_spans: Some(item_spans),
+        next_node_id: parse_state.next_node_id,
+        comments: None,
+    })
 }
 
 /// Parse a Stellar module.
-#[inline]
+///
+/// If the state memoizes results of analysis (see [`State::with_queries`]), the
+/// AST is taken from the [`ParseFile`] query, so the file is not parsed again,
+/// if its source didn't change since the queries were last used, see
+/// [`query`].
 #[must_use]
 pub fn parse_module(
     state: &mut State,
@@ -227,19 +362,42 @@ pub fn parse_module(
     source: &str,
 ) -> ParseResult {
     let module = ModuleData::alloc(state.db_mut(), package, path, filepath);
-    let mut parse_state = ParseState::new(filepath, source, state.diagnostics_mut());
+
+    // a partial AST of cancelled analysis must not be memoized
+    if state.queries().is_none() || state.is_cancelled() {
+        let token = state.cancellation_token().clone();
+        let mut parse_state = ParseState::new(filepath, source, state.diagnostics_mut())
+            .with_cancellation_token(token);
+        let (ast, item_spans) = parse_module_ast(&mut parse_state);
+
+        return ParseResult {
+            module,
+            ast,
+            item_spans: Some(item_spans),
+            next_node_id: parse_state.next_node_id,
+            comments: None,
+        };
+    }
+
+    let queries = state.queries_mut().unwrap();
+    queries.set::<SourceText>(filepath, Arc::from(source));
+    let parsed = queries.get::<ParseFile>(&filepath);
+
+    state.diagnostics_mut().merge(parsed.diagnostics().clone());
 
     ParseResult {
         module,
-        ast: Module {
-            filepath: parse_state.lexer.filepath,
-            docstring: parse_state.consume_module_docstring(),
-            items: ItemsParser.parse(&mut parse_state),
-        },
+        ast: parsed.ast().clone(),
+        item_spans: Some(parsed.item_spans().clone()),
+        next_node_id: parsed.next_node_id(),
+        comments: None,
     }
 }
 
 /// Parse a Stellar module using a given parse state.
+///
+/// If the state collects comments, see [`ParseState::with_comments`], they
+/// are available in [`ParseResult::comments`].
 #[inline]
 #[must_use]
 pub fn parse_module_using(
@@ -248,14 +406,149 @@ pub fn parse_module_using(
     path: Path,
     mut parse_state: ParseState<'_, '_>,
 ) -> ParseResult {
-    ParseResult::new(
-        ModuleData::alloc(state.db_mut(), package, path, parse_state.lexer.filepath),
-        Module {
-            filepath: parse_state.lexer.filepath,
-            docstring: parse_state.consume_module_docstring(),
-            items: ItemsParser.parse(&mut parse_state),
-        },
-    )
+    let module = ModuleData::alloc(state.db_mut(), package, path, parse_state.lexer.filepath);
+    let (ast, item_spans) = parse_module_ast(&mut parse_state);
+
+    ParseResult {
+        module,
+        ast,
+        item_spans: Some(item_spans),
+        next_node_id: parse_state.next_node_id,
+        comments: parse_state.comments,
+    }
+}
+
+/// Moves inline submodules of a parsed module, e.g. `module utils { ... }`, into
+/// separate modules, which are allocated in the database and added to
+/// submodules of their parents.
+///
+/// Inline submodules are replaced with declarations (`module utils;`) in their
+/// parents. Returns parse results of all inline submodules, nested ones
+/// included.
+pub fn extract_inline_modules(
+    state: &mut State,
+    parse_result: &mut ParseResult,
+) -> Vec<ParseResult> {
+    fn extract(
+        state: &mut State,
+        parent: ModuleId,
+        ast: &mut Module,
+        extracted: &mut Vec<ParseResult>,
+    ) {
+        for item in &mut ast.items {
+            let ModuleItem::Module(submodule) = item else {
+                continue;
+            };
+
+            let Some(items) = submodule.items.take() else {
+                continue;
+            };
+
+            let mut segments = parent.path(state.db()).segments().to_vec();
+            segments.push(submodule.name.id);
+
+            let module = ModuleData::alloc(
+                state.db_mut(),
+                parent.package(),
+                Path::new(segments),
+                ast.filepath,
+            );
+            parent.add_submodule(state.db_mut(), module);
+
+            let mut result = ParseResult::new(
+                module,
+                Module {
+                    filepath: ast.filepath,
+                    items,
+                    docstring: submodule.docstring,
+                },
+            );
+
+            extract(state, module, &mut result.ast, extracted);
+            extracted.push(result);
+        }
+    }
+
+    let mut extracted = vec![];
+    extract(
+        state,
+        parse_result.module,
+        &mut parse_result.ast,
+        &mut extracted,
+    );
+
+    // submodules are reparsed from scratch, but their new nodes must not
+    // reuse IDs of the parent
+    for result in &mut extracted {
+        result.next_node_id = parse_result.next_node_id;
+    }
+
+    extracted
+}
+
+/// Checks that every submodule declaration, e.g. `module utils;`, refers to an
+/// existing submodule.
+///
+/// Must be called after all modules of a package are parsed and linked together.
+pub fn check_submodule_declarations(state: &mut State, parse_results: &[ParseResult]) {
+    for parse_result in parse_results {
+        if state.is_cancelled() {
+            return;
+        }
+
+        for item in &parse_result.ast.items {
+            let ModuleItem::Module(submodule) = item else {
+                continue;
+            };
+
+            if !submodule.is_inline()
+                && parse_result
+                    .module
+                    .submodule(state.db(), submodule.name.id)
+                    .is_none()
+            {
+                state
+                    .diagnostics_mut()
+                    .add_diagnostic(SubmoduleNotFound::new(
+                        submodule.name.location,
+                        submodule.name.id,
+                    ));
+            }
+        }
+    }
+}
+
+/// Parse a module, recording parse events instead of returning the AST.
+///
+/// See [`event`] for more details.
+#[must_use]
+pub fn parse_events(
+    filepath: PathId,
+    source: impl AsRef<str>,
+    diagnostics: &mut Diagnostics,
+) -> Vec<Event> {
+    let mut state = ParseState::new(filepath, source.as_ref(), diagnostics).with_events();
+
+    state.consume_module_docstring();
+    ItemsParser.parse(&mut state);
+
+    state.events.unwrap_or_default()
+}
+
+/// Parse a module into a lossless concrete syntax tree.
+///
+/// See [`cst`] for more details.
+#[inline]
+#[must_use]
+pub fn parse_cst(
+    filepath: PathId,
+    source: impl AsRef<str>,
+    diagnostics: &mut Diagnostics,
+) -> SyntaxTree {
+    let source = source.as_ref();
+    let events = parse_events(filepath, source, diagnostics);
+
+    SyntaxTree::build(filepath, source, &events)
 }
 
 /// Parse an item.
@@ -276,6 +569,39 @@ pub fn parse_item_using(state: &mut ParseState<'_, '_>) -> Option<ModuleItem> {
     ItemParser.parse(state)
 }
 
+/// Parse a function body, which was skipped by the parser before.
+///
+/// Node IDs of the body start from zero, use [`parse_function_body_using()`]
+/// with [`ParseState::with_first_node_id`] to continue numbering of the module.
+///
+/// See [`ParseState::with_deferred_function_bodies`] for more details.
+#[inline]
+#[must_use]
+pub fn parse_function_body(
+    filepath: PathId,
+    source: impl AsRef<str>,
+    body_location: Location,
+    diagnostics: &mut Diagnostics,
+) -> Option<Vec<Statement>> {
+    parse_function_body_using(&mut ParseState::new_at(
+        filepath,
+        source.as_ref(),
+        body_location.start,
+        diagnostics,
+    ))
+}
+
+/// Parse a function body, which was skipped by the parser before, using a
+/// state created with [`ParseState::new_at`] at the start of the body.
+///
+/// Unlike [`parse_function_body()`], allows to configure the state, for
+/// example with the same [`options`] as the rest of the module.
+#[inline]
+#[must_use]
+pub fn parse_function_body_using(state: &mut ParseState<'_, '_>) -> Option<Vec<Statement>> {
+    StatementsBlockParser.parse(state)
+}
+
 /// Parse an expression.
 #[inline]
 #[must_use]
@@ -410,11 +736,35 @@ pub fn parse_pattern_using(state: &mut ParseState<'_, '_>) -> Option<Pattern> {
 // }
 
 impl<'s, 'd> ParseState<'s, 'd> {
+    /// The default nesting limit, see [`ParseState::with_max_depth`].
+    pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+    /// The default limit of chained operands, see
+    /// [`ParseState::with_max_chain_length`].
+    pub const DEFAULT_MAX_CHAIN_LENGTH: usize = 512;
+
     /// Creates an initial parse state from file source.
     #[must_use]
     pub fn new(filepath: PathId, source: &'s str, diagnostics: &'d mut Diagnostics) -> Self {
-        let mut lexer = Lexer::new(filepath, source);
+        Self::from_lexer(Lexer::new(filepath, source), diagnostics)
+    }
+
+    /// Creates a parse state, which resumes parsing the file source from
+    /// a given byte offset.
+    ///
+    /// # Panics
+    /// Panics if the offset is out of bounds or is not on a character boundary.
+    #[must_use]
+    pub fn new_at(
+        filepath: PathId,
+        source: &'s str,
+        offset: ByteOffset,
+        diagnostics: &'d mut Diagnostics,
+    ) -> Self {
+        Self::from_lexer(Lexer::new_at(filepath, source, offset), diagnostics)
+    }
 
+    fn from_lexer(mut lexer: Lexer<'s>, diagnostics: &'d mut Diagnostics) -> Self {
         let current_token = lexer.next_no_comments();
         let next_token = current_token;
 
@@ -423,12 +773,388 @@ impl<'s, 'd> ParseState<'s, 'd> {
             current_token,
             next_token,
             diagnostics,
+            defer_function_bodies: false,
+            fast_operands: true,
+            cancellation_token: CancellationToken::default(),
+            newline_termination: false,
+            significant_newlines: false,
+            events: None,
+            enabled_features: None,
+            depth: 0,
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            chain_length: 0,
+            max_chain_length: Self::DEFAULT_MAX_CHAIN_LENGTH,
+            fuel: None,
+            next_node_id: NodeId::default(),
+            comments: None,
+            comments_cursor: ByteOffset::default(),
         };
         state.check_next_token();
 
         state
     }
 
+    /// Makes the parser skip function bodies, only recording their locations
+    /// in [`Function::body_location`]. Skipped bodies can be parsed later using
+    /// [`parse_function_body()`].
+    ///
+    /// Useful when only signatures are needed, for example when indexing
+    /// symbols of the whole workspace.
+    ///
+    /// [`Function::body_location`]: stellar_ast::Function::body_location
+    #[inline]
+    #[must_use]
+    pub const fn with_deferred_function_bodies(mut self) -> Self {
+        self.defer_function_bodies = true;
+        self
+    }
+
+    /// Makes the parser parse literals, identifiers, calls and field accesses
+    /// through the precedence loop of [`ExpressionParser`], like all other
+    /// expressions, instead of parsing them directly.
+    ///
+    /// The resulting AST is the same, only parsing is slower, so this is
+    /// useful to check and to measure the direct parsing.
+    #[inline]
+    #[must_use]
+    pub const fn without_fast_operands(mut self) -> Self {
+        self.fast_operands = false;
+        self
+    }
+
+    /// Makes the parser stop before the next item, once a given token is
+    /// cancelled. Items parsed before that are kept.
+    ///
+    /// See [`stellar_database::cancellation`] for more details.
+    #[inline]
+    #[must_use]
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = token;
+        self
+    }
+
+    /// Enables the experimental mode, in which statements inside of blocks can
+    /// end at line breaks instead of semicolons:
+    ///
+    /// 1. A statement, that needs a semicolon, can instead end at a line break
+    ///    or before `}`.
+    /// 2. An expression continues on the next line, if the line starts with
+    ///    a token, that can only continue it, e.g. `.`, `*` or `as`.
+    /// 3. If the line starts with a token, that can both start and continue an
+    ///    expression (`(`, `[`, `{`, `+`, `-`, `++`, `--`, `..`, `..=`, `|`,
+    ///    `||`), the line starts a new statement and a warning is emitted.
+    ///
+    /// Line breaks inside of parentheses, brackets and braces of lists are
+    /// ignored.
+    #[cfg(feature = "newline-termination")]
+    #[inline]
+    #[must_use]
+    pub const fn with_newline_termination(mut self) -> Self {
+        self.newline_termination = true;
+        self
+    }
+
+    /// Makes the parser reject experimental syntax, unless its feature is
+    /// enabled in the options or is stable in the edition, see
+    /// [`crate::options`].
+    ///
+    /// Unknown enabled features are reported right away.
+    #[must_use]
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        let mut enabled_features = Feature::ALL
+            .into_iter()
+            .filter(|feature| {
+                feature
+                    .stable_since()
+                    .is_some_and(|edition| edition <= options.edition)
+            })
+            .collect::<Vec<_>>();
+
+        for name in options.enabled_features {
+            if let Some(feature) = Feature::from_name(&name.id.as_str()) {
+                enabled_features.push(feature);
+            } else {
+                self.diagnostics.add_diagnostic(UnknownFeature::new(
+                    name,
+                    all_of(Feature::ALL.iter().map(|feature| format!("`{feature}`"))),
+                ));
+            }
+        }
+
+        self.enabled_features = Some(enabled_features);
+        self
+    }
+
+    /// Reports a diagnostic, if syntax at a given location needs a feature,
+    /// that is not enabled, see [`ParseState::with_options`].
+    fn check_feature(&mut self, feature: Feature, location: Location) {
+        if self
+            .enabled_features
+            .as_ref()
+            .is_some_and(|features| !features.contains(&feature))
+        {
+            self.diagnostics
+                .add_diagnostic(FeatureNotEnabled::new(location, feature.name().to_owned()));
+        }
+    }
+
+    /// Sets how deep expressions, statements, patterns, types and items can be
+    /// nested, [`ParseState::DEFAULT_MAX_DEPTH`] by default.
+    ///
+    /// Deeper syntax is reported instead of being parsed, so that malicious or
+    /// generated input, e.g. thousands of nested parentheses, can't overflow
+    /// the stack. Chains of operators, calls and field accesses have a
+    /// separate limit, see [`ParseState::with_max_chain_length`].
+    #[inline]
+    #[must_use]
+    pub const fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets how many operands can be chained with operators, calls and field
+    /// accesses, e.g. in `a + b + c` or `a.b().c`,
+    /// [`ParseState::DEFAULT_MAX_CHAIN_LENGTH`] by default.
+    ///
+    /// Chains are flat in the source, but every operand adds a level to the
+    /// AST, that is later traversed recursively. Chains are common in real
+    /// code, so the limit is much higher than [`ParseState::with_max_depth`].
+    /// It applies to all chains enclosing the current position together.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_chain_length(mut self, max_chain_length: usize) -> Self {
+        self.max_chain_length = max_chain_length;
+        self
+    }
+
+    /// Limits how many tokens the parser can consume. Once the fuel is
+    /// exhausted, a diagnostic is reported and the parser stops, as if the
+    /// file ended there.
+    ///
+    /// Useful to bound the time spent on untrusted input, e.g. in fuzzing.
+    #[inline]
+    #[must_use]
+    pub const fn with_fuel(mut self, fuel: usize) -> Self {
+        self.fuel = Some((fuel, fuel));
+        self
+    }
+
+    /// Makes the parser assign node IDs starting from a given one instead of
+    /// zero, see [`NodeId`].
+    ///
+    /// Useful when nodes are added to an already parsed module, for example
+    /// when a deferred function body is parsed, so that their IDs don't clash
+    /// with the existing ones. See [`ParseState::next_node_id`].
+    #[inline]
+    #[must_use]
+    pub const fn with_first_node_id(mut self, node_id: NodeId) -> Self {
+        self.next_node_id = node_id;
+        self
+    }
+
+    /// Returns the ID, that will be assigned to the next parsed node.
+    #[inline]
+    #[must_use]
+    pub const fn next_node_id(&self) -> NodeId {
+        self.next_node_id
+    }
+
+    /// Returns a new node ID.
+    #[inline]
+    pub(crate) const fn new_node_id(&mut self) -> NodeId {
+        let node_id = self.next_node_id;
+        self.next_node_id = node_id.next();
+
+        node_id
+    }
+
+    /// Makes the parser record parse events, see [`crate::event`].
+    #[inline]
+    #[must_use]
+    pub fn with_events(mut self) -> Self {
+        self.events = Some(vec![]);
+        self
+    }
+
+    /// Returns parse events recorded so far, see [`ParseState::with_events`].
+    #[inline]
+    #[must_use]
+    pub fn events(&self) -> &[Event] {
+        self.events.as_deref().unwrap_or_default()
+    }
+
+    /// Makes the parser collect usual comments in statements blocks and
+    /// between struct fields, and attach them to the nearest statements and
+    /// fields, see [`stellar_ast::comments`].
+    #[inline]
+    #[must_use]
+    pub fn with_comments(mut self) -> Self {
+        self.comments = Some(Comments::new());
+        self
+    }
+
+    /// Returns comments collected so far, `None` if collection is disabled,
+    /// see [`ParseState::with_comments`].
+    #[inline]
+    #[must_use]
+    pub const fn comments(&self) -> Option<&Comments> {
+        self.comments.as_ref()
+    }
+
+    /// Makes comments after the current token, e.g. after `{` of a block,
+    /// available for attaching to the nodes, that follow.
+    #[inline]
+    const fn start_comments_after_current_token(&mut self) {
+        if self.comments.is_some() {
+            self.comments_cursor = self.current_token.location.end;
+        }
+    }
+
+    /// Attaches comments to a node, that was just parsed and starts at a
+    /// given offset: the ones after the previously attached node become
+    /// leading and the ones on the line, where the node ends (after an
+    /// optional `,`), become trailing.
+    fn attach_comments(&mut self, target: CommentTarget, start: ByteOffset) {
+        if self.comments.is_none() {
+            return;
+        }
+
+        let leading = self.comments_before(start);
+
+        let end = self.current_token.location.end;
+        let mut lexer = self.lexer_at(end);
+        let mut token = lexer.next_token();
+
+        if token.raw == Punctuator::Comma {
+            token = lexer.next_token();
+        }
+
+        let mut trailing = vec![];
+
+        while token.raw == RawToken::Comment
+            && !self.lexer.source[end.0..token.location.start.0].contains('\n')
+        {
+            trailing.push(Comment {
+                location: token.location,
+            });
+
+            token = lexer.next_token();
+        }
+
+        self.comments_cursor = trailing.last().map_or(end, |comment| comment.location.end);
+
+        let comments = self.comments.as_mut().unwrap();
+
+        for comment in leading {
+            comments.add_leading(target, comment);
+        }
+
+        for comment in trailing {
+            comments.add_trailing(target, comment);
+        }
+    }
+
+    /// Collects comments, that are not followed by a node in the enclosing
+    /// block, e.g. before `}`, as dangling.
+    fn attach_dangling_comments(&mut self) {
+        if self.comments.is_none() {
+            return;
+        }
+
+        for comment in self.comments_before(self.next_token.location.start) {
+            self.comments.as_mut().unwrap().add_dangling(comment);
+        }
+
+        self.comments_cursor = self.next_token.location.end;
+    }
+
+    /// Returns comments between the comments cursor and a given offset.
+    fn comments_before(&self, offset: ByteOffset) -> Vec<Comment> {
+        let mut lexer = self.lexer_at(self.comments_cursor);
+        let mut comments = vec![];
+
+        loop {
+            let token = lexer.next_token();
+
+            if token.raw == RawToken::EndOfFile || token.location.start >= offset {
+                break;
+            }
+
+            if token.raw == RawToken::Comment {
+                comments.push(Comment {
+                    location: token.location,
+                });
+            }
+        }
+
+        comments
+    }
+
+    /// Returns a lexer scanning tokens from a given offset.
+    #[inline]
+    fn lexer_at(&self, offset: ByteOffset) -> Lexer<'s> {
+        Lexer::new_at(self.lexer.filepath, self.lexer.source, offset)
+    }
+
+    /// Records a parse event, if recording is enabled.
+    #[inline]
+    fn push_event(&mut self, event: Event) {
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+
+    /// Runs a parser inside of a syntax node of a given kind.
+    ///
+    /// Reports a diagnostic and fails without consuming tokens, if the node
+    /// would be nested too deeply, see [`ParseState::with_max_depth`].
+    fn node<T>(&mut self, kind: NodeKind, parse: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        if self.depth >= self.max_depth {
+            self.diagnostics.add_diagnostic(NestingLimitReached::new(
+                self.next_token.location,
+                self.max_depth,
+            ));
+
+            return None;
+        }
+
+        self.depth += 1;
+        let chain_length = self.chain_length;
+
+        let marker = Marker::start(self, kind);
+        let result = parse(self);
+        marker.complete(self);
+
+        self.depth -= 1;
+
+        // forgets nodes folded inside of this one, see
+        // [`ParseState::enter_chain`]
+        self.chain_length = chain_length;
+
+        result
+    }
+
+    /// Counts a node, that is folded in a loop rather than parsed
+    /// recursively, e.g. every `+` in `1 + 1 + 1` or every call in `f()()()`,
+    /// reporting a diagnostic and failing if the limit is reached, see
+    /// [`ParseState::with_max_chain_length`].
+    ///
+    /// Folded nodes are counted until the enclosing node is completed.
+    fn enter_chain(&mut self) -> Option<()> {
+        if self.chain_length >= self.max_chain_length {
+            self.diagnostics.add_diagnostic(NestingLimitReached::new(
+                self.next_token.location,
+                self.max_chain_length,
+            ));
+
+            return None;
+        }
+
+        self.chain_length += 1;
+
+        Some(())
+    }
+
     /// Adds diagnostic if the next token has lex error in itself.
     #[inline]
     fn check_next_token(&mut self) {
@@ -441,6 +1167,14 @@ impl<'s, 'd> ParseState<'s, 'd> {
         }
     }
 
+    /// Returns a lexer scanning tokens after the next token, that can be used
+    /// to look further ahead without advancing the parser.
+    #[inline]
+    #[must_use]
+    fn lookahead(&self) -> Lexer<'s> {
+        self.lexer_at(self.next_token.location.end)
+    }
+
     /// Returns string slice corresponding to the given location.
     #[inline]
     #[must_use]
@@ -455,12 +1189,52 @@ impl<'s, 'd> ParseState<'s, 'd> {
         self.resolve_location(self.current_token.location)
     }
 
+    /// Returns the value of the current integer literal token.
+    fn resolve_integer_literal(&self) -> IntegerValue {
+        self.resolve_current_token_str()
+            .parse()
+            .expect("lexer only emits valid integer literals")
+    }
+
     /// Advances the iter to the next token (skips comment tokens).
     fn advance(&mut self) {
         self.check_next_token();
 
         self.current_token = self.next_token;
         self.next_token = self.lexer.next_no_comments();
+        self.consume_fuel();
+
+        self.push_event(Event::Token(self.current_token));
+    }
+
+    /// Consumes fuel for the current token and stops the parser as if the file
+    /// ended after it, once the fuel is exhausted, see [`ParseState::with_fuel`].
+    fn consume_fuel(&mut self) {
+        let Some((fuel, initial)) = &mut self.fuel else {
+            return;
+        };
+
+        if self.next_token.raw == RawToken::EndOfFile {
+            return;
+        }
+
+        if *fuel > 0 {
+            *fuel -= 1;
+
+            if *fuel > 0 {
+                return;
+            }
+
+            let initial = *initial;
+            self.diagnostics
+                .add_diagnostic(FuelExhausted::new(self.current_token.location, initial));
+        }
+
+        let end = self.current_token.location.end;
+        self.next_token = Token {
+            location: self.make_location(end, end),
+            raw: RawToken::EndOfFile,
+        };
     }
 
     /// Checks if the next token is [`expected`].
@@ -485,6 +1259,87 @@ impl<'s, 'd> ParseState<'s, 'd> {
         Some(())
     }
 
+    /// Returns `true` if the newline termination mode is enabled, line breaks
+    /// are significant and the next token starts a new line.
+    fn is_at_line_break(&self) -> bool {
+        let trivia = self.current_token.location.end.0..self.next_token.location.start.0;
+
+        self.newline_termination
+            && self.significant_newlines
+            && self.lexer.source[trivia].contains('\n')
+    }
+
+    /// Runs a parser with line breaks being significant or not, see
+    /// [`ParseState::with_newline_termination`].
+    fn with_significant_newlines<T>(
+        &mut self,
+        significant: bool,
+        parse: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let previous = mem::replace(&mut self.significant_newlines, significant);
+        let result = parse(self);
+        self.significant_newlines = previous;
+
+        result
+    }
+
+    /// Consumes `;` at the end of a statement. In the newline termination mode,
+    /// the statement can also end at a line break or before `}`.
+    fn consume_statement_end(&mut self) -> Option<()> {
+        if self.newline_termination && self.next_token.raw != Punctuator::Semicolon {
+            if self.is_at_line_break() {
+                self.check_line_break_ambiguity();
+
+                return Some(());
+            }
+
+            if self.next_token.raw == Punctuator::CloseBrace {
+                return Some(());
+            }
+        }
+
+        self.consume(Punctuator::Semicolon)
+    }
+
+    /// Warns if the statement ended at a line break, but the next line starts
+    /// with a token, that could also continue it.
+    fn check_line_break_ambiguity(&mut self) {
+        if can_start_and_continue_expression(self.next_token.raw) {
+            self.diagnostics
+                .add_diagnostic(AmbiguousLineBreak::new(self.next_token));
+        }
+    }
+
+    /// Skips a block enclosed in braces without parsing it.
+    fn skip_block(&mut self) -> Option<()> {
+        self.consume(Punctuator::OpenBrace)?;
+
+        let mut depth = 1_usize;
+
+        loop {
+            match self.next_token.raw {
+                RawToken::Punctuator(Punctuator::OpenBrace) => depth += 1,
+                RawToken::Punctuator(Punctuator::CloseBrace) => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        self.advance();
+
+                        return Some(());
+                    }
+                }
+                RawToken::EndOfFile => {
+                    self.add_unexpected_token_diagnostic(Punctuator::CloseBrace);
+
+                    return None;
+                }
+                _ => {}
+            }
+
+            self.advance();
+        }
+    }
+
     /// Creates a new location with the parser state's file id and
     /// the given starting and ending byte offsets.
     #[inline]
@@ -523,35 +1378,31 @@ impl<'s, 'd> ParseState<'s, 'd> {
     }
 
     /// Consumes the docstring for a module.
-    pub(crate) fn consume_module_docstring(&mut self) -> Option<String> {
-        if self.next_token.raw == RawToken::GlobalDocComment {
-            let mut module_docstring = String::new();
-
-            while self.next_token.raw == RawToken::GlobalDocComment {
-                self.advance();
-
-                module_docstring.push_str(self.resolve_location(self.current_token.location));
-            }
-            Some(module_docstring)
-        } else {
-            None
-        }
+    pub(crate) fn consume_module_docstring(&mut self) -> Option<Docstring> {
+        self.consume_docstring(RawToken::GlobalDocComment)
     }
 
     /// Consumes the docstring for a local item.
-    pub(crate) fn consume_local_docstring(&mut self) -> Option<String> {
-        if self.next_token.raw == RawToken::LocalDocComment {
-            let mut local_docstring = String::new();
+    pub(crate) fn consume_local_docstring(&mut self) -> Option<Docstring> {
+        self.consume_docstring(RawToken::LocalDocComment)
+    }
 
-            while self.next_token.raw == RawToken::LocalDocComment {
-                self.advance();
+    /// Consumes consecutive doc comments of a given kind. Their text is not
+    /// copied, see [`Docstring`] for more details.
+    fn consume_docstring(&mut self, kind: RawToken) -> Option<Docstring> {
+        if self.next_token.raw != kind {
+            return None;
+        }
 
-                local_docstring.push_str(self.resolve_location(self.current_token.location));
-            }
-            Some(local_docstring)
-        } else {
-            None
+        let start = self.next_token.location.start;
+
+        while self.next_token.raw == kind {
+            self.advance();
         }
+
+        Some(Docstring {
+            location: self.location_from(start),
+        })
     }
 
     /// Adds an unexpected token diagnostic.
@@ -559,6 +1410,12 @@ impl<'s, 'd> ParseState<'s, 'd> {
     /// See [`diagnostics::UnexpectedToken`] for more details.
     #[inline]
     pub(crate) fn add_unexpected_token_diagnostic(&mut self, expected: impl Into<String>) {
+        let expected = expected.into();
+
+        self.push_event(Event::Error {
+            location: self.next_token.location,
+            expected: expected.clone(),
+        });
         self.diagnostics.add_diagnostic(UnexpectedToken::new(
             self.current_token.location.end,
             self.next_token,
@@ -573,12 +1430,45 @@ impl Parse for VisibilityParser {
     type Output = Visibility;
 
     fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
-        if state.next_token.raw == Keyword::Pub {
-            state.advance();
+        if state.next_token.raw != Keyword::Pub {
+            return Visibility::Private;
+        }
 
-            Visibility::Public(state.current_token.location)
-        } else {
-            Visibility::Private
+        state.advance();
+
+        let start = state.current_token.location.start;
+
+        // `pub (A, B)` can be a visibility of a tuple field followed by its type,
+        // so the restriction is parsed only if it is a single identifier.
+        let mut lookahead = state.lookahead();
+        let restriction = lookahead.next_no_comments();
+
+        if state.next_token.raw != Punctuator::OpenParent
+            || restriction.raw != RawToken::Identifier
+            || lookahead.next_no_comments().raw != Punctuator::CloseParent
+        {
+            return Visibility::Public(state.current_token.location);
+        }
+
+        state.advance(); // `(`
+        state.advance(); // restriction
+        state.advance(); // `)`
+
+        let location = state.location_from(start);
+
+        match state.resolve_location(restriction.location) {
+            "package" => Visibility::Package(location),
+            "super" => Visibility::Super(location),
+            name => {
+                state
+                    .diagnostics
+                    .add_diagnostic(UnknownVisibilityRestriction::new(
+                        restriction.location,
+                        name.to_owned(),
+                    ));
+
+                Visibility::Public(location)
+            }
         }
     }
 }
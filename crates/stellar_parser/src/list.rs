@@ -33,16 +33,33 @@ where
     type Output = Option<Vec<E>>;
 
     fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        // line breaks inside of brackets never end statements
+        state.with_significant_newlines(false, |state| self.parse_elements(state, None))
+    }
+}
+
+impl<P, E> ListParser<'_, P, E>
+where
+    P: for<'s, 'd> Fn(&mut ParseState<'s, 'd>) -> Option<E>,
+{
+    /// Parses the rest of a list, when its first element has already been
+    /// parsed, e.g. `, 2]` in `[1, 2]`, after it turned out not to be `[1; 2]`.
+    pub(crate) fn parse_after(self, state: &mut ParseState<'_, '_>, first: E) -> Option<Vec<E>> {
+        state.with_significant_newlines(false, |state| self.parse_elements(state, Some(first)))
+    }
+
+    fn parse_elements(self, state: &mut ParseState<'_, '_>, first: Option<E>) -> Option<Vec<E>> {
         let mut result = vec![];
+        let mut first = first;
 
         // For instance: `(` `)` - empty list.
-        if self.closing_tokens.contains(&state.next_token.raw) {
+        if first.is_none() && self.closing_tokens.contains(&state.next_token.raw) {
             return Some(result);
         }
 
         loop {
             // `(` element
-            if let Some(element) = (self.parse_element_fn)(state) {
+            if let Some(element) = first.take().or_else(|| (self.parse_element_fn)(state)) {
                 result.push(element);
             } else {
                 return None;
@@ -1,9 +1,6 @@
 use stellar_ast::{token::RawToken, Literal};
 
-use crate::{
-    diagnostics::{FloatOverflow, IntegerOverflow},
-    Parse, ParseState,
-};
+use crate::{diagnostics::FloatOverflow, Parse, ParseState};
 
 pub(crate) struct LiteralParser;
 
@@ -15,21 +12,12 @@ impl Parse for LiteralParser {
             RawToken::IntegerLiteral => {
                 state.advance();
 
-                if let Ok(value) = state
-                    .resolve_current_token_str()
-                    .replace('_', "")
-                    .parse::<u64>()
-                {
-                    Some(Literal::Integer {
-                        value,
-                        location: state.current_token.location,
-                    })
-                } else {
-                    state
-                        .diagnostics
-                        .add_diagnostic(IntegerOverflow::new(state.current_token.location));
-                    None
-                }
+                // Overflows are reported by the type checker, when the type of
+                // the literal is known.
+                Some(Literal::Integer {
+                    value: state.resolve_integer_literal(),
+                    location: state.current_token.location,
+                })
             }
             RawToken::FloatLiteral => {
                 state.advance();
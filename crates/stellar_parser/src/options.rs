@@ -0,0 +1,136 @@
+//! Editions and feature gates of experimental syntax.
+//!
+//! By default the parser accepts all syntax it knows. Once options are set
+//! with [`ParseState::with_options`], experimental syntax is only accepted if
+//! its [`Feature`] is enabled for the package, or is stable in its edition:
+//!
+//! ```
+//! use stellar_ast::IdentifierAST;
+//! use stellar_diagnostics::Diagnostics;
+//! use stellar_filesystem::location::DUMMY_LOCATION;
+//! use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+//! use stellar_parser::{options::ParseOptions, parse_item_using, ParseState};
+//!
+//! let source = "gen fun numbers() { yield 1; }";
+//!
+//! let mut diagnostics = Diagnostics::new();
+//! let mut state = ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics)
+//!     .with_options(ParseOptions::default());
+//! parse_item_using(&mut state);
+//!
+//! // `generators` is not enabled
+//! assert!(!diagnostics.is_ok());
+//!
+//! let options = ParseOptions::default().enable_feature(IdentifierAST {
+//!     location: DUMMY_LOCATION,
+//!     id: IdentifierId::from("generators"),
+//! });
+//!
+//! let mut diagnostics = Diagnostics::new();
+//! let mut state = ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics).with_options(options);
+//! parse_item_using(&mut state);
+//!
+//! assert!(diagnostics.is_ok());
+//! ```
+//!
+//! [`ParseState::with_options`]: crate::ParseState::with_options
+
+use std::fmt::{self, Display};
+
+use stellar_ast::IdentifierAST;
+
+/// An edition of the language.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Edition {
+    /// The 2024 edition.
+    #[default]
+    Edition2024,
+}
+
+impl Display for Edition {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Edition2024 => "2024",
+        })
+    }
+}
+
+/// Experimental syntax, that must be enabled explicitly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    /// Generator functions, e.g. `gen fun numbers() { yield 1; }`.
+    Generators,
+
+    /// Try blocks, e.g. `try { parse(a)? + parse(b)? }`.
+    TryBlocks,
+}
+
+impl Feature {
+    /// All features.
+    pub const ALL: [Self; 2] = [Self::Generators, Self::TryBlocks];
+
+    /// Returns the name used to enable the feature.
+    #[inline]
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Generators => "generators",
+            Self::TryBlocks => "try_blocks",
+        }
+    }
+
+    /// Returns a feature with a given name, `None` if it doesn't exist.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|feature| feature.name() == name)
+    }
+
+    /// Returns the edition, since which the feature doesn't need to be enabled,
+    /// `None` if it is still experimental.
+    #[inline]
+    #[must_use]
+    pub const fn stable_since(self) -> Option<Edition> {
+        match self {
+            Self::Generators | Self::TryBlocks => None,
+        }
+    }
+}
+
+impl Display for Feature {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Options of a package, that affect parsing.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// The edition of the package.
+    pub edition: Edition,
+
+    /// Names of enabled features together with locations, where they are
+    /// enabled, e.g. in the package manifest.
+    pub enabled_features: Vec<IdentifierAST>,
+}
+
+impl ParseOptions {
+    /// Creates options for a given edition without enabled features.
+    #[inline]
+    #[must_use]
+    pub const fn new(edition: Edition) -> Self {
+        Self {
+            edition,
+            enabled_features: vec![],
+        }
+    }
+
+    /// Enables a feature with a given name.
+    #[inline]
+    #[must_use]
+    pub fn enable_feature(mut self, name: IdentifierAST) -> Self {
+        self.enabled_features.push(name);
+        self
+    }
+}
@@ -1,9 +1,9 @@
 use stellar_ast::{
-    token::{Keyword, Punctuator},
+    token::{Keyword, Punctuator, RawToken},
     ImportPath, Path,
 };
 
-use crate::{Parse, ParseState};
+use crate::{list::ListParser, Parse, ParseState};
 
 pub(crate) struct PathParser;
 
@@ -36,7 +36,56 @@ impl Parse for ImportPathParser {
     type Output = Option<ImportPath>;
 
     fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
-        let path = PathParser.parse(state)?;
+        let mut identifiers = vec![];
+
+        let first_identifier = state.consume_identifier()?;
+        identifiers.push(first_identifier);
+
+        let start = first_identifier.location.start;
+
+        while state.next_token.raw == Punctuator::Dot {
+            // location of the path without the trailing `.*` or `.{...}`
+            let location = state.location_from(start);
+
+            state.advance();
+
+            if state.next_token.raw == Punctuator::Asterisk {
+                state.advance();
+
+                return Some(ImportPath::Glob {
+                    path: Path {
+                        location,
+                        identifiers,
+                    },
+                });
+            }
+
+            if state.next_token.raw == Punctuator::OpenBrace {
+                state.advance();
+
+                let imports = ListParser::new(&[RawToken::from(Punctuator::CloseBrace)], |state| {
+                    Self.parse(state)
+                })
+                .parse(state)?;
+
+                state.advance();
+
+                return Some(ImportPath::Group {
+                    path: Path {
+                        location,
+                        identifiers,
+                    },
+                    imports,
+                });
+            }
+
+            identifiers.push(state.consume_identifier()?);
+        }
+
+        let path = Path {
+            location: state.location_from(start),
+            identifiers,
+        };
 
         let r#as = if state.next_token.raw == Keyword::As {
             state.advance();
@@ -46,6 +95,6 @@ impl Parse for ImportPathParser {
             None
         };
 
-        Some(ImportPath { path, as_: r#as })
+        Some(ImportPath::Single { path, as_: r#as })
     }
 }
@@ -1,14 +1,14 @@
 use stellar_ast::{
-    token::{Punctuator, RawToken},
-    NegativeNumericLiteral, Path, Pattern, StructFieldPattern,
+    token::{Keyword, Punctuator, RawToken},
+    BindingMode, IdentifierAST, NegativeNumericLiteral, Path, Pattern, RangeKind,
+    StructFieldPattern,
 };
 
+use stellar_filesystem::location::ByteOffset;
+
 use crate::{
-    diagnostics::{FloatOverflow, IntegerOverflow, UnexpectedToken},
-    list::ListParser,
-    literal::LiteralParser,
-    path::PathParser,
-    Parse, ParseState,
+    diagnostics::FloatOverflow, event::NodeKind, list::ListParser, literal::LiteralParser,
+    path::PathParser, Parse, ParseState,
 };
 
 pub(crate) struct PatternParser;
@@ -17,21 +17,38 @@ impl Parse for PatternParser {
     type Output = Option<Pattern>;
 
     fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
-        let left = PatternExceptOrParser.parse(state)?;
+        state.node(NodeKind::Pattern, |state| self.parse_pattern(state))
+    }
+}
 
+impl PatternParser {
+    fn parse_pattern(self, state: &mut ParseState<'_, '_>) -> Option<Pattern> {
+        // optional leading `|`, e.g. `| A | B => ...`
         if state.next_token.raw == Punctuator::Or {
             state.advance();
+        }
 
-            let right = Self.parse(state)?;
+        let first = PatternExceptOrParser.parse(state)?;
 
-            Some(Pattern::Or {
-                location: state.make_location(left.location().start, right.location().end),
-                left: Box::new(left),
-                right: Box::new(right),
-            })
-        } else {
-            Some(left)
+        if state.next_token.raw != Punctuator::Or {
+            return Some(first);
+        }
+
+        let mut alternatives = vec![first];
+
+        while state.next_token.raw == Punctuator::Or {
+            state.advance();
+
+            alternatives.push(PatternExceptOrParser.parse(state)?);
         }
+
+        Some(Pattern::Or {
+            location: state.make_location(
+                alternatives[0].location().start,
+                state.current_token.location.end,
+            ),
+            alternatives,
+        })
     }
 }
 
@@ -48,22 +65,10 @@ impl PatternExceptOrParser {
             RawToken::IntegerLiteral => {
                 state.advance();
 
-                if let Ok(value) = state
-                    .resolve_current_token_str()
-                    .replace('_', "")
-                    .parse::<u64>()
-                {
-                    Some(NegativeNumericLiteral::Integer {
-                        value,
-                        location: state.current_token.location,
-                    })
-                } else {
-                    state
-                        .diagnostics
-                        .add_diagnostic(IntegerOverflow::new(state.current_token.location));
-
-                    None
-                }
+                Some(NegativeNumericLiteral::Integer {
+                    value: state.resolve_integer_literal(),
+                    location: state.current_token.location,
+                })
             }
             RawToken::FloatLiteral => {
                 state.advance();
@@ -94,6 +99,44 @@ impl PatternExceptOrParser {
         .map(Pattern::NegativeNumericLiteral)
     }
 
+    /// Parses a range pattern, e.g. `1..=9`, if the bound is followed by
+    /// `..` or `..=`. Otherwise returns the bound itself.
+    fn parse_range_pattern(
+        &self,
+        state: &mut ParseState<'_, '_>,
+        start_offset: ByteOffset,
+        start: Pattern,
+    ) -> Option<Pattern> {
+        let kind = match state.next_token.raw {
+            RawToken::Punctuator(Punctuator::DoubleDot) => RangeKind::Exclusive,
+            RawToken::Punctuator(Punctuator::DoubleDotEq) => RangeKind::Inclusive,
+            _ => return Some(start),
+        };
+
+        state.advance();
+
+        let end = match state.next_token.raw {
+            RawToken::CharLiteral | RawToken::IntegerLiteral | RawToken::FloatLiteral => {
+                LiteralParser.parse(state).map(Pattern::Literal)?
+            }
+            RawToken::Punctuator(Punctuator::Minus) => {
+                self.parse_negative_numeric_literal_pattern(state)?
+            }
+            _ => {
+                state.add_unexpected_token_diagnostic("range pattern bound");
+
+                return None;
+            }
+        };
+
+        Some(Pattern::Range {
+            location: state.location_from(start_offset),
+            start: Box::new(start),
+            end: Box::new(end),
+            kind,
+        })
+    }
+
     fn parse_pattern_beginning_with_identifier(
         &self,
         state: &mut ParseState<'_, '_>,
@@ -117,29 +160,66 @@ impl PatternExceptOrParser {
                 .first()
                 .expect("Cannot get first identifier in path when parsing identifier pattern");
 
-            let pattern = if state.next_token.raw == Punctuator::At {
-                state.advance();
-                Some(Box::new(PatternParser.parse(state)?))
-            } else {
-                None
-            };
-
-            Some(Pattern::Identifier {
-                location: state.make_location(
-                    path.location.start,
-                    match pattern {
-                        Some(ref pattern) => pattern.location().end,
-                        None => path.location.end,
-                    },
-                ),
-                identifier: *identifier,
-                pattern,
-            })
+            Self::parse_identifier_pattern(
+                state,
+                path.location.start,
+                BindingMode::Value,
+                *identifier,
+            )
         } else {
             Some(Pattern::Path { path })
         }
     }
 
+    /// Parses an identifier pattern with an explicit binding mode, e.g. `mut x`,
+    /// `ref mut y`.
+    fn parse_identifier_pattern_with_binding_mode(
+        state: &mut ParseState<'_, '_>,
+    ) -> Option<Pattern> {
+        let start = state.next_token.location.start;
+
+        let binding_mode = if state.next_token.raw == Keyword::Ref {
+            state.advance();
+
+            if state.next_token.raw == Keyword::Mut {
+                state.advance();
+
+                BindingMode::MutableReference
+            } else {
+                BindingMode::Reference
+            }
+        } else {
+            state.advance(); // `mut`
+
+            BindingMode::MutableValue
+        };
+
+        let identifier = state.consume_identifier()?;
+
+        Self::parse_identifier_pattern(state, start, binding_mode, identifier)
+    }
+
+    fn parse_identifier_pattern(
+        state: &mut ParseState<'_, '_>,
+        start: ByteOffset,
+        binding_mode: BindingMode,
+        identifier: IdentifierAST,
+    ) -> Option<Pattern> {
+        let pattern = if state.next_token.raw == Punctuator::At {
+            state.advance();
+            Some(Box::new(PatternParser.parse(state)?))
+        } else {
+            None
+        };
+
+        Some(Pattern::Identifier {
+            location: state.location_from(start),
+            binding_mode,
+            identifier,
+            pattern,
+        })
+    }
+
     fn parse_list_pattern(&self, state: &mut ParseState<'_, '_>) -> Option<Pattern> {
         let start = state.next_token.location.start;
         state.advance();
@@ -276,13 +356,17 @@ impl Parse for PatternExceptOrParser {
     type Output = Option<Pattern>;
 
     fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        let start_offset = state.next_token.location.start;
+
         match state.next_token.raw {
-            RawToken::StringLiteral
-            | RawToken::CharLiteral
-            | RawToken::IntegerLiteral
-            | RawToken::FloatLiteral
-            | RawToken::TrueBoolLiteral
-            | RawToken::FalseBoolLiteral => LiteralParser.parse(state).map(Pattern::Literal),
+            RawToken::CharLiteral | RawToken::IntegerLiteral | RawToken::FloatLiteral => {
+                let start = LiteralParser.parse(state).map(Pattern::Literal)?;
+
+                self.parse_range_pattern(state, start_offset, start)
+            }
+            RawToken::StringLiteral | RawToken::TrueBoolLiteral | RawToken::FalseBoolLiteral => {
+                LiteralParser.parse(state).map(Pattern::Literal)
+            }
             RawToken::Punctuator(Punctuator::DoubleDot) => {
                 state.advance();
 
@@ -298,19 +382,21 @@ impl Parse for PatternExceptOrParser {
                 })
             }
             RawToken::Punctuator(Punctuator::Minus) => {
-                self.parse_negative_numeric_literal_pattern(state)
+                let start = self.parse_negative_numeric_literal_pattern(state)?;
+
+                self.parse_range_pattern(state, start_offset, start)
             }
             RawToken::Identifier => self.parse_pattern_beginning_with_identifier(state),
+            RawToken::Keyword(Keyword::Mut | Keyword::Ref) => {
+                Self::parse_identifier_pattern_with_binding_mode(state)
+            }
             RawToken::Punctuator(Punctuator::OpenBracket) => self.parse_list_pattern(state),
             RawToken::Punctuator(Punctuator::OpenParent) => {
                 self.parse_grouped_or_tuple_pattern(state)
             }
             _ => {
-                state.diagnostics.add_diagnostic(UnexpectedToken::new(
-                    state.current_token.location.end,
-                    state.next_token,
-                    "pattern",
-                ));
+                state.add_unexpected_token_diagnostic("pattern");
+
                 None
             }
         }
@@ -0,0 +1,115 @@
+//! Parsing of source files as memoized queries, see [`ParseFile`].
+//!
+//! If the state memoizes results of analysis (see `State::with_queries`),
+//! [`parse_module`](crate::parse_module) sets the source of a file as a
+//! [`SourceText`] input and takes the AST from [`ParseFile`], so a file, which
+//! source didn't change since the queries were last used, is not parsed again.
+//! Only parsing is memoized: lowering and resolution still run for every
+//! module:
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use stellar_database::query::Queries;
+//! use stellar_interner::DUMMY_PATH_ID;
+//! use stellar_parser::query::{ParseFile, SourceText};
+//!
+//! let mut queries = Queries::new();
+//! queries.set::<SourceText>(DUMMY_PATH_ID, Arc::from("fun main() {}"));
+//!
+//! let parsed = queries.get::<ParseFile>(&DUMMY_PATH_ID);
+//! assert_eq!(parsed.ast().items.len(), 1);
+//! assert!(parsed.diagnostics().is_ok());
+//!
+//! // setting the same source again doesn't invalidate the AST
+//! queries.set::<SourceText>(DUMMY_PATH_ID, Arc::from("fun main() {}"));
+//! assert!(Arc::ptr_eq(&parsed, &queries.get::<ParseFile>(&DUMMY_PATH_ID)));
+//! ```
+
+use std::sync::Arc;
+
+use stellar_ast::{Module, NodeId};
+use stellar_database::query::{Input, Queries, Query};
+use stellar_diagnostics::Diagnostics;
+use stellar_interner::PathId;
+
+use crate::{incremental::parse_module_ast, ItemSpans, ParseState};
+
+/// Source text of a file, the input of [`ParseFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SourceText;
+
+impl Input for SourceText {
+    type Key = PathId;
+    type Value = Arc<str>;
+}
+
+/// A source file parsed into an AST, that is not yet bound to a module in the
+/// database.
+#[derive(Debug, PartialEq)]
+pub struct ParsedFile {
+    ast: Module,
+    item_spans: ItemSpans,
+    next_node_id: NodeId,
+    diagnostics: Diagnostics,
+}
+
+impl ParsedFile {
+    /// Returns the module AST.
+    #[inline]
+    #[must_use]
+    pub const fn ast(&self) -> &Module {
+        &self.ast
+    }
+
+    /// Returns diagnostics emitted while parsing the file.
+    #[inline]
+    #[must_use]
+    pub const fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    /// Returns the ID, that will be assigned to the next node parsed into the
+    /// module.
+    #[inline]
+    #[must_use]
+    pub const fn next_node_id(&self) -> NodeId {
+        self.next_node_id
+    }
+
+    /// Returns where items of the module are, see [`ParseResult::reparse`].
+    ///
+    /// [`ParseResult::reparse`]: crate::ParseResult::reparse
+    pub(crate) const fn item_spans(&self) -> &ItemSpans {
+        &self.item_spans
+    }
+}
+
+/// Parses a source file, which source is set as a [`SourceText`] input.
+///
+/// Parsing a file is not interrupted by cancellation (see
+/// [`ParseState::with_cancellation_token`]), so that partially parsed ASTs
+/// are never memoized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseFile;
+
+impl Query for ParseFile {
+    type Key = PathId;
+    type Value = Arc<ParsedFile>;
+
+    fn execute(queries: &mut Queries, filepath: &PathId) -> Arc<ParsedFile> {
+        let source = queries.input::<SourceText>(filepath);
+
+        let mut diagnostics = Diagnostics::new();
+        let mut parse_state = ParseState::new(*filepath, &source, &mut diagnostics);
+        let (ast, item_spans) = parse_module_ast(&mut parse_state);
+        let next_node_id = parse_state.next_node_id;
+
+        Arc::new(ParsedFile {
+            ast,
+            item_spans,
+            next_node_id,
+            diagnostics,
+        })
+    }
+}
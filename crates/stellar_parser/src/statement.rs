@@ -1,10 +1,12 @@
 use stellar_ast::{
+    comments::CommentTarget,
     token::{Keyword, Punctuator, RawToken},
-    Statement,
+    DeferBody, Statement,
 };
 
 use crate::{
-    expression::ExpressionParser, pattern::PatternParser, r#type::TypeParser, Parse, ParseState,
+    event::NodeKind, expression::ExpressionParser, pattern::PatternParser, r#type::TypeParser,
+    Parse, ParseState,
 };
 
 pub(crate) struct StatementParser;
@@ -20,19 +22,54 @@ impl StatementParser {
 
         let expression = ExpressionParser::default().parse(state)?;
 
-        state.consume(Punctuator::Semicolon)?;
+        state.consume_statement_end()?;
 
-        Some(Statement::Return { expression })
+        Some(Statement::Return {
+            node_id: state.new_node_id(),
+            expression,
+        })
+    }
+
+    fn parse_yield_statement(self, state: &mut ParseState<'_, '_>) -> Option<Statement> {
+        state.advance();
+
+        let location = state.current_token.location;
+        let expression = ExpressionParser::default().parse(state)?;
+
+        state.consume_statement_end()?;
+
+        Some(Statement::Yield {
+            node_id: state.new_node_id(),
+            location,
+            expression,
+        })
     }
 
     fn parse_defer_statement(self, state: &mut ParseState<'_, '_>) -> Option<Statement> {
         state.advance();
 
+        // `defer { close(f); flush(log); }`
+        if state.next_token.raw == Punctuator::OpenBrace {
+            let start = state.next_token.location.start;
+            let block = StatementsBlockParser.parse(state)?;
+
+            return Some(Statement::Defer {
+                node_id: state.new_node_id(),
+                body: DeferBody::Block {
+                    location: state.location_from(start),
+                    block,
+                },
+            });
+        }
+
         let call = ExpressionParser::default().parse(state)?;
 
-        state.consume(Punctuator::Semicolon)?;
+        state.consume_statement_end()?;
 
-        Some(Statement::Defer { call })
+        Some(Statement::Defer {
+            node_id: state.new_node_id(),
+            body: DeferBody::Call(call),
+        })
     }
 
     fn parse_let_statement(self, state: &mut ParseState<'_, '_>) -> Option<Statement> {
@@ -52,9 +89,14 @@ impl StatementParser {
 
         let value = ExpressionParser::default().parse(state)?;
 
-        state.consume(Punctuator::Semicolon)?;
+        state.consume_statement_end()?;
 
-        Some(Statement::Let { pattern, value, ty })
+        Some(Statement::Let {
+            node_id: state.new_node_id(),
+            pattern,
+            value,
+            ty,
+        })
     }
 
     fn parse_continue_statement(self, state: &mut ParseState<'_, '_>) -> Option<Statement> {
@@ -62,9 +104,12 @@ impl StatementParser {
 
         let location = state.current_token.location;
 
-        state.consume(Punctuator::Semicolon)?;
+        state.consume_statement_end()?;
 
-        Some(Statement::Continue { location })
+        Some(Statement::Continue {
+            node_id: state.new_node_id(),
+            location,
+        })
     }
 
     fn parse_break_statement(self, state: &mut ParseState<'_, '_>) -> Option<Statement> {
@@ -72,9 +117,12 @@ impl StatementParser {
 
         let location = state.current_token.location;
 
-        state.consume(Punctuator::Semicolon)?;
+        state.consume_statement_end()?;
 
-        Some(Statement::Break { location })
+        Some(Statement::Break {
+            node_id: state.new_node_id(),
+            location,
+        })
     }
 
     fn parse_expression_statement(
@@ -92,6 +140,11 @@ impl StatementParser {
         } else if state.next_token.raw == Punctuator::Semicolon {
             state.advance();
 
+            (false, true)
+        } else if state.is_at_line_break() && state.next_token.raw != Punctuator::CloseBrace {
+            // the statement ends at the line break the same way as with `;`
+            state.check_line_break_ambiguity();
+
             (false, true)
         } else {
             (true, false)
@@ -99,20 +152,18 @@ impl StatementParser {
 
         Some(ExpressionStatementParseResult {
             expression_statement: Statement::Expression {
+                node_id: state.new_node_id(),
                 expression,
                 has_semicolon,
             },
             last_expression_in_block,
         })
     }
-}
 
-impl Parse for StatementParser {
-    type Output = Option<StatementParserResult>;
-
-    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+    fn parse_statement(self, state: &mut ParseState<'_, '_>) -> Option<StatementParserResult> {
         let (statement, last_expression_in_block) = match state.next_token.raw {
             RawToken::Keyword(Keyword::Return) => (self.parse_return_statement(state)?, false),
+            RawToken::Keyword(Keyword::Yield) => (self.parse_yield_statement(state)?, false),
             RawToken::Keyword(Keyword::Defer) => (self.parse_defer_statement(state)?, false),
             RawToken::Keyword(Keyword::Let) => (self.parse_let_statement(state)?, false),
             RawToken::Keyword(Keyword::Continue) => (self.parse_continue_statement(state)?, false),
@@ -134,6 +185,14 @@ impl Parse for StatementParser {
     }
 }
 
+impl Parse for StatementParser {
+    type Output = Option<StatementParserResult>;
+
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        state.node(NodeKind::Statement, |state| self.parse_statement(state))
+    }
+}
+
 pub(crate) struct ExpressionStatementParseResult {
     pub(crate) expression_statement: Statement,
     pub(crate) last_expression_in_block: bool,
@@ -145,13 +204,26 @@ impl Parse for StatementsBlockParser {
     type Output = Option<Vec<Statement>>;
 
     fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        state.node(NodeKind::StatementsBlock, |state| {
+            state.with_significant_newlines(true, Self::parse_statements)
+        })
+    }
+}
+
+impl StatementsBlockParser {
+    fn parse_statements(state: &mut ParseState<'_, '_>) -> Option<Vec<Statement>> {
         state.consume(Punctuator::OpenBrace)?;
+        state.start_comments_after_current_token();
 
         let mut block = vec![];
 
         loop {
             match state.next_token.raw {
-                RawToken::Punctuator(Punctuator::CloseBrace) => break,
+                RawToken::Punctuator(Punctuator::CloseBrace) => {
+                    state.attach_dangling_comments();
+
+                    break;
+                }
                 RawToken::EndOfFile => {
                     state.add_unexpected_token_diagnostic(Punctuator::CloseBrace);
 
@@ -166,13 +238,18 @@ impl Parse for StatementsBlockParser {
                 _ => {}
             }
 
+            let start = state.next_token.location.start;
             let StatementParserResult {
                 statement,
                 last_expression_in_block,
             } = StatementParser.parse(state)?;
+
+            state.attach_comments(CommentTarget::Statement(statement.node_id()), start);
             block.push(statement);
 
             if last_expression_in_block {
+                state.attach_dangling_comments();
+
                 break;
             }
         }
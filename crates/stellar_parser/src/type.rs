@@ -1,9 +1,12 @@
 use stellar_ast::{
     token::{Keyword, Punctuator, RawToken},
-    GenericParameter, Type, TypeConstructor, WherePredicate,
+    ConstValue, GenericParameter, Literal, Type, TypeConstructor, WherePredicate,
 };
 
-use crate::{list::ListParser, path::PathParser, OptionallyParse, Parse, ParseState};
+use crate::{
+    diagnostics::IntegerOverflow, event::NodeKind, list::ListParser, literal::LiteralParser,
+    path::PathParser, OptionallyParse, Parse, ParseState,
+};
 
 pub(crate) struct BoundsParser;
 
@@ -84,6 +87,58 @@ impl TypeParser {
         }
     }
 
+    fn parse_array_type(self, state: &mut ParseState<'_, '_>) -> Option<Type> {
+        let start = state.next_token.location.start;
+
+        state.advance(); // `[`
+
+        let element_type = Self.parse(state)?;
+
+        state.consume(Punctuator::Semicolon)?;
+
+        let size = GenericArgumentParser.parse(state)?;
+
+        state.consume(Punctuator::CloseBracket)?;
+
+        Some(Type::Array {
+            location: state.location_from(start),
+            element_type: Box::new(element_type),
+            size: Box::new(size),
+        })
+    }
+
+    fn parse_reference_type(self, state: &mut ParseState<'_, '_>) -> Option<Type> {
+        let start = state.next_token.location.start;
+        let is_double = state.next_token.raw == Punctuator::DoubleAmpersand;
+
+        state.advance(); // `&` or `&&`
+
+        let is_mutable = state.next_token.raw == Keyword::Mut;
+
+        if is_mutable {
+            state.advance();
+        }
+
+        let inner = Self.parse(state)?;
+
+        // `&&T` is lexed as a single token, but means `&(&T)`
+        let reference = Type::Reference {
+            location: state.location_from(if is_double { start + 1 } else { start }),
+            is_mutable,
+            inner: Box::new(inner),
+        };
+
+        Some(if is_double {
+            Type::Reference {
+                location: state.location_from(start),
+                is_mutable: false,
+                inner: Box::new(reference),
+            }
+        } else {
+            reference
+        })
+    }
+
     fn parse_dyn_type(self, state: &mut ParseState<'_, '_>) -> Option<Type> {
         let start = state.next_token.location.start;
 
@@ -124,16 +179,17 @@ impl TypeParser {
             return_type,
         })
     }
-}
-
-impl Parse for TypeParser {
-    type Output = Option<Type>;
 
-    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
-        match state.next_token.raw {
+    fn parse_type(self, state: &mut ParseState<'_, '_>) -> Option<Type> {
+        let start = state.next_token.location.start;
+        let mut ty = match state.next_token.raw {
             RawToken::Punctuator(Punctuator::OpenParent) => {
                 self.parse_parenthesized_or_tuple_type(state)
             }
+            RawToken::Punctuator(Punctuator::OpenBracket) => self.parse_array_type(state),
+            RawToken::Punctuator(Punctuator::Ampersand | Punctuator::DoubleAmpersand) => {
+                self.parse_reference_type(state)
+            }
             RawToken::Keyword(Keyword::Dyn) => self.parse_dyn_type(state),
             RawToken::Identifier => TypeConstructorParser.parse(state).map(Type::Constructor),
             RawToken::Punctuator(Punctuator::Underscore) => {
@@ -149,7 +205,27 @@ impl Parse for TypeParser {
 
                 None
             }
+        }?;
+
+        // `T?`, `T??`
+        while state.next_token.raw == Punctuator::QuestionMark {
+            state.advance();
+
+            ty = Type::Optional {
+                location: state.location_from(start),
+                inner: Box::new(ty),
+            };
         }
+
+        Some(ty)
+    }
+}
+
+impl Parse for TypeParser {
+    type Output = Option<Type>;
+
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        state.node(NodeKind::Type, |state| self.parse_type(state))
     }
 }
 
@@ -166,6 +242,27 @@ impl OptionallyParse for GenericParametersParser {
         state.advance();
 
         let result = ListParser::new(&[RawToken::from(Punctuator::CloseBracket)], |state| {
+            if state.next_token.raw == Keyword::Const {
+                state.advance();
+
+                let name = state.consume_identifier()?;
+
+                state.consume(Punctuator::Colon)?;
+
+                return Some(GenericParameter {
+                    name,
+                    bounds: None,
+                    const_type: Some(TypeParser.parse(state)?),
+                    default_value: if state.next_token.raw == Punctuator::Eq {
+                        state.advance();
+
+                        Some(GenericArgumentParser.parse(state)?)
+                    } else {
+                        None
+                    },
+                });
+            }
+
             Some(GenericParameter {
                 name: state.consume_identifier()?,
                 bounds: if state.next_token.raw == Punctuator::Colon {
@@ -175,10 +272,11 @@ impl OptionallyParse for GenericParametersParser {
                 } else {
                     None
                 },
+                const_type: None,
                 default_value: if state.next_token.raw == Punctuator::Eq {
                     state.advance();
 
-                    Some(TypeParser.parse(state)?)
+                    Some(GenericArgumentParser.parse(state)?)
                 } else {
                     None
                 },
@@ -230,7 +328,7 @@ impl Parse for TypeArgumentsParser {
         state.advance();
 
         let result = ListParser::new(&[RawToken::from(Punctuator::CloseBracket)], |state| {
-            TypeParser.parse(state)
+            GenericArgumentParser.parse(state)
         })
         .parse(state)?;
 
@@ -240,6 +338,44 @@ impl Parse for TypeArgumentsParser {
     }
 }
 
+/// Parses a type or a const generic argument, e.g. `4` in `Array[uint8, 4]`.
+struct GenericArgumentParser;
+
+impl Parse for GenericArgumentParser {
+    type Output = Option<Type>;
+
+    fn parse(self, state: &mut ParseState<'_, '_>) -> Self::Output {
+        if !matches!(
+            state.next_token.raw,
+            RawToken::IntegerLiteral
+                | RawToken::CharLiteral
+                | RawToken::TrueBoolLiteral
+                | RawToken::FalseBoolLiteral
+        ) {
+            return TypeParser.parse(state);
+        }
+
+        let (value, location) = match LiteralParser.parse(state)? {
+            Literal::Integer { value, location } => {
+                let Some(value) = value.to_u64() else {
+                    state
+                        .diagnostics
+                        .add_diagnostic(IntegerOverflow::new(location));
+
+                    return None;
+                };
+
+                (ConstValue::Integer { value }, location)
+            }
+            Literal::Character { value, location } => (ConstValue::Character { value }, location),
+            Literal::Boolean { value, location } => (ConstValue::Boolean { value }, location),
+            _ => unreachable!(),
+        };
+
+        Some(Type::Const { location, value })
+    }
+}
+
 pub(crate) struct WherePredicatesParser;
 
 impl OptionallyParse for WherePredicatesParser {
@@ -0,0 +1,86 @@
+#![cfg(feature = "arena")]
+
+use stellar_ast::{
+    arena::{self, Arena},
+    ModuleItem, Statement,
+};
+use stellar_diagnostics::Diagnostics;
+use stellar_interner::DUMMY_PATH_ID;
+use stellar_parser::{parse_expression, parse_item};
+
+fn parse_body(source: &str) -> Vec<Statement> {
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Function(function)) = parse_item(DUMMY_PATH_ID, source, &mut diagnostics)
+    else {
+        panic!("expected function");
+    };
+
+    assert!(diagnostics.is_ok());
+
+    function.body.unwrap()
+}
+
+#[test]
+fn statements_keep_node_ids() {
+    let body = parse_body(
+        "fun main() {
+            let a: List[int32] = [1, 2, 3];
+            defer { close(a); }
+            match a { [x, ..] -> x, _ -> 0 }
+            return \"done\";
+        }",
+    );
+
+    let arena = Arena::new();
+    let allocated = arena.alloc_statements_block(&body);
+
+    assert_eq!(allocated.len(), body.len());
+
+    for (statement, allocated) in body.iter().zip(allocated) {
+        assert_eq!(allocated.node_id(), statement.node_id());
+    }
+
+    assert!(matches!(
+        allocated[3],
+        arena::Statement::Return {
+            expression: arena::Expression::Literal {
+                literal: arena::Literal::String { value: "done", .. },
+                ..
+            },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn expressions_keep_locations() {
+    let source = "foo(a + b, |x| x * 2).bar as Result[T]";
+    let expression = parse_expression(DUMMY_PATH_ID, source, &mut Diagnostics::new()).unwrap();
+
+    let arena = Arena::new();
+    let allocated = arena.alloc_expression(&expression);
+
+    assert_eq!(allocated.location(), expression.location());
+    assert_eq!(allocated.node_id(), expression.node_id());
+
+    let arena::Expression::As { left, right, .. } = allocated else {
+        panic!("expected as expression");
+    };
+    let arena::Expression::FieldAccess { left: call, .. } = left else {
+        panic!("expected field access");
+    };
+    let arena::Expression::Call { arguments, .. } = call else {
+        panic!("expected call expression");
+    };
+
+    assert!(matches!(
+        right,
+        arena::Type::Constructor(constructor) if &source[constructor.location] == "Result[T]"
+    ));
+    assert_eq!(arguments.len(), 2);
+    assert!(matches!(
+        arguments[1].value,
+        arena::Expression::Lambda { parameters, .. } if parameters.len() == 1
+    ));
+}
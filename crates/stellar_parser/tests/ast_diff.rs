@@ -0,0 +1,95 @@
+use stellar_ast::{
+    ast_diff::{diff_modules, ItemChange},
+    Module,
+};
+use stellar_database::{PackageData, Path, State};
+use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+use stellar_parser::parse_module;
+
+fn parse(source: &str) -> Module {
+    let mut state = State::new();
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("test"), DUMMY_PATH_ID);
+
+    let result = parse_module(
+        &mut state,
+        package,
+        Path::from(IdentifierId::from("test")),
+        DUMMY_PATH_ID,
+        source,
+    );
+
+    assert!(state.diagnostics().is_ok(), "{source}");
+
+    result.ast().clone()
+}
+
+fn diff(old: &str, new: &str) -> String {
+    diff_modules(&parse(old), &parse(new)).to_string()
+}
+
+#[test]
+fn location_shifts_are_ignored() {
+    let old = "import std.io;\nfun foo() { a(); }\nstruct A { b: int32 }";
+    let new = "\n\nimport std.io;\n\nfun foo() {\n    a();\n}\n\nstruct A {\n    b: int32,\n}\n";
+
+    assert!(diff_modules(&parse(old), &parse(new)).is_empty());
+}
+
+#[test]
+fn added_removed_and_changed_items() {
+    assert_eq!(
+        diff(
+            "import std.io; fun foo() { a(); } fun bar() {} struct A { b: int32 }",
+            "import std.fs; fun foo() { b(); } struct A { b: int32, c: String } const C: int32 = 1;"
+        ),
+        "added `import std.fs`
+changed `foo`
+changed `A`
+added `C`
+removed `import std.io`
+removed `bar`"
+    );
+}
+
+#[test]
+fn methods_are_compared_separately() {
+    let old = parse("struct A { fun foo() { a(); } fun bar() {} } impl ToString for A {}");
+    let new =
+        parse("struct A { fun foo() { b(); } } impl ToString for A { fun to_string(self) {} }");
+
+    let diff = diff_modules(&old, &new);
+
+    assert_eq!(
+        diff.to_string(),
+        "changed `A.foo`
+added `impl ToString for A.to_string`
+removed `A.bar`"
+    );
+    assert!(matches!(
+        &diff.changes()[0],
+        ItemChange::Changed { old_location, new_location, .. }
+            if old_location.start.0 == 15 && new_location.start.0 == 15
+    ));
+}
+
+#[test]
+fn moved_functions() {
+    assert_eq!(
+        diff(
+            "fun new(): A { A {} } struct A {} module utils { fun helper() {} }",
+            "struct A { fun new(): A { A {} } } module utils {} fun helper() {}"
+        ),
+        "moved `new` to `A.new`
+moved `utils.helper` to `helper`"
+    );
+
+    // a function, that was changed while being moved, is reported as a new one
+    assert_eq!(
+        diff(
+            "fun foo() {} struct A {}",
+            "struct A { fun foo() { a(); } }"
+        ),
+        "added `A.foo`\nremoved `foo`"
+    );
+}
@@ -0,0 +1,123 @@
+use stellar_ast::{
+    builder::{generic_type, named_type, ExprBuilder, FunctionBuilder, StructBuilder},
+    unparse,
+    unparse::unparse_expression,
+    Module, ModuleItem, RawBinaryOperator, RawPostfixOperator,
+};
+use stellar_database::{PackageData, Path, State};
+use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+use stellar_parser::parse_module;
+
+fn module(items: Vec<ModuleItem>) -> Module {
+    Module {
+        filepath: DUMMY_PATH_ID,
+        items,
+        docstring: None,
+    }
+}
+
+/// Checks that the source parses without errors and returns it.
+fn parses(source: String) -> String {
+    let mut state = State::new();
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("test"), DUMMY_PATH_ID);
+
+    let _ = parse_module(
+        &mut state,
+        package,
+        Path::from(IdentifierId::from("test")),
+        DUMMY_PATH_ID,
+        &source,
+    );
+
+    assert!(state.diagnostics().is_ok(), "{source}");
+
+    source
+}
+
+#[test]
+fn expressions() {
+    let expression = ExprBuilder::identifier("a")
+        .binary(RawBinaryOperator::Plus, ExprBuilder::integer(1))
+        .parenthesized()
+        .binary(RawBinaryOperator::Asterisk, ExprBuilder::float(2.5))
+        .cast(named_type("float32"));
+
+    assert_eq!(
+        unparse_expression(&expression.into()),
+        "((a + 1) * 2.5) as float32"
+    );
+
+    let expression = ExprBuilder::identifier("file")
+        .method_call(
+            "read",
+            [ExprBuilder::string("a\n"), ExprBuilder::boolean(true)],
+        )
+        .field("len")
+        .postfix(RawPostfixOperator::QuestionMark);
+
+    assert_eq!(
+        unparse_expression(&expression.into()),
+        "file.read(\"a\\n\", true).len?"
+    );
+}
+
+#[test]
+fn items() {
+    let point = StructBuilder::new("Point")
+        .with_public_visibility()
+        .with_generic_parameter("T")
+        .with_implements("ToString")
+        .with_public_field("x", named_type("T"))
+        .with_field("tags", generic_type("List", [named_type("String")]))
+        .with_method(
+            FunctionBuilder::new("new")
+                .with_public_visibility()
+                .with_parameter("x", named_type("T"))
+                .with_return_type(named_type("Self"))
+                .with_let("tags", ExprBuilder::list::<ExprBuilder>([]))
+                .with_return(ExprBuilder::identifier("Self").call([
+                    ExprBuilder::identifier("x"),
+                    ExprBuilder::identifier("tags"),
+                ])),
+        );
+    let main = FunctionBuilder::new("main")
+        .with_attribute("inline")
+        .with_statement(
+            ExprBuilder::identifier("print")
+                .call([ExprBuilder::character('a')])
+                .into_statement(),
+        );
+
+    assert_eq!(
+        parses(unparse(&module(vec![point.into(), main.into()]))),
+        "pub struct Point[T] implements ToString {
+    pub x: T,
+    tags: List[String],
+
+    pub fun new(x: T): Self {
+        let tags = [];
+        return Self(x, tags);
+    }
+}
+
+#[inline]
+fun main() {
+    print('a');
+}
+"
+    );
+}
+
+#[test]
+fn function_without_body() {
+    let function = FunctionBuilder::new("len")
+        .with_self_parameter()
+        .with_return_type(named_type("usize"))
+        .without_body();
+
+    assert_eq!(
+        unparse(&module(vec![function.into()])),
+        "fun len(self): usize;\n"
+    );
+}
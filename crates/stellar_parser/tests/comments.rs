@@ -0,0 +1,142 @@
+use stellar_ast::{
+    comments::{Comment, CommentTarget, Comments},
+    Module, ModuleItem, Statement,
+};
+use stellar_database::{PackageData, Path, State};
+use stellar_diagnostics::Diagnostics;
+use stellar_filesystem::location::ByteOffset;
+use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+use stellar_parser::{incremental::TextEdit, parse_module_using, ParseResult, ParseState};
+
+fn parse(source: &str) -> ParseResult {
+    let mut state = State::new();
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("test"), DUMMY_PATH_ID);
+
+    let mut diagnostics = Diagnostics::new();
+    let result = parse_module_using(
+        &mut state,
+        package,
+        Path::from(IdentifierId::from("test")),
+        ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics).with_comments(),
+    );
+
+    assert!(diagnostics.is_ok(), "{source}");
+
+    result
+}
+
+fn body(ast: &Module) -> &[Statement] {
+    let ModuleItem::Function(function) = &ast.items[0] else {
+        panic!("expected function");
+    };
+
+    function.body.as_deref().unwrap()
+}
+
+fn texts<'s>(
+    comments: &Comments,
+    source: &'s str,
+    get: impl Fn(&Comments) -> &[Comment],
+) -> Vec<&'s str> {
+    get(comments)
+        .iter()
+        .map(|comment| comment.text(source))
+        .collect()
+}
+
+#[test]
+fn statement_comments() {
+    let source = "// item comment
+fun main() {
+    // first
+    // second
+    let a = 1; // trailing
+
+    if a > 0 {
+        // nested
+        println(a);
+    } // after if
+
+    // dangling
+}";
+    let result = parse(source);
+    let comments = result.comments().unwrap();
+    let body = body(result.ast());
+
+    let let_statement = CommentTarget::Statement(body[0].node_id());
+    assert_eq!(
+        texts(comments, source, |c| c.leading(let_statement)),
+        ["first", "second"]
+    );
+    assert_eq!(
+        texts(comments, source, |c| c.trailing(let_statement)),
+        ["trailing"]
+    );
+
+    let if_statement = CommentTarget::Statement(body[1].node_id());
+    assert!(comments.leading(if_statement).is_empty());
+    assert_eq!(
+        texts(comments, source, |c| c.trailing(if_statement)),
+        ["after if"]
+    );
+
+    assert_eq!(texts(comments, source, Comments::dangling), ["dangling"]);
+    assert!(comments
+        .dangling()
+        .iter()
+        .all(|comment| comment.text(source) != "nested"));
+
+    // only comments in blocks are collected, not the one before the item
+    assert_eq!(comments.len(), 6);
+}
+
+#[test]
+fn field_comments() {
+    let source = "struct Point {
+    // horizontal
+    x: float32, // trailing
+    /// Vertical.
+    y: float32 // last
+    // dangling
+}";
+    let result = parse(source);
+    let comments = result.comments().unwrap();
+
+    let ModuleItem::Struct(point) = &result.ast().items[0] else {
+        panic!("expected struct");
+    };
+    let x = CommentTarget::StructField(point.fields[0].name.location);
+    let y = CommentTarget::StructField(point.fields[1].name.location);
+
+    assert_eq!(texts(comments, source, |c| c.leading(x)), ["horizontal"]);
+    assert_eq!(texts(comments, source, |c| c.trailing(x)), ["trailing"]);
+    assert!(comments.leading(y).is_empty());
+    assert_eq!(texts(comments, source, |c| c.trailing(y)), ["last"]);
+    assert_eq!(texts(comments, source, Comments::dangling), ["dangling"]);
+}
+
+#[test]
+fn comments_are_not_collected_by_default() {
+    let mut diagnostics = Diagnostics::new();
+    let state = ParseState::new(DUMMY_PATH_ID, "fun main() { // a\n}", &mut diagnostics);
+
+    assert!(state.comments().is_none());
+}
+
+#[test]
+fn reparse_updates_comments() {
+    let source = "fun main() {\n    foo(); // old\n}";
+    let mut result = parse(source);
+
+    let edit = TextEdit::new(ByteOffset(27), ByteOffset(30), "new comment");
+    let new_source = edit.apply(source);
+    assert!(!result.reparse(&new_source, &edit, &mut Diagnostics::new()));
+
+    let statement = CommentTarget::Statement(body(result.ast())[0].node_id());
+    assert_eq!(
+        texts(result.comments().unwrap(), &new_source, |c| c
+            .trailing(statement)),
+        ["new comment"]
+    );
+}
@@ -0,0 +1,185 @@
+use stellar_ast::token::{Keyword, RawToken};
+use stellar_diagnostics::Diagnostics;
+use stellar_filesystem::location::ByteOffset;
+use stellar_interner::DUMMY_PATH_ID;
+use stellar_parser::{
+    cst::{SyntaxElement, SyntaxNode, SyntaxTokenKind},
+    event::{Event, NodeKind},
+    parse_cst, parse_events, parse_item_using, ParseState,
+};
+
+/// Returns kinds of a node and all its nested nodes in the preorder.
+fn node_kinds(node: &SyntaxNode) -> Vec<NodeKind> {
+    let mut kinds = vec![node.kind()];
+
+    for child in node.child_nodes() {
+        kinds.extend(node_kinds(child));
+    }
+
+    kinds
+}
+
+#[test]
+fn lossless_round_trip() {
+    let source = "// header
+import std.io;
+
+/// Docstring.
+pub fun main() {
+    let a = 1 + 2; // trailing
+    match a { 3 | 4 => println(a), _ => {} }
+}
+";
+    let mut diagnostics = Diagnostics::new();
+    let tree = parse_cst(DUMMY_PATH_ID, source, &mut diagnostics);
+
+    assert_eq!(tree.root().text(source), source);
+
+    // concatenated leaves must reproduce the source
+    fn collect(node: &SyntaxNode, source: &str, text: &mut String) {
+        for child in node.children() {
+            match child {
+                SyntaxElement::Node(node) => collect(node, source, text),
+                SyntaxElement::Token(token) => text.push_str(&source[token.location]),
+            }
+        }
+    }
+
+    let mut text = String::new();
+    collect(tree.root(), source, &mut text);
+
+    assert_eq!(text, source);
+}
+
+#[test]
+fn nested_nodes() {
+    let source = "fun foo(a: int32) { let b: int32 = a; }";
+    let tree = parse_cst(DUMMY_PATH_ID, source, &mut Diagnostics::new());
+
+    assert_eq!(
+        node_kinds(tree.root()),
+        [
+            NodeKind::Module,
+            NodeKind::Item,
+            NodeKind::Pattern,
+            NodeKind::Type,
+            NodeKind::StatementsBlock,
+            NodeKind::Statement,
+            NodeKind::Pattern,
+            NodeKind::Type,
+            NodeKind::Expression,
+        ]
+    );
+
+    let item = tree.root().child_nodes().next().unwrap();
+    assert_eq!(item.text(source), source);
+
+    let block = item.child_nodes().nth(2).unwrap();
+    assert_eq!(block.text(source), "{ let b: int32 = a; }");
+}
+
+#[test]
+fn trivia_and_comments() {
+    let source = "fun main() {\n    // comment\n    foo();\n}";
+    let tree = parse_cst(DUMMY_PATH_ID, source, &mut Diagnostics::new());
+
+    let block = tree
+        .root()
+        .child_nodes()
+        .next()
+        .unwrap()
+        .child_nodes()
+        .next()
+        .unwrap();
+
+    assert_eq!(block.kind(), NodeKind::StatementsBlock);
+    assert!(block.children().iter().any(|child| matches!(
+        child,
+        SyntaxElement::Token(token)
+            if token.kind == SyntaxTokenKind::Trivia
+                && source[token.location].contains("// comment")
+    )));
+
+    // leading trivia of the statement belongs to the block
+    let statement = block.child_nodes().next().unwrap();
+    assert_eq!(statement.text(source), "foo();");
+}
+
+#[test]
+fn errors_are_recorded() {
+    let source = "fun main() { let = 1; }\nstruct A {}";
+    let mut diagnostics = Diagnostics::new();
+    let tree = parse_cst(DUMMY_PATH_ID, source, &mut diagnostics);
+
+    assert!(!diagnostics.is_ok());
+    assert_eq!(tree.root().text(source), source);
+    assert_eq!(tree.errors().len(), 1);
+    assert_eq!(&source[tree.errors()[0].location], "=");
+
+    // the parser recovers at the next item
+    assert!(tree
+        .root()
+        .child_nodes()
+        .any(|node| node.text(source) == "struct A {}"));
+}
+
+#[test]
+fn events_are_balanced() {
+    let events = parse_events(
+        DUMMY_PATH_ID,
+        "fun main() { if a { b } else { c } }",
+        &mut Diagnostics::new(),
+    );
+
+    let mut depth = 0_usize;
+
+    for event in &events {
+        match event {
+            Event::Start { .. } => depth += 1,
+            Event::Finish => depth = depth.checked_sub(1).unwrap(),
+            Event::Token(_) | Event::Error { .. } => {}
+        }
+    }
+
+    assert_eq!(depth, 0);
+    assert!(matches!(
+        events.iter().find(|event| matches!(event, Event::Token(_))),
+        Some(Event::Token(token)) if token.raw == RawToken::Keyword(Keyword::Fun)
+    ));
+}
+
+#[test]
+fn recording_is_disabled_by_default() {
+    let mut diagnostics = Diagnostics::new();
+    let mut state = ParseState::new(DUMMY_PATH_ID, "fun main() {}", &mut diagnostics);
+
+    parse_item_using(&mut state).unwrap();
+
+    assert!(state.events().is_empty());
+}
+
+#[test]
+fn lookup_by_offset() {
+    let source = "fun main() { foo(1 + 2); }";
+    let tree = parse_cst(DUMMY_PATH_ID, source, &mut Diagnostics::new());
+    let offset = |text: &str| ByteOffset(source.find(text).unwrap());
+
+    let token = tree.root().token_at_offset(offset("foo")).unwrap();
+    assert_eq!(&source[token.location], "foo");
+    assert_eq!(token.kind, SyntaxTokenKind::Token(RawToken::Identifier));
+
+    let trivia = tree.root().token_at_offset(offset(" foo")).unwrap();
+    assert_eq!(trivia.kind, SyntaxTokenKind::Trivia);
+
+    assert!(tree
+        .root()
+        .token_at_offset(ByteOffset(source.len()))
+        .is_none());
+
+    // `1 + 2` is the innermost node covering `+`
+    let plus = tree.root().token_at_offset(offset("+")).unwrap();
+    let node = tree.root().covering_node(plus.location);
+
+    assert_eq!(node.kind(), NodeKind::Expression);
+    assert_eq!(node.text(source), "1 + 2");
+}
@@ -11,21 +11,660 @@ tests_using! {
     call -> "f()",
     nested_call -> "f()()",
     method_call -> "a.f()",
+    call_chain -> "a.b(c).d(e, 1).f",
+    call_chain_operand -> "-a.b() * f(x).y as int32 + 1.to_string().len()",
     integer_method_call -> "1.to_string()",
     float_method_call -> "1.2.to_string()",
     binary1 -> "1 + 2",
     binary2 -> "(f()? + 2) / 3.2 + !a()",
+    power -> "2 ** n * 3",
+    bitwise_and_assignment -> "flags &= mask",
+    range -> "a..b",
+    inclusive_range -> "1..=10",
+    range_to -> "..b + 1",
+    range_from -> "a..",
+    range_full -> "..",
+    range_in_call -> "f(1.., ..=2)",
+    full_range_in_call -> "f(.., a: ..)",
+    ranges_in_list -> "[.., 1, ..]",
     struct_ -> "Person { name: \"John\", age }",
+    struct_update -> "Person { name: \"Ann\", ..defaults }",
+    struct_update_only -> "Point { ..Point.origin() }",
     tuple -> "(1, (1, \"hello\"), true)",
     list -> "[1, 2, 3]",
     postfix -> "checked_div(1, 0)?",
     prefix -> "!++a",
     double_plus_hell -> "++a++",
     if_else -> "if true { 1 } else if f() { 3 } else { 2 }",
+    if_let -> "if let Some(x) = f() { x } else { 0 }",
+    if_let_chain -> "if let Some(x) = a { x } else if let Ok(y) = b { y } else if c { 1 }",
+    else_if_let -> "if a { 1 } else if let Some(x) = b { x } else { 2 }",
     loop_ -> "loop {}",
     while_ -> "while true { }",
+    while_let -> "while let Some(x) = iter.next() { println(x); }",
+    for_ -> "for (i, x) in items.enumerate() { println(x); }",
     underscore -> "_",
     match_ -> "match true { true -> 1, _ -> 2 }",
     lambda -> "|a, b: usize| a + b",
-    block -> "{ a++; a }"
+    lambda_with_captures -> "|[move a, &b, &mut c] d| a + b + c + d",
+    lambda_with_only_captures -> "|[&a]| a.len()",
+    block -> "{ a++; a }",
+    unsafe_block -> "unsafe { ptr.read() }",
+    await_ -> "fetch(url).await?.json().await",
+    try_block -> "try { parse(a)? + parse(b)? }",
+    named_arguments -> "draw(shape, x: 10, y: f(a: 1))",
+    shorthand_arguments -> "draw(shape, x:, y:)",
+    spread_elements -> "[1, ..rest, ..[2, 3], 5]",
+    spread_arguments -> "f(a, ..args, b: ..=5)",
+    array_repeat -> "[0; 4]",
+    nested_array_repeat -> "[[0; N]; 2 * N]",
+    map -> "{\"a\": 1, \"b\": { c: [1, 2] }, f(x): y,}",
+    empty_map -> "{:}",
+    borrow -> "&a",
+    mutable_borrow -> "&mut a.b[0]",
+    double_borrow -> "&&mut a",
+    tuple_index -> "a.0 + b.1.c",
+    nested_tuple_index -> "a.0.1.2",
+    tuple_index_call -> "f().0.len()"
+}
+
+#[test]
+fn named_argument_labels() {
+    use stellar_ast::Expression;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+    use stellar_parser::parse_expression;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(Expression::Call { arguments, .. }) =
+        parse_expression(DUMMY_PATH_ID, "draw(shape, x: 10)", &mut diagnostics)
+    else {
+        panic!("expected call expression");
+    };
+
+    assert_eq!(
+        arguments
+            .iter()
+            .map(|argument| argument.name.map(|name| name.id))
+            .collect::<Vec<_>>(),
+        [None, Some(IdentifierId::from("x"))]
+    );
+    assert!(diagnostics.is_ok());
+
+    // only identifiers can be labels
+    let _expression = parse_expression(DUMMY_PATH_ID, "draw(a.x: 10)", &mut diagnostics);
+
+    assert!(diagnostics.is_fatal());
+}
+
+#[test]
+fn shorthand_arguments() {
+    use stellar_ast::Expression;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+    use stellar_parser::parse_expression;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(Expression::Call { arguments, .. }) =
+        parse_expression(DUMMY_PATH_ID, "draw(x:, y: y, z:)", &mut diagnostics)
+    else {
+        panic!("expected call expression");
+    };
+
+    assert_eq!(
+        arguments
+            .iter()
+            .map(|argument| argument.is_shorthand)
+            .collect::<Vec<_>>(),
+        [true, false, true]
+    );
+    assert!(matches!(
+        arguments[0].value,
+        Expression::Identifier { identifier, .. } if identifier.id == IdentifierId::from("x")
+    ));
+    assert!(diagnostics.is_ok());
+}
+
+#[test]
+fn fast_operands() {
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::{parse_expression_using, ParseState};
+
+    let parse = |source: &str, fast: bool| {
+        let mut diagnostics = Diagnostics::new();
+        let mut state = ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics);
+
+        if !fast {
+            state = state.without_fast_operands();
+        }
+
+        let expression = parse_expression_using(&mut state);
+        assert!(diagnostics.is_ok(), "{source}");
+
+        expression
+    };
+
+    for source in [
+        // literals
+        "1",
+        "1.5",
+        "\"string\"",
+        "'c'",
+        "true",
+        // paths
+        "a",
+        "a.b.c",
+        "a.0.1",
+        // call and field access chains
+        "f()",
+        "f()(a, b: 1)",
+        "a.b(c).d(e, 1).f",
+        "1.to_string().len()",
+        "f().0.len()",
+        "fetch(url).await?.json()",
+        // operands of other expressions
+        "-a.b() * f(x).y as int32 + 1.to_string().len()",
+        "[a.b, f(c)..d.e, Point { x: a.x }]",
+        "if a.b() { c.d } else { e(f) }",
+    ] {
+        assert_eq!(parse(source, true), parse(source, false), "{source}");
+    }
+}
+
+#[test]
+fn spread_element_kinds() {
+    use stellar_ast::{ElementKind, Expression};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_expression;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(Expression::List { elements, .. }) =
+        parse_expression(DUMMY_PATH_ID, "[1, ..rest, ..=5]", &mut diagnostics)
+    else {
+        panic!("expected list expression");
+    };
+
+    assert_eq!(
+        elements
+            .iter()
+            .map(|element| element.kind)
+            .collect::<Vec<_>>(),
+        [
+            ElementKind::Single,
+            ElementKind::Spread,
+            ElementKind::Single
+        ]
+    );
+    assert!(matches!(elements[1].value, Expression::Identifier { .. }));
+    assert!(matches!(elements[2].value, Expression::Range { .. }));
+
+    let Some(Expression::Call { arguments, .. }) =
+        parse_expression(DUMMY_PATH_ID, "f(a, ..args)", &mut diagnostics)
+    else {
+        panic!("expected call expression");
+    };
+
+    assert_eq!(arguments[1].kind, ElementKind::Spread);
+    assert!(diagnostics.is_ok());
+}
+
+#[test]
+fn ranges_are_not_spread_elements() {
+    use stellar_ast::{ElementKind, Expression};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_expression;
+
+    let mut diagnostics = Diagnostics::new();
+
+    for source in ["[..]", "[1, ..]", "[.., 1]"] {
+        let Some(Expression::List { elements, .. }) =
+            parse_expression(DUMMY_PATH_ID, source, &mut diagnostics)
+        else {
+            panic!("expected list expression in {source}");
+        };
+
+        assert!(elements
+            .iter()
+            .all(|element| element.kind == ElementKind::Single));
+        assert!(elements.iter().any(|element| matches!(
+            element.value,
+            Expression::Range {
+                start: None,
+                end: None,
+                ..
+            }
+        )));
+    }
+
+    for source in ["f(..)", "f(a, ..)", "f(.., b: ..)"] {
+        let Some(Expression::Call { arguments, .. }) =
+            parse_expression(DUMMY_PATH_ID, source, &mut diagnostics)
+        else {
+            panic!("expected call expression in {source}");
+        };
+
+        assert!(arguments
+            .iter()
+            .all(|argument| argument.kind == ElementKind::Single));
+    }
+
+    assert!(diagnostics.is_ok());
+}
+
+#[test]
+fn array_repeat_or_list() {
+    use stellar_ast::Expression;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_expression;
+
+    let mut diagnostics = Diagnostics::new();
+
+    assert!(matches!(
+        parse_expression(DUMMY_PATH_ID, "[a; 2]", &mut diagnostics),
+        Some(Expression::ArrayRepeat { .. })
+    ));
+    assert!(matches!(
+        parse_expression(DUMMY_PATH_ID, "[a, 2]", &mut diagnostics),
+        Some(Expression::List { elements, .. }) if elements.len() == 2
+    ));
+    assert!(matches!(
+        parse_expression(DUMMY_PATH_ID, "[a]", &mut diagnostics),
+        Some(Expression::List { elements, .. }) if elements.len() == 1
+    ));
+    assert!(diagnostics.is_ok());
+
+    // spread elements can't be repeated
+    let _expression = parse_expression(DUMMY_PATH_ID, "[..a; 2]", &mut diagnostics);
+
+    assert!(diagnostics.is_fatal());
+}
+
+#[test]
+fn map_or_block() {
+    use stellar_ast::Expression;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_expression;
+
+    let mut diagnostics = Diagnostics::new();
+
+    for source in ["{\"a\": 1}", "{ (a, b): c }", "{ f(x): y }", "{:}"] {
+        assert!(
+            matches!(
+                parse_expression(DUMMY_PATH_ID, source, &mut diagnostics),
+                Some(Expression::Map { .. })
+            ),
+            "`{source}` is not a map"
+        );
+    }
+
+    for source in [
+        "{}",
+        "{ a }",
+        "{ let a: int32 = 1; a }",
+        "{ f(|a: int32| a); }",
+        "{ |a: int32| a }",
+        "{ Point { x: 1 } }",
+        "{ match a { Some(b) -> Point { b: 1 }, _ -> c } }",
+    ] {
+        assert!(
+            matches!(
+                parse_expression(DUMMY_PATH_ID, source, &mut diagnostics),
+                Some(Expression::StatementsBlock { .. })
+            ),
+            "`{source}` is not a block"
+        );
+    }
+
+    assert!(diagnostics.is_ok());
+}
+
+#[test]
+fn borrow_expressions() {
+    use stellar_ast::{Expression, RawBinaryOperator};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_expression;
+
+    let mut diagnostics = Diagnostics::new();
+
+    // `&&` is a borrow only in the prefix position
+    let Some(Expression::Binary { left, operator, .. }) =
+        parse_expression(DUMMY_PATH_ID, "&a && b", &mut diagnostics)
+    else {
+        panic!("expected binary expression");
+    };
+
+    assert_eq!(operator.raw, RawBinaryOperator::DoubleAmpersand);
+    assert!(matches!(
+        *left,
+        Expression::Borrow {
+            is_mutable: false,
+            ..
+        }
+    ));
+
+    let source = "&&mut a";
+    let Some(Expression::Borrow {
+        location,
+        is_mutable: false,
+        inner,
+        ..
+    }) = parse_expression(DUMMY_PATH_ID, source, &mut diagnostics)
+    else {
+        panic!("expected borrow expression");
+    };
+
+    assert_eq!(&source[location], "&&mut a");
+    assert!(matches!(
+        *inner,
+        Expression::Borrow { location, is_mutable: true, .. } if &source[location] == "&mut a"
+    ));
+    assert!(diagnostics.is_ok());
+}
+
+#[test]
+fn lambda_captures() {
+    use stellar_ast::{CaptureKind, Expression};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_expression;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let source = "|[move a, &b, &mut c] d| a + b + c + d";
+    let Some(Expression::Lambda {
+        captures,
+        parameters,
+        ..
+    }) = parse_expression(DUMMY_PATH_ID, source, &mut diagnostics)
+    else {
+        panic!("expected lambda expression");
+    };
+
+    assert_eq!(
+        captures
+            .iter()
+            .map(|capture| (capture.kind, &source[capture.location]))
+            .collect::<Vec<_>>(),
+        [
+            (CaptureKind::Move, "move a"),
+            (CaptureKind::Reference, "&b"),
+            (CaptureKind::MutableReference, "&mut c")
+        ]
+    );
+    assert_eq!(parameters.len(), 1);
+    assert!(diagnostics.is_ok());
+
+    // only variables can be captured
+    assert!(parse_expression(DUMMY_PATH_ID, "|[a]| a", &mut diagnostics).is_none());
+    assert!(!diagnostics.is_ok());
+}
+
+#[test]
+fn nesting_limit() {
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::{parse_expression, parse_expression_using, ParseState};
+
+    // deep enough to overflow the stack without the limit
+    let source = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+
+    let mut diagnostics = Diagnostics::new();
+    assert!(parse_expression(DUMMY_PATH_ID, &source, &mut diagnostics).is_none());
+    assert_eq!(diagnostics.diagnostics.len(), 1);
+    assert_eq!(diagnostics.diagnostics[0].code.as_deref(), Some("E023"));
+
+    let source = "-(-(-(a)))";
+
+    let mut diagnostics = Diagnostics::new();
+    let mut state = ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics).with_max_depth(4);
+    assert!(parse_expression_using(&mut state).is_none());
+    assert!(!diagnostics.is_ok());
+
+    let mut diagnostics = Diagnostics::new();
+    let mut state = ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics).with_max_depth(7);
+    assert!(parse_expression_using(&mut state).is_some());
+    assert!(diagnostics.is_ok());
+}
+
+#[test]
+fn nesting_limit_of_chains() {
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::{parse_expression, parse_expression_using, ParseState};
+
+    // flat in the source, but as deep as parentheses in the AST
+    for source in [
+        format!("1{}", " + 1".repeat(100_000)),
+        format!("f{}", "()".repeat(100_000)),
+        format!("a{}", ".b".repeat(100_000)),
+        format!("a{}", " = a".repeat(100_000)),
+    ] {
+        let mut diagnostics = Diagnostics::new();
+        assert!(parse_expression(DUMMY_PATH_ID, &source, &mut diagnostics).is_none());
+        assert_eq!(diagnostics.diagnostics.len(), 1);
+        assert_eq!(diagnostics.diagnostics[0].code.as_deref(), Some("E023"));
+    }
+
+    let source = "a.b(c) + 1";
+
+    let mut diagnostics = Diagnostics::new();
+    let mut state =
+        ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics).with_max_chain_length(2);
+    assert!(parse_expression_using(&mut state).is_none());
+    assert!(!diagnostics.is_ok());
+
+    let mut diagnostics = Diagnostics::new();
+    let mut state =
+        ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics).with_max_chain_length(3);
+    assert!(parse_expression_using(&mut state).is_some());
+    assert!(diagnostics.is_ok());
+
+    // chains don't count towards the nesting limit
+    let mut diagnostics = Diagnostics::new();
+    let mut state = ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics).with_max_depth(2);
+    assert!(parse_expression_using(&mut state).is_some());
+    assert!(diagnostics.is_ok());
+}
+
+#[test]
+fn long_chains() {
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::{parse_expression, parse_item_using, ParseState};
+
+    for source in [
+        format!("x{}", " + x".repeat(500)),
+        format!("a{}", ".b(c)".repeat(200)),
+        format!("(x{}).y", " * (x{})".repeat(300)),
+    ] {
+        let mut diagnostics = Diagnostics::new();
+        assert!(parse_expression(DUMMY_PATH_ID, &source, &mut diagnostics).is_some());
+        assert!(diagnostics.is_ok());
+    }
+
+    let source = format!(
+        "fun main() {{ if true {{ return x{}; }} }}",
+        " + x".repeat(ParseState::DEFAULT_MAX_CHAIN_LENGTH - 1)
+    );
+
+    // the limit is the same inside of nested syntax
+    let mut diagnostics = Diagnostics::new();
+    let mut state = ParseState::new(DUMMY_PATH_ID, &source, &mut diagnostics);
+    assert!(parse_item_using(&mut state).is_some());
+    assert!(diagnostics.is_ok());
+}
+
+#[test]
+fn fuel_limit() {
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::{parse_expression_using, ParseState};
+
+    let source = "a + b + c";
+
+    let mut diagnostics = Diagnostics::new();
+    let mut state = ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics).with_fuel(5);
+    assert!(parse_expression_using(&mut state).is_some());
+    assert!(diagnostics.is_ok());
+
+    // the parser stops after `a + b +`
+    let mut diagnostics = Diagnostics::new();
+    let mut state = ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics).with_fuel(4);
+    assert!(parse_expression_using(&mut state).is_none());
+    assert!(diagnostics
+        .diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.code.as_deref() == Some("E024")));
+}
+
+#[test]
+fn tuple_indices() {
+    use stellar_ast::Expression;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_expression;
+
+    let mut diagnostics = Diagnostics::new();
+
+    // `.0.1` is lexed as two float literals
+    let source = "a.0.1";
+    let Some(Expression::TupleIndex {
+        left,
+        index: 1,
+        index_location,
+        ..
+    }) = parse_expression(DUMMY_PATH_ID, source, &mut diagnostics)
+    else {
+        panic!("expected tuple index expression");
+    };
+
+    assert_eq!(&source[index_location], "1");
+    assert!(matches!(
+        *left,
+        Expression::TupleIndex { index: 0, location, .. } if &source[location] == "a.0"
+    ));
+    assert!(diagnostics.is_ok());
+
+    assert!(matches!(
+        parse_expression(DUMMY_PATH_ID, "a. 12", &mut diagnostics),
+        Some(Expression::TupleIndex { index: 12, .. })
+    ));
+    assert!(diagnostics.is_ok());
+
+    for source in ["a.01", "a.1e3", "a.1_0"] {
+        let mut diagnostics = Diagnostics::new();
+
+        assert!(parse_expression(DUMMY_PATH_ID, source, &mut diagnostics).is_none());
+        assert_eq!(diagnostics.diagnostics[0].code.as_deref(), Some("E025"));
+    }
+}
+
+#[test]
+fn struct_update() {
+    use stellar_ast::Expression;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_expression;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let source = "Person { name: \"Ann\", age, ..defaults }";
+    let Some(Expression::Struct {
+        location,
+        fields,
+        rest: Some(rest),
+        ..
+    }) = parse_expression(DUMMY_PATH_ID, source, &mut diagnostics)
+    else {
+        panic!("expected struct expression with rest");
+    };
+
+    assert_eq!(&source[location], source);
+    assert_eq!(fields.len(), 2);
+    assert_eq!(&source[rest.location()], "defaults");
+    assert!(diagnostics.is_ok());
+
+    // the base must be the last one and must be separated with a comma
+    for source in ["A { a ..b }", "A { ..b, }", "A { ..b, c }"] {
+        let mut diagnostics = Diagnostics::new();
+
+        assert!(parse_expression(DUMMY_PATH_ID, source, &mut diagnostics).is_none());
+        assert!(!diagnostics.is_ok());
+    }
+}
+
+#[test]
+fn operator_table() {
+    use stellar_ast::{
+        precedence::{Fixity, OPERATORS},
+        Expression,
+    };
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_expression;
+
+    for operator in OPERATORS {
+        if operator.fixity != Fixity::Infix || !operator.token.is_binary_operator() {
+            continue;
+        }
+
+        let operator = operator.token.to_string();
+        let operator = operator.trim_matches('`');
+        let source = format!("a {operator} b {operator} c");
+
+        let mut diagnostics = Diagnostics::new();
+        let Some(Expression::Binary { left, .. }) =
+            parse_expression(DUMMY_PATH_ID, &source, &mut diagnostics)
+        else {
+            panic!("expected binary expression: {source}");
+        };
+
+        // all binary operators are left-associative
+        assert!(matches!(*left, Expression::Binary { .. }), "{source}");
+        assert!(diagnostics.is_ok(), "{source}");
+    }
+}
+
+#[test]
+fn integer_literals() {
+    use stellar_ast::{Expression, Literal};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_expression;
+
+    let integer = |source| {
+        let mut diagnostics = Diagnostics::new();
+        let expression = parse_expression(DUMMY_PATH_ID, source, &mut diagnostics);
+        assert!(diagnostics.is_ok(), "{source}");
+
+        let Some(Expression::Literal {
+            literal: Literal::Integer { value, .. },
+            ..
+        }) = expression
+        else {
+            panic!("expected integer literal: {source}");
+        };
+
+        value
+    };
+
+    assert_eq!(integer("1_000").to_u64(), Some(1000));
+    assert_eq!(integer("0xFF").to_u64(), Some(255));
+    assert_eq!(integer("0o17").to_u64(), Some(15));
+    assert_eq!(integer("0b101").to_u64(), Some(5));
+
+    // overflows are reported during type checking
+    let value = integer("340282366920938463463374607431768211456");
+    assert_eq!(value.to_u128(), None);
+    assert_eq!(value.bits(), 129);
+    assert_eq!(value.to_string(), "340282366920938463463374607431768211456");
 }
@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use stellar_ast::{visit::Visitor, Expression, Module, NodeId, Statement};
+use stellar_database::{query::Queries, PackageData, Path, State};
+use stellar_diagnostics::Diagnostics;
+use stellar_filesystem::location::ByteOffset;
+use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+use stellar_parser::{incremental::TextEdit, parse_module, query::ParseFile, ParseResult};
+
+fn parse(source: &str) -> ParseResult {
+    let mut state = State::new();
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("test"), DUMMY_PATH_ID);
+
+    parse_module(
+        &mut state,
+        package,
+        Path::from(IdentifierId::from("test")),
+        DUMMY_PATH_ID,
+        source,
+    )
+}
+
+/// Returns the debug representation of a module without node IDs, which differ
+/// between reparsed modules and modules parsed from scratch.
+fn without_node_ids(ast: &Module) -> String {
+    let debug = format!("{ast:?}");
+    let mut parts = debug.split("node_id: NodeId(");
+
+    let mut result = parts.next().unwrap().to_owned();
+    for part in parts {
+        result.push_str(part.trim_start_matches(|c: char| c.is_ascii_digit()));
+    }
+
+    result
+}
+
+/// Collects node IDs of expressions and statements.
+#[derive(Default)]
+struct NodeIds(Vec<NodeId>);
+
+impl Visitor for NodeIds {
+    fn visit_expression(&mut self, expression: &Expression) {
+        self.0.push(expression.node_id());
+        stellar_ast::visit::walk_expression(self, expression);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        self.0.push(statement.node_id());
+        stellar_ast::visit::walk_statement(self, statement);
+    }
+}
+
+fn node_ids(ast: &Module) -> Vec<NodeId> {
+    let mut node_ids = NodeIds::default();
+    node_ids.visit_module(ast);
+    node_ids
+        .0
+        .extend(ast.items.iter().map(stellar_ast::ModuleItem::node_id));
+
+    node_ids.0
+}
+
+/// Replaces the first occurrence of `old` with `new`, checks that the reparsed
+/// module is the same as the module parsed from scratch (except for node IDs)
+/// and returns whether the edit was handled locally.
+fn reparse(source: &str, old: &str, new: &str) -> bool {
+    let start = source.find(old).unwrap();
+    let edit = TextEdit::new(ByteOffset(start), ByteOffset(start + old.len()), new);
+    let new_source = edit.apply(source);
+
+    let mut result = parse(source);
+    let is_local = result.reparse(&new_source, &edit, &mut Diagnostics::new());
+
+    assert_eq!(
+        without_node_ids(result.ast()),
+        without_node_ids(parse(&new_source).ast())
+    );
+
+    is_local
+}
+
+const SOURCE: &str = "//! Module docstring.
+
+import std.io;
+
+/// Docstring.
+fun foo() {
+    a(1 + 2);
+}
+
+struct A { a: int32 }
+
+// comment
+fun bar(): A { A { a: 3 } }
+";
+
+#[test]
+fn edit_inside_of_function_body() {
+    assert!(reparse(SOURCE, "a(1 + 2);", "a(1 + 2);\n    b(3);"));
+    assert!(reparse(SOURCE, "a(1 + 2);", ""));
+}
+
+#[test]
+fn following_items_are_moved() {
+    let start = SOURCE.find("a: int32").unwrap();
+    let edit = TextEdit::new(ByteOffset(start), ByteOffset(start + 1), "value");
+    let source = edit.apply(SOURCE);
+
+    let mut result = parse(SOURCE);
+    assert!(result.reparse(&source, &edit, &mut Diagnostics::new()));
+
+    let source = source.as_str();
+    assert_eq!(
+        result
+            .ast()
+            .items
+            .iter()
+            .map(|item| &source[item.location()])
+            .collect::<Vec<_>>(),
+        ["import std.io;", "foo", "A", "bar"]
+    );
+}
+
+#[test]
+fn adding_and_removing_items() {
+    assert!(reparse(
+        SOURCE,
+        "struct A { a: int32 }",
+        "struct A { a: int32 }\nstruct B {}"
+    ));
+    assert!(reparse(SOURCE, "struct A { a: int32 }", ""));
+    assert!(reparse(SOURCE, "}\n", "}\nfun baz() {}\n"));
+    assert!(reparse(SOURCE, "// comment", "/// Docstring."));
+}
+
+#[test]
+fn edit_across_items() {
+    assert!(reparse(SOURCE, "int32 }\n\n// comment", "int64 }"));
+}
+
+#[test]
+fn edit_at_the_end_of_file() {
+    assert!(reparse(SOURCE, "} }\n", "} }\n\nenum B { C }\n"));
+}
+
+#[test]
+fn fallback_to_parsing_whole_module() {
+    // the function body consumes the following items
+    assert!(!reparse(SOURCE, "a(1 + 2);\n}", "a(1 + 2);"));
+
+    // the comment hides the next item
+    assert!(!reparse(
+        "fun a() {}\nfun b() {} fun c() {}",
+        "b() {}",
+        "b() {} //"
+    ));
+
+    // the module docstring changes
+    assert!(!reparse(SOURCE, "Module docstring.", "Docs."));
+}
+
+#[test]
+fn node_ids_are_unique() {
+    let result = parse(SOURCE);
+
+    let mut node_ids = node_ids(result.ast());
+    let count = node_ids.len();
+    node_ids.sort_unstable();
+    node_ids.dedup();
+
+    assert_eq!(node_ids.len(), count);
+    assert!(node_ids
+        .iter()
+        .all(|node_id| *node_id < result.next_node_id()));
+}
+
+#[test]
+fn untouched_items_keep_node_ids() {
+    let start = SOURCE.find("a(1 + 2);").unwrap();
+    let edit = TextEdit::new(ByteOffset(start), ByteOffset(start), "b();\n    ");
+    let source = edit.apply(SOURCE);
+
+    let mut result = parse(SOURCE);
+    let old_ids = node_ids(result.ast());
+    let old_items = result.ast().items.clone();
+    let old_next_node_id = result.next_node_id();
+
+    assert!(result.reparse(&source, &edit, &mut Diagnostics::new()));
+
+    let items = &result.ast().items;
+    for idx in [0, 2, 3] {
+        assert_eq!(items[idx].node_id(), old_items[idx].node_id());
+    }
+
+    // the reparsed function doesn't reuse IDs of the old module
+    let Some(stellar_ast::ModuleItem::Function(function)) = items.get(1) else {
+        panic!("expected function");
+    };
+    assert!(function.node_id >= old_next_node_id);
+    assert!(node_ids(result.ast())
+        .iter()
+        .all(|node_id| old_ids.contains(node_id) || *node_id >= old_next_node_id));
+}
+
+#[test]
+fn parsed_files_are_memoized_only_with_queries() {
+    let parse_in = |state: &mut State| {
+        let package =
+            PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("test"), DUMMY_PATH_ID);
+
+        parse_module(
+            state,
+            package,
+            Path::from(IdentifierId::from("test")),
+            DUMMY_PATH_ID,
+            SOURCE,
+        )
+    };
+
+    // one-shot runs don't keep a copy of the AST
+    let mut state = State::new();
+    let _ = parse_in(&mut state);
+    assert!(state.queries().is_none());
+
+    let mut state = State::new().with_queries(Queries::new());
+    let first = parse_in(&mut state);
+    let parsed = state
+        .queries_mut()
+        .unwrap()
+        .get::<ParseFile>(&DUMMY_PATH_ID);
+    let second = parse_in(&mut state);
+
+    assert_eq!(first.ast(), parsed.ast());
+    assert_eq!(second.ast(), parsed.ast());
+    assert!(Arc::ptr_eq(
+        &parsed,
+        &state
+            .queries_mut()
+            .unwrap()
+            .get::<ParseFile>(&DUMMY_PATH_ID)
+    ));
+}
@@ -0,0 +1,626 @@
+mod macros;
+
+tests_using! {
+    parse_item,
+    attribute -> "#[inline] fun foo() {}",
+    attribute_with_arguments -> "#[deprecated(\"use `bar` instead\")] fun foo() {}",
+    several_attributes -> "#[inline, test] #[deprecated] pub fun foo() {}",
+    attribute_path -> "#[lint.allow(unused)] import std.io;",
+    method_attributes -> "struct A { a: int32, #[inline] fun foo() {} #[test] pub fun bar() {} }",
+    field_attributes -> "struct A { #[deprecated] pub a: int32, #[deprecated(\"\")] b: int32 }",
+    enum_method_attributes -> "enum A { B, C #[inline] fun foo() {} }",
+    interface_method_attributes -> "interface A { #[inline] fun foo(); }",
+    constant -> "const MAX_SIZE: uint32 = 1024;",
+    public_constant -> "pub const NAME: String = \"stellar\";",
+    interface_constants -> "interface A { const B: uint32; const C: uint32 = 1; fun foo(); }",
+    deprecated_trait_keyword -> "trait A { fun foo(); }",
+    static_item -> "static COUNTER: uint32 = 0;",
+    mutable_static_item -> "pub static mut COUNTER: uint32 = 0;",
+    impl_ -> "impl Point { pub fun new(): Point { Point { x: 0, y: 0 } } fun foo(self) {} }",
+    generic_impl -> "impl[T] List[T] where T: Clone { fun first(self): Option[T] {} }",
+    interface_impl -> "impl[T] Iterator[T] for Range[T] { #[inline] fun next(self): Option[T] {} }",
+    const_generic_parameters -> "struct Array[T, const N: usize = 4] { inner: Buffer[T, N] }",
+    const_generic_impl -> "impl[T, const N: usize] Array[T, N] { fun len(self): usize { N } }",
+    submodule_declaration -> "pub module utils;",
+    inline_submodule -> "module utils { pub fun foo() {} module fs { struct File {} } }",
+    package_visibility -> "pub(package) fun foo() {}",
+    super_visibility -> "struct A { pub(super) a: int32, pub(package) fun foo() {} }",
+    glob_import -> "import std.collections.*;",
+    grouped_import -> "import std.{io, fs as filesystem, collections.*, net.{tcp, udp},};",
+    empty_grouped_import -> "import std.{};",
+    reexport -> "pub import std.io.println;",
+    grouped_reexport -> "pub(package) import self.utils.{fs.File, net};",
+    extern_block -> "extern \"C\" { fun puts(s: CStr): int32; #[inline] pub fun abs(x: int32): int32; }",
+    empty_extern_block -> "extern \"C\" {}",
+    unsafe_function -> "pub unsafe fun dealloc(ptr: RawPtr) { unsafe { free(ptr) } }",
+    unsafe_methods -> "struct A { a: int32, unsafe fun b() {} } impl A { unsafe fun c(self) {} }",
+    unsafe_extern_function -> "extern \"C\" { unsafe fun free(ptr: RawPtr); }",
+    async_function -> "pub async fun fetch(url: String): Response { get(url).await }",
+    async_methods -> "interface A { async fun b(self); } impl A for C { async unsafe fun b(self) {} }"
+}
+
+#[test]
+fn deferred_function_body() {
+    use stellar_ast::ModuleItem;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::{parse_function_body, parse_item_using, ParseState};
+
+    let source = "fun foo() { if true { bar(); } \"}\" }";
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Function(function)) = parse_item_using(
+        &mut ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics)
+            .with_deferred_function_bodies(),
+    ) else {
+        panic!("expected function");
+    };
+
+    assert!(function.is_body_deferred());
+
+    let body_location = function.body_location.unwrap();
+    assert_eq!(body_location.end.0, source.len());
+
+    let body = parse_function_body(DUMMY_PATH_ID, source, body_location, &mut diagnostics);
+
+    assert_eq!(body.map(|body| body.len()), Some(2));
+    assert!(diagnostics.is_ok());
+}
+
+#[test]
+fn deferred_function_body_with_options() {
+    use stellar_ast::ModuleItem;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::{
+        options::ParseOptions, parse_function_body_using, parse_item_using, ParseState,
+    };
+
+    let source = "fun foo() { try { bar()? }; }";
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Function(function)) = parse_item_using(
+        &mut ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics)
+            .with_options(ParseOptions::default())
+            .with_deferred_function_bodies(),
+    ) else {
+        panic!("expected function");
+    };
+
+    // gated syntax inside of the body is not checked until it is parsed
+    assert!(diagnostics.is_ok());
+
+    let body = parse_function_body_using(
+        &mut ParseState::new_at(
+            DUMMY_PATH_ID,
+            source,
+            function.body_location.unwrap().start,
+            &mut diagnostics,
+        )
+        .with_options(ParseOptions::default()),
+    );
+
+    assert_eq!(body.map(|body| body.len()), Some(1));
+    assert_eq!(diagnostics.diagnostics[0].code.as_deref(), Some("E022"));
+}
+
+#[test]
+fn const_generic_parameter() {
+    use stellar_ast::{ConstValue, ModuleItem, Type};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_item;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Struct(struct_)) = parse_item(
+        DUMMY_PATH_ID,
+        "struct Array[T, const N: usize = 4] {}",
+        &mut diagnostics,
+    ) else {
+        panic!("expected struct");
+    };
+
+    let [type_parameter, const_parameter] = struct_.generic_parameters.as_slice() else {
+        panic!("expected two generic parameters");
+    };
+
+    assert!(!type_parameter.is_const());
+    assert!(const_parameter.is_const());
+    assert!(matches!(
+        const_parameter.default_value,
+        Some(Type::Const {
+            value: ConstValue::Integer { value: 4 },
+            ..
+        })
+    ));
+    assert!(diagnostics.is_ok());
+
+    // const generic arguments are stored as `u64`
+    assert!(parse_item(
+        DUMMY_PATH_ID,
+        "struct Array[T, const N: usize = 18446744073709551616] {}",
+        &mut diagnostics,
+    )
+    .is_none());
+    assert_eq!(diagnostics.diagnostics[0].code.as_deref(), Some("E002"));
+}
+
+#[test]
+fn cancelled_parsing() {
+    use stellar_database::{cancellation::CancellationToken, PackageData, Path, State};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+    use stellar_parser::{parse_module, parse_module_using, ParseState};
+
+    let source = "fun a() {} fun b() {}";
+    let token = CancellationToken::new();
+
+    let mut state = State::new().with_cancellation_token(token.clone());
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("test"), DUMMY_PATH_ID);
+    let path = || Path::from(IdentifierId::from("test"));
+
+    let result = parse_module(&mut state, package, path(), DUMMY_PATH_ID, source);
+    assert_eq!(result.ast().items.len(), 2);
+
+    token.cancel();
+
+    let result = parse_module(&mut state, package, path(), DUMMY_PATH_ID, source);
+    assert!(result.ast().items.is_empty());
+
+    let mut diagnostics = Diagnostics::new();
+    let result = parse_module_using(
+        &mut state,
+        package,
+        path(),
+        ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics).with_cancellation_token(token),
+    );
+    assert!(result.ast().items.is_empty());
+}
+
+#[test]
+fn glob_import_path() {
+    use stellar_ast::{ImportPath, ModuleItem};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_item;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Import {
+        path: ImportPath::Glob { path },
+        ..
+    }) = parse_item(DUMMY_PATH_ID, "import std.collections.*;", &mut diagnostics)
+    else {
+        panic!("expected glob import");
+    };
+
+    assert_eq!(path.identifiers.len(), 2);
+    assert_eq!(path.location.end.0, 22);
+    assert!(diagnostics.is_ok());
+
+    let _item = parse_item(
+        DUMMY_PATH_ID,
+        "import std.collections.* as c;",
+        &mut diagnostics,
+    );
+
+    assert!(!diagnostics.is_ok());
+}
+
+#[test]
+fn grouped_import_path() {
+    use stellar_ast::{ImportPath, ModuleItem};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_item;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Import { path, .. }) = parse_item(
+        DUMMY_PATH_ID,
+        "import std.{io.{print, println}, net as network, fs.*};",
+        &mut diagnostics,
+    ) else {
+        panic!("expected import");
+    };
+
+    assert!(diagnostics.is_ok());
+
+    let flattened = path
+        .flatten()
+        .into_iter()
+        .map(|import| {
+            let path = import
+                .path()
+                .identifiers
+                .iter()
+                .map(|identifier| identifier.id.as_str())
+                .collect::<Vec<_>>()
+                .join(".");
+
+            match import {
+                ImportPath::Single { as_: Some(as_), .. } => format!("{path} as {}", as_.id),
+                ImportPath::Glob { .. } => format!("{path}.*"),
+                _ => path,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        flattened,
+        [
+            "std.io.print",
+            "std.io.println",
+            "std.net as network",
+            "std.fs.*"
+        ]
+    );
+
+    let _item = parse_item(DUMMY_PATH_ID, "import std.{io, fs", &mut diagnostics);
+
+    assert!(!diagnostics.is_ok());
+}
+
+#[test]
+fn public_glob_import() {
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_item;
+
+    let mut diagnostics = Diagnostics::new();
+    let _item = parse_item(
+        DUMMY_PATH_ID,
+        "pub import std.collections.*;",
+        &mut diagnostics,
+    );
+
+    assert_eq!(diagnostics.diagnostics[0].code.as_deref(), Some("E004"));
+}
+
+#[test]
+fn extern_block() {
+    use stellar_ast::ModuleItem;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_item;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Extern(block)) = parse_item(
+        DUMMY_PATH_ID,
+        "extern \"system\" { fun GetLastError(): uint32; }",
+        &mut diagnostics,
+    ) else {
+        panic!("expected extern block");
+    };
+
+    assert_eq!(block.abi, "system");
+    assert_eq!(block.functions.len(), 1);
+    assert!(diagnostics.is_ok());
+
+    let _item = parse_item(
+        DUMMY_PATH_ID,
+        "extern \"C\" { fun puts(s: CStr): int32 {} }",
+        &mut diagnostics,
+    );
+
+    assert_eq!(diagnostics.diagnostics[0].code.as_deref(), Some("E018"));
+
+    // the ABI is required
+    let _item = parse_item(DUMMY_PATH_ID, "extern { fun exit(); }", &mut diagnostics);
+
+    assert_eq!(diagnostics.diagnostics[1].code.as_deref(), Some("E001"));
+}
+
+#[test]
+fn restricted_visibility() {
+    use stellar_ast::{ModuleItem, Visibility};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_item;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::TupleLikeStruct(struct_)) = parse_item(
+        DUMMY_PATH_ID,
+        "pub(super) struct A(pub (int32, int32), pub(package) int32);",
+        &mut diagnostics,
+    ) else {
+        panic!("expected tuple-like struct");
+    };
+
+    assert!(matches!(struct_.visibility, Visibility::Super(location) if location.end.0 == 10));
+    assert!(matches!(
+        struct_.fields[0].visibility,
+        Visibility::Public(_)
+    ));
+    assert!(matches!(
+        struct_.fields[1].visibility,
+        Visibility::Package(_)
+    ));
+    assert!(diagnostics.is_ok());
+
+    let _item = parse_item(DUMMY_PATH_ID, "pub(crate) fun foo() {}", &mut diagnostics);
+
+    assert!(!diagnostics.is_ok());
+}
+
+#[test]
+fn unsafe_function() {
+    use stellar_ast::ModuleItem;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_item;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Function(function)) =
+        parse_item(DUMMY_PATH_ID, "unsafe fun read() {}", &mut diagnostics)
+    else {
+        panic!("expected function");
+    };
+
+    assert!(function.signature.is_unsafe);
+    assert!(diagnostics.is_ok());
+
+    let Some(ModuleItem::Function(function)) =
+        parse_item(DUMMY_PATH_ID, "fun read() {}", &mut diagnostics)
+    else {
+        panic!("expected function");
+    };
+
+    assert!(!function.signature.is_unsafe);
+
+    // `unsafe` is only a function modifier
+    let _item = parse_item(DUMMY_PATH_ID, "unsafe struct A {}", &mut diagnostics);
+
+    assert!(diagnostics.is_fatal());
+}
+
+#[test]
+fn async_function() {
+    use stellar_ast::ModuleItem;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_item;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Function(function)) = parse_item(
+        DUMMY_PATH_ID,
+        "async unsafe fun read(): Bytes {}",
+        &mut diagnostics,
+    ) else {
+        panic!("expected function");
+    };
+
+    assert!(function.signature.is_async);
+    assert!(function.signature.is_unsafe);
+    assert!(diagnostics.is_ok());
+
+    // modifiers have a fixed order
+    let _item = parse_item(
+        DUMMY_PATH_ID,
+        "unsafe async fun read() {}",
+        &mut diagnostics,
+    );
+
+    assert!(diagnostics.is_fatal());
+}
+
+#[test]
+fn generator_function() {
+    use stellar_ast::{ModuleItem, Statement};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_item;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Function(function)) = parse_item(
+        DUMMY_PATH_ID,
+        "pub unsafe gen fun numbers(): Iterator[int32] { yield 1; yield 2; }",
+        &mut diagnostics,
+    ) else {
+        panic!("expected function");
+    };
+
+    assert!(function.signature.is_unsafe);
+    assert!(function.signature.is_generator);
+    assert!(matches!(
+        function.body.as_deref(),
+        Some([Statement::Yield { .. }, Statement::Yield { .. }])
+    ));
+    assert!(diagnostics.is_ok());
+
+    // `yield` requires a value
+    let _item = parse_item(DUMMY_PATH_ID, "gen fun f() { yield; }", &mut diagnostics);
+
+    assert!(diagnostics.is_fatal());
+}
+
+#[test]
+fn docstring() {
+    use stellar_ast::ModuleItem;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_item;
+
+    let source = "/// Reads a file.
+///
+/// ```
+///   read(path)
+/// ```
+// not a part of the docstring
+/// Returns contents of the file.
+fun read(path: String): String {}";
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Function(function)) = parse_item(DUMMY_PATH_ID, source, &mut diagnostics)
+    else {
+        panic!("expected function");
+    };
+
+    assert_eq!(
+        function.signature.docstring.unwrap().text(source),
+        "Reads a file.\n\n```\n  read(path)\n```\nReturns contents of the file."
+    );
+}
+
+#[test]
+fn structured_docstring() {
+    use stellar_ast::{
+        doc::{DocBlock, DocTag},
+        ModuleItem,
+    };
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_item;
+
+    let source = "/// Copies a file.
+///
+/// Existing files are overwritten.
+///
+/// @param from A path to the file,
+///   that is copied.
+/// @param to A destination path.
+/// @returns The number of copied bytes.
+/// @since 0.2
+///
+/// # Example
+///
+/// ```stellar
+/// copy(\"a.txt\", \"b.txt\");
+/// ```
+fun copy(from: String, to: String): uint64 {}";
+    let mut diagnostics = Diagnostics::new();
+
+    let Some(ModuleItem::Function(function)) = parse_item(DUMMY_PATH_ID, source, &mut diagnostics)
+    else {
+        panic!("expected function");
+    };
+
+    let doc = function.signature.docstring.unwrap().parse(source);
+
+    assert_eq!(doc.summary, "Copies a file.");
+    assert_eq!(
+        doc.description,
+        [DocBlock::Paragraph {
+            text: "Existing files are overwritten.".to_owned()
+        }]
+    );
+    assert_eq!(
+        doc.param("from"),
+        Some("A path to the file, that is copied.")
+    );
+    assert_eq!(doc.param("to"), Some("A destination path."));
+    assert_eq!(doc.returns(), Some("The number of copied bytes."));
+    assert_eq!(
+        doc.tags.last(),
+        Some(&DocTag::Other {
+            name: "since".to_owned(),
+            description: "0.2".to_owned()
+        })
+    );
+
+    assert_eq!(doc.sections.len(), 1);
+    assert_eq!(doc.sections[0].title, "Example");
+    assert_eq!(
+        doc.sections[0].blocks,
+        [DocBlock::Code {
+            language: Some("stellar".to_owned()),
+            code: "copy(\"a.txt\", \"b.txt\");".to_owned()
+        }]
+    );
+}
+
+#[test]
+fn recovery_at_next_item() {
+    use stellar_database::{PackageData, Path, State};
+    use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+    use stellar_parser::parse_module;
+
+    let source = "struct A { a: int32, b: }
+fun foo() { let = 1; }
+pub fun bar() {}
+enum B { C }
+interface D {}
+import std.io;";
+    let mut state = State::new();
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("test"), DUMMY_PATH_ID);
+
+    let result = parse_module(
+        &mut state,
+        package,
+        Path::from(IdentifierId::from("test")),
+        DUMMY_PATH_ID,
+        source,
+    );
+
+    // each broken item reports a single error and doesn't hide the next one
+    assert_eq!(
+        result
+            .ast()
+            .items
+            .iter()
+            .map(|item| &source[item.location()])
+            .collect::<Vec<_>>(),
+        ["bar", "B", "D", "import std.io;"]
+    );
+    assert_eq!(state.diagnostics().diagnostics.len(), 2);
+}
+
+#[test]
+fn feature_gates() {
+    use stellar_ast::IdentifierAST;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_filesystem::location::DUMMY_LOCATION;
+    use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+    use stellar_parser::{options::ParseOptions, parse_item_using, ParseState};
+
+    let source = "gen fun a() { let b = try { c()? }; yield b; }";
+    let codes = |options: Option<ParseOptions>| {
+        let mut diagnostics = Diagnostics::new();
+        let mut state = ParseState::new(DUMMY_PATH_ID, source, &mut diagnostics);
+
+        if let Some(options) = options {
+            state = state.with_options(options);
+        }
+
+        assert!(parse_item_using(&mut state).is_some());
+
+        diagnostics
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code.clone().unwrap())
+            .collect::<Vec<_>>()
+    };
+    let feature = |name: &str| IdentifierAST {
+        location: DUMMY_LOCATION,
+        id: IdentifierId::from(name),
+    };
+
+    // experimental syntax is not gated without options
+    assert!(codes(None).is_empty());
+
+    assert_eq!(codes(Some(ParseOptions::default())), ["E022", "E022"]);
+    assert_eq!(
+        codes(Some(
+            ParseOptions::default()
+                .enable_feature(feature("generators"))
+                .enable_feature(feature("try_blocks"))
+        )),
+        [] as [&str; 0]
+    );
+    assert_eq!(
+        codes(Some(
+            ParseOptions::default()
+                .enable_feature(feature("generators"))
+                .enable_feature(feature("pipelines"))
+        )),
+        ["E021", "E022"]
+    );
+}
@@ -0,0 +1,103 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::Hasher,
+};
+
+use stellar_ast::{
+    location_insensitive::{IgnoringLocations, LocationInsensitive},
+    Module, ModuleItem,
+};
+use stellar_database::{PackageData, Path, State};
+use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+use stellar_parser::parse_module;
+
+fn parse(source: &str) -> Module {
+    let mut state = State::new();
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("test"), DUMMY_PATH_ID);
+
+    let result = parse_module(
+        &mut state,
+        package,
+        Path::from(IdentifierId::from("test")),
+        DUMMY_PATH_ID,
+        source,
+    );
+
+    assert!(state.diagnostics().is_ok(), "{source}");
+
+    result.ast().clone()
+}
+
+fn hash(module: &Module) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    module.hash_ignoring_locations(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn reformatted_module_is_unchanged() {
+    let original = parse(
+        "struct Point { x: float32, y: float32 }
+
+fun length(p: Point): float32 {
+    let sum = p.x * p.x + p.y * p.y;
+    sqrt(sum)
+}",
+    );
+    let reformatted = parse(
+        "// comment
+struct Point {
+    x: float32,
+    y: float32,
+}
+fun length(p: Point): float32 { let sum = p.x*p.x + p.y*p.y; sqrt(sum) }",
+    );
+
+    assert_ne!(original, reformatted);
+    assert!(original.eq_ignoring_locations(&reformatted));
+    assert_eq!(hash(&original), hash(&reformatted));
+}
+
+#[test]
+fn changed_module_is_different() {
+    let original = parse("fun main() { foo(1, 2.5); }");
+
+    for changed in [
+        "fun main() { foo(1, 2.6); }",
+        "fun main() { foo(2, 2.5); }",
+        "fun main() { bar(1, 2.5); }",
+        "fun main() { foo(1, 2.5) }",
+        "fun main() { foo(1); }",
+        "pub fun main() { foo(1, 2.5); }",
+        "async fun main() { foo(1, 2.5); }",
+    ] {
+        let changed = parse(changed);
+
+        assert!(!original.eq_ignoring_locations(&changed), "{changed:?}");
+        assert_ne!(hash(&original), hash(&changed));
+    }
+}
+
+#[test]
+fn deduplication() {
+    let module = parse(
+        "fun a(): int32 { 1 + 2 }
+fun b(): int32 { 1+2 }
+fun c(): int32 { 2 + 1 }",
+    );
+
+    let bodies = module
+        .items
+        .iter()
+        .map(|item| {
+            let ModuleItem::Function(function) = item else {
+                panic!("expected function");
+            };
+
+            IgnoringLocations(function.body.as_deref().unwrap())
+        })
+        .collect::<HashSet<_>>();
+
+    assert_eq!(bodies.len(), 2);
+}
@@ -0,0 +1,81 @@
+use stellar_ast::Module;
+use stellar_database::{PackageData, Path, State};
+use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+use stellar_parser::parse_module;
+
+fn parse(source: &str) -> Module {
+    let mut state = State::new();
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("test"), DUMMY_PATH_ID);
+
+    let result = parse_module(
+        &mut state,
+        package,
+        Path::from(IdentifierId::from("test")),
+        DUMMY_PATH_ID,
+        source,
+    );
+
+    assert!(state.diagnostics().is_ok(), "{source}");
+
+    result.ast().clone()
+}
+
+#[test]
+fn node_counts() {
+    let metrics = parse(
+        "import std.io;
+        struct A { b: int32 }
+        module utils { const C: int32 = 1 + 2; }",
+    )
+    .metrics();
+
+    assert_eq!(metrics.items, 4);
+    assert_eq!(metrics.expressions, 3);
+    assert_eq!(metrics.types, 2);
+    assert_eq!(metrics.statements, 0);
+    assert!(metrics.functions.is_empty());
+}
+
+#[test]
+fn function_metrics() {
+    let source = "fun classify(a: int32): String {
+    if a > 0 && a < 10 {
+        while a > 0 {
+            a--;
+        }
+    } else if a == 0 {
+        return \"zero\";
+    }
+
+    match a {
+        1 -> \"one\",
+        2 -> \"two\",
+        _ -> \"many\",
+    }
+}
+
+struct A {
+    fun new(): A { A {} }
+}";
+    let metrics = parse(source).metrics();
+
+    assert_eq!(metrics.max_nesting_depth, 3);
+    assert_eq!(metrics.functions.len(), 2);
+
+    let classify = &metrics.functions[0];
+
+    assert_eq!(&*classify.name.id.as_str(), "classify");
+    assert_eq!(classify.statements, 5);
+    assert_eq!(classify.max_nesting_depth, 3);
+    // 1 + `if` + `&&` + `else if` + `while` + 2 match alternatives
+    assert_eq!(classify.cyclomatic_complexity, 7);
+    assert_eq!(classify.lines(source), 15);
+
+    let new = &metrics.functions[1];
+
+    assert_eq!(&*new.name.id.as_str(), "new");
+    assert_eq!(new.statements, 1);
+    assert_eq!(new.cyclomatic_complexity, 1);
+    assert_eq!(new.lines(source), 1);
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "newline-termination")]
+
+use stellar_ast::{ModuleItem, Statement};
+use stellar_diagnostics::Diagnostics;
+use stellar_interner::DUMMY_PATH_ID;
+use stellar_parser::{parse_item_using, ParseState};
+
+/// Parses a function in the newline termination mode and returns its body.
+fn parse_body(source: &str, diagnostics: &mut Diagnostics) -> Option<Vec<Statement>> {
+    let mut state = ParseState::new(DUMMY_PATH_ID, source, diagnostics).with_newline_termination();
+
+    match parse_item_using(&mut state)? {
+        ModuleItem::Function(function) => function.body,
+        _ => panic!("expected function"),
+    }
+}
+
+#[test]
+fn statements_end_at_line_breaks() {
+    let mut diagnostics = Diagnostics::new();
+
+    let body = parse_body(
+        "fun main(): String {
+    let a = 1
+    let b = a
+        .to_string()
+        as String
+    defer close(a,
+        b)
+    print(b); print(a)
+    b
+}",
+        &mut diagnostics,
+    )
+    .unwrap();
+
+    assert!(diagnostics.is_ok());
+    assert_eq!(body.len(), 6);
+    assert!(matches!(
+        body[4],
+        Statement::Expression {
+            has_semicolon: true,
+            ..
+        }
+    ));
+    assert!(matches!(
+        body[5],
+        Statement::Expression {
+            has_semicolon: false,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn closing_brace_ends_statement() {
+    let mut diagnostics = Diagnostics::new();
+
+    let body = parse_body("fun main() { return 1 }", &mut diagnostics).unwrap();
+
+    assert!(diagnostics.is_ok());
+    assert_eq!(body.len(), 1);
+}
+
+#[test]
+fn ambiguous_line_break() {
+    let mut diagnostics = Diagnostics::new();
+
+    let body = parse_body(
+        "fun main() {
+    let a = b
+    (c, d).print()
+}",
+        &mut diagnostics,
+    )
+    .unwrap();
+
+    assert_eq!(body.len(), 2);
+    assert_eq!(diagnostics.diagnostics.len(), 1);
+    assert_eq!(diagnostics.diagnostics[0].code.as_deref(), Some("W004"));
+}
+
+#[test]
+fn statements_on_the_same_line() {
+    let mut diagnostics = Diagnostics::new();
+
+    assert!(parse_body("fun main() { let a = 1 let b = 2 }", &mut diagnostics).is_none());
+    assert!(diagnostics.is_fatal());
+}
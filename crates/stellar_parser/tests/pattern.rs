@@ -12,5 +12,152 @@ tests_using! {
     tuple3 -> "(..)",
     list -> "[1, .., 3]",
     struct_ -> "Person { name: \"John\", age, .. }",
-    or -> "Some(_) | None"
+    or -> "Some(_) | None",
+    or_leading_pipe -> "| A | B",
+    inclusive_range -> "1..=9",
+    exclusive_range -> "'a'..'z'",
+    negative_range -> "-10..-1",
+    mutable_binding -> "mut x",
+    reference_binding -> "(ref a, ref mut b @ [1, ..])"
+}
+
+#[test]
+fn binding_modes() {
+    use stellar_ast::{BindingMode, Pattern};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_pattern;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let source = "(a, mut b, ref c, ref mut d @ [..])";
+    let Some(Pattern::Tuple { elements, .. }) =
+        parse_pattern(DUMMY_PATH_ID, source, &mut diagnostics)
+    else {
+        panic!("expected tuple pattern");
+    };
+
+    let bindings = elements
+        .iter()
+        .map(|element| match element {
+            Pattern::Identifier {
+                location,
+                binding_mode,
+                ..
+            } => (&source[*location], *binding_mode),
+            _ => panic!("expected identifier pattern"),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        bindings,
+        [
+            ("a", BindingMode::Value),
+            ("mut b", BindingMode::MutableValue),
+            ("ref c", BindingMode::Reference),
+            ("ref mut d @ [..]", BindingMode::MutableReference),
+        ]
+    );
+    assert!(diagnostics.is_ok());
+
+    // binding modes can only be used with identifiers
+    for source in ["mut _", "ref 1", "mut ref a"] {
+        let mut diagnostics = Diagnostics::new();
+
+        assert!(parse_pattern(DUMMY_PATH_ID, source, &mut diagnostics).is_none());
+        assert!(!diagnostics.is_ok());
+    }
+}
+
+#[test]
+fn range_patterns() {
+    use stellar_ast::{Literal, NegativeNumericLiteral, Pattern, RangeKind};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_pattern;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let source = "-1..=9 | 'a'..'z'";
+    let Some(Pattern::Or { alternatives, .. }) =
+        parse_pattern(DUMMY_PATH_ID, source, &mut diagnostics)
+    else {
+        panic!("expected or pattern");
+    };
+
+    let Pattern::Range {
+        location,
+        start,
+        end,
+        kind: RangeKind::Inclusive,
+    } = &alternatives[0]
+    else {
+        panic!("expected inclusive range pattern");
+    };
+
+    assert_eq!(&source[*location], "-1..=9");
+    assert!(matches!(
+        **start,
+        Pattern::NegativeNumericLiteral(NegativeNumericLiteral::Integer { ref value, .. })
+            if value.to_u64() == Some(1)
+    ));
+    assert!(matches!(
+        **end,
+        Pattern::Literal(Literal::Integer { ref value, .. }) if value.to_u64() == Some(9)
+    ));
+
+    assert!(matches!(
+        &alternatives[1],
+        Pattern::Range {
+            kind: RangeKind::Exclusive,
+            ..
+        }
+    ));
+    assert!(diagnostics.is_ok());
+
+    // only numeric and character literals can be bounds
+    for source in ["1..=a", "'a'..\"z\"", "1.."] {
+        let mut diagnostics = Diagnostics::new();
+
+        assert!(parse_pattern(DUMMY_PATH_ID, source, &mut diagnostics).is_none());
+        assert!(!diagnostics.is_ok());
+    }
+}
+
+#[test]
+fn or_patterns_are_flattened() {
+    use stellar_ast::Pattern;
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_pattern;
+
+    let mut diagnostics = Diagnostics::new();
+
+    let source = "| A | B(1 | 2) | C";
+    let Some(Pattern::Or {
+        location,
+        alternatives,
+    }) = parse_pattern(DUMMY_PATH_ID, source, &mut diagnostics)
+    else {
+        panic!("expected or pattern");
+    };
+
+    assert_eq!(&source[location], "A | B(1 | 2) | C");
+    assert_eq!(alternatives.len(), 3);
+    // nested or patterns are kept as is
+    let Pattern::TupleLike { inner_patterns, .. } = &alternatives[1] else {
+        panic!("expected tuple-like pattern");
+    };
+
+    assert!(matches!(
+        &inner_patterns[0],
+        Pattern::Or { alternatives, .. } if alternatives.len() == 2
+    ));
+
+    // a leading pipe alone doesn't make an or pattern
+    assert!(matches!(
+        parse_pattern(DUMMY_PATH_ID, "| A.B", &mut diagnostics),
+        Some(Pattern::Path { .. })
+    ));
+    assert!(diagnostics.is_ok());
 }
@@ -0,0 +1,41 @@
+mod macros;
+
+tests_using! {
+    parse_statement,
+    let_ -> "let a: int32 = 1;",
+    return_ -> "return a;",
+    defer -> "defer file.close();",
+    defer_block -> "defer { close(f); flush(log); }",
+    expression -> "print(a);"
+}
+
+#[test]
+fn defer_forms() {
+    use stellar_ast::{DeferBody, Expression, Statement};
+    use stellar_diagnostics::Diagnostics;
+    use stellar_interner::DUMMY_PATH_ID;
+    use stellar_parser::parse_statement;
+
+    let mut diagnostics = Diagnostics::new();
+
+    assert!(matches!(
+        parse_statement(DUMMY_PATH_ID, "defer file.close();", &mut diagnostics),
+        Some(Statement::Defer {
+            body: DeferBody::Call(Expression::Call { .. }),
+            ..
+        })
+    ));
+
+    let source = "defer { close(f); flush(log); }";
+    let Some(Statement::Defer {
+        body: DeferBody::Block { location, block },
+        ..
+    }) = parse_statement(DUMMY_PATH_ID, source, &mut diagnostics)
+    else {
+        panic!("expected defer block");
+    };
+
+    assert_eq!(&source[location], "{ close(f); flush(log); }");
+    assert_eq!(block.len(), 2);
+    assert!(diagnostics.is_ok());
+}
@@ -10,5 +10,15 @@ tests_using! {
     single_tuple_type -> "(A,)",
     tuple_type -> "(A, B)",
     function_type1 -> "fun (A, B)",
-    function_type2 -> "fun (A, B): C"
+    function_type2 -> "fun (A, B): C",
+    const_arguments -> "Array[uint8, 4]",
+    bool_and_char_const_arguments -> "Flags[true, 'a']",
+    array_type -> "[int32; 4]",
+    nested_array_type -> "[[T; N]; 2]",
+    reference_type -> "&String",
+    mutable_reference_type -> "&mut List[&T]",
+    double_reference_type -> "&&mut T",
+    optional_type -> "int32?",
+    nested_optional_type -> "List[String?]??",
+    optional_reference_type -> "&T?"
 }
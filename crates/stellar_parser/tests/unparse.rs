@@ -0,0 +1,188 @@
+use stellar_ast::{
+    unparse,
+    unparse::{unparse_expression, unparse_with_options, UnparseOptions},
+    Expression, Module,
+};
+use stellar_database::{PackageData, Path, State};
+use stellar_diagnostics::Diagnostics;
+use stellar_interner::{IdentifierId, DUMMY_PATH_ID};
+use stellar_parser::{parse_expression, parse_module};
+
+fn parse(source: &str) -> Module {
+    let mut state = State::new();
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("test"), DUMMY_PATH_ID);
+
+    let result = parse_module(
+        &mut state,
+        package,
+        Path::from(IdentifierId::from("test")),
+        DUMMY_PATH_ID,
+        source,
+    );
+
+    assert!(state.diagnostics().is_ok(), "{source}");
+
+    result.ast().clone()
+}
+
+/// Unparses a source and checks that the result parses without errors and
+/// is unparsed into itself.
+fn roundtrip(source: &str) -> String {
+    let unparsed = unparse(&parse(source));
+
+    assert_eq!(unparse(&parse(&unparsed)), unparsed);
+
+    unparsed
+}
+
+fn expression(source: &str) -> Expression {
+    parse_expression(DUMMY_PATH_ID, source, &mut Diagnostics::new()).unwrap()
+}
+
+#[test]
+fn items() {
+    assert_eq!(
+        roundtrip(
+            "import std.io; import std.{fs as f, collections.*};
+            #[inline] pub(package) fun foo[T: A + B, const N: usize = 4](self, x: T): T? where T: C { x }
+            struct A[T] implements B { pub a: T, fun new(): Self { Self { a: 1 } } }
+            struct Meters(pub float64);
+            enum Shape { Circle { radius: float64 }, Polygon(List[Point]), Empty }
+            interface Area: Shape { fun area(self): float64; }
+            module utils { const MAX: uint32 = 10; }
+            extern \"C\" { fun puts(s: CStr): int32; }"
+        ),
+        "import std.io;
+import std.{fs as f, collections.*};
+
+#[inline]
+pub(package) fun foo[T: A + B, const N: usize = 4](self, x: T): T? where T: C {
+    x
+}
+
+struct A[T] implements B {
+    pub a: T,
+
+    fun new(): Self {
+        Self { a: 1 }
+    }
+}
+
+struct Meters(pub float64);
+
+enum Shape {
+    Circle { radius: float64 },
+    Polygon(List[Point]),
+    Empty
+}
+
+interface Area: Shape {
+    fun area(self): float64;
+}
+
+module utils {
+    const MAX: uint32 = 10;
+}
+
+extern \"C\" {
+    fun puts(s: CStr): int32;
+}
+"
+    );
+}
+
+#[test]
+fn statements_and_expressions() {
+    assert_eq!(
+        roundtrip(
+            "fun main() {
+                let mut a: int32 = -(1 + 2) * 3;
+                defer { close(); }
+                if let Some(x) = a { x } else if a > 0 { 1 } else { 0 };
+                match a { | 1 | 2 -> \"small\", -9..0 -> \"negative\", _ -> \"other\\n\" }
+                let f = |[move a] b: int32| a + b;
+                return a;
+            }"
+        ),
+        "fun main() {
+    let mut a: int32 = -(1 + 2) * 3;
+    defer {
+        close();
+    }
+    if let Some(x) = a {
+        x
+    } else if a > 0 {
+        1
+    } else {
+        0
+    }
+    match a {
+        1 | 2 -> \"small\",
+        -9..0 -> \"negative\",
+        _ -> \"other\\n\",
+    }
+    let f = |[move a] b: int32| a + b;
+    return a;
+}
+"
+    );
+}
+
+#[test]
+fn corpus() {
+    for source in [
+        include_str!("../../stellar_driver/tests/corpus/expressions.sr"),
+        include_str!("../../stellar_driver/tests/corpus/items.sr"),
+        include_str!("../../stellar_driver/tests/corpus/statements.sr"),
+    ] {
+        roundtrip(source);
+    }
+}
+
+#[test]
+fn shorthand_arguments_are_preserved() {
+    assert_eq!(
+        unparse_expression(&expression("greet(name:, greeting: \"hi\", title: title)")),
+        "greet(name:, greeting: \"hi\", title: title)"
+    );
+}
+
+#[test]
+fn parentheses_are_inserted_by_precedence() {
+    let mut product = expression("x * c");
+
+    let Expression::Binary { left, .. } = &mut product else {
+        panic!("expected binary expression");
+    };
+    **left = expression("a + b");
+
+    assert_eq!(unparse_expression(&product), "(a + b) * c");
+
+    let mut call = expression("x.foo()");
+
+    let Expression::Call { callee, .. } = &mut call else {
+        panic!("expected call expression");
+    };
+    let Expression::FieldAccess { left, .. } = callee.as_mut() else {
+        panic!("expected field access");
+    };
+    **left = expression("a as T");
+
+    assert_eq!(unparse_expression(&call), "(a as T).foo()");
+    assert_eq!(
+        unparse_expression(&expression("a - (b - c)")),
+        "a - (b - c)"
+    );
+    assert_eq!(unparse_expression(&expression("- -a")), "- -a");
+}
+
+#[test]
+fn indentation() {
+    let module = parse("fun main() { loop { break; } }");
+
+    assert_eq!(
+        unparse_with_options(&module, &UnparseOptions::default().with_indentation("\t")),
+        "fun main() {\n\tloop {\n\t\tbreak;\n\t}\n}\n"
+    );
+}
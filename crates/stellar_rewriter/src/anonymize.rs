@@ -0,0 +1,323 @@
+//! Anonymization of sources for bug reports.
+//!
+//! [`Anonymizer`] replaces identifiers, literals and comments with stable
+//! placeholders, so that a reproducer of a parser or lowering bug can be
+//! shared without revealing proprietary code:
+//!
+//! ```
+//! use stellar_interner::PathId;
+//! use stellar_rewriter::anonymize::Anonymizer;
+//!
+//! let source = "fun check(password: String) { println(\"hunter2\", password); } // TODO";
+//! let anonymized = Anonymizer::new()
+//!     .anonymize(PathId::from("test.sr"), source)
+//!     .apply(source)
+//!     .unwrap();
+//!
+//! assert_eq!(
+//!     anonymized,
+//!     "fun aaaaa(aaaaaaaa: aaaaaa) { aaaaaaa(\"aaaaaaa\", aaaaaaaa); } // xxxx"
+//! );
+//! ```
+//!
+//! Every placeholder has the same length as the text it replaces, so the
+//! anonymized source has the same structure and locations of all AST nodes as
+//! the original one. The same identifier is always replaced with the same
+//! placeholder and different identifiers are replaced with different ones,
+//! including the ones used in different files anonymized by the same
+//! [`Anonymizer`]. Keywords, punctuators, escape sequences and invalid tokens
+//! are kept as is.
+//!
+//! [`Reproducer`] anonymizes all files of a package, including their paths,
+//! because module names are derived from them.
+
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use stellar_ast::token::{resolve_keyword, RawToken};
+use stellar_filesystem::text_edit::{FileEdit, TextEdit, WorkspaceEdit};
+use stellar_fx_hash::FxHashMap;
+use stellar_interner::{IdentifierId, PathId};
+use stellar_lexer::Lexer;
+
+/// Characters, that can start a placeholder.
+const FIRST_CHARACTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_";
+
+/// Characters, that can continue a placeholder.
+const CHARACTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_0123456789";
+
+/// Replaces identifiers, literals and comments with placeholders. See
+/// [module level docs](self) for more details.
+#[derive(Debug, Default)]
+pub struct Anonymizer {
+    identifiers: FxHashMap<IdentifierId, IdentifierId>,
+    strings: FxHashMap<String, String>,
+
+    /// The number of placeholders of a given length already used for
+    /// identifiers.
+    identifier_counters: FxHashMap<usize, usize>,
+
+    /// The number of placeholders of a given length already used for string
+    /// literals.
+    string_counters: FxHashMap<usize, usize>,
+}
+
+impl Anonymizer {
+    /// Creates an anonymizer without known identifiers.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns edits anonymizing a given source.
+    #[must_use]
+    pub fn anonymize(&mut self, filepath: PathId, source: &str) -> FileEdit {
+        let mut edit = FileEdit::new(filepath);
+        let mut lexer = Lexer::new(filepath, source);
+
+        loop {
+            let token = lexer.next_token();
+
+            if token.raw == RawToken::EndOfFile {
+                break;
+            }
+
+            let text = &source[token.location];
+            let replacement = match token.raw {
+                RawToken::Identifier => {
+                    if let Some(name) = text.strip_prefix('`').and_then(|t| t.strip_suffix('`')) {
+                        format!("`{}`", self.identifier(name))
+                    } else {
+                        self.identifier(text).to_string()
+                    }
+                }
+                RawToken::IntegerLiteral | RawToken::FloatLiteral => text
+                    .chars()
+                    .map(|c| if matches!(c, '1'..='9') { '1' } else { c })
+                    .collect(),
+                RawToken::StringLiteral => format!("\"{}\"", self.string(&text[1..text.len() - 1])),
+                RawToken::CharLiteral => {
+                    format!("'{}'", char_placeholder(&text[1..text.len() - 1]))
+                }
+                RawToken::Comment | RawToken::GlobalDocComment | RawToken::LocalDocComment => {
+                    let prefix_len = if token.raw == RawToken::Comment { 2 } else { 3 };
+
+                    format!(
+                        "{}{}",
+                        &text[..prefix_len],
+                        comment_placeholder(&text[prefix_len..])
+                    )
+                }
+                _ => continue,
+            };
+
+            if replacement != text {
+                edit.add(TextEdit::replace(token.location, replacement));
+            }
+        }
+
+        edit
+    }
+
+    /// Returns edits anonymizing several files.
+    #[must_use]
+    #[allow(single_use_lifetimes)] // anonymous lifetimes in `impl Trait` are unstable
+    pub fn anonymize_all<'a>(
+        &mut self,
+        files: impl IntoIterator<Item = (PathId, &'a str)>,
+    ) -> WorkspaceEdit {
+        let mut edit = WorkspaceEdit::new();
+
+        for (filepath, source) in files {
+            edit.add_file_edit(self.anonymize(filepath, source));
+        }
+
+        edit
+    }
+
+    /// Anonymizes names of directories and file stems in a relative path. File
+    /// stems are replaced with the same placeholders as identifiers, so
+    /// imports of anonymized modules are still valid.
+    #[must_use]
+    pub fn anonymize_path(&mut self, path: &Path) -> PathBuf {
+        let mut components = path.components().peekable();
+        let mut anonymized = PathBuf::new();
+
+        while let Some(component) = components.next() {
+            let Component::Normal(name) = component else {
+                anonymized.push(component);
+                continue;
+            };
+
+            let name = name.to_string_lossy();
+
+            // only the last component has an extension
+            match name.split_once('.') {
+                Some((stem, extension)) if components.peek().is_none() && !stem.is_empty() => {
+                    anonymized.push(format!("{}.{extension}", self.identifier(stem)));
+                }
+                _ => anonymized.push(&*self.identifier(&name)),
+            }
+        }
+
+        anonymized
+    }
+
+    /// Returns a placeholder for a given identifier.
+    fn identifier(&mut self, name: &str) -> Arc<str> {
+        let counters = &mut self.identifier_counters;
+
+        self.identifiers
+            .entry(IdentifierId::from(name))
+            .or_insert_with(|| {
+                let counter = counters.entry(name.len()).or_default();
+
+                loop {
+                    let placeholder = nth_placeholder(*counter, name.len());
+                    *counter += 1;
+
+                    // placeholders are identifiers as well
+                    if placeholder != "_"
+                        && placeholder != "true"
+                        && placeholder != "false"
+                        && resolve_keyword(&placeholder).is_none()
+                    {
+                        break IdentifierId::from(placeholder);
+                    }
+                }
+            })
+            .as_str()
+    }
+
+    /// Returns a placeholder for the content of a string literal.
+    fn string(&mut self, content: &str) -> &str {
+        let counters = &mut self.string_counters;
+
+        self.strings.entry(content.to_owned()).or_insert_with(|| {
+            let counter = counters.entry(content.len()).or_default();
+            *counter += 1;
+
+            nth_placeholder(*counter - 1, content.len())
+        })
+    }
+}
+
+/// Returns the `index`-th placeholder of a given length. Placeholders repeat,
+/// if there are less than `index` of them.
+fn nth_placeholder(mut index: usize, len: usize) -> String {
+    let mut placeholder = vec![0; len];
+
+    for (position, byte) in placeholder.iter_mut().enumerate().rev() {
+        let characters = if position == 0 {
+            FIRST_CHARACTERS
+        } else {
+            CHARACTERS
+        };
+
+        *byte = characters[index % characters.len()];
+        index /= characters.len();
+    }
+
+    String::from_utf8(placeholder).unwrap()
+}
+
+/// Returns a placeholder for the content of a character literal.
+///
+/// Escape sequences are kept, otherwise the placeholder would not be a single
+/// character of the same length.
+fn char_placeholder(content: &str) -> &str {
+    if content.starts_with('\\') {
+        return content;
+    }
+
+    match content.len() {
+        1 => "c",
+        2 => "ç",
+        3 => "€",
+        _ => "𝑐",
+    }
+}
+
+/// Returns a placeholder for the text of a comment, whitespace is kept.
+fn comment_placeholder(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_whitespace() {
+                c.to_string()
+            } else {
+                "x".repeat(c.len_utf8())
+            }
+        })
+        .collect()
+}
+
+/// Anonymized sources of a package, that can be attached to a bug report.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Reproducer {
+    /// Anonymized files.
+    pub files: Vec<ReproducerFile>,
+}
+
+/// An anonymized file of a [`Reproducer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReproducerFile {
+    /// Anonymized path of the file relative to the package root.
+    pub path: PathBuf,
+
+    /// Anonymized source of the file.
+    pub source: String,
+}
+
+impl Reproducer {
+    /// Anonymizes files with given paths relative to the package root and
+    /// sources.
+    ///
+    /// # Panics
+    /// Panics if the anonymizer produced overlapping edits, which should never
+    /// happen.
+    #[must_use]
+    pub fn new<'a>(files: impl IntoIterator<Item = (&'a Path, &'a str)>) -> Self {
+        let mut anonymizer = Anonymizer::new();
+
+        Self {
+            files: files
+                .into_iter()
+                .map(|(path, source)| ReproducerFile {
+                    path: anonymizer.anonymize_path(path),
+                    source: anonymizer
+                        .anonymize(PathId::from(path), source)
+                        .apply(source)
+                        .unwrap(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Serializes the reproducer.
+    ///
+    /// # Panics
+    /// Panics if the reproducer cannot be serialized.
+    #[inline]
+    #[must_use]
+    #[cfg(feature = "serde")]
+    pub fn serialize(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap()
+    }
+
+    /// Deserializes the reproducer.
+    ///
+    /// # Errors
+    /// Returns an error if bytes don't represent a valid reproducer.
+    #[inline]
+    #[cfg(feature = "serde")]
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(bytes)
+    }
+}
@@ -0,0 +1,314 @@
+//! # Rewriter
+//!
+//! Structural search and replace over AST. A [`Rewriter`] is a set of rules,
+//! each consisting of a [`Matcher`], that selects AST nodes of a particular
+//! kind, and a replacement, that describes how a matched node should be
+//! changed. Running a rewriter on a module produces a [`FileEdit`], running it
+//! on several modules produces a [`WorkspaceEdit`]:
+//!
+//! ```
+//! use stellar_driver::compile::{compile_str, CompilationStage, CompileOptions};
+//! use stellar_rewriter::{matchers::identifier, Rewriter};
+//!
+//! let source = "fun main() { println(foo); }";
+//! let output = compile_str(
+//!     "test",
+//!     source,
+//!     CompileOptions::new().stop_after(CompilationStage::Parse),
+//! );
+//!
+//! let rewriter = Rewriter::new().on_expression(identifier("foo"), |expression, context| {
+//!     context.replace(expression.location(), "bar");
+//! });
+//!
+//! let edit = rewriter.rewrite(output.ast(), source);
+//! assert_eq!(edit.apply(source).unwrap(), "fun main() { println(bar); }");
+//! ```
+//!
+//! Built-in migrations are defined in [`migrations`]. Sources can be
+//! anonymized for bug reports using [`anonymize`].
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/quantumatic/stellar/main/additional/icon/stellar.png",
+    html_favicon_url = "https://raw.githubusercontent.com/quantumatic/stellar/main/additional/icon/stellar.png"
+)]
+#![warn(clippy::dbg_macro, missing_docs)]
+#![warn(
+    // rustc lint groups https://doc.rust-lang.org/rustc/lints/groups.html
+    future_incompatible,
+    let_underscore,
+    nonstandard_style,
+    rust_2018_compatibility,
+    rust_2018_idioms,
+    rust_2021_compatibility,
+    unused,
+    // rustc allowed-by-default lints https://doc.rust-lang.org/rustc/lints/listing/allowed-by-default.html
+    macro_use_extern_crate,
+    meta_variable_misuse,
+    missing_abi,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    non_ascii_idents,
+    noop_method_call,
+    single_use_lifetimes,
+    trivial_casts,
+    trivial_numeric_casts,
+    unreachable_pub,
+    unsafe_op_in_unsafe_fn,
+    unused_import_braces,
+    unused_lifetimes,
+    unused_qualifications,
+    variant_size_differences,
+    // rustdoc lints https://doc.rust-lang.org/rustdoc/lints.html
+    rustdoc::broken_intra_doc_links,
+    rustdoc::private_intra_doc_links,
+    rustdoc::missing_crate_level_docs,
+    rustdoc::private_doc_tests,
+    rustdoc::invalid_codeblock_attributes,
+    rustdoc::invalid_rust_codeblocks,
+    rustdoc::bare_urls,
+    // clippy categories https://doc.rust-lang.org/clippy/
+    clippy::all,
+    clippy::correctness,
+    clippy::suspicious,
+    clippy::style,
+    clippy::complexity,
+    clippy::perf,
+    clippy::pedantic,
+    clippy::nursery,
+)]
+#![allow(clippy::module_name_repetitions)]
+
+pub mod anonymize;
+pub mod matchers;
+pub mod migrations;
+
+use std::fmt::Debug;
+
+pub use matchers::Matcher;
+use stellar_ast::{
+    visit::{walk_expression, walk_module_item, walk_type_constructor, Visitor},
+    Expression, Module, ModuleItem, TypeConstructor,
+};
+use stellar_filesystem::{
+    location::Location,
+    text_edit::{FileEdit, TextEdit, WorkspaceEdit},
+};
+
+/// Describes how a matched node of type `N` is changed.
+type Replacement<'r, N> = Box<dyn Fn(&N, &mut RewriteContext<'_>) + 'r>;
+
+/// A matcher together with a replacement.
+struct Rule<'r, N> {
+    matcher: Box<dyn Matcher<N> + 'r>,
+    replacement: Replacement<'r, N>,
+}
+
+impl<N> Rule<'_, N> {
+    fn apply(&self, node: &N, context: &mut RewriteContext<'_>) {
+        if self.matcher.matches(node) {
+            (self.replacement)(node, context);
+        }
+    }
+}
+
+/// A set of rewriting rules.
+///
+/// Rules are applied to every node of a module, nested nodes included, and
+/// edits produced by them are collected into a single [`FileEdit`]. See
+/// [crate level docs](crate) for more details.
+#[derive(Default)]
+pub struct Rewriter<'r> {
+    items: Vec<Rule<'r, ModuleItem>>,
+    expressions: Vec<Rule<'r, Expression>>,
+    type_constructors: Vec<Rule<'r, TypeConstructor>>,
+}
+
+impl Debug for Rewriter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Rewriter")
+            .field("items", &self.items.len())
+            .field("expressions", &self.expressions.len())
+            .field("type_constructors", &self.type_constructors.len())
+            .finish()
+    }
+}
+
+impl<'r> Rewriter<'r> {
+    /// Creates a rewriter without rules.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule for module items.
+    #[inline]
+    #[must_use]
+    pub fn on_item(
+        mut self,
+        matcher: impl Matcher<ModuleItem> + 'r,
+        replacement: impl Fn(&ModuleItem, &mut RewriteContext<'_>) + 'r,
+    ) -> Self {
+        self.items.push(Rule {
+            matcher: Box::new(matcher),
+            replacement: Box::new(replacement),
+        });
+        self
+    }
+
+    /// Adds a rule for expressions.
+    #[inline]
+    #[must_use]
+    pub fn on_expression(
+        mut self,
+        matcher: impl Matcher<Expression> + 'r,
+        replacement: impl Fn(&Expression, &mut RewriteContext<'_>) + 'r,
+    ) -> Self {
+        self.expressions.push(Rule {
+            matcher: Box::new(matcher),
+            replacement: Box::new(replacement),
+        });
+        self
+    }
+
+    /// Adds a rule for type constructors, including the ones used as bounds and
+    /// in `implements` clauses.
+    #[inline]
+    #[must_use]
+    pub fn on_type_constructor(
+        mut self,
+        matcher: impl Matcher<TypeConstructor> + 'r,
+        replacement: impl Fn(&TypeConstructor, &mut RewriteContext<'_>) + 'r,
+    ) -> Self {
+        self.type_constructors.push(Rule {
+            matcher: Box::new(matcher),
+            replacement: Box::new(replacement),
+        });
+        self
+    }
+
+    /// Adds all rules of another rewriter.
+    #[inline]
+    #[must_use]
+    pub fn with(mut self, other: Self) -> Self {
+        self.items.extend(other.items);
+        self.expressions.extend(other.expressions);
+        self.type_constructors.extend(other.type_constructors);
+        self
+    }
+
+    /// Runs the rules on a module with a given source and returns the
+    /// produced edits.
+    #[must_use]
+    pub fn rewrite(&self, module: &Module, source: &str) -> FileEdit {
+        let mut visitor = RewriteVisitor {
+            rewriter: self,
+            context: RewriteContext {
+                source,
+                edit: FileEdit::new(module.filepath),
+            },
+        };
+
+        visitor.visit_module(module);
+        visitor.context.edit
+    }
+
+    /// Runs the rules on several modules and returns the produced edits.
+    #[must_use]
+    pub fn rewrite_all<'a>(
+        &self,
+        modules: impl IntoIterator<Item = (&'a Module, &'a str)>,
+    ) -> WorkspaceEdit {
+        let mut edit = WorkspaceEdit::new();
+
+        for (module, source) in modules {
+            edit.add_file_edit(self.rewrite(module, source));
+        }
+
+        edit
+    }
+}
+
+/// Gives replacements access to the source text and collects their edits.
+#[derive(Debug)]
+pub struct RewriteContext<'s> {
+    source: &'s str,
+    edit: FileEdit,
+}
+
+impl<'s> RewriteContext<'s> {
+    /// Returns the source of the module being rewritten.
+    #[inline]
+    #[must_use]
+    pub const fn source(&self) -> &'s str {
+        self.source
+    }
+
+    /// Returns the source text at a given location.
+    #[inline]
+    #[must_use]
+    pub fn text(&self, location: Location) -> &'s str {
+        &self.source[location]
+    }
+
+    /// Replaces text at a given location.
+    #[inline]
+    pub fn replace(&mut self, location: Location, new_text: impl Into<String>) {
+        self.edit.add(TextEdit::replace(location, new_text));
+    }
+
+    /// Inserts text right before a given location.
+    #[inline]
+    pub fn insert_before(&mut self, location: Location, new_text: impl Into<String>) {
+        self.edit.add(TextEdit::insert(
+            location.filepath,
+            location.start,
+            new_text,
+        ));
+    }
+
+    /// Inserts text right after a given location.
+    #[inline]
+    pub fn insert_after(&mut self, location: Location, new_text: impl Into<String>) {
+        self.edit
+            .add(TextEdit::insert(location.filepath, location.end, new_text));
+    }
+
+    /// Removes text at a given location.
+    #[inline]
+    pub fn delete(&mut self, location: Location) {
+        self.edit.add(TextEdit::delete(location));
+    }
+}
+
+struct RewriteVisitor<'a, 'r, 's> {
+    rewriter: &'a Rewriter<'r>,
+    context: RewriteContext<'s>,
+}
+
+impl Visitor for RewriteVisitor<'_, '_, '_> {
+    fn visit_module_item(&mut self, module_item: &ModuleItem) {
+        for rule in &self.rewriter.items {
+            rule.apply(module_item, &mut self.context);
+        }
+
+        walk_module_item(self, module_item);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        for rule in &self.rewriter.expressions {
+            rule.apply(expression, &mut self.context);
+        }
+
+        walk_expression(self, expression);
+    }
+
+    fn visit_type_constructor(&mut self, constructor: &TypeConstructor) {
+        for rule in &self.rewriter.type_constructors {
+            rule.apply(constructor, &mut self.context);
+        }
+
+        walk_type_constructor(self, constructor);
+    }
+}
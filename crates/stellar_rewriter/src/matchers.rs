@@ -0,0 +1,181 @@
+//! Defines the [`Matcher`] trait and matchers for common AST nodes.
+//!
+//! Matchers can be combined using [`Matcher::and`], [`Matcher::or`] and
+//! [`Matcher::not`]. Any closure taking a node and returning `bool` is a
+//! matcher as well:
+//!
+//! ```
+//! use stellar_ast::{Expression, ModuleItemKind};
+//! use stellar_rewriter::matchers::{call, has_attribute, identifier, item_kind, Matcher};
+//!
+//! // calls of `foo` with exactly one argument
+//! let foo_calls = call(identifier("foo")).and(
+//!     |expression: &Expression| matches!(expression, Expression::Call { arguments, .. } if arguments.len() == 1),
+//! );
+//!
+//! // functions without the `inline` attribute
+//! let not_inlined = item_kind(ModuleItemKind::Function).and(has_attribute("inline").not());
+//! ```
+
+use stellar_ast::{Expression, ModuleItem, ModuleItemKind, Path, TypeConstructor};
+use stellar_interner::IdentifierId;
+
+/// Selects AST nodes of type `N`.
+pub trait Matcher<N: ?Sized> {
+    /// Returns `true` if the node is matched.
+    fn matches(&self, node: &N) -> bool;
+
+    /// Matches nodes matched by both matchers.
+    #[inline]
+    #[must_use]
+    fn and<M>(self, other: M) -> And<Self, M>
+    where
+        Self: Sized,
+        M: Matcher<N>,
+    {
+        And(self, other)
+    }
+
+    /// Matches nodes matched by any of the matchers.
+    #[inline]
+    #[must_use]
+    fn or<M>(self, other: M) -> Or<Self, M>
+    where
+        Self: Sized,
+        M: Matcher<N>,
+    {
+        Or(self, other)
+    }
+
+    /// Matches nodes not matched by the matcher.
+    #[inline]
+    #[must_use]
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self)
+    }
+}
+
+impl<N: ?Sized, F> Matcher<N> for F
+where
+    F: Fn(&N) -> bool,
+{
+    #[inline]
+    fn matches(&self, node: &N) -> bool {
+        self(node)
+    }
+}
+
+/// See [`Matcher::and`].
+#[derive(Debug, Clone, Copy)]
+pub struct And<A, B>(A, B);
+
+impl<N: ?Sized, A: Matcher<N>, B: Matcher<N>> Matcher<N> for And<A, B> {
+    #[inline]
+    fn matches(&self, node: &N) -> bool {
+        self.0.matches(node) && self.1.matches(node)
+    }
+}
+
+/// See [`Matcher::or`].
+#[derive(Debug, Clone, Copy)]
+pub struct Or<A, B>(A, B);
+
+impl<N: ?Sized, A: Matcher<N>, B: Matcher<N>> Matcher<N> for Or<A, B> {
+    #[inline]
+    fn matches(&self, node: &N) -> bool {
+        self.0.matches(node) || self.1.matches(node)
+    }
+}
+
+/// See [`Matcher::not`].
+#[derive(Debug, Clone, Copy)]
+pub struct Not<M>(M);
+
+impl<N: ?Sized, M: Matcher<N>> Matcher<N> for Not<M> {
+    #[inline]
+    fn matches(&self, node: &N) -> bool {
+        !self.0.matches(node)
+    }
+}
+
+/// Matches every node.
+#[inline]
+#[must_use]
+pub fn any<N: ?Sized>() -> impl Matcher<N> {
+    |_: &N| true
+}
+
+/// Returns `true` if the path consists of the given dot-separated segments,
+/// e.g. `std.io.println`.
+#[must_use]
+pub fn path_is(path: &Path, expected: &str) -> bool {
+    path.identifiers.len() == expected.split('.').count()
+        && path
+            .identifiers
+            .iter()
+            .zip(expected.split('.'))
+            .all(|(identifier, segment)| &*identifier.id.as_str() == segment)
+}
+
+/// Matches module items of a given kind.
+#[inline]
+#[must_use]
+pub fn item_kind(kind: ModuleItemKind) -> impl Matcher<ModuleItem> {
+    move |item: &ModuleItem| item.kind() == kind
+}
+
+/// Matches module items with a given name.
+#[inline]
+#[must_use]
+pub fn item_named(name: &str) -> impl Matcher<ModuleItem> {
+    let name = IdentifierId::from(name);
+
+    move |item: &ModuleItem| item.name_identifier_id() == Some(name)
+}
+
+/// Matches module items having an attribute with a given path, e.g. `inline`
+/// or `lint.allow`.
+#[inline]
+#[must_use]
+pub fn has_attribute(path: &str) -> impl Matcher<ModuleItem> + '_ {
+    move |item: &ModuleItem| {
+        item.attributes()
+            .iter()
+            .any(|attribute| path_is(&attribute.path, path))
+    }
+}
+
+/// Matches identifier expressions with a given name.
+#[inline]
+#[must_use]
+pub fn identifier(name: &str) -> impl Matcher<Expression> {
+    let name = IdentifierId::from(name);
+
+    move |expression: &Expression| matches!(expression, Expression::Identifier { identifier, .. } if identifier.id == name)
+}
+
+/// Matches field access expressions with a given field name, e.g. `a.len`.
+#[inline]
+#[must_use]
+pub fn field_access(name: &str) -> impl Matcher<Expression> {
+    let name = IdentifierId::from(name);
+
+    move |expression: &Expression| matches!(expression, Expression::FieldAccess { right, .. } if right.id == name)
+}
+
+/// Matches call expressions, which callee is matched by a given matcher.
+#[inline]
+#[must_use]
+pub fn call(callee: impl Matcher<Expression>) -> impl Matcher<Expression> {
+    move |expression: &Expression| matches!(expression, Expression::Call { callee: inner, .. } if callee.matches(inner))
+}
+
+/// Matches type constructors with a given path, e.g. `List` or `std.io.File`.
+#[inline]
+#[must_use]
+pub fn type_constructor(path: &str) -> impl Matcher<TypeConstructor> + '_ {
+    move |constructor: &TypeConstructor| path_is(&constructor.path, path)
+}
@@ -0,0 +1,143 @@
+//! Built-in migrations and autofixes.
+//!
+//! - [`trait_to_interface`] replaces the deprecated `trait` keyword with `interface`.
+//! - [`deprecations`] replaces usages of deprecated items with their replacements.
+
+use std::rc::Rc;
+
+use stellar_ast::{
+    Attribute, Expression, IdentifierAST, ImportPath, Literal, Module, ModuleItem, ModuleItemKind,
+    RawBinaryOperator, TypeConstructor,
+};
+use stellar_filesystem::location::{ByteOffset, Location};
+use stellar_fx_hash::FxHashMap;
+use stellar_interner::IdentifierId;
+
+use crate::{
+    matchers::{item_kind, path_is},
+    RewriteContext, Rewriter,
+};
+
+/// Returns a rewriter replacing the `trait` keyword, that is still accepted by
+/// the parser with a warning, with `interface`.
+#[must_use]
+pub fn trait_to_interface() -> Rewriter<'static> {
+    Rewriter::new().on_item(item_kind(ModuleItemKind::Interface), |item, context| {
+        // the keyword always directly precedes the interface name
+        let name = item.location();
+        let before_name = context.source()[..name.start.0].trim_end();
+
+        if before_name.ends_with("trait") {
+            context.replace(
+                Location {
+                    filepath: name.filepath,
+                    start: ByteOffset(before_name.len() - "trait".len()),
+                    end: ByteOffset(before_name.len()),
+                },
+                "interface",
+            );
+        }
+    })
+}
+
+/// Returns a rewriter replacing usages of deprecated items, that specify their
+/// replacement in the `deprecated` attribute:
+///
+/// ```stellar
+/// #[deprecated(replacement = "new_name")]
+/// pub fun old_name() {}
+/// ```
+///
+/// Deprecated items are collected from the given modules. Usages are matched by
+/// name, because rewriting runs before name resolution, so the rewriter
+/// should only be used for names, that are unique in the package.
+#[must_use]
+#[allow(single_use_lifetimes)] // anonymous lifetimes in `impl Trait` are unstable
+pub fn deprecations<'m>(modules: impl IntoIterator<Item = &'m Module>) -> Rewriter<'static> {
+    let replacements = Rc::new(
+        modules
+            .into_iter()
+            .flat_map(|module| &module.items)
+            .filter_map(|item| {
+                Some((
+                    item.name_identifier_id()?,
+                    replacement_of(item.attributes())?.to_owned(),
+                ))
+            })
+            .collect::<FxHashMap<_, _>>(),
+    );
+
+    let in_expressions = replacements.clone();
+    let in_types = replacements.clone();
+    let in_imports = replacements;
+
+    Rewriter::new()
+        .on_expression(
+            |expression: &Expression| matches!(expression, Expression::Identifier { .. }),
+            move |expression, context| {
+                if let Expression::Identifier { identifier, .. } = expression {
+                    replace_identifier(*identifier, &in_expressions, context);
+                }
+            },
+        )
+        .on_type_constructor(
+            |_: &TypeConstructor| true,
+            move |constructor, context| {
+                if let Some(identifier) = constructor.path.identifiers.last() {
+                    replace_identifier(*identifier, &in_types, context);
+                }
+            },
+        )
+        .on_item(item_kind(ModuleItemKind::Import), move |item, context| {
+            let ModuleItem::Import { path, .. } = item else {
+                return;
+            };
+
+            // every path of an import group is checked separately
+            for import in path.clone().flatten() {
+                if let ImportPath::Single { path, .. } = import {
+                    if let Some(identifier) = path.identifiers.last() {
+                        replace_identifier(*identifier, &in_imports, context);
+                    }
+                }
+            }
+        })
+}
+
+fn replace_identifier(
+    identifier: IdentifierAST,
+    replacements: &FxHashMap<IdentifierId, String>,
+    context: &mut RewriteContext<'_>,
+) {
+    if let Some(replacement) = replacements.get(&identifier.id) {
+        context.replace(identifier.location, replacement.as_str());
+    }
+}
+
+/// Returns the value of the `replacement` argument of the `deprecated` attribute.
+fn replacement_of(attributes: &[Attribute]) -> Option<&str> {
+    attributes
+        .iter()
+        .filter(|attribute| path_is(&attribute.path, "deprecated"))
+        .flat_map(|attribute| attribute.arguments.iter().flatten())
+        .find_map(|argument| match argument {
+            Expression::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } if operator.raw == RawBinaryOperator::Eq => match (&**left, &**right) {
+                (
+                    Expression::Identifier {
+                        identifier: key, ..
+                    },
+                    Expression::Literal {
+                        literal: Literal::String { value, .. },
+                        ..
+                    },
+                ) if &*key.id.as_str() == "replacement" => Some(value.as_str()),
+                _ => None,
+            },
+            _ => None,
+        })
+}
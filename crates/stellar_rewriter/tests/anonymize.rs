@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use stellar_ast::token::RawToken;
+use stellar_driver::compile::{compile_str, CompilationStage, CompileOptions};
+use stellar_interner::PathId;
+use stellar_lexer::Lexer;
+use stellar_rewriter::anonymize::{Anonymizer, Reproducer};
+
+fn anonymize(source: &str) -> String {
+    Anonymizer::new()
+        .anonymize(PathId::from("test.sr"), source)
+        .apply(source)
+        .unwrap()
+}
+
+fn tokens(source: &str) -> Vec<(RawToken, usize, usize)> {
+    let mut lexer = Lexer::new(PathId::from("test.sr"), source);
+    let mut tokens = vec![];
+
+    loop {
+        let token = lexer.next_token();
+
+        if token.raw == RawToken::EndOfFile {
+            return tokens;
+        }
+
+        tokens.push((token.raw, token.location.start.0, token.location.end.0));
+    }
+}
+
+#[test]
+fn placeholders() {
+    assert_eq!(
+        anonymize("fun `fun`(a: b, b: a) { a + b + c; }"),
+        "fun `aaa`(a: b, b: a) { a + b + c; }"
+    );
+    assert_eq!(
+        anonymize("let x = [\"foo\", \"bar\", \"foo\", \"\\n\"]; let y = ['a', '\\n', 'ä'];"),
+        "let a = [\"aaa\", \"aab\", \"aaa\", \"aa\"]; let b = ['c', '\\n', 'ç'];"
+    );
+    assert_eq!(
+        anonymize("let v = 0o17 + 0x9f + 1_024 + 3.14e5;"),
+        "let a = 0o11 + 0x1f + 1_011 + 1.11e1;"
+    );
+    assert_eq!(
+        anonymize("//! Module docs\n/// Frobnicates\nfun f() {} // secret"),
+        "//! xxxxxx xxxx\n/// xxxxxxxxxxx\nfun a() {} // xxxxxx"
+    );
+}
+
+#[test]
+fn preserves_structure() {
+    let source = "import std.io.println;
+
+    /// Docs.
+    pub struct Point[T: ToString] { x: T, y: T }
+
+    fun main() {
+        let point = Point { x: \"hello\", y: 'c' };
+
+        match point.x.len() {
+            0 -> println(\"empty\"),
+            _ -> println(`point`.y.to_string()),
+        }
+    }";
+    let anonymized = anonymize(source);
+
+    assert_ne!(source, anonymized);
+    assert_eq!(tokens(source), tokens(&anonymized));
+
+    let original = compile_str(
+        "test",
+        source,
+        CompileOptions::new().stop_after(CompilationStage::Parse),
+    );
+    let anonymized = compile_str(
+        "test",
+        &anonymized,
+        CompileOptions::new().stop_after(CompilationStage::Parse),
+    );
+
+    assert!(original.diagnostics().is_ok());
+    assert!(anonymized.diagnostics().is_ok());
+    assert!(original
+        .ast()
+        .items
+        .iter()
+        .zip(&anonymized.ast().items)
+        .all(
+            |(original, anonymized)| original.kind() == anonymized.kind()
+                && original.location().start == anonymized.location().start
+                && original.location().end == anonymized.location().end
+        ));
+}
+
+#[test]
+fn reproducer() {
+    let reproducer = Reproducer::new([
+        (Path::new("utils/fs.sr"), "pub fun read() {}"),
+        (Path::new("main.sr"), "import test.utils.fs.read;"),
+    ]);
+
+    assert_eq!(reproducer.files[0].path, Path::new("aaaaa/aa.sr"));
+    assert_eq!(reproducer.files[0].source, "pub fun aaaa() {}");
+    assert_eq!(reproducer.files[1].path, Path::new("aaab.sr"));
+    assert_eq!(reproducer.files[1].source, "import aaac.aaaaa.aa.aaaa;");
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn reproducer_serialization_roundtrip() {
+    let reproducer = Reproducer::new([(Path::new("main.sr"), "fun main() {}")]);
+
+    assert_eq!(
+        Reproducer::deserialize(&reproducer.serialize()).unwrap(),
+        reproducer
+    );
+}
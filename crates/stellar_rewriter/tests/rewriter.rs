@@ -0,0 +1,96 @@
+use stellar_ast::{Expression, ModuleItemKind};
+use stellar_driver::compile::{compile_str, CompilationStage, CompileOptions};
+use stellar_rewriter::{
+    matchers::{call, has_attribute, identifier, item_kind, type_constructor, Matcher},
+    migrations::{deprecations, trait_to_interface},
+    Rewriter,
+};
+
+fn rewrite(
+    rewriter: impl FnOnce(&stellar_ast::Module) -> Rewriter<'static>,
+    source: &str,
+) -> String {
+    let output = compile_str(
+        "test",
+        source,
+        CompileOptions::new().stop_after(CompilationStage::Parse),
+    );
+
+    rewriter(output.ast())
+        .rewrite(output.ast(), source)
+        .apply(source)
+        .unwrap()
+}
+
+#[test]
+fn nested_nodes() {
+    assert_eq!(
+        rewrite(
+            |_| Rewriter::new().on_expression(call(identifier("foo")), |expression, context| {
+                if let Expression::Call { callee, .. } = expression {
+                    context.replace(callee.location(), "bar");
+                }
+            }),
+            "fun main() { foo(foo(1), || foo()); }"
+        ),
+        "fun main() { bar(bar(1), || bar()); }"
+    );
+}
+
+#[test]
+fn combined_matchers() {
+    assert_eq!(
+        rewrite(
+            |_| Rewriter::new().on_item(
+                item_kind(ModuleItemKind::Function).and(has_attribute("inline").not()),
+                |item, context| context.insert_before(item.location(), "inline_"),
+            ),
+            "#[inline] fun a() {} fun b() {} struct C {}"
+        ),
+        "#[inline] fun a() {} fun inline_b() {} struct C {}"
+    );
+}
+
+#[test]
+fn type_constructors() {
+    assert_eq!(
+        rewrite(
+            |_| Rewriter::new().on_type_constructor(
+                type_constructor("Vec"),
+                |constructor, context| {
+                    context.replace(constructor.path.location, "List");
+                }
+            ),
+            "fun foo[T: Vec[int32]](a: Vec[Vec[T]]): Vec[T] where T: Vec[T] {}"
+        ),
+        "fun foo[T: List[int32]](a: List[List[T]]): List[T] where T: List[T] {}"
+    );
+}
+
+#[test]
+fn trait_migration() {
+    assert_eq!(
+        rewrite(
+            |_| trait_to_interface(),
+            "pub trait Iterator[T] { fun next(self): Option[T]; }\ninterface A {}"
+        ),
+        "pub interface Iterator[T] { fun next(self): Option[T]; }\ninterface A {}"
+    );
+}
+
+#[test]
+fn deprecation_autofix() {
+    assert_eq!(
+        rewrite(
+            |module| deprecations([module]),
+            "import std.io.old_print;
+            #[deprecated(replacement = \"Point\")] struct OldPoint {}
+            #[deprecated(replacement = \"print\")] fun old_print(p: OldPoint) {}
+            fun main() { old_print(OldPoint {}); }"
+        ),
+        "import std.io.print;
+            #[deprecated(replacement = \"Point\")] struct OldPoint {}
+            #[deprecated(replacement = \"print\")] fun old_print(p: Point) {}
+            fun main() { print(Point {}); }"
+    );
+}
@@ -0,0 +1,637 @@
+//! Compiles patterns of `match` expressions into decision trees.
+//!
+//! A decision tree describes, which parts of the matched value are tested and
+//! in which order, so that the interpreter and native backends don't have to
+//! re-derive semantics of nested patterns. Each test has a success and a
+//! failure branch. Tests of the same place, that follow each other through
+//! failure branches, form a switch chain:
+//!
+//! ```stellar
+//! match value {
+//!     Option.Some(0) -> "zero",
+//!     Option.Some(x) -> "some",
+//!     Option.None -> "none",
+//! }
+//! ```
+//!
+//! is compiled into:
+//!
+//! ```text
+//! if value is Option.Some {
+//!     if value[0] == 0 {
+//!         arm 0
+//!     } else {
+//!         let x = value[0];
+//!         arm 1
+//!     }
+//! } else if value is Option.None {
+//!     arm 2
+//! } else {
+//!     fail
+//! }
+//! ```
+//!
+//! Arms are tried in order, so the tree selects the first arm, that matches a
+//! value. Identifier patterns are bindings, wildcard and tuple patterns never
+//! fail, and alternatives of or patterns are compiled as separate rows of the
+//! same arm. Exhaustiveness is not checked here: a value, that doesn't match
+//! any arm, ends up in [`DecisionTree::Fail`].
+
+use std::fmt::{self, Display};
+
+use stellar_ast::{BindingMode, IdentifierAST, Literal, NegativeNumericLiteral, RangeKind};
+use stellar_hir::{pretty::print_pattern, MatchExpressionItem, Pattern, StructFieldPattern};
+use stellar_interner::IdentifierId;
+
+use crate::Path;
+
+/// A step from a value to one of its parts.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Projection {
+    /// A field of a struct or an enum item, e.g. `.name`.
+    Field(IdentifierId),
+
+    /// An element of a tuple, a list, a tuple-like struct or an enum item,
+    /// counting from the start, e.g. `[0]`.
+    Index(usize),
+
+    /// An element of a tuple or a list, counting from the end: `0` is the last
+    /// element. Used for patterns after `..`, e.g. `b` in `[a, .., b]`.
+    IndexFromEnd(usize),
+}
+
+/// A part of the matched value, e.g. `value[0].name`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Hash)]
+pub struct Place {
+    /// Projections applied to the matched value. The matched value itself has
+    /// no projections.
+    pub projections: Vec<Projection>,
+}
+
+impl Place {
+    /// Returns the place of the matched value itself.
+    #[inline]
+    #[must_use]
+    pub const fn root() -> Self {
+        Self {
+            projections: Vec::new(),
+        }
+    }
+
+    /// Returns the place of a part of the value at this place.
+    #[inline]
+    #[must_use]
+    pub fn project(&self, projection: Projection) -> Self {
+        let mut projections = self.projections.clone();
+        projections.push(projection);
+
+        Self { projections }
+    }
+}
+
+/// A constant, that a value is compared with, e.g. `3`, `-1`, `"hello"`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Constant {
+    Literal(Literal),
+    NegativeNumericLiteral(NegativeNumericLiteral),
+}
+
+impl Constant {
+    fn from_pattern(pattern: &Pattern) -> Self {
+        match pattern {
+            Pattern::Literal(literal) => Self::Literal(literal.clone()),
+            Pattern::NegativeNumericLiteral(literal) => {
+                Self::NegativeNumericLiteral(literal.clone())
+            }
+            _ => unreachable!("bounds of range patterns are literals"),
+        }
+    }
+
+    /// Returns `true` if both constants have the same value. Locations are
+    /// ignored.
+    #[must_use]
+    pub fn same_value(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Literal(left), Self::Literal(right)) => match (left, right) {
+                (Literal::Boolean { value: a, .. }, Literal::Boolean { value: b, .. }) => a == b,
+                (Literal::Character { value: a, .. }, Literal::Character { value: b, .. }) => {
+                    a == b
+                }
+                (Literal::String { value: a, .. }, Literal::String { value: b, .. }) => a == b,
+                (Literal::Integer { value: a, .. }, Literal::Integer { value: b, .. }) => a == b,
+                (Literal::Float { value: a, .. }, Literal::Float { value: b, .. }) => {
+                    a.to_bits() == b.to_bits()
+                }
+                _ => false,
+            },
+            (Self::NegativeNumericLiteral(left), Self::NegativeNumericLiteral(right)) => {
+                match (left, right) {
+                    (
+                        NegativeNumericLiteral::Integer { value: a, .. },
+                        NegativeNumericLiteral::Integer { value: b, .. },
+                    ) => a == b,
+                    (
+                        NegativeNumericLiteral::Float { value: a, .. },
+                        NegativeNumericLiteral::Float { value: b, .. },
+                    ) => a.to_bits() == b.to_bits(),
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pattern = match self {
+            Self::Literal(literal) => Pattern::Literal(literal.clone()),
+            Self::NegativeNumericLiteral(literal) => {
+                Pattern::NegativeNumericLiteral(literal.clone())
+            }
+        };
+
+        f.write_str(&print_pattern(&pattern))
+    }
+}
+
+/// A check of a single place of the matched value.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Test {
+    /// The value is a given struct or enum item, e.g. `Option.Some`.
+    ///
+    /// **Note**: the test always succeeds for structs, but names are resolved
+    /// after the tree is built, so struct patterns are tested like enum items.
+    Constructor(Path),
+
+    /// The value equals a constant, e.g. `3`.
+    Equals(Constant),
+
+    /// The value is in a range, e.g. `1..=9`.
+    InRange {
+        start: Constant,
+        end: Constant,
+        kind: RangeKind,
+    },
+
+    /// The list has exactly `length` elements, or at least `length` elements
+    /// if the pattern has a rest pattern, e.g. `[a, .., b]`.
+    Length { length: usize, has_rest: bool },
+}
+
+impl Test {
+    fn from_pattern(pattern: &Pattern) -> Self {
+        match pattern {
+            Pattern::Literal(_) | Pattern::NegativeNumericLiteral(_) => {
+                Self::Equals(Constant::from_pattern(pattern))
+            }
+            Pattern::Struct { path, .. }
+            | Pattern::TupleLike { path, .. }
+            | Pattern::Path { path } => Self::Constructor(path.into()),
+            Pattern::Range {
+                start, end, kind, ..
+            } => Self::InRange {
+                start: Constant::from_pattern(start),
+                end: Constant::from_pattern(end),
+                kind: *kind,
+            },
+            Pattern::List { inner_patterns, .. } => {
+                let has_rest = inner_patterns
+                    .iter()
+                    .any(|pattern| matches!(pattern, Pattern::Rest { .. }));
+
+                Self::Length {
+                    length: inner_patterns.len() - usize::from(has_rest),
+                    has_rest,
+                }
+            }
+            Pattern::Identifier { .. }
+            | Pattern::Wildcard { .. }
+            | Pattern::Tuple { .. }
+            | Pattern::Or { .. }
+            | Pattern::Rest { .. } => unreachable!("irrefutable patterns are not tested"),
+        }
+    }
+
+    /// Returns `true` if both tests check the same condition. Locations are
+    /// ignored.
+    fn same_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Constructor(left), Self::Constructor(right)) => left == right,
+            (Self::Equals(left), Self::Equals(right)) => left.same_value(right),
+            (
+                Self::InRange {
+                    start: left_start,
+                    end: left_end,
+                    kind: left_kind,
+                },
+                Self::InRange {
+                    start: right_start,
+                    end: right_end,
+                    kind: right_kind,
+                },
+            ) => {
+                left_kind == right_kind
+                    && left_start.same_value(right_start)
+                    && left_end.same_value(right_end)
+            }
+            (
+                Self::Length {
+                    length: left_length,
+                    has_rest: left_has_rest,
+                },
+                Self::Length {
+                    length: right_length,
+                    has_rest: right_has_rest,
+                },
+            ) => left_length == right_length && left_has_rest == right_has_rest,
+            _ => false,
+        }
+    }
+
+    /// Returns what is known about `other` on the same place, if `self`
+    /// succeeded.
+    fn on_success(&self, other: &Self) -> Outcome {
+        if self.same_as(other) {
+            return Outcome::Matches;
+        }
+
+        match (self, other) {
+            (Self::Constructor(_), Self::Constructor(_)) | (Self::Equals(_), Self::Equals(_)) => {
+                Outcome::Fails
+            }
+            (
+                Self::Length {
+                    length,
+                    has_rest: false,
+                },
+                Self::Length {
+                    length: other_length,
+                    has_rest,
+                },
+            ) => {
+                if *has_rest && length >= other_length {
+                    Outcome::Matches
+                } else {
+                    Outcome::Fails
+                }
+            }
+            (
+                Self::Length {
+                    length,
+                    has_rest: true,
+                },
+                Self::Length {
+                    length: other_length,
+                    has_rest,
+                },
+            ) => {
+                if other_length < length {
+                    if *has_rest {
+                        Outcome::Matches
+                    } else {
+                        Outcome::Fails
+                    }
+                } else {
+                    Outcome::Unknown
+                }
+            }
+            _ => Outcome::Unknown,
+        }
+    }
+}
+
+/// What is known about a pattern after a test of the same place.
+enum Outcome {
+    /// The pattern matches, only its subpatterns are left to test.
+    Matches,
+
+    /// The pattern can't match.
+    Fails,
+
+    /// The pattern still needs to be tested.
+    Unknown,
+}
+
+/// A variable bound by a pattern, e.g. `x` in `Option.Some(x)`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Binding {
+    pub identifier: IdentifierAST,
+    pub binding_mode: BindingMode,
+    pub place: Place,
+}
+
+/// A compiled `match` expression. See [module-level docs](self) for more details.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DecisionTree {
+    /// No arm matches the value.
+    Fail,
+
+    /// An arm is selected and its variables are bound.
+    Leaf {
+        /// The index of the selected arm.
+        arm: usize,
+        bindings: Vec<Binding>,
+    },
+
+    /// Tests a place of the value and continues in one of the branches.
+    Test {
+        place: Place,
+        test: Test,
+        success: Box<Self>,
+        failure: Box<Self>,
+    },
+}
+
+/// Compiles patterns of `match` expression arms into a decision tree.
+///
+/// # Example
+///
+/// ```
+/// use stellar_thir::decision_tree::{compile, DecisionTree};
+///
+/// assert_eq!(compile(&[]), DecisionTree::Fail);
+/// ```
+#[must_use]
+pub fn compile(arms: &[MatchExpressionItem]) -> DecisionTree {
+    let rows = arms
+        .iter()
+        .enumerate()
+        .flat_map(|(arm, item)| {
+            Row {
+                arm,
+                checks: Vec::new(),
+                bindings: Vec::new(),
+            }
+            .with_check(Place::root(), &item.left)
+        })
+        .collect();
+
+    compile_rows(rows)
+}
+
+/// A single alternative of an arm with places, that are left to test.
+#[derive(Clone)]
+struct Row<'p> {
+    arm: usize,
+
+    /// Places and refutable patterns, that are left to test.
+    checks: Vec<(Place, &'p Pattern)>,
+    bindings: Vec<Binding>,
+}
+
+impl<'p> Row<'p> {
+    /// Adds a check of a pattern. Irrefutable patterns are unwrapped right
+    /// away, and or patterns split the row into one row per alternative.
+    fn with_check(mut self, place: Place, pattern: &'p Pattern) -> Vec<Self> {
+        match pattern {
+            Pattern::Identifier {
+                binding_mode,
+                identifier,
+                pattern,
+                ..
+            } => {
+                self.bindings.push(Binding {
+                    identifier: *identifier,
+                    binding_mode: *binding_mode,
+                    place: place.clone(),
+                });
+
+                match pattern {
+                    Some(pattern) => self.with_check(place, pattern),
+                    None => vec![self],
+                }
+            }
+            Pattern::Wildcard { .. } | Pattern::Rest { .. } => vec![self],
+            Pattern::Tuple { elements, .. } => self.with_checks(element_checks(&place, elements)),
+            Pattern::Or { alternatives, .. } => alternatives
+                .iter()
+                .flat_map(|alternative| self.clone().with_check(place.clone(), alternative))
+                .collect(),
+            _ => {
+                self.checks.push((place, pattern));
+                vec![self]
+            }
+        }
+    }
+
+    fn with_checks(self, checks: Vec<(Place, &'p Pattern)>) -> Vec<Self> {
+        checks
+            .into_iter()
+            .fold(vec![self], |rows, (place, pattern)| {
+                rows.into_iter()
+                    .flat_map(|row| row.with_check(place.clone(), pattern))
+                    .collect()
+            })
+    }
+
+    /// Replaces a check, that is known to succeed, with checks of its
+    /// subpatterns.
+    fn specialize(mut self, index: usize) -> Vec<Self> {
+        let (place, pattern) = self.checks.remove(index);
+
+        match pattern {
+            Pattern::TupleLike { inner_patterns, .. } | Pattern::List { inner_patterns, .. } => {
+                self.with_checks(element_checks(&place, inner_patterns))
+            }
+            Pattern::Struct { fields, .. } => {
+                let mut checks = Vec::new();
+
+                for field in fields {
+                    let StructFieldPattern::NotRest {
+                        field_name,
+                        value_pattern,
+                        ..
+                    } = field
+                    else {
+                        continue;
+                    };
+
+                    let field_place = place.project(Projection::Field(field_name.id));
+
+                    match value_pattern {
+                        Some(pattern) => checks.push((field_place, pattern)),
+                        // `Person { name }` binds the field to `name`
+                        None => self.bindings.push(Binding {
+                            identifier: *field_name,
+                            binding_mode: BindingMode::Value,
+                            place: field_place,
+                        }),
+                    }
+                }
+
+                self.with_checks(checks)
+            }
+            _ => vec![self],
+        }
+    }
+}
+
+/// Returns places of elements of a tuple or a list pattern. Elements after
+/// the rest pattern are counted from the end.
+fn element_checks<'p>(place: &Place, elements: &'p [Pattern]) -> Vec<(Place, &'p Pattern)> {
+    let rest = elements
+        .iter()
+        .position(|element| matches!(element, Pattern::Rest { .. }));
+
+    elements
+        .iter()
+        .enumerate()
+        .filter_map(|(index, element)| {
+            let projection = match rest {
+                Some(rest) if index == rest => return None,
+                Some(rest) if index > rest => Projection::IndexFromEnd(elements.len() - 1 - index),
+                _ => Projection::Index(index),
+            };
+
+            Some((place.project(projection), element))
+        })
+        .collect()
+}
+
+fn compile_rows(rows: Vec<Row<'_>>) -> DecisionTree {
+    let Some(first) = rows.first() else {
+        return DecisionTree::Fail;
+    };
+
+    let Some((place, pattern)) = first.checks.first() else {
+        return DecisionTree::Leaf {
+            arm: first.arm,
+            bindings: first.bindings.clone(),
+        };
+    };
+
+    let place = place.clone();
+    let test = Test::from_pattern(pattern);
+
+    let mut success = Vec::new();
+    let mut failure = Vec::new();
+
+    for row in rows {
+        let Some(index) = row.checks.iter().position(|(p, _)| *p == place) else {
+            success.push(row.clone());
+            failure.push(row);
+            continue;
+        };
+
+        let row_test = Test::from_pattern(row.checks[index].1);
+
+        match test.on_success(&row_test) {
+            Outcome::Matches => success.extend(row.clone().specialize(index)),
+            Outcome::Fails => {}
+            Outcome::Unknown => success.push(row.clone()),
+        }
+
+        if !test.same_as(&row_test) {
+            failure.push(row);
+        }
+    }
+
+    DecisionTree::Test {
+        place,
+        test,
+        success: Box::new(compile_rows(success)),
+        failure: Box::new(compile_rows(failure)),
+    }
+}
+
+impl Display for Place {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("value")?;
+
+        for projection in &self.projections {
+            match projection {
+                Projection::Field(name) => write!(f, ".{name}")?,
+                Projection::Index(index) => write!(f, "[{index}]")?,
+                Projection::IndexFromEnd(index) => write!(f, "[-{}]", index + 1)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for DecisionTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+impl DecisionTree {
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "    ".repeat(depth);
+
+        match self {
+            Self::Fail => write!(f, "{indent}fail"),
+            Self::Leaf { arm, bindings } => {
+                for binding in bindings {
+                    let binding_mode = match binding.binding_mode {
+                        BindingMode::Value => "",
+                        BindingMode::MutableValue => "mut ",
+                        BindingMode::Reference => "ref ",
+                        BindingMode::MutableReference => "ref mut ",
+                    };
+
+                    writeln!(
+                        f,
+                        "{indent}let {binding_mode}{} = {};",
+                        binding.identifier.id, binding.place
+                    )?;
+                }
+
+                write!(f, "{indent}arm {arm}")
+            }
+            Self::Test { .. } => {
+                f.write_str(&indent)?;
+
+                let mut tree = self;
+
+                // tests of the same place are printed as `else if` chains
+                while let Self::Test {
+                    place,
+                    test,
+                    success,
+                    failure,
+                } = tree
+                {
+                    match test {
+                        Test::Constructor(path) => {
+                            let path = path
+                                .identifiers
+                                .iter()
+                                .map(|identifier| identifier.as_str())
+                                .collect::<Vec<_>>()
+                                .join(".");
+
+                            write!(f, "if {place} is {path} {{")?;
+                        }
+                        Test::Equals(constant) => write!(f, "if {place} == {constant} {{")?,
+                        Test::InRange { start, end, kind } => {
+                            let operator = match kind {
+                                RangeKind::Exclusive => "..",
+                                RangeKind::Inclusive => "..=",
+                            };
+
+                            write!(f, "if {place} in {start}{operator}{end} {{")?;
+                        }
+                        Test::Length { length, has_rest } => {
+                            let operator = if *has_rest { ">=" } else { "==" };
+
+                            write!(f, "if {place}.len() {operator} {length} {{")?;
+                        }
+                    }
+
+                    writeln!(f)?;
+                    success.fmt_indented(f, depth + 1)?;
+                    write!(f, "\n{indent}}} else ")?;
+
+                    tree = failure;
+                }
+
+                writeln!(f, "{{")?;
+                tree.fmt_indented(f, depth + 1)?;
+                write!(f, "\n{indent}}}")
+            }
+        }
+    }
+}
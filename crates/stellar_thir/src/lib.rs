@@ -63,12 +63,19 @@ use generic_parameter_scope::GenericParameterScope;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use stellar_ast::{IdentifierAST, Literal, Visibility};
+// dev-dependencies of integration tests
+#[cfg(test)]
+use stellar_ast_lowering as _;
+#[cfg(test)]
+use stellar_database as _;
 use stellar_filesystem::location::Location;
 use stellar_fx_hash::FxHashMap;
-use stellar_hir as _;
 use stellar_interner::IdentifierId;
+#[cfg(test)]
+use stellar_parser as _;
 use ty::{Type, TypeConstructor};
 
+pub mod decision_tree;
 pub mod generic_parameter_scope;
 pub mod ty;
 
@@ -123,8 +130,16 @@ pub enum Pattern {
     /// An or pattern, e.g. `Some(..) | None`.
     Or {
         location: Location,
-        left: Box<Self>,
-        right: Box<Self>,
+        alternatives: Vec<Self>,
+        ty: Type,
+    },
+
+    /// A range pattern, e.g. `1..=9`, `'a'..'z'`.
+    Range {
+        location: Location,
+        start: Box<Self>,
+        end: Box<Self>,
+        kind: stellar_ast::RangeKind,
         ty: Type,
     },
 
@@ -141,6 +156,7 @@ impl Pattern {
             Self::Identifier { location, .. }
             | Self::List { location, .. }
             | Self::Or { location, .. }
+            | Self::Range { location, .. }
             | Self::Rest { location, .. }
             | Self::Struct { location, .. }
             | Self::Tuple { location, .. }
@@ -227,6 +243,14 @@ pub enum Expression {
         right: IdentifierAST,
     },
 
+    /// Tuple index expression, e.g. `x.0`.
+    TupleIndex {
+        location: Location,
+        left: Box<Self>,
+        index: usize,
+        index_location: Location,
+    },
+
     /// Prefix expression, e.g. `!false`, `++a`.
     Prefix {
         location: Location,
@@ -276,6 +300,7 @@ pub enum Expression {
         location: Location,
         left: Box<Self>,
         fields: Vec<StructExpressionItem>,
+        rest: Option<Box<Self>>,
     },
 
     /// Match expression (`match fs.read_file(...) { ... }`).
@@ -283,6 +308,9 @@ pub enum Expression {
         location: Location,
         expression: Box<Self>,
         block: Vec<MatchExpressionItem>,
+
+        /// Patterns of the arms compiled with [`decision_tree::compile`].
+        decision_tree: decision_tree::DecisionTree,
     },
 
     /// Lambda expression (`|x| { x + 1 }`).
@@ -326,6 +354,7 @@ impl Expression {
             }
             | Self::If { location, .. }
             | Self::FieldAccess { location, .. }
+            | Self::TupleIndex { location, .. }
             | Self::Prefix { location, .. }
             | Self::Postfix { location, .. }
             | Self::While { location, .. }
@@ -373,8 +402,11 @@ impl Expression {
 /// A statement, e.g. `defer file.close()`, `return Some("hello");`, `break;`.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Statement {
-    /// Defer statement - `defer <expr>;`, e.g. `defer file.close()`.
-    Defer { call: Expression },
+    /// Defer statement, e.g. `defer { close(f); flush(log); }`.
+    Defer {
+        location: Location,
+        block: Vec<Self>,
+    },
 
     /// Expression statement, e.g. `call();`.
     Expression {
@@ -0,0 +1,142 @@
+use stellar_ast_lowering::LowerToHir;
+use stellar_database::{PackageData, Path, State};
+use stellar_hir::{Expression, ModuleItem, Statement};
+use stellar_interner::{IdentifierId, PathId};
+use stellar_parser::parse_module;
+use stellar_thir::decision_tree::compile;
+
+/// Parses and lowers a `match` expression and returns its compiled decision
+/// tree rendered as text.
+fn compile_match(source: &str) -> String {
+    let mut state = State::new();
+    let name = IdentifierId::from("test");
+    let filepath = PathId::from("test.sr");
+    let package = PackageData::alloc_in_memory(state.db_mut(), name, filepath);
+
+    let parse_result = parse_module(
+        &mut state,
+        package,
+        Path::new(vec![name]),
+        filepath,
+        &format!("fun main() {{ {source} }}"),
+    );
+    let module = parse_result.module();
+
+    assert!(
+        state.diagnostics().is_ok(),
+        "failed to parse `{source}`: {:?}",
+        state.diagnostics()
+    );
+
+    let hir = LowerToHir::run_all(&mut state, vec![parse_result])
+        .remove(&module)
+        .unwrap();
+
+    let ModuleItem::Function(function) = &hir.items[0] else {
+        unreachable!()
+    };
+
+    let Some(
+        [Statement::Expression {
+            expression: Expression::Match { block, .. },
+            ..
+        }],
+    ) = function.body.as_deref()
+    else {
+        panic!("`{source}` is not a match expression");
+    };
+
+    compile(block).to_string()
+}
+
+#[test]
+fn enum_items() {
+    assert_eq!(
+        compile_match(
+            "match value {
+                Option.Some(0) -> \"zero\",
+                Option.Some(x) -> \"some\",
+                Option.None -> \"none\",
+            }"
+        ),
+        "\
+if value is Option.Some {
+    if value[0] == 0 {
+        arm 0
+    } else {
+        let x = value[0];
+        arm 1
+    }
+} else if value is Option.None {
+    arm 2
+} else {
+    fail
+}"
+    );
+}
+
+#[test]
+fn or_patterns_and_bindings() {
+    assert_eq!(
+        compile_match("match value { 1 | 2 -> a, mut x @ 3..=5 -> b, _ -> c }"),
+        "\
+if value == 1 {
+    arm 0
+} else if value == 2 {
+    arm 0
+} else if value in 3..=5 {
+    let mut x = value;
+    arm 1
+} else {
+    arm 2
+}"
+    );
+}
+
+#[test]
+fn list_patterns() {
+    assert_eq!(
+        compile_match("match value { [] -> 0, [x] -> 1, [first, .., last] -> 2 }"),
+        "\
+if value.len() == 0 {
+    arm 0
+} else if value.len() == 1 {
+    let x = value[0];
+    arm 1
+} else if value.len() >= 2 {
+    let first = value[0];
+    let last = value[-1];
+    arm 2
+} else {
+    fail
+}"
+    );
+}
+
+#[test]
+fn struct_and_tuple_patterns() {
+    assert_eq!(
+        compile_match(
+            "match value {
+                (Point { x: 0, y }, _) -> 0,
+                (_, true) -> 1,
+                _ -> 2,
+            }"
+        ),
+        "\
+if value[0] is Point {
+    if value[0].x == 0 {
+        let y = value[0].y;
+        arm 0
+    } else if value[1] == true {
+        arm 1
+    } else {
+        arm 2
+    }
+} else if value[1] == true {
+    arm 1
+} else {
+    arm 2
+}"
+    );
+}
@@ -0,0 +1,214 @@
+#[cfg(feature = "debug")]
+use std::time::Instant;
+
+use itertools::Itertools;
+use stellar_ast::{IntegerValue, Literal, NegativeNumericLiteral, RawPrefixOperator};
+use stellar_database::{ModuleId, State};
+use stellar_filesystem::location::Location;
+use stellar_fx_hash::FxHashMap;
+use stellar_hir::{
+    visit::{walk_expression, Visitor},
+    Expression, Pattern, Type,
+};
+use stellar_interner::builtin_identifiers::{
+    INT16, INT32, INT64, INT8, ISIZE, UINT16, UINT32, UINT64, UINT8, USIZE,
+};
+#[cfg(feature = "debug")]
+use tracing::trace;
+
+use crate::diagnostics::IntegerLiteralOutOfRange;
+
+/// Reports integer literals, that don't fit into their types.
+///
+/// The parser accepts integer literals of any size, so overflows are only
+/// reported here. Types of expressions are not inferred yet, so a literal is
+/// checked against the type annotation of a `let` statement, which value it
+/// is, and against the widest integer type otherwise.
+pub struct CheckIntegerLiterals<'s> {
+    state: &'s mut State,
+    modules: &'s FxHashMap<ModuleId, stellar_hir::Module>,
+}
+
+impl<'s> CheckIntegerLiterals<'s> {
+    pub fn run_all(state: &'s mut State, modules: &'s FxHashMap<ModuleId, stellar_hir::Module>) {
+        #[cfg(feature = "debug")]
+        let now = Instant::now();
+
+        if state.is_cancelled() {
+            return;
+        }
+
+        CheckIntegerLiterals { state, modules }.run();
+
+        #[cfg(feature = "debug")]
+        trace!(
+            "check_integer_literals() <{} us>",
+            now.elapsed().as_micros()
+        );
+    }
+
+    fn run(self) {
+        let mut checker = IntegerLiteralChecker::default();
+
+        // sort modules to emit diagnostics in a deterministic order
+        for module in self
+            .modules
+            .values()
+            .sorted_by_key(|module| module.filepath.to_string())
+        {
+            checker.visit_module(module);
+        }
+
+        for (location, ty) in checker.overflows {
+            self.state
+                .diagnostics_mut()
+                .add_diagnostic(IntegerLiteralOutOfRange::new(location, ty.name));
+        }
+    }
+}
+
+/// A builtin integer type.
+#[derive(Debug, Clone, Copy)]
+struct IntegerType {
+    name: &'static str,
+    bits: u32,
+    signed: bool,
+}
+
+impl IntegerType {
+    const INT64: Self = Self::new("int64", 64, true);
+    const UINT64: Self = Self::new("uint64", 64, false);
+
+    const fn new(name: &'static str, bits: u32, signed: bool) -> Self {
+        Self { name, bits, signed }
+    }
+
+    /// Returns the integer type, that a given type refers to, if it's a
+    /// builtin integer type.
+    fn from_type(ty: &Type) -> Option<Self> {
+        let Type::Constructor(constructor) = ty else {
+            return None;
+        };
+
+        let [name] = constructor.path.identifiers.as_slice() else {
+            return None;
+        };
+
+        if !constructor.arguments.is_empty() {
+            return None;
+        }
+
+        // `isize` and `usize` are assumed to be 64 bits wide
+        Some(match name.id {
+            INT8 => Self::new("int8", 8, true),
+            INT16 => Self::new("int16", 16, true),
+            INT32 => Self::new("int32", 32, true),
+            INT64 => Self::INT64,
+            ISIZE => Self::new("isize", 64, true),
+            UINT8 => Self::new("uint8", 8, false),
+            UINT16 => Self::new("uint16", 16, false),
+            UINT32 => Self::new("uint32", 32, false),
+            UINT64 => Self::UINT64,
+            USIZE => Self::new("usize", 64, false),
+            _ => return None,
+        })
+    }
+
+    /// Returns the widest integer type, that can store a literal.
+    const fn widest(negative: bool) -> Self {
+        if negative {
+            Self::INT64
+        } else {
+            Self::UINT64
+        }
+    }
+
+    /// Returns `true` if a literal, negated if `negative` is `true`, fits into
+    /// the type.
+    fn fits(self, value: &IntegerValue, negative: bool) -> bool {
+        if self.signed {
+            value.fits_signed(self.bits, negative)
+        } else {
+            !negative && value.fits_unsigned(self.bits)
+        }
+    }
+}
+
+#[derive(Default)]
+struct IntegerLiteralChecker {
+    overflows: Vec<(Location, IntegerType)>,
+}
+
+impl IntegerLiteralChecker {
+    fn check(&mut self, value: &IntegerValue, negative: bool, location: Location, ty: IntegerType) {
+        if !ty.fits(value, negative) {
+            self.overflows.push((location, ty));
+        }
+    }
+
+    /// Checks a value of a `let` statement, which is expected to have a given
+    /// type.
+    fn check_value(&mut self, value: &Expression, ty: Option<IntegerType>) {
+        match (integer_literal(value), ty) {
+            (Some((value, negative, location)), Some(ty)) => {
+                self.check(value, negative, location, ty);
+            }
+            _ => self.visit_expression(value),
+        }
+    }
+}
+
+impl Visitor for IntegerLiteralChecker {
+    fn visit_let_statement(&mut self, pattern: &Pattern, value: &Expression, ty: Option<&Type>) {
+        self.visit_pattern(pattern);
+        self.check_value(value, ty.and_then(IntegerType::from_type));
+
+        if let Some(ty) = ty {
+            self.visit_type(ty);
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        if let Some((value, negative, location)) = integer_literal(expression) {
+            self.check(value, negative, location, IntegerType::widest(negative));
+        } else {
+            walk_expression(self, expression);
+        }
+    }
+
+    fn visit_literal_pattern(&mut self, literal: &Literal) {
+        if let Literal::Integer { value, location } = literal {
+            self.check(value, false, *location, IntegerType::widest(false));
+        }
+    }
+
+    fn visit_negative_numeric_literal_pattern(&mut self, literal: &NegativeNumericLiteral) {
+        if let NegativeNumericLiteral::Integer { value, location } = literal {
+            self.check(value, true, *location, IntegerType::widest(true));
+        }
+    }
+}
+
+/// Returns the absolute value of an integer literal (possibly negated, e.g.
+/// `-3`), whether it is negated, and its location.
+fn integer_literal(expression: &Expression) -> Option<(&IntegerValue, bool, Location)> {
+    match expression {
+        Expression::Literal {
+            literal: Literal::Integer { value, location },
+            ..
+        } => Some((value, false, *location)),
+        Expression::Prefix {
+            location,
+            inner,
+            operator,
+            ..
+        } if operator.raw == RawPrefixOperator::Minus => match &**inner {
+            Expression::Literal {
+                literal: Literal::Integer { value, .. },
+                ..
+            } => Some((value, true, *location)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
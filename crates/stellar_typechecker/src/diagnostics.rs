@@ -6,8 +6,8 @@ use stellar_diagnostics::{
     BuildDiagnostic,
 };
 use stellar_english_commons::pluralize::PluralizeExt;
-use stellar_filesystem::location::Location;
-use stellar_interner::PathId;
+use stellar_filesystem::location::{ByteOffset, Location};
+use stellar_interner::{IdentifierId, PathId};
 
 define_diagnostics! {
     /// Diagnostic related to an item defined multiple times error.
@@ -120,6 +120,26 @@ define_diagnostics! {
         }
     }
 
+    /// Diagnostic, that occurs when the compiler tries to resolve a module's item
+    /// outside of the scope, that its visibility is restricted to with
+    /// `pub(package)` or `pub(super)`.
+    diagnostic(error) FailedToResolveRestrictedModuleItem(
+        self,
+        item_name: String,
+        item_name_location: Location,
+        visibility_location: Location,
+        scope: String
+    ) {
+        code { "E008" }
+        message { format!("module item `{}` is not visible here", self.item_name) }
+        labels {
+            primary { self.item_name_location }
+            secondary {
+                self.visibility_location => format!("`{}` is only visible in {}", self.item_name, self.scope)
+            }
+        }
+    }
+
     /// Diagnostic, that appears when you try to access a name in a namespace of
     /// a module item except an enum, for example:
     ///
@@ -222,6 +242,283 @@ define_diagnostics! {
             "note: types cannot be inferred in signatures, because of explicitness."
         }
     }
+
+    /// Diagnostic, that occurs when an imported name clashes with an item or a
+    /// submodule defined in the module itself, for example:
+    ///
+    /// ```txt
+    /// import std.io.println;
+    ///               ^^^^^^^ wrong
+    ///
+    /// fun println() {}
+    /// ```
+    diagnostic(error) ImportShadowsModuleItem(
+        self,
+        name: IdentifierAST
+    ) {
+        code { "E010" }
+        message { format!("the name `{}` is imported, but it is already defined in this module", self.name.id) }
+        labels {
+            primary {
+                self.name.location => format!("`{}` is imported here", self.name.id)
+            }
+        }
+        notes {
+            "note: imports cannot shadow items defined in the same module".to_owned()
+            format!("help: use `as` to import `{}` under a different name", self.name.id)
+        }
+    }
+
+    /// Diagnostic, that occurs when two imports in the same module bind the same name.
+    diagnostic(error) NameImportedMultipleTimes(
+        self,
+        name: IdentifierAST,
+        first_import_location: Location
+    ) {
+        code { "E032" }
+        message { format!("the name `{}` is imported multiple times", self.name.id) }
+        labels {
+            primary {
+                self.name.location => format!("`{}` reimported here", self.name.id)
+            }
+            secondary {
+                self.first_import_location => format!("previous import of `{}` is here", self.name.id)
+            }
+        }
+        notes {
+            format!("help: use `as` to import `{}` under a different name", self.name.id)
+        }
+    }
+
+    /// Diagnostic, that occurs when two glob imports in the same module bring
+    /// different items with the same name, for example:
+    ///
+    /// ```txt
+    /// import std.io.*;          // defines `Error`
+    /// import std.collections.*; // defines `Error` as well
+    /// ```
+    diagnostic(error) AmbiguousGlobImport(
+        self,
+        name: IdentifierId,
+        first_import_location: Location,
+        second_import_location: Location
+    ) {
+        code { "E033" }
+        message { format!("the name `{}` is imported by multiple glob imports", self.name) }
+        labels {
+            primary {
+                self.second_import_location => format!("`{}` is imported here", self.name)
+            }
+            secondary {
+                self.first_import_location => format!("another item named `{}` is imported here", self.name)
+            }
+        }
+        notes {
+            format!("help: import `{}` explicitly to choose one of the items", self.name)
+        }
+    }
+
+    /// Diagnostic, that occurs when a glob import refers to something, that is
+    /// neither a module nor an enum, e.g. `import std.io.println.*`.
+    diagnostic(error) GlobImportFromNonNamespace(
+        self,
+        name: IdentifierAST
+    ) {
+        code { "E016" }
+        message { format!("cannot glob import names from `{}`", self.name.id) }
+        labels {
+            primary {
+                self.name.location => format!("`{}` is not a module or an enum", self.name.id)
+            }
+        }
+    }
+
+    /// Diagnostic, that occurs when an import re-exports an item to modules, that
+    /// can't use the item itself, e.g. `pub import self.utils.helper;`, where
+    /// `helper` is only visible in its package.
+    diagnostic(error) ReexportMoreVisibleThanItem(
+        self,
+        name: IdentifierAST,
+        visibility_location: Location
+    ) {
+        code { "E017" }
+        message { format!("`{}` cannot be re-exported with a wider visibility than its own", self.name.id) }
+        labels {
+            primary {
+                self.visibility_location => "re-export is more visible than the item"
+            }
+            secondary {
+                self.name.location => format!("`{}` is imported here", self.name.id)
+            }
+        }
+        notes {
+            "help: make the re-export less visible or the item more visible"
+        }
+    }
+
+    /// Diagnostic, that occurs when the first segment of an import path is both
+    /// the name of the current package and the name of one of its dependencies.
+    diagnostic(error) AmbiguousPackageReference(
+        self,
+        package_name: IdentifierAST
+    ) {
+        code { "E011" }
+        message { format!("`{}` is ambiguous", self.package_name.id) }
+        labels {
+            primary {
+                self.package_name.location => format!("`{}` can refer both to the current package and to a dependency", self.package_name.id)
+            }
+        }
+        notes {
+            "help: use `self` to refer to the current package"
+            "help: rename the dependency in the manifest file to refer to it"
+        }
+    }
+
+    /// Diagnostic, that occurs when two module files map to the same module, for
+    /// example when both `foo.sr` and the `foo` directory define the module `foo`.
+    diagnostic(error) ModuleDefinedMultipleTimes(
+        self,
+        module_path: String,
+        first_filepath: PathId,
+        second_filepath: PathId
+    ) {
+        code { "E012" }
+        message { format!("the module `{}` is defined multiple times", self.module_path) }
+        labels {
+            primary { file_location(self.second_filepath) => format!("second definition of `{}` is in this file", self.module_path) }
+            secondary { file_location(self.first_filepath) => format!("first definition of `{}` is in this file", self.module_path) }
+        }
+        notes {
+            format!("note: first definition is in `{}`", self.first_filepath)
+            format!("note: second, conflicting definition is in `{}`", self.second_filepath)
+        }
+    }
+
+    /// Diagnostic, that occurs when names of two modules in the same namespace differ
+    /// only in case, e.g. `Main.sr` and `main.sr`. On case-insensitive filesystems both
+    /// names refer to the same file.
+    diagnostic(error) ModuleNamesDifferOnlyInCase(
+        self,
+        first_module_path: String,
+        first_filepath: PathId,
+        second_module_path: String,
+        second_filepath: PathId
+    ) {
+        code { "E012" }
+        message { format!("the modules `{}` and `{}` differ only in case", self.first_module_path, self.second_module_path) }
+        labels {
+            primary { file_location(self.second_filepath) => format!("`{}` is defined in this file", self.second_module_path) }
+            secondary { file_location(self.first_filepath) => format!("`{}` is defined in this file", self.first_module_path) }
+        }
+        notes {
+            format!("note: `{}` and `{}` refer to the same file on case-insensitive filesystems", self.first_filepath, self.second_filepath)
+            "help: rename one of the modules".to_owned()
+        }
+    }
+
+    /// Diagnostic, that occurs when an implementation block is written for
+    /// something, that is not a struct or an enum, e.g. `impl (int32, int32) {}`.
+    diagnostic(error) ExpectedTypeInImpl(
+        self,
+        location: Location,
+        found: String
+    ) {
+        code { "E013" }
+        message { format!("expected a struct or an enum, found {}", self.found) }
+        labels {
+            primary { self.location => "cannot define methods here" }
+        }
+    }
+
+    /// Diagnostic, that occurs when a name before `for` in an implementation block
+    /// doesn't refer to an interface.
+    diagnostic(error) ExpectedInterfaceInImpl(
+        self,
+        location: Location,
+        found: String
+    ) {
+        code { "E013" }
+        message { format!("expected an interface, found {}", self.found) }
+        labels {
+            primary { self.location => "not an interface" }
+        }
+    }
+
+    /// Diagnostic, that occurs when inherent methods are defined for a type
+    /// from another package.
+    diagnostic(error) InherentImplForForeignType(
+        self,
+        location: Location,
+        type_name: IdentifierId
+    ) {
+        code { "E013" }
+        message { format!("cannot define inherent methods for `{}` outside of the package where it is defined", self.type_name) }
+        labels {
+            primary { self.location => format!("`{}` is defined in another package", self.type_name) }
+        }
+        notes {
+            "help: define an interface with the methods and implement it for the type instead"
+        }
+    }
+
+    /// Diagnostic related to a method defined multiple times for the same type.
+    diagnostic(error) MethodDefinedMultipleTimes(
+        self,
+        type_name: IdentifierId,
+        method_name: IdentifierId,
+        first_definition_location: Location,
+        second_definition_location: Location
+    ) {
+        code { "E006" }
+        message { format!("duplicate definition of the method `{}` in `{}`", self.method_name, self.type_name) }
+        labels {
+            primary {
+                self.first_definition_location => format!("first definition of `{}`", self.method_name)
+            }
+            secondary { self.second_definition_location => format!("second, conflicting definition of `{}`", self.method_name) }
+        }
+    }
+
+    /// Diagnostic related to an associated constant defined multiple times in the same interface.
+    diagnostic(error) AssociatedConstantDefinedMultipleTimes(
+        self,
+        interface_name: IdentifierId,
+        constant_name: IdentifierId,
+        first_definition_location: Location,
+        second_definition_location: Location
+    ) {
+        code { "E006" }
+        message { format!("duplicate definition of the associated constant `{}` in `{}`", self.constant_name, self.interface_name) }
+        labels {
+            primary {
+                self.first_definition_location => format!("first definition of `{}`", self.constant_name)
+            }
+            secondary { self.second_definition_location => format!("second, conflicting definition of `{}`", self.constant_name) }
+        }
+    }
+
+    /// Diagnostic related to a private module item, that is never used in the package.
+    diagnostic(warning) UnusedItem(
+        self,
+        kind: ModuleItemKind,
+        name: IdentifierAST
+    ) {
+        code { "W007" }
+        message { format!("{} `{}` is never used", self.kind, self.name.id) }
+        labels {
+            primary { self.name.location => format!("{} is never used", self.kind) }
+        }
+    }
+
+    /// Diagnostic related to an integer literal, that doesn't fit into its type.
+    diagnostic(error) IntegerLiteralOutOfRange(self, location: Location, ty: &'static str) {
+        code { "E002" }
+        message { format!("integer literal is out of range for `{}`", self.ty) }
+        labels {
+            primary { self.location => format!("this literal doesn't fit into `{}`", self.ty) }
+        }
+    }
 }
 
 pub struct CycleDetectedWhenComputingSignatureOf {
@@ -257,3 +554,13 @@ impl BuildDiagnostic for CycleDetectedWhenComputingSignatureOf {
             )
     }
 }
+
+/// Returns an empty location at the start of a file, for diagnostics about
+/// whole files, e.g. modules, that are defined multiple times.
+const fn file_location(filepath: PathId) -> Location {
+    Location {
+        filepath,
+        start: ByteOffset(0),
+        end: ByteOffset(0),
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(warnings)]
 
+pub mod check_integer_literals;
 mod diagnostics;
 pub mod resolution;
 pub mod signature_analysis;
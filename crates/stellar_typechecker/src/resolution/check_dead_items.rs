@@ -0,0 +1,198 @@
+#[cfg(feature = "debug")]
+use std::time::Instant;
+
+use itertools::Itertools;
+use stellar_ast::{ModuleItemKind, Visibility};
+use stellar_database::{Database, ModuleId, State, Symbol};
+use stellar_filesystem::location::Location;
+use stellar_fx_hash::{FxHashMap, FxHashSet};
+use stellar_hir::visit::Visitor;
+#[cfg(feature = "debug")]
+use tracing::trace;
+
+use super::collect_references::{resolve_path, ReferenceCollector};
+use crate::diagnostics::UnusedItem;
+
+/// Reports private functions, structs and type aliases, that are never
+/// referenced in the package.
+///
+/// Items, that can be used outside of the package (or outside of the
+/// analysis, like `main` function of the root module), as well as items of
+/// other kinds and imported items, are always considered used. Every item
+/// referenced from a used one is used as well. Implementation blocks of a
+/// private type are only analyzed once the type itself is used, so methods
+/// don't keep their type alive.
+pub struct CheckDeadItems<'s> {
+    state: &'s mut State,
+    modules: &'s FxHashMap<ModuleId, stellar_hir::Module>,
+
+    /// Private items, that are not known to be used yet.
+    unused: FxHashSet<Symbol>,
+
+    /// Implementation blocks of private types, that are not known to be used
+    /// yet.
+    impls: FxHashMap<Symbol, Vec<(ModuleId, usize)>>,
+
+    /// Used items, which bodies are not analyzed yet.
+    worklist: Vec<(ModuleId, usize)>,
+}
+
+impl<'s> CheckDeadItems<'s> {
+    pub fn run_all(state: &'s mut State, modules: &'s FxHashMap<ModuleId, stellar_hir::Module>) {
+        #[cfg(feature = "debug")]
+        let now = Instant::now();
+
+        if state.is_cancelled() {
+            return;
+        }
+
+        CheckDeadItems {
+            state,
+            modules,
+            unused: FxHashSet::default(),
+            impls: FxHashMap::default(),
+            worklist: Vec::new(),
+        }
+        .run();
+
+        #[cfg(feature = "debug")]
+        trace!("check_dead_items() <{} us>", now.elapsed().as_micros());
+    }
+
+    fn run(mut self) {
+        let db = self.state.db();
+
+        for &module in self.modules.keys() {
+            self.unused.extend(
+                module
+                    .module_item_symbols(db)
+                    .values()
+                    .copied()
+                    .filter(|&symbol| can_be_unused(db, symbol)),
+            );
+        }
+
+        // items, that are only analyzed once they are known to be used
+        let mut deferred = self
+            .unused
+            .iter()
+            .map(|&symbol| (symbol.module(db), symbol.signature(db).node_idx(db)))
+            .collect::<FxHashSet<_>>();
+
+        for &module in self.modules.keys() {
+            for &impl_ in module.impls(db) {
+                let ty = impl_.ty(db);
+
+                if self.unused.contains(&ty) {
+                    let node = (module, impl_.node_idx(db));
+
+                    deferred.insert(node);
+                    self.impls.entry(ty).or_default().push(node);
+                }
+            }
+        }
+
+        let mut imported = Vec::new();
+
+        for (&module, hir) in self.modules {
+            for idx in 0..hir.items.len() {
+                let node = (module, idx);
+
+                if !deferred.contains(&node) {
+                    self.worklist.push(node);
+                }
+            }
+
+            imported.extend(module.resolved_imports(db).values().copied());
+            imported.extend(
+                module
+                    .reexports(db)
+                    .values()
+                    .map(|reexport| reexport.symbol),
+            );
+        }
+
+        for symbol in imported {
+            self.mark(symbol);
+        }
+
+        while let Some((module, idx)) = self.worklist.pop() {
+            let mut collector = ReferenceCollector::default();
+            collector.visit_module_item(&self.modules[&module].items[idx]);
+
+            for path in collector.paths {
+                for symbol in resolve_path(self.state.db(), module, &path) {
+                    self.mark(symbol);
+                }
+            }
+        }
+
+        self.report();
+    }
+
+    /// Marks a symbol as used and schedules the analysis of its item.
+    fn mark(&mut self, symbol: Symbol) {
+        if !self.unused.remove(&symbol) {
+            return;
+        }
+
+        let db = self.state.db();
+        self.worklist
+            .push((symbol.module(db), symbol.signature(db).node_idx(db)));
+        self.worklist
+            .extend(self.impls.remove(&symbol).unwrap_or_default());
+    }
+
+    fn report(self) {
+        let db = self.state.db();
+
+        // sort items to emit diagnostics in a deterministic order
+        let unused = self
+            .unused
+            .into_iter()
+            .map(|symbol| (item_kind(symbol), symbol.signature(db).name(db)))
+            .sorted_by_key(|(_, name)| location_key(name.location))
+            .collect::<Vec<_>>();
+
+        for (kind, name) in unused {
+            self.state
+                .diagnostics_mut()
+                .add_diagnostic(UnusedItem::new(kind, name));
+        }
+    }
+}
+
+/// Returns `true` if a given symbol is a private function, struct or type
+/// alias, that may never be used.
+fn can_be_unused(db: &Database, symbol: Symbol) -> bool {
+    if !matches!(
+        symbol,
+        Symbol::Function(_) | Symbol::Struct(_) | Symbol::TupleLikeStruct(_) | Symbol::TypeAlias(_)
+    ) {
+        return false;
+    }
+
+    let signature = symbol.signature(db);
+    if signature.visibility(db) != Visibility::Private {
+        return false;
+    }
+
+    // `main` is called by the runtime
+    let module = symbol.module(db);
+    !(symbol.is_function()
+        && &*signature.name(db).id.as_str() == "main"
+        && module.package().root_module(db) == module)
+}
+
+fn item_kind(symbol: Symbol) -> ModuleItemKind {
+    match symbol {
+        Symbol::Struct(_) => ModuleItemKind::Struct,
+        Symbol::TupleLikeStruct(_) => ModuleItemKind::TupleLikeStruct,
+        Symbol::TypeAlias(_) => ModuleItemKind::TypeAlias,
+        _ => ModuleItemKind::Function,
+    }
+}
+
+fn location_key(location: Location) -> (String, usize) {
+    (location.filepath.to_string(), location.start.0)
+}
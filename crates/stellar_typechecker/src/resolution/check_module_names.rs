@@ -0,0 +1,100 @@
+#[cfg(feature = "debug")]
+use std::time::Instant;
+
+use itertools::Itertools;
+use stellar_database::{ModuleId, PackageId, State};
+use stellar_fx_hash::FxHashMap;
+use stellar_interner::IdentifierId;
+#[cfg(feature = "debug")]
+use tracing::trace;
+
+use crate::diagnostics::{ModuleDefinedMultipleTimes, ModuleNamesDifferOnlyInCase};
+
+/// Checks that module names are unique in their namespace regardless of case.
+///
+/// On case-insensitive filesystems `Main.sr` and `main.sr` (or a module file
+/// and a directory with the same name) alias each other, which makes the
+/// mapping from names to module IDs nondeterministic.
+pub struct CheckModuleNames<'s> {
+    state: &'s mut State,
+}
+
+impl<'s> CheckModuleNames<'s> {
+    pub fn run_all(state: &'s mut State, modules: &FxHashMap<ModuleId, stellar_hir::Module>) {
+        #[cfg(feature = "debug")]
+        let now = Instant::now();
+
+        CheckModuleNames { state }.run(modules.keys().copied());
+
+        #[cfg(feature = "debug")]
+        trace!("check_module_names() <{} us>", now.elapsed().as_micros());
+    }
+
+    fn run(mut self, modules: impl Iterator<Item = ModuleId>) {
+        let mut namespaces: FxHashMap<(PackageId, Vec<IdentifierId>, String), Vec<ModuleId>> =
+            FxHashMap::default();
+
+        if self.state.is_cancelled() {
+            return;
+        }
+
+        for module in modules {
+            let segments = module.path(self.state.db()).segments();
+            let Some((name, parent)) = segments.split_last() else {
+                continue;
+            };
+
+            namespaces
+                .entry((
+                    module.package(),
+                    parent.to_vec(),
+                    name.as_str().to_lowercase(),
+                ))
+                .or_default()
+                .push(module);
+        }
+
+        for modules in namespaces.into_values() {
+            // sort modules to emit diagnostics in a deterministic order
+            let mut modules = modules
+                .into_iter()
+                .sorted_by_key(|module| module.filepath(self.state.db()).to_string());
+
+            let Some(first_module) = modules.next() else {
+                continue;
+            };
+
+            for module in modules {
+                self.check_module_names(first_module, module);
+            }
+        }
+    }
+
+    fn check_module_names(&mut self, first_module: ModuleId, second_module: ModuleId) {
+        let db = self.state.db();
+
+        let first_module_path = first_module.path(db).to_string();
+        let first_filepath = first_module.filepath(db);
+        let second_module_path = second_module.path(db).to_string();
+        let second_filepath = second_module.filepath(db);
+
+        if first_module.name(db) == second_module.name(db) {
+            self.state
+                .diagnostics_mut()
+                .add_diagnostic(ModuleDefinedMultipleTimes::new(
+                    first_module_path,
+                    first_filepath,
+                    second_filepath,
+                ));
+        } else {
+            self.state
+                .diagnostics_mut()
+                .add_diagnostic(ModuleNamesDifferOnlyInCase::new(
+                    first_module_path,
+                    first_filepath,
+                    second_module_path,
+                    second_filepath,
+                ));
+        }
+    }
+}
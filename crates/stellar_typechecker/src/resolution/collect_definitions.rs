@@ -4,14 +4,18 @@ use std::time::Instant;
 use stellar_ast::IdentifierAST;
 use stellar_ast_lowering::LoweredModule;
 use stellar_database::{
-    EnumData, EnumId, EnumItemData, FunctionData, InterfaceData, ModuleId, PackageId,
-    SignatureData, State, StructData, Symbol, TupleLikeStructData, TypeAliasData, TypeAliasId,
+    ConstantData, ConstantId, EnumData, EnumId, EnumItemData, FunctionData, InterfaceData,
+    InterfaceId, ModuleId, PackageId, SignatureData, State, StaticData, StructData, Symbol,
+    TupleLikeStructData, TypeAliasData, TypeAliasId,
 };
 use stellar_fx_hash::FxHashMap;
 #[cfg(feature = "debug")]
 use tracing::trace;
 
-use crate::diagnostics::{EnumItemDefinedMultipleTimes, ItemDefinedMultipleTimes};
+use super::{add_method_to_type, alloc_method};
+use crate::diagnostics::{
+    AssociatedConstantDefinedMultipleTimes, EnumItemDefinedMultipleTimes, ItemDefinedMultipleTimes,
+};
 
 pub struct CollectDefinitions<'s> {
     state: &'s mut State,
@@ -35,12 +39,27 @@ impl<'s> CollectDefinitions<'s> {
         #[cfg(feature = "debug")]
         let now = Instant::now();
 
-        for item in &module.items {
+        for (node_idx, item) in module.items.iter().enumerate() {
+            if self.state.is_cancelled() {
+                return;
+            }
+
+            self.current_node_idx = node_idx;
+
             match item {
+                stellar_hir::ModuleItem::Constant(constant) => {
+                    self.collect_definition_of_constant(constant)
+                }
                 stellar_hir::ModuleItem::Enum(enum_) => self.collect_definition_of_enum(enum_),
                 stellar_hir::ModuleItem::Function(function) => {
                     self.collect_definition_of_function(function)
                 }
+                stellar_hir::ModuleItem::Extern(block) => {
+                    self.collect_definitions_of_foreign_functions(block)
+                }
+                stellar_hir::ModuleItem::Static(static_) => {
+                    self.collect_definition_of_static(static_)
+                }
                 stellar_hir::ModuleItem::Struct(struct_) => {
                     self.collect_definition_of_struct(struct_)
                 }
@@ -71,6 +90,7 @@ impl<'s> CollectDefinitions<'s> {
 
         let signature = SignatureData::alloc(
             self.state.db_mut(),
+            enum_hir.attributes.clone(),
             enum_hir.visibility,
             enum_hir.name,
             self.current_node_idx,
@@ -78,6 +98,8 @@ impl<'s> CollectDefinitions<'s> {
         );
         let mut enum_ = EnumData::alloc(self.state.db_mut(), signature);
 
+        self.collect_definitions_of_methods(Symbol::Enum(enum_), &enum_hir.methods);
+
         for item in &enum_hir.items {
             let name = item.name();
 
@@ -117,6 +139,7 @@ impl<'s> CollectDefinitions<'s> {
     fn collect_definition_of_function(&mut self, function: &stellar_hir::Function) {
         let signature = SignatureData::alloc(
             self.state.db_mut(),
+            function.signature.attributes.clone(),
             function.signature.visibility,
             function.signature.name,
             self.current_node_idx,
@@ -134,12 +157,36 @@ impl<'s> CollectDefinitions<'s> {
         );
     }
 
+    fn collect_definitions_of_foreign_functions(&mut self, block: &stellar_hir::ExternBlock) {
+        for function in &block.functions {
+            let signature = SignatureData::alloc(
+                self.state.db_mut(),
+                function.attributes.clone(),
+                function.visibility,
+                function.name,
+                self.current_node_idx,
+                self.module,
+            );
+
+            let id = FunctionData::alloc_foreign(self.state.db_mut(), signature, block.abi.clone());
+
+            self.check_for_duplicate_definition(function.name);
+
+            self.module.add_module_item(
+                self.state.db_mut(),
+                function.name.id,
+                Symbol::Function(id),
+            );
+        }
+    }
+
     fn collect_definition_of_struct(&mut self, struct_: &stellar_hir::Struct) {
         #[cfg(feature = "debug")]
         let now = Instant::now();
 
         let signature = SignatureData::alloc(
             self.state.db_mut(),
+            struct_.attributes.clone(),
             struct_.visibility,
             struct_.name,
             self.current_node_idx,
@@ -148,6 +195,8 @@ impl<'s> CollectDefinitions<'s> {
 
         let id = StructData::alloc(self.state.db_mut(), signature);
 
+        self.collect_definitions_of_methods(Symbol::Struct(id), &struct_.methods);
+
         self.check_for_duplicate_definition(struct_.name);
 
         self.module
@@ -168,6 +217,7 @@ impl<'s> CollectDefinitions<'s> {
 
         let signature = SignatureData::alloc(
             self.state.db_mut(),
+            struct_.attributes.clone(),
             struct_.visibility,
             struct_.name,
             self.current_node_idx,
@@ -176,6 +226,8 @@ impl<'s> CollectDefinitions<'s> {
 
         let id = TupleLikeStructData::alloc(self.state.db_mut(), signature);
 
+        self.collect_definitions_of_methods(Symbol::TupleLikeStruct(id), &struct_.methods);
+
         self.check_for_duplicate_definition(struct_.name);
 
         self.module.add_module_item(
@@ -199,6 +251,7 @@ impl<'s> CollectDefinitions<'s> {
 
         let signature = SignatureData::alloc(
             self.state.db_mut(),
+            interface.attributes.clone(),
             interface.visibility,
             interface.name,
             self.current_node_idx,
@@ -207,6 +260,15 @@ impl<'s> CollectDefinitions<'s> {
 
         let id = InterfaceData::alloc(self.state.db_mut(), signature);
 
+        for constant in &interface.constants {
+            self.check_for_duplicate_associated_constant(id, constant.name);
+
+            let constant_id = self.alloc_constant(constant);
+            id.add_constant(self.state.db_mut(), constant.name.id, constant_id);
+        }
+
+        self.collect_definitions_of_methods(Symbol::Interface(id), &interface.methods);
+
         self.check_for_duplicate_definition(interface.name);
 
         self.module.add_module_item(
@@ -230,6 +292,7 @@ impl<'s> CollectDefinitions<'s> {
 
         let signature = SignatureData::alloc(
             self.state.db_mut(),
+            alias.attributes.clone(),
             alias.visibility,
             alias.name,
             self.current_node_idx,
@@ -252,6 +315,76 @@ impl<'s> CollectDefinitions<'s> {
         );
     }
 
+    fn collect_definition_of_constant(&mut self, constant: &stellar_hir::Constant) {
+        #[cfg(feature = "debug")]
+        let now = Instant::now();
+
+        let id = self.alloc_constant(constant);
+
+        self.check_for_duplicate_definition(constant.name);
+
+        self.module
+            .add_module_item(self.state.db_mut(), constant.name.id, Symbol::Constant(id));
+
+        #[cfg(feature = "debug")]
+        trace!(
+            "collect_definition_of_constant(name = '{}', module = '{}') <{} us>",
+            constant.name.id,
+            self.module.filepath(self.state.db()),
+            now.elapsed().as_micros()
+        );
+    }
+
+    fn alloc_constant(&mut self, constant: &stellar_hir::Constant) -> ConstantId {
+        let signature = SignatureData::alloc(
+            self.state.db_mut(),
+            constant.attributes.clone(),
+            constant.visibility,
+            constant.name,
+            self.current_node_idx,
+            self.module,
+        );
+
+        ConstantData::alloc(self.state.db_mut(), signature, constant.value.is_some())
+    }
+
+    fn collect_definition_of_static(&mut self, static_: &stellar_hir::Static) {
+        #[cfg(feature = "debug")]
+        let now = Instant::now();
+
+        let signature = SignatureData::alloc(
+            self.state.db_mut(),
+            static_.attributes.clone(),
+            static_.visibility,
+            static_.name,
+            self.current_node_idx,
+            self.module,
+        );
+
+        let id = StaticData::alloc(self.state.db_mut(), signature, static_.is_mutable);
+
+        self.check_for_duplicate_definition(static_.name);
+
+        self.module
+            .add_module_item(self.state.db_mut(), static_.name.id, Symbol::Static(id));
+
+        #[cfg(feature = "debug")]
+        trace!(
+            "collect_definition_of_static(name = '{}', module = '{}') <{} us>",
+            static_.name.id,
+            self.module.filepath(self.state.db()),
+            now.elapsed().as_micros()
+        );
+    }
+
+    fn collect_definitions_of_methods(&mut self, ty: Symbol, methods: &[stellar_hir::Function]) {
+        for method in methods {
+            let function = alloc_method(self.state, self.module, self.current_node_idx, method);
+
+            add_method_to_type(self.state, ty, function);
+        }
+    }
+
     fn check_for_duplicate_definition(&mut self, name: IdentifierAST) {
         if let Some(symbol) = self
             .module
@@ -267,6 +400,29 @@ impl<'s> CollectDefinitions<'s> {
         }
     }
 
+    fn check_for_duplicate_associated_constant(
+        &mut self,
+        interface: InterfaceId,
+        constant_name: IdentifierAST,
+    ) {
+        if let Some(constant) = interface.constant(self.state.db(), constant_name.id) {
+            let diagnostic = AssociatedConstantDefinedMultipleTimes::new(
+                interface
+                    .signature(self.state.db())
+                    .name(self.state.db())
+                    .id,
+                constant_name.id,
+                constant
+                    .signature(self.state.db())
+                    .name(self.state.db())
+                    .location,
+                constant_name.location,
+            );
+
+            self.state.diagnostics_mut().add_diagnostic(diagnostic);
+        }
+    }
+
     fn check_for_duplicate_enum_item(&mut self, enum_: EnumId, item_name: IdentifierAST) {
         if let Some(enum_item) = enum_.item(self.state.db(), item_name.id) {
             let diagnostic = EnumItemDefinedMultipleTimes::new(
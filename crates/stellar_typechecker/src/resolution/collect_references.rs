@@ -0,0 +1,339 @@
+#[cfg(feature = "debug")]
+use std::time::Instant;
+
+use itertools::Itertools;
+use stellar_ast::IdentifierAST;
+use stellar_database::{Database, ModuleId, State, Symbol};
+use stellar_filesystem::location::Location;
+use stellar_fx_hash::{FxHashMap, FxHashSet};
+use stellar_hir::{
+    visit::{walk_module_item, walk_type_constructor, Visitor},
+    BindingMode, Expression, Function, GenericParameter, LambdaCapture, LambdaFunctionParameter,
+    MatchExpressionItem, ModuleItem, Path, Pattern, Statement, StructFieldPattern, Type,
+    TypeConstructor,
+};
+use stellar_interner::IdentifierId;
+#[cfg(feature = "debug")]
+use tracing::trace;
+
+/// Records locations, where module items, enum items and modules are
+/// referenced in bodies and signatures of items, see
+/// [`Symbol::references()`].
+///
+/// Must be run after imports and implementation blocks are resolved.
+/// Local variables, parameters and generic parameters shadow module items,
+/// references, that can't be resolved (e.g. methods), are skipped: they are
+/// reported by other passes.
+pub struct CollectReferences;
+
+impl CollectReferences {
+    pub fn run_all(state: &mut State, modules: &FxHashMap<ModuleId, stellar_hir::Module>) {
+        #[cfg(feature = "debug")]
+        let now = Instant::now();
+
+        // modules are sorted to record references in a deterministic order
+        for (&module, hir) in modules
+            .iter()
+            .sorted_by_key(|(_, hir)| hir.filepath.as_path())
+        {
+            for item in &hir.items {
+                if state.is_cancelled() {
+                    return;
+                }
+
+                let mut collector = ReferenceCollector::default();
+                collector.visit_module_item(item);
+
+                for path in collector.paths {
+                    let symbols = resolve_path(state.db(), module, &path);
+
+                    for (symbol, segment) in symbols.into_iter().zip(path) {
+                        state.db_mut().add_reference(symbol, segment.location);
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "debug")]
+        trace!("collect_references() <{} us>", now.elapsed().as_micros());
+    }
+}
+
+/// Resolves a path in a given module and returns all symbols it goes
+/// through, e.g. the module `a` and the function `f` for `a.f`, or the enum
+/// `Color` and its item `Red` for `Color.Red`.
+///
+/// Unlike the name resolution, it doesn't report anything: paths, that can't
+/// be resolved (e.g. methods), are simply skipped.
+pub(crate) fn resolve_path(db: &Database, module: ModuleId, path: &[IdentifierAST]) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+
+    let Some((first, rest)) = path.split_first() else {
+        return symbols;
+    };
+
+    let Some(mut symbol) = module
+        .symbol_or_none(db, first.id)
+        .or(module.resolved_import_or_none(db, first.id))
+    else {
+        return symbols;
+    };
+    symbols.push(symbol);
+
+    for segment in rest {
+        let next = match symbol {
+            Symbol::Module(module) => module.symbol_or_none(db, segment.id).or(module
+                .reexport_or_none(db, segment.id)
+                .map(|reexport| reexport.symbol)),
+            Symbol::Enum(enum_) => enum_.item(db, segment.id).map(Symbol::EnumItem),
+            _ => None,
+        };
+
+        let Some(next) = next else {
+            break;
+        };
+
+        symbol = next;
+        symbols.push(symbol);
+    }
+
+    symbols
+}
+
+/// Names, that are bound in a scope and shadow module items.
+#[derive(Default)]
+struct Scope {
+    /// Local variables and parameters.
+    variables: FxHashSet<IdentifierId>,
+
+    /// Generic parameters.
+    generic_parameters: FxHashSet<IdentifierId>,
+}
+
+/// Collects paths, that may refer to module items.
+///
+/// Paths starting with local variables, parameters or generic parameters,
+/// e.g. `helper` in `fun f(helper: int32) { helper }`, are skipped.
+#[derive(Default)]
+pub(crate) struct ReferenceCollector {
+    pub(crate) paths: Vec<Vec<IdentifierAST>>,
+
+    /// Scopes enclosing the visited node, the innermost one is the last.
+    scopes: Vec<Scope>,
+}
+
+impl ReferenceCollector {
+    /// Visits nodes inside of a new scope, names bound in it are forgotten
+    /// afterwards.
+    fn in_scope(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.scopes.push(Scope::default());
+        visit(self);
+        self.scopes.pop();
+    }
+
+    fn bind_variable(&mut self, name: IdentifierId) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.variables.insert(name);
+        }
+    }
+
+    fn bind_generic_parameter(&mut self, name: IdentifierId) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.generic_parameters.insert(name);
+        }
+    }
+
+    fn is_variable(&self, name: IdentifierId) -> bool {
+        self.scopes.iter().any(|scope| {
+            scope.variables.contains(&name) || scope.generic_parameters.contains(&name)
+        })
+    }
+
+    fn is_generic_parameter(&self, name: IdentifierId) -> bool {
+        self.scopes
+            .iter()
+            .any(|scope| scope.generic_parameters.contains(&name))
+    }
+
+    /// Records a path used as a value, e.g. in `a.f()`.
+    fn add_value_path(&mut self, path: Vec<IdentifierAST>) {
+        if !self.is_variable(path[0].id) {
+            self.paths.push(path);
+        }
+    }
+
+    /// Records a path used as a type or in a pattern, e.g. in `List[T]`.
+    fn add_type_path(&mut self, path: &Path) {
+        if !self.is_generic_parameter(path.identifiers[0].id) {
+            self.paths.push(path.identifiers.clone());
+        }
+    }
+}
+
+impl Visitor for ReferenceCollector {
+    fn visit_module_item(&mut self, module_item: &ModuleItem) {
+        self.in_scope(|this| walk_module_item(this, module_item));
+    }
+
+    fn visit_generic_parameter(&mut self, generic_parameter: &GenericParameter) {
+        self.bind_generic_parameter(generic_parameter.name.id);
+
+        if let Some(const_type) = &generic_parameter.const_type {
+            self.visit_type(const_type);
+        }
+
+        if let Some(default_value) = &generic_parameter.default_value {
+            self.visit_type(default_value);
+        }
+
+        if let Some(bounds) = &generic_parameter.bounds {
+            self.visit_bounds(bounds);
+        }
+    }
+
+    fn visit_function(&mut self, function: &Function) {
+        self.in_scope(|this| {
+            this.visit_function_signature(&function.signature);
+
+            if let Some(body) = &function.body {
+                this.visit_statements_block(body);
+            }
+        });
+    }
+
+    fn visit_statements_block(&mut self, statements: &[Statement]) {
+        self.in_scope(|this| {
+            for statement in statements {
+                this.visit_statement(statement);
+            }
+        });
+    }
+
+    fn visit_let_statement(&mut self, pattern: &Pattern, value: &Expression, ty: Option<&Type>) {
+        // the variable is not yet bound in its value, e.g. in `let a = a + 1;`
+        self.visit_expression(value);
+
+        if let Some(ty) = ty {
+            self.visit_type(ty);
+        }
+
+        self.visit_pattern(pattern);
+    }
+
+    fn visit_identifier_pattern(
+        &mut self,
+        _location: Location,
+        _binding_mode: BindingMode,
+        identifier: IdentifierAST,
+        pattern: Option<&Pattern>,
+    ) {
+        self.bind_variable(identifier.id);
+
+        if let Some(pattern) = pattern {
+            self.visit_pattern(pattern);
+        }
+    }
+
+    fn visit_match_expression_item(&mut self, item: &MatchExpressionItem) {
+        self.in_scope(|this| {
+            this.visit_pattern(&item.left);
+            this.visit_expression(&item.right);
+        });
+    }
+
+    fn visit_lambda_expression(
+        &mut self,
+        _location: Location,
+        _captures: &[LambdaCapture],
+        parameters: &[LambdaFunctionParameter],
+        return_type: Option<&Type>,
+        value: &Expression,
+    ) {
+        self.in_scope(|this| {
+            for parameter in parameters {
+                this.visit_lambda_function_parameter(parameter);
+            }
+
+            if let Some(return_type) = return_type {
+                this.visit_type(return_type);
+            }
+
+            this.visit_expression(value);
+        });
+    }
+
+    fn visit_lambda_function_parameter(&mut self, parameter: &LambdaFunctionParameter) {
+        if let Some(ty) = &parameter.ty {
+            self.visit_type(ty);
+        }
+
+        self.bind_variable(parameter.name.id);
+    }
+
+    fn visit_type_constructor(&mut self, constructor: &TypeConstructor) {
+        self.add_type_path(&constructor.path);
+
+        walk_type_constructor(self, constructor);
+    }
+
+    fn visit_identifier_expression(&mut self, identifier: IdentifierAST) {
+        self.add_value_path(vec![identifier]);
+    }
+
+    fn visit_field_access_expression(
+        &mut self,
+        _location: Location,
+        left: &Expression,
+        right: IdentifierAST,
+    ) {
+        if let Some(mut path) = identifier_chain(left) {
+            path.push(right);
+            self.add_value_path(path);
+        } else {
+            self.visit_expression(left);
+        }
+    }
+
+    fn visit_path_pattern(&mut self, path: &Path) {
+        self.add_type_path(path);
+    }
+
+    fn visit_struct_pattern(
+        &mut self,
+        _location: Location,
+        path: &Path,
+        field_patterns: &[StructFieldPattern],
+    ) {
+        self.add_type_path(path);
+
+        for field_pattern in field_patterns {
+            self.visit_struct_field_pattern(field_pattern);
+        }
+    }
+
+    fn visit_tuple_like_pattern(
+        &mut self,
+        _location: Location,
+        path: &Path,
+        inner_patterns: &[Pattern],
+    ) {
+        self.add_type_path(path);
+
+        for pattern in inner_patterns {
+            self.visit_pattern(pattern);
+        }
+    }
+}
+
+/// Returns identifiers of a chain of field accesses, e.g. `a.b.c`.
+fn identifier_chain(expression: &Expression) -> Option<Vec<IdentifierAST>> {
+    match expression {
+        Expression::Identifier { identifier, .. } => Some(vec![*identifier]),
+        Expression::FieldAccess { left, right, .. } => {
+            let mut chain = identifier_chain(left)?;
+            chain.push(*right);
+            Some(chain)
+        }
+        _ => None,
+    }
+}
@@ -1,15 +1,26 @@
+pub mod check_dead_items;
+pub mod check_module_names;
 pub mod collect_definitions;
+pub mod collect_references;
+pub mod resolve_impls;
 pub mod resolve_imports;
 
 use std::iter;
 
 use itertools::Itertools;
-use stellar_ast::IdentifierAST;
-use stellar_database::{EnumId, ModuleId, PackageId, State, Symbol, TypeAliasId};
+use stellar_ast::{IdentifierAST, Visibility};
+use stellar_database::{
+    Database, EnumId, FunctionData, FunctionId, ModuleId, PackageId, SignatureData, State, Symbol,
+    TypeAliasId,
+};
+
+use stellar_interner::{builtin_identifiers::SMALL_SELF, IdentifierId};
 
 use crate::diagnostics::{
-    EnumItemsDoNotServeAsNamespaces, FailedToResolveEnumItem, FailedToResolveNameInModule,
-    FailedToResolvePackage, ModuleItemsExceptEnumsDoNotServeAsNamespaces,
+    AmbiguousPackageReference, EnumItemsDoNotServeAsNamespaces, FailedToResolveEnumItem,
+    FailedToResolveNameInModule, FailedToResolvePackage, FailedToResolvePrivateModuleItem,
+    FailedToResolveRestrictedModuleItem, MethodDefinedMultipleTimes,
+    ModuleItemsExceptEnumsDoNotServeAsNamespaces,
 };
 
 pub(crate) fn resolve_global_path_in_module_context(
@@ -20,7 +31,10 @@ pub(crate) fn resolve_global_path_in_module_context(
     let mut identifiers = path.identifiers.iter();
     let namespace = identifiers.next()?;
 
-    let Some(namespace_symbol) = module.symbol_or_none(state.db(), namespace.id) else {
+    let Some(namespace_symbol) = module
+        .symbol_or_none(state.db(), namespace.id)
+        .or(module.resolved_import_or_none(state.db(), namespace.id))
+    else {
         state
             .diagnostics_mut()
             .add_diagnostic(FailedToResolvePackage::new(
@@ -31,39 +45,72 @@ pub(crate) fn resolve_global_path_in_module_context(
         return None;
     };
 
-    resolve_global_path_by_first_symbol(state, namespace_symbol, namespace, identifiers)
+    resolve_global_path_by_first_symbol(state, module, namespace_symbol, namespace, identifiers)
 }
 
+/// Resolves an import path in a given module.
 pub(crate) fn resolve_global_path(
     state: &mut State,
-    package: PackageId,
-    path: &stellar_ast::ImportPath,
+    module: ModuleId,
+    path: &stellar_ast::Path,
 ) -> Option<Symbol> {
-    let mut identifiers = path.path.identifiers.iter();
+    let mut identifiers = path.identifiers.iter();
     let namespace = identifiers.next()?;
 
-    let Some(package) = (if namespace.id == package.name(state.db()) {
-        Some(package)
-    } else {
-        package.dependencies(state.db()).get(&namespace.id).copied()
-    }) else {
-        state
-            .diagnostics_mut()
-            .add_diagnostic(FailedToResolvePackage::new(
-                namespace.location,
-                namespace.id,
-            ));
+    let package = resolve_package(state, module.package(), *namespace)?;
+    let root_module = package.root_module(state.db());
 
-        return None;
-    };
+    resolve_global_path_by_first_symbol(
+        state,
+        module,
+        Symbol::Module(root_module),
+        namespace,
+        identifiers,
+    )
+}
 
-    let root_module = package.root_module(state.db());
+/// Resolves the first segment of an import path into a package.
+///
+/// `self` always refers to the current package. The current package's name
+/// can be used as well, unless one of its dependencies has the same name, in
+/// which case the reference is ambiguous and must be written using `self`.
+fn resolve_package(
+    state: &mut State,
+    package: PackageId,
+    name: IdentifierAST,
+) -> Option<PackageId> {
+    if name.id == SMALL_SELF {
+        return Some(package);
+    }
 
-    resolve_global_path_by_first_symbol(state, Symbol::Module(root_module), namespace, identifiers)
+    let dependency = package.dependencies(state.db()).get(&name.id).copied();
+
+    if name.id == package.name(state.db()) {
+        if dependency.is_some() {
+            state
+                .diagnostics_mut()
+                .add_diagnostic(AmbiguousPackageReference::new(name));
+
+            return None;
+        }
+
+        return Some(package);
+    }
+
+    if dependency.is_none() {
+        state
+            .diagnostics_mut()
+            .add_diagnostic(FailedToResolvePackage::new(name.location, name.id));
+    }
+
+    dependency
 }
 
+/// Resolves the rest of a path in a given module (`context`), starting from
+/// the symbol, that the first segment refers to.
 fn resolve_global_path_by_first_symbol<'a>(
     state: &mut State,
+    context: ModuleId,
     symbol: Symbol,
     namespace: &'a IdentifierAST,
     identifiers: impl Iterator<Item = &'a IdentifierAST>,
@@ -72,19 +119,20 @@ fn resolve_global_path_by_first_symbol<'a>(
         .chain(identifiers)
         .tuple_windows()
         .try_fold(symbol, |symbol, (namespace, member)| {
-            resolve_global_path_segment(state, symbol, *namespace, *member)
+            resolve_global_path_segment(state, context, symbol, *namespace, *member)
         })
 }
 
 fn resolve_global_path_segment(
     state: &mut State,
+    context: ModuleId,
     symbol: Symbol,
     namespace: IdentifierAST,
     member: IdentifierAST,
 ) -> Option<Symbol> {
     match symbol {
         Symbol::Module(module) => {
-            resolve_symbol_in_module_namespace(state, module, namespace, member)
+            resolve_symbol_in_module_namespace(state, context, module, namespace, member)
         }
         Symbol::Enum(enum_) => resolve_symbol_in_enum_namespace(state, enum_, namespace, member),
         Symbol::EnumItem(_) => {
@@ -110,6 +158,7 @@ fn resolve_global_path_segment(
 
 fn resolve_symbol_in_module_namespace(
     state: &mut State,
+    context: ModuleId,
     module: ModuleId,
     namespace: IdentifierAST,
     member: IdentifierAST,
@@ -119,7 +168,30 @@ fn resolve_symbol_in_module_namespace(
         .map(Symbol::Module)
         .or(module.module_item_symbol_or_none(state.db(), member.id))
     {
+        if let Some((visibility, definition_module)) = visibility_of(state.db(), symbol) {
+            check_visibility(
+                state,
+                context,
+                visibility,
+                definition_module,
+                namespace,
+                member,
+            )?;
+        }
+
         Some(symbol)
+    } else if let Some(reexport) = module.reexport_or_none(state.db(), member.id) {
+        // re-exports are restricted relative to the re-exporting module
+        check_visibility(
+            state,
+            context,
+            reexport.visibility,
+            module,
+            namespace,
+            member,
+        )?;
+
+        Some(reexport.symbol)
     } else {
         state
             .diagnostics_mut()
@@ -134,6 +206,111 @@ fn resolve_symbol_in_module_namespace(
     }
 }
 
+/// Checks if a name with a given visibility defined in a given module, that a
+/// path segment (`member`) refers to, can be used in another module
+/// (`context`).
+fn check_visibility(
+    state: &mut State,
+    context: ModuleId,
+    visibility: Visibility,
+    module: ModuleId,
+    namespace: IdentifierAST,
+    member: IdentifierAST,
+) -> Option<()> {
+    if is_visibility_in_scope(state.db(), visibility, module, context) {
+        return Some(());
+    }
+
+    let scope = match visibility {
+        Visibility::Package(_) => format!("package `{}`", module.package().name(state.db())),
+        Visibility::Super(_) => format!(
+            "module `{}`",
+            visibility_scope(state.db(), module, visibility)
+                .iter()
+                .join(".")
+        ),
+        _ => {
+            state
+                .diagnostics_mut()
+                .add_diagnostic(FailedToResolvePrivateModuleItem::new(
+                    namespace.id,
+                    namespace.location,
+                    member.id,
+                    member.location,
+                ));
+
+            return None;
+        }
+    };
+
+    state
+        .diagnostics_mut()
+        .add_diagnostic(FailedToResolveRestrictedModuleItem::new(
+            member.id,
+            member.location,
+            visibility.location_or_none().unwrap(),
+            scope,
+        ));
+
+    None
+}
+
+/// Returns the visibility of a module item (or an enum item) together with the
+/// module, in which it is defined, or `None` if the symbol is visible
+/// everywhere.
+pub(crate) fn visibility_of(db: &Database, symbol: Symbol) -> Option<(Visibility, ModuleId)> {
+    match symbol {
+        // modules don't have visibility qualifiers
+        Symbol::Module(_) | Symbol::BuiltinSymbol(_) => None,
+        Symbol::EnumItem(item) => visibility_of(db, Symbol::Enum(item.enum_(db))),
+        _ => Some((symbol.signature(db).visibility(db), symbol.module(db))),
+    }
+}
+
+/// Returns `true` if a module item (or an enum item) can be used in a given
+/// module.
+fn is_visible_in(db: &Database, symbol: Symbol, module: ModuleId) -> bool {
+    visibility_of(db, symbol).map_or(true, |(visibility, definition_module)| {
+        is_visibility_in_scope(db, visibility, definition_module, module)
+    })
+}
+
+/// Returns `true` if a name with a given visibility defined in a given module
+/// (`definition_module`) can be used in another module.
+fn is_visibility_in_scope(
+    db: &Database,
+    visibility: Visibility,
+    definition_module: ModuleId,
+    module: ModuleId,
+) -> bool {
+    match visibility {
+        Visibility::Public(_) => true,
+        Visibility::Package(_) => module.package() == definition_module.package(),
+        Visibility::Private | Visibility::Super(_) => {
+            module.package() == definition_module.package()
+                && module.path(db).segments().starts_with(visibility_scope(
+                    db,
+                    definition_module,
+                    visibility,
+                ))
+        }
+    }
+}
+
+/// Returns the path of the module, in which (including its submodules) an
+/// item with a private or `pub(super)` visibility defined in a given module is
+/// visible.
+fn visibility_scope(db: &Database, module: ModuleId, visibility: Visibility) -> &[IdentifierId] {
+    let segments = module.path(db).segments();
+
+    match visibility {
+        // `pub(super)` in the root module is the same as private visibility,
+        // because the root module doesn't have a parent.
+        Visibility::Super(_) => &segments[..(segments.len() - 1).max(1)],
+        _ => segments,
+    }
+}
+
 fn resolve_symbol_in_enum_namespace(
     state: &mut State,
     enum_: EnumId,
@@ -150,3 +327,60 @@ fn resolve_symbol_in_enum_namespace(
         None
     }
 }
+
+/// Creates a function object for a method defined in a given module.
+pub(crate) fn alloc_method(
+    state: &mut State,
+    module: ModuleId,
+    node_idx: usize,
+    method: &stellar_hir::Function,
+) -> FunctionId {
+    let signature = SignatureData::alloc(
+        state.db_mut(),
+        method.signature.attributes.clone(),
+        method.signature.visibility,
+        method.signature.name,
+        node_idx,
+        module,
+    );
+
+    FunctionData::alloc(state.db_mut(), signature)
+}
+
+/// Adds a method to a struct, an enum or an interface, unless a method with the same name is
+/// already defined for it.
+pub(crate) fn add_method_to_type(state: &mut State, ty: Symbol, method: FunctionId) {
+    let name = method.signature(state.db()).name(state.db());
+
+    let first_definition = match ty {
+        Symbol::Enum(enum_) => enum_.method(state.db(), name.id),
+        Symbol::Struct(struct_) => struct_.method(state.db(), name.id),
+        Symbol::TupleLikeStruct(struct_) => struct_.method(state.db(), name.id),
+        Symbol::Interface(interface) => interface.method(state.db(), name.id),
+        _ => unreachable!(),
+    };
+
+    if let Some(first_definition) = first_definition {
+        let diagnostic = MethodDefinedMultipleTimes::new(
+            ty.name(state.db()).id,
+            name.id,
+            first_definition
+                .signature(state.db())
+                .name(state.db())
+                .location,
+            name.location,
+        );
+
+        state.diagnostics_mut().add_diagnostic(diagnostic);
+
+        return;
+    }
+
+    match ty {
+        Symbol::Enum(enum_) => enum_.add_method(state.db_mut(), name.id, method),
+        Symbol::Struct(struct_) => struct_.add_method(state.db_mut(), name.id, method),
+        Symbol::TupleLikeStruct(struct_) => struct_.add_method(state.db_mut(), name.id, method),
+        Symbol::Interface(interface) => interface.add_method(state.db_mut(), name.id, method),
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,177 @@
+#[cfg(feature = "debug")]
+use std::time::Instant;
+
+use stellar_database::{ImplData, InterfaceId, ModuleId, State, Symbol};
+use stellar_fx_hash::FxHashMap;
+#[cfg(feature = "debug")]
+use tracing::trace;
+
+use super::{add_method_to_type, alloc_method, resolve_global_path_in_module_context};
+use crate::diagnostics::{
+    ExpectedInterfaceInImpl, ExpectedTypeInImpl, InherentImplForForeignType,
+    MethodDefinedMultipleTimes,
+};
+
+/// Resolves types and interfaces of implementation blocks and attaches their
+/// methods to the corresponding types.
+///
+/// Must be run after imports are resolved, because types in implementation blocks
+/// can be imported from other modules.
+pub struct ResolveImpls<'s> {
+    state: &'s mut State,
+    module: ModuleId,
+}
+
+impl<'s> ResolveImpls<'s> {
+    pub fn run_all(state: &'s mut State, modules: &FxHashMap<ModuleId, stellar_hir::Module>) {
+        for module in modules {
+            ResolveImpls {
+                state,
+                module: *module.0,
+            }
+            .run(module.1);
+        }
+    }
+
+    fn run(mut self, module: &stellar_hir::Module) {
+        for (node_idx, item) in module.items.iter().enumerate() {
+            if self.state.is_cancelled() {
+                return;
+            }
+
+            if let stellar_hir::ModuleItem::Impl(impl_) = item {
+                self.resolve_impl(node_idx, impl_);
+            }
+        }
+    }
+
+    fn resolve_impl(&mut self, node_idx: usize, impl_: &stellar_hir::Impl) {
+        #[cfg(feature = "debug")]
+        let now = Instant::now();
+
+        let Some(ty) = self.resolve_type(&impl_.ty) else {
+            return;
+        };
+
+        let interface = if let Some(interface) = &impl_.interface {
+            let Some(interface) = self.resolve_interface(interface) else {
+                return;
+            };
+
+            Some(interface)
+        } else {
+            None
+        };
+
+        if interface.is_none() && ty.module(self.state.db()).package() != self.module.package() {
+            let diagnostic =
+                InherentImplForForeignType::new(impl_.ty.location(), ty.name(self.state.db()).id);
+
+            self.state.diagnostics_mut().add_diagnostic(diagnostic);
+
+            return;
+        }
+
+        let id = ImplData::alloc(self.state.db_mut(), self.module, node_idx, ty, interface);
+        self.module.add_impl(self.state.db_mut(), id);
+
+        for method in &impl_.methods {
+            let function = alloc_method(self.state, self.module, node_idx, method);
+            let name = method.signature.name;
+
+            if interface.is_none() {
+                add_method_to_type(self.state, ty, function);
+            } else if let Some(first_definition) = id.method(self.state.db(), name.id) {
+                // methods of interface implementations are only accessible through
+                // the interface, so they cannot conflict with inherent methods
+                let diagnostic = MethodDefinedMultipleTimes::new(
+                    ty.name(self.state.db()).id,
+                    name.id,
+                    first_definition
+                        .signature(self.state.db())
+                        .name(self.state.db())
+                        .location,
+                    name.location,
+                );
+
+                self.state.diagnostics_mut().add_diagnostic(diagnostic);
+
+                continue;
+            }
+
+            id.add_method(self.state.db_mut(), name.id, function);
+        }
+
+        #[cfg(feature = "debug")]
+        trace!(
+            "resolve_impl(type = '{}', module = '{}') <{} us>",
+            ty.name(self.state.db()).id,
+            self.module.filepath(self.state.db()),
+            now.elapsed().as_micros()
+        );
+    }
+
+    fn resolve_type(&mut self, ty: &stellar_hir::Type) -> Option<Symbol> {
+        let found = match ty {
+            stellar_hir::Type::Constructor(constructor) => {
+                let symbol = resolve_global_path_in_module_context(
+                    self.state,
+                    &constructor.path,
+                    self.module,
+                )?;
+
+                if matches!(
+                    symbol,
+                    Symbol::Enum(_) | Symbol::Struct(_) | Symbol::TupleLikeStruct(_)
+                ) {
+                    return Some(symbol);
+                }
+
+                self.describe(symbol)
+            }
+            stellar_hir::Type::Tuple { .. } => "tuple type".to_owned(),
+            stellar_hir::Type::Function { .. } => "function type".to_owned(),
+            stellar_hir::Type::Underscore { .. } => "underscore type".to_owned(),
+            stellar_hir::Type::InterfaceObject { .. } => "interface object type".to_owned(),
+            stellar_hir::Type::Const { .. } => "const argument".to_owned(),
+            stellar_hir::Type::Array { .. } => "array type".to_owned(),
+            stellar_hir::Type::Reference { .. } => "reference type".to_owned(),
+        };
+
+        self.state
+            .diagnostics_mut()
+            .add_diagnostic(ExpectedTypeInImpl::new(ty.location(), found));
+
+        None
+    }
+
+    fn resolve_interface(
+        &mut self,
+        interface: &stellar_hir::TypeConstructor,
+    ) -> Option<InterfaceId> {
+        let symbol =
+            resolve_global_path_in_module_context(self.state, &interface.path, self.module)?;
+
+        if let Symbol::Interface(interface) = symbol {
+            return Some(interface);
+        }
+
+        let found = self.describe(symbol);
+
+        self.state
+            .diagnostics_mut()
+            .add_diagnostic(ExpectedInterfaceInImpl::new(interface.location, found));
+
+        None
+    }
+
+    fn describe(&self, symbol: Symbol) -> String {
+        let kind = match symbol {
+            Symbol::Module(_) => "module".to_owned(),
+            Symbol::EnumItem(_) => "enum item".to_owned(),
+            _ => symbol.module_item_kind().to_string(),
+        };
+
+        format!("{kind} `{}`", symbol.name(self.state.db()).id)
+    }
+}
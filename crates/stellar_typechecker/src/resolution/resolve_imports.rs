@@ -1,46 +1,179 @@
 #[cfg(feature = "debug")]
 use std::time::Instant;
 
+use std::{collections::hash_map::Entry, mem};
+
+use stellar_ast::{IdentifierAST, ImportPath, Path, Visibility};
 use stellar_ast_lowering::LoweredModule;
-use stellar_database::{ModuleId, State};
+use stellar_database::{ModuleId, Reexport, State, Symbol};
 use stellar_filesystem::location::Location;
 use stellar_fx_hash::FxHashMap;
+use stellar_interner::IdentifierId;
 #[cfg(feature = "debug")]
 use tracing::trace;
 
-use super::resolve_global_path;
-use crate::diagnostics::PackageImport;
+use super::{is_visibility_in_scope, is_visible_in, resolve_global_path, visibility_of};
+use crate::diagnostics::{
+    AmbiguousGlobImport, GlobImportFromNonNamespace, ImportShadowsModuleItem,
+    NameImportedMultipleTimes, PackageImport, ReexportMoreVisibleThanItem,
+};
 
 pub struct ResolveImports<'s> {
     state: &'s mut State,
     module: ModuleId,
+
+    /// Locations of names, that have already been imported into the module.
+    imported_names: FxHashMap<IdentifierId, Location>,
+
+    /// Names brought into the module by glob imports.
+    glob_imported_names: FxHashMap<IdentifierId, GlobImportedName>,
+
+    /// Whether references to imported symbols are recorded, see
+    /// [`ResolveImports::resolve_reexports`].
+    record_references: bool,
+}
+
+/// A name brought into a module by a glob import.
+struct GlobImportedName {
+    symbol: Symbol,
+
+    /// Location of the first glob import, that brings the name.
+    location: Location,
+
+    /// Whether another glob import brings a different item with the same name.
+    ambiguous: bool,
 }
 
 impl<'s> ResolveImports<'s> {
     pub fn run_all(state: &'s mut State, modules: &FxHashMap<ModuleId, stellar_hir::Module>) {
+        Self::resolve_reexports(state, modules);
+
         for module in modules {
-            ResolveImports {
-                state,
-                module: *module.0,
+            ResolveImports::new(state, *module.0).run(module.1)
+        }
+    }
+
+    fn new(state: &'s mut State, module: ModuleId) -> Self {
+        Self {
+            state,
+            module,
+            imported_names: FxHashMap::default(),
+            glob_imported_names: FxHashMap::default(),
+            record_references: true,
+        }
+    }
+
+    const fn without_references(mut self) -> Self {
+        self.record_references = false;
+        self
+    }
+
+    /// Registers re-exports of all modules before imports are resolved, so
+    /// that an import can refer to a name re-exported by a module, whose
+    /// imports are not resolved yet.
+    ///
+    /// Re-exports can refer to other re-exports, so they are resolved
+    /// repeatedly, until no more of them can be resolved. Diagnostics are
+    /// discarded, the failing imports are reported once all re-exports are
+    /// known.
+    ///
+    /// Re-exports are resolved again together with other imports, so
+    /// references to re-exported symbols are only recorded then.
+    fn resolve_reexports(state: &mut State, modules: &FxHashMap<ModuleId, stellar_hir::Module>) {
+        let diagnostics = mem::take(state.diagnostics_mut());
+
+        let mut reexports = modules
+            .iter()
+            .flat_map(|(module, hir)| {
+                hir.items.iter().filter_map(move |item| match item {
+                    stellar_hir::ModuleItem::Import {
+                        location,
+                        visibility,
+                        path: ImportPath::Single { path, as_ },
+                        ..
+                    } if *visibility != Visibility::Private => {
+                        Some((*module, *location, *visibility, path, *as_))
+                    }
+                    _ => None,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        loop {
+            let unresolved = reexports.len();
+
+            reexports.retain(|(module, location, visibility, path, as_)| {
+                !state.is_cancelled()
+                    && !ResolveImports::new(state, *module)
+                        .without_references()
+                        .resolve_import(*location, *visibility, path, *as_)
+            });
+
+            if reexports.len() == unresolved || state.is_cancelled() {
+                break;
             }
-            .run(module.1)
         }
+
+        *state.diagnostics_mut() = diagnostics;
     }
 
     fn run(mut self, module: &stellar_hir::Module) {
+        // explicit imports shadow glob imports, so they are resolved first
+        for item in &module.items {
+            if self.state.is_cancelled() {
+                return;
+            }
+
+            if let stellar_hir::ModuleItem::Import {
+                location,
+                visibility,
+                path: ImportPath::Single { path, as_ },
+                ..
+            } = item
+            {
+                self.resolve_import(*location, *visibility, path, *as_);
+            }
+        }
+
         for item in &module.items {
-            if let stellar_hir::ModuleItem::Import { location, path } = item {
-                self.resolve_import(*location, path)
+            if self.state.is_cancelled() {
+                return;
+            }
+
+            if let stellar_hir::ModuleItem::Import {
+                location,
+                path: ImportPath::Glob { path },
+                ..
+            } = item
+            {
+                self.resolve_glob_import(*location, path);
+            }
+        }
+
+        for (name, glob_imported_name) in self.glob_imported_names {
+            if !glob_imported_name.ambiguous {
+                self.module.add_resolved_import(
+                    self.state.db_mut(),
+                    name,
+                    glob_imported_name.symbol,
+                );
             }
         }
     }
 
-    fn resolve_import(&mut self, location: Location, path: &stellar_ast::ImportPath) {
+    /// Resolves an explicit import and returns `true` if it succeeded.
+    fn resolve_import(
+        &mut self,
+        location: Location,
+        visibility: Visibility,
+        path: &Path,
+        as_: Option<IdentifierAST>,
+    ) -> bool {
         #[cfg(feature = "debug")]
         let now = Instant::now();
 
-        let Some(symbol) = resolve_global_path(self.state, self.module.package(), path) else {
-            return;
+        let Some(symbol) = resolve_global_path(self.state, self.module, path) else {
+            return false;
         };
 
         if let Some(module) = symbol.to_module_or_none() {
@@ -55,20 +188,63 @@ impl<'s> ResolveImports<'s> {
                     .diagnostics_mut()
                     .add_diagnostic(PackageImport::new(
                         location,
-                        *path.path.identifiers.first().unwrap(),
+                        *path.identifiers.first().unwrap(),
                     ));
-                return;
+                return false;
             }
         }
 
-        let name = if let Some(as_) = path.as_ {
-            as_.id
-        } else {
-            symbol.name(self.state.db()).id
-        };
+        // the last segment can differ from the name of the item, if it refers
+        // to a renamed re-export
+        let name = as_.unwrap_or(*path.identifiers.last().unwrap());
+
+        // importing a submodule of the current module under its own name
+        // doesn't shadow anything
+        if self
+            .module
+            .symbol_or_none(self.state.db(), name.id)
+            .is_some_and(|defined| defined != symbol)
+        {
+            self.state
+                .diagnostics_mut()
+                .add_diagnostic(ImportShadowsModuleItem::new(name));
+            return false;
+        }
+
+        if let Some(first_import_location) = self.imported_names.get(&name.id) {
+            self.state
+                .diagnostics_mut()
+                .add_diagnostic(NameImportedMultipleTimes::new(name, *first_import_location));
+            return false;
+        }
+
+        if visibility != Visibility::Private {
+            if reexport_visibility_rank(visibility) > item_visibility_rank(self.state, symbol) {
+                self.state
+                    .diagnostics_mut()
+                    .add_diagnostic(ReexportMoreVisibleThanItem::new(
+                        name,
+                        visibility.location_or_none().unwrap(),
+                    ));
+                return false;
+            }
 
+            self.module.add_reexport(
+                self.state.db_mut(),
+                name.id,
+                Reexport { symbol, visibility },
+            );
+        }
+
+        self.imported_names.insert(name.id, name.location);
         self.module
-            .add_resolved_import(self.state.db_mut(), name, symbol);
+            .add_resolved_import(self.state.db_mut(), name.id, symbol);
+
+        if self.record_references {
+            self.state
+                .db_mut()
+                .add_reference(symbol, path.identifiers.last().unwrap().location);
+        }
 
         #[cfg(feature = "debug")]
         trace!(
@@ -76,6 +252,106 @@ impl<'s> ResolveImports<'s> {
             path,
             self.module,
             now.elapsed().as_millis()
-        )
+        );
+
+        true
+    }
+
+    /// Collects names, that a glob import brings into the module. Names
+    /// defined in the module or imported explicitly are skipped, because they
+    /// shadow glob imports.
+    fn resolve_glob_import(&mut self, location: Location, path: &Path) {
+        let Some(symbol) = resolve_global_path(self.state, self.module, path) else {
+            return;
+        };
+
+        let db = self.state.db();
+        let names: Vec<(IdentifierId, Symbol)> = match symbol {
+            Symbol::Module(module) => module
+                .module_item_symbols(db)
+                .iter()
+                .map(|(name, symbol)| (*name, *symbol))
+                .chain(
+                    module
+                        .submodules(db)
+                        .iter()
+                        .map(|(name, submodule)| (*name, Symbol::Module(*submodule))),
+                )
+                .filter(|(_, symbol)| is_visible_in(db, *symbol, self.module))
+                .chain(
+                    module
+                        .reexports(db)
+                        .iter()
+                        .filter(|(_, reexport)| {
+                            is_visibility_in_scope(db, reexport.visibility, module, self.module)
+                        })
+                        .map(|(name, reexport)| (*name, reexport.symbol)),
+                )
+                .collect(),
+            Symbol::Enum(enum_) => enum_
+                .items(db)
+                .iter()
+                .map(|(name, item)| (*name, Symbol::EnumItem(*item)))
+                .collect(),
+            _ => {
+                self.state
+                    .diagnostics_mut()
+                    .add_diagnostic(GlobImportFromNonNamespace::new(
+                        *path.identifiers.last().unwrap(),
+                    ));
+                return;
+            }
+        };
+
+        for (name, symbol) in names {
+            if self.module.symbol_or_none(self.state.db(), name).is_some()
+                || self.imported_names.contains_key(&name)
+            {
+                continue;
+            }
+
+            match self.glob_imported_names.entry(name) {
+                Entry::Vacant(entry) => {
+                    entry.insert(GlobImportedName {
+                        symbol,
+                        location,
+                        ambiguous: false,
+                    });
+                }
+                Entry::Occupied(mut entry) => {
+                    let glob_imported_name = entry.get_mut();
+
+                    if glob_imported_name.symbol != symbol && !glob_imported_name.ambiguous {
+                        glob_imported_name.ambiguous = true;
+
+                        self.state
+                            .diagnostics_mut()
+                            .add_diagnostic(AmbiguousGlobImport::new(
+                                name,
+                                glob_imported_name.location,
+                                location,
+                            ));
+                    }
+                }
+            }
+        }
     }
 }
+
+/// Returns how widely a re-export with a given visibility is visible: private
+/// and `pub(super)` re-exports are visible only in their package, `pub(package)`
+/// ones are visible in the whole package and `pub` ones everywhere.
+const fn reexport_visibility_rank(visibility: Visibility) -> u8 {
+    match visibility {
+        Visibility::Private | Visibility::Super(_) => 0,
+        Visibility::Package(_) => 1,
+        Visibility::Public(_) => 2,
+    }
+}
+
+/// Returns how widely an imported item is visible, see
+/// [`reexport_visibility_rank`].
+fn item_visibility_rank(state: &State, symbol: Symbol) -> u8 {
+    visibility_of(state.db(), symbol)
+        .map_or(2, |(visibility, _)| reexport_visibility_rank(visibility))
+}
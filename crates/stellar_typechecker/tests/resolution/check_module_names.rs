@@ -0,0 +1,99 @@
+use stellar_ast_lowering::LowerToHir;
+use stellar_database::{PackageData, State};
+use stellar_diagnostics::diagnostic::LabelStyle;
+use stellar_interner::{IdentifierId, PathId, DUMMY_PATH_ID};
+use stellar_parser::parse_module;
+use stellar_typechecker::resolution::check_module_names::CheckModuleNames;
+
+#[test]
+fn module_names_ok() {
+    let mut state = State::new();
+
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let first = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("main").into(),
+        PathId::from("a/main.sr"),
+        "",
+    );
+    let second = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("utils").into(),
+        PathId::from("a/utils.sr"),
+        "",
+    );
+
+    let hir = LowerToHir::run_all(&mut state, vec![first, second]);
+
+    CheckModuleNames::run_all(&mut state, &hir);
+
+    assert!(state.diagnostics().is_ok());
+}
+
+#[test]
+fn module_names_differ_only_in_case() {
+    let mut state = State::new();
+
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let first = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("Main").into(),
+        PathId::from("a/Main.sr"),
+        "",
+    );
+    let second = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("main").into(),
+        PathId::from("a/main.sr"),
+        "",
+    );
+
+    let hir = LowerToHir::run_all(&mut state, vec![first, second]);
+
+    CheckModuleNames::run_all(&mut state, &hir);
+
+    assert!(state.diagnostics().is_fatal());
+}
+
+#[test]
+fn module_defined_multiple_times() {
+    let mut state = State::new();
+
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let file = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("foo").into(),
+        PathId::from("a/foo.sr"),
+        "",
+    );
+    let directory = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("foo").into(),
+        PathId::from("a/foo/package.sr"),
+        "",
+    );
+
+    let hir = LowerToHir::run_all(&mut state, vec![file, directory]);
+
+    CheckModuleNames::run_all(&mut state, &hir);
+
+    assert!(state.diagnostics().is_fatal());
+
+    // the diagnostic points to the conflicting file
+    let labels = &state.diagnostics().diagnostics[0].labels;
+    assert_eq!(labels[0].style, LabelStyle::Primary);
+    assert_eq!(
+        labels[0].location.filepath,
+        PathId::from("a/foo/package.sr")
+    );
+    assert_eq!(labels[1].location.filepath, PathId::from("a/foo.sr"));
+}
@@ -10,11 +10,11 @@ fn test_enum() {
     let filepath = PathId::from("test.sr");
     let source_code = "enum A {}\nenum B {}";
 
-    let package = PackageData::alloc(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
+    let package = PackageData::alloc_in_memory(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
     let parse_result = parse_module(
         &mut state,
         package,
-        DUMMY_IDENTIFIER_ID,
+        DUMMY_IDENTIFIER_ID.into(),
         filepath,
         source_code,
     );
@@ -36,11 +36,11 @@ fn test_duplicate_definition() {
     let filepath = PathId::from("test.sr");
     let source_code = "enum A {}\nenum A {}";
 
-    let package = PackageData::alloc(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
+    let package = PackageData::alloc_in_memory(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
     let parse_result = parse_module(
         &mut state,
         package,
-        DUMMY_IDENTIFIER_ID,
+        DUMMY_IDENTIFIER_ID.into(),
         filepath,
         source_code,
     );
@@ -59,11 +59,11 @@ fn test_enum_items() {
     let filepath = PathId::from("test.sr");
     let source_code = "enum A { A, B, C }";
 
-    let package = PackageData::alloc(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
+    let package = PackageData::alloc_in_memory(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
     let parse_result = parse_module(
         &mut state,
         package,
-        DUMMY_IDENTIFIER_ID,
+        DUMMY_IDENTIFIER_ID.into(),
         filepath,
         source_code,
     );
@@ -92,11 +92,11 @@ fn duplicate_enum_item_definitions() {
     let filepath = PathId::from("test.sr");
     let source_code = "enum A { A, A }";
 
-    let package = PackageData::alloc(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
+    let package = PackageData::alloc_in_memory(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
     let parse_result = parse_module(
         &mut state,
         package,
-        DUMMY_IDENTIFIER_ID,
+        DUMMY_IDENTIFIER_ID.into(),
         filepath,
         source_code,
     );
@@ -115,11 +115,11 @@ fn test_function() {
     let filepath = PathId::from("test.sr");
     let source_code = "fun a() {}";
 
-    let package = PackageData::alloc(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
+    let package = PackageData::alloc_in_memory(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
     let parse_result = parse_module(
         &mut state,
         package,
-        DUMMY_IDENTIFIER_ID,
+        DUMMY_IDENTIFIER_ID.into(),
         filepath,
         source_code,
     );
@@ -142,11 +142,11 @@ fn test_struct() {
     let filepath = PathId::from("test.sr");
     let source_code = "struct A {}";
 
-    let package = PackageData::alloc(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
+    let package = PackageData::alloc_in_memory(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
     let parse_result = parse_module(
         &mut state,
         package,
-        DUMMY_IDENTIFIER_ID,
+        DUMMY_IDENTIFIER_ID.into(),
         filepath,
         source_code,
     );
@@ -169,11 +169,11 @@ fn test_interface() {
     let filepath = PathId::from("test.sr");
     let source_code = "interface A {}";
 
-    let package = PackageData::alloc(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
+    let package = PackageData::alloc_in_memory(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
     let parse_result = parse_module(
         &mut state,
         package,
-        DUMMY_IDENTIFIER_ID,
+        DUMMY_IDENTIFIER_ID.into(),
         filepath,
         source_code,
     );
@@ -196,11 +196,11 @@ fn test_type_alias() {
     let filepath = PathId::from("test.sr");
     let source_code = "type A = int8;";
 
-    let package = PackageData::alloc(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
+    let package = PackageData::alloc_in_memory(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
     let parse_result = parse_module(
         &mut state,
         package,
-        DUMMY_IDENTIFIER_ID,
+        DUMMY_IDENTIFIER_ID.into(),
         filepath,
         source_code,
     );
@@ -1,2 +1,3 @@
+mod check_module_names;
 mod collect_definitions;
 mod resolve_imports;
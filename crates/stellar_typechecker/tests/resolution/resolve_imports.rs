@@ -11,18 +11,19 @@ use stellar_typechecker::{
 fn resolve_submodule_import_ok() {
     let mut state = State::new();
 
-    let package = PackageData::alloc(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
     let submodule = parse_module(
         &mut state,
         package,
-        IdentifierId::from("b"),
+        IdentifierId::from("b").into(),
         PathId::from("a/b.sr"),
         "",
     );
     let root = parse_module(
         &mut state,
         package,
-        IdentifierId::from("a"),
+        IdentifierId::from("a").into(),
         PathId::from("a/package.sr"),
         "import a.b;",
     );
@@ -43,18 +44,19 @@ fn resolve_submodule_import_ok() {
 fn resolve_submodule_import_err() {
     let mut state = State::new();
 
-    let package = PackageData::alloc(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
     let submodule = parse_module(
         &mut state,
         package,
-        IdentifierId::from("b"),
+        IdentifierId::from("b").into(),
         PathId::from("a/b.sr"),
         "",
     );
     let root = parse_module(
         &mut state,
         package,
-        IdentifierId::from("a"),
+        IdentifierId::from("a").into(),
         PathId::from("a/package.sr"),
         "import a.c;",
     );
@@ -74,18 +76,19 @@ fn resolve_submodule_import_err() {
 fn resolve_module_item_ok() {
     let mut state = State::new();
 
-    let package = PackageData::alloc(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
     let submodule = parse_module(
         &mut state,
         package,
-        IdentifierId::from("b"),
+        IdentifierId::from("b").into(),
         PathId::from("a/b.sr"),
-        "fun foo() {}",
+        "pub fun foo() {}",
     );
     let root = parse_module(
         &mut state,
         package,
-        IdentifierId::from("a"),
+        IdentifierId::from("a").into(),
         PathId::from("a/package.sr"),
         "import a.b.foo;",
     );
@@ -106,18 +109,19 @@ fn resolve_module_item_ok() {
 fn resolve_module_item_err1() {
     let mut state = State::new();
 
-    let package = PackageData::alloc(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
     let submodule = parse_module(
         &mut state,
         package,
-        IdentifierId::from("b"),
+        IdentifierId::from("b").into(),
         PathId::from("a/b.sr"),
-        "fun foo() {}",
+        "pub fun foo() {}",
     );
     let root = parse_module(
         &mut state,
         package,
-        IdentifierId::from("a"),
+        IdentifierId::from("a").into(),
         PathId::from("a/package.sr"),
         "import a.b.foo;
 import a.b.foo2;",
@@ -139,18 +143,19 @@ import a.b.foo2;",
 fn resolve_module_item_err2() {
     let mut state = State::new();
 
-    let package = PackageData::alloc(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
     let submodule = parse_module(
         &mut state,
         package,
-        IdentifierId::from("b"),
+        IdentifierId::from("b").into(),
         PathId::from("a/b.sr"),
-        "fun foo() {}",
+        "pub fun foo() {}",
     );
     let root = parse_module(
         &mut state,
         package,
-        IdentifierId::from("a"),
+        IdentifierId::from("a").into(),
         PathId::from("a/package.sr"),
         "import a.c.foo;",
     );
@@ -171,18 +176,19 @@ fn resolve_module_item_err2() {
 fn resolve_enum_item_ok() {
     let mut state = State::new();
 
-    let package = PackageData::alloc(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
     let submodule = parse_module(
         &mut state,
         package,
-        IdentifierId::from("b"),
+        IdentifierId::from("b").into(),
         PathId::from("a/b.sr"),
-        "enum Result[T, E] { Ok(T), Err(E) }",
+        "pub enum Result[T, E] { Ok(T), Err(E) }",
     );
     let root = parse_module(
         &mut state,
         package,
-        IdentifierId::from("a"),
+        IdentifierId::from("a").into(),
         PathId::from("a/package.sr"),
         "import a.b.Result;
 import a.b.Result.Ok;
@@ -205,18 +211,19 @@ import a.b.Result.Err;",
 fn resolve_enum_item_err1() {
     let mut state = State::new();
 
-    let package = PackageData::alloc(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
     let submodule = parse_module(
         &mut state,
         package,
-        IdentifierId::from("b"),
+        IdentifierId::from("b").into(),
         PathId::from("a/b.sr"),
-        "enum Result[T, E] { Ok(T), Err(E) }",
+        "pub enum Result[T, E] { Ok(T), Err(E) }",
     );
     let root = parse_module(
         &mut state,
         package,
-        IdentifierId::from("a"),
+        IdentifierId::from("a").into(),
         PathId::from("a/package.sr"),
         "import a.b.Result.Foo;",
     );
@@ -237,18 +244,19 @@ fn resolve_enum_item_err1() {
 fn resolve_enum_item_err2() {
     let mut state = State::new();
 
-    let package = PackageData::alloc(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
     let submodule = parse_module(
         &mut state,
         package,
-        IdentifierId::from("b"),
+        IdentifierId::from("b").into(),
         PathId::from("a/b.sr"),
-        "enum Result[T, E] { Ok(T), Err(E) }",
+        "pub enum Result[T, E] { Ok(T), Err(E) }",
     );
     let root = parse_module(
         &mut state,
         package,
-        IdentifierId::from("a"),
+        IdentifierId::from("a").into(),
         PathId::from("a/package.sr"),
         "import a.b.Result.Ok.Foo;",
     );
@@ -269,18 +277,19 @@ fn resolve_enum_item_err2() {
 fn resolve_name_in_module_items_except_enums() {
     let mut state = State::new();
 
-    let package = PackageData::alloc(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
     let submodule = parse_module(
         &mut state,
         package,
-        IdentifierId::from("b"),
+        IdentifierId::from("b").into(),
         PathId::from("a/b.sr"),
-        "fun foo() {}",
+        "pub fun foo() {}",
     );
     let root = parse_module(
         &mut state,
         package,
-        IdentifierId::from("a"),
+        IdentifierId::from("a").into(),
         PathId::from("a/package.sr"),
         "import a.b.foo.foo;",
     );
@@ -301,18 +310,19 @@ fn resolve_name_in_module_items_except_enums() {
 fn importing_package() {
     let mut state = State::new();
 
-    let package = PackageData::alloc(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
     let submodule = parse_module(
         &mut state,
         package,
-        IdentifierId::from("b"),
+        IdentifierId::from("b").into(),
         PathId::from("a/b.sr"),
         "import a;",
     );
     let root = parse_module(
         &mut state,
         package,
-        IdentifierId::from("a"),
+        IdentifierId::from("a").into(),
         PathId::from("a/package.sr"),
         "",
     );
@@ -328,3 +338,215 @@ fn importing_package() {
 
     assert!(state.diagnostics().is_fatal());
 }
+
+#[test]
+fn resolve_self_package_import_ok() {
+    let mut state = State::new();
+
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let submodule = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("b").into(),
+        PathId::from("a/b.sr"),
+        "pub fun foo() {}",
+    );
+    let root = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("a").into(),
+        PathId::from("a/package.sr"),
+        "import self.b.foo;",
+    );
+
+    package.set_root_module(state.db_mut(), root.module());
+    root.module()
+        .add_submodule(state.db_mut(), submodule.module());
+
+    let hir = LowerToHir::run_all(&mut state, vec![root, submodule]);
+
+    CollectDefinitions::run_all(&mut state, &hir);
+    ResolveImports::run_all(&mut state, &hir);
+
+    assert!(state.diagnostics().is_ok());
+}
+
+#[test]
+fn importing_self_package() {
+    let mut state = State::new();
+
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let submodule = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("b").into(),
+        PathId::from("a/b.sr"),
+        "import self;",
+    );
+    let root = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("a").into(),
+        PathId::from("a/package.sr"),
+        "",
+    );
+
+    package.set_root_module(state.db_mut(), root.module());
+    root.module()
+        .add_submodule(state.db_mut(), submodule.module());
+
+    let hir = LowerToHir::run_all(&mut state, vec![root, submodule]);
+
+    CollectDefinitions::run_all(&mut state, &hir);
+    ResolveImports::run_all(&mut state, &hir);
+
+    assert!(state.diagnostics().is_fatal());
+}
+
+#[test]
+fn import_shadows_module_item() {
+    let mut state = State::new();
+
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let submodule = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("b").into(),
+        PathId::from("a/b.sr"),
+        "pub fun foo() {}",
+    );
+    let root = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("a").into(),
+        PathId::from("a/package.sr"),
+        "import a.b.foo;
+fun foo() {}",
+    );
+
+    package.set_root_module(state.db_mut(), root.module());
+    root.module()
+        .add_submodule(state.db_mut(), submodule.module());
+
+    let hir = LowerToHir::run_all(&mut state, vec![root, submodule]);
+
+    CollectDefinitions::run_all(&mut state, &hir);
+    ResolveImports::run_all(&mut state, &hir);
+
+    assert_eq!(
+        state.diagnostics().diagnostics[0].code.as_deref(),
+        Some("E010")
+    );
+}
+
+#[test]
+fn import_alias_avoids_shadowing() {
+    let mut state = State::new();
+
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let submodule = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("b").into(),
+        PathId::from("a/b.sr"),
+        "pub fun foo() {}",
+    );
+    let root = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("a").into(),
+        PathId::from("a/package.sr"),
+        "import a.b.foo as bfoo;
+fun foo() {}",
+    );
+
+    package.set_root_module(state.db_mut(), root.module());
+    root.module()
+        .add_submodule(state.db_mut(), submodule.module());
+
+    let hir = LowerToHir::run_all(&mut state, vec![root, submodule]);
+
+    CollectDefinitions::run_all(&mut state, &hir);
+    ResolveImports::run_all(&mut state, &hir);
+
+    assert!(state.diagnostics().is_ok());
+}
+
+#[test]
+fn name_imported_multiple_times() {
+    let mut state = State::new();
+
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let submodule = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("b").into(),
+        PathId::from("a/b.sr"),
+        "pub fun foo() {}
+pub fun bar() {}",
+    );
+    let root = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("a").into(),
+        PathId::from("a/package.sr"),
+        "import a.b.foo;
+import self.b.bar as foo;",
+    );
+
+    package.set_root_module(state.db_mut(), root.module());
+    root.module()
+        .add_submodule(state.db_mut(), submodule.module());
+
+    let hir = LowerToHir::run_all(&mut state, vec![root, submodule]);
+
+    CollectDefinitions::run_all(&mut state, &hir);
+    ResolveImports::run_all(&mut state, &hir);
+
+    assert_eq!(
+        state.diagnostics().diagnostics[0].code.as_deref(),
+        Some("E032")
+    );
+}
+
+#[test]
+fn ambiguous_package_reference() {
+    let mut state = State::new();
+
+    let package =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    let dependency =
+        PackageData::alloc_in_memory(state.db_mut(), IdentifierId::from("a"), DUMMY_PATH_ID);
+    package.add_dependency(state.db_mut(), IdentifierId::from("a"), dependency);
+
+    let submodule = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("b").into(),
+        PathId::from("a/b.sr"),
+        "pub fun foo() {}",
+    );
+    let root = parse_module(
+        &mut state,
+        package,
+        IdentifierId::from("a").into(),
+        PathId::from("a/package.sr"),
+        "import a.b.foo;",
+    );
+
+    package.set_root_module(state.db_mut(), root.module());
+    root.module()
+        .add_submodule(state.db_mut(), submodule.module());
+
+    let hir = LowerToHir::run_all(&mut state, vec![root, submodule]);
+
+    CollectDefinitions::run_all(&mut state, &hir);
+    ResolveImports::run_all(&mut state, &hir);
+
+    assert!(state.diagnostics().is_fatal());
+}
@@ -8,15 +8,16 @@ use stellar_typechecker::{
 };
 
 #[test]
+#[ignore = "signature collection is not implemented yet, see `CollectSignatures::run_all`"]
 fn simple_generic_parameter() {
     let mut state = State::new();
     let source_code = "struct Box[T](T);";
 
-    let package = PackageData::alloc(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
+    let package = PackageData::alloc_in_memory(state.db_mut(), DUMMY_IDENTIFIER_ID, DUMMY_PATH_ID);
     let parse_result = parse_module(
         &mut state,
         package,
-        DUMMY_IDENTIFIER_ID,
+        DUMMY_IDENTIFIER_ID.into(),
         DUMMY_PATH_ID,
         source_code,
     );